@@ -0,0 +1,105 @@
+//! Compares `world_transform` and `Pose::in_frame` of the reference-counted and the
+//! arena-backed frame trees.
+//!
+//! Run with `cargo bench --bench backends`. Both backends get the same tree: a chain of
+//! `DEPTH` frames with a branch of the same depth at its middle.
+
+use cartesian_tree::Frame;
+use cartesian_tree::frame::arena;
+use cartesian_tree::tree::HasChildren;
+use nalgebra::{UnitQuaternion, Vector3};
+use std::hint::black_box;
+use std::time::Instant;
+
+const DEPTH: usize = 32;
+const ITERATIONS: u32 = 100_000;
+
+/// Runs `routine` `ITERATIONS` times after a short warm-up and prints the mean duration.
+fn bench(name: &str, mut routine: impl FnMut()) {
+    for _ in 0..ITERATIONS / 10 {
+        routine();
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        routine();
+    }
+    println!("{name:<44} {:>12.1?}", start.elapsed() / ITERATIONS);
+}
+
+fn orientation() -> UnitQuaternion<f64> {
+    UnitQuaternion::from_euler_angles(0.01, 0.02, 0.03)
+}
+
+/// Returns the root, the tip of the chain and the tip of the branch.
+fn reference_tree() -> (Frame, Frame, Frame) {
+    let root = Frame::new_origin("world").unwrap();
+    let mut tip = root.clone();
+    let mut branch = None;
+    for i in 0..DEPTH {
+        tip = tip
+            .add_child(format!("link{i}"), Vector3::x(), orientation())
+            .unwrap();
+        if i == DEPTH / 2 {
+            branch = Some(tip.clone());
+        }
+    }
+    let mut branch = branch.unwrap();
+    for i in 0..DEPTH {
+        branch = branch
+            .add_child(format!("branch{i}"), Vector3::y(), orientation())
+            .unwrap();
+    }
+    (root, tip, branch)
+}
+
+/// Returns the root, the tip of the chain and the tip of the branch.
+fn arena_tree() -> (arena::Frame, arena::Frame, arena::Frame) {
+    let root = arena::Frame::new_origin("world").unwrap();
+    let mut tip = root.clone();
+    let mut branch = None;
+    for i in 0..DEPTH {
+        tip = tip
+            .add_child(format!("link{i}"), Vector3::x(), orientation())
+            .unwrap();
+        if i == DEPTH / 2 {
+            branch = Some(tip.clone());
+        }
+    }
+    let mut branch = branch.unwrap();
+    for i in 0..DEPTH {
+        branch = branch
+            .add_child(format!("branch{i}"), Vector3::y(), orientation())
+            .unwrap();
+    }
+    (root, tip, branch)
+}
+
+fn main() {
+    let (root, tip, branch) = reference_tree();
+    let first = root.children()[0].clone();
+    let pose = tip.add_pose(Vector3::z(), orientation()).unwrap();
+    bench("reference: world_transform (cached)", || {
+        black_box(tip.world_transform().unwrap());
+    });
+    bench("reference: world_transform after set", || {
+        first.set(Vector3::x(), orientation()).unwrap();
+        black_box(tip.world_transform().unwrap());
+    });
+    bench("reference: in_frame between branches", || {
+        black_box(pose.in_frame(&branch).unwrap());
+    });
+
+    let (root, tip, branch) = arena_tree();
+    let first = root.children()[0].clone();
+    let pose = tip.add_pose(Vector3::z(), orientation()).unwrap();
+    bench("arena: world_transform", || {
+        black_box(tip.world_transform().unwrap());
+    });
+    bench("arena: world_transform after set", || {
+        first.set(Vector3::x(), orientation()).unwrap();
+        black_box(tip.world_transform().unwrap());
+    });
+    bench("arena: in_frame between branches", || {
+        black_box(pose.in_frame(&branch).unwrap());
+    });
+}
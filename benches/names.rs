@@ -0,0 +1,121 @@
+//! Measures the effect of interned frame names on a large synthetic tree.
+//!
+//! Run with `cargo bench --bench names`. The tree holds `ROBOTS` robots with the same
+//! `LINKS`-link chain each, as if every robot was imported from the same URDF file. The
+//! benchmark reports the heap used for the tree and for its names, and the time of name
+//! accessors and lookups.
+
+use cartesian_tree::Frame;
+use nalgebra::{UnitQuaternion, Vector3};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashSet;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+const ROBOTS: usize = 500;
+const LINKS: usize = 20;
+const ITERATIONS: u32 = 20;
+
+/// Counts the bytes currently allocated on the heap.
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+// SAFETY: Forwards every call to the system allocator unchanged.
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        // SAFETY: The caller upholds the contract of `GlobalAlloc::alloc`.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        // SAFETY: The caller upholds the contract of `GlobalAlloc::dealloc`.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Runs `routine` `ITERATIONS` times and prints the mean duration.
+fn bench(name: &str, mut routine: impl FnMut()) {
+    routine();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        routine();
+    }
+    println!("{name:<44} {:>12.1?}", start.elapsed() / ITERATIONS);
+}
+
+fn build() -> Frame {
+    let root = Frame::new_origin("world").unwrap();
+    for robot in 0..ROBOTS {
+        let mut link = root
+            .add_child(
+                format!("robot{robot}"),
+                Vector3::x(),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        for index in 0..LINKS {
+            link = link
+                .add_child(
+                    format!("manipulator_link_{index}"),
+                    Vector3::z(),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+        }
+    }
+    root
+}
+
+fn main() {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let root = build();
+    let tree_bytes = ALLOCATED.load(Ordering::Relaxed) - before;
+    let frames: Vec<_> = root.iter_dfs().collect();
+    let copied_bytes: usize = frames.iter().map(|frame| frame.name().len()).sum();
+    let shared = frames
+        .iter()
+        .map(Frame::shared_name)
+        .collect::<HashSet<_>>();
+    let interned_bytes: usize = shared.iter().map(|name| name.len()).sum();
+    println!("{:<44} {:>12}", "frames", frames.len());
+    println!("{:<44} {tree_bytes:>10} B", "heap of the tree");
+    println!(
+        "{:<44} {copied_bytes:>10} B",
+        "names stored per frame (without interning)"
+    );
+    println!(
+        "{:<44} {interned_bytes:>10} B",
+        "names stored once (interned)"
+    );
+
+    bench("build the tree", || {
+        black_box(build());
+    });
+    bench("name() of every frame", || {
+        for frame in &frames {
+            black_box(frame.name());
+        }
+    });
+    bench("shared_name() of every frame", || {
+        for frame in &frames {
+            black_box(frame.shared_name());
+        }
+    });
+    let links: Vec<_> = (0..LINKS)
+        .map(|index| format!("manipulator_link_{index}"))
+        .collect();
+    let path = format!("robot{}/{}", ROBOTS - 1, links.join("/"));
+    bench("resolve_path to the last tip", || {
+        black_box(root.resolve_path(&path).unwrap());
+    });
+    bench("find_all of a shared link name", || {
+        black_box(root.find_all("manipulator_link_7"));
+    });
+}
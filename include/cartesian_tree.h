@@ -0,0 +1,183 @@
+/* Generated by cbindgen from src/capi.rs. Do not edit, run `just header` instead. */
+
+#ifndef CARTESIAN_TREE_H
+#define CARTESIAN_TREE_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The result of a call through the C interface.
+ */
+typedef enum CtStatus {
+  /**
+   * The call succeeded.
+   */
+  CT_STATUS_OK = 0,
+  /**
+   * A required pointer argument was `NULL`.
+   */
+  CT_STATUS_NULL_POINTER = 1,
+  /**
+   * A string argument was not valid UTF-8, or a returned string contained a NUL byte.
+   */
+  CT_STATUS_INVALID_STRING = 2,
+  /**
+   * The operation failed, e.g. because of a duplicate frame name or a dropped frame.
+   */
+  CT_STATUS_TREE_ERROR = 3,
+  /**
+   * The library panicked. The handles involved should not be used any further.
+   */
+  CT_STATUS_PANIC = 4,
+} CtStatus;
+
+/**
+ * An opaque handle to a [`Frame`].
+ */
+typedef struct CtFrame CtFrame;
+
+/**
+ * An opaque handle to a [`Pose`].
+ */
+typedef struct CtPose CtPose;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns a description of the last failed call on this thread, or `NULL` if none failed.
+ *
+ * The string belongs to the library and stays valid until the next failing call on this
+ * thread.
+ */
+const char *ct_last_error_message(void);
+
+/**
+ * Creates the root frame of a new tree, see [`Frame::new_origin`].
+ *
+ * # Safety
+ * `name` must be a NUL-terminated string and `out` valid for writes, or `NULL`.
+ */
+enum CtStatus ct_frame_new_origin(const char *name, struct CtFrame **out);
+
+/**
+ * Adds a child to `parent`, see [`Frame::add_child`].
+ *
+ * # Safety
+ * `parent` must be a live handle, `name` a NUL-terminated string, `position` and
+ * `orientation` arrays of 3 and 4 doubles and `out` valid for writes, or `NULL`.
+ */
+enum CtStatus ct_frame_add_child(const struct CtFrame *parent,
+                                 const char *name,
+                                 const double *position,
+                                 const double *orientation,
+                                 struct CtFrame **out);
+
+/**
+ * Writes a copy of the name of `frame` to `out`, to be released with [`ct_string_free`].
+ *
+ * # Safety
+ * `frame` must be a live handle and `out` valid for writes, or `NULL`.
+ */
+enum CtStatus ct_frame_name(const struct CtFrame *frame, char **out);
+
+/**
+ * Moves `frame` relative to its parent, see [`Frame::set`].
+ *
+ * # Safety
+ * `frame` must be a live handle and `position` and `orientation` arrays of 3 and 4 doubles,
+ * or `NULL`.
+ */
+enum CtStatus ct_frame_set(const struct CtFrame *frame,
+                           const double *position,
+                           const double *orientation);
+
+/**
+ * Writes the transform from `frame` to the root of its tree, see [`Frame::world_transform`].
+ *
+ * # Safety
+ * `frame` must be a live handle and `position` and `orientation` writable arrays of 3 and 4
+ * doubles, or `NULL`.
+ */
+enum CtStatus ct_frame_world_transform(const struct CtFrame *frame,
+                                       double *position,
+                                       double *orientation);
+
+/**
+ * Writes the transform from `frame` to `target`, see [`Frame::transform_to`].
+ *
+ * # Safety
+ * `frame` and `target` must be live handles and `position` and `orientation` writable arrays
+ * of 3 and 4 doubles, or `NULL`.
+ */
+enum CtStatus ct_frame_transform_to(const struct CtFrame *frame,
+                                    const struct CtFrame *target,
+                                    double *position,
+                                    double *orientation);
+
+/**
+ * Releases a frame handle. The tree lives on while handles to any of its frames are left.
+ *
+ * # Safety
+ * `frame` must be `NULL` or a handle not released before.
+ */
+void ct_frame_free(struct CtFrame *frame);
+
+/**
+ * Adds a pose to `frame`, see [`Frame::add_pose`].
+ *
+ * # Safety
+ * `frame` must be a live handle, `position` and `orientation` arrays of 3 and 4 doubles and
+ * `out` valid for writes, or `NULL`.
+ */
+enum CtStatus ct_frame_add_pose(const struct CtFrame *frame,
+                                const double *position,
+                                const double *orientation,
+                                struct CtPose **out);
+
+/**
+ * Expresses `pose` in `target` as a new pose, see [`Pose::in_frame`].
+ *
+ * # Safety
+ * `pose` and `target` must be live handles and `out` valid for writes, or `NULL`.
+ */
+enum CtStatus ct_pose_in_frame(const struct CtPose *pose,
+                               const struct CtFrame *target,
+                               struct CtPose **out);
+
+/**
+ * Writes the transform from `pose` to its frame, see [`Pose::transformation`].
+ *
+ * # Safety
+ * `pose` must be a live handle and `position` and `orientation` writable arrays of 3 and 4
+ * doubles, or `NULL`.
+ */
+enum CtStatus ct_pose_transformation(const struct CtPose *pose,
+                                     double *position,
+                                     double *orientation);
+
+/**
+ * Releases a pose handle.
+ *
+ * # Safety
+ * `pose` must be `NULL` or a handle not released before.
+ */
+void ct_pose_free(struct CtPose *pose);
+
+/**
+ * Releases a string returned by the library, e.g. from [`ct_frame_name`].
+ *
+ * # Safety
+ * `string` must be `NULL` or a string returned by the library and not released before.
+ */
+void ct_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CARTESIAN_TREE_H */
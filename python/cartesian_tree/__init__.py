@@ -2,8 +2,119 @@
 
 from .angles import RPY
 from .base_types import Isometry, Rotation, Vector3
+from .exceptions import (
+    CannotUpdateRootTransform,
+    CartesianTreeError,
+    ChildNotFound,
+    ConcurrentAccess,
+    DegenerateInput,
+    DuplicateChildName,
+    DuplicateFrameId,
+    DuplicatePoseName,
+    DuplicateRegionName,
+    EmptyHistory,
+    FrameDropped,
+    HistoryNotEnabled,
+    IkNotConverged,
+    InvalidBinary,
+    InvalidConfig,
+    InvalidDocument,
+    InvalidFrameName,
+    InvalidRotationMatrix,
+    InvalidScale,
+    InvalidTransformMatrix,
+    IsNoAncestor,
+    JointLimitExceeded,
+    LengthMismatch,
+    Mismatch,
+    MissingFrames,
+    NoCommonAncestor,
+    NoJoint,
+    NonFiniteValue,
+    PathNotFound,
+    PoseNotInFrame,
+    ReparentCycle,
+    RootHasNoParent,
+    ScaledTransform,
+    SerdeError,
+    StaticFrame,
+    TimeOutOfRange,
+    UndeclaredUnit,
+    UnsupportedConfigVersion,
+    WeakUpgradeFailed,
+)
 from .lazy_access import rx, ry, rz, x, y, z
-from .lib import Frame, Pose
+from .lib import (
+    CalibrationReport,
+    Frame,
+    PlaneFitReport,
+    Pose,
+    Region,
+    RegionShape,
+    Trajectory,
+    TreeDiff,
+    TreeState,
+)
 from .quaternion import Quaternion
 
-__all__ = ["RPY", "Frame", "Isometry", "Pose", "Quaternion", "Rotation", "Vector3", "rx", "ry", "rz", "x", "y", "z"]
+__all__ = [
+    "RPY",
+    "CalibrationReport",
+    "CannotUpdateRootTransform",
+    "CartesianTreeError",
+    "ChildNotFound",
+    "ConcurrentAccess",
+    "DegenerateInput",
+    "DuplicateChildName",
+    "DuplicateFrameId",
+    "DuplicatePoseName",
+    "DuplicateRegionName",
+    "EmptyHistory",
+    "Frame",
+    "FrameDropped",
+    "HistoryNotEnabled",
+    "IkNotConverged",
+    "InvalidBinary",
+    "InvalidConfig",
+    "InvalidDocument",
+    "InvalidFrameName",
+    "InvalidRotationMatrix",
+    "InvalidScale",
+    "InvalidTransformMatrix",
+    "IsNoAncestor",
+    "Isometry",
+    "JointLimitExceeded",
+    "LengthMismatch",
+    "Mismatch",
+    "MissingFrames",
+    "NoCommonAncestor",
+    "NoJoint",
+    "NonFiniteValue",
+    "PathNotFound",
+    "PlaneFitReport",
+    "Pose",
+    "PoseNotInFrame",
+    "Quaternion",
+    "Region",
+    "RegionShape",
+    "ReparentCycle",
+    "RootHasNoParent",
+    "Rotation",
+    "ScaledTransform",
+    "SerdeError",
+    "StaticFrame",
+    "TimeOutOfRange",
+    "Trajectory",
+    "TreeDiff",
+    "TreeState",
+    "UndeclaredUnit",
+    "UnsupportedConfigVersion",
+    "Vector3",
+    "WeakUpgradeFailed",
+    "rx",
+    "ry",
+    "rz",
+    "x",
+    "y",
+    "z",
+]
@@ -25,6 +25,22 @@ class RPY:
         instance._core_rotation = _core.Rotation.from_rpy(0.0, 0.0, 0.0)
         return instance
 
+    @classmethod
+    def from_degrees(cls, roll: float, pitch: float, yaw: float) -> RPY:
+        """Initializes the roll-pitch-yaw angles from degrees.
+
+        Args:
+            roll: The roll angle in degrees.
+            pitch: The pitch angle in degrees.
+            yaw: The yaw angle in degrees.
+
+        Returns:
+            The initialized instance.
+        """
+        instance = cls.__new__(cls)
+        instance._core_rotation = _core.Rotation.from_rpy_degrees(roll, pitch, yaw)
+        return instance
+
     @property
     def roll(self) -> float:
         """The roll angle in radians."""
@@ -56,6 +72,14 @@ class RPY:
         """
         return self._core_rotation.as_rpy()
 
+    def to_degrees(self) -> tuple[float, float, float]:
+        """Returns the angles in degrees.
+
+        Returns:
+            The roll, pitch and yaw angles in degrees.
+        """
+        return self._core_rotation.to_rpy_degrees()
+
     @classmethod
     def _from_rust(cls, rust_rotation: _core.Rotation) -> RPY:
         instance = cls.__new__(cls)
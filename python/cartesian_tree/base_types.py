@@ -2,12 +2,20 @@
 
 from __future__ import annotations
 
-from typing import Any
+from typing import TYPE_CHECKING, Any
+
+import numpy as np
 
 from .angles import RPY
 from .quaternion import Quaternion
 from cartesian_tree import _cartesian_tree as _core  # type: ignore[attr-defined]
 
+if TYPE_CHECKING:
+    from collections.abc import Sequence
+    from typing import TypeAlias
+
+    from numpy.typing import ArrayLike, NDArray
+
 
 class Rotation:
     """Defines a unified rotation representation."""
@@ -47,6 +55,58 @@ class Rotation:
         instance._core_rotation = _core.Rotation.from_rpy(roll, pitch, yaw)
         return instance
 
+    @classmethod
+    def from_axis_angle(cls, axis: Vector3, angle: float) -> Rotation:
+        """Initializes the rotation from an axis and an angle.
+
+        Args:
+            axis: The rotation axis (normalized internally).
+            angle: The rotation angle in radians.
+
+        Returns:
+            The initialized instance.
+
+        Raises:
+            ValueError: If the axis is the zero vector.
+        """
+        instance = cls.__new__(cls)
+        instance._core_rotation = _core.Rotation.from_axis_angle(axis._binding_structure, angle)
+        return instance
+
+    @classmethod
+    def from_matrix(cls, matrix: ArrayLike, *, orthonormalize: bool = False) -> Rotation:
+        """Initializes the rotation from a 3x3 rotation matrix.
+
+        Args:
+            matrix: The rotation matrix as a 3x3 numpy array or nested lists (row-major).
+            orthonormalize: Whether to use the closest rotation matrix instead of rejecting
+                matrices that are not orthonormal.
+
+        Returns:
+            The initialized instance.
+
+        Raises:
+            InvalidRotationMatrix: If the matrix is not orthonormal or is a reflection.
+        """
+        rows = np.asarray(matrix, dtype=np.float64).tolist()
+        instance = cls.__new__(cls)
+        instance._core_rotation = _core.Rotation.from_matrix(rows, orthonormalize)
+        return instance
+
+    @classmethod
+    def from_rotation_vector(cls, vector: Vector3) -> Rotation:
+        """Initializes the rotation from a rotation vector (axis scaled by the angle in radians).
+
+        Args:
+            vector: The rotation vector, e.g. as returned by OpenCV's Rodrigues conversion.
+
+        Returns:
+            The initialized instance.
+        """
+        instance = cls.__new__(cls)
+        instance._core_rotation = _core.Rotation.from_rotation_vector(vector._binding_structure)
+        return instance
+
     @classmethod
     def identity(cls) -> Rotation:
         """Initializes the identity rotation."""
@@ -70,6 +130,67 @@ class Rotation:
         """
         return RPY._from_rust(self._core_rotation)
 
+    def to_matrix(self) -> NDArray[np.float64]:
+        """Converts the rotation to a 3x3 rotation matrix.
+
+        Returns:
+            The rotation matrix.
+        """
+        return np.array(self._core_rotation.to_matrix(), dtype=np.float64)
+
+    def to_axis_angle(self) -> tuple[Vector3, float]:
+        """Converts the rotation to a unit axis and an angle.
+
+        A zero rotation yields the x-axis with an angle of zero.
+
+        Returns:
+            The axis and the angle in radians.
+        """
+        binding_axis, angle = self._core_rotation.to_axis_angle()
+        return Vector3(*binding_axis.to_tuple()), angle
+
+    def to_rotation_vector(self) -> Vector3:
+        """Converts the rotation to a rotation vector (axis scaled by the angle in radians).
+
+        Returns:
+            The rotation vector.
+        """
+        return Vector3(*self._core_rotation.to_rotation_vector().to_tuple())
+
+    def inverse(self) -> Rotation:
+        """Returns the inverse of the rotation.
+
+        Returns:
+            The inverse rotation.
+        """
+        return Rotation._from_rust(self._core_rotation.inverse())
+
+    def slerp(self, other: Rotation, t: float) -> Rotation:
+        """Interpolates along the shortest arc between this rotation and another one.
+
+        Args:
+            other: The rotation reached at `t = 1`.
+            t: The interpolation parameter, where `t = 0` yields this rotation.
+
+        Returns:
+            The interpolated rotation.
+        """
+        return Rotation._from_rust(self._core_rotation.slerp(other._binding_structure, t))
+
+    def angle_to(self, other: Rotation) -> float:
+        """Returns the angle of the rotation between this rotation and another one.
+
+        Args:
+            other: The other rotation.
+
+        Returns:
+            The angle in radians, in [0, pi].
+        """
+        return self._core_rotation.angle_to(other._binding_structure)
+
+    def __mul__(self, other: Rotation) -> Rotation:
+        return Rotation._from_rust(self._core_rotation.__mul__(other._binding_structure))
+
     @property
     def _binding_structure(self) -> Any:
         return self._core_rotation
@@ -168,50 +289,50 @@ class Isometry:
         return instance
 
     @classmethod
-    def from_translation(cls, translation: Vector3) -> Isometry:
+    def from_translation(cls, translation: VectorLike) -> Isometry:
         """Initializes the isometry from translation only.
 
         Note, the rotation will be identity.
 
         Args:
-            translation: The translation part.
+            translation: The translation part, a `Vector3` or a sequence `(x, y, z)`.
 
         Returns:
             The initialized isometry instance.
         """
         instance = cls.__new__(cls)
-        instance._core_isometry = _core.Isometry.from_translation(translation._binding_structure)
+        instance._core_isometry = _core.Isometry.from_translation(_binding_vector(translation))
         return instance
 
     @classmethod
-    def from_rotation(cls, rotation: Rotation) -> Isometry:
+    def from_rotation(cls, rotation: RotationLike) -> Isometry:
         """Initializes the isometry from rotation only.
 
         Note, the translation will be identity.
 
         Args:
-            rotation: The rotation part.
+            rotation: The rotation part, see `RotationLike`.
 
         Returns:
             The initialized isometry instance.
         """
         instance = cls.__new__(cls)
-        instance._core_isometry = _core.Isometry.from_rotation(rotation._binding_structure)
+        instance._core_isometry = _core.Isometry.from_rotation(_binding_rotation(rotation))
         return instance
 
     @classmethod
-    def from_parts(cls, translation: Vector3, rotation: Rotation) -> Isometry:
+    def from_parts(cls, translation: VectorLike, rotation: RotationLike) -> Isometry:
         """Initializes the isometry from translation and rotation.
 
         Args:
-            translation: The translation part.
-            rotation: The rotation part.
+            translation: The translation part, a `Vector3` or a sequence `(x, y, z)`.
+            rotation: The rotation part, see `RotationLike`.
 
         Returns:
             The initialized isometry instance.
         """
         instance = cls.__new__(cls)
-        instance._core_isometry = _core.Isometry.from_parts(translation._binding_structure, rotation._binding_structure)
+        instance._core_isometry = _core.Isometry.from_parts(_binding_vector(translation), _binding_rotation(rotation))
         return instance
 
     def decompose(self) -> tuple[Vector3, Rotation]:
@@ -267,3 +388,26 @@ class Isometry:
 
     def __repr__(self) -> str:
         return self._core_isometry.__repr__()
+
+
+if TYPE_CHECKING:
+    VectorLike: TypeAlias = Vector3 | Sequence[float] | NDArray[np.floating[Any]]
+    """A vector given as `Vector3` or as a sequence `(x, y, z)`, e.g. a tuple, list or 1-D numpy array."""
+
+    RotationLike: TypeAlias = Rotation | Sequence[float] | NDArray[np.floating[Any]] | None
+    """An orientation given as `Rotation`, as a quaternion `(x, y, z, w)`, as roll-pitch-yaw angles
+    `(roll, pitch, yaw)` in radians, or as `None` for the identity. Sequences are told apart by their length."""
+
+
+def _binding_vector(value: VectorLike) -> Any:
+    """Returns the binding representation of a vector argument."""
+    if isinstance(value, Vector3):
+        return value._binding_structure
+    return value
+
+
+def _binding_rotation(value: RotationLike) -> Any:
+    """Returns the binding representation of an orientation argument."""
+    if isinstance(value, Rotation):
+        return value._binding_structure
+    return value
@@ -0,0 +1,94 @@
+"""Defines the exceptions raised by the library.
+
+All exceptions derive from `CartesianTreeError`, which in turn derives from `ValueError`.
+Errors raised while loading or applying a configuration (`SerdeError`, `InvalidDocument`
+and `Mismatch`) share the intermediate base `InvalidConfig`.
+
+Each exception carries the details of the failure as attributes, named after the fields
+of the underlying Rust error (e.g. `DuplicateChildName.parent` and `.child`). The ends of
+a `NoCommonAncestor` or `ScaledTransform` are exposed as `from_frame` and `to_frame`.
+"""
+
+from cartesian_tree import _cartesian_tree as _core  # type: ignore[attr-defined]
+
+CartesianTreeError = _core.CartesianTreeError
+FrameDropped = _core.FrameDropped
+RootHasNoParent = _core.RootHasNoParent
+CannotUpdateRootTransform = _core.CannotUpdateRootTransform
+StaticFrame = _core.StaticFrame
+DuplicateChildName = _core.DuplicateChildName
+ChildNotFound = _core.ChildNotFound
+ReparentCycle = _core.ReparentCycle
+PathNotFound = _core.PathNotFound
+NoCommonAncestor = _core.NoCommonAncestor
+IsNoAncestor = _core.IsNoAncestor
+WeakUpgradeFailed = _core.WeakUpgradeFailed
+InvalidConfig = _core.InvalidConfig
+SerdeError = _core.SerdeError
+InvalidDocument = _core.InvalidDocument
+InvalidRotationMatrix = _core.InvalidRotationMatrix
+Mismatch = _core.Mismatch
+LengthMismatch = _core.LengthMismatch
+DegenerateInput = _core.DegenerateInput
+PoseNotInFrame = _core.PoseNotInFrame
+ConcurrentAccess = _core.ConcurrentAccess
+NonFiniteValue = _core.NonFiniteValue
+DuplicatePoseName = _core.DuplicatePoseName
+DuplicateRegionName = _core.DuplicateRegionName
+InvalidFrameName = _core.InvalidFrameName
+MissingFrames = _core.MissingFrames
+InvalidTransformMatrix = _core.InvalidTransformMatrix
+HistoryNotEnabled = _core.HistoryNotEnabled
+EmptyHistory = _core.EmptyHistory
+TimeOutOfRange = _core.TimeOutOfRange
+InvalidScale = _core.InvalidScale
+ScaledTransform = _core.ScaledTransform
+UndeclaredUnit = _core.UndeclaredUnit
+UnsupportedConfigVersion = _core.UnsupportedConfigVersion
+InvalidBinary = _core.InvalidBinary
+NoJoint = _core.NoJoint
+JointLimitExceeded = _core.JointLimitExceeded
+IkNotConverged = _core.IkNotConverged
+DuplicateFrameId = _core.DuplicateFrameId
+
+__all__ = [
+    "CannotUpdateRootTransform",
+    "CartesianTreeError",
+    "ChildNotFound",
+    "ConcurrentAccess",
+    "DegenerateInput",
+    "DuplicateChildName",
+    "DuplicateFrameId",
+    "DuplicatePoseName",
+    "DuplicateRegionName",
+    "EmptyHistory",
+    "FrameDropped",
+    "HistoryNotEnabled",
+    "IkNotConverged",
+    "InvalidBinary",
+    "InvalidConfig",
+    "InvalidDocument",
+    "InvalidFrameName",
+    "InvalidRotationMatrix",
+    "InvalidScale",
+    "InvalidTransformMatrix",
+    "IsNoAncestor",
+    "JointLimitExceeded",
+    "LengthMismatch",
+    "Mismatch",
+    "MissingFrames",
+    "NoCommonAncestor",
+    "NoJoint",
+    "NonFiniteValue",
+    "PathNotFound",
+    "PoseNotInFrame",
+    "ReparentCycle",
+    "RootHasNoParent",
+    "ScaledTransform",
+    "SerdeError",
+    "StaticFrame",
+    "TimeOutOfRange",
+    "UndeclaredUnit",
+    "UnsupportedConfigVersion",
+    "WeakUpgradeFailed",
+]
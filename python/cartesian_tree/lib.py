@@ -4,10 +4,20 @@ from __future__ import annotations
 
 from typing import TYPE_CHECKING, Any
 
-from .base_types import Isometry, Rotation, Vector3
+import numpy as np
+
+from .angles import RPY
+from .base_types import Isometry, Rotation, Vector3, _binding_rotation, _binding_vector
+from .metadata import FrameMetadata
+from .quaternion import Quaternion
 from cartesian_tree import _cartesian_tree as _core  # type: ignore[attr-defined]
 
 if TYPE_CHECKING:
+    from collections.abc import Callable, Iterable, Iterator, Sequence
+
+    from numpy.typing import ArrayLike, NDArray
+
+    from .base_types import RotationLike, VectorLike
     from .lazy_access import LazyRotation, LazyTranslation
 
 
@@ -23,6 +33,10 @@ class Frame:
 
         Args:
             name: The name of the root frame.
+
+        Raises:
+            InvalidFrameName: If the name is empty, "." or "..", contains "/" or control characters, or has leading
+                or trailing whitespace.
         """
         self._core_frame = _core.Frame(name)
 
@@ -48,25 +62,120 @@ class Frame:
         binding_orientation = self._core_frame.orientation
         return Rotation._from_rust(binding_orientation)
 
-    def add_child(self, name: str, position: Vector3, orientation: Rotation) -> Frame:
+    @property
+    def scale(self) -> float:
+        """The uniform scale of the frame's coordinates, 1.0 unless created via `add_scaled_child`."""
+        return self._core_frame.scale
+
+    @property
+    def is_static(self) -> bool:
+        """Whether the frame's transform to its parent is fixed after construction.
+
+        Updates of a static frame raise `StaticFrame` unless `set` is called with
+        `override_static=True`. The flag is kept by `to_json` and `from_json`.
+        """
+        return self._core_frame.is_static
+
+    @is_static.setter
+    def is_static(self, is_static: bool) -> None:
+        self._core_frame.is_static = is_static
+
+    @property
+    def metadata(self) -> FrameMetadata:
+        """The application data attached to the frame, as a dict-like view (e.g. `frame.metadata["mass"] = 1.2`)."""
+        return FrameMetadata(self._core_frame)
+
+    def clone_subtree(self) -> Frame:
+        """Deep-copies the frame and its descendants into a new, detached tree.
+
+        Names, transforms, metadata and stored poses are copied; the copy shares no data with the original. The copy
+        of this frame becomes the root of the new tree.
+
+        Returns:
+            The root of the copy.
+        """
+        return Frame._from_rust(self._core_frame.clone_subtree())
+
+    def attach_copy_under(self, parent: Frame, rename: str | None = None) -> Frame:
+        """Deep-copies the frame and its descendants and attaches the copy to another frame.
+
+        The copy keeps the transform of this frame to its parent.
+
+        Args:
+            parent: The frame to attach the copy to.
+            rename: The name of the copy. Keeps the name of this frame if omitted.
+
+        Returns:
+            The copy of this frame.
+
+        Raises:
+            DuplicateChildName: If the parent already has a child with the name of the copy.
+            InvalidFrameName: If a copied name is not valid in the tree of the parent.
+        """
+        return Frame._from_rust(self._core_frame.attach_copy_under(parent._binding_structure, rename))
+
+    def rename(self, new_name: str) -> None:
+        """Renames the frame.
+
+        Paths, JSON documents and lookups by name use the new name afterwards. Renaming the root is allowed.
+
+        Args:
+            new_name: The new name of the frame.
+
+        Raises:
+            InvalidFrameName: If the name is not a valid frame name.
+            DuplicateChildName: If a sibling of the frame already has the name.
+        """
+        self._core_frame.rename(new_name)
+
+    def add_child(self, name: str, position: VectorLike, orientation: RotationLike) -> Frame:
         """Adds a new child frame to the current frame.
 
+        Args:
+            name: The name of the new child frame.
+            position: The translational offset from the parent, a `Vector3` or a sequence `(x, y, z)` such as a
+                tuple or numpy array.
+            orientation: The orientational offset from the parent, a `Rotation`, a quaternion `(x, y, z, w)`,
+                roll-pitch-yaw angles `(roll, pitch, yaw)` in radians, or `None` for the identity.
+
+        Returns:
+            The newly created child frame.
+
+        Raises:
+            InvalidFrameName: If the name is empty, "." or "..", contains "/" or control characters, or has leading
+                or trailing whitespace.
+            DuplicateChildName: If a child with the same name already exists.
+            NonFiniteValue: If a component of the position or orientation is NaN or infinite.
+        """
+        binding_frame = self._core_frame.add_child(name, _binding_vector(position), _binding_rotation(orientation))
+        return Frame._from_rust(binding_frame)
+
+    def add_scaled_child(self, name: str, position: VectorLike, orientation: RotationLike, scale: float) -> Frame:
+        """Adds a new child frame whose coordinates are uniformly scaled relative to the current frame.
+
+        A point `p` in the child maps to `orientation * (scale * p) + position` in this frame, e.g. for registering
+        a scan with a slightly wrong scale.
+
         Args:
             name: The name of the new child frame.
             position: The translational offset from the parent.
             orientation: The orientational offset from the parent.
+            scale: The scale of the child coordinates, strictly positive.
 
         Returns:
             The newly created child frame.
 
         Raises:
-            ValueError: If a child with the same name already exists.
+            InvalidScale: If the scale is not a positive finite number.
+            DuplicateChildName: If a child with the same name already exists.
         """
-        binding_frame = self._core_frame.add_child(name, position._binding_structure, orientation._binding_structure)
+        binding_frame = self._core_frame.add_scaled_child(
+            name, _binding_vector(position), _binding_rotation(orientation), scale
+        )
         return Frame._from_rust(binding_frame)
 
     def calibrate_child(
-        self, name: str, desired_position: Vector3, desired_orientation: Rotation, reference_pose: Pose
+        self, name: str, desired_position: VectorLike, desired_orientation: RotationLike, reference_pose: Pose
     ) -> Frame:
         """Adds a child frame such that a reference pose, expressed in the new frame, matches the desired isometry.
 
@@ -80,29 +189,278 @@ class Frame:
             The newly created child frame.
 
         Raises:
-            ValueError: If a child with the same name already exists.
+            DuplicateChildName: If a child with the same name already exists.
         """
         binding_frame = self._core_frame.calibrate_child(
             name,
-            desired_position._binding_structure,
-            desired_orientation._binding_structure,
+            _binding_vector(desired_position),
+            _binding_rotation(desired_orientation),
             reference_pose._binding_structure,
         )
         return Frame._from_rust(binding_frame)
 
-    def add_pose(self, position: Vector3, orientation: Rotation) -> Pose:
+    def calibrate_child_from_points(self, name: str, observed: ArrayLike, expected: ArrayLike) -> tuple[Frame, float]:
+        """Adds a child frame fitted to point correspondences in the least-squares sense (Kabsch algorithm).
+
+        The transform of the child best maps the `expected` coordinates (in the new frame) onto the `observed`
+        coordinates (in this frame).
+
+        Args:
+            name: The name of the new child frame.
+            observed: The measured points in this frame, as an (N, 3) array or a sequence of (x, y, z) tuples.
+            expected: The coordinates of the same points in the new frame, in the same layout.
+
+        Returns:
+            The newly created child frame and the root-mean-square distance between the observed points and the
+            fitted expected points.
+
+        Raises:
+            ValueError: If the points do not have shape (N, 3).
+            LengthMismatch: If `observed` and `expected` differ in length.
+            DegenerateInput: If there are fewer than three points, or the points are coincident or collinear.
+            DuplicateChildName: If a child with the same name already exists.
+        """
+        binding_frame, rms = self._core_frame.calibrate_child_from_points(
+            name, _point_rows(observed, "observed"), _point_rows(expected, "expected")
+        )
+        return Frame._from_rust(binding_frame), rms
+
+    def calibrate_child_from_poses(
+        self, name: str, desired: Sequence[tuple[VectorLike, RotationLike]], observed: Sequence[Pose]
+    ) -> tuple[Frame, CalibrationReport]:
+        """Adds a child frame that best fits several reference poses in the least-squares sense.
+
+        Each observed pose should appear at the corresponding desired position and orientation
+        when expressed in the new frame.
+
+        Args:
+            name: The name of the new child frame.
+            desired: The desired positions and orientations of the poses in the new frame.
+            observed: The observed poses.
+
+        Returns:
+            The newly created child frame and a report of the per-sample residuals.
+
+        Raises:
+            LengthMismatch: If `desired` and `observed` differ in length.
+            DegenerateInput: If no pose pairs are given.
+            DuplicateChildName: If a child with the same name already exists.
+        """
+        binding_frame, binding_report = self._core_frame.calibrate_child_from_poses(
+            name,
+            [(_binding_vector(position), _binding_rotation(orientation)) for position, orientation in desired],
+            [pose._binding_structure for pose in observed],
+        )
+        return Frame._from_rust(binding_frame), CalibrationReport._from_rust(binding_report)
+
+    def add_child_from_three_points(
+        self,
+        name: str,
+        origin: VectorLike,
+        x_point: VectorLike,
+        xy_point: VectorLike,
+    ) -> Frame:
+        """Adds a child frame defined by three measured points, all expressed in this frame.
+
+        The X axis points from `origin` to `x_point`, the Z axis is the normal of the plane
+        through the three points with `xy_point` on the positive Y side, and Y completes the
+        right-handed frame.
+
+        Args:
+            name: The name of the new child frame.
+            origin: The origin of the new frame.
+            x_point: A point on the positive X axis of the new frame.
+            xy_point: A point in the XY plane of the new frame.
+
+        Returns:
+            The newly created child frame.
+
+        Raises:
+            DegenerateInput: If the points are coincident or collinear.
+            DuplicateChildName: If a child with the same name already exists.
+        """
+        binding_frame = self._core_frame.add_child_from_three_points(
+            name, _binding_vector(origin), _binding_vector(x_point), _binding_vector(xy_point)
+        )
+        return Frame._from_rust(binding_frame)
+
+    def add_child_from_plane_fit(
+        self, name: str, points: ArrayLike, x_hint: VectorLike | None = None
+    ) -> tuple[Frame, PlaneFitReport]:
+        """Adds a child frame whose XY plane is fitted to measured points, e.g. probed on a table surface.
+
+        The origin of the new frame is the centroid of the points and its Z axis the least-squares plane normal,
+        pointing towards the origin of this frame. The X axis is `x_hint` projected into the plane, or the direction
+        in which the points spread the most.
+
+        Args:
+            name: The name of the new child frame.
+            points: The measured points in this frame, as an (N, 3) array or a sequence of (x, y, z) tuples.
+            x_hint: A direction in this frame for the X axis of the new frame.
+
+        Returns:
+            The newly created child frame and a report of the out-of-plane distances of the points.
+
+        Raises:
+            ValueError: If the points do not have shape (N, 3).
+            DegenerateInput: If there are fewer than three points, the points are collinear, or `x_hint` is
+                perpendicular to the plane.
+            DuplicateChildName: If a child with the same name already exists.
+        """
+        binding_frame, binding_report = self._core_frame.add_child_from_plane_fit(
+            name, _point_rows(points, "points"), None if x_hint is None else _binding_vector(x_hint)
+        )
+        return Frame._from_rust(binding_frame), PlaneFitReport._from_rust(binding_report)
+
+    def add_pose(self, position: VectorLike, orientation: RotationLike) -> Pose:
         """Adds a pose to the current frame.
 
         Args:
-            position: The translational part of the pose.
-            orientation: The orientational part of the pose.
+            position: The translational part of the pose, see `Frame.add_child`.
+            orientation: The orientational part of the pose, see `Frame.add_child`.
+
+        Returns:
+            The newly created pose.
+
+        Raises:
+            NonFiniteValue: If a component of the position or orientation is NaN or infinite.
+        """
+        binding_pose = self._core_frame.add_pose(_binding_vector(position), _binding_rotation(orientation))
+        return Pose._from_rust(binding_pose)
+
+    def store_pose(self, name: str, pose: Pose, *, overwrite: bool = False) -> None:
+        """Stores a pose on this frame under the given name.
+
+        The pose is expressed in this frame first. Stored poses are included in `to_json` and restored by
+        `from_json` and `apply_config`.
+
+        Args:
+            name: The name of the pose, e.g. "home".
+            pose: The pose to store.
+            overwrite: Whether a pose already stored under the same name is replaced.
+
+        Raises:
+            DuplicatePoseName: If a pose with the same name is stored already and `overwrite` is False.
+            FrameDropped: If the frame of the pose has been dropped.
+            NoCommonAncestor: If the pose belongs to another tree.
+        """
+        self._core_frame.store_pose(name, pose._binding_structure, overwrite=overwrite)
+
+    def get_pose(self, name: str) -> Pose | None:
+        """Returns the pose stored under the given name, or None if there is no such pose."""
+        binding_pose = self._core_frame.get_pose(name)
+        return None if binding_pose is None else Pose._from_rust(binding_pose)
+
+    def remove_pose(self, name: str) -> Pose | None:
+        """Removes the pose stored under the given name and returns it, or None if there was no such pose."""
+        binding_pose = self._core_frame.remove_pose(name)
+        return None if binding_pose is None else Pose._from_rust(binding_pose)
+
+    def pose_names(self) -> list[str]:
+        """Returns the names of all poses stored on this frame in sorted order."""
+        return self._core_frame.pose_names()
+
+    def poses(self) -> list[Pose]:
+        """Returns copies of all live poses expressed in this frame, in the order they were created.
+
+        Dropped poses are skipped. The copies reflect the latest changes of the poses, but do not count as poses
+        of the frame themselves.
+        """
+        return [Pose._from_rust(binding_pose) for binding_pose in self._core_frame.poses()]
+
+    def pose_count(self) -> int:
+        """Returns the number of live poses expressed in this frame."""
+        return self._core_frame.pose_count()
+
+    def store_region(self, name: str, shape: RegionShape, *, overwrite: bool = False) -> Region:
+        """Stores a region of the given shape on this frame under the given name and returns it.
+
+        Stored regions are included in `to_json` and restored by `from_json` and `apply_config`.
+
+        Args:
+            name: The name of the region, e.g. "workspace".
+            shape: The shape of the region, in the coordinates of this frame.
+            overwrite: Whether a region already stored under the same name is replaced.
+
+        Raises:
+            DuplicateRegionName: If a region with the same name is stored already and `overwrite` is False.
+            DegenerateInput: If a corner of a box exceeds the other or a radius is negative.
+            NonFiniteValue: If a value of the shape is NaN or infinite.
+        """
+        return Region._from_rust(self._core_frame.store_region(name, shape._binding_structure, overwrite=overwrite))
+
+    def get_region(self, name: str) -> Region | None:
+        """Returns the region stored under the given name, or None if there is no such region."""
+        binding_region = self._core_frame.get_region(name)
+        return None if binding_region is None else Region._from_rust(binding_region)
+
+    def remove_region(self, name: str) -> Region | None:
+        """Removes the region stored under the given name and returns it, or None if there was no such region."""
+        binding_region = self._core_frame.remove_region(name)
+        return None if binding_region is None else Region._from_rust(binding_region)
+
+    def region_names(self) -> list[str]:
+        """Returns the names of all regions stored on this frame in sorted order."""
+        return self._core_frame.region_names()
+
+    def add_pose_from_matrix(self, matrix: ArrayLike, *, orthonormalize: bool = False) -> Pose:
+        """Adds a pose given as a 4x4 homogeneous transformation matrix to the current frame.
+
+        Args:
+            matrix: The transformation as a 4x4 numpy array or nested lists (row-major).
+            orthonormalize: Whether to use the closest rotation instead of rejecting rotation blocks that are not
+                orthonormal.
 
         Returns:
             The newly created pose.
+
+        Raises:
+            ValueError: If the matrix does not have shape (4, 4).
+            InvalidTransformMatrix: If the last row is not [0, 0, 0, 1], the translation is not finite, or the
+                rotation block is not orthonormal or is a reflection.
         """
-        binding_pose = self._core_frame.add_pose(position._binding_structure, orientation._binding_structure)
+        rows = np.asarray(matrix, dtype=np.float64)
+        if rows.shape != (4, 4):
+            msg = f"matrix must have shape (4, 4), got {rows.shape}"
+            raise ValueError(msg)
+        binding_pose = self._core_frame.add_pose_from_matrix(rows.tolist(), orthonormalize)
         return Pose._from_rust(binding_pose)
 
+    def transform_to(self, target: Frame) -> NDArray[np.float64]:
+        """Returns the transformation from this frame to the target frame as a 4x4 homogeneous matrix.
+
+        The matrix maps coordinates expressed in this frame to coordinates expressed in the target frame. Chains
+        through scaled frames (see `add_scaled_child`) yield a similarity, whose rotation block carries the scale.
+
+        Args:
+            target: The frame to transform into.
+
+        Returns:
+            The homogeneous transformation matrix.
+
+        Raises:
+            NoCommonAncestor: If the frames belong to different trees.
+        """
+        return np.array(self._core_frame.transform_to(target._binding_structure), dtype=np.float64)
+
+    def transformation_to(self, target: Frame) -> tuple[Vector3, Quaternion]:
+        """Returns the transformation from this frame to the target frame.
+
+        This is the decomposed counterpart of `transform_to`.
+
+        Args:
+            target: The frame to transform into.
+
+        Returns:
+            The translation and orientation of this frame expressed in the target frame.
+
+        Raises:
+            NoCommonAncestor: If the frames belong to different trees.
+            ScaledTransform: If the chain passes through a scaled frame, which is no isometry.
+        """
+        binding_position, binding_rotation = self._core_frame.transformation_to(target._binding_structure)
+        return Vector3(*binding_position.to_tuple()), Quaternion._from_rust(binding_rotation)
+
     def transformation(self) -> tuple[Vector3, Rotation]:
         """Returns the transformation from this frame to its parent frame.
 
@@ -110,7 +468,7 @@ class Frame:
             The transformation from this frame to its parent frame (translation, rotation).
 
         Raises:
-            ValueError: If the frame has no parent.
+            RootHasNoParent: If the frame has no parent.
         """
         binding_position, binding_rotation = self._core_frame.transformation()
         return (
@@ -118,17 +476,24 @@ class Frame:
             Rotation._from_rust(binding_rotation),
         )
 
-    def set(self, position: Vector3, orientation: Rotation) -> None:
+    def set(
+        self, position: VectorLike, orientation: RotationLike, *, override_static: bool = False
+    ) -> None:
         """Sets the frames transformation relative to its parent.
 
         Args:
-            position: The translational offset from the parent.
-            orientation: The orientational offset from the parent.
+            position: The translational offset from the parent, see `add_child`.
+            orientation: The orientational offset from the parent, see `add_child`.
+            override_static: Whether to update the frame even if it is static, see `is_static`.
 
         Raises:
-            ValueError: If the frame has no parent.
+            CannotUpdateRootTransform: If the frame has no parent.
+            StaticFrame: If the frame is static and `override_static` is not set.
+            NonFiniteValue: If a component of the position or orientation is NaN or infinite.
         """
-        self._core_frame.set(position._binding_structure, orientation._binding_structure)
+        self._core_frame.set(
+            _binding_vector(position), _binding_rotation(orientation), override_static
+        )
 
     def apply_in_parent_frame(self, isometry: Isometry) -> None:
         """Applies the provided isometry interpreted in the parent frame to this frame.
@@ -138,7 +503,8 @@ class Frame:
                 apply to the current transformation.
 
         Raises:
-            ValueError: If the frame has no parent.
+            CannotUpdateRootTransform: If the frame has no parent.
+            StaticFrame: If the frame is static.
         """
         self._core_frame.apply_in_parent_frame(isometry._binding_structure)
 
@@ -150,18 +516,104 @@ class Frame:
                 transformation.
 
         Raises:
-            ValueError: If the frame has no parent.
+            CannotUpdateRootTransform: If the frame has no parent.
+            StaticFrame: If the frame is static.
         """
         self._core_frame.apply_in_local_frame(isometry._binding_structure)
 
+    def transform_point_to(self, target: Frame, point: VectorLike) -> Vector3:
+        """Maps a point expressed in this frame into the target frame.
+
+        Args:
+            target: The frame to express the point in.
+            point: The point in this frame.
+
+        Returns:
+            The point expressed in the target frame.
+
+        Raises:
+            NoCommonAncestor: If the frames belong to different trees.
+        """
+        binding_point = self._core_frame.transform_point_to(target._binding_structure, _binding_vector(point))
+        return Vector3(*binding_point.to_tuple())
+
+    def transform_points_to(self, target: Frame, points: ArrayLike) -> NDArray[np.float64]:
+        """Maps a batch of points expressed in this frame into the target frame.
+
+        The transformation between the frames is resolved once and applied to all points in
+        native code. The GIL is released while the points are transformed, so other Python threads
+        keep running during large batches.
+
+        Args:
+            target: The frame to express the points in.
+            points: The points in this frame as an array of shape (N, 3).
+
+        Returns:
+            The points expressed in the target frame as a new array of shape (N, 3).
+
+        Raises:
+            ValueError: If the points do not have shape (N, 3).
+            NoCommonAncestor: If the frames belong to different trees.
+        """
+        array = np.ascontiguousarray(points, dtype=np.float64)
+        if array.ndim != 2 or array.shape[1] != 3:  # noqa: PLR2004
+            msg = f"expected points of shape (N, 3), got {array.shape}"
+            raise ValueError(msg)
+        result = np.empty_like(array)
+        self._core_frame.transform_points_to(target._binding_structure, array, result)
+        return result
+
+    def on_transform_changed(self, callback: Callable[[Frame], None], *, include_descendants: bool = False) -> int:
+        """Registers a callback that is invoked after the transform of this frame changed.
+
+        Callbacks run after `set`, `apply_in_parent_frame`, `apply_in_local_frame` and `apply_config`
+        and receive the changed frame. They may read and modify the tree; exceptions raised by a
+        callback are reported as unraisable and do not affect the update.
+
+        Args:
+            callback: Called with the changed frame.
+            include_descendants: Whether changes to descendants of this frame notify the callback too.
+
+        Returns:
+            The id used to unsubscribe the callback.
+        """
+        return self._core_frame.on_transform_changed(
+            lambda rust_frame: callback(Frame._from_rust(rust_frame)),
+            include_descendants=include_descendants,
+        )
+
+    def unsubscribe(self, subscription_id: int) -> bool:
+        """Removes a callback registered on this frame.
+
+        Args:
+            subscription_id: The id returned by `on_transform_changed`.
+
+        Returns:
+            True if the callback was registered on this frame and has been removed.
+        """
+        return self._core_frame.unsubscribe(subscription_id)
+
+    def validate(self) -> list[str]:
+        """Checks the structure of this frame and its descendants.
+
+        Verifies parent references, unique sibling names, the absence of cycles, and that all transforms are
+        finite with normalized orientations.
+
+        Returns:
+            A description of each violation found, naming the path of the offending frame. Empty if the tree is valid.
+        """
+        return self._core_frame.validate()
+
     def to_json(self) -> str:
         """Serializes the frame tree to a JSON string.
 
+        The GIL is released while the JSON text is generated.
+
         Returns:
             The JSON representation of the tree.
 
         Raises:
-            ValueError: On serialization failure.
+            SerdeError: On serialization failure.
         """
         return self._core_frame.to_json()
 
@@ -176,10 +628,112 @@ class Frame:
             config_json: The JSON string to apply.
 
         Raises:
-            ValueError: On deserialization or mismatch errors (e.g. if this frame is not the root).
+            SerdeError: On deserialization errors.
+            Mismatch: If the root names do not match (e.g. if this frame is not the root).
+            UnsupportedConfigVersion: If the config was written in an unsupported format version.
         """
         self._core_frame.apply_config(config_json)
 
+    def apply_patch(self, patch_json: str, create_missing: bool = False) -> None:
+        """Updates the transforms of individual frames from a JSON patch.
+
+        The patch is a list of entries such as
+        `{"path": "robot/flange/tool", "position": [0, 0, 0.1], "rpy": [0, 0, 1.57]}` with paths
+        relative to this frame. The orientation is given either as `rpy` (radians) or as an
+        `orientation` quaternion `[x, y, z, w]`. Omitted fields keep their current values.
+        Nothing is changed if an error is raised.
+
+        Args:
+            patch_json: The JSON patch.
+            create_missing: Whether frames for unknown paths are created instead of rejected.
+                Intermediate frames get identity transforms.
+
+        Raises:
+            SerdeError: If the string is not valid JSON or an entry is malformed.
+            MissingFrames: If paths are unknown and `create_missing` is false. All unknown paths
+                are reported together.
+            InvalidDocument: If an entry sets both `rpy` and `orientation`.
+        """
+        self._core_frame.apply_patch(patch_json, create_missing)
+
+    def capture_state(self) -> TreeState:
+        """Records the transforms to the parents of this frame and its descendants.
+
+        Only the transforms are copied, which makes this much cheaper than `to_json`, e.g. to
+        capture the tree before every edit for undo.
+
+        Returns:
+            The recorded transforms, keyed by their path relative to this frame.
+        """
+        return TreeState._from_rust(self._core_frame.capture_state())
+
+    def restore_state(self, state: TreeState) -> None:
+        """Writes transforms recorded by `capture_state` back into the tree.
+
+        Frames are matched by their path relative to this frame. Structure, metadata and stored
+        poses are not changed.
+
+        Args:
+            state: The transforms to restore.
+
+        Raises:
+            MissingFrames: If captured frames no longer exist. No transform is changed then.
+        """
+        self._core_frame.restore_state(state._core_state)
+
+    def diff(self, other: Frame, lin_tol: float = 1e-9, ang_tol: float = 1e-9) -> TreeDiff:
+        """Compares this frame and its descendants with another frame and its descendants.
+
+        Frames are matched by their path below the compared frames. Metadata and stored poses are
+        ignored.
+
+        Args:
+            other: The frame to compare with.
+            lin_tol: The translational distance up to which transforms are considered equal.
+            ang_tol: The angle in radians up to which rotations are considered equal.
+
+        Returns:
+            The frames added or removed in `other`, and those whose transforms to their parents
+            differ.
+        """
+        return TreeDiff._from_rust(self._core_frame.diff(other._core_frame, lin_tol, ang_tol))
+
+    def diff_config(self, config_json: str, lin_tol: float = 1e-9, ang_tol: float = 1e-9) -> TreeDiff:
+        """Compares this frame and its descendants with a JSON config without changing the tree.
+
+        Args:
+            config_json: The JSON string to compare with, in the format written by `to_json`.
+            lin_tol: The translational distance up to which transforms are considered equal.
+            ang_tol: The angle in radians up to which rotations are considered equal.
+
+        Returns:
+            The differences as in `diff`, i.e. what `apply_config` would change.
+
+        Raises:
+            SerdeError: If the string is not valid JSON.
+            InvalidDocument: If a node is malformed or two siblings share a name.
+        """
+        return TreeDiff._from_rust(self._core_frame.diff_config(config_json, lin_tol, ang_tol))
+
+    @classmethod
+    def from_json(cls, json_str: str) -> Frame:
+        """Builds a new frame tree from a JSON string in the format written by `to_json`.
+
+        Args:
+            json_str: The JSON string describing the tree.
+
+        Returns:
+            The root frame of the new tree.
+
+        Raises:
+            SerdeError: If the string is not valid JSON.
+            InvalidDocument: If a node is malformed or two siblings share a name. The message
+                contains the JSON path of the offending field.
+            UnsupportedConfigVersion: If the document was written in an unsupported format version.
+                Documents of older versions are migrated.
+        """
+        return cls._from_rust(_core.Frame.from_json(json_str))
+
     def parent(self) -> Frame | None:
         """Returns the parent of the frame.
 
@@ -208,41 +762,401 @@ class Frame:
         """
         return [Frame._from_rust(binding_child) for binding_child in self._core_frame.children()]
 
-    def __add__(self, lazy_access: LazyTranslation) -> Frame:
-        return Frame._from_rust(self._core_frame + lazy_access.inner)
+    def lowest_common_ancestor(self, other: Frame) -> Frame | None:
+        """Returns the lowest common ancestor of this frame and another frame.
 
-    def __sub__(self, lazy_access: LazyTranslation) -> Frame:
-        return Frame._from_rust(self._core_frame - lazy_access.inner)
+        A frame counts as part of its own subtree here, so the result is this frame if `other` is one of its
+        descendants (or the frame itself).
 
-    def __mul__(self, lazy_access: LazyRotation) -> Frame:
-        return Frame._from_rust(self._core_frame * lazy_access.inner)
+        Args:
+            other: The other frame.
 
-    def __str__(self) -> str:
-        return self._core_frame.__str__()
+        Returns:
+            The deepest frame whose subtree contains both frames, or None if they belong to different trees.
+        """
+        binding_frame = self._core_frame.lowest_common_ancestor(other._binding_structure)
+        if binding_frame is None:
+            return None
+        return Frame._from_rust(binding_frame)
 
-    def __repr__(self) -> str:
-        return self._core_frame.__repr__()
+    def is_ancestor_of(self, other: Frame) -> bool:
+        """Returns whether this frame is a (strict) ancestor of another frame.
 
-    @property
-    def _binding_structure(self) -> Any:
-        return self._core_frame
+        Args:
+            other: The other frame.
 
-    @classmethod
+        Returns:
+            True if `other` lies in the subtree below this frame. A frame is not an ancestor of itself.
+        """
+        return self._core_frame.is_ancestor_of(other._binding_structure)
+
+    def find(self, name: str) -> Frame | None:
+        """Searches this frame and its descendants (depth-first) for a frame with the given name.
+
+        Args:
+            name: The name of the frame to search for.
+
+        Returns:
+            The first frame with the given name or None if there is no such frame.
+        """
+        binding_frame = self._core_frame.find(name)
+        if binding_frame is None:
+            return None
+        return Frame._from_rust(binding_frame)
+
+    def resolve(self, path: str) -> Frame:
+        """Resolves a path relative to this frame.
+
+        Segments are separated by `/`, `..` steps to the parent, `.` stays at the current frame and a leading `/`
+        starts at the root, whose name must be the first segment.
+
+        Args:
+            path: The path to resolve, e.g. `"robot/flange"` or `"/world/robot"`.
+
+        Returns:
+            The frame the path points to.
+
+        Raises:
+            PathNotFound: If a segment cannot be resolved. The exception names the `segment`.
+        """
+        return Frame._from_rust(self._core_frame.resolve(path))
+
+    def find_all(self, name: str) -> list[Frame]:
+        """Searches this frame and its descendants for all frames with the given name.
+
+        Args:
+            name: The name of the frames to search for.
+
+        Returns:
+            All frames with the given name in depth-first order.
+        """
+        return [Frame._from_rust(binding_frame) for binding_frame in self._core_frame.find_all(name)]
+
+    def __getitem__(self, path: str) -> Frame:
+        """Returns the frame at a path relative to this frame, e.g. `frame["robot/flange/tool"]`.
+
+        A plain name resolves a direct child. Paths are resolved like in `resolve`.
+
+        Raises:
+            KeyError: If the path cannot be resolved.
+        """
+        return Frame._from_rust(self._core_frame[path])
+
+    def __contains__(self, path: object) -> bool:
+        """Returns whether `frame[path]` would succeed."""
+        return isinstance(path, str) and path in self._core_frame
+
+    def __len__(self) -> int:
+        """Returns the number of direct children."""
+        return len(self._core_frame)
+
+    def __iter__(self) -> Iterator[Frame]:
+        """Iterates over the direct children.
+
+        The children are collected when iteration starts. Children added, renamed or moved while iterating do
+        not change which frames the loop yields.
+        """
+        return (Frame._from_rust(binding_child) for binding_child in self._core_frame)
+
+    def __bool__(self) -> bool:
+        """Returns True, so frames without children are not falsy despite `__len__`."""
+        return True
+
+    def render_tree(self, *, ascii_only: bool = False) -> str:
+        """Renders this frame and its descendants as a tree, similar to the `tree` command.
+
+        Each line shows the frame name, its translation and its RPY angles relative to its parent.
+
+        Args:
+            ascii_only: Whether to draw the branches with plain ASCII instead of box-drawing characters.
+
+        Returns:
+            The rendered tree, one line per frame.
+        """
+        return self._core_frame.render_tree(ascii_only)
+
+    def to_dot(self, *, include_transforms: bool = True, precision: int = 3) -> str:
+        """Exports this frame and its descendants as a Graphviz digraph.
+
+        Nodes are labelled with the frame name and world translation, edges with the translation
+        and RPY angles of the child relative to its parent.
+
+        Args:
+            include_transforms: Whether to add the transforms to the labels.
+            precision: The number of decimal places used for transform values.
+
+        Returns:
+            The DOT source, which can be piped into `dot`.
+        """
+        return self._core_frame.to_dot(include_transforms, precision)
+
+    def to_csv(self, *, degrees: bool = False, quaternion: bool = False) -> str:
+        """Exports this frame and its descendants as a CSV table, one line per frame.
+
+        The columns are the frame path, the world position, the world orientation, the depth and
+        the path of the parent, with frames in depth-first order.
+
+        Args:
+            degrees: Whether roll, pitch and yaw are given in degrees instead of radians.
+            quaternion: Whether the orientation is given as quaternion (qx, qy, qz, qw) instead of
+                roll, pitch and yaw.
+
+        Returns:
+            The CSV text including a header line.
+        """
+        return self._core_frame.to_csv(degrees, quaternion)
+
+    def __add__(self, lazy_access: LazyTranslation) -> Frame:
+        return Frame._from_rust(self._core_frame + lazy_access.inner)
+
+    def __sub__(self, lazy_access: LazyTranslation) -> Frame:
+        return Frame._from_rust(self._core_frame - lazy_access.inner)
+
+    def __mul__(self, lazy_access: LazyRotation) -> Frame:
+        return Frame._from_rust(self._core_frame * lazy_access.inner)
+
+    def same_frame(self, other: Frame) -> bool:
+        """Checks whether this object and another one refer to the same frame in the tree.
+
+        Frames returned by different accessors (e.g. `find`, `children` or `parent`) are distinct Python objects,
+        but refer to the same frame. `==` and `hash` follow this identity, so frames can be used in sets and as
+        dictionary keys.
+
+        Args:
+            other: The frame to compare with.
+
+        Returns:
+            True if both refer to the same frame.
+        """
+        return self._core_frame.same_frame(other._binding_structure)
+
+    def __eq__(self, other: object) -> bool:
+        if not isinstance(other, Frame):
+            return NotImplemented
+        return self.same_frame(other)
+
+    def __hash__(self) -> int:
+        return hash(self._core_frame)
+
+    def __reduce__(self) -> tuple[Callable[[_core.Frame], Frame], tuple[_core.Frame]]:
+        """Pickles the tree below this root frame via `to_json`.
+
+        Only root frames can be pickled, as the JSON of a child frame lacks its ancestors and an unpickled child
+        frame would be detached from its tree. Pickle the root, or a copy from `clone_subtree`, instead.
+
+        Raises:
+            ValueError: If the frame is not a root.
+        """
+        return (Frame._from_rust, (self._core_frame,))
+
+    def __copy__(self) -> Frame:
+        """Returns another handle to the same frame."""
+        return Frame._from_rust(self._core_frame)
+
+    def __deepcopy__(self, memo: dict[int, Any]) -> Frame:
+        """Returns a detached copy of this frame and its descendants, see `clone_subtree`."""
+        return self.clone_subtree()
+
+    def __str__(self) -> str:
+        return self._core_frame.__str__()
+
+    def __repr__(self) -> str:
+        return self._core_frame.__repr__()
+
+    @property
+    def _binding_structure(self) -> Any:
+        return self._core_frame
+
+    @classmethod
     def _from_rust(cls, rust_frame: _core.Frame) -> Frame:
         instance = cls.__new__(cls)
         instance._core_frame = rust_frame
         return instance
 
 
+def _point_rows(points: ArrayLike, argument: str) -> list[list[float]]:
+    """Returns an (N, 3) point argument as nested lists for the bindings."""
+    array = np.asarray(points, dtype=np.float64)
+    if array.size == 0:
+        array = array.reshape(0, 3)
+    if array.ndim != 2 or array.shape[1] != 3:  # noqa: PLR2004
+        msg = f"expected {argument} of shape (N, 3), got {array.shape}"
+        raise ValueError(msg)
+    return array.tolist()
+
+
+class CalibrationReport:
+    """Summarizes how well a calibrated frame fits the measurements it was computed from."""
+
+    _core_report: _core.CalibrationReport
+
+    @property
+    def residuals(self) -> list[tuple[float, float]]:
+        """The translational and rotational (radians) residuals of each sample, in input order."""
+        return self._core_report.residuals
+
+    @property
+    def max_translation_error(self) -> float:
+        """The largest translational residual."""
+        return self._core_report.max_translation_error
+
+    @property
+    def mean_translation_error(self) -> float:
+        """The mean translational residual."""
+        return self._core_report.mean_translation_error
+
+    @property
+    def max_rotation_error(self) -> float:
+        """The largest rotational residual in radians."""
+        return self._core_report.max_rotation_error
+
+    @property
+    def mean_rotation_error(self) -> float:
+        """The mean rotational residual in radians."""
+        return self._core_report.mean_rotation_error
+
+    @classmethod
+    def _from_rust(cls, rust_report: _core.CalibrationReport) -> CalibrationReport:
+        instance = cls.__new__(cls)
+        instance._core_report = rust_report
+        return instance
+
+    def __str__(self) -> str:
+        return self._core_report.__str__()
+
+    def __repr__(self) -> str:
+        return self._core_report.__repr__()
+
+
+class PlaneFitReport:
+    """Summarizes how well a plane fits the points it was computed from, see `Frame.add_child_from_plane_fit`."""
+
+    _core_report: _core.PlaneFitReport
+
+    @property
+    def residuals(self) -> list[float]:
+        """The signed distance of each point from the plane along its normal, in input order."""
+        return self._core_report.residuals
+
+    @property
+    def rms_error(self) -> float:
+        """The root-mean-square distance of the points from the plane."""
+        return self._core_report.rms_error
+
+    @property
+    def max_error(self) -> float:
+        """The largest distance of a point from the plane."""
+        return self._core_report.max_error
+
+    @classmethod
+    def _from_rust(cls, rust_report: _core.PlaneFitReport) -> PlaneFitReport:
+        instance = cls.__new__(cls)
+        instance._core_report = rust_report
+        return instance
+
+    def __str__(self) -> str:
+        return self._core_report.__str__()
+
+    def __repr__(self) -> str:
+        return self._core_report.__repr__()
+
+
+class TreeState:
+    """The transforms of a frame and its descendants, see `Frame.capture_state`."""
+
+    _core_state: _core.TreeState
+
+    def paths(self) -> list[str]:
+        """Returns the paths of the captured frames relative to the captured frame (`.`)."""
+        return self._core_state.paths()
+
+    @classmethod
+    def _from_rust(cls, rust_state: _core.TreeState) -> TreeState:
+        instance = cls.__new__(cls)
+        instance._core_state = rust_state
+        return instance
+
+    def __len__(self) -> int:
+        return len(self._core_state)
+
+    def __str__(self) -> str:
+        return self._core_state.__str__()
+
+    def __repr__(self) -> str:
+        return self._core_state.__repr__()
+
+
+class TreeDiff:
+    """The differences between two frame trees, with paths relative to the compared frames."""
+
+    _core_diff: _core.TreeDiff
+
+    @property
+    def added(self) -> list[str]:
+        """The paths of frames that only exist in the other tree."""
+        return self._core_diff.added
+
+    @property
+    def removed(self) -> list[str]:
+        """The paths of frames that only exist in this tree."""
+        return self._core_diff.removed
+
+    @property
+    def translation_changed(self) -> list[tuple[str, float]]:
+        """The paths and translational distances of frames that moved."""
+        return self._core_diff.translation_changed
+
+    @property
+    def rotation_changed(self) -> list[tuple[str, float]]:
+        """The paths and angles in radians of frames that rotated."""
+        return self._core_diff.rotation_changed
+
+    def is_empty(self) -> bool:
+        """Returns whether the trees are equal within the tolerances."""
+        return self._core_diff.is_empty()
+
+    def to_json(self) -> str:
+        """Serializes the differences to a JSON string.
+
+        Returns:
+            The JSON representation of the differences.
+        """
+        return self._core_diff.to_json()
+
+    @classmethod
+    def _from_rust(cls, rust_diff: _core.TreeDiff) -> TreeDiff:
+        instance = cls.__new__(cls)
+        instance._core_diff = rust_diff
+        return instance
+
+    def __str__(self) -> str:
+        return self._core_diff.__str__()
+
+    def __repr__(self) -> str:
+        return self._core_diff.__repr__()
+
+
 class Pose:
     """Defines a Cartesian pose."""
 
     _core_pose: _core.Pose
 
     def frame(self) -> Frame:
-        """Returns the frame of the pose."""
+        """Returns the frame of the pose.
+
+        Raises:
+            FrameDropped: If the frame has been dropped, e.g. because no handle to its tree is left.
+        """
         return Frame._from_rust(self._core_pose.frame())
 
+    def to_matrix(self) -> NDArray[np.float64]:
+        """Returns the transformation of the pose to its parent frame as a 4x4 homogeneous matrix.
+
+        Returns:
+            The homogeneous transformation matrix.
+        """
+        return np.array(self._core_pose.to_matrix(), dtype=np.float64)
+
     def transformation(self) -> tuple[Vector3, Rotation]:
         """Returns the transformation of the pose to its parent frame.
 
@@ -257,24 +1171,44 @@ class Pose:
 
     @property
     def position(self) -> Vector3:
-        """The position of the pose."""
+        """The position of the pose. Setting it keeps the orientation."""
         binding_position = self._core_pose.position
         return Vector3(*binding_position.to_tuple())
 
+    @position.setter
+    def position(self, position: Vector3) -> None:
+        self._core_pose.position = _binding_vector(position)
+
     @property
     def orientation(self) -> Rotation:
-        """The orientation of the pose."""
+        """The orientation of the pose. Setting it keeps the position."""
         binding_orientation = self._core_pose.orientation
         return Rotation._from_rust(binding_orientation)
 
-    def set(self, position: Vector3, orientation: Rotation) -> None:
+    @orientation.setter
+    def orientation(self, orientation: Rotation) -> None:
+        self._core_pose.orientation = _binding_rotation(orientation)
+
+    @property
+    def rpy(self) -> RPY:
+        """The orientation of the pose as roll, pitch and yaw angles. Setting it keeps the position."""
+        return RPY._from_rust(self._core_pose.orientation)
+
+    @rpy.setter
+    def rpy(self, rpy: RPY) -> None:
+        self._core_pose.orientation = rpy._core_rotation
+
+    def set(self, position: VectorLike, orientation: RotationLike) -> None:
         """Sets the pose's transformation.
 
         Args:
-            position: The translational part of the pose.
-            orientation: The orientational part of the pose.
+            position: The translational part of the pose, see `Frame.add_child`.
+            orientation: The orientational part of the pose, see `Frame.add_child`.
+
+        Raises:
+            NonFiniteValue: If a component of the position or orientation is NaN or infinite.
         """
-        self._core_pose.set(position._binding_structure, orientation._binding_structure)
+        self._core_pose.set(_binding_vector(position), _binding_rotation(orientation))
 
     def apply_in_parent_frame(self, isometry: Isometry) -> None:
         """Applies the provided isometry interpreted in the parent frame to this pose.
@@ -297,6 +1231,21 @@ class Pose:
         """
         self._core_pose.apply_in_local_frame(isometry._binding_structure)
 
+    def into_frame(self, name: str) -> Frame:
+        """Adds a child frame at this pose to the frame of the pose.
+
+        Args:
+            name: The name of the new child frame.
+
+        Returns:
+            The newly created child frame.
+
+        Raises:
+            FrameDropped: If the frame of the pose has been dropped.
+            DuplicateChildName: If the frame of the pose already has a child with the same name.
+        """
+        return Frame._from_rust(self._core_pose.into_frame(name))
+
     def in_frame(self, target_frame: Frame) -> Pose:
         """Transforms this pose into the coordinate system of the given target frame.
 
@@ -309,6 +1258,133 @@ class Pose:
         binding_pose = self._core_pose.in_frame(target_frame._binding_structure)
         return Pose._from_rust(binding_pose)
 
+    def rebase(self, new_parent: Frame) -> None:
+        """Re-parents this pose onto another frame in place, keeping its world location.
+
+        Args:
+            new_parent: The frame to attach this pose to.
+        """
+        self._core_pose.rebase(new_parent._binding_structure)
+
+    @classmethod
+    def mean(cls, poses: Sequence[Pose], weights: Sequence[float] | None = None) -> Pose:
+        """Averages several measurements of the same pose.
+
+        All poses are expressed in the frame of the first pose. Positions are averaged arithmetically and
+        orientations as the dominant eigenvector of the summed quaternion outer products, which does not depend on
+        the sign of the quaternions. A single pose is returned unchanged.
+
+        Args:
+            poses: The measurements to average.
+            weights: The non-negative weight of each measurement. Equal weights if omitted.
+
+        Returns:
+            The average pose in the frame of the first pose.
+
+        Raises:
+            DegenerateInput: If `poses` is empty or a weight is negative or not finite.
+            LengthMismatch: If `weights` and `poses` differ in length.
+            NoCommonAncestor: If the poses belong to different trees.
+        """
+        binding_weights = None if weights is None else list(weights)
+        return cls._from_rust(_core.Pose.mean([pose._binding_structure for pose in poses], binding_weights))
+
+    def interpolate(self, other: Pose, t: float) -> Pose:
+        """Interpolates between this pose (t = 0) and another pose (t = 1).
+
+        The other pose is expressed in the frame of this pose first. The position is interpolated linearly and the
+        orientation along the shortest arc.
+
+        Args:
+            other: The pose at t = 1.
+            t: The interpolation parameter. Values outside [0, 1] extrapolate.
+
+        Returns:
+            The interpolated pose in the frame of this pose.
+
+        Raises:
+            NonFiniteValue: If t is NaN or infinite.
+            NoCommonAncestor: If the poses belong to different trees.
+        """
+        return Pose._from_rust(self._core_pose.interpolate(other._binding_structure, t))
+
+    def translational_distance_to(self, other: Pose) -> float:
+        """Returns the distance between the positions of this pose and another pose.
+
+        The other pose is expressed in the frame of this pose first.
+
+        Args:
+            other: The pose to compare with.
+
+        Returns:
+            The Euclidean distance between the positions.
+
+        Raises:
+            NoCommonAncestor: If the poses belong to different trees.
+        """
+        return self._core_pose.translational_distance_to(other._binding_structure)
+
+    def angular_distance_to(self, other: Pose) -> float:
+        """Returns the angle of the rotation between the orientations of this pose and another pose.
+
+        The other pose is expressed in the frame of this pose first.
+
+        Args:
+            other: The pose to compare with.
+
+        Returns:
+            The angle in radians, between 0 and pi.
+
+        Raises:
+            NoCommonAncestor: If the poses belong to different trees.
+        """
+        return self._core_pose.angular_distance_to(other._binding_structure)
+
+    def approx_eq(self, other: Pose, lin_tol: float = 1e-9, ang_tol: float = 1e-9) -> bool:
+        """Checks whether this pose and another pose are within the given tolerances.
+
+        Unlike `==`, which requires the same frame and an exactly equal transformation, the poses may be
+        expressed in different frames of the same tree.
+
+        Args:
+            other: The pose to compare with.
+            lin_tol: The maximal translational distance.
+            ang_tol: The maximal angular distance in radians.
+
+        Returns:
+            True if both distances are within their tolerance.
+
+        Raises:
+            NoCommonAncestor: If the poses belong to different trees.
+        """
+        return self._core_pose.approx_eq(other._binding_structure, lin_tol, ang_tol)
+
+    def transform_point(self, point: VectorLike) -> Vector3:
+        """Maps a point from the local coordinates of this pose into its frame.
+
+        Args:
+            point: The point relative to this pose.
+
+        Returns:
+            The point expressed in the frame of this pose.
+        """
+        binding_point = self._core_pose.transform_point(_binding_vector(point))
+        return Vector3(*binding_point.to_tuple())
+
+    def transform_vector(self, vector: VectorLike) -> Vector3:
+        """Maps a direction from the local coordinates of this pose into its frame.
+
+        Only the orientation of the pose is applied, its position is ignored.
+
+        Args:
+            vector: The direction relative to this pose.
+
+        Returns:
+            The direction expressed in the frame of this pose.
+        """
+        binding_vector = self._core_pose.transform_vector(_binding_vector(vector))
+        return Vector3(*binding_vector.to_tuple())
+
     @property
     def _binding_structure(self) -> Any:
         return self._core_pose
@@ -328,8 +1404,259 @@ class Pose:
     def __mul__(self, lazy_access: LazyRotation) -> Pose:
         return Pose._from_rust(self._core_pose * lazy_access.inner)
 
+    def __eq__(self, other: object) -> bool:
+        """Checks whether the poses are in the same frame with exactly equal transformations, see `approx_eq`."""
+        if not isinstance(other, Pose):
+            return NotImplemented
+        return self._core_pose.__eq__(other._binding_structure)
+
+    # Poses are mutable, so they are not hashable.
+    __hash__ = None  # type: ignore[assignment]
+
     def __str__(self) -> str:
         return self._core_pose.__str__()
 
     def __repr__(self) -> str:
         return self._core_pose.__repr__()
+
+
+class Trajectory:
+    """An ordered sequence of poses, all expressed in the same frame.
+
+    Supports `len`, indexing (including negative indices) and iteration like a list.
+    """
+
+    _core_trajectory: _core.Trajectory
+
+    def __init__(self, frame: Frame, poses: Iterable[Pose] = (), *, convert: bool = False) -> None:
+        """Initializes a trajectory in the given frame.
+
+        Args:
+            frame: The frame all poses are expressed in.
+            poses: The poses, in order.
+            convert: Whether poses in other frames are expressed in `frame` instead of being rejected, here and
+                in `push`.
+
+        Raises:
+            PoseNotInFrame: If a pose is not expressed in `frame` and `convert` is False.
+            NoCommonAncestor: If a pose belongs to another tree.
+        """
+        self._core_trajectory = _core.Trajectory(
+            frame._binding_structure, [pose._binding_structure for pose in poses], convert=convert
+        )
+
+    @property
+    def frame(self) -> Frame:
+        """The frame the poses are expressed in."""
+        return Frame._from_rust(self._core_trajectory.frame)
+
+    def push(self, pose: Pose) -> None:
+        """Appends a pose to the end of the trajectory.
+
+        Args:
+            pose: The pose to append.
+
+        Raises:
+            PoseNotInFrame: If the pose is not expressed in the frame of the trajectory and it was created without
+                `convert`.
+            NoCommonAncestor: If the pose belongs to another tree.
+        """
+        self._core_trajectory.push(pose._binding_structure)
+
+    def in_frame(self, target_frame: Frame) -> Trajectory:
+        """Returns the trajectory expressed in the given target frame.
+
+        The poses are converted with the GIL released.
+
+        Raises:
+            NoCommonAncestor: If the frames belong to different trees.
+        """
+        return Trajectory._from_rust(self._core_trajectory.in_frame(target_frame._binding_structure))
+
+    def total_length(self) -> float:
+        """Returns the summed translational distance between consecutive poses."""
+        return self._core_trajectory.total_length()
+
+    def resample(self, count: int) -> Trajectory:
+        """Resamples the trajectory into poses evenly spaced along its path.
+
+        The first and last poses are kept. Positions are interpolated linearly and orientations along the shortest
+        arc. If all poses share the same position, the poses are spaced evenly by index instead.
+
+        Args:
+            count: The number of poses of the resampled trajectory.
+
+        Returns:
+            The resampled trajectory.
+
+        Raises:
+            DegenerateInput: If the trajectory is empty or `count` is less than 2.
+        """
+        return Trajectory._from_rust(self._core_trajectory.resample(count))
+
+    def resample_by_distance(self, step: float) -> Trajectory:
+        """Resamples the trajectory into poses spaced `step` apart along its path.
+
+        The first and last poses are kept, so the last step may be shorter.
+
+        Args:
+            step: The distance between consecutive poses.
+
+        Returns:
+            The resampled trajectory.
+
+        Raises:
+            DegenerateInput: If the trajectory is empty or `step` is not a positive finite number.
+        """
+        return Trajectory._from_rust(self._core_trajectory.resample_by_distance(step))
+
+    @classmethod
+    def _from_rust(cls, rust_trajectory: _core.Trajectory) -> Trajectory:
+        instance = cls.__new__(cls)
+        instance._core_trajectory = rust_trajectory
+        return instance
+
+    def __len__(self) -> int:
+        return len(self._core_trajectory)
+
+    def __getitem__(self, index: int) -> Pose:
+        return Pose._from_rust(self._core_trajectory[index])
+
+    def __iter__(self) -> Iterator[Pose]:
+        return (Pose._from_rust(binding_pose) for binding_pose in self._core_trajectory.poses())
+
+    def __str__(self) -> str:
+        return self._core_trajectory.__str__()
+
+    def __repr__(self) -> str:
+        return self._core_trajectory.__repr__()
+
+
+class RegionShape:
+    """The shape of a `Region`, in the coordinates of its frame.
+
+    Shapes are closed: points on the boundary belong to them.
+    """
+
+    _core_shape: _core.RegionShape
+
+    @classmethod
+    def box(cls, min_corner: VectorLike, max_corner: VectorLike) -> RegionShape:
+        """Returns a box aligned with the axes of the frame, spanning from `min_corner` to `max_corner`."""
+        return cls._from_rust(_core.RegionShape.box(_binding_vector(min_corner), _binding_vector(max_corner)))
+
+    @classmethod
+    def sphere(cls, center: VectorLike, radius: float) -> RegionShape:
+        """Returns a ball of the given radius around `center`."""
+        return cls._from_rust(_core.RegionShape.sphere(_binding_vector(center), radius))
+
+    @property
+    def kind(self) -> str:
+        """The kind of the shape, "box" or "sphere"."""
+        return self._core_shape.kind
+
+    def corners(self) -> tuple[Vector3, Vector3] | None:
+        """Returns the corners `(min, max)` of a box, or None for other shapes."""
+        corners = self._core_shape.corners()
+        if corners is None:
+            return None
+        return Vector3(*corners[0].to_tuple()), Vector3(*corners[1].to_tuple())
+
+    def center_and_radius(self) -> tuple[Vector3, float] | None:
+        """Returns the center and radius of a sphere, or None for other shapes."""
+        sphere = self._core_shape.center_and_radius()
+        if sphere is None:
+            return None
+        return Vector3(*sphere[0].to_tuple()), sphere[1]
+
+    def signed_distance(self, point: VectorLike) -> float:
+        """Returns the signed distance of a point to the boundary: positive outside, negative inside, zero on it."""
+        return self._core_shape.signed_distance(_binding_vector(point))
+
+    def contains_point(self, point: VectorLike) -> bool:
+        """Returns whether a point lies inside the shape or on its boundary."""
+        return self._core_shape.contains_point(_binding_vector(point))
+
+    @property
+    def _binding_structure(self) -> Any:
+        return self._core_shape
+
+    @classmethod
+    def _from_rust(cls, rust_shape: _core.RegionShape) -> RegionShape:
+        instance = cls.__new__(cls)
+        instance._core_shape = rust_shape
+        return instance
+
+    def __eq__(self, other: object) -> bool:
+        """Checks whether the shapes are of the same kind with exactly equal values."""
+        if not isinstance(other, RegionShape):
+            return NotImplemented
+        return self._core_shape.__eq__(other._binding_structure)
+
+    # Consistent with `Pose`, shapes compare by value but are not hashable.
+    __hash__ = None  # type: ignore[assignment]
+
+    def __str__(self) -> str:
+        return self._core_shape.__str__()
+
+    def __repr__(self) -> str:
+        return self._core_shape.__repr__()
+
+
+class Region:
+    """A `RegionShape` anchored to a frame, e.g. the box a tool has to stay in.
+
+    Poses are expressed in the frame of the region before they are tested, so the region follows its frame when
+    the frame is moved or reparented.
+    """
+
+    _core_region: _core.Region
+
+    def __init__(self, frame: Frame, shape: RegionShape) -> None:
+        """Initializes a region of the given shape in the coordinates of `frame`.
+
+        Raises:
+            DegenerateInput: If a corner of a box exceeds the other or a radius is negative.
+            NonFiniteValue: If a value of the shape is NaN or infinite.
+        """
+        self._core_region = _core.Region(frame._binding_structure, shape._binding_structure)
+
+    @property
+    def frame(self) -> Frame:
+        """The frame the region is defined in."""
+        return Frame._from_rust(self._core_region.frame)
+
+    @property
+    def shape(self) -> RegionShape:
+        """The shape of the region, in the coordinates of its frame."""
+        return RegionShape._from_rust(self._core_region.shape)
+
+    def contains(self, pose: Pose) -> bool:
+        """Returns whether the position of the pose lies inside the region or on its boundary.
+
+        Raises:
+            NoCommonAncestor: If the pose belongs to another tree.
+        """
+        return self._core_region.contains(pose._binding_structure)
+
+    def distance_to_boundary(self, pose: Pose) -> float:
+        """Returns the signed distance of the position of the pose to the boundary of the region.
+
+        The distance is positive outside, negative inside and zero on the boundary.
+
+        Raises:
+            NoCommonAncestor: If the pose belongs to another tree.
+        """
+        return self._core_region.distance_to_boundary(pose._binding_structure)
+
+    @classmethod
+    def _from_rust(cls, rust_region: _core.Region) -> Region:
+        instance = cls.__new__(cls)
+        instance._core_region = rust_region
+        return instance
+
+    def __str__(self) -> str:
+        return self._core_region.__str__()
+
+    def __repr__(self) -> str:
+        return self._core_region.__repr__()
@@ -0,0 +1,48 @@
+"""Defines the dict-like view on the metadata of a frame."""
+
+from __future__ import annotations
+
+from collections.abc import Iterator, MutableMapping
+from typing import Any
+
+from cartesian_tree import _cartesian_tree as _core  # type: ignore[attr-defined]
+
+
+class FrameMetadata(MutableMapping[str, Any]):
+    """A live, dict-like view on the metadata entries of a frame.
+
+    Values can be booleans, integers, floats, strings, or nested JSON-compatible values
+    (dicts with string keys, lists, tuples and `None`). Changes are written to the frame
+    directly and are included in `Frame.to_json`.
+    """
+
+    def __init__(self, core_frame: _core.Frame) -> None:
+        """Initializes the view on the given binding frame.
+
+        Args:
+            core_frame: The binding frame whose metadata is exposed.
+        """
+        self._core_frame = core_frame
+
+    def __getitem__(self, key: str) -> Any:
+        value = self._core_frame.get_metadata(key)
+        if value is None and key not in self._core_frame.metadata_keys():
+            raise KeyError(key)
+        return value
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self._core_frame.set_metadata(key, value)
+
+    def __delitem__(self, key: str) -> None:
+        if key not in self._core_frame.metadata_keys():
+            raise KeyError(key)
+        self._core_frame.remove_metadata(key)
+
+    def __iter__(self) -> Iterator[str]:
+        return iter(self._core_frame.metadata_keys())
+
+    def __len__(self) -> int:
+        return len(self._core_frame.metadata_keys())
+
+    def __repr__(self) -> str:
+        return f"FrameMetadata({dict(self)!r})"
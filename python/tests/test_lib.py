@@ -1,10 +1,51 @@
 """Contains unit tests for the library."""
 
+import copy
+import pickle
+import threading
+import time
 from math import pi, radians
 
+import numpy as np
 import pytest
 
-from cartesian_tree import Frame, Isometry, Pose, Rotation, Vector3, rz, y, z
+from cartesian_tree import (
+    RPY,
+    CannotUpdateRootTransform,
+    CartesianTreeError,
+    DegenerateInput,
+    DuplicateChildName,
+    DuplicatePoseName,
+    DuplicateRegionName,
+    Frame,
+    FrameDropped,
+    InvalidConfig,
+    InvalidFrameName,
+    InvalidRotationMatrix,
+    InvalidScale,
+    InvalidTransformMatrix,
+    Isometry,
+    LengthMismatch,
+    Mismatch,
+    MissingFrames,
+    NoCommonAncestor,
+    NonFiniteValue,
+    PathNotFound,
+    Pose,
+    PoseNotInFrame,
+    Region,
+    RegionShape,
+    Rotation,
+    ScaledTransform,
+    StaticFrame,
+    Trajectory,
+    UnsupportedConfigVersion,
+    Vector3,
+    WeakUpgradeFailed,
+    rz,
+    y,
+    z,
+)
 
 
 def test_create_root_frame() -> None:
@@ -27,6 +68,194 @@ def test_tree_structure() -> None:
     assert grandchild.root().name == "root"
 
 
+def test_error_subclasses() -> None:
+    root = Frame("root")
+    root.add_child("child", Vector3(0.0, 0.0, 0.0), Rotation.identity())
+    with pytest.raises(DuplicateChildName):
+        root.add_child("child", Vector3(1.0, 0.0, 0.0), Rotation.identity())
+    with pytest.raises(CannotUpdateRootTransform):
+        root.set(Vector3(1.0, 0.0, 0.0), Rotation.identity())
+    assert issubclass(DuplicateChildName, CartesianTreeError)
+    assert issubclass(CartesianTreeError, ValueError)
+
+
+def test_static_frames() -> None:
+    root = Frame("root")
+    mount = root.add_child("mount", Vector3(1.0, 0.0, 0.0), Rotation.identity())
+    assert not mount.is_static
+    mount.is_static = True
+    assert mount.is_static
+    with pytest.raises(StaticFrame):
+        mount.set(Vector3(2.0, 0.0, 0.0), Rotation.identity())
+    mount.set(Vector3(2.0, 0.0, 0.0), Rotation.identity(), override_static=True)
+    assert mount.position.x == 2.0
+    assert Frame.from_json(root.to_json()).find("mount").is_static
+
+
+def test_error_attributes() -> None:
+    root = Frame("root")
+    arm = root.add_child("arm", Vector3.zeros(), Rotation.identity())
+    with pytest.raises(DuplicateChildName) as duplicate:
+        root.add_child("arm", Vector3.zeros(), Rotation.identity())
+    assert duplicate.value.parent == "root"
+    assert duplicate.value.child == "arm"
+
+    other = Frame("other")
+    with pytest.raises(NoCommonAncestor) as unrelated:
+        arm.transform_to(other)
+    assert unrelated.value.from_frame == "arm"
+    assert unrelated.value.to_frame == "other"
+
+    with pytest.raises(InvalidConfig) as mismatch:
+        other.apply_config(root.to_json())
+    assert isinstance(mismatch.value, Mismatch)
+    assert mismatch.value.expected == "other"
+    assert mismatch.value.found == "root"
+
+    camera = Frame("camera")
+    pose = camera.add_pose(Vector3.zeros(), Rotation.identity())
+    del camera
+    with pytest.raises(WeakUpgradeFailed) as dropped:
+        pose.in_frame(root)
+    assert dropped.value.frame == "camera"
+    assert dropped.value.operation == "in_frame walk from 'camera' to 'root'"
+    assert "'camera' has been dropped" in str(dropped.value)
+
+
+def test_pose_of_dropped_frame() -> None:
+    camera = Frame("camera")
+    pose = camera.add_pose(Vector3(1.0, 0.0, 0.0), Rotation.identity())
+    del camera
+    world = Frame("world")
+    alive = world.add_pose(Vector3.zeros(), Rotation.identity())
+
+    with pytest.raises(FrameDropped):
+        pose.frame()
+    with pytest.raises(FrameDropped):
+        pose.into_frame("target")
+    with pytest.raises(FrameDropped):
+        pose.interpolate(alive, 0.5)
+    with pytest.raises(FrameDropped):
+        Pose.mean([pose])
+    with pytest.raises(FrameDropped):
+        pose.translational_distance_to(alive)
+    with pytest.raises(FrameDropped):
+        pose.approx_eq(alive)
+    with pytest.raises(WeakUpgradeFailed):
+        pose.in_frame(world)
+    with pytest.raises(WeakUpgradeFailed):
+        pose.rebase(world)
+
+def test_find_frames() -> None:
+    root = Frame("root")
+    left = root.add_child("left", Vector3.zeros(), Rotation.identity())
+    right = root.add_child("right", Vector3.zeros(), Rotation.identity())
+    left.add_child("tool", Vector3(1, 0, 0), Rotation.identity())
+    right.add_child("tool", Vector3(2, 0, 0), Rotation.identity())
+
+    found_root = root.find("root")
+    assert found_root is not None
+    assert found_root.name == "root"
+
+    tool = root.find("tool")
+    assert tool is not None
+    assert tool.position.as_tuple() == pytest.approx((1.0, 0.0, 0.0), abs=1e-5)
+    assert root.find("missing") is None
+
+    tools = root.find_all("tool")
+    assert [t.position.x for t in tools] == pytest.approx([1.0, 2.0], abs=1e-5)
+
+
+def test_render_tree() -> None:
+    root = Frame("world")
+    robot = root.add_child("robot", Vector3(1, 0, 0), Rotation.identity())
+    robot.add_child("camera", Vector3(0, 0, 0.5), Rotation.identity())
+
+    assert root.render_tree().splitlines() == [
+        "world  xyz: (0.000, 0.000, 0.000)  rpy: (0.000, 0.000, 0.000)",
+        "└── robot  xyz: (1.000, 0.000, 0.000)  rpy: (0.000, 0.000, 0.000)",
+        "    └── camera  xyz: (0.000, 0.000, 0.500)  rpy: (0.000, 0.000, 0.000)",
+    ]
+    assert "`-- robot" in root.render_tree(ascii_only=True)
+    assert repr(root) == root.render_tree()
+    assert repr(robot) == "robot"
+    assert str(robot) == "robot (/world/robot): 1.0000 0.0000 0.0000 / 0.0000 0.0000 0.0000"
+    pose = robot.add_pose(Vector3(0, 0, 0.5), Rotation.identity())
+    assert str(pose) == "pose in robot: 0.0000 0.0000 0.5000 / 0.0000 0.0000 0.0000"
+    assert repr(pose) == "(0.00, 0.00, 0.50)(0.0000, 0.0000, 0.0000, 1.0000)"
+
+
+def test_to_dot() -> None:
+    root = Frame("world")
+    robot = root.add_child("robot", Vector3(1, 0, 0), Rotation.identity())
+    robot.add_child("camera", Vector3(0, 0, 1), Rotation.identity())
+
+    dot = root.to_dot()
+    assert dot.startswith("digraph frames {")
+    assert dot.count(" -> ") == 2
+    assert "xyz" not in root.to_dot(include_transforms=False)
+
+
+def test_to_csv() -> None:
+    root = Frame("world")
+    robot = root.add_child("robot", Vector3(1, 0, 0), Rotation.from_rpy(0, 0, radians(90)))
+    robot.add_child("camera", Vector3(0, 0, 1), Rotation.identity())
+
+    lines = root.to_csv(degrees=True).splitlines()
+    assert lines[0] == "path,x,y,z,roll,pitch,yaw,depth,parent"
+    assert len(lines) == 4
+    camera = lines[3].split(",")
+    assert camera[0] == "/world/robot/camera"
+    assert [float(value) for value in camera[1:7]] == pytest.approx([1, 0, 1, 0, 0, 90])
+    assert camera[7:] == ["2", "/world/robot"]
+    assert "qw" in root.to_csv(quaternion=True).splitlines()[0]
+
+
+def test_axis_angle_rotation() -> None:
+    rotation = Rotation.from_axis_angle(Vector3(0, 0, 2), pi / 2)
+    assert rotation.as_rpy().as_tuple() == pytest.approx((0.0, 0.0, pi / 2))
+    axis, angle = rotation.to_axis_angle()
+    assert axis.as_tuple() == pytest.approx((0.0, 0.0, 1.0))
+    assert angle == pytest.approx(pi / 2)
+
+    vector = Rotation.from_rpy(0.1, -0.2, 0.3).to_rotation_vector()
+    back = Rotation.from_rotation_vector(vector)
+    assert back.as_rpy().as_tuple() == pytest.approx((0.1, -0.2, 0.3))
+
+    axis, angle = Rotation.from_rotation_vector(Vector3.zeros()).to_axis_angle()
+    assert axis.as_tuple() == (1.0, 0.0, 0.0)
+    assert angle == 0.0
+
+
+def test_matrix_rotation() -> None:
+    rows = [[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]
+    rotation = Rotation.from_matrix(np.array(rows))
+    assert rotation.as_rpy().as_tuple() == pytest.approx((0.0, 0.0, pi / 2))
+    assert Rotation.from_matrix(rows).to_matrix() == pytest.approx(np.array(rows))
+
+    with pytest.raises(InvalidRotationMatrix):
+        Rotation.from_matrix(np.diag([1.0, 1.0, -1.0]))
+    drifted = np.array(rows) * 1.001
+    with pytest.raises(InvalidRotationMatrix):
+        Rotation.from_matrix(drifted)
+    assert Rotation.from_matrix(drifted, orthonormalize=True).to_matrix() == pytest.approx(np.array(rows))
+
+
+def test_rpy_degrees() -> None:
+    rpy = RPY.from_degrees(0.0, 0.0, 90.0)
+    assert rpy.yaw == pi / 2
+    assert rpy.to_degrees() == (0.0, 0.0, 90.0)
+    assert RPY(0.1, -0.2, 0.3).to_degrees() == pytest.approx((5.729577951, -11.459155903, 17.188733854))
+
+
+def test_rotation_algebra() -> None:
+    quarter = Rotation.from_rpy(0.0, 0.0, pi / 4)
+    assert (quarter * quarter).as_rpy().yaw == pytest.approx(pi / 2)
+    assert (quarter * quarter.inverse()).angle_to(Rotation.identity()) == pytest.approx(0.0, abs=1e-12)
+    assert Rotation.identity().slerp(quarter, 0.5).as_rpy().yaw == pytest.approx(pi / 8)
+    assert Rotation.from_rpy(0.0, 0.0, 0.4).angle_to(Rotation.from_rpy(0.0, 0.0, -2.8)) == pytest.approx(2 * pi - 3.2)
+
+
 def test_add_child_frame_with_quaternion() -> None:
     root = Frame("base")
     position = Vector3(1.0, 2.0, 3.0)
@@ -43,9 +272,9 @@ def test_add_child_frame_with_quaternion() -> None:
 
 def test_add_child_frame_with_rpy() -> None:
     root = Frame("world")
-    position = Vector3(0.0, 0.0, 0.0)
-    rpy = Rotation.from_rpy(0.0, 0.0, 0.0)
-    child = root.add_child("child_rpy", position, rpy)
+    child = root.add_child("child_rpy", (0, 0, 1), (0, 0, 1.57))
+    assert child.position.as_tuple() == pytest.approx((0.0, 0.0, 1.0))
+    assert child.orientation.as_rpy().as_tuple() == pytest.approx((0.0, 0.0, 1.57))
 
     assert isinstance(child, Frame)
     assert child.name == "child_rpy"
@@ -191,6 +420,19 @@ def test_add_pose_and_update() -> None:
     assert len(frame_of_pose.children()) == 1
 
 
+def test_frame_poses() -> None:
+    root = Frame("root")
+    first = root.add_pose(Vector3(1.0, 0.0, 0.0), Rotation.identity())
+    second = root.add_pose(Vector3(0.0, 1.0, 0.0), Rotation.identity())
+    assert root.pose_count() == 2
+
+    del first
+    poses = root.poses()
+    assert [pose.position.as_tuple() for pose in poses] == [(0.0, 1.0, 0.0)]
+    assert root.pose_count() == 1
+    del second
+    assert root.pose_count() == 0
+
 def test_pose_in_frame() -> None:
     base = Frame("base")
     frame_1 = base.add_child("frame1", Vector3(1, 1, 1), Rotation.identity())
@@ -205,6 +447,48 @@ def test_pose_in_frame() -> None:
     assert quat.as_rpy().as_tuple() == pytest.approx((0.0, 0.0, -radians(90)), abs=1e-5)
 
 
+def test_transform_points() -> None:
+    root = Frame("root")
+    sensor = root.add_child("sensor", Vector3(1, 2, 0), Rotation.from_rpy(0, 0, radians(90)))
+    pose = root.add_pose(Vector3(1, 2, 0), Rotation.from_rpy(0, 0, radians(90)))
+
+    assert pose.transform_vector((1, 0, 0)).as_tuple() == pytest.approx((0.0, 1.0, 0.0))
+    assert pose.transform_point(Vector3(1, 0, 3)).as_tuple() == pytest.approx((1.0, 3.0, 3.0))
+    point = sensor.transform_point_to(root, (0, 1, 0))
+    assert point.as_tuple() == pytest.approx((0.0, 2.0, 0.0))
+
+
+def test_transform_point_batches() -> None:
+    root = Frame("root")
+    camera = root.add_child("camera", Vector3(1, 2, 0), Rotation.from_rpy(0.1, 0.2, 0.3))
+    cloud = np.array([[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.5, -1.0, 2.0]])
+
+    batch = camera.transform_points_to(root, cloud)
+
+    assert batch.shape == (3, 3)
+    for point, transformed in zip(cloud, batch, strict=True):
+        single = camera.transform_point_to(root, tuple(point))
+        assert tuple(transformed) == pytest.approx(single.as_tuple())
+    assert camera.transform_points_to(root, np.empty((0, 3))).shape == (0, 3)
+    with pytest.raises(ValueError, match="shape"):
+        camera.transform_points_to(root, np.zeros((2, 2)))
+
+
+def test_pose_distances() -> None:
+    root = Frame("root")
+    child = root.add_child("child", Vector3(1, 0, 0), Rotation.from_rpy(0, 0, radians(90)))
+    in_root = root.add_pose(Vector3(1, 1, 0), Rotation.from_rpy(0, 0, radians(90)))
+    in_child = child.add_pose(Vector3(1, 0, 0), Rotation.identity())
+    moved = child.add_pose(Vector3(1, 0, 2), Rotation.from_rpy(0.3, 0, 0))
+
+    assert in_root.translational_distance_to(in_child) == pytest.approx(0.0, abs=1e-12)
+    assert in_root.angular_distance_to(in_child) == pytest.approx(0.0, abs=1e-12)
+    assert in_root.translational_distance_to(moved) == pytest.approx(2.0)
+    assert in_root.angular_distance_to(moved) == pytest.approx(0.3)
+    assert in_root.approx_eq(moved, 2.5, 0.4)
+    assert not in_root.approx_eq(moved, 2.5, 0.2)
+
+
 def test_calibrate_frame() -> None:
     base = Frame("base")
     reference_frame = base.add_child("reference", Vector3(1, 1, 1), Rotation.identity())
@@ -218,6 +502,127 @@ def test_calibrate_frame() -> None:
     assert quat.as_quaternion().as_tuple() == pytest.approx((0.0, 0.0, 0.0, 1.0), abs=1e-5)
 
 
+def test_calibrate_child_from_poses() -> None:
+    root = Frame("world")
+    desired = [(Vector3(0.0, 0.0, 0.0), Rotation.identity()), (Vector3(0.0, 1.0, 0.0), Rotation.identity())]
+    observed = [
+        root.add_pose(Vector3(1.0, 0.0, 0.0), Rotation.identity()),
+        root.add_pose(Vector3(1.0, 1.2, 0.0), Rotation.identity()),
+    ]
+    fixture, report = root.calibrate_child_from_poses("fixture", desired, observed)
+    position, _ = fixture.transformation()
+    assert position.as_tuple() == pytest.approx((1.0, 0.1, 0.0))
+    assert [translation for translation, _ in report.residuals] == pytest.approx([0.1, 0.1])
+    assert report.max_translation_error == pytest.approx(0.1)
+    assert report.mean_rotation_error == pytest.approx(0.0)
+
+    with pytest.raises(LengthMismatch):
+        root.calibrate_child_from_poses("other", desired, observed[:1])
+    with pytest.raises(DegenerateInput):
+        root.calibrate_child_from_poses("other", [], [])
+
+
+def test_add_child_from_three_points() -> None:
+    root = Frame("world")
+    table = root.add_child_from_three_points("table", (1.0, 1.0, 0.0), (1.0, 3.0, 0.0), Vector3(0.0, 2.0, 0.0))
+    assert root.transform_point_to(table, (1.0, 3.0, 0.0)).as_tuple() == pytest.approx((2.0, 0.0, 0.0))
+    assert root.transform_point_to(table, (0.0, 2.0, 0.0)).as_tuple() == pytest.approx((1.0, 1.0, 0.0))
+
+    with pytest.raises(DegenerateInput):
+        root.add_child_from_three_points("line", (0.0, 0.0, 0.0), (1.0, 0.0, 0.0), (2.0, 0.0, 0.0))
+
+
+
+def test_calibrate_child_from_points() -> None:
+    root = Frame("world")
+    truth = root.add_child("truth", (0.5, -1.0, 2.0), (0.1, -0.2, 0.3))
+    indices = np.arange(12.0)
+    expected = np.column_stack([np.sin(1.3 * indices), np.cos(0.7 * indices) * 0.5, 0.1 * indices - 0.4])
+    exact = truth.transform_points_to(root, expected)
+
+    fixture, rms = root.calibrate_child_from_points("fixture", exact, expected)
+    assert rms < 1e-12
+    assert np.abs(fixture.transform_to(truth) - np.eye(4)).max() < 1e-9
+
+    noise = np.column_stack([np.sin(3.1 * indices), np.cos(5.3 * indices), np.sin(7.7 * indices)]) * 1e-3
+    noisy, rms = root.calibrate_child_from_points("noisy", [tuple(point) for point in exact + noise], expected)
+    assert 0.0 < rms < 2e-3
+    position, orientation = noisy.transformation_to(truth)
+    assert np.linalg.norm(position.as_tuple()) < 5e-3
+    assert abs(orientation.w) > 1 - 1e-5
+
+    with pytest.raises(LengthMismatch) as mismatch:
+        root.calibrate_child_from_points("short", exact[:4], expected[:5])
+    assert (mismatch.value.expected, mismatch.value.found) == (5, 4)
+    with pytest.raises(DegenerateInput):
+        root.calibrate_child_from_points("few", exact[:2], expected[:2])
+    collinear = [(i, 2.0 * i, 0.0) for i in range(5)]
+    with pytest.raises(DegenerateInput):
+        root.calibrate_child_from_points("line", collinear, collinear)
+    with pytest.raises(ValueError, match="shape"):
+        root.calibrate_child_from_points("flat", [1.0, 2.0, 3.0], expected)
+    assert sorted(child.name for child in root) == ["fixture", "noisy", "truth"]
+
+
+def test_add_child_from_plane_fit() -> None:
+    root = Frame("world")
+    points = [(0.0, 0.0, 1.0), (2.0, 0.0, 1.0), (0.0, 1.0, 1.0), (2.0, 1.0, 1.02)]
+    table, report = root.add_child_from_plane_fit("table", points, x_hint=(1.0, 0.0, 0.0))
+    position, orientation = table.transformation()
+    assert position.as_tuple() == pytest.approx((1.0, 0.5, 1.005))
+    assert root.transform_point_to(table, (0.0, 0.0, 0.0)).as_tuple()[2] > 0.0
+    assert orientation.as_quaternion().as_tuple() == pytest.approx((1.0, 0.0, 0.0, 0.0), abs=1e-2)
+    assert len(report.residuals) == 4
+    assert report.max_error == pytest.approx(max(abs(residual) for residual in report.residuals))
+    assert 0.0 < report.rms_error <= report.max_error
+
+    with pytest.raises(DegenerateInput):
+        root.add_child_from_plane_fit("line", [(i, 2.0 * i, 0.0) for i in range(5)])
+
+
+def test_resolve_and_transformation_to() -> None:
+    root = Frame("world")
+    robot = root.add_child("robot", (1, 0, 0), None)
+    camera = robot.add_child("camera", (0, 0, 1), (0, 0, pi / 2))
+
+    assert root.resolve("robot/camera") == camera
+    assert camera.resolve("../..") == root
+    assert camera.resolve("/world/robot") == robot
+    with pytest.raises(PathNotFound) as missing:
+        root.resolve("robot/camrea")
+    assert missing.value.segment == "camrea"
+    with pytest.raises(PathNotFound):
+        root.resolve("..")
+
+    position, orientation = camera.transformation_to(root)
+    assert position.as_tuple() == pytest.approx((1.0, 0.0, 1.0))
+    assert orientation.as_tuple() == pytest.approx((0.0, 0.0, 2**-0.5, 2**-0.5))
+    with pytest.raises(NoCommonAncestor):
+        camera.transformation_to(Frame("other"))
+
+
+def test_scaled_child() -> None:
+    root = Frame("world")
+    scan = root.add_scaled_child("scan", (1, 0, 0), (0, 0, pi / 2), 2.0)
+    marker = scan.add_child("marker", (1, 0, 0), None)
+    assert scan.scale == 2.0
+    assert marker.scale == 1.0
+
+    # The scale applies once, on the way out of the scan frame.
+    matrix = marker.transform_to(root)
+    assert matrix[:3, 3] == pytest.approx((1.0, 2.0, 0.0))
+    pose = marker.add_pose((0, 0, 1), None).in_frame(root)
+    assert pose.position.as_tuple() == pytest.approx((1.0, 2.0, 2.0))
+    with pytest.raises(ScaledTransform) as scaled:
+        marker.transformation_to(root)
+    assert scaled.value.to_frame == "world"
+    with pytest.raises(InvalidScale):
+        root.add_scaled_child("flat", (0, 0, 0), None, 0.0)
+
+    copy = Frame.from_json(root.to_json())
+    assert copy.resolve("scan").scale == 2.0
+
+
 def test_serialization() -> None:
     root = Frame("root")
     child1 = root.add_child("child1", Vector3(1, 0, 0), Rotation.identity())
@@ -238,6 +643,377 @@ def test_serialization() -> None:
     assert position.as_tuple() == pytest.approx((0.0, 1.0, 0.0), abs=1e-5)  # Updated back to '1'
 
 
+def test_from_json() -> None:
+    root = Frame("root")
+    child = root.add_child("child", Vector3(1, 0, 0), Rotation.from_rpy(0, 0, radians(90)))
+    child.add_child("grandchild", Vector3(0, 1, 0), Rotation.identity())
+
+    copy = Frame.from_json(root.to_json())
+
+    assert copy.name == "root"
+    copy_child = copy.find("child")
+    assert copy_child is not None
+    assert copy_child.position.as_tuple() == pytest.approx((1.0, 0.0, 0.0))
+    assert copy.find("grandchild") is not None
+
+
+def test_json_schema_version() -> None:
+    root = Frame("root")
+    json = root.to_json()
+    assert '"schema_version": 2' in json
+
+    with pytest.raises(UnsupportedConfigVersion) as info:
+        Frame.from_json(json.replace('"schema_version": 2', '"schema_version": 99'))
+    assert info.value.found == 99
+    assert info.value.supported == 2
+
+
+def test_pose_accessors() -> None:
+    root = Frame("root")
+    pose = root.add_pose(Vector3(1, 2, 3), Rotation.from_rpy(0.1, -0.2, 0.3))
+    orientation = pose.orientation.as_quaternion().as_tuple()
+
+    pose.position = Vector3(-4, 5, 0.5)
+    assert pose.position.as_tuple() == pytest.approx((-4.0, 5.0, 0.5))
+    assert pose.orientation.as_quaternion().as_tuple() == orientation
+    assert pose.rpy.as_tuple() == pytest.approx((0.1, -0.2, 0.3))
+
+    pose.rpy = RPY(0.0, 0.0, pi / 2)
+    assert pose.position.as_tuple() == pytest.approx((-4.0, 5.0, 0.5))
+    assert pose.rpy.yaw == pytest.approx(pi / 2)
+
+    pose.orientation = Rotation.identity()
+    assert pose.rpy.as_tuple() == pytest.approx((0.0, 0.0, 0.0))
+
+
+def test_pose_rebase() -> None:
+    root = Frame("root")
+    a = root.add_child("a", Vector3(1, -2, 0.5), Rotation.from_rpy(0.4, -0.3, 1.1))
+    b = root.add_child("b", Vector3(-0.7, 0.2, 3), Rotation.from_rpy(-1.2, 0.6, 0.2))
+    pose = a.add_pose(Vector3(0.3, 0.1, -0.2), Rotation.from_rpy(0.2, 0.1, -0.9))
+    original = a.add_pose(Vector3(0.3, 0.1, -0.2), Rotation.from_rpy(0.2, 0.1, -0.9))
+
+    pose.rebase(b)
+    assert pose.frame().name == "b"
+    assert pose.in_frame(root).approx_eq(original.in_frame(root), 1e-9, 1e-9)
+
+    pose.rebase(a)
+    assert pose.frame().name == "a"
+    assert pose.approx_eq(original, 1e-9, 1e-9)
+
+
+def test_pose_mean() -> None:
+    root = Frame("root")
+    tracker = root.add_child("tracker", Vector3(0.5, -1, 2), Rotation.from_rpy(0.2, 0.4, -2.8))
+    measurements = [
+        root.add_pose(Vector3(1, 0, 0), Rotation.from_rpy(0, 0, 0.1)),
+        root.add_pose(Vector3(3, 0, 0), Rotation.from_rpy(0, 0, 0.3)).in_frame(tracker),
+    ]
+
+    mean = Pose.mean(measurements).in_frame(root)
+    assert mean.position.as_tuple() == pytest.approx((2.0, 0.0, 0.0), abs=1e-9)
+    assert mean.rpy.yaw == pytest.approx(0.2, abs=1e-9)
+
+    weighted = Pose.mean(measurements, weights=[3.0, 1.0])
+    assert weighted.position.x == pytest.approx(1.5, abs=1e-9)
+    assert Pose.mean(measurements[:1]).approx_eq(measurements[0], 1e-12, 1e-12)
+    with pytest.raises(LengthMismatch):
+        Pose.mean(measurements, weights=[1.0])
+    with pytest.raises(DegenerateInput):
+        Pose.mean([])
+
+
+def test_trajectory() -> None:
+    root = Frame("root")
+    tool = root.add_child("tool", Vector3(0, 0, 1), Rotation.identity())
+    start = root.add_pose(Vector3(0.1, -0.4, 0.2), Rotation.from_rpy(0.3, -0.2, 0.1))
+    end = root.add_pose(Vector3(0.9, 0.5, -0.3), Rotation.from_rpy(-0.6, 0.4, 2.1))
+
+    trajectory = Trajectory(root, [start, end])
+    resampled = trajectory.resample(3)
+    assert len(resampled) == 3
+    assert resampled[1].approx_eq(start.interpolate(end, 0.5), 1e-9, 1e-9)
+    assert resampled[-1].approx_eq(end, 1e-12, 1e-12)
+    assert [pose.position.x for pose in resampled] == pytest.approx([0.1, 0.5, 0.9])
+    with pytest.raises(IndexError):
+        resampled[3]
+
+    assert trajectory.total_length() == pytest.approx(start.translational_distance_to(end))
+    assert len(trajectory.resample_by_distance(0.1)) == 15
+    with pytest.raises(DegenerateInput):
+        trajectory.resample(1)
+
+    with pytest.raises(PoseNotInFrame):
+        trajectory.push(tool.add_pose(Vector3(0, 0, 0), Rotation.identity()))
+    converted = Trajectory(root, [tool.add_pose(Vector3(0, 0, 0), Rotation.identity())], convert=True)
+    assert converted[0].position.as_tuple() == pytest.approx((0.0, 0.0, 1.0))
+    assert converted.in_frame(tool).frame.name == "tool"
+
+
+def test_pose_into_frame() -> None:
+    root = Frame("root")
+    table = root.add_child("table", Vector3(1, 0, 0.5), Rotation.from_rpy(0, 0, 0.7))
+    grasp = table.add_pose(Vector3(0.2, -0.1, 0.3), Rotation.from_rpy(0.3, 0, -1.2))
+
+    grasp_frame = grasp.into_frame("grasp_1")
+    parent = grasp_frame.parent()
+    assert parent is not None
+    assert parent.name == "table"
+
+    origin = grasp_frame.add_pose(Vector3(0, 0, 0), Rotation.identity()).in_frame(root)
+    expected = grasp.in_frame(root)
+    assert origin.approx_eq(expected, 1e-9, 1e-9)
+    with pytest.raises(DuplicateChildName):
+        grasp.into_frame("grasp_1")
+
+
+def test_ancestor_queries() -> None:
+    root = Frame("root")
+    robot = root.add_child("robot", Vector3(0, 0, 0), Rotation.identity())
+    camera = robot.add_child("camera", Vector3(0, 0, 0), Rotation.identity())
+    gripper = robot.add_child("gripper", Vector3(0, 0, 0), Rotation.identity())
+
+    lca = camera.lowest_common_ancestor(gripper)
+    assert lca is not None
+    assert lca.name == "robot"
+    lca = camera.lowest_common_ancestor(camera)
+    assert lca is not None
+    assert lca.name == "camera"
+    assert camera.lowest_common_ancestor(Frame("other")) is None
+
+    assert root.is_ancestor_of(camera)
+    assert robot.is_ancestor_of(gripper)
+    assert not camera.is_ancestor_of(robot)
+    assert not camera.is_ancestor_of(gripper)
+    assert not camera.is_ancestor_of(camera)
+
+
+def test_validate() -> None:
+    root = Frame("root")
+    arm = root.add_child("arm", Vector3(0, 0, 1), Rotation.identity())
+    arm.add_child("tool", Vector3(0, 0, 1), Rotation.identity())
+    arm.set(Vector3(1, 0, 0), Rotation.from_rpy(0, 0, pi / 2))
+
+    assert root.validate() == []
+
+
+def test_non_finite_values() -> None:
+    root = Frame("root")
+    child = root.add_child("child", Vector3(0, 0, 0), Rotation.identity())
+
+    with pytest.raises(NonFiniteValue, match=r"position\.x"):
+        root.add_child("nan", Vector3(float("nan"), 0, 0), Rotation.identity())
+    with pytest.raises(NonFiniteValue, match=r"position\.z"):
+        root.add_pose(Vector3(0, 0, float("inf")), Rotation.identity())
+    with pytest.raises(NonFiniteValue, match=r"orientation"):
+        child.set(Vector3(0, 0, 0), Rotation.from_quaternion(0, 0, 0, 0))
+
+    pose = child.add_pose(Vector3(0, 0, 0), Rotation.identity())
+    with pytest.raises(NonFiniteValue):
+        pose.set(Vector3(0, float("nan"), 0), Rotation.identity())
+    assert pose.position.as_tuple() == (0.0, 0.0, 0.0)
+
+
+def test_transform_callbacks() -> None:
+    root = Frame("root")
+    arm = root.add_child("arm", Vector3(0, 0, 0), Rotation.identity())
+    tool = arm.add_child("tool", Vector3(0, 0, 1), Rotation.identity())
+    seen: list[tuple[str, tuple[float, float, float]]] = []
+
+    def record(frame: Frame) -> None:
+        origin = frame.add_pose(Vector3(0, 0, 0), Rotation.identity()).in_frame(root)
+        seen.append((frame.name, origin.position.as_tuple()))
+
+    subscription = arm.on_transform_changed(record, include_descendants=True)
+    tool.set(Vector3(0, 0, 2), Rotation.identity())
+    arm.set(Vector3(1, 0, 0), Rotation.identity())
+    assert [name for name, _ in seen] == ["tool", "arm"]
+    assert seen[0][1] == pytest.approx((0.0, 0.0, 2.0))
+
+    assert arm.unsubscribe(subscription)
+    assert not arm.unsubscribe(subscription)
+    arm.set(Vector3(2, 0, 0), Rotation.identity())
+    assert len(seen) == 2
+
+
+
+def test_child_indexing_and_iteration() -> None:
+    root = Frame("root")
+    robot = root.add_child("robot", (0, 0, 0), None)
+    flange = robot.add_child("flange", (0, 0, 1), None)
+    tool = flange.add_child("tool", (0, 0, 0.1), None)
+    camera = root.add_child("camera", (1, 0, 0), None)
+
+    assert root["robot"] == robot
+    assert root["robot/flange/tool"] == tool
+    assert tool["../.."] == robot
+    assert "camera" in root
+    assert "robot/flange" in root
+    assert "tool" not in root
+    assert 3 not in root
+    with pytest.raises(KeyError, match="missing"):
+        root["robot/missing"]
+
+    assert len(root) == 2
+    assert len(tool) == 0
+    assert tool
+    assert list(root) == [robot, camera]
+
+    # Iteration works on a snapshot, so frames added during the loop are not visited.
+    visited = []
+    for child in root:
+        visited.append(child.name)
+        child.add_child("sensor", (0, 0, 0), None)
+        root.add_child(f"{child.name}_copy", (0, 0, 0), None)
+    assert visited == ["robot", "camera"]
+    assert len(root) == 4
+    assert "camera/sensor" in root
+
+def test_frame_metadata() -> None:
+    root = Frame("root")
+    link = root.add_child("link", Vector3(0, 0, 0), Rotation.identity())
+
+    link.metadata["mass"] = 1.2
+    link.metadata["mass"] = 1.5
+    link.metadata["serial"] = "SN-1"
+    link.metadata["mesh"] = {"file": "link.stl", "scale": [1.0, 1.0, 1.0], "visible": True}
+
+    assert link.metadata["mass"] == pytest.approx(1.5)
+    assert sorted(link.metadata) == ["mass", "mesh", "serial"]
+    assert link.metadata.get("missing") is None
+    with pytest.raises(KeyError):
+        link.metadata["missing"]
+
+    del link.metadata["serial"]
+    assert "serial" not in link.metadata
+    assert len(link.metadata) == 2
+
+    copy = Frame.from_json(root.to_json())
+    copy_link = copy.find("link")
+    assert copy_link is not None
+    assert dict(copy_link.metadata) == dict(link.metadata)
+
+
+def test_invalid_frame_names() -> None:
+    root = Frame("root")
+    for name in ["", "a/b", "..", "tab\t", " padded"]:
+        with pytest.raises(InvalidFrameName):
+            Frame(name)
+        with pytest.raises(InvalidFrameName):
+            root.add_child(name, Vector3(0, 0, 0), Rotation.identity())
+    assert root.children() == []
+
+
+def test_clone_subtree() -> None:
+    root = Frame("cell")
+    left = root.add_child("left", Vector3(0, 1, 0), Rotation.identity())
+    right = root.add_child("right", Vector3(0, -1, 0), Rotation.identity())
+    gripper = left.add_child("gripper", Vector3(0, 0, 0.2), Rotation.identity())
+    tcp = gripper.add_child("tcp", Vector3(0, 0, 0.1), Rotation.identity())
+    tcp.metadata["mass"] = 0.4
+
+    copy = gripper.clone_subtree()
+    assert copy.parent() is None
+    copy_tcp = copy.find("tcp")
+    assert copy_tcp is not None
+    assert copy_tcp.metadata["mass"] == pytest.approx(0.4)
+    copy_tcp.set(Vector3(1, 1, 1), Rotation.identity())
+    assert tcp.position.as_tuple() == pytest.approx((0.0, 0.0, 0.1))
+
+    grafted = gripper.attach_copy_under(right, rename="right_gripper")
+    assert grafted.parent().name == "right"
+    with pytest.raises(DuplicateChildName):
+        gripper.attach_copy_under(right, rename="right_gripper")
+
+
+def test_rename() -> None:
+    root = Frame("root")
+    arm = root.add_child("arm", Vector3(0, 0, 0), Rotation.identity())
+    camera = arm.add_child("camrea", Vector3(0, 0, 0), Rotation.identity())
+    arm.add_child("gripper", Vector3(0, 0, 0), Rotation.identity())
+
+    with pytest.raises(DuplicateChildName):
+        camera.rename("gripper")
+    with pytest.raises(InvalidFrameName):
+        camera.rename("")
+
+    camera.rename("camera")
+    assert camera.name == "camera"
+    assert root.find("camera") is not None
+    assert root.find("camrea") is None
+    root.rename("world")
+    assert Frame.from_json(root.to_json()).name == "world"
+
+
+def test_pose_registry() -> None:
+    root = Frame("root")
+    arm = root.add_child("arm", Vector3(1.0, 0.0, 0.0), Rotation.identity())
+    home = root.add_pose(Vector3(1.0, 2.0, 0.0), Rotation.identity())
+
+    arm.store_pose("home", home)
+    with pytest.raises(DuplicatePoseName):
+        arm.store_pose("home", home)
+    arm.store_pose("home", arm.add_pose(Vector3(0.0, 0.0, 0.5), Rotation.identity()), overwrite=True)
+    arm.store_pose("approach", home)
+
+    assert arm.pose_names() == ["approach", "home"]
+    stored = arm.get_pose("home")
+    assert stored is not None
+    assert stored.position.as_tuple() == pytest.approx((0.0, 0.0, 0.5), abs=1e-5)
+
+    copy = Frame.from_json(root.to_json())
+    copy_arm = copy.find("arm")
+    assert copy_arm is not None
+    approach = copy_arm.get_pose("approach")
+    assert approach is not None
+    assert approach.position.as_tuple() == pytest.approx((0.0, 2.0, 0.0), abs=1e-5)
+
+    assert arm.remove_pose("approach") is not None
+    assert arm.remove_pose("approach") is None
+    assert arm.get_pose("approach") is None
+
+
+def test_regions() -> None:
+    root = Frame("root")
+    left = root.add_child("left", Vector3(0.0, 1.0, 0.0), Rotation.identity())
+    table = left.add_child("table", Vector3(0.0, 0.0, 0.5), Rotation.identity())
+
+    bounds = table.store_region("bounds", RegionShape.box((-0.5, -0.5, 0.0), (0.5, 0.5, 1.0)))
+    with pytest.raises(DuplicateRegionName):
+        table.store_region("bounds", RegionShape.sphere((0, 0, 0), 1.0))
+    with pytest.raises(DegenerateInput):
+        Region(table, RegionShape.box((1, 0, 0), (0, 0, 0)))
+    ball = Region(table, RegionShape.sphere((0, 0, 0.5), 0.5))
+
+    on_face = root.add_pose(Vector3(0.5, 1.0, 1.0), Rotation.identity())
+    assert bounds.contains(on_face)
+    assert bounds.distance_to_boundary(on_face) == 0.0
+    assert ball.distance_to_boundary(on_face) == pytest.approx(0.0)
+    outside = root.add_pose(Vector3(0.0, 1.0, 2.0), Rotation.identity())
+    assert not bounds.contains(outside)
+    assert bounds.distance_to_boundary(outside) == pytest.approx(0.5)
+
+    # The region follows its frame.
+    left.set((0.0, -1.0, 0.0), None)
+    assert not bounds.contains(on_face)
+
+    assert table.region_names() == ["bounds"]
+    copy = Frame.from_json(root.to_json())
+    copy_table = copy.find("table")
+    assert copy_table is not None
+    restored = copy_table.get_region("bounds")
+    assert restored is not None
+    assert restored.shape == bounds.shape
+    corners = restored.shape.corners()
+    assert corners is not None
+    assert [corner.as_tuple() for corner in corners] == [(-0.5, -0.5, 0.0), (0.5, 0.5, 1.0)]
+    assert ball.shape.kind == "sphere"
+    assert str(ball) == "sphere around (0.0000, 0.0000, 0.5000) with radius 0.5000 in table"
+
+    assert table.remove_region("bounds") is not None
+    assert table.get_region("bounds") is None
+
+
 def test_lazy_translation_frame() -> None:
     root = Frame("root")
     child = root.add_child("child", Vector3(0.0, 0.0, 0.0), Rotation.identity())
@@ -302,3 +1078,205 @@ def test_lazy_rotation_pose() -> None:
     pos, rot = pose.transformation()
     roll, pitch, yaw = rot.as_rpy().as_tuple()
     assert Vector3(roll, pitch, yaw).as_tuple() == pytest.approx((0.0, 0.0, 0.0), abs=1e-10)
+
+
+def test_diff() -> None:
+    root = Frame("world")
+    arm = root.add_child("arm", Vector3(1, 0, 0), Rotation.identity())
+    arm.add_child("camera", Vector3(0, 0, 0.2), Rotation.identity())
+    config = root.to_json()
+
+    other = Frame.from_json(config)
+    assert other.diff(root).is_empty()
+    other.find("camera").set(Vector3(0, 0.01, 0.2), Rotation.from_rpy(0, 0, 0.1))
+    other.find("arm").add_child("gripper", Vector3(0, 0, 0), Rotation.identity())
+    arm.add_child("lidar", Vector3(0, 0, 0), Rotation.identity())
+
+    diff = root.diff(other)
+    assert diff.added == ["arm/gripper"]
+    assert diff.removed == ["arm/lidar"]
+    [(path, distance)] = diff.translation_changed
+    assert path == "arm/camera"
+    assert distance == pytest.approx(0.01)
+    assert diff.rotation_changed[0][1] == pytest.approx(0.1)
+    assert "+ arm/gripper" in str(diff)
+    assert '"added"' in diff.to_json()
+    assert root.diff(other, lin_tol=0.1, ang_tol=0.2).translation_changed == []
+
+    assert root.diff_config(config).added == []
+    assert root.diff_config(config).removed == ["arm/lidar"]
+    assert root.find("lidar") is not None
+
+
+def test_capture_and_restore_state() -> None:
+    root = Frame("world")
+    arm = root.add_child("arm", Vector3(1, 0, 0), Rotation.identity())
+    camera = arm.add_child("camera", Vector3(0, 0, 0.2), Rotation.from_rpy(0, 0.5, 0))
+    state = root.capture_state()
+    assert state.paths() == ["arm", "arm/camera"]
+    assert len(state) == 2
+
+    arm.set(Vector3(3, 0, 0), Rotation.identity())
+    camera.set(Vector3(0, 0, 0), Rotation.identity())
+    root.restore_state(state)
+    assert arm.position.as_tuple() == (1.0, 0.0, 0.0)
+    assert camera.position.as_tuple() == (0.0, 0.0, 0.2)
+
+    arm.rename("robot")
+    with pytest.raises(MissingFrames, match="arm/camera"):
+        root.restore_state(state)
+
+
+def test_apply_patch() -> None:
+    root = Frame("world")
+    robot = root.add_child("robot", Vector3(1, 0, 0), Rotation.identity())
+    tool = robot.add_child("tool", Vector3(0, 0, 0.1), Rotation.identity())
+
+    root.apply_patch('[{"path": "robot/tool", "rpy": [0, 0, 0.5]}, {"path": "robot", "position": [2, 0, 0]}]')
+    assert robot.position.as_tuple() == pytest.approx((2.0, 0.0, 0.0))
+    assert tool.position.as_tuple() == pytest.approx((0.0, 0.0, 0.1))
+    assert tool.orientation.as_rpy().as_tuple() == pytest.approx((0.0, 0.0, 0.5))
+
+    patch = '[{"path": "robot", "position": [3, 0, 0]}, {"path": "a/b"}, {"path": "robot/c"}]'
+    with pytest.raises(MissingFrames, match=r"a/b.*robot/c"):
+        root.apply_patch(patch)
+    assert robot.position.as_tuple() == pytest.approx((2.0, 0.0, 0.0))
+    root.apply_patch(patch, create_missing=True)
+    assert root.find("b") is not None
+    assert robot.position.as_tuple() == pytest.approx((3.0, 0.0, 0.0))
+
+
+def test_plain_sequence_arguments() -> None:
+    root = Frame("world")
+    quaternion = root.add_child("quaternion", [1, 2, 3], [0, 0, 1, 1])
+    assert quaternion.orientation.as_rpy().yaw == pytest.approx(pi / 2)
+    array = root.add_child("array", np.array([0.5, 0.0, 0.0]), np.array([0.0, 0.0, 0.1]))
+    assert array.position.as_tuple() == pytest.approx((0.5, 0.0, 0.0))
+    identity = root.add_child("identity", Vector3(0, 0, 1), None)
+    assert identity.orientation.as_quaternion().as_tuple() == pytest.approx((0.0, 0.0, 0.0, 1.0))
+
+    pose = root.add_pose((1, 0, 0), None)
+    pose.set([2, 0, 0], (0, 0, pi))
+    assert pose.position.as_tuple() == pytest.approx((2.0, 0.0, 0.0))
+    assert Isometry.from_parts((1, 2, 3), None).translation().as_tuple() == pytest.approx((1.0, 2.0, 3.0))
+
+    for position, orientation in [((1, 2), None), ("abc", None), ((0, 0, 0), (1, 2)), (np.eye(3), None)]:
+        with pytest.raises(TypeError):
+            root.add_child("invalid", position, orientation)
+
+
+def test_pose_matrices() -> None:
+    root = Frame("world")
+    robot = root.add_child("robot", (1, 2, 3), (0.1, -0.2, 0.3))
+    pose = robot.add_pose((0.3, -1 / 3, 2.5), (0.4, -1.2, 2.9))
+
+    matrix = pose.to_matrix()
+    assert matrix.shape == (4, 4)
+    copy = robot.add_pose_from_matrix(matrix)
+    assert np.abs(copy.to_matrix() - matrix).max() < 1e-12
+
+    to_root = robot.transform_to(root)
+    in_root = pose.in_frame(root)
+    assert np.abs(to_root @ matrix - in_root.to_matrix()).max() < 1e-12
+    assert np.abs(root.transform_to(robot) @ to_root - np.eye(4)).max() < 1e-12
+
+    drifted = matrix.copy()
+    drifted[0, 0] += 1e-4
+    with pytest.raises(InvalidTransformMatrix, match="orthonormalize"):
+        robot.add_pose_from_matrix(drifted)
+    corrected = robot.add_pose_from_matrix(drifted, orthonormalize=True)
+    assert np.abs(corrected.to_matrix() - matrix).max() < 1e-4
+    with pytest.raises(InvalidTransformMatrix, match="last row"):
+        robot.add_pose_from_matrix(np.ones((4, 4)))
+    with pytest.raises(ValueError, match="shape"):
+        robot.add_pose_from_matrix(np.eye(3))
+
+
+def test_frame_identity() -> None:
+    root = Frame("world")
+    robot = root.add_child("robot", (1, 0, 0), None)
+    camera = robot.add_child("camera", (0, 0, 1), None)
+
+    found = root.find("robot")
+    assert found is not None
+    assert found is not robot
+    assert found == robot
+    assert found.same_frame(robot)
+    assert camera.parent() == robot
+    assert camera.root() == root
+    assert robot != camera
+    assert robot != "robot"
+
+    assert len({robot, found, root.children()[0], camera.parent()}) == 1
+    labels = {robot: "arm", camera: "eye"}
+    assert labels[root.children()[0]] == "arm"
+    assert labels[root.find_all("camera")[0]] == "eye"
+    assert root.find("camera") in set(root.find_all("camera"))
+    assert robot not in {root, camera}
+
+    pose = robot.add_pose((1, 2, 3), None)
+    assert pose == robot.add_pose((1, 2, 3), None)
+    assert pose != robot.add_pose((1, 2, 3.1), None)
+    assert pose != pose.in_frame(root)
+    assert pose.approx_eq(pose.in_frame(root))
+    assert pose.approx_eq(robot.add_pose((1, 2, 3 + 1e-12), None))
+    with pytest.raises(TypeError):
+        hash(pose)
+
+
+def test_pickle_and_deepcopy() -> None:
+    root = Frame("world")
+    robot = root.add_child("robot", (1, 0, 0), (0, 0, 0.5))
+    camera = robot.add_child("camera", (0, 0.2, 1), (0.1, 0.2, 0.3))
+    camera.metadata["serial"] = "A-17"
+    camera.store_pose("target", camera.add_pose((0, 0, 2), None))
+
+    restored = pickle.loads(pickle.dumps(root))  # noqa: S301
+    restored_camera = restored.find("camera")
+    assert restored_camera is not None
+    assert restored_camera != camera
+    assert np.abs(restored_camera.transform_to(restored) - camera.transform_to(root)).max() < 1e-12
+    assert restored_camera.metadata["serial"] == "A-17"
+    assert restored_camera.get_pose("target") is not None
+
+    with pytest.raises(ValueError, match="not a root"):
+        pickle.dumps(camera)
+    detached = pickle.loads(pickle.dumps(camera.clone_subtree()))  # noqa: S301
+    assert detached.parent() is None
+
+    copied = copy.deepcopy(root)
+    copied_camera = copied.find("camera")
+    assert copied_camera is not None
+    assert np.abs(copied_camera.transform_to(copied) - camera.transform_to(root)).max() < 1e-12
+    copied_camera.set((5, 5, 5), None)
+    assert camera.position.as_tuple() == pytest.approx((0.0, 0.2, 1.0))
+    assert copy.deepcopy(robot).parent() is None
+    assert copy.copy(robot) == robot
+
+
+def test_batch_transform_releases_gil() -> None:
+    root = Frame("root")
+    arm = root.add_child("arm", (1, 2, 3), (0, 0, 0.5))
+    points = np.zeros((10_000_000, 3))
+    ticks: list[float] = []
+    done = threading.Event()
+
+    def count() -> None:
+        while not done.is_set():
+            ticks.append(time.perf_counter())
+            time.sleep(0.001)
+
+    counter = threading.Thread(target=count)
+    counter.start()
+    while not ticks:
+        time.sleep(0.001)
+    start = time.perf_counter()
+    result = arm.transform_points_to(root, points)
+    end = time.perf_counter()
+    done.set()
+    counter.join()
+
+    # The counter has to advance well inside the call, not only around its edges.
+    margin = (end - start) / 4
+    assert any(start + margin < tick < end - margin for tick in ticks)
+    np.testing.assert_allclose(result[-1], [1, 2, 3])
@@ -0,0 +1,147 @@
+//! [`approx`] comparisons of rotations and poses, enabled by the `approx` feature.
+//!
+//! [`Rotation`] implements [`AbsDiffEq`] and [`RelativeEq`] on its quaternion, so `q` and `-q`
+//! compare equal like they do with [`PartialEq`]. [`Pose`] compares its transform to its frame
+//! with the same tolerance on the translation and the quaternion components.
+//!
+//! Like [`PartialEq`], these comparisons never convert between frames: poses in different
+//! frames are unequal, whatever the tolerance, as the traits cannot report that the frames have
+//! no common ancestor. To compare poses in different frames of a tree, express one in the frame
+//! of the other first, see [`Pose::in_frame`], or use
+//! [`assert_pose_eq!`](crate::assert_pose_eq), which does so and panics if it fails.
+//!
+//! # Example
+//! ```
+//! use approx::assert_relative_eq;
+//! use cartesian_tree::Frame;
+//! use cartesian_tree::rotation::Rotation;
+//! use nalgebra::{UnitQuaternion, Vector3};
+//!
+//! let rpy = Rotation::from_rpy(0.0, 0.0, std::f64::consts::FRAC_PI_2);
+//! let half = std::f64::consts::FRAC_1_SQRT_2;
+//! assert_relative_eq!(rpy, Rotation::from_quaternion(0.0, 0.0, -half, -half));
+//!
+//! let world = Frame::new_origin("world").unwrap();
+//! let tool = world
+//!     .add_child("tool", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+//!     .unwrap();
+//! let pose = tool.add_pose(Vector3::new(0.0, 0.0, 0.5), rpy).unwrap();
+//! let expected = world.add_pose(Vector3::new(1.0, 0.0, 0.5), rpy).unwrap();
+//! assert_relative_eq!(pose.in_frame(&world).unwrap(), expected, epsilon = 1e-12);
+//! ```
+
+use crate::pose::Pose;
+use crate::rotation::Rotation;
+use crate::scalar::Real;
+use approx::{AbsDiffEq, RelativeEq};
+
+impl<T: Real> AbsDiffEq for Rotation<T> {
+    type Epsilon = T;
+
+    fn default_epsilon() -> T {
+        T::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: T) -> bool {
+        self.as_quaternion()
+            .abs_diff_eq(&other.as_quaternion(), epsilon)
+    }
+}
+
+impl<T: Real> RelativeEq for Rotation<T> {
+    fn default_max_relative() -> T {
+        T::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Self, epsilon: T, max_relative: T) -> bool {
+        self.as_quaternion()
+            .relative_eq(&other.as_quaternion(), epsilon, max_relative)
+    }
+}
+
+impl<T: Real> AbsDiffEq for Pose<T> {
+    type Epsilon = T;
+
+    fn default_epsilon() -> T {
+        T::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: T) -> bool {
+        self.same_frame(other)
+            && self
+                .transformation()
+                .abs_diff_eq(&other.transformation(), epsilon)
+    }
+}
+
+impl<T: Real> RelativeEq for Pose<T> {
+    fn default_max_relative() -> T {
+        T::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Self, epsilon: T, max_relative: T) -> bool {
+        self.same_frame(other)
+            && self
+                .transformation()
+                .relative_eq(&other.transformation(), epsilon, max_relative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Frame;
+    use approx::{assert_abs_diff_eq, assert_relative_eq, assert_relative_ne};
+    use nalgebra::{UnitQuaternion, Vector3};
+
+    #[test]
+    fn quaternions_of_either_sign_compare_equal() {
+        let q = UnitQuaternion::from_euler_angles(0.3, -0.2, 1.1);
+        let negated = UnitQuaternion::new_unchecked(-q.into_inner());
+        assert_relative_eq!(Rotation::Quaternion(q), Rotation::Quaternion(negated));
+        assert_abs_diff_eq!(
+            Rotation::from_rpy(0.3, -0.2, 1.1),
+            Rotation::Quaternion(negated),
+            epsilon = 1e-12
+        );
+        assert_relative_ne!(
+            Rotation::Quaternion(q),
+            Rotation::Quaternion(q.inverse()),
+            epsilon = 1e-6
+        );
+
+        let world = Frame::new_origin("world").unwrap();
+        let pose = world.add_pose(Vector3::new(1.0, 2.0, 3.0), q).unwrap();
+        let flipped = world
+            .add_pose(Vector3::new(1.0, 2.0, 3.0), negated)
+            .unwrap();
+        assert_eq!(pose, flipped);
+        assert_relative_eq!(pose, flipped);
+    }
+
+    #[test]
+    fn poses_only_compare_equal_in_the_same_frame() {
+        let world = Frame::new_origin("world").unwrap();
+        let tool = world
+            .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let position = Vector3::new(0.5, 0.0, 0.0);
+        let in_world = world
+            .add_pose(position, UnitQuaternion::identity())
+            .unwrap();
+        let in_tool = tool.add_pose(position, UnitQuaternion::identity()).unwrap();
+
+        // The frames coincide, but the poses are in different frames.
+        assert_ne!(in_world, in_tool);
+        assert_relative_ne!(in_world, in_tool, epsilon = 1.0);
+        assert_relative_eq!(in_world, in_tool.in_frame(&world).unwrap());
+        crate::assert_pose_eq!(in_world, in_tool);
+
+        let nudged = world
+            .add_pose(position.add_scalar(1e-7), UnitQuaternion::identity())
+            .unwrap();
+        assert_ne!(in_world, nudged);
+        assert_abs_diff_eq!(in_world, nudged, epsilon = 1e-6);
+        assert_relative_ne!(in_world, nudged);
+    }
+}
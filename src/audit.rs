@@ -0,0 +1,513 @@
+//! Audit logs of transform changes, for tracing when and how frames moved.
+//!
+//! Trees do not log changes by default. After [`Frame::enable_audit`], every change of a
+//! transform in the tree appends an [`AuditEntry`] to a log shared by all its frames, in the
+//! order the changes happened. [`AuditOperation`] tells the entry points apart. Frames moved
+//! into an audited tree log to its log; frames removed from it stop logging.
+//!
+//! Logs are bounded by a [`HistoryCapacity`], evicting the oldest entries first, so they can run
+//! indefinitely. They need the `std` feature for their time stamps.
+
+#[cfg(feature = "std")]
+use crate::CartesianTreeError;
+#[cfg(feature = "std")]
+use crate::frame::Frame;
+#[cfg(feature = "std")]
+use crate::history::HistoryCapacity;
+#[cfg(feature = "std")]
+use crate::tree::Walking;
+#[cfg(feature = "std")]
+use nalgebra::Isometry3;
+#[cfg(feature = "std")]
+use std::cell::RefCell;
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+#[cfg(feature = "std")]
+use std::rc::Rc;
+#[cfg(feature = "std")]
+use std::time::SystemTime;
+
+/// The number of entries [`Frame::enable_audit`] keeps.
+#[cfg(feature = "std")]
+pub const DEFAULT_AUDIT_CAPACITY: usize = 10_000;
+
+/// The kind of call that changed a transform.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditOperation {
+    /// An update of the transform, e.g. [`Frame::set`], [`Frame::apply_in_parent_frame`] or
+    /// [`Frame::batch_update`].
+    Update,
+    /// [`Frame::apply_config`].
+    Config,
+    /// [`Frame::reparent`].
+    Reparent,
+    /// The creation of a calibrated frame, e.g. [`Frame::calibrate_child`].
+    Calibration,
+}
+
+#[cfg(feature = "std")]
+impl AuditOperation {
+    /// Returns the name used in the JSON export, e.g. `"update"`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Update => "update",
+            Self::Config => "config",
+            Self::Reparent => "reparent",
+            Self::Calibration => "calibration",
+        }
+    }
+}
+
+/// One change of the transform of a frame to its parent.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditEntry {
+    /// When the change happened.
+    pub timestamp: SystemTime,
+    /// The path of the frame after the change, see [`Frame::path`].
+    pub frame_path: String,
+    /// The transform before the change, `None` for frames created by a calibration.
+    pub old_transform: Option<Isometry3<f64>>,
+    /// The transform after the change.
+    pub new_transform: Isometry3<f64>,
+    /// The source set by [`Frame::set_audit_source`] at the time of the change.
+    pub source: Option<String>,
+    /// The kind of call that made the change.
+    pub operation: AuditOperation,
+}
+
+/// The entries logged for a tree, oldest first.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct AuditLog {
+    capacity: HistoryCapacity,
+    source: Option<String>,
+    entries: VecDeque<AuditEntry>,
+}
+
+#[cfg(feature = "std")]
+impl AuditLog {
+    const fn new(capacity: HistoryCapacity) -> Self {
+        Self {
+            capacity,
+            source: None,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Returns which entries are kept.
+    #[must_use]
+    pub const fn capacity(&self) -> HistoryCapacity {
+        self.capacity
+    }
+
+    /// Returns the number of entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no entries are logged.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entries, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &AuditEntry> {
+        self.entries.iter()
+    }
+
+    /// Serializes the entries to a JSON array, oldest first.
+    ///
+    /// Time stamps are written as seconds since the Unix epoch, transforms like the frames of
+    /// [`Frame::to_json`]: `{"position": [x, y, z], "orientation": {"quaternion": [x, y, z, w]}}`.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - On serialization failure.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, CartesianTreeError> {
+        let transform = |isometry: &Isometry3<f64>| {
+            serde_json::json!({
+                "position": isometry.translation.vector.as_slice(),
+                "orientation": { "quaternion": isometry.rotation.coords.as_slice() },
+            })
+        };
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let timestamp = entry
+                    .timestamp
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default();
+                serde_json::json!({
+                    "timestamp": timestamp.as_secs_f64(),
+                    "frame": entry.frame_path,
+                    "operation": entry.operation.as_str(),
+                    "source": entry.source,
+                    "old_transform": entry.old_transform.as_ref().map(transform),
+                    "new_transform": transform(&entry.new_transform),
+                })
+            })
+            .collect();
+        Ok(serde_json::to_string_pretty(&entries)?)
+    }
+
+    pub(crate) fn record(&mut self, entry: AuditEntry) {
+        self.entries.push_back(entry);
+        self.evict();
+    }
+
+    pub(crate) fn source(&self) -> Option<String> {
+        self.source.clone()
+    }
+
+    fn evict(&mut self) {
+        match self.capacity {
+            HistoryCapacity::Samples(count) => {
+                let excess = self.entries.len().saturating_sub(count);
+                self.entries.drain(..excess);
+            }
+            HistoryCapacity::Duration(duration) => {
+                let Some(latest) = self.entries.back().map(|entry| entry.timestamp) else {
+                    return;
+                };
+                while self.entries.front().is_some_and(|entry| {
+                    latest
+                        .duration_since(entry.timestamp)
+                        .is_ok_and(|age| age > duration)
+                }) {
+                    self.entries.pop_front();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Frame {
+    /// Starts logging the transform changes of the whole tree, keeping the most recent
+    /// [`DEFAULT_AUDIT_CAPACITY`] entries, see [`crate::audit`].
+    ///
+    /// Does nothing if the tree is logged already.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::audit::AuditOperation;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let tool = root.add_child("tool", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// root.enable_audit();
+    /// root.set_audit_source("nightly_calibration");
+    /// tool.set(Vector3::new(0.1, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    ///
+    /// let log = tool.audit_log().unwrap();
+    /// let entry = log.iter().next().unwrap();
+    /// assert_eq!(entry.frame_path, "/world/tool");
+    /// assert_eq!(entry.operation, AuditOperation::Update);
+    /// assert_eq!(entry.source.as_deref(), Some("nightly_calibration"));
+    /// ```
+    pub fn enable_audit(&self) {
+        if self.borrow().audit.is_none() {
+            self.share_audit(Some(&Rc::new(RefCell::new(AuditLog::new(
+                HistoryCapacity::Samples(DEFAULT_AUDIT_CAPACITY),
+            )))));
+        }
+    }
+
+    /// Starts logging the transform changes of the whole tree with the given capacity.
+    ///
+    /// If the tree is logged already, only the capacity is changed, evicting entries that no
+    /// longer fit.
+    ///
+    /// # Arguments
+    /// - `capacity`: The number of entries to keep (`usize`), or how far back from the most
+    ///   recent entry to keep them ([`core::time::Duration`]).
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::DegenerateInput`] if the capacity is zero entries.
+    pub fn enable_audit_with(
+        &self,
+        capacity: impl Into<HistoryCapacity>,
+    ) -> Result<(), CartesianTreeError> {
+        let capacity = capacity.into();
+        if capacity == HistoryCapacity::Samples(0) {
+            return Err(CartesianTreeError::DegenerateInput {
+                reason: "the audit log has to keep at least one entry".to_string(),
+            });
+        }
+        let log = self.borrow().audit.clone();
+        match log {
+            Some(log) => {
+                let mut log = log.borrow_mut();
+                log.capacity = capacity;
+                log.evict();
+            }
+            None => self.share_audit(Some(&Rc::new(RefCell::new(AuditLog::new(capacity))))),
+        }
+        Ok(())
+    }
+
+    /// Stops logging the transform changes of the tree and discards its log.
+    pub fn disable_audit(&self) {
+        self.share_audit(None);
+    }
+
+    /// Sets the source recorded with the following changes of the tree, e.g. the name of the
+    /// job making them. Does nothing if the tree is not logged.
+    pub fn set_audit_source(&self, source: impl Into<String>) {
+        if let Some(log) = &self.borrow().audit {
+            log.borrow_mut().source = Some(source.into());
+        }
+    }
+
+    /// Stops recording a source with the following changes of the tree.
+    pub fn clear_audit_source(&self) {
+        if let Some(log) = &self.borrow().audit {
+            log.borrow_mut().source = None;
+        }
+    }
+
+    /// Returns a copy of the audit log of the tree, or `None` if it is not logged.
+    #[must_use]
+    pub fn audit_log(&self) -> Option<AuditLog> {
+        let log = self.borrow().audit.clone()?;
+        let log = log.borrow().clone();
+        Some(log)
+    }
+
+    /// Makes every frame of the tree log to `log`.
+    fn share_audit(&self, log: Option<&Rc<RefCell<AuditLog>>>) {
+        for frame in self.root().iter_dfs() {
+            frame.data.borrow_mut().audit = log.cloned();
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use super::*;
+    use crate::frame::ReparentPolicy;
+    use crate::tree::HasChildren;
+    use core::time::Duration;
+    #[cfg(feature = "serde")]
+    use nalgebra::Point3;
+    use nalgebra::{UnitQuaternion, Vector3};
+
+    /// Returns the operations and frame paths of the entries of `log`.
+    fn summary(log: &AuditLog) -> Vec<(AuditOperation, String)> {
+        log.iter()
+            .map(|entry| (entry.operation, entry.frame_path.clone()))
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn every_entry_point_is_logged() {
+        let world = Frame::new_origin("world").unwrap();
+        let table = world
+            .add_child(
+                "table",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let robot = world
+            .add_child("robot", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let tool = robot
+            .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        // Nothing is logged before the audit is enabled.
+        tool.set(Vector3::new(0.5, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        tool.enable_audit();
+        assert!(world.audit_log().unwrap().is_empty());
+        let config = world.to_json().unwrap();
+
+        world.set_audit_source("operator");
+        tool.set(Vector3::new(0.0, 0.0, 0.2), UnitQuaternion::identity())
+            .unwrap();
+        tool.apply_in_parent_frame(&Isometry3::translation(0.1, 0.0, 0.0))
+            .unwrap();
+        world
+            .batch_update(|batch| {
+                batch.set(
+                    &robot,
+                    Vector3::new(0.0, 1.0, 0.0),
+                    UnitQuaternion::identity(),
+                );
+                Ok(())
+            })
+            .unwrap();
+        world.clear_audit_source();
+        // Restores the tool and the robot, but leaves the table as it is.
+        world.apply_config(&config).unwrap();
+        tool.reparent(&table, ReparentPolicy::KeepWorld).unwrap();
+        world.set_audit_source("nightly_calibration");
+        let expected = [
+            Point3::origin(),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ];
+        let observed = expected.map(|point| point + Vector3::z());
+        let (fixture, _) = table
+            .calibrate_child_from_points("fixture", &observed, &expected)
+            .unwrap();
+        // A failed update changes nothing, so it is not logged.
+        assert!(
+            world
+                .set(Vector3::zeros(), UnitQuaternion::identity())
+                .is_err()
+        );
+
+        let log = fixture.audit_log().unwrap();
+        assert_eq!(
+            summary(&log),
+            [
+                (AuditOperation::Update, "/world/robot/tool".to_owned()),
+                (AuditOperation::Update, "/world/robot/tool".to_owned()),
+                (AuditOperation::Update, "/world/robot".to_owned()),
+                (AuditOperation::Config, "/world/robot".to_owned()),
+                (AuditOperation::Config, "/world/robot/tool".to_owned()),
+                (AuditOperation::Reparent, "/world/table/tool".to_owned()),
+                (
+                    AuditOperation::Calibration,
+                    "/world/table/fixture".to_owned()
+                ),
+            ]
+        );
+        let entries: Vec<_> = log.iter().collect();
+        assert_eq!(
+            entries[0].old_transform.unwrap().translation.vector,
+            Vector3::new(0.5, 0.0, 0.0)
+        );
+        assert_eq!(
+            entries[1].new_transform.translation.vector,
+            Vector3::new(0.1, 0.0, 0.2)
+        );
+        assert_eq!(entries[2].source.as_deref(), Some("operator"));
+        assert_eq!(entries[3].source, None);
+        assert_eq!(entries[3].new_transform, Isometry3::identity());
+        // The tool keeps its place in the world, 1 m off the table.
+        assert_eq!(
+            entries[5].old_transform,
+            Some(Isometry3::translation(0.5, 0.0, 0.0))
+        );
+        assert_eq!(
+            entries[5].new_transform.translation.vector,
+            Vector3::new(-0.5, 0.0, 0.0)
+        );
+        assert_eq!(entries[6].old_transform, None);
+        assert_eq!(entries[6].source.as_deref(), Some("nightly_calibration"));
+        assert!(
+            entries
+                .windows(2)
+                .all(|pair| pair[0].timestamp <= pair[1].timestamp)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn logs_export_to_json() {
+        let world = Frame::new_origin("world").unwrap();
+        world.enable_audit();
+        world.set_audit_source("operator");
+        let tool = world
+            .calibrate_child(
+                "tool",
+                Vector3::zeros(),
+                UnitQuaternion::identity(),
+                &world
+                    .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+                    .unwrap(),
+            )
+            .unwrap();
+        tool.set(Vector3::new(0.0, 0.0, 0.2), UnitQuaternion::identity())
+            .unwrap();
+
+        let json = world.audit_log().unwrap().to_json().unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+        assert_eq!(json[0]["operation"], "calibration");
+        assert!(json[0]["old_transform"].is_null());
+        assert_eq!(json[1]["frame"], "/world/tool");
+        assert_eq!(json[1]["operation"], "update");
+        assert_eq!(json[1]["source"], "operator");
+        assert_eq!(
+            json[1]["new_transform"],
+            serde_json::json!({
+                "position": [0.0, 0.0, 0.2],
+                "orientation": { "quaternion": [0.0, 0.0, 0.0, 1.0] },
+            })
+        );
+        assert!(json[1]["timestamp"].as_f64().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn capacity_evicts_the_oldest_entries() {
+        let world = Frame::new_origin("world").unwrap();
+        let tool = world
+            .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        world.enable_audit_with(3).unwrap();
+        for step in 0..5 {
+            tool.set(
+                Vector3::new(f64::from(step), 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        }
+        let positions = |log: &AuditLog| -> Vec<f64> {
+            log.iter()
+                .map(|entry| entry.new_transform.translation.x)
+                .collect()
+        };
+        assert_eq!(positions(&world.audit_log().unwrap()), [2.0, 3.0, 4.0]);
+
+        tool.enable_audit_with(1).unwrap();
+        assert_eq!(positions(&world.audit_log().unwrap()), [4.0]);
+        assert!(world.enable_audit_with(0).is_err());
+
+        world.enable_audit_with(Duration::ZERO).unwrap();
+        let log = world.audit_log().unwrap();
+        assert_eq!(log.capacity(), HistoryCapacity::Duration(Duration::ZERO));
+        assert!(log.len() <= 1);
+
+        world.disable_audit();
+        assert!(tool.audit_log().is_none());
+    }
+
+    #[test]
+    fn frames_follow_the_log_of_their_tree() {
+        let world = Frame::new_origin("world").unwrap();
+        let robot = world
+            .add_child("robot", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        world.enable_audit();
+        let tool = robot
+            .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let removed = world.remove_child("robot").unwrap();
+        assert!(removed.audit_log().is_none());
+        assert!(tool.audit_log().is_none());
+
+        removed.reparent(&world, ReparentPolicy::KeepLocal).unwrap();
+        tool.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        assert_eq!(
+            summary(&world.audit_log().unwrap()),
+            [
+                (AuditOperation::Reparent, "/world/robot".to_owned()),
+                (AuditOperation::Update, "/world/robot/tool".to_owned()),
+            ]
+        );
+        assert_eq!(world.children().len(), 1);
+    }
+}
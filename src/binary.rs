@@ -0,0 +1,537 @@
+//! Compact binary encoding of frame trees, for trees too large to exchange as JSON.
+//!
+//! [`Frame::to_bytes`] stores the same content as [`Frame::to_json`]: the structure, the
+//! transforms, scales and static flags, the metadata, the stored poses and the canonical length unit. The
+//! data starts with the magic bytes [`MAGIC`] and the little-endian `u16` [`BINARY_VERSION`].
+//! The number of frames follows, then the frames in depth-first order, each with the index of
+//! its parent, so reading the data back needs no recursion. Numbers are little-endian `f64`;
+//! counts, lengths and indices are LEB128 varints.
+//!
+//! The layout is written by hand rather than derived for a serde format such as `postcard`:
+//! errors report the offset of the offending byte, the layout only changes with
+//! [`BINARY_VERSION`] and not with the fields of the frame types, and frames are created while
+//! reading instead of from an intermediate copy of the whole tree. Values read back go through
+//! the same checks as in [`Frame::from_json`].
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::metadata::MetadataValue;
+use crate::tree::HasChildren;
+use crate::units::LengthUnit;
+use crate::validation::{
+    QUATERNION_NORM_TOLERANCE, check_orientation, check_position, read_unit_quaternion,
+};
+use nalgebra::{Isometry3, Quaternion, Translation3, Vector3};
+
+/// The bytes every encoded tree starts with.
+pub const MAGIC: [u8; 4] = *b"CTRB";
+
+/// The version of the layout written by this build.
+pub const BINARY_VERSION: u16 = 1;
+
+/// Set in the flags of a frame if a scale follows its transform.
+const FLAG_SCALED: u8 = 1;
+/// Set in the flags of a frame marked static, see [`Frame::set_static`].
+const FLAG_STATIC: u8 = 2;
+
+const METADATA_BOOL: u8 = 0;
+const METADATA_INT: u8 = 1;
+const METADATA_FLOAT: u8 = 2;
+const METADATA_STRING: u8 = 3;
+const METADATA_JSON: u8 = 4;
+
+impl Frame {
+    /// Encodes the tree below this frame in the compact binary format of [`crate::binary`].
+    ///
+    /// Like [`Frame::to_json`], the transform of this frame itself is not stored, as it becomes
+    /// the root when the data is read back.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// root.add_child("robot", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let copy = Frame::from_bytes(&root.to_bytes()).unwrap();
+    /// assert!(copy.resolve_path("robot").is_ok());
+    /// ```
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.bytes.extend_from_slice(&MAGIC);
+        writer
+            .bytes
+            .extend_from_slice(&BINARY_VERSION.to_le_bytes());
+        writer.bytes.push(match self.canonical_unit() {
+            None => 0,
+            Some(LengthUnit::Millimeter) => 1,
+            Some(LengthUnit::Centimeter) => 2,
+            Some(LengthUnit::Meter) => 3,
+            Some(LengthUnit::Inch) => 4,
+        });
+        writer.varint(self.iter_dfs().count());
+
+        let mut stack = vec![(self.clone(), None)];
+        let mut index = 0;
+        while let Some((frame, parent)) = stack.pop() {
+            writer.string(&frame.name());
+            if let Some(parent) = parent {
+                writer.varint(parent);
+                let data = frame.borrow();
+                writer.isometry(&data.transform_to_parent);
+                let mut flags = 0;
+                if data.scale.is_some() {
+                    flags |= FLAG_SCALED;
+                }
+                if data.is_static {
+                    flags |= FLAG_STATIC;
+                }
+                writer.bytes.push(flags);
+                if let Some(scale) = data.scale {
+                    writer.f64(scale);
+                }
+            }
+            let keys = frame.metadata_keys();
+            writer.varint(keys.len());
+            for key in keys {
+                writer.string(&key);
+                if let Some(value) = frame.get_metadata(&key) {
+                    writer.metadata(&value);
+                }
+            }
+            let poses = frame.borrow().poses.clone();
+            writer.varint(poses.len());
+            for (name, pose) in &poses {
+                writer.string(name);
+                writer.isometry(pose);
+            }
+            for child in frame.children().into_iter().rev() {
+                stack.push((child, Some(index)));
+            }
+            index += 1;
+        }
+        writer.bytes
+    }
+
+    /// Builds a new frame tree from data written by [`Frame::to_bytes`].
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The data does not start with [`MAGIC`] or is truncated or malformed. The error holds the
+    ///   offset of the offending byte.
+    /// - The data was written in another layout version than [`BINARY_VERSION`].
+    /// - A frame name or transform is invalid, or two siblings share the same name.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CartesianTreeError> {
+        let mut reader = Reader { bytes, offset: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(invalid_binary(0, "not a cartesian tree"));
+        }
+        let version = u16::from_le_bytes(reader.array()?);
+        if version != BINARY_VERSION {
+            return Err(CartesianTreeError::UnsupportedConfigVersion {
+                found: version.into(),
+                supported: BINARY_VERSION.into(),
+            });
+        }
+        let unit_offset = reader.offset;
+        let unit = match reader.array::<1>()?[0] {
+            0 => None,
+            1 => Some(LengthUnit::Millimeter),
+            2 => Some(LengthUnit::Centimeter),
+            3 => Some(LengthUnit::Meter),
+            4 => Some(LengthUnit::Inch),
+            _ => return Err(invalid_binary(unit_offset, "unknown length unit")),
+        };
+
+        let count_offset = reader.offset;
+        let count = reader.varint()?;
+        if count == 0 {
+            return Err(invalid_binary(count_offset, "the tree has no frames"));
+        }
+
+        let root = Self::new_origin(reader.string()?)?;
+        root.data.borrow_mut().canonical_unit = unit;
+        reader.attachments(&root)?;
+        let mut frames = vec![root.clone()];
+        while frames.len() < count {
+            let name = reader.string()?;
+            let parent_offset = reader.offset;
+            let parent = frames
+                .get(reader.varint()?)
+                .ok_or_else(|| invalid_binary(parent_offset, "parent index out of range"))?;
+            let transform = reader.isometry()?;
+            let flags_offset = reader.offset;
+            let flags = reader.array::<1>()?[0];
+            if flags & !(FLAG_SCALED | FLAG_STATIC) != 0 {
+                return Err(invalid_binary(flags_offset, "invalid frame flags"));
+            }
+            let frame = if flags & FLAG_SCALED == 0 {
+                parent.add_child(name, transform.translation.vector, transform.rotation)?
+            } else {
+                parent.add_scaled_child(
+                    name,
+                    transform.translation.vector,
+                    transform.rotation,
+                    reader.f64()?,
+                )?
+            };
+            frame.set_static(flags & FLAG_STATIC != 0);
+            reader.attachments(&frame)?;
+            frames.push(frame);
+        }
+        if reader.offset < bytes.len() {
+            return Err(invalid_binary(
+                reader.offset,
+                "unexpected data after the last frame",
+            ));
+        }
+        Ok(root)
+    }
+}
+
+fn invalid_binary(offset: usize, reason: &str) -> CartesianTreeError {
+    CartesianTreeError::InvalidBinary {
+        offset,
+        reason: reason.to_string(),
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn varint(&mut self, mut value: usize) {
+        while value >= 0x80 {
+            // Only the low seven bits are kept, so the truncation is intended.
+            #[allow(clippy::cast_possible_truncation)]
+            self.bytes.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        self.bytes.push(value as u8);
+    }
+
+    fn f64(&mut self, value: f64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn string(&mut self, value: &str) {
+        self.varint(value.len());
+        self.bytes.extend_from_slice(value.as_bytes());
+    }
+
+    fn isometry(&mut self, isometry: &Isometry3<f64>) {
+        let quaternion = isometry.rotation.coords;
+        for value in isometry.translation.vector.iter().chain(quaternion.iter()) {
+            self.f64(*value);
+        }
+    }
+
+    fn metadata(&mut self, value: &MetadataValue) {
+        match value {
+            MetadataValue::Bool(value) => {
+                self.bytes.push(METADATA_BOOL);
+                self.bytes.push(u8::from(*value));
+            }
+            MetadataValue::Int(value) => {
+                self.bytes.push(METADATA_INT);
+                self.bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            MetadataValue::Float(value) => {
+                self.bytes.push(METADATA_FLOAT);
+                self.f64(*value);
+            }
+            MetadataValue::String(value) => {
+                self.bytes.push(METADATA_STRING);
+                self.string(value);
+            }
+            MetadataValue::Json(value) => {
+                self.bytes.push(METADATA_JSON);
+                self.string(&value.to_string());
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], CartesianTreeError> {
+        let end = self
+            .offset
+            .checked_add(count)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| invalid_binary(self.bytes.len(), "unexpected end of data"))?;
+        let taken = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], CartesianTreeError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn varint(&mut self) -> Result<usize, CartesianTreeError> {
+        let start = self.offset;
+        let mut value = 0usize;
+        for shift in (0..usize::BITS).step_by(7) {
+            let byte = self.array::<1>()?[0];
+            let bits = usize::from(byte & 0x7f);
+            if bits
+                .checked_shl(shift)
+                .is_none_or(|shifted| shifted >> shift != bits)
+            {
+                break;
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid_binary(start, "varint overflows"))
+    }
+
+    fn f64(&mut self) -> Result<f64, CartesianTreeError> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    fn string(&mut self) -> Result<String, CartesianTreeError> {
+        let length = self.varint()?;
+        let start = self.offset;
+        let bytes = self.take(length)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| invalid_binary(start, "invalid UTF-8"))
+    }
+
+    fn isometry(&mut self) -> Result<Isometry3<f64>, CartesianTreeError> {
+        let start = self.offset;
+        let position = Vector3::new(self.f64()?, self.f64()?, self.f64()?);
+        let [x, y, z, w] = [self.f64()?, self.f64()?, self.f64()?, self.f64()?];
+        let quaternion = Quaternion::new(w, x, y, z);
+        let norm_error = (quaternion.norm() - 1.0).abs();
+        if norm_error.is_nan() || norm_error > QUATERNION_NORM_TOLERANCE {
+            return Err(invalid_binary(start + 24, "expected a unit quaternion"));
+        }
+        let orientation = read_unit_quaternion(quaternion);
+        check_position("position", &position)?;
+        check_orientation("orientation", &orientation)?;
+        Ok(Isometry3::from_parts(
+            Translation3::from(position),
+            orientation,
+        ))
+    }
+
+    fn metadata(&mut self) -> Result<MetadataValue, CartesianTreeError> {
+        let start = self.offset;
+        Ok(match self.array::<1>()?[0] {
+            METADATA_BOOL => match self.array::<1>()?[0] {
+                0 => MetadataValue::Bool(false),
+                1 => MetadataValue::Bool(true),
+                _ => return Err(invalid_binary(start + 1, "invalid boolean")),
+            },
+            METADATA_INT => MetadataValue::Int(i64::from_le_bytes(self.array()?)),
+            METADATA_FLOAT => MetadataValue::Float(self.f64()?),
+            METADATA_STRING => MetadataValue::String(self.string()?),
+            METADATA_JSON => {
+                let text = self.string()?;
+                MetadataValue::Json(
+                    serde_json::from_str(&text)
+                        .map_err(|_| invalid_binary(start + 1, "invalid JSON metadata"))?,
+                )
+            }
+            _ => return Err(invalid_binary(start, "unknown metadata type")),
+        })
+    }
+
+    /// Reads the metadata and stored poses of `frame`.
+    fn attachments(&mut self, frame: &Frame) -> Result<(), CartesianTreeError> {
+        for _ in 0..self.varint()? {
+            let key = self.string()?;
+            frame.set_metadata(key, self.metadata()?);
+        }
+        for _ in 0..self.varint()? {
+            let name = self.string()?;
+            let pose = self.isometry()?;
+            let mut data = frame.data.borrow_mut();
+            // Like `Frame::store_pose` with `StorePolicy::Reject`.
+            if data.poses.contains_key(&name) {
+                return Err(CartesianTreeError::DuplicatePoseName {
+                    frame: data.name.to_string(),
+                    name,
+                });
+            }
+            data.poses.insert(name, pose);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rotation::Rotation;
+    use crate::tree::Walking;
+    use nalgebra::UnitQuaternion;
+
+    /// Builds a tree of `count` frames in chains of ten, with metadata and poses.
+    fn large_tree(count: usize) -> Frame {
+        let root = Frame::new_origin("world").unwrap();
+        root.set_canonical_unit(LengthUnit::Millimeter);
+        let mut parent = root.clone();
+        for index in 1..count {
+            if index % 10 == 1 {
+                parent = root.clone();
+            }
+            let offset = f64::from(u32::try_from(index).unwrap());
+            parent = parent
+                .add_child(
+                    format!("frame_{index}"),
+                    Vector3::new(offset, -0.5, 2.25),
+                    Rotation::from_rpy(0.1, 0.2, offset * 0.01),
+                )
+                .unwrap();
+            parent.set_static(index % 5 == 0);
+            if index % 7 == 0 {
+                parent.set_metadata("part", format!("P-{index}"));
+                parent.set_metadata("weight", 1.5);
+                parent.set_metadata("tags", serde_json::json!(["a", {"b": null}]));
+                let pose = parent
+                    .add_pose(Vector3::new(0.0, 0.0, offset), UnitQuaternion::identity())
+                    .unwrap();
+                parent
+                    .store_pose("grip", &pose, crate::frame::StorePolicy::Overwrite)
+                    .unwrap();
+            }
+        }
+        parent
+            .add_scaled_child("scaled", Vector3::zeros(), UnitQuaternion::identity(), 2.0)
+            .unwrap()
+            .set_static(true);
+        root
+    }
+
+    #[test]
+    fn round_trip_matches_json_and_is_smaller() {
+        let root = large_tree(1000);
+        let bytes = root.to_bytes();
+        let json = root.to_json().unwrap();
+        assert!(
+            bytes.len() * 3 < json.len(),
+            "{} bytes vs. {} bytes of JSON",
+            bytes.len(),
+            json.len()
+        );
+
+        let copy = Frame::from_bytes(&bytes).unwrap();
+        assert_eq!(copy.iter_dfs().count(), 1001);
+        assert_eq!(copy.to_json().unwrap(), json);
+        assert_eq!(copy.to_bytes(), bytes);
+        assert_eq!(copy.canonical_unit(), Some(LengthUnit::Millimeter));
+    }
+
+    #[test]
+    fn subtrees_become_roots() {
+        let root = large_tree(30);
+        let branch = root.resolve_path("frame_11/frame_12").unwrap();
+        let copy = Frame::from_bytes(&branch.to_bytes()).unwrap();
+        assert_eq!(copy.path(), "/frame_12");
+        assert_eq!(copy.iter_dfs().count(), branch.iter_dfs().count());
+        assert_eq!(copy.depth(), 0);
+    }
+
+    #[test]
+    fn mismatched_header_is_rejected() {
+        let mut bytes = large_tree(5).to_bytes();
+        bytes[0] = b'X';
+        assert!(matches!(
+            Frame::from_bytes(&bytes),
+            Err(CartesianTreeError::InvalidBinary { offset: 0, .. })
+        ));
+
+        let mut bytes = large_tree(5).to_bytes();
+        bytes[4..6].copy_from_slice(&(BINARY_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            Frame::from_bytes(&bytes),
+            Err(CartesianTreeError::UnsupportedConfigVersion { found, .. })
+                if found == u64::from(BINARY_VERSION + 1)
+        ));
+        assert!(Frame::from_bytes(b"").is_err());
+    }
+
+    #[test]
+    fn stored_poses_are_checked_when_read() {
+        // A root named "world" storing `poses` under the same name.
+        let encode = |poses: &[Isometry3<f64>]| {
+            let mut writer = Writer::default();
+            writer.bytes.extend_from_slice(&MAGIC);
+            writer
+                .bytes
+                .extend_from_slice(&BINARY_VERSION.to_le_bytes());
+            writer.bytes.push(0);
+            writer.varint(1);
+            writer.string("world");
+            writer.varint(0);
+            writer.varint(poses.len());
+            for pose in poses {
+                writer.string("grip");
+                writer.isometry(pose);
+            }
+            writer.bytes
+        };
+        // Unit within the accepted tolerance, but not up to rounding.
+        let pose = Isometry3::from_parts(
+            Translation3::identity(),
+            UnitQuaternion::new_unchecked(Quaternion::new(1.000_000_5, 0.0, 0.0, 0.0)),
+        );
+
+        let root = Frame::from_bytes(&encode(&[pose])).unwrap();
+        let stored = root.get_pose("grip").unwrap().transformation();
+        assert!((stored.rotation.norm() - 1.0).abs() < 1e-15);
+
+        assert!(matches!(
+            Frame::from_bytes(&encode(&[pose, pose])),
+            Err(CartesianTreeError::DuplicatePoseName { frame, name })
+                if frame == "world" && name == "grip"
+        ));
+    }
+
+    #[test]
+    fn corrupted_bytes_produce_errors_not_panics() {
+        let bytes = large_tree(40).to_bytes();
+
+        // Every truncation is detected.
+        for length in 0..bytes.len() {
+            assert!(
+                Frame::from_bytes(&bytes[..length]).is_err(),
+                "length {length}"
+            );
+        }
+
+        // Random corruptions may still decode to some tree, but must never panic.
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            usize::try_from(state >> 32).unwrap()
+        };
+        for _ in 0..2000 {
+            let mut corrupted = bytes.clone();
+            for _ in 0..=next() % 4 {
+                let index = next() % corrupted.len();
+                corrupted[index] =
+                    corrupted[index].wrapping_add(u8::try_from(next() % 255 + 1).unwrap());
+            }
+            let _ = Frame::from_bytes(&corrupted);
+        }
+    }
+}
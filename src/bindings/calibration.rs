@@ -0,0 +1,91 @@
+use pyo3::prelude::*;
+
+use crate::calibration::{CalibrationReport, PlaneFitReport};
+
+#[pyclass(name = "CalibrationReport", unsendable)]
+#[derive(Clone)]
+pub struct PyCalibrationReport {
+    pub(crate) inner: CalibrationReport,
+}
+
+#[pymethods]
+impl PyCalibrationReport {
+    #[getter]
+    fn residuals(&self) -> Vec<(f64, f64)> {
+        self.inner
+            .residuals
+            .iter()
+            .map(|residual| (residual.translation, residual.rotation))
+            .collect()
+    }
+
+    #[getter]
+    const fn max_translation_error(&self) -> f64 {
+        self.inner.max_translation_error
+    }
+
+    #[getter]
+    const fn mean_translation_error(&self) -> f64 {
+        self.inner.mean_translation_error
+    }
+
+    #[getter]
+    const fn max_rotation_error(&self) -> f64 {
+        self.inner.max_rotation_error
+    }
+
+    #[getter]
+    const fn mean_rotation_error(&self) -> f64 {
+        self.inner.mean_rotation_error
+    }
+
+    fn __str__(&self) -> String {
+        format!(
+            "CalibrationReport(samples: {}, max translation error: {:.4e}, max rotation error: {:.4e})",
+            self.inner.residuals.len(),
+            self.inner.max_translation_error,
+            self.inner.max_rotation_error
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        self.__str__()
+    }
+}
+
+#[pyclass(name = "PlaneFitReport", unsendable)]
+#[derive(Clone)]
+pub struct PyPlaneFitReport {
+    pub(crate) inner: PlaneFitReport,
+}
+
+#[pymethods]
+impl PyPlaneFitReport {
+    #[getter]
+    fn residuals(&self) -> Vec<f64> {
+        self.inner.residuals.clone()
+    }
+
+    #[getter]
+    const fn rms_error(&self) -> f64 {
+        self.inner.rms_error
+    }
+
+    #[getter]
+    const fn max_error(&self) -> f64 {
+        self.inner.max_error
+    }
+
+    fn __str__(&self) -> String {
+        format!(
+            "PlaneFitReport(points: {}, rms error: {:.4e}, max error: {:.4e})",
+            self.inner.residuals.len(),
+            self.inner.rms_error,
+            self.inner.max_error
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        self.__str__()
+    }
+}
@@ -0,0 +1,55 @@
+use pyo3::prelude::*;
+
+use crate::diff::{TransformChange, TreeDiff};
+
+#[pyclass(name = "TreeDiff", unsendable)]
+#[derive(Clone)]
+pub struct PyTreeDiff {
+    pub(crate) inner: TreeDiff,
+}
+
+fn changes(changes: &[TransformChange]) -> Vec<(String, f64)> {
+    changes
+        .iter()
+        .map(|change| (change.path.clone(), change.difference))
+        .collect()
+}
+
+#[pymethods]
+impl PyTreeDiff {
+    #[getter]
+    fn added(&self) -> Vec<String> {
+        self.inner.added.clone()
+    }
+
+    #[getter]
+    fn removed(&self) -> Vec<String> {
+        self.inner.removed.clone()
+    }
+
+    #[getter]
+    fn translation_changed(&self) -> Vec<(String, f64)> {
+        changes(&self.inner.translation_changed)
+    }
+
+    #[getter]
+    fn rotation_changed(&self) -> Vec<(String, f64)> {
+        changes(&self.inner.rotation_changed)
+    }
+
+    const fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        Ok(self.inner.to_json()?)
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        self.__str__()
+    }
+}
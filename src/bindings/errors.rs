@@ -0,0 +1,372 @@
+use pyo3::IntoPyObjectExt;
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::CartesianTreeError;
+
+// Every exception derives from `CartesianTreeError`, which itself derives from
+// `ValueError` so that existing `except ValueError` handlers keep working.
+// Each exception carries the fields of its Rust variant as attributes.
+create_exception!(cartesian_tree, PyCartesianTreeError, PyValueError);
+// Groups the errors raised while loading or applying a frame configuration.
+create_exception!(cartesian_tree, InvalidConfig, PyCartesianTreeError);
+create_exception!(cartesian_tree, FrameDropped, PyCartesianTreeError);
+create_exception!(cartesian_tree, RootHasNoParent, PyCartesianTreeError);
+create_exception!(
+    cartesian_tree,
+    CannotUpdateRootTransform,
+    PyCartesianTreeError
+);
+create_exception!(cartesian_tree, StaticFrame, PyCartesianTreeError);
+create_exception!(cartesian_tree, DuplicateChildName, PyCartesianTreeError);
+create_exception!(cartesian_tree, ChildNotFound, PyCartesianTreeError);
+create_exception!(cartesian_tree, ReparentCycle, PyCartesianTreeError);
+create_exception!(cartesian_tree, PathNotFound, PyCartesianTreeError);
+create_exception!(cartesian_tree, NoCommonAncestor, PyCartesianTreeError);
+create_exception!(cartesian_tree, IsNoAncestor, PyCartesianTreeError);
+create_exception!(cartesian_tree, WeakUpgradeFailed, PyCartesianTreeError);
+create_exception!(cartesian_tree, SerdeError, InvalidConfig);
+create_exception!(cartesian_tree, InvalidRotationMatrix, PyCartesianTreeError);
+create_exception!(cartesian_tree, InvalidDocument, InvalidConfig);
+create_exception!(cartesian_tree, Mismatch, InvalidConfig);
+create_exception!(cartesian_tree, LengthMismatch, PyCartesianTreeError);
+create_exception!(cartesian_tree, DegenerateInput, PyCartesianTreeError);
+create_exception!(cartesian_tree, PoseNotInFrame, PyCartesianTreeError);
+create_exception!(cartesian_tree, ConcurrentAccess, PyCartesianTreeError);
+create_exception!(cartesian_tree, NonFiniteValue, PyCartesianTreeError);
+create_exception!(cartesian_tree, DuplicatePoseName, PyCartesianTreeError);
+create_exception!(cartesian_tree, DuplicateRegionName, PyCartesianTreeError);
+create_exception!(cartesian_tree, InvalidFrameName, PyCartesianTreeError);
+create_exception!(cartesian_tree, MissingFrames, PyCartesianTreeError);
+create_exception!(cartesian_tree, InvalidTransformMatrix, PyCartesianTreeError);
+create_exception!(cartesian_tree, HistoryNotEnabled, PyCartesianTreeError);
+create_exception!(cartesian_tree, EmptyHistory, PyCartesianTreeError);
+create_exception!(cartesian_tree, TimeOutOfRange, PyCartesianTreeError);
+create_exception!(cartesian_tree, InvalidScale, PyCartesianTreeError);
+create_exception!(cartesian_tree, ScaledTransform, PyCartesianTreeError);
+create_exception!(cartesian_tree, UndeclaredUnit, PyCartesianTreeError);
+create_exception!(
+    cartesian_tree,
+    UnsupportedConfigVersion,
+    PyCartesianTreeError
+);
+create_exception!(cartesian_tree, InvalidBinary, PyCartesianTreeError);
+create_exception!(cartesian_tree, NoJoint, PyCartesianTreeError);
+create_exception!(cartesian_tree, JointLimitExceeded, PyCartesianTreeError);
+create_exception!(cartesian_tree, IkNotConverged, PyCartesianTreeError);
+create_exception!(cartesian_tree, DuplicateFrameId, PyCartesianTreeError);
+
+impl From<CartesianTreeError> for PyErr {
+    // One arm per variant; splitting the match would only scatter the mapping.
+    #[allow(clippy::too_many_lines)]
+    fn from(err: CartesianTreeError) -> Self {
+        let message = err.to_string();
+        Python::with_gil(|py| {
+            let (exception, attributes): (Self, Vec<(&str, PyObject)>) = match err {
+                CartesianTreeError::FrameDropped => (FrameDropped::new_err(message), vec![]),
+                CartesianTreeError::RootHasNoParent { frame } => (
+                    RootHasNoParent::new_err(message),
+                    vec![("frame", frame.into_py_any(py)?)],
+                ),
+                CartesianTreeError::CannotUpdateRootTransform { frame } => (
+                    CannotUpdateRootTransform::new_err(message),
+                    vec![("frame", frame.into_py_any(py)?)],
+                ),
+                CartesianTreeError::StaticFrame { frame } => (
+                    StaticFrame::new_err(message),
+                    vec![("frame", frame.into_py_any(py)?)],
+                ),
+                CartesianTreeError::DuplicateChildName { parent, child } => (
+                    DuplicateChildName::new_err(message),
+                    vec![
+                        ("parent", parent.into_py_any(py)?),
+                        ("child", child.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::ChildNotFound { parent, child } => (
+                    ChildNotFound::new_err(message),
+                    vec![
+                        ("parent", parent.into_py_any(py)?),
+                        ("child", child.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::ReparentCycle { frame, new_parent } => (
+                    ReparentCycle::new_err(message),
+                    vec![
+                        ("frame", frame.into_py_any(py)?),
+                        ("new_parent", new_parent.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::PathNotFound { path, segment } => (
+                    PathNotFound::new_err(message),
+                    vec![
+                        ("path", path.into_py_any(py)?),
+                        ("segment", segment.into_py_any(py)?),
+                    ],
+                ),
+                // `from` is a Python keyword, so both ends get a `_frame` suffix.
+                CartesianTreeError::NoCommonAncestor { from, to } => (
+                    NoCommonAncestor::new_err(message),
+                    vec![
+                        ("from_frame", from.into_py_any(py)?),
+                        ("to_frame", to.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::IsNoAncestor { ancestor, frame } => (
+                    IsNoAncestor::new_err(message),
+                    vec![
+                        ("ancestor", ancestor.into_py_any(py)?),
+                        ("frame", frame.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::WeakUpgradeFailed { frame, operation } => (
+                    WeakUpgradeFailed::new_err(message),
+                    vec![
+                        ("frame", frame.into_py_any(py)?),
+                        ("operation", operation.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::SerdeError(source) => (
+                    SerdeError::new_err(message),
+                    vec![
+                        ("line", source.line().into_py_any(py)?),
+                        ("column", source.column().into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::InvalidRotationMatrix { reason } => (
+                    InvalidRotationMatrix::new_err(message),
+                    vec![("reason", reason.into_py_any(py)?)],
+                ),
+                CartesianTreeError::InvalidDocument { path, reason } => (
+                    InvalidDocument::new_err(message),
+                    vec![
+                        ("path", path.into_py_any(py)?),
+                        ("reason", reason.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::Mismatch { expected, found } => (
+                    Mismatch::new_err(message),
+                    vec![
+                        ("expected", expected.into_py_any(py)?),
+                        ("found", found.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::LengthMismatch { expected, found } => (
+                    LengthMismatch::new_err(message),
+                    vec![
+                        ("expected", expected.into_py_any(py)?),
+                        ("found", found.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::DegenerateInput { reason } => (
+                    DegenerateInput::new_err(message),
+                    vec![("reason", reason.into_py_any(py)?)],
+                ),
+                CartesianTreeError::PoseNotInFrame { frame, index } => (
+                    PoseNotInFrame::new_err(message),
+                    vec![
+                        ("frame", frame.into_py_any(py)?),
+                        ("index", index.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::ConcurrentAccess => {
+                    (ConcurrentAccess::new_err(message), vec![])
+                }
+                CartesianTreeError::NonFiniteValue { field, value } => (
+                    NonFiniteValue::new_err(message),
+                    vec![
+                        ("field", field.into_py_any(py)?),
+                        ("value", value.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::DuplicatePoseName { frame, name } => (
+                    DuplicatePoseName::new_err(message),
+                    vec![
+                        ("frame", frame.into_py_any(py)?),
+                        ("name", name.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::DuplicateRegionName { frame, name } => (
+                    DuplicateRegionName::new_err(message),
+                    vec![
+                        ("frame", frame.into_py_any(py)?),
+                        ("name", name.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::InvalidFrameName { name, reason } => (
+                    InvalidFrameName::new_err(message),
+                    vec![
+                        ("name", name.into_py_any(py)?),
+                        ("reason", reason.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::MissingFrames { paths } => (
+                    MissingFrames::new_err(message),
+                    vec![("paths", paths.into_py_any(py)?)],
+                ),
+                CartesianTreeError::InvalidTransformMatrix { reason } => (
+                    InvalidTransformMatrix::new_err(message),
+                    vec![("reason", reason.into_py_any(py)?)],
+                ),
+                CartesianTreeError::HistoryNotEnabled { frame } => (
+                    HistoryNotEnabled::new_err(message),
+                    vec![("frame", frame.into_py_any(py)?)],
+                ),
+                CartesianTreeError::EmptyHistory { frame } => (
+                    EmptyHistory::new_err(message),
+                    vec![("frame", frame.into_py_any(py)?)],
+                ),
+                CartesianTreeError::TimeOutOfRange {
+                    frame,
+                    time,
+                    earliest,
+                    latest,
+                } => (
+                    TimeOutOfRange::new_err(message),
+                    vec![
+                        ("frame", frame.into_py_any(py)?),
+                        ("time", time.into_py_any(py)?),
+                        ("earliest", earliest.into_py_any(py)?),
+                        ("latest", latest.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::InvalidScale { frame, scale } => (
+                    InvalidScale::new_err(message),
+                    vec![
+                        ("frame", frame.into_py_any(py)?),
+                        ("scale", scale.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::ScaledTransform { from, to } => (
+                    ScaledTransform::new_err(message),
+                    vec![
+                        ("from_frame", from.into_py_any(py)?),
+                        ("to_frame", to.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::UndeclaredUnit { frame } => (
+                    UndeclaredUnit::new_err(message),
+                    vec![("frame", frame.into_py_any(py)?)],
+                ),
+                CartesianTreeError::UnsupportedConfigVersion { found, supported } => (
+                    UnsupportedConfigVersion::new_err(message),
+                    vec![
+                        ("found", found.into_py_any(py)?),
+                        ("supported", supported.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::InvalidBinary { offset, reason } => (
+                    InvalidBinary::new_err(message),
+                    vec![
+                        ("offset", offset.into_py_any(py)?),
+                        ("reason", reason.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::NoJoint { frame } => (
+                    NoJoint::new_err(message),
+                    vec![("frame", frame.into_py_any(py)?)],
+                ),
+                CartesianTreeError::JointLimitExceeded {
+                    frame,
+                    value,
+                    lower,
+                    upper,
+                } => (
+                    JointLimitExceeded::new_err(message),
+                    vec![
+                        ("frame", frame.into_py_any(py)?),
+                        ("value", value.into_py_any(py)?),
+                        ("lower", lower.into_py_any(py)?),
+                        ("upper", upper.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::IkNotConverged {
+                    joint_values,
+                    position_error,
+                    orientation_error,
+                    iterations,
+                } => (
+                    IkNotConverged::new_err(message),
+                    vec![
+                        ("joint_values", joint_values.into_py_any(py)?),
+                        ("position_error", position_error.into_py_any(py)?),
+                        ("orientation_error", orientation_error.into_py_any(py)?),
+                        ("iterations", iterations.into_py_any(py)?),
+                    ],
+                ),
+                CartesianTreeError::DuplicateFrameId { id, first, second } => (
+                    DuplicateFrameId::new_err(message),
+                    vec![
+                        ("id", id.into_py_any(py)?),
+                        ("first", first.into_py_any(py)?),
+                        ("second", second.into_py_any(py)?),
+                    ],
+                ),
+            };
+            let value = exception.value(py);
+            for (name, attribute) in attributes {
+                value.setattr(name, attribute)?;
+            }
+            Ok(exception)
+        })
+        .unwrap_or_else(|err| err)
+    }
+}
+
+/// Registers the exception hierarchy on the Python module.
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("CartesianTreeError", py.get_type::<PyCartesianTreeError>())?;
+    m.add("FrameDropped", py.get_type::<FrameDropped>())?;
+    m.add("RootHasNoParent", py.get_type::<RootHasNoParent>())?;
+    m.add(
+        "CannotUpdateRootTransform",
+        py.get_type::<CannotUpdateRootTransform>(),
+    )?;
+    m.add("StaticFrame", py.get_type::<StaticFrame>())?;
+    m.add("DuplicateChildName", py.get_type::<DuplicateChildName>())?;
+    m.add("ChildNotFound", py.get_type::<ChildNotFound>())?;
+    m.add("ReparentCycle", py.get_type::<ReparentCycle>())?;
+    m.add("PathNotFound", py.get_type::<PathNotFound>())?;
+    m.add("NoCommonAncestor", py.get_type::<NoCommonAncestor>())?;
+    m.add("IsNoAncestor", py.get_type::<IsNoAncestor>())?;
+    m.add("WeakUpgradeFailed", py.get_type::<WeakUpgradeFailed>())?;
+    m.add("InvalidConfig", py.get_type::<InvalidConfig>())?;
+    m.add("SerdeError", py.get_type::<SerdeError>())?;
+    m.add(
+        "InvalidRotationMatrix",
+        py.get_type::<InvalidRotationMatrix>(),
+    )?;
+    m.add("InvalidDocument", py.get_type::<InvalidDocument>())?;
+    m.add("Mismatch", py.get_type::<Mismatch>())?;
+    m.add("LengthMismatch", py.get_type::<LengthMismatch>())?;
+    m.add("DegenerateInput", py.get_type::<DegenerateInput>())?;
+    m.add("PoseNotInFrame", py.get_type::<PoseNotInFrame>())?;
+    m.add("ConcurrentAccess", py.get_type::<ConcurrentAccess>())?;
+    m.add("NonFiniteValue", py.get_type::<NonFiniteValue>())?;
+    m.add("DuplicatePoseName", py.get_type::<DuplicatePoseName>())?;
+    m.add("DuplicateRegionName", py.get_type::<DuplicateRegionName>())?;
+    m.add("InvalidFrameName", py.get_type::<InvalidFrameName>())?;
+    m.add("MissingFrames", py.get_type::<MissingFrames>())?;
+    m.add(
+        "InvalidTransformMatrix",
+        py.get_type::<InvalidTransformMatrix>(),
+    )?;
+    m.add("HistoryNotEnabled", py.get_type::<HistoryNotEnabled>())?;
+    m.add("EmptyHistory", py.get_type::<EmptyHistory>())?;
+    m.add("TimeOutOfRange", py.get_type::<TimeOutOfRange>())?;
+    m.add("InvalidScale", py.get_type::<InvalidScale>())?;
+    m.add("ScaledTransform", py.get_type::<ScaledTransform>())?;
+    m.add("UndeclaredUnit", py.get_type::<UndeclaredUnit>())?;
+    m.add(
+        "UnsupportedConfigVersion",
+        py.get_type::<UnsupportedConfigVersion>(),
+    )?;
+    m.add("InvalidBinary", py.get_type::<InvalidBinary>())?;
+    m.add("NoJoint", py.get_type::<NoJoint>())?;
+    m.add("JointLimitExceeded", py.get_type::<JointLimitExceeded>())?;
+    m.add("IkNotConverged", py.get_type::<IkNotConverged>())?;
+    m.add("DuplicateFrameId", py.get_type::<DuplicateFrameId>())?;
+    Ok(())
+}
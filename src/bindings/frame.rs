@@ -1,16 +1,35 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::rc::Rc;
+
+use nalgebra::{Matrix4, Point3};
+use pyo3::buffer::{PyBuffer, ReadOnlyCell};
+use pyo3::exceptions::{PyKeyError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{PyIterator, PyList};
 
 use crate::{
-    Frame as RustFrame,
+    CartesianTreeError, Frame as RustFrame,
     bindings::{
         PyPose,
+        calibration::{PyCalibrationReport, PyPlaneFitReport},
+        diff::PyTreeDiff,
         lazy_access::{PyLazyRotation, PyLazyTranslation},
-        utils::{PyIsometry, PyRotation, PyVector3},
+        metadata::{metadata_from_py, metadata_to_py},
+        region::{PyRegion, PyRegionShape},
+        snapshot::PyTreeState,
+        utils::{PyIsometry, PyRotation, PyRotationLike, PyVector3, PyVector3Like, matrix4_rows},
     },
-    tree::{HasChildren, HasParent, Walking},
+    diff::DiffTolerances,
+    frame::{DotOptions, StaticPolicy, StorePolicy, SubscriptionId, SubscriptionScope, TreeStyle},
+    patch::MissingFramePolicy,
+    table::TableOptions,
+    tree::{HasChildren, HasParent, NodeEquality, Walking},
 };
 
-#[pyclass(name = "Frame", unsendable)]
+/// Number of points `transform_points_to` copies and transforms per release of the GIL.
+const POINTS_PER_CHUNK: usize = 1 << 16;
+
+#[pyclass(name = "Frame", module = "cartesian_tree._cartesian_tree", unsendable)]
 #[derive(Clone)]
 pub struct PyFrame {
     pub(crate) rust_frame: RustFrame,
@@ -20,10 +39,10 @@ pub struct PyFrame {
 impl PyFrame {
     #[new]
     #[pyo3(signature = (name))]
-    fn new(name: String) -> Self {
-        Self {
-            rust_frame: RustFrame::new_origin(name),
-        }
+    fn new(name: String) -> PyResult<Self> {
+        Ok(Self {
+            rust_frame: RustFrame::new_origin(name)?,
+        })
     }
 
     #[getter]
@@ -31,33 +50,71 @@ impl PyFrame {
         self.rust_frame.name()
     }
 
+    fn clone_subtree(&self) -> Self {
+        Self {
+            rust_frame: self.rust_frame.clone_subtree(),
+        }
+    }
+
+    #[pyo3(signature = (parent, rename = None))]
+    fn attach_copy_under(&self, parent: &Self, rename: Option<&str>) -> PyResult<Self> {
+        Ok(Self {
+            rust_frame: self
+                .rust_frame
+                .attach_copy_under(&parent.rust_frame, rename)?,
+        })
+    }
+
+    #[pyo3(signature = (new_name))]
+    fn rename(&self, new_name: String) -> PyResult<()> {
+        self.rust_frame.rename(new_name)?;
+        Ok(())
+    }
+
     #[pyo3(signature = (name, position, orientation))]
     fn add_child(
         &self,
         name: String,
-        position: PyVector3,
-        orientation: PyRotation,
+        position: PyVector3Like,
+        orientation: PyRotationLike,
     ) -> PyResult<Self> {
         let child_frame =
             self.rust_frame
-                .add_child(name, position.inner, orientation.rust_rotation)?;
+                .add_child(name, position.into_inner(), orientation.into_inner())?;
         Ok(Self {
             rust_frame: child_frame,
         })
     }
 
+    #[pyo3(signature = (name, position, orientation, scale))]
+    fn add_scaled_child(
+        &self,
+        name: String,
+        position: PyVector3Like,
+        orientation: PyRotationLike,
+        scale: f64,
+    ) -> PyResult<Self> {
+        let rust_frame = self.rust_frame.add_scaled_child(
+            name,
+            position.into_inner(),
+            orientation.into_inner(),
+            scale,
+        )?;
+        Ok(Self { rust_frame })
+    }
+
     #[pyo3(signature = (name, desired_position, desired_orientation, reference_pose))]
     fn calibrate_child(
         &self,
         name: String,
-        desired_position: PyVector3,
-        desired_orientation: PyRotation,
+        desired_position: PyVector3Like,
+        desired_orientation: PyRotationLike,
         reference_pose: &PyPose,
     ) -> PyResult<Self> {
         let new_rust_frame = self.rust_frame.calibrate_child(
             name,
-            desired_position.inner,
-            desired_orientation.rust_rotation,
+            desired_position.into_inner(),
+            desired_orientation.into_inner(),
             &reference_pose.rust_pose,
         )?;
         Ok(Self {
@@ -65,12 +122,121 @@ impl PyFrame {
         })
     }
 
+    #[pyo3(signature = (name, observed, expected))]
+    fn calibrate_child_from_points(
+        &self,
+        name: String,
+        observed: Vec<PyVector3Like>,
+        expected: Vec<PyVector3Like>,
+    ) -> PyResult<(Self, f64)> {
+        let to_points = |points: Vec<PyVector3Like>| -> Vec<_> {
+            points
+                .into_iter()
+                .map(|point| Point3::from(point.into_inner()))
+                .collect()
+        };
+        let (rust_frame, rms) = self.rust_frame.calibrate_child_from_points(
+            name,
+            &to_points(observed),
+            &to_points(expected),
+        )?;
+        Ok((Self { rust_frame }, rms))
+    }
+
+    fn calibrate_child_from_poses(
+        &self,
+        name: String,
+        desired: Vec<(PyVector3Like, PyRotationLike)>,
+        observed: Vec<PyPose>,
+    ) -> PyResult<(Self, PyCalibrationReport)> {
+        let desired: Vec<_> = desired
+            .into_iter()
+            .map(|(position, orientation)| (position.into_inner(), orientation.into_inner()))
+            .collect();
+        let observed: Vec<_> = observed.into_iter().map(|pose| pose.rust_pose).collect();
+        let (rust_frame, report) = self
+            .rust_frame
+            .calibrate_child_from_poses(name, &desired, &observed)?;
+        Ok((Self { rust_frame }, PyCalibrationReport { inner: report }))
+    }
+
+    #[allow(clippy::similar_names)]
+    fn add_child_from_three_points(
+        &self,
+        name: String,
+        origin: PyVector3Like,
+        x_point: PyVector3Like,
+        xy_point: PyVector3Like,
+    ) -> PyResult<Self> {
+        let rust_frame = self.rust_frame.add_child_from_three_points(
+            name,
+            Point3::from(origin.into_inner()),
+            Point3::from(x_point.into_inner()),
+            Point3::from(xy_point.into_inner()),
+        )?;
+        Ok(Self { rust_frame })
+    }
+
+    #[pyo3(signature = (name, points, x_hint=None))]
+    fn add_child_from_plane_fit(
+        &self,
+        name: String,
+        points: Vec<PyVector3Like>,
+        x_hint: Option<PyVector3Like>,
+    ) -> PyResult<(Self, PyPlaneFitReport)> {
+        let points: Vec<_> = points
+            .into_iter()
+            .map(|point| Point3::from(point.into_inner()))
+            .collect();
+        let (rust_frame, report) = self.rust_frame.add_child_from_plane_fit(
+            name,
+            &points,
+            x_hint.map(PyVector3Like::into_inner),
+        )?;
+        Ok((Self { rust_frame }, PyPlaneFitReport { inner: report }))
+    }
+
     #[pyo3(signature = (position, orientation))]
-    fn add_pose(&self, position: PyVector3, orientation: PyRotation) -> PyPose {
+    fn add_pose(&self, position: PyVector3Like, orientation: PyRotationLike) -> PyResult<PyPose> {
         let rust_pose = self
             .rust_frame
-            .add_pose(position.inner, orientation.rust_rotation);
-        PyPose { rust_pose }
+            .add_pose(position.into_inner(), orientation.into_inner())?;
+        Ok(PyPose { rust_pose })
+    }
+
+    #[pyo3(signature = (matrix, orthonormalize = false))]
+    fn add_pose_from_matrix(
+        &self,
+        matrix: [[f64; 4]; 4],
+        orthonormalize: bool,
+    ) -> PyResult<PyPose> {
+        let matrix = Matrix4::from_fn(|row, column| matrix[row][column]);
+        let rust_pose = if orthonormalize {
+            self.rust_frame
+                .add_pose_from_matrix_orthonormalized(&matrix)?
+        } else {
+            self.rust_frame.add_pose_from_matrix(&matrix)?
+        };
+        Ok(PyPose { rust_pose })
+    }
+
+    #[pyo3(signature = (target))]
+    fn transform_to(&self, target: &Self) -> PyResult<[[f64; 4]; 4]> {
+        let similarity = self.rust_frame.similarity_to(&target.rust_frame)?;
+        Ok(matrix4_rows(&similarity.to_homogeneous()))
+    }
+
+    #[pyo3(signature = (target))]
+    fn transformation_to(&self, target: &Self) -> PyResult<(PyVector3, PyRotation)> {
+        let isometry = self.rust_frame.transform_to(&target.rust_frame)?;
+        Ok((
+            PyVector3 {
+                inner: isometry.translation.vector,
+            },
+            PyRotation {
+                rust_rotation: isometry.rotation.into(),
+            },
+        ))
     }
 
     fn transformation(&self) -> PyResult<(PyVector3, PyRotation)> {
@@ -99,10 +265,35 @@ impl PyFrame {
         }
     }
 
-    #[pyo3(signature = (position, orientation))]
-    fn set(&self, position: PyVector3, orientation: PyRotation) -> PyResult<()> {
+    #[getter]
+    fn scale(&self) -> f64 {
+        self.rust_frame.scale()
+    }
+
+    #[getter]
+    fn is_static(&self) -> bool {
+        self.rust_frame.is_static()
+    }
+
+    #[setter]
+    fn set_is_static(&self, is_static: bool) {
+        self.rust_frame.set_static(is_static);
+    }
+
+    #[pyo3(signature = (position, orientation, override_static=false))]
+    fn set(
+        &self,
+        position: PyVector3Like,
+        orientation: PyRotationLike,
+        override_static: bool,
+    ) -> PyResult<()> {
+        let policy = if override_static {
+            StaticPolicy::Override
+        } else {
+            StaticPolicy::Reject
+        };
         self.rust_frame
-            .set(position.inner, orientation.rust_rotation)?;
+            .set_with(position.into_inner(), orientation.into_inner(), policy)?;
         Ok(())
     }
 
@@ -118,8 +309,151 @@ impl PyFrame {
         Ok(())
     }
 
-    fn to_json(&self) -> PyResult<String> {
-        Ok(self.rust_frame.to_json()?)
+    #[pyo3(signature = (key))]
+    fn get_metadata(&self, py: Python<'_>, key: &str) -> PyResult<Option<PyObject>> {
+        self.rust_frame
+            .get_metadata(key)
+            .map(|value| metadata_to_py(py, &value))
+            .transpose()
+    }
+
+    #[pyo3(signature = (key, value))]
+    fn set_metadata(&self, key: String, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.rust_frame.set_metadata(key, metadata_from_py(value)?);
+        Ok(())
+    }
+
+    #[pyo3(signature = (key))]
+    fn remove_metadata(&self, py: Python<'_>, key: &str) -> PyResult<Option<PyObject>> {
+        self.rust_frame
+            .remove_metadata(key)
+            .map(|value| metadata_to_py(py, &value))
+            .transpose()
+    }
+
+    fn metadata_keys(&self) -> Vec<String> {
+        self.rust_frame.metadata_keys()
+    }
+
+    #[pyo3(signature = (name, pose, overwrite = false))]
+    fn store_pose(&self, name: String, pose: &PyPose, overwrite: bool) -> PyResult<()> {
+        let policy = if overwrite {
+            StorePolicy::Overwrite
+        } else {
+            StorePolicy::Reject
+        };
+        self.rust_frame.store_pose(name, &pose.rust_pose, policy)?;
+        Ok(())
+    }
+
+    #[pyo3(signature = (name))]
+    fn get_pose(&self, name: &str) -> Option<PyPose> {
+        self.rust_frame
+            .get_pose(name)
+            .map(|rust_pose| PyPose { rust_pose })
+    }
+
+    #[pyo3(signature = (name))]
+    fn remove_pose(&self, name: &str) -> Option<PyPose> {
+        self.rust_frame
+            .remove_pose(name)
+            .map(|rust_pose| PyPose { rust_pose })
+    }
+
+    fn pose_names(&self) -> Vec<String> {
+        self.rust_frame.pose_names()
+    }
+
+    fn poses(&self) -> Vec<PyPose> {
+        self.rust_frame
+            .poses()
+            .into_iter()
+            .map(|rust_pose| PyPose { rust_pose })
+            .collect()
+    }
+
+    fn pose_count(&self) -> usize {
+        self.rust_frame.pose_count()
+    }
+
+    #[pyo3(signature = (name, shape, overwrite = false))]
+    fn store_region(
+        &self,
+        name: String,
+        shape: &PyRegionShape,
+        overwrite: bool,
+    ) -> PyResult<PyRegion> {
+        let policy = if overwrite {
+            StorePolicy::Overwrite
+        } else {
+            StorePolicy::Reject
+        };
+        let inner = self
+            .rust_frame
+            .store_region(name, shape.inner.clone(), policy)?;
+        Ok(PyRegion { inner })
+    }
+
+    #[pyo3(signature = (name))]
+    fn get_region(&self, name: &str) -> Option<PyRegion> {
+        self.rust_frame
+            .get_region(name)
+            .map(|inner| PyRegion { inner })
+    }
+
+    #[pyo3(signature = (name))]
+    fn remove_region(&self, name: &str) -> Option<PyRegion> {
+        self.rust_frame
+            .remove_region(name)
+            .map(|inner| PyRegion { inner })
+    }
+
+    fn region_names(&self) -> Vec<String> {
+        self.rust_frame.region_names()
+    }
+
+    #[pyo3(signature = (callback, include_descendants = false))]
+    fn on_transform_changed(&self, callback: PyObject, include_descendants: bool) -> usize {
+        let scope = if include_descendants {
+            SubscriptionScope::Subtree
+        } else {
+            SubscriptionScope::Frame
+        };
+        let id = self
+            .rust_frame
+            .on_transform_changed_with(scope, move |frame| {
+                Python::with_gil(|py| {
+                    let frame = Self {
+                        rust_frame: frame.clone(),
+                    };
+                    if let Err(err) = callback.call1(py, (frame,)) {
+                        err.write_unraisable(py, Some(callback.bind(py)));
+                    }
+                });
+            });
+        id.0
+    }
+
+    #[pyo3(signature = (subscription_id))]
+    fn unsubscribe(&self, subscription_id: usize) -> bool {
+        self.rust_frame.unsubscribe(SubscriptionId(subscription_id))
+    }
+
+    fn validate(&self) -> Vec<String> {
+        self.rust_frame
+            .validate()
+            .err()
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    fn to_json(&self, py: Python<'_>) -> PyResult<String> {
+        let serial = self.rust_frame.to_serial();
+        // Only the snapshot is serialized, so other Python threads may run meanwhile.
+        let json = py.allow_threads(move || serial.to_json(true));
+        Ok(json.map_err(CartesianTreeError::from)?)
     }
 
     #[pyo3(signature = (json))]
@@ -128,6 +462,55 @@ impl PyFrame {
         Ok(())
     }
 
+    #[pyo3(signature = (json, create_missing = false))]
+    fn apply_patch(&self, json: &str, create_missing: bool) -> PyResult<()> {
+        let missing = if create_missing {
+            MissingFramePolicy::Create
+        } else {
+            MissingFramePolicy::Reject
+        };
+        self.rust_frame.apply_patch(json, missing)?;
+        Ok(())
+    }
+
+    fn capture_state(&self) -> PyTreeState {
+        PyTreeState {
+            inner: self.rust_frame.capture_state(),
+        }
+    }
+
+    #[pyo3(signature = (state))]
+    fn restore_state(&self, state: &PyTreeState) -> PyResult<()> {
+        self.rust_frame.restore_state(&state.inner)?;
+        Ok(())
+    }
+
+    #[pyo3(signature = (other, lin_tol = 1e-9, ang_tol = 1e-9))]
+    fn diff(&self, other: &Self, lin_tol: f64, ang_tol: f64) -> PyTreeDiff {
+        PyTreeDiff {
+            inner: self
+                .rust_frame
+                .diff(&other.rust_frame, DiffTolerances { lin_tol, ang_tol }),
+        }
+    }
+
+    #[pyo3(signature = (json, lin_tol = 1e-9, ang_tol = 1e-9))]
+    fn diff_config(&self, json: &str, lin_tol: f64, ang_tol: f64) -> PyResult<PyTreeDiff> {
+        Ok(PyTreeDiff {
+            inner: self
+                .rust_frame
+                .diff_config(json, DiffTolerances { lin_tol, ang_tol })?,
+        })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (json))]
+    fn from_json(json: &str) -> PyResult<Self> {
+        Ok(Self {
+            rust_frame: RustFrame::from_json(json)?,
+        })
+    }
+
     #[getter]
     fn depth(&self) -> usize {
         self.rust_frame.depth()
@@ -151,6 +534,120 @@ impl PyFrame {
             .collect()
     }
 
+    #[pyo3(signature = (other))]
+    fn lowest_common_ancestor(&self, other: &Self) -> Option<Self> {
+        self.rust_frame
+            .lowest_common_ancestor(&other.rust_frame)
+            .map(|rust_frame| Self { rust_frame })
+    }
+
+    #[pyo3(signature = (other))]
+    fn is_ancestor_of(&self, other: &Self) -> bool {
+        self.rust_frame.is_ancestor_of(&other.rust_frame)
+    }
+
+    #[pyo3(signature = (name))]
+    fn find(&self, name: &str) -> Option<Self> {
+        self.rust_frame.find(name).map(|rf| Self { rust_frame: rf })
+    }
+
+    #[pyo3(signature = (path))]
+    fn resolve(&self, path: &str) -> PyResult<Self> {
+        Ok(Self {
+            rust_frame: self.rust_frame.resolve_path(path)?,
+        })
+    }
+
+    #[pyo3(signature = (name))]
+    fn find_all(&self, name: &str) -> Vec<Self> {
+        self.rust_frame
+            .find_all(name)
+            .into_iter()
+            .map(|rf| Self { rust_frame: rf })
+            .collect()
+    }
+
+    #[pyo3(signature = (ascii_only = false))]
+    fn render_tree(&self, ascii_only: bool) -> String {
+        let style = if ascii_only {
+            TreeStyle::Ascii
+        } else {
+            TreeStyle::Unicode
+        };
+        self.rust_frame.render_tree_with(style)
+    }
+
+    #[pyo3(signature = (include_transforms = true, precision = 3))]
+    fn to_dot(&self, include_transforms: bool, precision: usize) -> String {
+        self.rust_frame.to_dot_with(&DotOptions {
+            include_transforms,
+            precision,
+        })
+    }
+
+    #[pyo3(signature = (degrees = false, quaternion = false))]
+    fn to_csv(&self, degrees: bool, quaternion: bool) -> String {
+        self.rust_frame.to_csv(TableOptions {
+            degrees,
+            quaternion,
+        })
+    }
+
+    #[pyo3(signature = (target, point))]
+    fn transform_point_to(&self, target: &Self, point: PyVector3Like) -> PyResult<PyVector3> {
+        let point = self
+            .rust_frame
+            .transform_point_to(&target.rust_frame, &Point3::from(point.into_inner()))?;
+        Ok(PyVector3 {
+            inner: point.coords,
+        })
+    }
+
+    // The points are transformed in chunks that are copied out of the buffers, so the GIL can
+    // be released for the arithmetic without copying the whole point cloud.
+    #[allow(clippy::needless_pass_by_value)]
+    #[pyo3(signature = (target, points, out))]
+    fn transform_points_to(
+        &self,
+        py: Python<'_>,
+        target: &Self,
+        points: PyBuffer<f64>,
+        out: PyBuffer<f64>,
+    ) -> PyResult<()> {
+        let input = points
+            .as_slice(py)
+            .ok_or_else(|| PyValueError::new_err("points must be a C-contiguous float64 buffer"))?;
+        let output = out.as_mut_slice(py).ok_or_else(|| {
+            PyValueError::new_err("out must be a writable C-contiguous float64 buffer")
+        })?;
+        if input.len() % 3 != 0 || input.len() != output.len() {
+            return Err(PyValueError::new_err(
+                "points and out must both hold N x 3 values",
+            ));
+        }
+
+        let similarity = self.rust_frame.similarity_to(&target.rust_frame)?;
+        let mut chunk = Vec::with_capacity(input.len().min(3 * POINTS_PER_CHUNK));
+        for (source, destination) in input
+            .chunks(3 * POINTS_PER_CHUNK)
+            .zip(output.chunks(3 * POINTS_PER_CHUNK))
+        {
+            chunk.clear();
+            chunk.extend(source.iter().map(ReadOnlyCell::get));
+            py.allow_threads(|| {
+                for values in chunk.chunks_exact_mut(3) {
+                    let point =
+                        similarity.transform_point(&Point3::new(values[0], values[1], values[2]));
+                    values.copy_from_slice(point.coords.as_slice());
+                }
+            });
+            for (cell, value) in destination.iter().zip(&chunk) {
+                cell.set(*value);
+            }
+        }
+        Ok(())
+    }
+
     fn __add__(&self, other: PyLazyTranslation) -> Self {
         let new_rust_frame = &self.rust_frame + other.inner;
         Self {
@@ -172,11 +669,80 @@ impl PyFrame {
         }
     }
 
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyAny>, (String,))> {
+        let frame = &slf.borrow().rust_frame;
+        // The JSON of a child lacks its ancestors, so an unpickled child would be detached.
+        if frame.parent().is_some() {
+            return Err(PyValueError::new_err(format!(
+                "cannot pickle frame '{}' as it is not a root; pickle its root or a copy from \
+                 clone_subtree() instead",
+                frame.name()
+            )));
+        }
+        Ok((slf.get_type().getattr("from_json")?, (frame.to_json()?,)))
+    }
+
+    fn __copy__(&self) -> Self {
+        self.clone()
+    }
+
+    #[pyo3(signature = (_memo))]
+    fn __deepcopy__(&self, _memo: &Bound<'_, PyAny>) -> Self {
+        self.clone_subtree()
+    }
+
+    fn __getitem__(&self, path: &str) -> PyResult<Self> {
+        self.rust_frame
+            .resolve_path(path)
+            .map(|rust_frame| Self { rust_frame })
+            .map_err(|err| PyKeyError::new_err(err.to_string()))
+    }
+
+    fn __contains__(&self, path: &str) -> bool {
+        self.rust_frame.resolve_path(path).is_ok()
+    }
+
+    fn __len__(&self) -> usize {
+        self.rust_frame.children().len()
+    }
+
+    // Iterates over a snapshot of the children, so the tree may be edited during the loop.
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        PyList::new(py, self.children())?.try_iter()
+    }
+
+    // Without this, `__len__` would make leaf frames falsy.
+    #[allow(clippy::unused_self)]
+    const fn __bool__(&self) -> bool {
+        true
+    }
+
+    #[pyo3(signature = (other))]
+    fn same_frame(&self, other: &Self) -> bool {
+        self.rust_frame.is_same(&other.rust_frame)
+    }
+
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        other
+            .extract::<PyRef<'_, Self>>()
+            .is_ok_and(|other| self.same_frame(&other))
+    }
+
+    fn __hash__(&self) -> u64 {
+        // The allocation is stable for the lifetime of the frame and shared by all handles.
+        let mut hasher = DefaultHasher::new();
+        Rc::as_ptr(&self.rust_frame.data).hash(&mut hasher);
+        hasher.finish()
+    }
+
     fn __str__(&self) -> String {
-        self.rust_frame.name()
+        self.rust_frame.to_string()
     }
 
     fn __repr__(&self) -> String {
-        self.__str__()
+        if self.rust_frame.parent().is_none() {
+            return self.rust_frame.render_tree();
+        }
+        self.rust_frame.name()
     }
 }
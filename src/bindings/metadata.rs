@@ -0,0 +1,82 @@
+use pyo3::IntoPyObjectExt;
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+
+use crate::metadata::MetadataValue;
+
+/// Converts a Python value into a metadata value.
+///
+/// `bool` is checked before `int`, since Python booleans are integers.
+pub(crate) fn metadata_from_py(value: &Bound<'_, PyAny>) -> PyResult<MetadataValue> {
+    if value.is_instance_of::<PyBool>() {
+        Ok(MetadataValue::Bool(value.extract()?))
+    } else if value.is_instance_of::<PyInt>() {
+        Ok(MetadataValue::Int(value.extract()?))
+    } else if value.is_instance_of::<PyFloat>() {
+        Ok(MetadataValue::Float(value.extract()?))
+    } else if value.is_instance_of::<PyString>() {
+        Ok(MetadataValue::String(value.extract()?))
+    } else {
+        Ok(MetadataValue::Json(json_from_py(value)?))
+    }
+}
+
+fn json_from_py(value: &Bound<'_, PyAny>) -> PyResult<serde_json::Value> {
+    if value.is_none() {
+        return Ok(serde_json::Value::Null);
+    }
+    if let Ok(dict) = value.downcast::<PyDict>() {
+        let mut object = serde_json::Map::new();
+        for (key, item) in dict {
+            object.insert(key.extract()?, json_from_py(&item)?);
+        }
+        return Ok(object.into());
+    }
+    if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        return value
+            .try_iter()?
+            .map(|item| json_from_py(&item?))
+            .collect::<PyResult<Vec<_>>>()
+            .map(Into::into);
+    }
+    match metadata_from_py(value)? {
+        MetadataValue::Json(_) => Err(PyTypeError::new_err(format!(
+            "unsupported metadata type: {}",
+            value.get_type().name()?
+        ))),
+        scalar => Ok(scalar.to_json()),
+    }
+}
+
+/// Converts a metadata value into the equivalent Python value.
+pub(crate) fn metadata_to_py(py: Python<'_>, value: &MetadataValue) -> PyResult<PyObject> {
+    match value {
+        MetadataValue::Bool(value) => value.into_py_any(py),
+        MetadataValue::Int(value) => value.into_py_any(py),
+        MetadataValue::Float(value) => value.into_py_any(py),
+        MetadataValue::String(value) => value.into_py_any(py),
+        MetadataValue::Json(value) => json_to_py(py, value),
+    }
+}
+
+fn json_to_py(py: Python<'_>, value: &serde_json::Value) -> PyResult<PyObject> {
+    match value {
+        serde_json::Value::Null => Ok(py.None()),
+        serde_json::Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(|item| json_to_py(py, item))
+                .collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, items)?.into_py_any(py)
+        }
+        serde_json::Value::Object(entries) => {
+            let dict = PyDict::new(py);
+            for (key, item) in entries {
+                dict.set_item(key, json_to_py(py, item)?)?;
+            }
+            dict.into_py_any(py)
+        }
+        scalar => metadata_to_py(py, &MetadataValue::from(scalar.clone())),
+    }
+}
@@ -1,6 +1,13 @@
+pub mod calibration;
+pub mod diff;
+pub mod errors;
 pub mod frame;
 pub mod lazy_access;
+pub mod metadata;
 pub mod pose;
+pub mod region;
+pub mod snapshot;
+pub mod trajectory;
 pub mod utils;
 
 pub use frame::PyFrame;
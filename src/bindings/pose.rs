@@ -1,3 +1,4 @@
+use nalgebra::Point3;
 use pyo3::prelude::*;
 
 use crate::{
@@ -5,8 +6,9 @@ use crate::{
     bindings::{
         PyFrame,
         lazy_access::{PyLazyRotation, PyLazyTranslation},
-        utils::{PyIsometry, PyRotation, PyVector3},
+        utils::{PyIsometry, PyRotation, PyRotationLike, PyVector3, PyVector3Like, matrix4_rows},
     },
+    tree::NodeEquality,
 };
 
 #[pyclass(name = "Pose", unsendable)]
@@ -17,10 +19,15 @@ pub struct PyPose {
 
 #[pymethods]
 impl PyPose {
-    fn frame(&self) -> Option<PyFrame> {
-        self.rust_pose
-            .frame()
-            .map(|frame| PyFrame { rust_frame: frame })
+    fn frame(&self) -> PyResult<PyFrame> {
+        Ok(PyFrame {
+            rust_frame: self.rust_pose.frame()?,
+        })
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn to_matrix(&self) -> [[f64; 4]; 4] {
+        matrix4_rows(&self.rust_pose.to_matrix())
     }
 
     fn transformation(&self) -> (PyVector3, PyRotation) {
@@ -49,10 +56,23 @@ impl PyPose {
         }
     }
 
+    #[setter]
+    fn set_position(&mut self, position: PyVector3Like) -> PyResult<()> {
+        self.rust_pose.set_position(position.into_inner())?;
+        Ok(())
+    }
+
+    #[setter]
+    fn set_orientation(&mut self, orientation: PyRotationLike) -> PyResult<()> {
+        self.rust_pose.set_orientation(orientation.into_inner())?;
+        Ok(())
+    }
+
     #[pyo3(signature = (position, orientation))]
-    fn set(&mut self, position: PyVector3, orientation: PyRotation) {
+    fn set(&mut self, position: PyVector3Like, orientation: PyRotationLike) -> PyResult<()> {
         self.rust_pose
-            .set(position.inner, orientation.rust_rotation);
+            .set(position.into_inner(), orientation.into_inner())?;
+        Ok(())
     }
 
     #[pyo3(signature = (isometry))]
@@ -65,6 +85,14 @@ impl PyPose {
         self.rust_pose.apply_in_local_frame(&isometry.inner);
     }
 
+    #[pyo3(signature = (name))]
+    #[allow(clippy::wrong_self_convention)]
+    fn into_frame(&self, name: String) -> PyResult<PyFrame> {
+        Ok(PyFrame {
+            rust_frame: self.rust_pose.into_frame(name)?,
+        })
+    }
+
     #[pyo3(signature = (target_frame))]
     fn in_frame(&self, target_frame: &PyFrame) -> PyResult<Self> {
         let new_rust_pose = self.rust_pose.in_frame(&target_frame.rust_frame)?;
@@ -73,6 +101,64 @@ impl PyPose {
         })
     }
 
+    #[pyo3(signature = (new_parent))]
+    fn rebase(&mut self, new_parent: &PyFrame) -> PyResult<()> {
+        self.rust_pose.rebase(&new_parent.rust_frame)?;
+        Ok(())
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (poses, weights = None))]
+    fn mean(poses: Vec<Self>, weights: Option<Vec<f64>>) -> PyResult<Self> {
+        let rust_poses: Vec<RustPose> = poses.into_iter().map(|pose| pose.rust_pose).collect();
+        let rust_pose = match weights {
+            Some(weights) => RustPose::weighted_mean(&rust_poses, &weights)?,
+            None => RustPose::mean(&rust_poses)?,
+        };
+        Ok(Self { rust_pose })
+    }
+
+    #[pyo3(signature = (other, t))]
+    fn interpolate(&self, other: &Self, t: f64) -> PyResult<Self> {
+        Ok(Self {
+            rust_pose: self.rust_pose.interpolate(&other.rust_pose, t)?,
+        })
+    }
+
+    #[pyo3(signature = (other))]
+    fn translational_distance_to(&self, other: &Self) -> PyResult<f64> {
+        Ok(self.rust_pose.translational_distance_to(&other.rust_pose)?)
+    }
+
+    #[pyo3(signature = (other))]
+    fn angular_distance_to(&self, other: &Self) -> PyResult<f64> {
+        Ok(self.rust_pose.angular_distance_to(&other.rust_pose)?)
+    }
+
+    #[pyo3(signature = (other, lin_tol = 1e-9, ang_tol = 1e-9))]
+    fn approx_eq(&self, other: &Self, lin_tol: f64, ang_tol: f64) -> PyResult<bool> {
+        Ok(self
+            .rust_pose
+            .approx_eq(&other.rust_pose, lin_tol, ang_tol)?)
+    }
+
+    #[pyo3(signature = (point))]
+    fn transform_point(&self, point: PyVector3Like) -> PyVector3 {
+        PyVector3 {
+            inner: self
+                .rust_pose
+                .transform_point(&Point3::from(point.into_inner()))
+                .coords,
+        }
+    }
+
+    #[pyo3(signature = (vector))]
+    fn transform_vector(&self, vector: PyVector3Like) -> PyVector3 {
+        PyVector3 {
+            inner: self.rust_pose.transform_vector(&vector.into_inner()),
+        }
+    }
+
     fn __add__(&self, other: PyLazyTranslation) -> Self {
         let new_rust_pose = &self.rust_pose + other.inner;
         Self {
@@ -94,7 +180,22 @@ impl PyPose {
         }
     }
 
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        let Ok(other) = other.extract::<PyRef<'_, Self>>() else {
+            return false;
+        };
+        let same_frame = match (self.rust_pose.frame(), other.rust_pose.frame()) {
+            (Ok(frame), Ok(other_frame)) => frame.is_same(&other_frame),
+            _ => false,
+        };
+        same_frame && self.rust_pose.transformation() == other.rust_pose.transformation()
+    }
+
     fn __str__(&self) -> String {
+        self.rust_pose.to_string()
+    }
+
+    fn __repr__(&self) -> String {
         let isometry = self.rust_pose.transformation();
         let vector = isometry.translation.vector;
         let quaternion = isometry.rotation.coords;
@@ -103,8 +204,4 @@ impl PyPose {
             vector.x, vector.y, vector.z, quaternion.x, quaternion.y, quaternion.z, quaternion.w,
         )
     }
-
-    fn __repr__(&self) -> String {
-        self.__str__()
-    }
 }
@@ -0,0 +1,136 @@
+use pyo3::prelude::*;
+use pyo3::types::PyType;
+
+use crate::{
+    bindings::{
+        PyFrame, PyPose,
+        utils::{PyVector3, PyVector3Like},
+    },
+    region::{Region, RegionShape},
+};
+
+#[pyclass(name = "RegionShape", unsendable)]
+#[derive(Clone)]
+pub struct PyRegionShape {
+    pub(crate) inner: RegionShape,
+}
+
+#[pymethods]
+impl PyRegionShape {
+    #[classmethod]
+    #[pyo3(name = "box", signature = (min, max))]
+    const fn new_box(_cls: &Bound<'_, PyType>, min: PyVector3Like, max: PyVector3Like) -> Self {
+        Self {
+            inner: RegionShape::Box {
+                min: min.into_inner(),
+                max: max.into_inner(),
+            },
+        }
+    }
+
+    #[classmethod]
+    #[pyo3(signature = (center, radius))]
+    const fn sphere(_cls: &Bound<'_, PyType>, center: PyVector3Like, radius: f64) -> Self {
+        Self {
+            inner: RegionShape::Sphere {
+                center: center.into_inner(),
+                radius,
+            },
+        }
+    }
+
+    #[getter]
+    const fn kind(&self) -> &'static str {
+        match self.inner {
+            RegionShape::Box { .. } => "box",
+            RegionShape::Sphere { .. } => "sphere",
+        }
+    }
+
+    #[pyo3(signature = (point))]
+    fn signed_distance(&self, point: PyVector3Like) -> f64 {
+        self.inner.signed_distance(&point.into_inner())
+    }
+
+    #[pyo3(signature = (point))]
+    fn contains_point(&self, point: PyVector3Like) -> bool {
+        self.inner.contains_point(&point.into_inner())
+    }
+
+    const fn corners(&self) -> Option<(PyVector3, PyVector3)> {
+        match self.inner {
+            RegionShape::Box { min, max } => {
+                Some((PyVector3 { inner: min }, PyVector3 { inner: max }))
+            }
+            RegionShape::Sphere { .. } => None,
+        }
+    }
+
+    const fn center_and_radius(&self) -> Option<(PyVector3, f64)> {
+        match self.inner {
+            RegionShape::Box { .. } => None,
+            RegionShape::Sphere { center, radius } => Some((PyVector3 { inner: center }, radius)),
+        }
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RegionShape({})", self.inner)
+    }
+}
+
+#[pyclass(name = "Region", unsendable)]
+#[derive(Clone)]
+pub struct PyRegion {
+    pub(crate) inner: Region,
+}
+
+#[pymethods]
+impl PyRegion {
+    #[new]
+    #[pyo3(signature = (frame, shape))]
+    fn new(frame: &PyFrame, shape: &PyRegionShape) -> PyResult<Self> {
+        Ok(Self {
+            inner: Region::new(&frame.rust_frame, shape.inner.clone())?,
+        })
+    }
+
+    #[getter]
+    fn frame(&self) -> PyFrame {
+        PyFrame {
+            rust_frame: self.inner.frame(),
+        }
+    }
+
+    #[getter]
+    fn shape(&self) -> PyRegionShape {
+        PyRegionShape {
+            inner: self.inner.shape().clone(),
+        }
+    }
+
+    #[pyo3(signature = (pose))]
+    fn contains(&self, pose: &PyPose) -> PyResult<bool> {
+        Ok(self.inner.contains(&pose.rust_pose)?)
+    }
+
+    #[pyo3(signature = (pose))]
+    fn distance_to_boundary(&self, pose: &PyPose) -> PyResult<f64> {
+        Ok(self.inner.distance_to_boundary(&pose.rust_pose)?)
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Region({})", self.inner)
+    }
+}
@@ -0,0 +1,28 @@
+use pyo3::prelude::*;
+
+use crate::snapshot::TreeState;
+
+#[pyclass(name = "TreeState", unsendable)]
+#[derive(Clone)]
+pub struct PyTreeState {
+    pub(crate) inner: TreeState,
+}
+
+#[pymethods]
+impl PyTreeState {
+    fn paths(&self) -> Vec<String> {
+        self.inner.paths().collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __str__(&self) -> String {
+        format!("TreeState(frames: {})", self.inner.len())
+    }
+
+    fn __repr__(&self) -> String {
+        self.__str__()
+    }
+}
@@ -0,0 +1,123 @@
+use pyo3::exceptions::PyIndexError;
+use pyo3::prelude::*;
+
+use crate::{
+    Pose,
+    bindings::{PyFrame, PyPose},
+    trajectory::{FrameMismatch, Trajectory},
+};
+
+#[pyclass(name = "Trajectory", unsendable)]
+#[derive(Clone)]
+pub struct PyTrajectory {
+    pub(crate) inner: Trajectory,
+}
+
+#[pymethods]
+impl PyTrajectory {
+    #[new]
+    #[pyo3(signature = (frame, poses, convert = false))]
+    fn new(frame: &PyFrame, poses: Vec<PyPose>, convert: bool) -> PyResult<Self> {
+        let mismatch = if convert {
+            FrameMismatch::Convert
+        } else {
+            FrameMismatch::Reject
+        };
+        let inner = Trajectory::new(
+            &frame.rust_frame,
+            poses.into_iter().map(|pose| pose.rust_pose),
+            mismatch,
+        )?;
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn frame(&self) -> PyFrame {
+        PyFrame {
+            rust_frame: self.inner.frame(),
+        }
+    }
+
+    #[pyo3(signature = (pose))]
+    fn push(&mut self, pose: &PyPose) -> PyResult<()> {
+        self.inner.push(pose.rust_pose.clone())?;
+        Ok(())
+    }
+
+    fn poses(&self) -> Vec<PyPose> {
+        self.inner
+            .iter()
+            .map(|pose| PyPose {
+                rust_pose: pose.clone(),
+            })
+            .collect()
+    }
+
+    #[pyo3(signature = (target_frame))]
+    fn in_frame(&self, py: Python<'_>, target_frame: &PyFrame) -> PyResult<Self> {
+        let isometry = self.inner.frame().transform_to(&target_frame.rust_frame)?;
+        let transforms: Vec<_> = self.inner.iter().map(Pose::transformation).collect();
+        // Poses refer to their frame, so only their plain transforms are converted without the GIL.
+        let converted = py.allow_threads(move || {
+            transforms
+                .into_iter()
+                .map(|transform| isometry * transform)
+                .collect::<Vec<_>>()
+        });
+        Ok(Self {
+            inner: self
+                .inner
+                .with_transforms(&target_frame.rust_frame, converted),
+        })
+    }
+
+    fn total_length(&self) -> f64 {
+        self.inner.total_length()
+    }
+
+    #[pyo3(signature = (count))]
+    fn resample(&self, count: usize) -> PyResult<Self> {
+        Ok(Self {
+            inner: self.inner.resample(count)?,
+        })
+    }
+
+    #[pyo3(signature = (step))]
+    fn resample_by_distance(&self, step: f64) -> PyResult<Self> {
+        Ok(Self {
+            inner: self.inner.resample_by_distance(step)?,
+        })
+    }
+
+    const fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __getitem__(&self, index: isize) -> PyResult<PyPose> {
+        let resolved = if index < 0 {
+            index.checked_add_unsigned(self.inner.len())
+        } else {
+            Some(index)
+        };
+        resolved
+            .and_then(|index| usize::try_from(index).ok())
+            .and_then(|index| self.inner.poses().get(index))
+            .map(|pose| PyPose {
+                rust_pose: pose.clone(),
+            })
+            .ok_or_else(|| PyIndexError::new_err("trajectory index out of range"))
+    }
+
+    fn __str__(&self) -> String {
+        format!(
+            "Trajectory(frame: {}, poses: {}, length: {:.4})",
+            self.inner.frame().name(),
+            self.inner.len(),
+            self.inner.total_length()
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        self.__str__()
+    }
+}
@@ -1,16 +1,10 @@
-use nalgebra::{Isometry3, Translation3, UnitQuaternion, Vector3};
+use nalgebra::{Isometry3, Matrix3, Matrix4, Translation3, Unit, UnitQuaternion, Vector3};
+use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyType;
+use pyo3::types::{PyString, PyType};
 
-use crate::CartesianTreeError;
 use crate::rotation::Rotation;
 
-impl From<CartesianTreeError> for PyErr {
-    fn from(err: CartesianTreeError) -> Self {
-        pyo3::exceptions::PyValueError::new_err(err.to_string())
-    }
-}
-
 #[pyclass(name = "Rotation", unsendable)]
 #[derive(Clone, Copy, Debug)]
 pub struct PyRotation {
@@ -33,6 +27,13 @@ impl PyRotation {
         }
     }
 
+    #[classmethod]
+    const fn from_rpy_degrees(_cls: &Bound<'_, PyType>, roll: f64, pitch: f64, yaw: f64) -> Self {
+        Self {
+            rust_rotation: Rotation::from_rpy_degrees(roll, pitch, yaw),
+        }
+    }
+
     #[classmethod]
     fn identity(_cls: &Bound<'_, PyType>) -> Self {
         Self {
@@ -40,6 +41,42 @@ impl PyRotation {
         }
     }
 
+    #[classmethod]
+    fn from_axis_angle(
+        _cls: &Bound<'_, PyType>,
+        axis: PyVector3Like,
+        angle: f64,
+    ) -> PyResult<Self> {
+        let axis = Unit::try_new(axis.into_inner(), 0.0)
+            .ok_or_else(|| PyValueError::new_err("axis must not be the zero vector"))?;
+        Ok(Self {
+            rust_rotation: Rotation::from_axis_angle(axis, angle),
+        })
+    }
+
+    #[classmethod]
+    #[pyo3(signature = (matrix, orthonormalize = false))]
+    fn from_matrix(
+        _cls: &Bound<'_, PyType>,
+        matrix: [[f64; 3]; 3],
+        orthonormalize: bool,
+    ) -> PyResult<Self> {
+        let matrix = Matrix3::from_fn(|row, column| matrix[row][column]);
+        let rust_rotation = if orthonormalize {
+            Rotation::from_matrix_orthonormalized(matrix)?
+        } else {
+            Rotation::from_matrix(matrix)?
+        };
+        Ok(Self { rust_rotation })
+    }
+
+    #[classmethod]
+    fn from_rotation_vector(_cls: &Bound<'_, PyType>, vector: PyVector3Like) -> Self {
+        Self {
+            rust_rotation: Rotation::from_rotation_vector(vector.into_inner()),
+        }
+    }
+
     #[allow(clippy::wrong_self_convention)]
     fn as_quaternion(&self) -> (f64, f64, f64, f64) {
         let quat = self.rust_rotation.as_quaternion();
@@ -52,6 +89,58 @@ impl PyRotation {
         (rpy.x, rpy.y, rpy.z)
     }
 
+    #[allow(clippy::wrong_self_convention)]
+    fn to_rpy_degrees(&self) -> (f64, f64, f64) {
+        let rpy = self.rust_rotation.to_rpy_degrees();
+        (rpy.x, rpy.y, rpy.z)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn to_matrix(&self) -> [[f64; 3]; 3] {
+        let matrix = self.rust_rotation.to_matrix();
+        [0, 1, 2].map(|row| [0, 1, 2].map(|column| matrix[(row, column)]))
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn to_axis_angle(&self) -> (PyVector3, f64) {
+        let (axis, angle) = self.rust_rotation.to_axis_angle();
+        (
+            PyVector3 {
+                inner: axis.into_inner(),
+            },
+            angle,
+        )
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn to_rotation_vector(&self) -> PyVector3 {
+        PyVector3 {
+            inner: self.rust_rotation.to_rotation_vector(),
+        }
+    }
+
+    fn inverse(&self) -> Self {
+        Self {
+            rust_rotation: self.rust_rotation.inverse(),
+        }
+    }
+
+    fn slerp(&self, other: &Self, t: f64) -> Self {
+        Self {
+            rust_rotation: self.rust_rotation.slerp(&other.rust_rotation, t),
+        }
+    }
+
+    fn angle_to(&self, other: &Self) -> f64 {
+        self.rust_rotation.angle_to(&other.rust_rotation)
+    }
+
+    fn __mul__(&self, other: &Self) -> Self {
+        Self {
+            rust_rotation: self.rust_rotation.compose(&other.rust_rotation),
+        }
+    }
+
     fn __str__(&self) -> String {
         match &self.rust_rotation {
             Rotation::Quaternion(q) => {
@@ -61,6 +150,10 @@ impl PyRotation {
                 )
             }
             Rotation::Rpy(rpy) => format!("RPY({:.4}, {:.4}, {:.4})", rpy.x, rpy.y, rpy.z),
+            Rotation::AxisAngle(axis, angle) => format!(
+                "AxisAngle(<{:.4}, {:.4}, {:.4}>, {:.4})",
+                axis.x, axis.y, axis.z, angle
+            ),
         }
     }
 
@@ -113,6 +206,79 @@ impl PyVector3 {
     }
 }
 
+/// A vector argument given as `Vector3` or as a sequence `(x, y, z)`, e.g. a tuple, list or
+/// 1-D numpy array.
+pub struct PyVector3Like(Vector3<f64>);
+
+impl PyVector3Like {
+    #[must_use]
+    pub const fn into_inner(self) -> Vector3<f64> {
+        self.0
+    }
+}
+
+impl<'py> FromPyObject<'py> for PyVector3Like {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(vector) = ob.extract::<PyVector3>() {
+            return Ok(Self(vector.inner));
+        }
+        match extract_components(ob).as_deref() {
+            Some(&[x, y, z]) => Ok(Self(Vector3::new(x, y, z))),
+            _ => Err(PyTypeError::new_err(
+                "expected a Vector3 or a sequence of 3 numbers",
+            )),
+        }
+    }
+}
+
+/// An orientation argument given as `Rotation`, as a quaternion sequence `(x, y, z, w)`, as a
+/// roll-pitch-yaw sequence `(roll, pitch, yaw)` in radians, or as `None` for the identity.
+///
+/// Sequences are told apart by their length only.
+pub struct PyRotationLike(Rotation);
+
+impl PyRotationLike {
+    #[must_use]
+    pub const fn into_inner(self) -> Rotation {
+        self.0
+    }
+}
+
+impl<'py> FromPyObject<'py> for PyRotationLike {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if ob.is_none() {
+            return Ok(Self(Rotation::identity()));
+        }
+        if let Ok(rotation) = ob.extract::<PyRotation>() {
+            return Ok(Self(rotation.rust_rotation));
+        }
+        match extract_components(ob).as_deref() {
+            Some(&[x, y, z, w]) => Ok(Self(Rotation::from_quaternion(x, y, z, w))),
+            Some(&[roll, pitch, yaw]) => Ok(Self(Rotation::from_rpy(roll, pitch, yaw))),
+            _ => Err(PyTypeError::new_err(
+                "expected a Rotation, None, a quaternion (x, y, z, w) or roll-pitch-yaw angles",
+            )),
+        }
+    }
+}
+
+/// Returns the rows of a 4x4 matrix, e.g. for conversion to a numpy array.
+pub(crate) fn matrix4_rows(matrix: &Matrix4<f64>) -> [[f64; 4]; 4] {
+    [0, 1, 2, 3].map(|row| [0, 1, 2, 3].map(|column| matrix[(row, column)]))
+}
+
+/// Reads a flat sequence of numbers, e.g. a tuple, list or 1-D numpy array.
+fn extract_components(ob: &Bound<'_, PyAny>) -> Option<Vec<f64>> {
+    // Strings are sequences too, but never meant as numbers.
+    if ob.is_instance_of::<PyString>() {
+        return None;
+    }
+    ob.try_iter()
+        .ok()?
+        .map(|item| item.ok()?.extract::<f64>().ok())
+        .collect()
+}
+
 #[pyclass(name = "Isometry", unsendable)]
 #[derive(Clone, Copy, Debug)]
 pub struct PyIsometry {
@@ -129,31 +295,35 @@ impl PyIsometry {
     }
 
     #[classmethod]
-    fn from_translation(_cls: &Bound<'_, PyType>, translation: PyVector3) -> Self {
+    fn from_translation(_cls: &Bound<'_, PyType>, translation: PyVector3Like) -> Self {
         Self {
             inner: Isometry3::from_parts(
-                Translation3::from(translation.inner),
+                Translation3::from(translation.into_inner()),
                 UnitQuaternion::identity(),
             ),
         }
     }
 
     #[classmethod]
-    fn from_rotation(_cls: &Bound<'_, PyType>, rotation: PyRotation) -> Self {
+    fn from_rotation(_cls: &Bound<'_, PyType>, rotation: PyRotationLike) -> Self {
         Self {
             inner: Isometry3::from_parts(
                 Translation3::new(0.0, 0.0, 0.0),
-                rotation.rust_rotation.as_quaternion(),
+                rotation.into_inner().as_quaternion(),
             ),
         }
     }
 
     #[classmethod]
-    fn from_parts(_cls: &Bound<'_, PyType>, translation: PyVector3, rotation: PyRotation) -> Self {
+    fn from_parts(
+        _cls: &Bound<'_, PyType>,
+        translation: PyVector3Like,
+        rotation: PyRotationLike,
+    ) -> Self {
         Self {
             inner: Isometry3::from_parts(
-                Translation3::from(translation.inner),
-                rotation.rust_rotation.as_quaternion(),
+                Translation3::from(translation.into_inner()),
+                rotation.into_inner().as_quaternion(),
             ),
         }
     }
@@ -0,0 +1,272 @@
+//! Declaring whole frame trees in one expression, e.g. for fixtures and demos.
+//!
+//! [`TreeBuilder`] collects the frames first and checks them all before creating any, so every
+//! invalid name and value is reported at once. The tree is then built with
+//! [`Frame::add_child`], so it is identical to a tree built by hand.
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::rotation::Rotation;
+use crate::validation::{FrameNameRules, check_frame_name, check_orientation, check_position};
+use nalgebra::{UnitQuaternion, Vector3};
+use std::collections::BTreeSet;
+
+/// Builds a frame tree from nested declarations.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::builder::TreeBuilder;
+/// use nalgebra::{UnitQuaternion, Vector3};
+///
+/// let world = TreeBuilder::root("world")
+///     .child("robot", [1.0, 0.0, 0.0], UnitQuaternion::identity(), |robot| {
+///         robot.child("flange", [0.0, 0.0, 0.8], UnitQuaternion::identity(), |flange| {
+///             flange.leaf("tool", [0.0, 0.0, 0.1], UnitQuaternion::identity())
+///         })
+///     })
+///     .leaf("camera", [0.0, 2.0, 1.5], UnitQuaternion::identity())
+///     .build()
+///     .unwrap();
+///
+/// let tool = world.resolve_path("robot/flange/tool").unwrap();
+/// assert_eq!(tool.world_transform().unwrap().translation.vector, Vector3::new(1.0, 0.0, 0.9));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TreeBuilder {
+    name: String,
+    rules: FrameNameRules,
+    children: ChildBuilder,
+}
+
+/// The children of a frame declared with [`TreeBuilder::child`].
+#[derive(Clone, Debug, Default)]
+pub struct ChildBuilder {
+    children: Vec<ChildSpec>,
+}
+
+#[derive(Clone, Debug)]
+struct ChildSpec {
+    name: String,
+    position: Vector3<f64>,
+    orientation: UnitQuaternion<f64>,
+    children: ChildBuilder,
+}
+
+impl TreeBuilder {
+    /// Starts a tree with the given root, checking names against the default
+    /// [`FrameNameRules`].
+    #[must_use]
+    pub fn root(name: impl Into<String>) -> Self {
+        Self::root_with(name, FrameNameRules::default())
+    }
+
+    /// Starts a tree with the given root, checking names against `rules`, see
+    /// [`Frame::new_origin_with`].
+    #[must_use]
+    pub fn root_with(name: impl Into<String>, rules: FrameNameRules) -> Self {
+        Self {
+            name: name.into(),
+            rules,
+            children: ChildBuilder::default(),
+        }
+    }
+
+    /// Declares a child of the root, whose own children are declared by `children`.
+    #[must_use]
+    pub fn child(
+        mut self,
+        name: impl Into<String>,
+        position: impl Into<Vector3<f64>>,
+        orientation: impl Into<Rotation>,
+        children: impl FnOnce(ChildBuilder) -> ChildBuilder,
+    ) -> Self {
+        self.children = self.children.child(name, position, orientation, children);
+        self
+    }
+
+    /// Declares a child of the root without children of its own.
+    #[must_use]
+    pub fn leaf(
+        self,
+        name: impl Into<String>,
+        position: impl Into<Vector3<f64>>,
+        orientation: impl Into<Rotation>,
+    ) -> Self {
+        self.child(name, position, orientation, |children| children)
+    }
+
+    /// Checks all declarations and builds the tree.
+    ///
+    /// # Returns
+    /// The root of the new tree.
+    ///
+    /// # Errors
+    /// Returns every problem found, parent by parent in depth-first order, if:
+    /// - A name violates the [`FrameNameRules`].
+    /// - Two siblings share a name.
+    /// - A position or orientation is not finite.
+    pub fn build(self) -> Result<Frame, Vec<CartesianTreeError>> {
+        let mut errors = Vec::new();
+        if let Err(error) = check_frame_name(&self.name, self.rules) {
+            errors.push(error);
+        }
+        let mut pending = vec![(format!("/{}", self.name), &self.children)];
+        while let Some((path, children)) = pending.pop() {
+            let mut names = BTreeSet::new();
+            for child in &children.children {
+                let child_path = format!("{path}/{}", child.name);
+                if let Err(error) = check_frame_name(&child.name, self.rules) {
+                    errors.push(error);
+                }
+                if !names.insert(child.name.as_str()) {
+                    errors.push(CartesianTreeError::DuplicateChildName {
+                        parent: path.clone(),
+                        child: child.name.clone(),
+                    });
+                }
+                if let Err(error) =
+                    check_position(&format!("{child_path}.position"), &child.position)
+                {
+                    errors.push(error);
+                }
+                if let Err(error) =
+                    check_orientation(&format!("{child_path}.orientation"), &child.orientation)
+                {
+                    errors.push(error);
+                }
+            }
+            // Reversed, so siblings are visited in declaration order.
+            for child in children.children.iter().rev() {
+                pending.push((format!("{path}/{}", child.name), &child.children));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let root = Frame::new_origin_with(self.name, self.rules).map_err(|error| vec![error])?;
+        let mut pending = vec![(root.clone(), self.children)];
+        while let Some((parent, children)) = pending.pop() {
+            for child in children.children {
+                let frame = parent
+                    .add_child(child.name, child.position, child.orientation)
+                    .map_err(|error| vec![error])?;
+                pending.push((frame, child.children));
+            }
+        }
+        Ok(root)
+    }
+}
+
+impl ChildBuilder {
+    /// Declares a child, whose own children are declared by `children`.
+    #[must_use]
+    pub fn child(
+        mut self,
+        name: impl Into<String>,
+        position: impl Into<Vector3<f64>>,
+        orientation: impl Into<Rotation>,
+        children: impl FnOnce(Self) -> Self,
+    ) -> Self {
+        self.children.push(ChildSpec {
+            name: name.into(),
+            position: position.into(),
+            orientation: orientation.into().as_quaternion(),
+            children: children(Self::default()),
+        });
+        self
+    }
+
+    /// Declares a child without children of its own.
+    #[must_use]
+    pub fn leaf(
+        self,
+        name: impl Into<String>,
+        position: impl Into<Vector3<f64>>,
+        orientation: impl Into<Rotation>,
+    ) -> Self {
+        self.child(name, position, orientation, |children| children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn builds_the_same_tree_as_add_child() {
+        let built = TreeBuilder::root("world")
+            .child(
+                "robot",
+                [1.0, 2.0, 0.0],
+                Rotation::from_rpy(0.0, 0.0, 0.5),
+                |robot| {
+                    robot.child(
+                        "flange",
+                        [0.0, 0.0, 0.8],
+                        UnitQuaternion::identity(),
+                        |flange| {
+                            flange.leaf("tool", [0.0, 0.0, 0.1], Rotation::from_rpy(0.1, 0.0, 0.0))
+                        },
+                    )
+                },
+            )
+            .leaf("camera", [0.0, 2.0, 1.5], UnitQuaternion::identity())
+            .build()
+            .unwrap();
+
+        let manual = Frame::new_origin("world").unwrap();
+        let robot = manual
+            .add_child("robot", [1.0, 2.0, 0.0], Rotation::from_rpy(0.0, 0.0, 0.5))
+            .unwrap();
+        robot
+            .add_child("flange", [0.0, 0.0, 0.8], UnitQuaternion::identity())
+            .unwrap()
+            .add_child("tool", [0.0, 0.0, 0.1], Rotation::from_rpy(0.1, 0.0, 0.0))
+            .unwrap();
+        manual
+            .add_child("camera", [0.0, 2.0, 1.5], UnitQuaternion::identity())
+            .unwrap();
+
+        assert_eq!(built.to_json().unwrap(), manual.to_json().unwrap());
+        let paths: Vec<_> = built.iter_dfs().map(|frame| frame.path()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/world",
+                "/world/robot",
+                "/world/robot/flange",
+                "/world/robot/flange/tool",
+                "/world/camera"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_all_problems_together() {
+        let errors = TreeBuilder::root("world")
+            .child("arm", [0.0, 0.0, 0.0], UnitQuaternion::identity(), |arm| {
+                arm.leaf("tool", [f64::NAN, 0.0, 0.0], UnitQuaternion::identity())
+                    .leaf("bad/name", [0.0, 0.0, 0.0], UnitQuaternion::identity())
+            })
+            .leaf("arm", [0.0, 0.0, 0.0], UnitQuaternion::identity())
+            .build()
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 3, "{errors:?}");
+        assert!(matches!(
+            &errors[0],
+            CartesianTreeError::DuplicateChildName { parent, child }
+                if parent == "/world" && child == "arm"
+        ));
+        assert!(matches!(
+            &errors[1],
+            CartesianTreeError::NonFiniteValue { field, .. } if field == "/world/arm/tool.position.x"
+        ));
+        assert!(matches!(
+            &errors[2],
+            CartesianTreeError::InvalidFrameName { .. }
+        ));
+    }
+}
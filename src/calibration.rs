@@ -0,0 +1,1210 @@
+//! Calibration of frames from measurements.
+//!
+//! Complements [`Frame::calibrate_child`], which places a frame from a single reference pose,
+//! with least-squares fits over several measurements.
+
+use crate::CartesianTreeError;
+use crate::audit::AuditOperation;
+use crate::frame::Frame;
+use crate::pose::Pose;
+use crate::rotation::Rotation;
+use crate::tree::Walking;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use nalgebra::ComplexField as _;
+use nalgebra::{
+    Isometry3, Matrix3, Matrix4, Point3, Quaternion, Rotation3, SymmetricEigen, Translation3,
+    UnitQuaternion, Vector3,
+};
+
+/// Relative size below which the points defining a frame are considered coincident or
+/// collinear.
+const THREE_POINT_TOLERANCE: f64 = 1e-9;
+
+/// Relative size of the second singular value of the cross-covariance below which the points
+/// are considered collinear.
+const COLLINEARITY_TOLERANCE: f64 = 1e-9;
+
+/// Rotation angle in radians below which a hand-eye motion is considered a pure translation.
+const MIN_MOTION_ANGLE: f64 = 1e-6;
+
+/// Relative size of the smallest eigenvalue of the hand-eye normal equations below which the
+/// rotation axes of the motions are considered parallel.
+const PARALLEL_AXES_TOLERANCE: f64 = 1e-9;
+
+/// The residual errors of a single calibration sample.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SampleResidual {
+    /// The distance between the fitted and the observed position.
+    pub translation: f64,
+    /// The angle in radians between the fitted and the observed orientation.
+    pub rotation: f64,
+}
+
+/// Summary of how well a calibrated frame fits the measurements it was computed from.
+///
+/// Samples with residuals far above the mean usually indicate bad measurements.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalibrationReport {
+    /// The residuals of each sample, in input order.
+    pub residuals: Vec<SampleResidual>,
+    /// The largest translational residual.
+    pub max_translation_error: f64,
+    /// The mean translational residual.
+    pub mean_translation_error: f64,
+    /// The largest rotational residual in radians.
+    pub max_rotation_error: f64,
+    /// The mean rotational residual in radians.
+    pub mean_rotation_error: f64,
+}
+
+impl CalibrationReport {
+    fn new(residuals: Vec<SampleResidual>) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let count = residuals.len() as f64;
+        let fold = |select: fn(&SampleResidual) -> f64| {
+            residuals
+                .iter()
+                .map(select)
+                .fold((0.0, 0.0_f64), |(sum, max), value| {
+                    (sum + value, max.max(value))
+                })
+        };
+        let (translation_sum, max_translation_error) = fold(|residual| residual.translation);
+        let (rotation_sum, max_rotation_error) = fold(|residual| residual.rotation);
+        Self {
+            max_translation_error,
+            mean_translation_error: translation_sum / count,
+            max_rotation_error,
+            mean_rotation_error: rotation_sum / count,
+            residuals,
+        }
+    }
+}
+
+/// Summary of how well a plane fits the points it was computed from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlaneFitReport {
+    /// The signed distance of each point from the plane along the normal, in input order.
+    pub residuals: Vec<f64>,
+    /// The root-mean-square distance of the points from the plane.
+    pub rms_error: f64,
+    /// The largest distance of a point from the plane.
+    pub max_error: f64,
+}
+
+impl PlaneFitReport {
+    fn new(residuals: Vec<f64>) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let count = residuals.len() as f64;
+        let squared_sum: f64 = residuals.iter().map(|residual| residual * residual).sum();
+        Self {
+            rms_error: (squared_sum / count).sqrt(),
+            max_error: residuals
+                .iter()
+                .fold(0.0, |max, residual| residual.abs().max(max)),
+            residuals,
+        }
+    }
+}
+
+/// Summary of how well a hand-eye calibration fits the motion pairs it was computed from.
+///
+/// Each residual compares `A * X` with `X * B` for one motion pair, see [`solve_hand_eye`].
+pub type HandEyeReport = CalibrationReport;
+
+impl Frame {
+    /// Adds a new child frame fitted to point correspondences.
+    ///
+    /// The transform of the child is the rigid transform that best maps the `expected`
+    /// coordinates (in the new frame) onto the `observed` coordinates (in this frame) in the
+    /// least-squares sense, computed with the Kabsch algorithm.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new child frame.
+    /// - `observed`: The measured points, expressed in this frame.
+    /// - `expected`: The coordinates of the same points in the new frame.
+    ///
+    /// # Returns
+    /// The new child frame and the root-mean-square distance between the observed points and
+    /// the fitted expected points.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The slices differ in length.
+    /// - There are fewer than three points, or the points are coincident or collinear.
+    /// - A child with the same name already exists.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::Point3;
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let expected = [
+    ///     Point3::new(0.0, 0.0, 0.0),
+    ///     Point3::new(1.0, 0.0, 0.0),
+    ///     Point3::new(0.0, 1.0, 0.0),
+    /// ];
+    /// let observed = expected.map(|point| point + nalgebra::Vector3::new(2.0, 0.0, 0.0));
+    ///
+    /// let (fixture, rms) = root
+    ///     .calibrate_child_from_points("fixture", &observed, &expected)
+    ///     .unwrap();
+    /// assert!(rms < 1e-12);
+    /// ```
+    pub fn calibrate_child_from_points(
+        &self,
+        name: impl Into<String>,
+        observed: &[Point3<f64>],
+        expected: &[Point3<f64>],
+    ) -> Result<(Self, f64), CartesianTreeError> {
+        let (transform, rms) = fit_rigid_transform(expected, observed)?;
+        let child = self.add_child(name, transform.translation.vector, transform.rotation)?;
+        child.record_audit(AuditOperation::Calibration, None);
+        Ok((child, rms))
+    }
+
+    /// Adds a new child frame calibrated from several reference poses.
+    ///
+    /// This is the least-squares counterpart of [`Frame::calibrate_child`]: each observed pose
+    /// should appear at the corresponding desired position and orientation when expressed in
+    /// the new frame. The orientation of the frame is the quaternion average of the
+    /// orientations implied by each sample, and its position the mean of the implied positions
+    /// given that orientation.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new child frame.
+    /// - `desired`: The desired positions and orientations of the poses in the new frame.
+    /// - `observed`: The observed poses, in any frame sharing an ancestor with this frame.
+    ///
+    /// # Returns
+    /// The new child frame and a report of the per-sample residuals.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The slices are empty or differ in length.
+    /// - The frame of an observed pose has been dropped or shares no ancestor with this frame.
+    /// - A child with the same name already exists.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::rotation::Rotation;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let observed = [
+    ///     root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap(),
+    ///     root.add_pose(Vector3::new(1.0, 1.0, 0.0), UnitQuaternion::identity()).unwrap(),
+    /// ];
+    /// let desired = [
+    ///     (Vector3::zeros(), Rotation::identity()),
+    ///     (Vector3::new(0.0, 1.0, 0.0), Rotation::identity()),
+    /// ];
+    ///
+    /// let (fixture, report) = root
+    ///     .calibrate_child_from_poses("fixture", &desired, &observed)
+    ///     .unwrap();
+    /// assert!((fixture.position() - Vector3::new(1.0, 0.0, 0.0)).norm() < 1e-12);
+    /// assert!(report.max_translation_error < 1e-12);
+    /// ```
+    pub fn calibrate_child_from_poses(
+        &self,
+        name: impl Into<String>,
+        desired: &[(Vector3<f64>, Rotation)],
+        observed: &[Pose],
+    ) -> Result<(Self, CalibrationReport), CartesianTreeError> {
+        if desired.len() != observed.len() {
+            return Err(CartesianTreeError::LengthMismatch {
+                expected: desired.len(),
+                found: observed.len(),
+            });
+        }
+        if desired.is_empty() {
+            return Err(degenerate_input("at least one pose pair is required"));
+        }
+
+        let samples = desired
+            .iter()
+            .zip(observed)
+            .map(|((position, orientation), pose)| {
+                let desired = Isometry3::from_parts(
+                    Translation3::from(*position),
+                    orientation.as_quaternion(),
+                );
+                Ok((desired, pose.in_frame(self)?.transformation()))
+            })
+            .collect::<Result<Vec<_>, CartesianTreeError>>()?;
+
+        let rotation = average_rotations(
+            samples
+                .iter()
+                .map(|(desired, observed)| observed.rotation * desired.rotation.inverse()),
+        );
+        #[allow(clippy::cast_precision_loss)]
+        let count = samples.len() as f64;
+        let translation = samples
+            .iter()
+            .map(|(desired, observed)| {
+                observed.translation.vector - rotation * desired.translation.vector
+            })
+            .sum::<Vector3<f64>>()
+            / count;
+        let transform = Isometry3::from_parts(Translation3::from(translation), rotation);
+
+        let residuals = samples
+            .iter()
+            .map(|(desired, observed)| {
+                let fitted = transform * desired;
+                SampleResidual {
+                    translation: (fitted.translation.vector - observed.translation.vector).norm(),
+                    rotation: Rotation::from(fitted.rotation)
+                        .angle_to(&Rotation::from(observed.rotation)),
+                }
+            })
+            .collect();
+
+        let child = self.add_child(name, translation, rotation)?;
+        child.record_audit(AuditOperation::Calibration, None);
+        Ok((child, CalibrationReport::new(residuals)))
+    }
+
+    /// Adds a new child frame defined by three measured points.
+    ///
+    /// The new frame has its origin at `origin` and its X axis pointing towards `x_point`. Its
+    /// Z axis is the normal of the plane through all three points, oriented such that
+    /// `xy_point` lies on the positive Y side, and Y completes the right-handed frame.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new child frame.
+    /// - `origin`: The origin of the new frame, expressed in this frame.
+    /// - `x_point`: A point on the positive X axis of the new frame, expressed in this frame.
+    /// - `xy_point`: A point in the XY plane of the new frame, expressed in this frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `x_point` coincides with `origin`, or the three points are collinear.
+    /// - A child with the same name already exists.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Point3, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let table = root
+    ///     .add_child_from_three_points(
+    ///         "table",
+    ///         Point3::new(1.0, 1.0, 0.0),
+    ///         Point3::new(1.0, 2.0, 0.0),
+    ///         Point3::new(0.0, 1.0, 0.0),
+    ///     )
+    ///     .unwrap();
+    /// assert!((table.position() - Vector3::new(1.0, 1.0, 0.0)).norm() < 1e-12);
+    /// ```
+    #[allow(clippy::similar_names)]
+    pub fn add_child_from_three_points(
+        &self,
+        name: impl Into<String>,
+        origin: Point3<f64>,
+        x_point: Point3<f64>,
+        xy_point: Point3<f64>,
+    ) -> Result<Self, CartesianTreeError> {
+        let x_direction = x_point - origin;
+        let in_plane = xy_point - origin;
+        let scale = x_direction.norm().max(in_plane.norm());
+        if x_direction.norm() <= scale * THREE_POINT_TOLERANCE || scale == 0.0 {
+            return Err(degenerate_input("origin and x_point coincide"));
+        }
+        let normal = x_direction.cross(&in_plane);
+        if normal.norm() <= x_direction.norm() * in_plane.norm() * THREE_POINT_TOLERANCE {
+            return Err(degenerate_input(
+                "origin, x_point and xy_point are collinear",
+            ));
+        }
+
+        let x_axis = x_direction.normalize();
+        let z_axis = normal.normalize();
+        let y_axis = z_axis.cross(&x_axis);
+        let rotation = UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(
+            Matrix3::from_columns(&[x_axis, y_axis, z_axis]),
+        ));
+        let child = self.add_child(name, origin.coords, rotation)?;
+        child.record_audit(AuditOperation::Calibration, None);
+        Ok(child)
+    }
+
+    /// Adds a new child frame whose XY plane is fitted to measured points, e.g. probed on a
+    /// table surface.
+    ///
+    /// The plane is fitted in the least-squares sense. The origin of the new frame is the
+    /// centroid of the points and its Z axis the plane normal, pointing towards the origin of
+    /// this frame (or along the positive Z axis of this frame if that origin lies in the plane).
+    /// The X axis is `x_hint` projected into the plane, or the direction in which the points
+    /// spread the most if no hint is given.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new child frame.
+    /// - `points`: The measured points, expressed in this frame.
+    /// - `x_hint`: A direction in this frame for the X axis of the new frame.
+    ///
+    /// # Returns
+    /// The new child frame and a report of the out-of-plane distances of the points.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - There are fewer than three points, or the points are coincident or collinear.
+    /// - `x_hint` is zero or perpendicular to the plane.
+    /// - A child with the same name already exists.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Point3, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let points = [
+    ///     Point3::new(0.0, 0.0, -1.0),
+    ///     Point3::new(2.0, 0.0, -1.0),
+    ///     Point3::new(0.0, 1.0, -1.0),
+    ///     Point3::new(2.0, 1.0, -1.0),
+    /// ];
+    ///
+    /// let (table, report) = root
+    ///     .add_child_from_plane_fit("table", &points, Some(Vector3::x()))
+    ///     .unwrap();
+    /// assert!((table.position() - Vector3::new(1.0, 0.5, -1.0)).norm() < 1e-12);
+    /// assert!(report.rms_error < 1e-12);
+    /// ```
+    pub fn add_child_from_plane_fit(
+        &self,
+        name: impl Into<String>,
+        points: &[Point3<f64>],
+        x_hint: Option<Vector3<f64>>,
+    ) -> Result<(Self, PlaneFitReport), CartesianTreeError> {
+        if points.len() < 3 {
+            return Err(degenerate_input(&format!(
+                "at least 3 points are required, got {}",
+                points.len()
+            )));
+        }
+        let center = centroid(points);
+        let covariance = points.iter().fold(Matrix3::zeros(), |sum, point| {
+            sum + (point - center) * (point - center).transpose()
+        });
+        let svd = covariance.svd(true, false);
+        let largest = svd.singular_values[0];
+        if largest <= 0.0 || svd.singular_values[1] <= largest * COLLINEARITY_TOLERANCE {
+            return Err(degenerate_input("points are coincident or collinear"));
+        }
+        let Some(u) = svd.u else {
+            return Err(degenerate_input("singular value decomposition failed"));
+        };
+
+        let mut z_axis: Vector3<f64> = u.column(2).into_owned();
+        let towards_origin = -z_axis.dot(&center.coords);
+        let in_plane = towards_origin.abs() <= center.coords.norm() * THREE_POINT_TOLERANCE;
+        if (in_plane && z_axis.z < 0.0) || (!in_plane && towards_origin < 0.0) {
+            z_axis = -z_axis;
+        }
+        let x_direction = match x_hint {
+            Some(hint) => {
+                let projected = hint - z_axis * z_axis.dot(&hint);
+                if projected.norm() <= hint.norm() * THREE_POINT_TOLERANCE || hint.norm() == 0.0 {
+                    return Err(degenerate_input(
+                        "x_hint is zero or perpendicular to the plane",
+                    ));
+                }
+                projected
+            }
+            None => u.column(0).into_owned(),
+        };
+        let x_axis = x_direction.normalize();
+        let y_axis = z_axis.cross(&x_axis);
+        let rotation = UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(
+            Matrix3::from_columns(&[x_axis, y_axis, z_axis]),
+        ));
+
+        let residuals = points
+            .iter()
+            .map(|point| (point - center).dot(&z_axis))
+            .collect();
+        let child = self.add_child(name, center.coords, rotation)?;
+        child.record_audit(AuditOperation::Calibration, None);
+        Ok((child, PlaneFitReport::new(residuals)))
+    }
+
+    /// Adds a camera mounted on this frame, calibrated from robot motions (eye-in-hand).
+    ///
+    /// While the robot moves this frame (the flange) between stations, the camera observes a
+    /// target that stays fixed in the robot cell. Consecutive stations form the motion pairs of
+    /// [`solve_hand_eye`], whose solution is the transform of the camera to this frame.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new camera frame.
+    /// - `flange_poses`: The measured poses of this frame at each station, in any frame that
+    ///   stays fixed while the robot moves (typically the robot base).
+    /// - `target_in_camera`: The transform of the target to the camera at each station, e.g.
+    ///   from a pose estimation.
+    ///
+    /// # Returns
+    /// The new camera frame and a report of the per-motion residuals.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The slices differ in length.
+    /// - The frame of a flange pose has been dropped or shares no ancestor with this frame.
+    /// - The motions are degenerate, see [`solve_hand_eye`].
+    /// - A child with the same name already exists.
+    pub fn calibrate_child_hand_eye(
+        &self,
+        name: impl Into<String>,
+        flange_poses: &[Pose],
+        target_in_camera: &[Isometry3<f64>],
+    ) -> Result<(Self, HandEyeReport), CartesianTreeError> {
+        if flange_poses.len() != target_in_camera.len() {
+            return Err(CartesianTreeError::LengthMismatch {
+                expected: flange_poses.len(),
+                found: target_in_camera.len(),
+            });
+        }
+        let root = self.root();
+        let flange = flange_poses
+            .iter()
+            .map(|pose| Ok(pose.in_frame(&root)?.transformation()))
+            .collect::<Result<Vec<_>, CartesianTreeError>>()?;
+
+        let a: Vec<_> = flange
+            .windows(2)
+            .map(|pair| pair[1].inverse() * pair[0])
+            .collect();
+        let b: Vec<_> = target_in_camera
+            .windows(2)
+            .map(|pair| pair[1] * pair[0].inverse())
+            .collect();
+        let (transform, report) = solve_hand_eye(&a, &b)?;
+
+        let child = self.add_child(name, transform.translation.vector, transform.rotation)?;
+        child.record_audit(AuditOperation::Calibration, None);
+        Ok((child, report))
+    }
+}
+
+/// Solves the hand-eye equation `A * X = X * B` for `X` with the method of Tsai and Lenz.
+///
+/// Each pair `(a[i], b[i])` describes the same rigid motion seen from two frames rigidly
+/// attached to each other, e.g. the motion of a robot flange and the corresponding motion of a
+/// camera mounted on it; `X` is the transform between those frames. The rotation of `X` is
+/// fitted first from the rotation axes of the motions, then its translation, both in the
+/// least-squares sense.
+///
+/// The method cannot represent an `X` that rotates by exactly 180°.
+///
+/// # Returns
+/// The transform `X` and a report of the per-pair residuals.
+///
+/// # Errors
+/// Returns a [`CartesianTreeError`] if:
+/// - The slices differ in length.
+/// - There are fewer than two motion pairs.
+/// - A motion is a pure translation, or the rotation axes of all motions are parallel.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::calibration::solve_hand_eye;
+/// use nalgebra::{Isometry3, Vector3};
+///
+/// let x = Isometry3::new(Vector3::new(0.0, 0.1, 0.2), Vector3::new(0.0, 0.0, 0.5));
+/// let a = [
+///     Isometry3::new(Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.3, 0.0, 0.0)),
+///     Isometry3::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.4, 0.0)),
+/// ];
+/// let b = a.map(|a| x.inverse() * a * x);
+///
+/// let (solved, report) = solve_hand_eye(&a, &b).unwrap();
+/// assert!((solved.translation.vector - x.translation.vector).norm() < 1e-9);
+/// assert!(report.max_rotation_error < 1e-9);
+/// ```
+pub fn solve_hand_eye(
+    a: &[Isometry3<f64>],
+    b: &[Isometry3<f64>],
+) -> Result<(Isometry3<f64>, HandEyeReport), CartesianTreeError> {
+    if a.len() != b.len() {
+        return Err(CartesianTreeError::LengthMismatch {
+            expected: a.len(),
+            found: b.len(),
+        });
+    }
+    if a.len() < 2 {
+        return Err(degenerate_input(&format!(
+            "at least 2 motion pairs are required, got {}",
+            a.len()
+        )));
+    }
+
+    // With quaternions `(w, v)` of equal sign, `A * X = X * B` becomes
+    // `(v_a + v_b) × v_x / w_x = v_b - v_a`, which is linear in `v_x / w_x`.
+    let mut normal = Matrix3::zeros();
+    let mut rhs = Vector3::zeros();
+    for (index, (a, b)) in a.iter().zip(b).enumerate() {
+        if a.rotation.angle() < MIN_MOTION_ANGLE || b.rotation.angle() < MIN_MOTION_ANGLE {
+            return Err(degenerate_input(&format!(
+                "motion pair {index} is a pure translation"
+            )));
+        }
+        let v_a = positive_quaternion(a.rotation).imag();
+        let v_b = positive_quaternion(b.rotation).imag();
+        let skew = (v_a + v_b).cross_matrix();
+        normal += skew.transpose() * skew;
+        rhs += skew.transpose() * (v_b - v_a);
+    }
+    let tangent = solve_normal_equations(normal, rhs)?;
+    let rotation = UnitQuaternion::new_normalize(Quaternion::from_parts(1.0, tangent));
+
+    // The translation solves `(R_a - I) * t_x = R_x * t_b - t_a`.
+    let mut normal = Matrix3::zeros();
+    let mut rhs = Vector3::zeros();
+    for (a, b) in a.iter().zip(b) {
+        let coefficients = a.rotation.to_rotation_matrix().into_inner() - Matrix3::identity();
+        normal += coefficients.transpose() * coefficients;
+        rhs += coefficients.transpose() * (rotation * b.translation.vector - a.translation.vector);
+    }
+    let translation = solve_normal_equations(normal, rhs)?;
+    let transform = Isometry3::from_parts(Translation3::from(translation), rotation);
+
+    let residuals = a
+        .iter()
+        .zip(b)
+        .map(|(a, b)| {
+            let left = a * transform;
+            let right = transform * b;
+            SampleResidual {
+                translation: (left.translation.vector - right.translation.vector).norm(),
+                rotation: left.rotation.angle_to(&right.rotation),
+            }
+        })
+        .collect();
+    Ok((transform, CalibrationReport::new(residuals)))
+}
+
+/// Returns the quaternion of `rotation` with a non-negative scalar part.
+fn positive_quaternion(rotation: UnitQuaternion<f64>) -> Quaternion<f64> {
+    if rotation.w < 0.0 {
+        -rotation.into_inner()
+    } else {
+        rotation.into_inner()
+    }
+}
+
+/// Solves the symmetric normal equations `normal * x = rhs` of the hand-eye problem.
+fn solve_normal_equations(
+    normal: Matrix3<f64>,
+    rhs: Vector3<f64>,
+) -> Result<Vector3<f64>, CartesianTreeError> {
+    let eigenvalues = SymmetricEigen::new(normal).eigenvalues;
+    let largest = eigenvalues.max();
+    if largest <= 0.0 || eigenvalues.min() <= largest * PARALLEL_AXES_TOLERANCE {
+        return Err(degenerate_input(
+            "the rotation axes of all motions are parallel",
+        ));
+    }
+    normal
+        .try_inverse()
+        .map(|inverse| inverse * rhs)
+        .ok_or_else(|| degenerate_input("the rotation axes of all motions are parallel"))
+}
+
+/// Averages rotations as the dominant eigenvector of the sum of quaternion outer products.
+///
+/// Unlike averaging components directly, this is insensitive to the sign of the quaternions.
+pub(crate) fn average_rotations(
+    rotations: impl IntoIterator<Item = UnitQuaternion<f64>>,
+) -> UnitQuaternion<f64> {
+    weighted_average_rotations(rotations.into_iter().map(|rotation| (rotation, 1.0)))
+}
+
+/// Like [`average_rotations`], with each outer product scaled by the weight of the rotation.
+pub(crate) fn weighted_average_rotations(
+    rotations: impl IntoIterator<Item = (UnitQuaternion<f64>, f64)>,
+) -> UnitQuaternion<f64> {
+    let accumulator = rotations
+        .into_iter()
+        .fold(Matrix4::zeros(), |sum, (rotation, weight)| {
+            sum + rotation.coords * rotation.coords.transpose() * weight
+        });
+    let eigen = SymmetricEigen::new(accumulator);
+    let dominant = eigen
+        .eigenvectors
+        .column(eigen.eigenvalues.imax())
+        .into_owned();
+    UnitQuaternion::new_normalize(Quaternion::from(dominant))
+}
+
+/// Finds the rigid transform that maps `from` onto `to` in the least-squares sense.
+///
+/// Returns the transform and the root-mean-square residual.
+pub(crate) fn fit_rigid_transform(
+    from: &[Point3<f64>],
+    to: &[Point3<f64>],
+) -> Result<(Isometry3<f64>, f64), CartesianTreeError> {
+    if from.len() != to.len() {
+        return Err(CartesianTreeError::LengthMismatch {
+            expected: from.len(),
+            found: to.len(),
+        });
+    }
+    if from.len() < 3 {
+        return Err(degenerate_input(&format!(
+            "at least 3 point pairs are required, got {}",
+            from.len()
+        )));
+    }
+
+    let from_centroid = centroid(from);
+    let to_centroid = centroid(to);
+    let covariance = from
+        .iter()
+        .zip(to)
+        .fold(Matrix3::zeros(), |sum, (from_point, to_point)| {
+            sum + (from_point - from_centroid) * (to_point - to_centroid).transpose()
+        });
+
+    let svd = covariance.svd(true, true);
+    let largest = svd.singular_values[0];
+    if largest <= 0.0 || svd.singular_values[1] <= largest * COLLINEARITY_TOLERANCE {
+        return Err(degenerate_input("points are coincident or collinear"));
+    }
+    let (Some(u), Some(v_t)) = (svd.u, svd.v_t) else {
+        return Err(degenerate_input("singular value decomposition failed"));
+    };
+    // Flip the axis of the smallest singular value if the best orthogonal fit is a reflection.
+    let mut correction = Matrix3::identity();
+    if (v_t.transpose() * u.transpose()).determinant() < 0.0 {
+        correction[(2, 2)] = -1.0;
+    }
+    let rotation = UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(
+        v_t.transpose() * correction * u.transpose(),
+    ));
+    let translation: Vector3<f64> = to_centroid - rotation * from_centroid;
+    let transform = Isometry3::from_parts(Translation3::from(translation), rotation);
+
+    #[allow(clippy::cast_precision_loss)]
+    let count = from.len() as f64;
+    let squared_error: f64 = from
+        .iter()
+        .zip(to)
+        .map(|(from_point, to_point)| (transform * from_point - to_point).norm_squared())
+        .sum();
+    Ok((transform, (squared_error / count).sqrt()))
+}
+
+fn centroid(points: &[Point3<f64>]) -> Point3<f64> {
+    #[allow(clippy::cast_precision_loss)]
+    let count = points.len() as f64;
+    Point3::from(
+        points
+            .iter()
+            .map(|point| point.coords)
+            .sum::<Vector3<f64>>()
+            / count,
+    )
+}
+
+fn degenerate_input(reason: &str) -> CartesianTreeError {
+    CartesianTreeError::DegenerateInput {
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::{HasChildren, HasParent};
+    use approx::assert_relative_eq;
+
+    fn ground_truth() -> Isometry3<f64> {
+        Isometry3::from_parts(
+            Translation3::new(0.4, -1.2, 0.75),
+            UnitQuaternion::from_euler_angles(0.3, -0.5, 2.2),
+        )
+    }
+
+    fn sample_points() -> Vec<Point3<f64>> {
+        (0..12)
+            .map(|i| {
+                let i = f64::from(i);
+                Point3::new(
+                    (1.3 * i).sin(),
+                    (0.7 * i).cos() * 0.5,
+                    0.1f64.mul_add(i, -0.4),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn recovers_transform_from_noisy_points() {
+        let root = Frame::new_origin("world").unwrap();
+        let expected = sample_points();
+        let observed: Vec<_> = expected
+            .iter()
+            .enumerate()
+            .map(|(i, point)| {
+                let i = f64::from(u32::try_from(i).unwrap());
+                let noise = Vector3::new((3.1 * i).sin(), (5.3 * i).cos(), (7.7 * i).sin()) * 1e-3;
+                ground_truth() * point + noise
+            })
+            .collect();
+
+        let (fixture, rms) = root
+            .calibrate_child_from_points("fixture", &observed, &expected)
+            .unwrap();
+
+        let fitted = fixture.transformation().unwrap();
+        assert_relative_eq!(
+            fitted.translation.vector,
+            ground_truth().translation.vector,
+            epsilon = 5e-3
+        );
+        assert!(fitted.rotation.angle_to(&ground_truth().rotation) < 5e-3);
+        assert!(rms > 0.0 && rms < 2e-3);
+    }
+
+    #[test]
+    fn exact_points_give_zero_residual() {
+        let expected = sample_points();
+        let observed: Vec<_> = expected
+            .iter()
+            .map(|point| ground_truth() * point)
+            .collect();
+
+        let (transform, rms) = fit_rigid_transform(&expected, &observed).unwrap();
+
+        assert!(transform.rotation.angle_to(&ground_truth().rotation) < 1e-12);
+        assert!(rms < 1e-12);
+    }
+
+    #[test]
+    fn calibrates_from_noisy_pose_pairs() {
+        let root = Frame::new_origin("world").unwrap();
+        let table = root
+            .add_child(
+                "table",
+                Vector3::new(0.0, 0.5, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, 0.4),
+            )
+            .unwrap();
+        let desired: Vec<_> = (0..8)
+            .map(|i| {
+                let i = f64::from(i);
+                (
+                    Vector3::new(i.sin(), (2.0 * i).cos(), 0.1 * i),
+                    Rotation::from_rpy(0.2 * i, -0.1 * i, (0.5 * i).sin()),
+                )
+            })
+            .collect();
+        let observed: Vec<_> = desired
+            .iter()
+            .enumerate()
+            .map(|(i, (position, orientation))| {
+                let i = f64::from(u32::try_from(i).unwrap());
+                let noise = Isometry3::new(
+                    Vector3::new((3.1 * i).sin(), (5.3 * i).cos(), (7.7 * i).sin()) * 1e-3,
+                    Vector3::new((1.7 * i).cos(), (2.9 * i).sin(), (4.3 * i).cos()) * 1e-3,
+                );
+                let pose = ground_truth()
+                    * Isometry3::from_parts(
+                        Translation3::from(*position),
+                        orientation.as_quaternion(),
+                    )
+                    * noise;
+                root.add_pose(pose.translation.vector, pose.rotation)
+                    .unwrap()
+            })
+            .collect();
+
+        let (fixture, report) = table
+            .calibrate_child_from_poses("fixture", &desired, &observed)
+            .unwrap();
+
+        let fitted = fixture.transform_to(&root).unwrap();
+        assert_relative_eq!(
+            fitted.translation.vector,
+            ground_truth().translation.vector,
+            epsilon = 5e-3
+        );
+        assert!(fitted.rotation.angle_to(&ground_truth().rotation) < 5e-3);
+        assert_eq!(report.residuals.len(), 8);
+        assert!(report.max_translation_error < 1e-2);
+        assert!(report.max_rotation_error < 1e-2);
+        assert!(report.mean_translation_error <= report.max_translation_error);
+        assert!(report.mean_rotation_error <= report.max_rotation_error);
+    }
+
+    #[test]
+    fn report_flags_bad_sample() {
+        let root = Frame::new_origin("world").unwrap();
+        let desired = vec![(Vector3::zeros(), Rotation::identity()); 5];
+        let mut observed = vec![
+            root.add_pose(Vector3::new(1.0, 0.0, 0.0), Rotation::identity())
+                .unwrap();
+            5
+        ];
+        observed[3] = root
+            .add_pose(Vector3::new(1.5, 0.0, 0.0), Rotation::identity())
+            .unwrap();
+
+        let (_, report) = root
+            .calibrate_child_from_poses("fixture", &desired, &observed)
+            .unwrap();
+
+        let worst = (0..5)
+            .max_by(|&a, &b| {
+                report.residuals[a]
+                    .translation
+                    .total_cmp(&report.residuals[b].translation)
+            })
+            .unwrap();
+        assert_eq!(worst, 3);
+        assert_relative_eq!(report.max_translation_error, 0.4, epsilon = 1e-12);
+        assert_relative_eq!(report.mean_translation_error, 0.16, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn averages_rotations_regardless_of_sign() {
+        let a = UnitQuaternion::from_euler_angles(0.0, 0.0, 0.2);
+        let b = UnitQuaternion::from_euler_angles(0.0, 0.0, 0.4);
+        let negated_b = UnitQuaternion::new_unchecked(-b.into_inner());
+
+        let average = average_rotations([a, negated_b]);
+
+        assert!(average.angle_to(&UnitQuaternion::from_euler_angles(0.0, 0.0, 0.3)) < 1e-12);
+    }
+
+    #[test]
+    fn rejects_invalid_pose_pairs() {
+        let root = Frame::new_origin("world").unwrap();
+        let pose = root
+            .add_pose(Vector3::zeros(), Rotation::identity())
+            .unwrap();
+
+        assert!(matches!(
+            root.calibrate_child_from_poses("a", &[], &[]),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+        assert!(matches!(
+            root.calibrate_child_from_poses("a", &[], &[pose]),
+            Err(CartesianTreeError::LengthMismatch {
+                expected: 0,
+                found: 1
+            })
+        ));
+        assert!(root.children().is_empty());
+    }
+
+    #[test]
+    #[allow(clippy::similar_names)]
+    fn frame_from_three_points() {
+        let root = Frame::new_origin("world").unwrap();
+        let origin = Point3::new(0.5, -1.0, 2.0);
+        let x_point = Point3::new(1.5, 0.0, 2.0);
+        let xy_point = Point3::new(0.0, 3.0, 2.5);
+
+        let fixture = root
+            .add_child_from_three_points("fixture", origin, x_point, xy_point)
+            .unwrap();
+
+        let to_fixture = |point| root.transform_point_to(&fixture, &point).unwrap();
+        assert_relative_eq!(to_fixture(origin), Point3::origin(), epsilon = 1e-12);
+        let x = to_fixture(x_point);
+        assert_relative_eq!(x, Point3::new(2.0_f64.sqrt(), 0.0, 0.0), epsilon = 1e-12);
+        let xy = to_fixture(xy_point);
+        assert!(xy.y > 0.0);
+        assert!(xy.z.abs() < 1e-12);
+        let rotation = fixture.transformation().unwrap().rotation;
+        assert_relative_eq!(
+            (rotation * Vector3::x()).cross(&(rotation * Vector3::y())),
+            rotation * Vector3::z(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn three_points_reject_degenerate_input() {
+        let root = Frame::new_origin("world").unwrap();
+        let origin = Point3::new(1.0, 1.0, 1.0);
+
+        for (x_point, xy_point) in [
+            (origin, Point3::new(0.0, 1.0, 0.0)),
+            (Point3::new(2.0, 2.0, 2.0), Point3::new(3.0, 3.0, 3.0)),
+            (Point3::new(2.0, 2.0, 2.0), origin),
+        ] {
+            assert!(matches!(
+                root.add_child_from_three_points("fixture", origin, x_point, xy_point),
+                Err(CartesianTreeError::DegenerateInput { .. })
+            ));
+        }
+        assert!(root.children().is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_point_sets() {
+        let root = Frame::new_origin("world").unwrap();
+        let points = sample_points();
+
+        assert!(matches!(
+            root.calibrate_child_from_points("a", &points[..4], &points[..5]),
+            Err(CartesianTreeError::LengthMismatch {
+                expected: 5,
+                found: 4
+            })
+        ));
+        assert!(matches!(
+            root.calibrate_child_from_points("a", &points[..2], &points[..2]),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+        let collinear: Vec<_> = (0..5)
+            .map(|i| Point3::new(f64::from(i), 2.0 * f64::from(i), 0.0))
+            .collect();
+        assert!(matches!(
+            root.calibrate_child_from_points("a", &collinear, &collinear),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+        let coincident = vec![Point3::new(1.0, 1.0, 1.0); 4];
+        assert!(matches!(
+            root.calibrate_child_from_points("a", &coincident, &coincident),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+        assert!(root.children().is_empty());
+    }
+
+    /// Probed points on the XY plane of [`ground_truth`], with a small out-of-plane noise and
+    /// one outlier at index 5.
+    fn plane_points() -> Vec<Point3<f64>> {
+        (0..12)
+            .map(|index| {
+                let i = f64::from(index);
+                let height = if index == 5 {
+                    0.02
+                } else {
+                    (2.7 * i).sin() * 1e-4
+                };
+                ground_truth() * Point3::new((1.3 * i).sin(), (0.9 * i).cos() * 0.5, height)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fits_frame_to_plane() {
+        let root = Frame::new_origin("world").unwrap();
+        let points = plane_points();
+        let truth = ground_truth().rotation;
+        let hint = truth * Vector3::x() + truth * Vector3::z() * 0.3;
+
+        let (table, report) = root
+            .add_child_from_plane_fit("table", &points, Some(hint))
+            .unwrap();
+
+        assert_relative_eq!(table.position(), centroid(&points).coords, epsilon = 1e-12);
+        let fitted = table.transformation().unwrap().rotation;
+        // The origin of `world` lies on the negative Z side of the ground truth plane.
+        assert!((fitted * Vector3::z()).angle(&-(truth * Vector3::z())) < 5e-3);
+        assert!((fitted * Vector3::x()).angle(&(truth * Vector3::x())) < 5e-3);
+        assert_eq!(report.residuals.len(), 12);
+        let outlier = (0..12)
+            .max_by(|&a, &b| {
+                report.residuals[a]
+                    .abs()
+                    .total_cmp(&report.residuals[b].abs())
+            })
+            .unwrap();
+        assert_eq!(outlier, 5);
+        assert_relative_eq!(report.max_error, report.residuals[5].abs());
+        assert!(report.rms_error < report.max_error);
+        assert_relative_eq!(report.residuals.iter().sum::<f64>(), 0.0, epsilon = 1e-12);
+
+        // Without a hint, X follows the direction in which the points spread the most.
+        let (flat, _) = root
+            .add_child_from_plane_fit(
+                "flat",
+                &[
+                    Point3::new(0.0, 0.0, 2.0),
+                    Point3::new(0.0, 4.0, 2.0),
+                    Point3::new(1.0, 0.0, 2.0),
+                    Point3::new(1.0, 4.0, 2.0),
+                ],
+                None,
+            )
+            .unwrap();
+        let rotation = flat.transformation().unwrap().rotation;
+        assert_relative_eq!((rotation * Vector3::x()).y.abs(), 1.0, epsilon = 1e-12);
+        assert_relative_eq!(rotation * Vector3::z(), -Vector3::z(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn plane_fit_rejects_degenerate_input() {
+        let root = Frame::new_origin("world").unwrap();
+        let points = plane_points();
+        assert!(matches!(
+            root.add_child_from_plane_fit("a", &points[..2], None),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+        let collinear: Vec<_> = (0..5)
+            .map(|i| Point3::new(f64::from(i), 2.0 * f64::from(i), 1.0))
+            .collect();
+        assert!(matches!(
+            root.add_child_from_plane_fit("a", &collinear, None),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+        let square = [
+            Point3::new(0.0, 0.0, 1.0),
+            Point3::new(1.0, 0.0, 1.0),
+            Point3::new(0.0, 1.0, 1.0),
+        ];
+        for hint in [Vector3::z(), Vector3::zeros()] {
+            assert!(matches!(
+                root.add_child_from_plane_fit("a", &square, Some(hint)),
+                Err(CartesianTreeError::DegenerateInput { .. })
+            ));
+        }
+        assert!(root.children().is_empty());
+    }
+
+    /// Robot stations: flange poses in the base and the noisy target observations of a camera
+    /// mounted at [`ground_truth`] on the flange.
+    fn hand_eye_stations() -> (Vec<Isometry3<f64>>, Vec<Isometry3<f64>>) {
+        let target = Isometry3::from_parts(
+            Translation3::new(1.5, 0.2, -0.3),
+            UnitQuaternion::from_euler_angles(0.1, 0.2, -0.4),
+        );
+        (0..10)
+            .map(|i| {
+                let i = f64::from(i);
+                let flange = Isometry3::from_parts(
+                    Translation3::new((0.9 * i).sin(), (1.7 * i).cos(), 0.1 * i),
+                    UnitQuaternion::from_euler_angles(
+                        (1.1 * i).sin() * 0.8,
+                        (2.3 * i).cos() * 0.6,
+                        0.4 * i,
+                    ),
+                );
+                let noise = Isometry3::new(
+                    Vector3::new((3.1 * i).sin(), (5.3 * i).cos(), (7.7 * i).sin()) * 1e-4,
+                    Vector3::new((4.3 * i).cos(), (6.1 * i).sin(), (2.9 * i).cos()) * 1e-4,
+                );
+                let observed = noise * (flange * ground_truth()).inverse() * target;
+                (flange, observed)
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn solves_hand_eye_from_noisy_motions() {
+        let (flange, observed) = hand_eye_stations();
+        let a: Vec<_> = flange.windows(2).map(|w| w[1].inverse() * w[0]).collect();
+        let b: Vec<_> = observed.windows(2).map(|w| w[1] * w[0].inverse()).collect();
+
+        let (x, report) = solve_hand_eye(&a, &b).unwrap();
+
+        assert_relative_eq!(
+            x.translation.vector,
+            ground_truth().translation.vector,
+            epsilon = 5e-3
+        );
+        assert!(x.rotation.angle_to(&ground_truth().rotation) < 5e-3);
+        assert_eq!(report.residuals.len(), 9);
+        assert!(report.max_translation_error < 5e-3);
+        assert!(report.max_rotation_error < 5e-3);
+
+        // Noise-free motions are solved exactly.
+        let b: Vec<_> = a
+            .iter()
+            .map(|a| ground_truth().inverse() * a * ground_truth())
+            .collect();
+        let (x, report) = solve_hand_eye(&a, &b).unwrap();
+        assert_relative_eq!(x, ground_truth(), epsilon = 1e-9);
+        assert!(report.max_translation_error < 1e-9);
+    }
+
+    #[test]
+    fn calibrates_camera_under_flange() {
+        let root = Frame::new_origin("world").unwrap();
+        let base = root
+            .add_child(
+                "base",
+                Vector3::new(0.5, 0.0, 0.0),
+                Rotation::from_rpy(0.0, 0.0, 0.7),
+            )
+            .unwrap();
+        let flange = base
+            .add_child("flange", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let (stations, observed) = hand_eye_stations();
+        let poses: Vec<_> = stations
+            .iter()
+            .map(|station| {
+                base.add_pose(station.translation.vector, station.rotation)
+                    .unwrap()
+            })
+            .collect();
+
+        let (camera, report) = flange
+            .calibrate_child_hand_eye("camera", &poses, &observed)
+            .unwrap();
+
+        assert_eq!(camera.parent().unwrap().name(), "flange");
+        let fitted = camera.transformation().unwrap();
+        assert_relative_eq!(
+            fitted.translation.vector,
+            ground_truth().translation.vector,
+            epsilon = 5e-3
+        );
+        assert!(fitted.rotation.angle_to(&ground_truth().rotation) < 5e-3);
+        assert!(report.mean_rotation_error < 5e-3);
+
+        assert!(matches!(
+            flange.calibrate_child_hand_eye("other", &poses[..3], &observed[..4]),
+            Err(CartesianTreeError::LengthMismatch {
+                expected: 3,
+                found: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_degenerate_hand_eye_motions() {
+        let turn = Isometry3::new(Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.5));
+        let tilt = Isometry3::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.4, 0.0, 0.0));
+        let shift = Isometry3::translation(0.0, 0.0, 1.0);
+
+        assert!(matches!(
+            solve_hand_eye(&[turn], &[turn]),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+        assert!(matches!(
+            solve_hand_eye(&[turn, tilt], &[turn]),
+            Err(CartesianTreeError::LengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        ));
+        match solve_hand_eye(&[turn, shift], &[turn, shift]) {
+            Err(CartesianTreeError::DegenerateInput { reason }) => {
+                assert!(reason.contains("pure translation"));
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+        // Rotations about a single axis leave the solution underdetermined.
+        let twist = Isometry3::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, -0.9));
+        assert!(matches!(
+            solve_hand_eye(&[turn, twist], &[turn, twist]),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+    }
+}
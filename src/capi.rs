@@ -0,0 +1,546 @@
+//! C interface to frames and poses, enabled by the `capi` feature.
+//!
+//! The declarations are in `include/cartesian_tree.h`, generated from this module by
+//! `cbindgen` (`just header`). Every function returns a [`CtStatus`]; on failure,
+//! [`ct_last_error_message`] describes what went wrong. Panics never cross the boundary:
+//! they are caught and reported as [`CtStatus::Panic`].
+//!
+//! Positions are passed as 3 doubles `[x, y, z]`, orientations as quaternions of 4 doubles
+//! `[x, y, z, w]`, in arrays provided by the caller.
+//!
+//! # Ownership
+//! - Handles written to `out` parameters belong to the caller, who releases each exactly once
+//!   with [`ct_frame_free`] or [`ct_pose_free`]. Releasing `NULL` does nothing.
+//! - A frame handle keeps its whole tree alive, as for [`Frame`]; the tree is freed once every
+//!   handle to its frames is released.
+//! - A pose handle does not keep its frame alive. Once the tree of its frame is freed, it
+//!   reports [`CtStatus::TreeError`] from conversions.
+//! - Strings written to `out` parameters belong to the caller and are released with
+//!   [`ct_string_free`].
+//! - The string returned by [`ct_last_error_message`] belongs to the library and stays
+//!   valid until the next failing call on the same thread.
+//!
+//! Handles are not thread-safe: a tree and its poses must only be used from one thread.
+
+use crate::rotation::Rotation;
+use crate::{CartesianTreeError, Frame, Pose};
+use nalgebra::{Isometry3, Vector3};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// The result of a call through the C interface.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CtStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was `NULL`.
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8, or a returned string contained a NUL byte.
+    InvalidString = 2,
+    /// The operation failed, e.g. because of a duplicate frame name or a dropped frame.
+    TreeError = 3,
+    /// The library panicked. The handles involved should not be used any further.
+    Panic = 4,
+}
+
+/// An opaque handle to a [`Frame`].
+pub struct CtFrame(Frame);
+
+/// An opaque handle to a [`Pose`].
+pub struct CtPose(Pose);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Why a call failed, before it is recorded as the last error.
+struct Failure {
+    status: CtStatus,
+    message: String,
+}
+
+impl Failure {
+    fn new(status: CtStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<CartesianTreeError> for Failure {
+    fn from(error: CartesianTreeError) -> Self {
+        Self::new(CtStatus::TreeError, error.to_string())
+    }
+}
+
+/// Runs `body`, catching panics and recording failures as the last error of this thread.
+fn guard(body: impl FnOnce() -> Result<(), Failure>) -> CtStatus {
+    let result = panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| (*message).to_owned())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_owned());
+        Err(Failure::new(CtStatus::Panic, format!("panic: {message}")))
+    });
+    match result {
+        Ok(()) => CtStatus::Ok,
+        Err(failure) => {
+            // Messages built from valid frame names cannot contain NUL bytes; stay safe anyway.
+            let message =
+                CString::new(failure.message.replace('\0', "\u{fffd}")).unwrap_or_default();
+            LAST_ERROR.with_borrow_mut(|last| *last = Some(message));
+            failure.status
+        }
+    }
+}
+
+/// Returns the referenced value, or an error naming `argument` if `pointer` is `NULL`.
+///
+/// # Safety
+/// `pointer` must be `NULL` or valid for reads for the lifetime `'a`.
+unsafe fn deref<'a, V>(pointer: *const V, argument: &str) -> Result<&'a V, Failure> {
+    // SAFETY: guaranteed by the caller.
+    unsafe { pointer.as_ref() }
+        .ok_or_else(|| Failure::new(CtStatus::NullPointer, format!("'{argument}' is NULL")))
+}
+
+/// Reads a NUL-terminated UTF-8 string.
+///
+/// # Safety
+/// `pointer` must be `NULL` or point to a NUL-terminated string valid for the lifetime `'a`.
+unsafe fn read_str<'a>(pointer: *const c_char, argument: &str) -> Result<&'a str, Failure> {
+    if pointer.is_null() {
+        return Err(Failure::new(
+            CtStatus::NullPointer,
+            format!("'{argument}' is NULL"),
+        ));
+    }
+    // SAFETY: guaranteed by the caller.
+    unsafe { CStr::from_ptr(pointer) }.to_str().map_err(|_| {
+        Failure::new(
+            CtStatus::InvalidString,
+            format!("'{argument}' is not valid UTF-8"),
+        )
+    })
+}
+
+/// Reads `N` doubles.
+///
+/// # Safety
+/// `pointer` must be `NULL` or valid for reads of `N` doubles.
+unsafe fn read_array<const N: usize>(
+    pointer: *const f64,
+    argument: &str,
+) -> Result<[f64; N], Failure> {
+    // SAFETY: guaranteed by the caller.
+    unsafe { deref(pointer.cast::<[f64; N]>(), argument) }.copied()
+}
+
+/// Writes `value` to `out`.
+///
+/// # Safety
+/// `out` must be `NULL` or valid for writes.
+unsafe fn write<V>(out: *mut V, value: V, argument: &str) -> Result<(), Failure> {
+    if out.is_null() {
+        return Err(Failure::new(
+            CtStatus::NullPointer,
+            format!("'{argument}' is NULL"),
+        ));
+    }
+    // SAFETY: guaranteed by the caller.
+    unsafe { out.write(value) };
+    Ok(())
+}
+
+/// Reads a position and an orientation given as `[x, y, z, w]`.
+///
+/// # Safety
+/// As for [`read_array`].
+unsafe fn read_transform(
+    position: *const f64,
+    orientation: *const f64,
+) -> Result<(Vector3<f64>, Rotation), Failure> {
+    // SAFETY: guaranteed by the caller.
+    let position = unsafe { read_array::<3>(position, "position") }?;
+    // SAFETY: guaranteed by the caller.
+    let orientation = unsafe { read_array::<4>(orientation, "orientation") }?;
+    Ok((Vector3::from(position), Rotation::from(orientation)))
+}
+
+/// Writes the position and the orientation, as `[x, y, z, w]`, of `transform`.
+///
+/// # Safety
+/// As for [`write`], for 3 and 4 doubles.
+unsafe fn write_transform(
+    transform: &Isometry3<f64>,
+    position: *mut f64,
+    orientation: *mut f64,
+) -> Result<(), Failure> {
+    let translation: [f64; 3] = transform.translation.vector.into();
+    let rotation: [f64; 4] = transform.rotation.coords.into();
+    // SAFETY: guaranteed by the caller.
+    unsafe { write(position.cast::<[f64; 3]>(), translation, "position") }?;
+    // SAFETY: guaranteed by the caller.
+    unsafe { write(orientation.cast::<[f64; 4]>(), rotation, "orientation") }
+}
+
+/// Returns a description of the last failed call on this thread, or `NULL` if none failed.
+///
+/// The string belongs to the library and stays valid until the next failing call on this
+/// thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn ct_last_error_message() -> *const c_char {
+    LAST_ERROR.with_borrow(|last| {
+        last.as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Creates the root frame of a new tree, see [`Frame::new_origin`].
+///
+/// # Safety
+/// `name` must be a NUL-terminated string and `out` valid for writes, or `NULL`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_frame_new_origin(
+    name: *const c_char,
+    out: *mut *mut CtFrame,
+) -> CtStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let name = unsafe { read_str(name, "name") }?;
+        let frame = Frame::new_origin(name)?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { write(out, Box::into_raw(Box::new(CtFrame(frame))), "out") }
+    })
+}
+
+/// Adds a child to `parent`, see [`Frame::add_child`].
+///
+/// # Safety
+/// `parent` must be a live handle, `name` a NUL-terminated string, `position` and
+/// `orientation` arrays of 3 and 4 doubles and `out` valid for writes, or `NULL`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_frame_add_child(
+    parent: *const CtFrame,
+    name: *const c_char,
+    position: *const f64,
+    orientation: *const f64,
+    out: *mut *mut CtFrame,
+) -> CtStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let parent = unsafe { deref(parent, "parent") }?;
+        // SAFETY: guaranteed by the caller.
+        let name = unsafe { read_str(name, "name") }?;
+        // SAFETY: guaranteed by the caller.
+        let (position, orientation) = unsafe { read_transform(position, orientation) }?;
+        let child = parent.0.add_child(name, position, orientation)?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { write(out, Box::into_raw(Box::new(CtFrame(child))), "out") }
+    })
+}
+
+/// Writes a copy of the name of `frame` to `out`, to be released with [`ct_string_free`].
+///
+/// # Safety
+/// `frame` must be a live handle and `out` valid for writes, or `NULL`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_frame_name(frame: *const CtFrame, out: *mut *mut c_char) -> CtStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let frame = unsafe { deref(frame, "frame") }?;
+        let name = CString::new(frame.0.name()).map_err(|_| {
+            Failure::new(
+                CtStatus::InvalidString,
+                "the frame name contains a NUL byte",
+            )
+        })?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { write(out, name.into_raw(), "out") }
+    })
+}
+
+/// Moves `frame` relative to its parent, see [`Frame::set`].
+///
+/// # Safety
+/// `frame` must be a live handle and `position` and `orientation` arrays of 3 and 4 doubles,
+/// or `NULL`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_frame_set(
+    frame: *const CtFrame,
+    position: *const f64,
+    orientation: *const f64,
+) -> CtStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let frame = unsafe { deref(frame, "frame") }?;
+        // SAFETY: guaranteed by the caller.
+        let (position, orientation) = unsafe { read_transform(position, orientation) }?;
+        Ok(frame.0.set(position, orientation)?)
+    })
+}
+
+/// Writes the transform from `frame` to the root of its tree, see [`Frame::world_transform`].
+///
+/// # Safety
+/// `frame` must be a live handle and `position` and `orientation` writable arrays of 3 and 4
+/// doubles, or `NULL`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_frame_world_transform(
+    frame: *const CtFrame,
+    position: *mut f64,
+    orientation: *mut f64,
+) -> CtStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let frame = unsafe { deref(frame, "frame") }?;
+        let transform = frame.0.world_transform()?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { write_transform(&transform, position, orientation) }
+    })
+}
+
+/// Writes the transform from `frame` to `target`, see [`Frame::transform_to`].
+///
+/// # Safety
+/// `frame` and `target` must be live handles and `position` and `orientation` writable arrays
+/// of 3 and 4 doubles, or `NULL`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_frame_transform_to(
+    frame: *const CtFrame,
+    target: *const CtFrame,
+    position: *mut f64,
+    orientation: *mut f64,
+) -> CtStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let frame = unsafe { deref(frame, "frame") }?;
+        // SAFETY: guaranteed by the caller.
+        let target = unsafe { deref(target, "target") }?;
+        let transform = frame.0.transform_to(&target.0)?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { write_transform(&transform, position, orientation) }
+    })
+}
+
+/// Releases a frame handle. The tree lives on while handles to any of its frames are left.
+///
+/// # Safety
+/// `frame` must be `NULL` or a handle not released before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_frame_free(frame: *mut CtFrame) {
+    if !frame.is_null() {
+        // SAFETY: guaranteed by the caller; dropping a frame does not panic.
+        drop(unsafe { Box::from_raw(frame) });
+    }
+}
+
+/// Adds a pose to `frame`, see [`Frame::add_pose`].
+///
+/// # Safety
+/// `frame` must be a live handle, `position` and `orientation` arrays of 3 and 4 doubles and
+/// `out` valid for writes, or `NULL`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_frame_add_pose(
+    frame: *const CtFrame,
+    position: *const f64,
+    orientation: *const f64,
+    out: *mut *mut CtPose,
+) -> CtStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let frame = unsafe { deref(frame, "frame") }?;
+        // SAFETY: guaranteed by the caller.
+        let (position, orientation) = unsafe { read_transform(position, orientation) }?;
+        let pose = frame.0.add_pose(position, orientation)?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { write(out, Box::into_raw(Box::new(CtPose(pose))), "out") }
+    })
+}
+
+/// Expresses `pose` in `target` as a new pose, see [`Pose::in_frame`].
+///
+/// # Safety
+/// `pose` and `target` must be live handles and `out` valid for writes, or `NULL`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_pose_in_frame(
+    pose: *const CtPose,
+    target: *const CtFrame,
+    out: *mut *mut CtPose,
+) -> CtStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let pose = unsafe { deref(pose, "pose") }?;
+        // SAFETY: guaranteed by the caller.
+        let target = unsafe { deref(target, "target") }?;
+        let converted = pose.0.in_frame(&target.0)?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { write(out, Box::into_raw(Box::new(CtPose(converted))), "out") }
+    })
+}
+
+/// Writes the transform from `pose` to its frame, see [`Pose::transformation`].
+///
+/// # Safety
+/// `pose` must be a live handle and `position` and `orientation` writable arrays of 3 and 4
+/// doubles, or `NULL`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_pose_transformation(
+    pose: *const CtPose,
+    position: *mut f64,
+    orientation: *mut f64,
+) -> CtStatus {
+    guard(|| {
+        // SAFETY: guaranteed by the caller.
+        let pose = unsafe { deref(pose, "pose") }?;
+        // SAFETY: guaranteed by the caller.
+        unsafe { write_transform(&pose.0.transformation(), position, orientation) }
+    })
+}
+
+/// Releases a pose handle.
+///
+/// # Safety
+/// `pose` must be `NULL` or a handle not released before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_pose_free(pose: *mut CtPose) {
+    if !pose.is_null() {
+        // SAFETY: guaranteed by the caller.
+        drop(unsafe { Box::from_raw(pose) });
+    }
+}
+
+/// Releases a string returned by the library, e.g. from [`ct_frame_name`].
+///
+/// # Safety
+/// `string` must be `NULL` or a string returned by the library and not released before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ct_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: guaranteed by the caller.
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn last_error() -> String {
+        let message = ct_last_error_message();
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn frames_and_poses_round_trip_through_handles() {
+        unsafe {
+            let mut root = ptr::null_mut();
+            assert_eq!(
+                ct_frame_new_origin(c"world".as_ptr(), &raw mut root),
+                CtStatus::Ok
+            );
+            let mut arm = ptr::null_mut();
+            let position = [1.0, 0.0, 0.0];
+            let quarter_turn = [0.0, 0.0, 0.5_f64.sqrt(), 0.5_f64.sqrt()];
+            assert_eq!(
+                ct_frame_add_child(
+                    root,
+                    c"arm".as_ptr(),
+                    position.as_ptr(),
+                    quarter_turn.as_ptr(),
+                    &raw mut arm
+                ),
+                CtStatus::Ok
+            );
+
+            let mut pose = ptr::null_mut();
+            let identity = [0.0, 0.0, 0.0, 1.0];
+            assert_eq!(
+                ct_frame_add_pose(arm, position.as_ptr(), identity.as_ptr(), &raw mut pose),
+                CtStatus::Ok
+            );
+            let mut in_root = ptr::null_mut();
+            assert_eq!(ct_pose_in_frame(pose, root, &raw mut in_root), CtStatus::Ok);
+            let mut out_position = [0.0; 3];
+            let mut out_orientation = [0.0; 4];
+            assert_eq!(
+                ct_pose_transformation(
+                    in_root,
+                    out_position.as_mut_ptr(),
+                    out_orientation.as_mut_ptr()
+                ),
+                CtStatus::Ok
+            );
+            assert_relative_eq!(
+                out_position.as_slice(),
+                [1.0, 1.0, 0.0].as_slice(),
+                epsilon = 1e-12
+            );
+            assert_relative_eq!(
+                out_orientation.as_slice(),
+                quarter_turn.as_slice(),
+                epsilon = 1e-12
+            );
+
+            let mut name = ptr::null_mut();
+            assert_eq!(ct_frame_name(arm, &raw mut name), CtStatus::Ok);
+            assert_eq!(CStr::from_ptr(name), c"arm");
+            ct_string_free(name);
+
+            ct_pose_free(in_root);
+            ct_pose_free(pose);
+            ct_frame_free(arm);
+            ct_frame_free(root);
+        }
+    }
+
+    #[test]
+    fn failures_report_a_status_and_a_message() {
+        unsafe {
+            let mut root = ptr::null_mut();
+            assert_eq!(
+                ct_frame_new_origin(ptr::null(), &raw mut root),
+                CtStatus::NullPointer
+            );
+            assert_eq!(last_error(), "'name' is NULL");
+            assert_eq!(
+                ct_frame_new_origin(c"\xff".as_ptr(), &raw mut root),
+                CtStatus::InvalidString
+            );
+            assert!(root.is_null());
+
+            assert_eq!(
+                ct_frame_new_origin(c"world".as_ptr(), &raw mut root),
+                CtStatus::Ok
+            );
+            let mut position = [0.0; 3];
+            let orientation = [0.0; 4];
+            assert_eq!(
+                ct_frame_set(root, position.as_ptr(), orientation.as_ptr()),
+                CtStatus::TreeError
+            );
+            assert!(last_error().contains("world"));
+            assert_eq!(
+                ct_frame_world_transform(root, position.as_mut_ptr(), ptr::null_mut()),
+                CtStatus::NullPointer
+            );
+            ct_frame_free(root);
+            ct_frame_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn panics_are_caught_at_the_boundary() {
+        assert_eq!(guard(|| panic!("boom")), CtStatus::Panic);
+        assert_eq!(last_error(), "panic: boom");
+    }
+}
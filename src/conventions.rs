@@ -0,0 +1,419 @@
+//! Conversions between the axis conventions of different ecosystems.
+//!
+//! An [`AxisConvention`] names the axes that point up and forward, and whether the coordinate
+//! system is right- or left-handed. Together these fix the remaining axis. Transforms are
+//! converted by re-expressing both their translation and their rotation in the new axes, which
+//! also handles conversions between left- and right-handed systems.
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::rotation::Rotation;
+use nalgebra::{Isometry3, Matrix3, Rotation3, Translation3, UnitQuaternion, Vector3};
+
+/// The orientation of a coordinate system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Handedness {
+    /// `x × y = z` holds for the physical directions of the axes.
+    Right,
+    /// `x × y = -z` holds for the physical directions of the axes.
+    Left,
+}
+
+/// A signed coordinate axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// The positive x axis.
+    PosX,
+    /// The negative x axis.
+    NegX,
+    /// The positive y axis.
+    PosY,
+    /// The negative y axis.
+    NegY,
+    /// The positive z axis.
+    PosZ,
+    /// The negative z axis.
+    NegZ,
+}
+
+impl Axis {
+    /// Returns the unit vector along this axis.
+    #[must_use]
+    pub fn vector(self) -> Vector3<f64> {
+        match self {
+            Self::PosX => Vector3::x(),
+            Self::NegX => -Vector3::x(),
+            Self::PosY => Vector3::y(),
+            Self::NegY => -Vector3::y(),
+            Self::PosZ => Vector3::z(),
+            Self::NegZ => -Vector3::z(),
+        }
+    }
+}
+
+/// Describes how the axes of a coordinate system map to physical directions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AxisConvention {
+    handedness: Handedness,
+    up: Axis,
+    forward: Axis,
+}
+
+/// ROS (REP 103): right-handed, x forward, y left, z up.
+pub const ROS: AxisConvention = AxisConvention {
+    handedness: Handedness::Right,
+    up: Axis::PosZ,
+    forward: Axis::PosX,
+};
+
+/// Unity: left-handed, x right, y up, z forward.
+pub const UNITY: AxisConvention = AxisConvention {
+    handedness: Handedness::Left,
+    up: Axis::PosY,
+    forward: Axis::PosZ,
+};
+
+/// `OpenCV` camera coordinates: right-handed, x right, y down, z forward along the optical axis.
+pub const OPENCV_CAMERA: AxisConvention = AxisConvention {
+    handedness: Handedness::Right,
+    up: Axis::NegY,
+    forward: Axis::PosZ,
+};
+
+/// `OpenGL` camera coordinates: right-handed, x right, y up, looking along -z.
+pub const OPENGL: AxisConvention = AxisConvention {
+    handedness: Handedness::Right,
+    up: Axis::PosY,
+    forward: Axis::NegZ,
+};
+
+impl AxisConvention {
+    /// Creates a convention from its handedness and the axes pointing up and forward.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if `up` and `forward` lie on the same axis.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::conventions::{Axis, AxisConvention, Handedness, ROS};
+    ///
+    /// let ros = AxisConvention::new(Handedness::Right, Axis::PosZ, Axis::PosX).unwrap();
+    /// assert_eq!(ros, ROS);
+    /// assert!(AxisConvention::new(Handedness::Right, Axis::PosZ, Axis::NegZ).is_err());
+    /// ```
+    pub fn new(
+        handedness: Handedness,
+        up: Axis,
+        forward: Axis,
+    ) -> Result<Self, CartesianTreeError> {
+        if up.vector().cross(&forward.vector()) == Vector3::zeros() {
+            return Err(CartesianTreeError::DegenerateInput {
+                reason: format!("the up axis {up:?} and forward axis {forward:?} are parallel"),
+            });
+        }
+        Ok(Self {
+            handedness,
+            up,
+            forward,
+        })
+    }
+
+    /// Returns the handedness of the convention.
+    #[must_use]
+    pub const fn handedness(&self) -> Handedness {
+        self.handedness
+    }
+
+    /// Returns the axis pointing up.
+    #[must_use]
+    pub const fn up(&self) -> Axis {
+        self.up
+    }
+
+    /// Returns the axis pointing forward.
+    #[must_use]
+    pub const fn forward(&self) -> Axis {
+        self.forward
+    }
+
+    /// Returns the matrix mapping coordinates in this convention to (forward, left, up)
+    /// coordinates, i.e. to [`ROS`].
+    fn to_forward_left_up(self) -> Matrix3<f64> {
+        let forward = self.forward.vector();
+        let up = self.up.vector();
+        // The cross product is evaluated on coordinates, so it points the other way in a
+        // left-handed system.
+        let left = match self.handedness {
+            Handedness::Right => up.cross(&forward),
+            Handedness::Left => forward.cross(&up),
+        };
+        Matrix3::from_rows(&[forward.transpose(), left.transpose(), up.transpose()])
+    }
+}
+
+/// Returns the matrix mapping coordinates in `from` to coordinates in `to`.
+fn change_of_basis(from: AxisConvention, to: AxisConvention) -> Matrix3<f64> {
+    to.to_forward_left_up().transpose() * from.to_forward_left_up()
+}
+
+/// Re-expresses a position or direction given in `from` in the axes of `to`.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::conventions::{ROS, UNITY, convert_position};
+/// use nalgebra::Vector3;
+///
+/// // One unit right, two up and three forward in Unity.
+/// let position = convert_position(&Vector3::new(1.0, 2.0, 3.0), UNITY, ROS);
+/// assert_eq!(position, Vector3::new(3.0, -1.0, 2.0));
+/// ```
+#[must_use]
+pub fn convert_position(
+    position: &Vector3<f64>,
+    from: AxisConvention,
+    to: AxisConvention,
+) -> Vector3<f64> {
+    change_of_basis(from, to) * position
+}
+
+/// Re-expresses a rotation given in `from` in the axes of `to`.
+///
+/// The rotation is conjugated with the change of basis, so a conversion between left- and
+/// right-handed systems also reverses its sense of rotation.
+#[must_use]
+pub fn convert_rotation(
+    rotation: &UnitQuaternion<f64>,
+    from: AxisConvention,
+    to: AxisConvention,
+) -> UnitQuaternion<f64> {
+    let basis = change_of_basis(from, to);
+    let matrix = basis * rotation.to_rotation_matrix().matrix() * basis.transpose();
+    UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(matrix))
+}
+
+/// Re-expresses an isometry given in `from` in the axes of `to`.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::conventions::{ROS, UNITY, convert_isometry};
+/// use nalgebra::{Isometry3, Translation3, UnitQuaternion, Vector3};
+///
+/// // Unity turns clockwise (seen from above) for positive angles about its up axis.
+/// let turned_right = Isometry3::from_parts(
+///     Translation3::new(0.0, 0.0, 1.0),
+///     UnitQuaternion::from_axis_angle(&Vector3::y_axis(), std::f64::consts::FRAC_PI_2),
+/// );
+/// let in_ros = convert_isometry(&turned_right, UNITY, ROS);
+/// assert_eq!(in_ros.translation.vector, Vector3::new(1.0, 0.0, 0.0));
+/// assert!((in_ros.rotation.euler_angles().2 + std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+/// ```
+#[must_use]
+pub fn convert_isometry(
+    isometry: &Isometry3<f64>,
+    from: AxisConvention,
+    to: AxisConvention,
+) -> Isometry3<f64> {
+    Isometry3::from_parts(
+        Translation3::from(convert_position(&isometry.translation.vector, from, to)),
+        convert_rotation(&isometry.rotation, from, to),
+    )
+}
+
+impl Frame {
+    /// Adds a new child frame from a transform given in another axis convention.
+    ///
+    /// The position and orientation are converted from `from` into `to`, the convention this
+    /// tree uses, before the child is added as with [`Frame::add_child`].
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new child frame.
+    /// - `position`: The translational offset from the parent, in `from`.
+    /// - `orientation`: The orientation relative to the parent, in `from`.
+    /// - `from`: The convention of the incoming data.
+    /// - `to`: The convention of this tree.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the child cannot be added, see [`Frame::add_child`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::conventions::{ROS, UNITY};
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let world = Frame::new_origin("world").unwrap();
+    /// let marker = world
+    ///     .add_child_with_convention(
+    ///         "marker",
+    ///         Vector3::new(0.0, 1.0, 2.0),
+    ///         UnitQuaternion::identity(),
+    ///         UNITY,
+    ///         ROS,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(marker.position(), Vector3::new(2.0, 0.0, 1.0));
+    /// ```
+    pub fn add_child_with_convention(
+        &self,
+        name: impl Into<String>,
+        position: impl Into<Vector3<f64>>,
+        orientation: impl Into<Rotation>,
+        from: AxisConvention,
+        to: AxisConvention,
+    ) -> Result<Self, CartesianTreeError> {
+        let position = convert_position(&position.into(), from, to);
+        let orientation = convert_rotation(&orientation.into().as_quaternion(), from, to);
+        self.add_child(name, position, orientation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use nalgebra::Quaternion;
+    use std::f64::consts::FRAC_PI_2;
+
+    const CONVENTIONS: [AxisConvention; 4] = [ROS, UNITY, OPENCV_CAMERA, OPENGL];
+
+    fn quaternion(x: f64, y: f64, z: f64, w: f64) -> UnitQuaternion<f64> {
+        UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z))
+    }
+
+    fn assert_same_rotation(actual: &UnitQuaternion<f64>, expected: &UnitQuaternion<f64>) {
+        assert_relative_eq!(actual.angle_to(expected), 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn unity_matches_ros_tcp_connector() {
+        // The Unity ROS-TCP-Connector maps (x, y, z) to (z, -x, y) and (x, y, z, w) to
+        // (z, -x, y, -w) when converting to ROS.
+        let position = Vector3::new(0.3, -1.2, 2.5);
+        assert_eq!(
+            convert_position(&position, UNITY, ROS),
+            Vector3::new(2.5, -0.3, -1.2)
+        );
+        let rotation = quaternion(0.1, 0.7, -0.2, 0.6);
+        assert_same_rotation(
+            &convert_rotation(&rotation, UNITY, ROS),
+            &quaternion(rotation.k, -rotation.i, rotation.j, -rotation.w),
+        );
+
+        // Turning right about Unity's up axis is a negative yaw in ROS.
+        let turned = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), FRAC_PI_2);
+        assert_same_rotation(
+            &convert_rotation(&turned, UNITY, ROS),
+            &UnitQuaternion::from_euler_angles(0.0, 0.0, -FRAC_PI_2),
+        );
+    }
+
+    #[test]
+    fn opencv_matches_ros_optical_frame() {
+        // REP 103: the optical frame is rotated by (x, y, z, w) = (-0.5, 0.5, -0.5, 0.5)
+        // relative to the camera body frame.
+        let optical = quaternion(-0.5, 0.5, -0.5, 0.5);
+        let point = Vector3::new(0.2, -0.4, 3.0);
+        assert_relative_eq!(
+            convert_position(&point, OPENCV_CAMERA, ROS),
+            optical * point,
+            epsilon = 1e-12
+        );
+
+        // Rolling about the optical axis is a roll about x in ROS.
+        let about_optical_axis = UnitQuaternion::from_axis_angle(&Vector3::z_axis(), 0.3);
+        assert_same_rotation(
+            &convert_rotation(&about_optical_axis, OPENCV_CAMERA, ROS),
+            &UnitQuaternion::from_euler_angles(0.3, 0.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn opengl_and_opencv_differ_by_flipped_y_and_z() {
+        // The common camera pose conversion between OpenGL (e.g. NeRF, Blender) and OpenCV
+        // flips the y and z axes.
+        let flip = Matrix3::from_diagonal(&Vector3::new(1.0, -1.0, -1.0));
+        let pose = Isometry3::from_parts(
+            Translation3::new(0.5, 1.5, -2.0),
+            UnitQuaternion::from_euler_angles(0.2, -0.4, 1.1),
+        );
+        let converted = convert_isometry(&pose, OPENGL, OPENCV_CAMERA);
+        assert_eq!(converted.translation.vector, flip * pose.translation.vector);
+        let expected = flip * pose.rotation.to_rotation_matrix().matrix() * flip;
+        assert_relative_eq!(
+            *converted.rotation.to_rotation_matrix().matrix(),
+            expected,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            convert_position(&Vector3::new(1.0, 2.0, 3.0), OPENGL, ROS),
+            Vector3::new(-3.0, -1.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn conversions_round_trip_and_preserve_composition() {
+        let a = Isometry3::from_parts(
+            Translation3::new(1.0, -2.0, 0.5),
+            UnitQuaternion::from_euler_angles(0.3, -0.2, 0.9),
+        );
+        let b = Isometry3::from_parts(
+            Translation3::new(-0.4, 0.1, 2.0),
+            UnitQuaternion::from_euler_angles(-1.1, 0.4, 0.2),
+        );
+        for from in CONVENTIONS {
+            for to in CONVENTIONS {
+                let back = convert_isometry(&convert_isometry(&a, from, to), to, from);
+                assert_relative_eq!(back, a, epsilon = 1e-12);
+                assert_relative_eq!(
+                    convert_isometry(&(a * b), from, to),
+                    convert_isometry(&a, from, to) * convert_isometry(&b, from, to),
+                    epsilon = 1e-12
+                );
+            }
+            assert_relative_eq!(convert_isometry(&a, from, from), a, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn conventions_reject_parallel_axes() {
+        assert_eq!(
+            AxisConvention::new(Handedness::Left, Axis::PosY, Axis::PosZ).unwrap(),
+            UNITY
+        );
+        assert!(matches!(
+            AxisConvention::new(Handedness::Right, Axis::PosY, Axis::NegY),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+        assert!(AxisConvention::new(Handedness::Left, Axis::PosX, Axis::PosX).is_err());
+    }
+
+    #[test]
+    fn add_child_with_convention_converts_at_the_boundary() {
+        let world = Frame::new_origin("world").unwrap();
+        let orientation = UnitQuaternion::from_euler_angles(0.1, 0.5, -0.3);
+        let child = world
+            .add_child_with_convention(
+                "from_unity",
+                Vector3::new(1.0, 2.0, 3.0),
+                orientation,
+                UNITY,
+                ROS,
+            )
+            .unwrap();
+        assert_eq!(child.position(), Vector3::new(3.0, -1.0, 2.0));
+        assert_same_rotation(
+            &child.orientation().as_quaternion(),
+            &convert_rotation(&orientation, UNITY, ROS),
+        );
+        assert!(matches!(
+            world.add_child_with_convention(
+                "from_unity",
+                Vector3::zeros(),
+                UnitQuaternion::identity(),
+                UNITY,
+                ROS
+            ),
+            Err(CartesianTreeError::DuplicateChildName { .. })
+        ));
+    }
+}
@@ -0,0 +1,393 @@
+//! Comparison of frame trees, e.g. to review a calibration config before applying it.
+
+#[cfg(feature = "serde")]
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::tree::HasChildren;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The deviations up to which [`Frame::diff`] considers transforms equal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiffTolerances {
+    /// The maximal translational distance.
+    pub lin_tol: f64,
+    /// The maximal angular distance in radians.
+    pub ang_tol: f64,
+}
+
+impl Default for DiffTolerances {
+    /// Tolerances that only absorb floating-point rounding.
+    fn default() -> Self {
+        Self {
+            lin_tol: 1e-9,
+            ang_tol: 1e-9,
+        }
+    }
+}
+
+/// A frame whose transform to its parent differs between two trees.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TransformChange {
+    /// The path of the frame relative to the compared frames.
+    pub path: String,
+    /// The translational distance, or the angle in radians, between the two transforms.
+    pub difference: f64,
+}
+
+/// The differences between two frame trees, see [`Frame::diff`].
+///
+/// Paths are relative to the compared frames, so they can be passed to [`Frame::resolve_path`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TreeDiff {
+    /// Frames that only exist in the other tree.
+    pub added: Vec<String>,
+    /// Frames that only exist in this tree.
+    pub removed: Vec<String>,
+    /// Frames whose translation differs by more than the linear tolerance.
+    pub translation_changed: Vec<TransformChange>,
+    /// Frames whose rotation differs by more than the angular tolerance.
+    pub rotation_changed: Vec<TransformChange>,
+}
+
+impl TreeDiff {
+    /// Returns `true` if the trees are equal within the tolerances.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.translation_changed.is_empty()
+            && self.rotation_changed.is_empty()
+    }
+
+    /// Serializes the differences to a JSON string.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError::SerdeError`] if serialization fails.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, CartesianTreeError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl fmt::Display for TreeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no differences");
+        }
+        let count = self.added.len()
+            + self.removed.len()
+            + self.translation_changed.len()
+            + self.rotation_changed.len();
+        write!(
+            f,
+            "{count} difference{}:",
+            if count == 1 { "" } else { "s" }
+        )?;
+        for path in &self.added {
+            write!(f, "\n  + {path}")?;
+        }
+        for path in &self.removed {
+            write!(f, "\n  - {path}")?;
+        }
+        for change in &self.translation_changed {
+            write!(
+                f,
+                "\n  ~ {}: translation differs by {:.6}",
+                change.path, change.difference
+            )?;
+        }
+        for change in &self.rotation_changed {
+            write!(
+                f,
+                "\n  ~ {}: rotation differs by {:.6} rad",
+                change.path, change.difference
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Frame {
+    /// Compares this frame and its descendants with `other` and its descendants.
+    ///
+    /// Frames are matched by their path below the compared frames. The names and transforms of
+    /// the compared frames themselves are not compared, as they anchor the comparison. Metadata
+    /// and stored poses are ignored.
+    ///
+    /// # Arguments
+    /// - `other`: The frame to compare with.
+    /// - `tolerances`: The deviations up to which transforms are considered equal.
+    ///
+    /// # Returns
+    /// The frames added in `other`, removed in `other`, and present in both with different
+    /// transforms to their parents.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::diff::DiffTolerances;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let current = Frame::new_origin("world").unwrap();
+    /// current
+    ///     .add_child("camera", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let calibrated = Frame::new_origin("world").unwrap();
+    /// calibrated
+    ///     .add_child("camera", Vector3::new(1.0, 0.0, 0.01), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let diff = current.diff(&calibrated, DiffTolerances::default());
+    /// assert_eq!(diff.translation_changed[0].path, "camera");
+    /// println!("{diff}");
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &Self, tolerances: DiffTolerances) -> TreeDiff {
+        let mut diff = TreeDiff::default();
+        let mut stack = vec![(self.clone(), other.clone(), String::new())];
+        while let Some((mine, theirs, path)) = stack.pop() {
+            let theirs_children = theirs.children();
+            let mut pending = Vec::new();
+            for child in mine.children() {
+                let name = child.name();
+                let child_path = join(&path, &name);
+                match theirs_children.iter().find(|other| other.name() == name) {
+                    Some(matched) => {
+                        compare_transforms(&child, matched, &child_path, tolerances, &mut diff);
+                        pending.push((child, matched.clone(), child_path));
+                    }
+                    None => diff.removed.extend(subtree_paths(&child, &child_path)),
+                }
+            }
+            let my_names: Vec<String> = mine.children().iter().map(Self::name).collect();
+            for child in theirs_children {
+                if !my_names.contains(&child.name()) {
+                    let child_path = join(&path, &child.name());
+                    diff.added.extend(subtree_paths(&child, &child_path));
+                }
+            }
+            // Reversed, so children are visited in order.
+            stack.extend(pending.into_iter().rev());
+        }
+        diff
+    }
+
+    /// Compares this frame and its descendants with a config document, without changing the
+    /// tree.
+    ///
+    /// The document is read like [`Frame::from_json`] and compared as in [`Frame::diff`], which
+    /// shows what [`Frame::apply_config`] would change. Frames only present in the document are
+    /// reported as added, although `apply_config` ignores them.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the document cannot be read, see [`Frame::from_json`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::diff::DiffTolerances;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let arm = root
+    ///     .add_child("arm", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let config = root.to_json().unwrap();
+    /// arm.set(Vector3::new(2.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    ///
+    /// let diff = root.diff_config(&config, DiffTolerances::default()).unwrap();
+    /// assert_eq!(diff.translation_changed[0].difference, 1.0);
+    /// assert_eq!(arm.position(), Vector3::new(2.0, 0.0, 0.0));
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn diff_config(
+        &self,
+        json: &str,
+        tolerances: DiffTolerances,
+    ) -> Result<TreeDiff, CartesianTreeError> {
+        Ok(self.diff(&Self::from_json(json)?, tolerances))
+    }
+}
+
+fn compare_transforms(
+    mine: &Frame,
+    theirs: &Frame,
+    path: &str,
+    tolerances: DiffTolerances,
+    diff: &mut TreeDiff,
+) {
+    let mine = mine.borrow().transform_to_parent;
+    let theirs = theirs.borrow().transform_to_parent;
+    let distance = (theirs.translation.vector - mine.translation.vector).norm();
+    if distance > tolerances.lin_tol {
+        diff.translation_changed.push(TransformChange {
+            path: path.to_string(),
+            difference: distance,
+        });
+    }
+    let angle = mine.rotation.angle_to(&theirs.rotation);
+    if angle > tolerances.ang_tol {
+        diff.rotation_changed.push(TransformChange {
+            path: path.to_string(),
+            difference: angle,
+        });
+    }
+}
+
+/// Returns the paths of `frame` and its descendants in depth-first order, given the path of
+/// `frame`.
+fn subtree_paths(frame: &Frame, path: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut stack = vec![(frame.clone(), path.to_string())];
+    while let Some((frame, path)) = stack.pop() {
+        for child in frame.children().into_iter().rev() {
+            let child_path = join(&path, &child.name());
+            stack.push((child, child_path));
+        }
+        paths.push(path);
+    }
+    paths
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{path}/{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{UnitQuaternion, Vector3};
+
+    fn tree() -> Frame {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "arm",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        arm.add_child(
+            "camera",
+            Vector3::new(0.0, 0.0, 0.2),
+            UnitQuaternion::from_euler_angles(0.0, 0.5, 0.0),
+        )
+        .unwrap();
+        let lidar = arm
+            .add_child("lidar", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        lidar
+            .add_child("mount", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        root
+    }
+
+    #[test]
+    fn structural_differences() {
+        let mine = tree();
+        let theirs = tree();
+        assert!(mine.diff(&theirs, DiffTolerances::default()).is_empty());
+
+        let arm = theirs.resolve_path("arm").unwrap();
+        arm.remove_child("lidar").unwrap();
+        arm.add_child("gripper", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap()
+            .add_child("tcp", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        // Renamed roots are still compared.
+        theirs.rename("lab").unwrap();
+
+        let diff = mine.diff(&theirs, DiffTolerances::default());
+        assert_eq!(diff.added, ["arm/gripper", "arm/gripper/tcp"]);
+        assert_eq!(diff.removed, ["arm/lidar", "arm/lidar/mount"]);
+        assert!(diff.translation_changed.is_empty());
+        assert!(diff.rotation_changed.is_empty());
+        assert!(mine.resolve_path(&diff.removed[1]).is_ok());
+        assert_eq!(
+            diff.to_string(),
+            "4 differences:\n  + arm/gripper\n  + arm/gripper/tcp\n  - arm/lidar\n  - arm/lidar/mount"
+        );
+
+        let reverse = theirs.diff(&mine, DiffTolerances::default());
+        assert_eq!(reverse.added, diff.removed);
+        assert_eq!(reverse.removed, diff.added);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn numeric_differences() {
+        let mine = tree();
+        let theirs = tree();
+        let camera = theirs.resolve_path("arm/camera").unwrap();
+        camera
+            .set(
+                Vector3::new(0.0, 0.003, 0.2),
+                UnitQuaternion::from_euler_angles(0.0, 0.52, 0.0),
+            )
+            .unwrap();
+        theirs
+            .resolve_path("arm")
+            .unwrap()
+            .set(Vector3::new(1.0, 0.0, 1e-7), UnitQuaternion::identity())
+            .unwrap();
+
+        let strict = mine.diff(&theirs, DiffTolerances::default());
+        assert!(strict.added.is_empty() && strict.removed.is_empty());
+        let moved: Vec<_> = strict.translation_changed.iter().map(|c| &c.path).collect();
+        assert_eq!(moved, ["arm", "arm/camera"]);
+        assert!((strict.translation_changed[1].difference - 0.003).abs() < 1e-12);
+        assert_eq!(strict.rotation_changed.len(), 1);
+        assert_eq!(strict.rotation_changed[0].path, "arm/camera");
+        assert!((strict.rotation_changed[0].difference - 0.02).abs() < 1e-12);
+
+        let loose = mine.diff(
+            &theirs,
+            DiffTolerances {
+                lin_tol: 1e-3,
+                ang_tol: 0.05,
+            },
+        );
+        assert_eq!(loose.translation_changed.len(), 1);
+        assert!(loose.rotation_changed.is_empty());
+        assert_eq!(
+            loose.to_string(),
+            "1 difference:\n  ~ arm/camera: translation differs by 0.003000"
+        );
+
+        let json = loose.to_json().unwrap();
+        let parsed: TreeDiff = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, loose);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn diff_config_leaves_tree_untouched() {
+        let root = tree();
+        let camera = root.resolve_path("arm/camera").unwrap();
+        let config = root.to_json().unwrap();
+        camera
+            .set(Vector3::new(0.1, 0.0, 0.2), UnitQuaternion::identity())
+            .unwrap();
+        root.resolve_path("arm/lidar/mount")
+            .unwrap()
+            .add_child("sensor", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        let diff = root
+            .diff_config(&config, DiffTolerances::default())
+            .unwrap();
+        assert_eq!(diff.removed, ["arm/lidar/mount/sensor"]);
+        assert_eq!(diff.translation_changed[0].path, "arm/camera");
+        assert_eq!(diff.rotation_changed[0].path, "arm/camera");
+        assert_eq!(camera.position(), Vector3::new(0.1, 0.0, 0.2));
+        assert!(root.diff_config("{", DiffTolerances::default()).is_err());
+    }
+}
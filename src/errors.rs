@@ -1,21 +1,106 @@
+use alloc::{string::String, vec::Vec};
 #[derive(Debug, thiserror::Error)]
 pub enum CartesianTreeError {
-    #[error("Frame '{0}' not found or has been dropped")]
-    FrameDropped(String),
-    #[error("Frame '{0}' is a root frame and has no parent")]
-    RootHasNoParent(String),
-    #[error("Cannot update transform for frame '{0}' as it has no parent")]
-    CannotUpdateRootTransform(String),
-    #[error("A child frame with name '{0}' already exists for parent '{1}'")]
-    ChildNameConflict(String, String),
-    #[error("Failed to find a common ancestor between frame '{0}' and '{1}'")]
-    NoCommonAncestor(String, String),
-    #[error("Frame '{0}' is not an ancestor of '{1}'")]
-    IsNoAncestor(String, String),
-    #[error("Internal error: Weak pointer upgrade failed")]
-    WeakUpgradeFailed(),
+    #[error("Referenced frame has been dropped")]
+    FrameDropped,
+    #[error("Frame '{frame}' is a root frame and has no parent")]
+    RootHasNoParent { frame: String },
+    #[error("Cannot update transform for frame '{frame}' as it has no parent")]
+    CannotUpdateRootTransform { frame: String },
+    #[error("Cannot update transform for frame '{frame}' as it is static")]
+    StaticFrame { frame: String },
+    #[error("A child frame with name '{child}' already exists for parent '{parent}'")]
+    DuplicateChildName { parent: String, child: String },
+    #[error("Frame '{parent}' has no child named '{child}'")]
+    ChildNotFound { parent: String, child: String },
+    #[error("Cannot reparent frame '{frame}' onto itself or its descendant '{new_parent}'")]
+    ReparentCycle { frame: String, new_parent: String },
+    #[error("Cannot resolve segment '{segment}' of path '{path}'")]
+    PathNotFound { path: String, segment: String },
+    #[error("Failed to find a common ancestor between frame '{from}' and '{to}'")]
+    NoCommonAncestor { from: String, to: String },
+    #[error("Frame '{ancestor}' is not an ancestor of '{frame}'")]
+    IsNoAncestor { ancestor: String, frame: String },
+    #[error("Frame '{frame}' has been dropped during {operation}")]
+    WeakUpgradeFailed { frame: String, operation: String },
+    #[cfg(feature = "serde")]
     #[error("Serialization/Deserialization error: {0}")]
     SerdeError(#[from] serde_json::Error),
-    #[error("Tree structure mismatch during config apply: {0}")]
-    Mismatch(String),
+    #[error("Invalid rotation matrix: {reason}")]
+    InvalidRotationMatrix { reason: String },
+    #[error("Invalid frame document at '{path}': {reason}")]
+    InvalidDocument { path: String, reason: String },
+    #[error("Frame names do not match during config apply: expected '{expected}', found '{found}'")]
+    Mismatch { expected: String, found: String },
+    #[error("Expected {expected} values but got {found}")]
+    LengthMismatch { expected: usize, found: usize },
+    #[error("Degenerate input: {reason}")]
+    DegenerateInput { reason: String },
+    #[error("Pose {index} is not expressed in frame '{frame}'")]
+    PoseNotInFrame { frame: String, index: usize },
+    #[error("Frame is already being accessed by another operation")]
+    ConcurrentAccess,
+    #[error("Non-finite value {value} for '{field}'")]
+    NonFiniteValue { field: String, value: f64 },
+    #[error("Frame '{frame}' already stores a pose named '{name}'")]
+    DuplicatePoseName { frame: String, name: String },
+    #[error("Frame '{frame}' already stores a region named '{name}'")]
+    DuplicateRegionName { frame: String, name: String },
+    #[error("Invalid frame name {name:?}: {reason}")]
+    InvalidFrameName { name: String, reason: String },
+    #[error("Frames not found: {paths:?}")]
+    MissingFrames { paths: Vec<String> },
+    #[error("Invalid homogeneous transformation matrix: {reason}")]
+    InvalidTransformMatrix { reason: String },
+    #[error("Frame '{frame}' does not record a transform history")]
+    HistoryNotEnabled { frame: String },
+    #[error("Frame '{frame}' has not recorded any transforms yet")]
+    EmptyHistory { frame: String },
+    #[error(
+        "Time {time} is outside the recorded history of frame '{frame}', which covers [{earliest}, {latest}]"
+    )]
+    TimeOutOfRange {
+        frame: String,
+        time: f64,
+        earliest: f64,
+        latest: f64,
+    },
+    #[error("Scale {scale} of frame '{frame}' is not a positive finite number")]
+    InvalidScale { frame: String, scale: f64 },
+    #[error(
+        "The transform from '{from}' to '{to}' passes through a scaled frame and is no isometry; use a similarity instead"
+    )]
+    ScaledTransform { from: String, to: String },
+    #[error("The tree of frame '{frame}' declares no canonical length unit")]
+    UndeclaredUnit { frame: String },
+    #[error(
+        "Config schema version {found} is not supported; this build reads versions 1 to {supported}"
+    )]
+    UnsupportedConfigVersion { found: u64, supported: u64 },
+    #[error("Invalid binary tree data at byte {offset}: {reason}")]
+    InvalidBinary { offset: usize, reason: String },
+    #[error("Frame '{frame}' has no joint")]
+    NoJoint { frame: String },
+    #[error("Joint value {value} of frame '{frame}' is outside its limits [{lower}, {upper}]")]
+    JointLimitExceeded {
+        frame: String,
+        value: f64,
+        lower: f64,
+        upper: f64,
+    },
+    #[error(
+        "Inverse kinematics did not converge within {iterations} iterations; the best attempt has position error {position_error} and orientation error {orientation_error}"
+    )]
+    IkNotConverged {
+        joint_values: Vec<f64>,
+        position_error: f64,
+        orientation_error: f64,
+        iterations: usize,
+    },
+    #[error("Frames '{first}' and '{second}' share the frame id '{id}'")]
+    DuplicateFrameId {
+        id: String,
+        first: String,
+        second: String,
+    },
 }
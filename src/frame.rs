@@ -1,95 +1,763 @@
 use crate::CartesianTreeError;
 use crate::Pose;
+use crate::audit::AuditOperation;
+#[cfg(feature = "std")]
+use crate::audit::{AuditEntry, AuditLog};
+use crate::history::FrameHistory;
+use crate::intern::{intern, lookup, same_name};
+use crate::joint::Joint;
+#[cfg(all(feature = "std", feature = "serde"))]
+use crate::json::JsonDocument;
+#[cfg(feature = "std")]
 use crate::lazy_access::LazyRotation;
+#[cfg(feature = "std")]
 use crate::lazy_access::LazyTranslation;
-use crate::rotation::Rotation;
+use crate::metadata::MetadataValue;
+use crate::pose::PoseEntry;
+use crate::region::RegionShape;
+use crate::rotation::{LookAtConvention, MATRIX_TOLERANCE, Rotation};
+#[cfg(all(feature = "std", feature = "serde"))]
+use crate::scalar::Precision;
+#[cfg(feature = "std")]
+use crate::scalar::isometry_to_f64;
+use crate::scalar::{Real, from_f64};
+#[cfg(all(feature = "std", feature = "serde"))]
+use crate::schema::{SCHEMA_VERSION, migrate};
 use crate::tree::Walking;
-use crate::tree::{HasChildren, HasParent, NodeEquality};
-
-use nalgebra::UnitQuaternion;
-use nalgebra::{Isometry3, Translation3, Vector3};
-use std::cell::RefCell;
-use std::ops::Add;
-use std::ops::Mul;
-use std::ops::Sub;
-use std::rc::{Rc, Weak};
-
+use crate::tree::{Ancestors, BreadthFirst, DepthFirst, HasChildren, HasParent, NodeEquality};
+use crate::units::LengthUnit;
+#[cfg(all(feature = "std", feature = "serde"))]
+use crate::validation::read_unit_quaternion;
+use crate::validation::{
+    FrameNameRules, QUATERNION_NORM_TOLERANCE, TreeValidationError, check_frame_name,
+    check_isometry, check_orientation, check_position,
+};
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::rc::{Rc, Weak};
+use core::cell::RefCell;
+use core::fmt::Write;
+#[cfg(feature = "std")]
+use core::ops::{Add, Mul, Sub};
+use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(not(feature = "std"))]
+use nalgebra::ComplexField as _;
+use nalgebra::{Isometry3, Matrix3, Matrix4, Point3, Similarity3, Translation3, Vector3, Vector4};
+#[cfg(all(feature = "std", feature = "serde"))]
+use nalgebra::{Quaternion, UnitQuaternion};
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(all(feature = "std", feature = "serde"))]
 use serde::{Deserialize, Serialize};
-use serde_json;
+#[cfg(feature = "std")]
 use uuid::Uuid;
 
+pub mod arena;
+#[cfg(feature = "std")]
+pub mod sync;
+
 /// Represents a coordinate frame in a Cartesian tree structure.
 ///
 /// Each frame can have one parent and multiple children. The frame stores its
 /// transformation (position and orientation) relative to its parent.
 ///
 /// Root frames (created via `Frame::new_origin`) have no parent and use the identity transform.
-#[derive(Clone, Debug)]
-pub struct Frame {
-    pub(crate) data: Rc<RefCell<FrameData>>,
+///
+/// Frames are shared handles; no method keeps a frame borrowed while it calls back into user
+/// code. Should an update still conflict with an ongoing access of the same frame, it fails with
+/// [`CartesianTreeError::ConcurrentAccess`] instead of panicking.
+///
+/// A handle keeps its whole tree alive, so holding a leaf is enough to walk up to the root; the
+/// tree is freed once no handle to any of its frames is left. A frame moved to another tree
+/// (see [`Frame::reparent`] and [`Frame::remove_child`]) keeps the new tree alive instead.
+#[derive(Clone)]
+pub struct Frame<T: Real = f64> {
+    pub(crate) data: Rc<RefCell<FrameData<T>>>,
+    /// Holds the root of the tree, `None` for the handles parents keep of their children.
+    anchor: Option<Rc<TreeAnchor<T>>>,
+}
+
+/// The strong reference from the handles of a frame to the root of its tree.
+///
+/// Children only refer weakly to their parents, so without it dropping the root would cut off
+/// the ancestors of every remaining handle. The handles of one frame share an anchor, which
+/// follows the frame when it is moved to another tree.
+struct TreeAnchor<T: Real> {
+    root: RefCell<Rc<RefCell<FrameData<T>>>>,
+}
+
+impl<T: Real> core::fmt::Debug for TreeAnchor<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TreeAnchor").finish_non_exhaustive()
+    }
+}
+
+impl<T: Real> core::fmt::Debug for Frame<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // A frame being updated is formatted without its data rather than panicking.
+        let Ok(data) = self.data.try_borrow() else {
+            return f.debug_struct("Frame").finish_non_exhaustive();
+        };
+        let parent = (!data.parent_name.is_empty()).then_some(&data.parent_name);
+        f.debug_struct("Frame")
+            .field("name", &data.name)
+            .field("parent", &parent)
+            .field("data", &*data)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The number of decimals [`Frame`] and [`Pose`] are displayed with by default.
+pub const DISPLAY_PRECISION: usize = 4;
+
+/// Summarizes the frame as its name, its path and its transform to the parent, e.g.
+/// `camera (/world/camera): 0.1000 0.0000 1.0000 / 0.0000 0.0000 1.5708`.
+///
+/// The transform is written as the position `x y z` and the RPY angles `r p y` in radians,
+/// with [`DISPLAY_PRECISION`] decimals. A precision overrides them, e.g. `{:.2}`; the alternate
+/// flag `{:#}` writes the values at full precision instead. The Python bindings convert frames
+/// to strings alike.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::Frame;
+/// use nalgebra::{UnitQuaternion, Vector3};
+///
+/// let world = Frame::new_origin("world").unwrap();
+/// let camera = world
+///     .add_child("camera", Vector3::new(0.1, 0.0, 1.0), UnitQuaternion::identity())
+///     .unwrap();
+/// assert_eq!(
+///     camera.to_string(),
+///     "camera (/world/camera): 0.1000 0.0000 1.0000 / 0.0000 0.0000 0.0000"
+/// );
+/// assert_eq!(format!("{camera:.1}"), "camera (/world/camera): 0.1 0.0 1.0 / 0.0 0.0 0.0");
+/// assert_eq!(format!("{camera:#}"), "camera (/world/camera): 0.1 0 1 / 0 0 0");
+/// ```
+impl<T: Real> core::fmt::Display for Frame<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Like in `Debug`, a frame being updated is formatted without its data.
+        let Some(path) = self.try_path() else {
+            return f.write_str("Frame { .. }");
+        };
+        let Ok(data) = self.data.try_borrow() else {
+            return f.write_str("Frame { .. }");
+        };
+        write!(f, "{} ({path}): ", data.name)?;
+        write_transform(f, &data.transform_to_parent)
+    }
+}
+
+/// Writes `transform` as `x y z / r p y`, see the [`Display`](core::fmt::Display) of [`Frame`].
+pub(crate) fn write_transform<T: Real>(
+    f: &mut core::fmt::Formatter<'_>,
+    transform: &Isometry3<T>,
+) -> core::fmt::Result {
+    let position = transform.translation.vector;
+    let rpy = Rotation::from(transform.rotation).as_rpy();
+    let precision = f.precision().unwrap_or(DISPLAY_PRECISION);
+    for (index, value) in position.iter().chain(rpy.iter()).enumerate() {
+        let separator = match index {
+            0 => "",
+            3 => " / ",
+            _ => " ",
+        };
+        // Adding zero turns -0 into 0, e.g. in the angles of identity rotations.
+        let value = *value + T::zero();
+        if f.alternate() {
+            write!(f, "{separator}{value}")?;
+        } else {
+            write!(f, "{separator}{value:.precision$}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Determines which transform is preserved when a frame is moved to a new parent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReparentPolicy {
+    /// Recomputes the transform to the new parent so the frame keeps its pose relative to the root.
+    KeepWorld,
+    /// Keeps the transform to the parent, so the frame moves along with its new parent.
+    KeepLocal,
 }
 
-#[derive(Debug)]
-pub(crate) struct FrameData {
+/// Determines what [`Frame::store_pose`] and [`Frame::store_region`] do if a pose or region
+/// with the same name is already stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorePolicy {
+    /// Replaces the stored pose or region.
+    Overwrite,
+    /// Keeps the stored pose or region and returns [`CartesianTreeError::DuplicatePoseName`] or
+    /// [`CartesianTreeError::DuplicateRegionName`].
+    Reject,
+}
+
+/// Determines whether [`Frame::set_with`] updates the transform of a static frame, see
+/// [`Frame::set_static`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StaticPolicy {
+    /// Keeps the transform of a static frame and returns [`CartesianTreeError::StaticFrame`].
+    #[default]
+    Reject,
+    /// Updates the transform of static and dynamic frames alike.
+    Override,
+}
+
+/// The coordinates a delta passed to [`Frame::apply_delta`] or [`Pose::apply_delta`] is
+/// expressed in.
+///
+/// With `T` the transform to the parent, the two spaces differ in the order of multiplication:
+///
+/// | Space | New transform | Translations move along | Rotations turn about |
+/// |---|---|---|---|
+/// | [`Local`](DeltaSpace::Local) | `T * delta` | the axes of the frame | the origin of the frame |
+/// | [`Parent`](DeltaSpace::Parent) | `delta * T` | the axes of the parent | the origin of the parent |
+///
+/// Rotating in the parent space thus also swings the position of the frame about the origin of
+/// the parent. To nudge a frame by a small correction of its own, e.g. 2 mm along its X axis
+/// and 0.5° about its Z axis, use [`Local`](DeltaSpace::Local).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeltaSpace {
+    /// The delta is expressed in the frame itself and post-multiplied: `T * delta`.
+    Local,
+    /// The delta is expressed in the parent frame and pre-multiplied: `delta * T`.
+    Parent,
+}
+
+/// Selects the characters used to draw the branches in [`Frame::render_tree_with`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TreeStyle {
+    /// Unicode box-drawing characters (`├──`, `└──`, `│`).
+    #[default]
+    Unicode,
+    /// Plain ASCII characters (`|--`, `` `-- ``, `|`).
+    Ascii,
+}
+
+/// Controls the output of [`Frame::to_dot_with`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DotOptions {
+    /// Whether to label nodes with their world translation and edges with the local transform.
+    pub include_transforms: bool,
+    /// The number of decimal places used for transform values.
+    pub precision: usize,
+}
+
+impl Default for DotOptions {
+    fn default() -> Self {
+        Self {
+            include_transforms: true,
+            precision: 3,
+        }
+    }
+}
+
+/// Identifies a callback registered with [`Frame::on_transform_changed`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(pub(crate) usize);
+
+/// Selects which changes notify a callback registered with [`Frame::on_transform_changed_with`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SubscriptionScope {
+    /// Only changes to the transform of the frame itself.
+    #[default]
+    Frame,
+    /// Changes to the transform of the frame or of any of its descendants.
+    Subtree,
+}
+
+static NEXT_SUBSCRIPTION_ID: AtomicUsize = AtomicUsize::new(0);
+
+type TransformCallback<T> = Rc<RefCell<dyn FnMut(&Frame<T>)>>;
+
+struct Subscription<T: Real> {
+    id: SubscriptionId,
+    scope: SubscriptionScope,
+    callback: TransformCallback<T>,
+}
+
+impl<T: Real> core::fmt::Debug for Subscription<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Subscription")
+            .field("id", &self.id)
+            .field("scope", &self.scope)
+            .finish_non_exhaustive()
+    }
+}
+
+pub(crate) struct FrameData<T: Real = f64> {
     /// The name of the frame (must be unique among siblings).
-    pub(crate) name: String,
+    pub(crate) name: Rc<str>,
     /// Reference to the parent frame.
-    parent: Option<Weak<RefCell<FrameData>>>,
+    parent: Option<Weak<RefCell<Self>>>,
+    /// The name of the parent frame, to report it should the parent have been dropped.
+    parent_name: Rc<str>,
     /// Transformation from this frame to its parent frame.
-    transform_to_parent: Isometry3<f64>,
+    pub(crate) transform_to_parent: Isometry3<T>,
+    /// Cached transformation from this frame to its root, `None` if outdated.
+    ///
+    /// An outdated frame never has descendants with an up-to-date cache.
+    world_transform: Option<Isometry3<T>>,
     /// Child frames directly connected to this frame.
-    children: Vec<Frame>,
+    children: Vec<Frame<T>>,
+    /// Application data attached to this frame.
+    pub(crate) metadata: BTreeMap<String, MetadataValue>,
+    /// Named poses stored on this frame, relative to it.
+    pub(crate) poses: BTreeMap<String, Isometry3<f64>>,
+    /// Named regions stored on this frame, in its coordinates.
+    pub(crate) regions: BTreeMap<String, RegionShape>,
+    /// Callbacks notified when the transform of this frame (or its subtree) changes.
+    subscriptions: Vec<Subscription<T>>,
+    /// The rules names of new children are checked against, inherited from the root.
+    name_rules: FrameNameRules,
+    /// Time-stamped transforms to the parent, see [`Frame::enable_history`].
+    pub(crate) history: Option<FrameHistory>,
+    /// Linear and angular velocity relative to the parent, see [`Frame::set_twist`].
+    pub(crate) twist: Option<(Vector3<f64>, Vector3<f64>)>,
+    /// The joint articulating this frame and its transform to the parent at joint value zero,
+    /// see [`Frame::attach_joint`].
+    pub(crate) joint: Option<(Joint, Isometry3<f64>)>,
+    /// Uniform scale of this frame's coordinates, see [`Frame::add_scaled_child`].
+    pub(crate) scale: Option<f64>,
+    /// Whether the transform to the parent is fixed after construction, see [`Frame::set_static`].
+    pub(crate) is_static: bool,
+    /// The audit log shared by all frames of the tree, see [`Frame::enable_audit`].
+    #[cfg(feature = "std")]
+    pub(crate) audit: Option<Rc<RefCell<AuditLog>>>,
+    /// The length unit of all translations in the tree, only set on roots, see
+    /// [`Frame::set_canonical_unit`].
+    pub(crate) canonical_unit: Option<LengthUnit>,
+    /// The anchor shared by the handles of this frame, if any are alive.
+    anchor: RefCell<Weak<TreeAnchor<T>>>,
+    /// The poses expressed in this frame, see [`Frame::poses`]. Entries of dropped poses are
+    /// pruned lazily.
+    pose_entries: RefCell<Vec<Weak<PoseEntry<T>>>>,
+}
+
+impl<T: Real> core::fmt::Debug for FrameData<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Children are listed by name, as formatting them in turn recurses once per level,
+        // which overflows the stack for very deep trees.
+        let children: Vec<_> = self
+            .children
+            .iter()
+            .filter_map(|child| child.data.try_borrow().ok().map(|data| data.name.clone()))
+            .collect();
+        // The name and the parent are formatted by the `Debug` of `Frame`.
+        f.debug_struct("FrameData")
+            .field("transform_to_parent", &self.transform_to_parent)
+            .field("world_transform", &self.world_transform)
+            .field("children", &children)
+            .field("metadata", &self.metadata)
+            .field("poses", &self.poses)
+            .field("regions", &self.regions)
+            .field("subscriptions", &self.subscriptions)
+            .field("name_rules", &self.name_rules)
+            .field("history", &self.history)
+            .field("twist", &self.twist)
+            .field("joint", &self.joint)
+            .field("scale", &self.scale)
+            .field("is_static", &self.is_static)
+            .field("canonical_unit", &self.canonical_unit)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Real> FrameData<T> {
+    /// Registers a pose expressed in this frame.
+    pub(crate) fn register_pose(&self, entry: &Rc<PoseEntry<T>>) {
+        let mut entries = self.pose_entries.borrow_mut();
+        // Pruning only when the vector would grow keeps registering O(1) amortized.
+        if entries.len() == entries.capacity() {
+            entries.retain(|entry| entry.strong_count() > 0);
+        }
+        entries.push(Rc::downgrade(entry));
+    }
+}
+
+impl<T: Real> Drop for FrameData<T> {
+    fn drop(&mut self) {
+        // Dropping the children recursively overflows the stack for deep trees (long kinematic
+        // chains), so descendants that are no longer referenced are dismantled iteratively.
+        let mut pending = core::mem::take(&mut self.children);
+        while let Some(child) = pending.pop() {
+            if let Ok(data) = Rc::try_unwrap(child.data) {
+                pending.append(&mut data.into_inner().children);
+            }
+        }
+    }
 }
 
+/// Returns the root of the tree of `data`, walking up its parents.
+fn tree_root<T: Real>(data: &Rc<RefCell<FrameData<T>>>) -> Rc<RefCell<FrameData<T>>> {
+    let mut current = data.clone();
+    loop {
+        let parent = current
+            .try_borrow()
+            .ok()
+            .and_then(|node| node.parent.as_ref().and_then(Weak::upgrade));
+        match parent {
+            Some(parent) => current = parent,
+            None => return current,
+        }
+    }
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
 #[derive(Serialize, Deserialize, Debug, Clone)]
-struct SerialFrame {
+pub(crate) struct SerialFrame {
+    /// The version of the format, only written at the top level, see [`crate::schema`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schema_version: Option<u64>,
+    /// The scalar type of the tree, only written at the top level of non-`f64` trees.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    precision: Option<Precision>,
     name: String,
     position: Vector3<f64>,
-    orientation: UnitQuaternion<f64>,
-    children: Vec<SerialFrame>,
+    orientation: SerialOrientation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scale: Option<f64>,
+    /// Whether the frame is static, see [`Frame::set_static`]; only written for static frames.
+    #[serde(
+        rename = "static",
+        default,
+        skip_serializing_if = "core::ops::Not::not"
+    )]
+    is_static: bool,
+    /// The length unit of all translations in the document, only written at the top level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    units: Option<LengthUnit>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) metadata: BTreeMap<String, MetadataValue>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    poses: BTreeMap<String, SerialPose>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    regions: BTreeMap<String, RegionShape>,
+    /// Written by [`SerialFrame::to_json`] instead, as `serde_json` recurses per level.
+    #[serde(skip_serializing)]
+    children: Vec<Self>,
 }
 
-impl Frame {
-    /// Creates a new root frame (origin) with the given name.
+#[cfg(all(feature = "std", feature = "serde"))]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SerialPose {
+    position: Vector3<f64>,
+    orientation: SerialOrientation,
+}
+
+/// An orientation tagged with its representation, e.g. `{"quaternion": [x, y, z, w]}`.
+#[cfg(all(feature = "std", feature = "serde"))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum SerialOrientation {
+    Quaternion(UnitQuaternion<f64>),
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+impl SerialOrientation {
+    /// Returns the normalized quaternion; zero-length ones become NaN.
+    fn normalized(self) -> UnitQuaternion<f64> {
+        // Deserialized quaternions are not normalized.
+        match self {
+            Self::Quaternion(quaternion) => UnitQuaternion::new_normalize(quaternion.into_inner()),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+impl SerialFrame {
+    /// Converts the translations of this node, its poses and its descendants from `from` to `to`.
+    fn convert_units(&mut self, from: LengthUnit, to: LengthUnit) {
+        let mut pending = vec![self];
+        while let Some(node) = pending.pop() {
+            node.position = from.convert_vector(node.position, to);
+            for pose in node.poses.values_mut() {
+                pose.position = from.convert_vector(pose.position, to);
+            }
+            for region in node.regions.values_mut() {
+                region.convert_units(from, to);
+            }
+            pending.extend(&mut node.children);
+        }
+    }
+
+    /// Writes this node and its descendants as JSON, in the layout of `serde_json`.
     ///
-    /// The origin has no parent and uses the identity transform.
-    /// # Arguments
-    /// - `name`: The name of the root frame.
+    /// `serde_json` recurses once per level of nesting, which overflows the stack for very deep
+    /// trees (e.g. long kinematic chains), so only the fields of each node are written by it
+    /// and the children with an explicit stack.
+    pub(crate) fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        enum Step<'a> {
+            Open(&'a SerialFrame, usize),
+            Separator,
+            Close(usize, bool),
+        }
+
+        let mut output = String::new();
+        let mut pending = vec![Step::Open(self, 0)];
+        while let Some(step) = pending.pop() {
+            match step {
+                Step::Open(node, depth) => {
+                    // Each node is nested in an object and an array per ancestor.
+                    let indent = "    ".repeat(depth);
+                    if pretty {
+                        let fields = serde_json::to_string_pretty(node)?;
+                        let fields = fields.strip_suffix("\n}").unwrap_or(&fields);
+                        // Strings are escaped, so every line break separates two tokens.
+                        for (index, line) in fields.lines().enumerate() {
+                            if index > 0 {
+                                output.push('\n');
+                            }
+                            output.push_str(&indent);
+                            output.push_str(line);
+                        }
+                        let _ = write!(output, ",\n{indent}  \"children\": [");
+                    } else {
+                        let fields = serde_json::to_string(node)?;
+                        output.push_str(fields.strip_suffix('}').unwrap_or(&fields));
+                        output.push_str(",\"children\":[");
+                    }
+                    pending.push(Step::Close(depth, !node.children.is_empty()));
+                    for (index, child) in node.children.iter().enumerate().rev() {
+                        pending.push(Step::Open(child, depth + 1));
+                        if index > 0 {
+                            pending.push(Step::Separator);
+                        }
+                    }
+                    if pretty && !node.children.is_empty() {
+                        output.push('\n');
+                    }
+                }
+                Step::Separator => output.push_str(if pretty { ",\n" } else { "," }),
+                Step::Close(depth, has_children) => {
+                    if pretty {
+                        let indent = "    ".repeat(depth);
+                        if has_children {
+                            let _ = write!(output, "\n{indent}  ");
+                        }
+                        let _ = write!(output, "]\n{indent}}}");
+                    } else {
+                        output.push_str("]}");
+                    }
+                }
+            }
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+impl Drop for SerialFrame {
+    fn drop(&mut self) {
+        // Like for `FrameData`, deep trees are dismantled iteratively.
+        let mut pending = core::mem::take(&mut self.children);
+        while let Some(mut child) = pending.pop() {
+            pending.append(&mut child.children);
+        }
+    }
+}
+
+impl<T: Real> Frame<T> {
+    /// Creates a new root frame (origin) of a tree with the scalar type `T`, e.g. `f32`.
+    ///
+    /// Like [`Frame::new_origin`], which creates `f64` trees.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::InvalidFrameName`] if the name violates
+    /// [`FrameNameRules::Strict`].
     ///
     /// # Example
     /// ```
     /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
     ///
-    /// let origin = Frame::new_origin("world");
+    /// let origin = Frame::<f32>::origin("world").unwrap();
+    /// let camera = origin
+    ///     .add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// assert_eq!(camera.position(), Vector3::new(0.0, 0.0, 1.0_f32));
     /// ```
-    pub fn new_origin(name: impl Into<String>) -> Self {
+    pub fn origin(name: impl Into<String>) -> Result<Self, CartesianTreeError> {
+        Self::origin_with(name, FrameNameRules::Strict)
+    }
+
+    /// Creates a new root frame (origin) of a tree with the scalar type `T`, whose tree checks
+    /// frame names against `rules`.
+    ///
+    /// Like [`Frame::new_origin_with`], which creates `f64` trees.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::InvalidFrameName`] if the name violates `rules`.
+    pub fn origin_with(
+        name: impl Into<String>,
+        rules: FrameNameRules,
+    ) -> Result<Self, CartesianTreeError> {
+        let name = name.into();
+        check_frame_name(&name, rules)?;
+        Ok(Self::from_data(Rc::new(RefCell::new(FrameData {
+            name: intern(&name),
+            parent: None,
+            parent_name: intern(""),
+            children: Vec::new(),
+            transform_to_parent: Isometry3::identity(),
+            world_transform: Some(Isometry3::identity()),
+            metadata: BTreeMap::new(),
+            poses: BTreeMap::new(),
+            regions: BTreeMap::new(),
+            subscriptions: Vec::new(),
+            name_rules: rules,
+            history: None,
+            twist: None,
+            joint: None,
+            scale: None,
+            is_static: false,
+            #[cfg(feature = "std")]
+            audit: None,
+            canonical_unit: None,
+            anchor: RefCell::default(),
+            pose_entries: RefCell::default(),
+        }))))
+    }
+
+    /// Returns the rules the names of new frames in this tree are checked against.
+    #[must_use]
+    pub fn name_rules(&self) -> FrameNameRules {
+        self.borrow().name_rules
+    }
+
+    /// Returns a handle to `data` that keeps its tree alive, see [`TreeAnchor`].
+    pub(crate) fn from_data(data: Rc<RefCell<FrameData<T>>>) -> Self {
+        Self::anchored(data, tree_root)
+    }
+
+    /// Returns a handle to `data`, a frame of the same tree as this one, that keeps the tree
+    /// alive.
+    fn related(&self, data: Rc<RefCell<FrameData<T>>>) -> Self {
+        Self::anchored(data, |_| self.tree_root())
+    }
+
+    /// Returns a handle to `data` that does not keep the tree alive, as parents hold their
+    /// children.
+    pub(crate) const fn link(data: Rc<RefCell<FrameData<T>>>) -> Self {
+        Self { data, anchor: None }
+    }
+
+    /// Returns a handle to `data` sharing the anchor of its other handles, or with a new anchor
+    /// holding the given root.
+    fn anchored(
+        data: Rc<RefCell<FrameData<T>>>,
+        root: impl FnOnce(&Rc<RefCell<FrameData<T>>>) -> Rc<RefCell<FrameData<T>>>,
+    ) -> Self {
+        let shared = data
+            .try_borrow()
+            .ok()
+            .and_then(|node| node.anchor.borrow().upgrade());
+        let anchor = shared.unwrap_or_else(|| {
+            let anchor = Rc::new(TreeAnchor {
+                root: RefCell::new(root(&data)),
+            });
+            // A frame borrowed for an update just gets an anchor of its own.
+            if let Ok(node) = data.try_borrow() {
+                *node.anchor.borrow_mut() = Rc::downgrade(&anchor);
+            }
+            anchor
+        });
         Self {
-            data: Rc::new(RefCell::new(FrameData {
-                name: name.into(),
-                parent: None,
-                children: Vec::new(),
-                transform_to_parent: Isometry3::identity(),
-            })),
+            data,
+            anchor: Some(anchor),
+        }
+    }
+
+    /// Returns the root of the tree of this frame.
+    fn tree_root(&self) -> Rc<RefCell<FrameData<T>>> {
+        self.anchor.as_ref().map_or_else(
+            || tree_root(&self.data),
+            |anchor| anchor.root.borrow().clone(),
+        )
+    }
+
+    /// Points the anchors of this frame and its descendants to the root of their tree, after the
+    /// subtree has been moved to another tree.
+    fn reanchor(&self) {
+        let root = tree_root(&self.data);
+        let mut anchors: Vec<_> = self.anchor.iter().cloned().collect();
+        let mut stack = vec![self.data.clone()];
+        while let Some(data) = stack.pop() {
+            let node = data.borrow();
+            anchors.extend(node.anchor.borrow().upgrade());
+            stack.extend(node.children.iter().map(|child| child.data.clone()));
         }
+        // Releasing the old root may free the old tree, so no frame is borrowed here.
+        let old_roots: Vec<_> = anchors
+            .iter()
+            .map(|anchor| anchor.root.replace(root.clone()))
+            .collect();
+        drop(old_roots);
     }
 
-    pub(crate) fn borrow(&self) -> std::cell::Ref<'_, FrameData> {
+    pub(crate) fn borrow(&self) -> core::cell::Ref<'_, FrameData<T>> {
         self.data.borrow()
     }
 
-    fn borrow_mut(&self) -> std::cell::RefMut<'_, FrameData> {
+    fn borrow_mut(&self) -> core::cell::RefMut<'_, FrameData<T>> {
         self.data.borrow_mut()
     }
 
-    pub(crate) fn downgrade(&self) -> Weak<RefCell<FrameData>> {
+    /// Borrows the frame data for an update, reporting a conflicting access as an error instead of
+    /// panicking.
+    pub(crate) fn try_borrow_mut(
+        &self,
+    ) -> Result<core::cell::RefMut<'_, FrameData<T>>, CartesianTreeError> {
+        self.data
+            .try_borrow_mut()
+            .map_err(|_| CartesianTreeError::ConcurrentAccess)
+    }
+
+    /// Returns the parent frame, reporting a conflicting access as an error instead of panicking.
+    pub(crate) fn try_parent(&self) -> Result<Option<Self>, CartesianTreeError> {
+        let data = self
+            .data
+            .try_borrow()
+            .map_err(|_| CartesianTreeError::ConcurrentAccess)?;
+        Ok(data
+            .parent
+            .as_ref()
+            .and_then(Weak::upgrade)
+            .map(|data| self.related(data)))
+    }
+
+    pub(crate) fn downgrade(&self) -> Weak<RefCell<FrameData<T>>> {
         Rc::downgrade(&self.data)
     }
 
     pub(crate) fn walk_up_and_transform(
         &self,
         target: &Self,
-    ) -> Result<Isometry3<f64>, CartesianTreeError> {
-        let mut transform = Isometry3::identity();
+    ) -> Result<Isometry3<T>, CartesianTreeError> {
+        let (similarity, scaled) = self.walk_up_and_scale(target)?;
+        if scaled {
+            return Err(CartesianTreeError::ScaledTransform {
+                from: self.name(),
+                to: target.name(),
+            });
+        }
+        Ok(similarity.isometry)
+    }
+
+    /// Composes the transforms up to the ancestor `target` as a similarity, also reporting
+    /// whether a scaled frame was passed on the way.
+    fn walk_up_and_scale(
+        &self,
+        target: &Self,
+    ) -> Result<(Similarity3<T>, bool), CartesianTreeError> {
+        let mut transform = Similarity3::identity();
+        let mut scaled = false;
         let mut current = self.clone();
 
         while !current.is_same(target) {
@@ -99,1019 +767,6664 @@ impl Frame {
 
                 // If current frame is root and not target, then target is not an ancestor.
                 if current_data.parent.is_none() {
-                    return Err(CartesianTreeError::IsNoAncestor(target.name(), self.name()));
+                    return Err(CartesianTreeError::IsNoAncestor {
+                        ancestor: target.name(),
+                        frame: self.name(),
+                    });
                 }
-                current_data.transform_to_parent
+                scaled |= current_data.scale.is_some();
+                Similarity3::from_isometry(
+                    current_data.transform_to_parent,
+                    current_data.scale.map_or_else(T::one, from_f64),
+                )
             };
 
             transform = transform_to_its_parent * transform;
 
             let parent_frame_opt = current.parent();
-            current = parent_frame_opt
-                .ok_or_else(|| CartesianTreeError::IsNoAncestor(target.name(), self.name()))?;
-        }
-
-        Ok(transform)
-    }
-
-    /// Returns the name of the frame.
-    #[must_use]
-    pub fn name(&self) -> String {
-        self.borrow().name.clone()
-    }
-
-    /// Returns the transformation from this frame to its parent frame.
-    ///
-    /// # Returns
-    /// - The isometry from this frame to its parent frame.
-    ///
-    /// # Errors
-    /// Returns a [`CartesianTreeError`] if:
-    /// - The frame has no parent.
-    pub fn transformation(&self) -> Result<Isometry3<f64>, CartesianTreeError> {
-        if self.parent().is_none() {
-            return Err(CartesianTreeError::RootHasNoParent(self.name()));
+            current = parent_frame_opt.ok_or_else(|| CartesianTreeError::IsNoAncestor {
+                ancestor: target.name(),
+                frame: self.name(),
+            })?;
         }
-        Ok(self.borrow().transform_to_parent)
-    }
-
-    /// Returns the position of this frame relative to its parent frame.
-    ///
-    /// # Returns
-    /// The position of the frame in its parent frame.
-    #[must_use]
-    pub fn position(&self) -> Vector3<f64> {
-        self.borrow().transform_to_parent.translation.vector
-    }
 
-    /// Returns the orientation of this frame relative to its parent frame.
-    ///
-    /// # Returns
-    /// The orientation of the frame in its parent frame.
-    #[must_use]
-    pub fn orientation(&self) -> Rotation {
-        self.borrow().transform_to_parent.rotation.into()
+        Ok((transform, scaled))
     }
 
-    /// Sets the frame's transformation relative to its parent.
+    /// Returns the transformation from this frame to the target frame.
     ///
-    /// This method modifies the frame's position and orientation relative to its parent frame.
-    /// It fails if the frame is a root frame (i.e., has no parent).
+    /// The resulting isometry maps coordinates expressed in this frame to coordinates
+    /// expressed in `target`. The chain is resolved via the lowest common ancestor of both frames.
     ///
     /// # Arguments
-    /// - `position`: A 3D vector representing the new translational offset from the parent.
-    /// - `orientation`: An orientation convertible into a unit quaternion for new orientational offset from the parent.
+    /// - `target`: The frame to transform into.
     ///
     /// # Returns
-    /// - `Ok(())` if the transformation was updated successfully.
+    /// The isometry from this frame to the target frame (identity if both are the same frame).
     ///
     /// # Errors
     /// Returns a [`CartesianTreeError`] if:
-    /// - The frame has no parent (i.e., the root frame).
+    /// - The frames do not share a common ancestor (i.e., they belong to different trees).
+    /// - The chain passes through a scaled frame, see [`Frame::similarity_to`].
     ///
     /// # Example
     /// ```
     /// use cartesian_tree::Frame;
-    /// use nalgebra::{Vector3, UnitQuaternion};
+    /// use nalgebra::{Point3, Vector3, UnitQuaternion};
     ///
-    /// let root = Frame::new_origin("root");
-    /// let child = root
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let camera = root
     ///     .add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
     ///     .unwrap();
-    /// child.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
-    ///     .unwrap();
+    /// let camera_to_world = camera.transform_to(&root).unwrap();
+    /// assert_eq!(camera_to_world * Point3::origin(), Point3::new(0.0, 0.0, 1.0));
     /// ```
-    pub fn set(
-        &self,
-        position: Vector3<f64>,
-        orientation: impl Into<Rotation>,
-    ) -> Result<(), CartesianTreeError> {
-        if self.parent().is_none() {
-            return Err(CartesianTreeError::CannotUpdateRootTransform(self.name()));
+    pub fn transform_to(&self, target: &Self) -> Result<Isometry3<T>, CartesianTreeError> {
+        let (similarity, scaled) = self.resolve_similarity(target)?;
+        if scaled {
+            return Err(CartesianTreeError::ScaledTransform {
+                from: self.name(),
+                to: target.name(),
+            });
         }
-        self.borrow_mut().transform_to_parent = Isometry3::from_parts(
-            Translation3::from(position),
-            orientation.into().as_quaternion(),
-        );
-        Ok(())
+        Ok(similarity.isometry)
     }
 
-    /// Applies the provided isometry interpreted in the parent frame to this frame.
-    ///
-    /// This method modifies the frame's position and orientation relative to its current position and orientation.
-    /// It fails if the frame is a root frame (i.e., has no parent).
-    ///
-    /// # Arguments
-    /// - `isometry`: The isometry (describing a motion in the parent frame coordinates) to apply to the current transformation.
+    /// Returns the similarity transformation from this frame to the target frame.
     ///
-    /// # Returns
-    /// - `Ok(())` if the transformation was updated successfully.
+    /// Like [`Frame::transform_to`], but also resolves chains through frames created with
+    /// [`Frame::add_scaled_child`]. For chains without scaled frames the similarity has a
+    /// scaling of exactly one and the same isometry as [`Frame::transform_to`].
     ///
     /// # Errors
     /// Returns a [`CartesianTreeError`] if:
-    /// - The frame has no parent (i.e., the root frame).
+    /// - The frames do not share a common ancestor (i.e., they belong to different trees).
     ///
     /// # Example
     /// ```
     /// use cartesian_tree::Frame;
-    /// use nalgebra::{Isometry3, Translation3, Vector3, UnitQuaternion};
+    /// use nalgebra::{Point3, UnitQuaternion, Vector3};
     ///
-    /// let root = Frame::new_origin("root");
-    /// let child = root
-    ///     .add_child("camera", Vector3::new(1.0, 0.0, 1.0), UnitQuaternion::identity())
-    ///     .unwrap();
-    /// child.apply_in_parent_frame(&Isometry3::from_parts(Translation3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()))
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let scan = root
+    ///     .add_scaled_child("scan", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity(), 2.0)
     ///     .unwrap();
-    ///
+    /// let scan_to_world = scan.similarity_to(&root).unwrap();
+    /// assert_eq!(scan_to_world.scaling(), 2.0);
+    /// assert_eq!(scan_to_world * Point3::new(1.0, 0.0, 0.0), Point3::new(3.0, 0.0, 0.0));
+    /// assert!(scan.transform_to(&root).is_err());
     /// ```
-    pub fn apply_in_parent_frame(
+    pub fn similarity_to(&self, target: &Self) -> Result<Similarity3<T>, CartesianTreeError> {
+        Ok(self.resolve_similarity(target)?.0)
+    }
+
+    /// Resolves the similarity to `target` via the lowest common ancestor, also reporting
+    /// whether a scaled frame lies on the chain.
+    fn resolve_similarity(
         &self,
-        isometry: &Isometry3<f64>,
-    ) -> Result<(), CartesianTreeError> {
-        if self.parent().is_none() {
-            return Err(CartesianTreeError::CannotUpdateRootTransform(self.name()));
-        }
-        let mut borrow = self.borrow_mut();
-        borrow.transform_to_parent = isometry * borrow.transform_to_parent;
-        Ok(())
+        target: &Self,
+    ) -> Result<(Similarity3<T>, bool), CartesianTreeError> {
+        let ancestor =
+            self.lca_with(target)
+                .ok_or_else(|| CartesianTreeError::NoCommonAncestor {
+                    from: self.name(),
+                    to: target.name(),
+                })?;
+        let (tf_up, up_scaled) = self.walk_up_and_scale(&ancestor)?;
+        let (tf_down, down_scaled) = target.walk_up_and_scale(&ancestor)?;
+        Ok((tf_down.inverse() * tf_up, up_scaled || down_scaled))
     }
 
-    /// Applies the provided isometry interpreted in this frame to this frame.
-    ///
-    /// This method modifies the frame's position and orientation relative to its current position and orientation.
-    /// It fails if the frame is a root frame (i.e., has no parent).
+    /// Maps a point expressed in this frame into the `target` frame.
     ///
     /// # Arguments
-    /// - `isometry`: The isometry (describing a motion in this frame) to apply to the current transformation.
+    /// - `target`: The frame to express the point in.
+    /// - `point`: The point in this frame.
     ///
     /// # Returns
-    /// - `Ok(())` if the transformation was updated successfully.
+    /// The point expressed in the target frame.
     ///
     /// # Errors
     /// Returns a [`CartesianTreeError`] if:
-    /// - The frame has no parent (i.e., the root frame).
+    /// - The frames do not share a common ancestor (i.e., they belong to different trees).
     ///
     /// # Example
     /// ```
     /// use cartesian_tree::Frame;
-    /// use nalgebra::{Isometry3, Translation3, Vector3, UnitQuaternion};
+    /// use nalgebra::{Point3, Vector3, UnitQuaternion};
     ///
-    /// let root = Frame::new_origin("root");
-    /// let child = root
-    ///     .add_child("camera", Vector3::zeros(), UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2))
-    ///     .unwrap();
-    /// child.apply_in_local_frame(&Isometry3::from_parts(Translation3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()))
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let camera = root
+    ///     .add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
     ///     .unwrap();
-    ///
+    /// let point = camera.transform_point_to(&root, &Point3::new(1.0, 0.0, 0.0)).unwrap();
+    /// assert_eq!(point, Point3::new(1.0, 0.0, 1.0));
     /// ```
-    pub fn apply_in_local_frame(
+    pub fn transform_point_to(
         &self,
-        isometry: &Isometry3<f64>,
-    ) -> Result<(), CartesianTreeError> {
-        if self.parent().is_none() {
-            return Err(CartesianTreeError::CannotUpdateRootTransform(self.name()));
-        }
-        let mut borrow = self.borrow_mut();
-        borrow.transform_to_parent *= isometry;
-        Ok(())
+        target: &Self,
+        point: &Point3<T>,
+    ) -> Result<Point3<T>, CartesianTreeError> {
+        Ok(self.similarity_to(target)?.transform_point(point))
     }
 
-    /// Adds a new child frame to the current frame.
+    /// Maps a batch of points expressed in this frame into the `target` frame.
     ///
-    /// The child is positioned and oriented relative to this frame.
-    ///
-    /// Returns an error if a child with the same name already exists.
+    /// The transformation between the frames is resolved once and then applied to every point.
     ///
     /// # Arguments
-    /// - `name`: The name of the new child frame.
-    /// - `position`: A 3D vector representing the translational offset from the parent.
-    /// - `orientation`: An orientation convertible into a unit quaternion.
+    /// - `target`: The frame to express the points in.
+    /// - `points`: The points in this frame.
     ///
     /// # Returns
-    /// The newly added child frame.
+    /// The points expressed in the target frame, in the same order.
     ///
     /// # Errors
     /// Returns a [`CartesianTreeError`] if:
-    /// - A child with the same name already exists.
+    /// - The frames do not share a common ancestor (i.e., they belong to different trees).
     ///
     /// # Example
     /// ```
     /// use cartesian_tree::Frame;
-    /// use nalgebra::{Vector3, UnitQuaternion};
+    /// use nalgebra::{Point3, Vector3, UnitQuaternion};
     ///
-    /// let root = Frame::new_origin("base");
-    /// let child = root
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let camera = root
     ///     .add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
     ///     .unwrap();
+    /// let cloud = [Point3::new(1.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0)];
+    /// let points = camera.transform_points_to(&root, &cloud).unwrap();
+    /// assert_eq!(points[1], Point3::new(0.0, 1.0, 1.0));
     /// ```
-    pub fn add_child(
+    pub fn transform_points_to(
         &self,
-        name: impl Into<String>,
-        position: Vector3<f64>,
-        orientation: impl Into<Rotation>,
-    ) -> Result<Self, CartesianTreeError> {
-        let child_name = name.into();
-        {
-            let frame = self.borrow();
-            if frame
-                .children
-                .iter()
-                .any(|child| child.borrow().name == child_name)
-            {
-                return Err(CartesianTreeError::ChildNameConflict(
-                    child_name,
-                    self.name(),
-                ));
-            }
-        }
-        let transform = Isometry3::from_parts(
-            Translation3::from(position),
-            orientation.into().as_quaternion(),
-        );
+        target: &Self,
+        points: &[Point3<T>],
+    ) -> Result<Vec<Point3<T>>, CartesianTreeError> {
+        let similarity = self.similarity_to(target)?;
+        Ok(points
+            .iter()
+            .map(|point| similarity.transform_point(point))
+            .collect())
+    }
 
-        let child = Self {
-            data: Rc::new(RefCell::new(FrameData {
-                name: child_name,
-                parent: Some(Rc::downgrade(&self.data)),
-                children: Vec::new(),
-                transform_to_parent: transform,
-            })),
+    /// Returns the transformation from this frame to the root of its tree.
+    ///
+    /// The result is cached per frame and only recomputed for frames whose transform (or the
+    /// transform of one of their ancestors) has changed since the last query, so repeated queries
+    /// on an unchanged tree are O(1). Frames that are static, as are all their ancestors, are
+    /// only reached by overriding updates (see [`Frame::set_static`]), so their cache is
+    /// computed once and survives any number of updates of dynamic frames.
+    ///
+    /// # Returns
+    /// The isometry from this frame to its root (identity for the root itself).
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - This frame or an ancestor is scaled, see [`Frame::similarity_to`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let robot = root
+    ///     .add_child("robot", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let camera = robot
+    ///     .add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let world = camera.world_transform().unwrap();
+    /// assert_eq!(world.translation.vector, Vector3::new(1.0, 0.0, 1.0));
+    /// ```
+    pub fn world_transform(&self) -> Result<Isometry3<T>, CartesianTreeError> {
+        // Collect all frames up to the first one with an up-to-date cache (or the root).
+        let mut outdated = Vec::new();
+        let mut current = self.clone();
+        let mut world = loop {
+            let parent = {
+                let data = current.borrow();
+                if let Some(cached) = data.world_transform {
+                    break cached;
+                }
+                match &data.parent {
+                    Some(parent) => Some(parent.upgrade().ok_or_else(|| {
+                        CartesianTreeError::WeakUpgradeFailed {
+                            frame: data.parent_name.to_string(),
+                            operation: format!("world_transform walk from '{}'", data.name),
+                        }
+                    })?),
+                    None => None,
+                }
+            };
+            let Some(data) = parent else {
+                current.borrow_mut().world_transform = Some(Isometry3::identity());
+                break Isometry3::identity();
+            };
+            outdated.push(current);
+            current = Self::link(data);
         };
 
-        self.borrow_mut().children.push(child.clone());
-        Ok(child)
+        for frame in outdated.into_iter().rev() {
+            let mut data = frame.borrow_mut();
+            if data.scale.is_some() {
+                drop(data);
+                return Err(CartesianTreeError::ScaledTransform {
+                    from: self.name(),
+                    to: self.root().name(),
+                });
+            }
+            world *= data.transform_to_parent;
+            data.world_transform = Some(world);
+        }
+        Ok(world)
     }
 
-    /// Adds a new child frame calibrated such that a reference pose, when expressed in the new frame,
-    /// matches the desired position and orientation.
+    /// Sets the frame's transformation such that it ends up at the given pose relative to the root.
+    ///
+    /// The transform to the parent is computed from the parent's world transform, so the
+    /// frame's descendants move along with it.
     ///
     /// # Arguments
-    /// - `name`: The name of the new child frame.
-    /// - `desired_position`: The desired position of the reference pose in the new frame.
-    /// - `desired_orientation`: The desired orientation of the reference pose in the new frame.
-    /// - `reference_pose`: The existing pose (in some frame A) used as the calibration reference.
+    /// - `position`: The desired position of the frame relative to the root.
+    /// - `orientation`: The desired orientation of the frame relative to the root.
     ///
     /// # Returns
-    /// - The new child frame if successful.
+    /// - `Ok(())` if the transformation was updated successfully.
     ///
     /// # Errors
     /// Returns a [`CartesianTreeError`] if:
-    /// - The reference frame is invalid.
-    /// - No common ancestor exists.
-    /// - A child with the same name already exists.
+    /// - The frame has no parent (i.e., the root frame).
+    /// - The frame is static, see [`Frame::set_static`].
+    /// - A component of the new transform is NaN or infinite.
     ///
     /// # Example
     /// ```
     /// use cartesian_tree::Frame;
     /// use nalgebra::{Vector3, UnitQuaternion};
     ///
-    /// let root = Frame::new_origin("root");
-    /// let reference_pose = root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity());
-    /// let calibrated_child = root.calibrate_child(
-    ///     "calibrated",
-    ///     Vector3::zeros(),
-    ///     UnitQuaternion::identity(),
-    ///     &reference_pose,
-    /// ).unwrap();
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let robot = root
+    ///     .add_child("robot", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let camera = robot
+    ///     .add_child("camera", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// camera
+    ///     .set_world_transform(Vector3::new(1.0, 2.0, 3.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// assert_eq!(camera.position(), Vector3::new(0.0, 2.0, 3.0));
     /// ```
-    pub fn calibrate_child(
+    pub fn set_world_transform(
         &self,
-        name: impl Into<String>,
-        desired_position: Vector3<f64>,
-        desired_orientation: impl Into<Rotation>,
-        reference_pose: &Pose,
-    ) -> Result<Self, CartesianTreeError> {
-        let reference_frame = reference_pose.frame().ok_or_else(|| {
-            CartesianTreeError::FrameDropped("Reference pose frame has been dropped".to_string())
-        })?;
-
-        let ancestor = self.lca_with(&reference_frame).ok_or_else(|| {
-            CartesianTreeError::NoCommonAncestor(self.name(), reference_frame.name())
-        })?;
-
-        let t_reference_to_ancestor = reference_frame.walk_up_and_transform(&ancestor)?;
-        let t_pose_to_reference = reference_pose.transformation();
-        let t_pose_to_ancestor = t_reference_to_ancestor * t_pose_to_reference;
+        position: Vector3<T>,
+        orientation: impl Into<Rotation<T>>,
+    ) -> Result<(), CartesianTreeError> {
+        let parent = self
+            .try_parent()?
+            .ok_or_else(|| CartesianTreeError::CannotUpdateRootTransform { frame: self.name() })?;
+        self.check_dynamic()?;
+        let desired = Isometry3::from_parts(
+            Translation3::from(position),
+            orientation.into().as_quaternion(),
+        );
+        check_isometry("transform", &desired)?;
+        let transform = parent.world_transform()?.inverse() * desired;
+        let old = core::mem::replace(&mut self.try_borrow_mut()?.transform_to_parent, transform);
+        self.invalidate_world_transform();
+        self.record_audit(AuditOperation::Update, Some(old));
+        self.notify_transform_changed();
+        Ok(())
+    }
 
-        let t_parent_to_ancestor = self.walk_up_and_transform(&ancestor)?;
-        let t_ancestor_to_parent = t_parent_to_ancestor.inverse();
+    /// Marks the cached world transform of this frame and all its descendants as outdated.
+    fn invalidate_world_transform(&self) {
+        Self::invalidate_world_transforms([self.clone()]);
+    }
 
-        let desired_pose = Isometry3::from_parts(
-            Translation3::from(desired_position),
-            desired_orientation.into().as_quaternion(),
-        );
+    /// Marks the cached world transforms of `frames` and all their descendants as outdated in a
+    /// single pass, which visits shared descendants only once.
+    pub(crate) fn invalidate_world_transforms(frames: impl IntoIterator<Item = Self>) {
+        let mut stack: Vec<_> = frames.into_iter().collect();
+        while let Some(frame) = stack.pop() {
+            let mut data = frame.borrow_mut();
+            // Descendants of an outdated frame are outdated already.
+            if data.world_transform.take().is_some() {
+                stack.extend(data.children.iter().cloned());
+            }
+        }
+    }
 
-        let t_calibrated_to_parent =
-            t_pose_to_ancestor * desired_pose.inverse() * t_ancestor_to_parent;
+    /// Appends the change of the transform to the parent from `old` to the current one to the
+    /// audit log of the tree, if it is logged, see [`Frame::enable_audit`].
+    pub(crate) fn record_audit(&self, operation: AuditOperation, old: Option<Isometry3<T>>) {
+        #[cfg(feature = "std")]
+        {
+            let Some(log) = self.borrow().audit.clone() else {
+                return;
+            };
+            let source = log.borrow().source();
+            let entry = AuditEntry {
+                timestamp: std::time::SystemTime::now(),
+                frame_path: self.path(),
+                old_transform: old.map(isometry_to_f64),
+                new_transform: isometry_to_f64(self.borrow().transform_to_parent),
+                source,
+                operation,
+            };
+            log.borrow_mut().record(entry);
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = (operation, old);
+    }
 
-        self.add_child(
-            name,
-            t_calibrated_to_parent.translation.vector,
-            t_calibrated_to_parent.rotation,
-        )
+    /// Makes this frame and its descendants log to the audit log of the tree of `tree`, or to
+    /// none.
+    #[cfg(feature = "std")]
+    fn adopt_audit(&self, tree: Option<&Self>) {
+        let audit = tree.and_then(|tree| tree.borrow().audit.clone());
+        let unchanged = match (&self.borrow().audit, &audit) {
+            (Some(current), Some(new)) => Rc::ptr_eq(current, new),
+            (current, new) => current.is_none() && new.is_none(),
+        };
+        if !unchanged {
+            for frame in self.iter_dfs() {
+                frame.borrow_mut().audit.clone_from(&audit);
+            }
+        }
     }
 
-    /// Adds a pose to the current frame.
+    /// Registers a callback that is invoked after the transform of this frame changed.
+    ///
+    /// Equivalent to [`Frame::on_transform_changed_with`] with [`SubscriptionScope::Frame`].
     ///
     /// # Arguments
-    /// - `position`: The translational part of the pose.
-    /// - `orientation`: The orientational part of the pose.
+    /// - `callback`: Called with the changed frame.
     ///
     /// # Returns
-    /// - The newly added pose.
+    /// The id used to [`unsubscribe`](Frame::unsubscribe) the callback.
     ///
     /// # Example
     /// ```
     /// use cartesian_tree::Frame;
-    /// use nalgebra::{Vector3, UnitQuaternion};
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
     ///
-    /// let frame = Frame::new_origin("base");
-    /// let pose = frame.add_pose(Vector3::new(0.5, 0.0, 0.0), UnitQuaternion::identity());
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let arm = root
+    ///     .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let updates = Rc::new(Cell::new(0));
+    /// let counter = updates.clone();
+    /// arm.on_transform_changed(move |_| counter.set(counter.get() + 1));
+    ///
+    /// arm.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// assert_eq!(updates.get(), 1);
     /// ```
-    pub fn add_pose(&self, position: Vector3<f64>, orientation: impl Into<Rotation>) -> Pose {
-        Pose::new(self.downgrade(), position, orientation)
+    pub fn on_transform_changed(&self, callback: impl FnMut(&Self) + 'static) -> SubscriptionId {
+        self.on_transform_changed_with(SubscriptionScope::Frame, callback)
     }
 
-    /// Serializes the frame tree to a JSON string.
+    /// Registers a callback that is invoked after the transform of this frame, or with
+    /// [`SubscriptionScope::Subtree`] of any of its descendants, changed.
     ///
-    /// This recursively serializes the hierarchy starting from this frame (ideally the root).
-    /// Transforms for root frames are set to identity.
+    /// Callbacks run after [`Frame::set`], [`Frame::set_world_transform`],
+    /// [`Frame::apply_in_parent_frame`], [`Frame::apply_in_local_frame`], [`Frame::reparent`] and
+    /// [`Frame::apply_config`] and receive the changed frame. No frame is borrowed while they run,
+    /// so they may read and modify the tree. A callback is not re-entered by changes it makes
+    /// itself.
+    ///
+    /// The callback is owned by this frame; capturing the frame itself in it creates a reference
+    /// cycle, use the frame passed to the callback instead.
+    ///
+    /// # Arguments
+    /// - `scope`: Which changes notify the callback.
+    /// - `callback`: Called with the changed frame.
     ///
     /// # Returns
-    /// The serialized tree as a JSON string.
+    /// The id used to [`unsubscribe`](Frame::unsubscribe) the callback.
+    pub fn on_transform_changed_with(
+        &self,
+        scope: SubscriptionScope,
+        callback: impl FnMut(&Self) + 'static,
+    ) -> SubscriptionId {
+        let id = SubscriptionId(NEXT_SUBSCRIPTION_ID.fetch_add(1, Ordering::Relaxed));
+        self.borrow_mut().subscriptions.push(Subscription {
+            id,
+            scope,
+            callback: Rc::new(RefCell::new(callback)),
+        });
+        id
+    }
+
+    /// Removes a callback registered on this frame.
     ///
-    /// # Errors
-    /// Returns a [`CartesianTreeError`] if:
-    /// - On deserialization failure.
-    pub fn to_json(&self) -> Result<String, CartesianTreeError> {
-        let serial = self.to_serial();
-        Ok(serde_json::to_string_pretty(&serial)?)
+    /// # Arguments
+    /// - `id`: The id returned when the callback was registered.
+    ///
+    /// # Returns
+    /// `true` if the callback was registered on this frame and has been removed.
+    #[must_use]
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let removed = {
+            let mut data = self.borrow_mut();
+            let index = data
+                .subscriptions
+                .iter()
+                .position(|subscription| subscription.id == id);
+            index.map(|index| data.subscriptions.remove(index))
+        };
+        // Dropping the callback may run user code, so it happens after the borrow is released.
+        removed.is_some()
     }
 
-    /// Helper function to convert the frame and its children recursively into a serializable structure.
+    /// Notifies the changes of `frames` in turn, like [`Frame::notify_transform_changed`].
     ///
-    /// This is used internally for JSON serialization.
-    fn to_serial(&self) -> SerialFrame {
-        let (position, orientation) = if self.parent().is_some() {
-            let iso = self
-                .transformation()
-                .unwrap_or_else(|_| Isometry3::identity());
-            (iso.translation.vector, iso.rotation)
-        } else {
-            (Vector3::zeros(), UnitQuaternion::identity())
+    /// Changed frames often form long chains, e.g. when applying a configuration, so the frames
+    /// with subtree callbacks above each frame are looked up once for all of them. Callbacks
+    /// subscribed by the callbacks themselves take effect from the next change on.
+    pub(crate) fn notify_transforms_changed(frames: &[Self]) {
+        // The nearest frame with subtree callbacks among each visited frame and its ancestors,
+        // keyed by address. The frames are kept, so their addresses stay unique.
+        let mut nearest: BTreeMap<usize, (Self, Option<Self>)> = BTreeMap::new();
+        let mut nearest_subscribed = |frame: Option<Self>| {
+            let mut walked = Vec::new();
+            let mut current = frame;
+            let found = loop {
+                let Some(frame) = current else { break None };
+                if let Some((_, known)) = nearest.get(&(Rc::as_ptr(&frame.data) as usize)) {
+                    break known.clone();
+                }
+                if frame
+                    .borrow()
+                    .subscriptions
+                    .iter()
+                    .any(|subscription| subscription.scope == SubscriptionScope::Subtree)
+                {
+                    break Some(frame);
+                }
+                current = frame.parent();
+                walked.push(frame);
+            };
+            for frame in walked {
+                nearest.insert(Rc::as_ptr(&frame.data) as usize, (frame, found.clone()));
+            }
+            found
         };
+        for frame in frames {
+            let mut callbacks: Vec<_> = frame
+                .borrow()
+                .subscriptions
+                .iter()
+                .map(|subscription| subscription.callback.clone())
+                .collect();
+            let mut ancestor = nearest_subscribed(frame.parent());
+            while let Some(subscribed) = ancestor {
+                callbacks.extend(
+                    subscribed
+                        .borrow()
+                        .subscriptions
+                        .iter()
+                        .filter(|subscription| subscription.scope == SubscriptionScope::Subtree)
+                        .map(|subscription| subscription.callback.clone()),
+                );
+                ancestor = nearest_subscribed(subscribed.parent());
+            }
+            for callback in callbacks {
+                // A callback that is still running triggered this change itself.
+                if let Ok(mut callback) = callback.try_borrow_mut() {
+                    callback(frame);
+                }
+            }
+        }
+    }
 
-        SerialFrame {
-            name: self.name(),
-            position,
-            orientation,
-            children: self.children().into_iter().map(|c| c.to_serial()).collect(),
+    /// Invokes the callbacks of this frame and the subtree callbacks of its ancestors.
+    pub(crate) fn notify_transform_changed(&self) {
+        let mut callbacks = Vec::new();
+        let mut current = Some(self.clone());
+        while let Some(frame) = current {
+            callbacks.extend(
+                frame
+                    .borrow()
+                    .subscriptions
+                    .iter()
+                    .filter(|subscription| {
+                        subscription.scope == SubscriptionScope::Subtree || frame.is_same(self)
+                    })
+                    .map(|subscription| subscription.callback.clone()),
+            );
+            current = frame.parent();
+        }
+        for callback in callbacks {
+            // A callback that is still running triggered this change itself.
+            if let Ok(mut callback) = callback.try_borrow_mut() {
+                callback(self);
+            }
         }
     }
 
-    /// Applies a JSON config to this frame tree by updating matching transforms.
+    /// Returns the name of the frame.
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.borrow().name.to_string()
+    }
+
+    /// Returns the name of the frame without copying it.
     ///
-    /// Deserializes the JSON to a temporary structure, then recursively updates transforms
-    /// where names match (partial apply; ignores unmatched frames in config).
-    /// Skips updating root frames (identity assumed) - assumes this frame is the root.
+    /// Names are interned, so frames with equal names share one allocation, e.g. the many
+    /// `base_link`s of a tree imported from URDF files.
     ///
-    /// # Arguments
-    /// - `json`: The JSON string to apply.
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    ///
+    /// let origin = Frame::new_origin("world").unwrap();
+    /// assert_eq!(&*origin.shared_name(), "world");
+    /// ```
+    #[must_use]
+    pub fn shared_name(&self) -> Rc<str> {
+        self.borrow().name.clone()
+    }
+
+    /// Returns the transformation from this frame to its parent frame.
     ///
     /// # Returns
-    /// `Ok(())` if applied successfully (even if partial).
+    /// - The isometry from this frame to its parent frame.
     ///
     /// # Errors
     /// Returns a [`CartesianTreeError`] if:
-    /// - On deserialization failure.
-    /// - The frame names do not match at the root.
-    ///
-    pub fn apply_config(&self, json: &str) -> Result<(), CartesianTreeError> {
-        let serial: SerialFrame = serde_json::from_str(json)?;
-        self.apply_serial(&serial)
-    }
-
-    fn apply_serial(&self, serial: &SerialFrame) -> Result<(), CartesianTreeError> {
-        if self.name() != serial.name {
-            return Err(CartesianTreeError::Mismatch(format!(
-                "Frame names do not match: {} vs {}",
-                self.name(),
-                serial.name
-            )));
-        }
-
-        // only update if frame has parent
-        if self.parent().is_some() {
-            self.set(serial.position, serial.orientation)?;
-        }
-
-        for potential_child in &serial.children {
-            if let Some(child) = self
-                .children()
-                .into_iter()
-                .find(|c| c.name() == potential_child.name)
-            {
-                child.apply_serial(potential_child)?;
-            }
+    /// - The frame has no parent.
+    pub fn transformation(&self) -> Result<Isometry3<T>, CartesianTreeError> {
+        if self.parent().is_none() {
+            return Err(CartesianTreeError::RootHasNoParent { frame: self.name() });
         }
-
-        Ok(())
+        Ok(self.borrow().transform_to_parent)
     }
-}
 
-impl Add<LazyTranslation> for &Frame {
-    type Output = Frame;
+    /// Returns the position of this frame relative to its parent frame.
+    ///
+    /// # Returns
+    /// The position of the frame in its parent frame.
+    #[must_use]
+    pub fn position(&self) -> Vector3<T> {
+        self.borrow().transform_to_parent.translation.vector
+    }
 
-    fn add(self, rhs: LazyTranslation) -> Self::Output {
-        let auto_name = Uuid::new_v4().to_string();
-        let current_position = self.borrow().transform_to_parent.translation.vector;
-        let current_orientation = self.borrow().transform_to_parent.rotation;
-        let child = self
-            .add_child(auto_name, current_position, current_orientation)
-            .unwrap();
-        child.apply_in_parent_frame(&rhs.inner).unwrap();
-        child // Not sure yet what to do with errors
+    /// Returns the orientation of this frame relative to its parent frame.
+    ///
+    /// # Returns
+    /// The orientation of the frame in its parent frame.
+    #[must_use]
+    pub fn orientation(&self) -> Rotation<T> {
+        self.borrow().transform_to_parent.rotation.into()
     }
-}
 
-impl Sub<LazyTranslation> for &Frame {
-    type Output = Frame;
+    /// Marks the frame as static, i.e. its transform to the parent is not expected to change
+    /// after construction, or as dynamic again.
+    ///
+    /// Frames are dynamic when created. Updates of a static frame, e.g. [`Frame::set`] or
+    /// [`Frame::set_joint_value`], fail with [`CartesianTreeError::StaticFrame`] unless
+    /// [`StaticPolicy::Override`] is passed to [`Frame::set_with`]. Only the frame itself is
+    /// marked, not its descendants.
+    ///
+    /// Static frames share the world-transform cache of dynamic ones, without special tracking:
+    /// as an update only invalidates the cache of the updated frame and its descendants, the
+    /// cache of a frame whose ancestors are all static is computed once, unless one of them is
+    /// updated with [`StaticPolicy::Override`]. The flag is
+    /// persisted by [`Frame::to_json`] and decides whether a frame is published on `/tf` or
+    /// `/tf_static` by the `ros2` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::frame::StaticPolicy;
+    /// use cartesian_tree::{CartesianTreeError, Frame};
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let mount = root
+    ///     .add_child("camera_mount", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// mount.set_static(true);
+    /// assert!(mount.is_static());
+    ///
+    /// let moved = Vector3::new(0.0, 0.0, 2.0);
+    /// assert!(matches!(
+    ///     mount.set(moved, UnitQuaternion::identity()),
+    ///     Err(CartesianTreeError::StaticFrame { .. })
+    /// ));
+    /// mount
+    ///     .set_with(moved, UnitQuaternion::identity(), StaticPolicy::Override)
+    ///     .unwrap();
+    /// assert_eq!(mount.position(), moved);
+    /// ```
+    pub fn set_static(&self, is_static: bool) {
+        self.borrow_mut().is_static = is_static;
+    }
 
-    fn sub(self, rhs: LazyTranslation) -> Self::Output {
-        let auto_name = Uuid::new_v4().to_string();
-        let current_position = self.borrow().transform_to_parent.translation.vector;
-        let current_orientation = self.borrow().transform_to_parent.rotation;
-        let child = self
-            .add_child(auto_name, current_position, current_orientation)
-            .unwrap();
-        child.apply_in_parent_frame(&rhs.inner.inverse()).unwrap();
-        child // Not sure yet what to do with errors
+    /// Returns whether the frame is marked as static, see [`Frame::set_static`].
+    #[must_use]
+    pub fn is_static(&self) -> bool {
+        self.borrow().is_static
     }
-}
 
-impl Mul<LazyRotation> for &Frame {
-    type Output = Frame;
+    /// Returns [`CartesianTreeError::StaticFrame`] if the frame is static.
+    pub(crate) fn check_dynamic(&self) -> Result<(), CartesianTreeError> {
+        let data = self
+            .data
+            .try_borrow()
+            .map_err(|_| CartesianTreeError::ConcurrentAccess)?;
+        if data.is_static {
+            return Err(CartesianTreeError::StaticFrame {
+                frame: data.name.to_string(),
+            });
+        }
+        Ok(())
+    }
 
-    fn mul(self, rhs: LazyRotation) -> Self::Output {
-        let auto_name = Uuid::new_v4().to_string();
-        let current_position = self.borrow().transform_to_parent.translation.vector;
-        let current_orientation = self.borrow().transform_to_parent.rotation;
-        let child = self
+    /// Sets the frame's transformation relative to its parent.
+    ///
+    /// This method modifies the frame's position and orientation relative to its parent frame.
+    /// It fails if the frame is a root frame (i.e., has no parent).
+    ///
+    /// # Arguments
+    /// - `position`: A 3D vector representing the new translational offset from the parent.
+    /// - `orientation`: An orientation convertible into a unit quaternion for new orientational offset from the parent.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the transformation was updated successfully.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame has no parent (i.e., the root frame).
+    /// - The frame is static, see [`Frame::set_static`].
+    /// - A component of the new transform is NaN or infinite.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let child = root
+    ///     .add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// child.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// ```
+    pub fn set(
+        &self,
+        position: Vector3<T>,
+        orientation: impl Into<Rotation<T>>,
+    ) -> Result<(), CartesianTreeError> {
+        self.set_with(position, orientation, StaticPolicy::Reject)
+    }
+
+    /// Sets the frame's transformation relative to its parent, like [`Frame::set`], but
+    /// updates static frames if `policy` is [`StaticPolicy::Override`].
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame has no parent (i.e., the root frame).
+    /// - The frame is static and `policy` is [`StaticPolicy::Reject`].
+    /// - A component of the new transform is NaN or infinite.
+    pub fn set_with(
+        &self,
+        position: Vector3<T>,
+        orientation: impl Into<Rotation<T>>,
+        policy: StaticPolicy,
+    ) -> Result<(), CartesianTreeError> {
+        if self.try_parent()?.is_none() {
+            return Err(CartesianTreeError::CannotUpdateRootTransform { frame: self.name() });
+        }
+        if policy == StaticPolicy::Reject {
+            self.check_dynamic()?;
+        }
+        let orientation = orientation.into().as_quaternion();
+        check_position("position", &position)?;
+        check_orientation("orientation", &orientation)?;
+        let old = core::mem::replace(
+            &mut self.try_borrow_mut()?.transform_to_parent,
+            Isometry3::from_parts(Translation3::from(position), orientation),
+        );
+        self.invalidate_world_transform();
+        self.record_audit(AuditOperation::Update, Some(old));
+        self.notify_transform_changed();
+        Ok(())
+    }
+
+    /// Applies the provided isometry interpreted in the parent frame to this frame.
+    ///
+    /// This method modifies the frame's position and orientation relative to its current position and orientation.
+    /// It fails if the frame is a root frame (i.e., has no parent).
+    ///
+    /// # Arguments
+    /// - `isometry`: The isometry (describing a motion in the parent frame coordinates) to apply to the current transformation.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the transformation was updated successfully.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame has no parent (i.e., the root frame).
+    /// - The frame is static, see [`Frame::set_static`].
+    /// - A component of the new transform is NaN or infinite.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Isometry3, Translation3, Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let child = root
+    ///     .add_child("camera", Vector3::new(1.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// child.apply_in_parent_frame(&Isometry3::from_parts(Translation3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()))
+    ///     .unwrap();
+    ///
+    /// ```
+    pub fn apply_in_parent_frame(&self, isometry: &Isometry3<T>) -> Result<(), CartesianTreeError> {
+        if self.try_parent()?.is_none() {
+            return Err(CartesianTreeError::CannotUpdateRootTransform { frame: self.name() });
+        }
+        self.check_dynamic()?;
+        check_isometry("isometry", isometry)?;
+        let old = {
+            let mut borrow = self.try_borrow_mut()?;
+            let old = borrow.transform_to_parent;
+            borrow.transform_to_parent = isometry * old;
+            old
+        };
+        self.invalidate_world_transform();
+        self.record_audit(AuditOperation::Update, Some(old));
+        self.notify_transform_changed();
+        Ok(())
+    }
+
+    /// Applies the provided isometry interpreted in this frame to this frame.
+    ///
+    /// This method modifies the frame's position and orientation relative to its current position and orientation.
+    /// It fails if the frame is a root frame (i.e., has no parent).
+    ///
+    /// # Arguments
+    /// - `isometry`: The isometry (describing a motion in this frame) to apply to the current transformation.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the transformation was updated successfully.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame has no parent (i.e., the root frame).
+    /// - The frame is static, see [`Frame::set_static`].
+    /// - A component of the new transform is NaN or infinite.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Isometry3, Translation3, Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let child = root
+    ///     .add_child("camera", Vector3::zeros(), UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2))
+    ///     .unwrap();
+    /// child.apply_in_local_frame(&Isometry3::from_parts(Translation3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()))
+    ///     .unwrap();
+    ///
+    /// ```
+    pub fn apply_in_local_frame(&self, isometry: &Isometry3<T>) -> Result<(), CartesianTreeError> {
+        if self.try_parent()?.is_none() {
+            return Err(CartesianTreeError::CannotUpdateRootTransform { frame: self.name() });
+        }
+        self.check_dynamic()?;
+        check_isometry("isometry", isometry)?;
+        let old = {
+            let mut borrow = self.try_borrow_mut()?;
+            let old = borrow.transform_to_parent;
+            borrow.transform_to_parent = old * isometry;
+            old
+        };
+        self.invalidate_world_transform();
+        self.record_audit(AuditOperation::Update, Some(old));
+        self.notify_transform_changed();
+        Ok(())
+    }
+
+    /// Moves this frame by `delta`, expressed in this frame or in its parent.
+    ///
+    /// With `T` the transform to the parent, [`DeltaSpace::Local`] sets it to `T * delta`, like
+    /// [`Frame::apply_in_local_frame`], and [`DeltaSpace::Parent`] to `delta * T`, like
+    /// [`Frame::apply_in_parent_frame`]. See [`DeltaSpace`] for how the results differ.
+    ///
+    /// # Arguments
+    /// - `delta`: The motion to apply.
+    /// - `space`: The frame `delta` is expressed in.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame has no parent (i.e., the root frame).
+    /// - The frame is static, see [`Frame::set_static`].
+    /// - A component of the new transform is NaN or infinite.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::frame::DeltaSpace;
+    /// use nalgebra::{Isometry3, UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// // The tool's X axis points along the Y axis of the world.
+    /// let tool = root
+    ///     .add_child("tool", Vector3::zeros(), UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2))
+    ///     .unwrap();
+    /// // 2 mm along the X axis of the tool and 0.5° about its Z axis.
+    /// let nudge = Isometry3::new(Vector3::new(0.002, 0.0, 0.0), Vector3::z() * 0.5_f64.to_radians());
+    /// tool.apply_delta(nudge, DeltaSpace::Local).unwrap();
+    /// assert!((tool.position() - Vector3::new(0.0, 0.002, 0.0)).norm() < 1e-12);
+    /// ```
+    pub fn apply_delta(
+        &self,
+        delta: Isometry3<T>,
+        space: DeltaSpace,
+    ) -> Result<(), CartesianTreeError> {
+        match space {
+            DeltaSpace::Local => self.apply_in_local_frame(&delta),
+            DeltaSpace::Parent => self.apply_in_parent_frame(&delta),
+        }
+    }
+
+    /// Adds a new child frame to the current frame.
+    ///
+    /// The child is positioned and oriented relative to this frame.
+    ///
+    /// Returns an error if a child with the same name already exists.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new child frame.
+    /// - `position`: The translational offset from the parent, e.g. a [`Vector3`] or `[x, y, z]`.
+    /// - `orientation`: An orientation convertible into a [`Rotation`], e.g. a unit quaternion,
+    ///   `[x, y, z, w]` quaternion components or `[roll, pitch, yaw]` in radians.
+    ///
+    /// # Returns
+    /// The newly added child frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The name violates the [`FrameNameRules`] of the tree.
+    /// - A child with the same name already exists.
+    /// - A component of the new transform is NaN or infinite.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("base").unwrap();
+    /// let child = root
+    ///     .add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// // Plain arrays: quaternion components in the order [x, y, z, w] ...
+    /// let tool = root.add_child("tool", [0.1, 0.0, 0.2], [0.0, 0.0, 0.0, 1.0]).unwrap();
+    /// // ... or roll, pitch and yaw in radians.
+    /// let flange = root.add_child("flange", [0.0, 0.0, 0.5], [0.0, 0.0, 1.57]).unwrap();
+    /// ```
+    pub fn add_child(
+        &self,
+        name: impl Into<String>,
+        position: impl Into<Vector3<T>>,
+        orientation: impl Into<Rotation<T>>,
+    ) -> Result<Self, CartesianTreeError> {
+        let child_name = name.into();
+        // Conversions are done before borrowing, as they may run user code.
+        let position = position.into();
+        let orientation = orientation.into().as_quaternion();
+        check_position("position", &position)?;
+        check_orientation("orientation", &orientation)?;
+        let transform = Isometry3::from_parts(Translation3::from(position), orientation);
+
+        let mut frame = self.try_borrow_mut()?;
+        check_frame_name(&child_name, frame.name_rules)?;
+        let interned = intern(&child_name);
+        if frame
+            .children
+            .iter()
+            .any(|child| same_name(&child.borrow().name, &interned))
+        {
+            return Err(CartesianTreeError::DuplicateChildName {
+                parent: frame.name.to_string(),
+                child: child_name,
+            });
+        }
+
+        let child = Rc::new(RefCell::new(FrameData {
+            name: interned,
+            parent: Some(Rc::downgrade(&self.data)),
+            parent_name: frame.name.clone(),
+            children: Vec::new(),
+            transform_to_parent: transform,
+            world_transform: None,
+            metadata: BTreeMap::new(),
+            poses: BTreeMap::new(),
+            regions: BTreeMap::new(),
+            subscriptions: Vec::new(),
+            name_rules: frame.name_rules,
+            history: None,
+            twist: None,
+            joint: None,
+            scale: None,
+            is_static: false,
+            #[cfg(feature = "std")]
+            audit: frame.audit.clone(),
+            canonical_unit: None,
+            anchor: RefCell::default(),
+            pose_entries: RefCell::default(),
+        }));
+
+        frame.children.push(Self::link(child.clone()));
+        drop(frame);
+        Ok(self.related(child))
+    }
+
+    /// Searches this frame and its descendants (depth-first) for a frame with the given name.
+    ///
+    /// Names are only unique among siblings, so the first match in depth-first pre-order is
+    /// returned. Use [`Frame::find_all`] to get every match.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the frame to search for.
+    ///
+    /// # Returns
+    /// The first frame with the given name, or `None` if there is no such frame in the subtree.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let robot = root
+    ///     .add_child("robot", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// robot
+    ///     .add_child("camera", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// assert_eq!(root.find("camera").unwrap().name(), "camera");
+    /// assert!(root.find("lidar").is_none());
+    /// ```
+    #[must_use]
+    pub fn find(&self, name: &str) -> Option<Self> {
+        // Names no frame has are not interned, which spares the walk.
+        let name = lookup(name)?;
+        self.iter_dfs()
+            .find(|frame| same_name(&frame.borrow().name, &name))
+    }
+
+    /// Searches this frame and its descendants for all frames with the given name.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the frames to search for.
+    ///
+    /// # Returns
+    /// All frames with the given name in depth-first pre-order.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// for arm in ["left", "right"] {
+    ///     root.add_child(arm, Vector3::zeros(), UnitQuaternion::identity())
+    ///         .unwrap()
+    ///         .add_child("gripper", Vector3::zeros(), UnitQuaternion::identity())
+    ///         .unwrap();
+    /// }
+    /// assert_eq!(root.find_all("gripper").len(), 2);
+    /// ```
+    #[must_use]
+    pub fn find_all(&self, name: &str) -> Vec<Self> {
+        let Some(name) = lookup(name) else {
+            return Vec::new();
+        };
+        self.iter_dfs()
+            .filter(|frame| same_name(&frame.borrow().name, &name))
+            .collect()
+    }
+
+    /// Returns an iterator over this frame and its descendants in depth-first pre-order.
+    ///
+    /// The iterator yields owned frame handles and does not keep any frame borrowed between
+    /// two items, so the transforms of visited frames can be modified while iterating.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let a = root.add_child("a", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// a.add_child("a1", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// root.add_child("b", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    ///
+    /// let names: Vec<String> = root.iter_dfs().map(|frame| frame.name()).collect();
+    /// assert_eq!(names, ["world", "a", "a1", "b"]);
+    /// ```
+    pub fn iter_dfs(&self) -> impl Iterator<Item = Self> + use<T> {
+        DepthFirst::new(self.clone())
+    }
+
+    /// Returns an iterator over this frame and its descendants in breadth-first (level) order.
+    ///
+    /// The iterator yields owned frame handles and does not keep any frame borrowed between
+    /// two items, so the transforms of visited frames can be modified while iterating.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let a = root.add_child("a", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// a.add_child("a1", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// root.add_child("b", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    ///
+    /// let names: Vec<String> = root.iter_bfs().map(|frame| frame.name()).collect();
+    /// assert_eq!(names, ["world", "a", "b", "a1"]);
+    /// ```
+    pub fn iter_bfs(&self) -> impl Iterator<Item = Self> + use<T> {
+        BreadthFirst::new(self.clone())
+    }
+
+    /// Returns an iterator over the ancestors of this frame, from its parent up to the root.
+    ///
+    /// Like [`Frame::iter_dfs`], the iterator does not keep any frame borrowed between two items.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let robot = root.add_child("robot", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// let camera = robot.add_child("camera", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    ///
+    /// let names: Vec<String> = camera.ancestors().map(|frame| frame.name()).collect();
+    /// assert_eq!(names, ["robot", "world"]);
+    /// ```
+    pub fn ancestors(&self) -> impl Iterator<Item = Self> + use<T> {
+        Ancestors::new(self)
+    }
+
+    /// Returns whether this frame is a (strict) ancestor of `other`, i.e. whether `other` lies in
+    /// the subtree below this frame.
+    ///
+    /// A frame is not an ancestor of itself.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let robot = root.add_child("robot", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// let camera = robot.add_child("camera", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    ///
+    /// assert!(root.is_ancestor_of(&camera));
+    /// assert!(!camera.is_ancestor_of(&robot));
+    /// assert!(!robot.is_ancestor_of(&robot));
+    /// ```
+    #[must_use]
+    pub fn is_ancestor_of(&self, other: &Self) -> bool {
+        other.ancestors().any(|ancestor| ancestor.is_same(self))
+    }
+
+    /// Returns whether this frame is a (strict) descendant of `other`.
+    ///
+    /// Equivalent to `other.is_ancestor_of(self)`, see [`Frame::is_ancestor_of`].
+    #[must_use]
+    pub fn is_descendant_of(&self, other: &Self) -> bool {
+        other.is_ancestor_of(self)
+    }
+
+    /// Returns the lowest common ancestor of this frame and `other`.
+    ///
+    /// This is the deepest frame whose subtree contains both frames. A frame counts as part of
+    /// its own subtree here, so the result is this frame if `other` is one of its descendants
+    /// (or the frame itself).
+    ///
+    /// # Returns
+    /// The lowest common ancestor, or `None` if the frames belong to different trees.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let robot = root.add_child("robot", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// let camera = robot.add_child("camera", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// let gripper = robot.add_child("gripper", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    ///
+    /// assert_eq!(camera.lowest_common_ancestor(&gripper).unwrap().name(), "robot");
+    /// assert_eq!(robot.lowest_common_ancestor(&camera).unwrap().name(), "robot");
+    /// assert!(camera.lowest_common_ancestor(&Frame::new_origin("other").unwrap()).is_none());
+    /// ```
+    #[must_use]
+    pub fn lowest_common_ancestor(&self, other: &Self) -> Option<Self> {
+        self.lca_with(other)
+    }
+
+    /// Returns the absolute path of this frame.
+    ///
+    /// The path consists of the frame names from the root down to this frame, each prefixed by a
+    /// `/`, e.g. `/world/robot/camera`. It can be resolved again via [`Frame::resolve_path`] from
+    /// any frame of the same tree.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let camera = root
+    ///     .add_child("camera", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// assert_eq!(camera.path(), "/world/camera");
+    /// ```
+    #[must_use]
+    pub fn path(&self) -> String {
+        let mut names = vec![self.name()];
+        let mut current = self.parent();
+        while let Some(frame) = current {
+            names.push(frame.name());
+            current = frame.parent();
+        }
+        join_path(&names)
+    }
+
+    /// Like [`Frame::path`], but returns `None` if a frame on the way is being updated.
+    fn try_path(&self) -> Option<String> {
+        let mut names = Vec::new();
+        let mut current = Some(self.clone());
+        while let Some(frame) = current {
+            names.push(frame.data.try_borrow().ok()?.name.to_string());
+            current = frame.try_parent().ok()?;
+        }
+        Some(join_path(&names))
+    }
+
+    /// Resolves a `/`-separated path to a frame.
+    ///
+    /// Relative paths (e.g. `robot/camera`) are resolved starting at this frame by walking down
+    /// the children with the given names. Absolute paths (starting with `/`, as returned by
+    /// [`Frame::path`]) start at the root, whose name must match the first segment.
+    /// The segment `..` moves to the parent and `.` stays at the current frame.
+    ///
+    /// # Arguments
+    /// - `path`: The path to resolve.
+    ///
+    /// # Returns
+    /// The frame the path points to.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - A segment does not name an existing child (or the root for absolute paths).
+    /// - A `..` segment is applied to a root frame.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let robot = root
+    ///     .add_child("robot", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let camera = robot
+    ///     .add_child("camera", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// assert_eq!(root.resolve_path("robot/camera").unwrap().name(), "camera");
+    /// assert_eq!(camera.resolve_path("../..").unwrap().name(), "world");
+    /// assert_eq!(camera.resolve_path(&camera.path()).unwrap().name(), "camera");
+    /// ```
+    pub fn resolve_path(&self, path: &str) -> Result<Self, CartesianTreeError> {
+        let unresolved = |segment: &str| CartesianTreeError::PathNotFound {
+            path: path.to_string(),
+            segment: segment.to_string(),
+        };
+
+        let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+        let mut current = if path.starts_with('/') {
+            let root = self.root();
+            let first = segments.next().unwrap_or_default();
+            if *root.borrow().name != *first {
+                return Err(unresolved(first));
+            }
+            root
+        } else {
+            self.clone()
+        };
+
+        for segment in segments {
+            current = match segment {
+                "." => current,
+                ".." => current.parent().ok_or_else(|| unresolved(segment))?,
+                name => {
+                    // Names no frame has are not interned, which spares the comparisons.
+                    let name = lookup(name).ok_or_else(|| unresolved(segment))?;
+                    current
+                        .children()
+                        .into_iter()
+                        .find(|child| same_name(&child.borrow().name, &name))
+                        .ok_or_else(|| unresolved(segment))?
+                }
+            };
+        }
+        Ok(current)
+    }
+
+    /// Adds a pose to the current frame.
+    ///
+    /// # Arguments
+    /// - `position`: The translational part of the pose, e.g. a [`Vector3`] or `[x, y, z]`.
+    /// - `orientation`: The orientational part of the pose, convertible into a [`Rotation`].
+    ///
+    /// # Returns
+    /// - The newly added pose.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::NonFiniteValue`] if a component of the position or
+    /// orientation is NaN or infinite.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let frame = Frame::new_origin("base").unwrap();
+    /// let pose = frame.add_pose(Vector3::new(0.5, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    ///
+    /// // Quaternion components are ordered [x, y, z, w].
+    /// let grasp = frame.add_pose([0.5, 0.0, 0.1], [1.0, 0.0, 0.0, 0.0]).unwrap();
+    /// assert_eq!(grasp.position(), Vector3::new(0.5, 0.0, 0.1));
+    /// ```
+    pub fn add_pose(
+        &self,
+        position: impl Into<Vector3<T>>,
+        orientation: impl Into<Rotation<T>>,
+    ) -> Result<Pose<T>, CartesianTreeError> {
+        let position = position.into();
+        let orientation = orientation.into();
+        check_position("position", &position)?;
+        check_orientation("orientation", &orientation.as_quaternion())?;
+        Ok(Pose::new(self, position, orientation))
+    }
+
+    /// Returns copies of all live poses expressed in this frame, in the order they were created.
+    ///
+    /// Every pose counts, whether it was created by [`Frame::add_pose`], converted by
+    /// [`Pose::in_frame`] or cloned; dropped poses are skipped. The copies reflect the latest
+    /// changes of the poses, but are not registered themselves, so they change neither the
+    /// list nor [`Frame::pose_count`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let frame = Frame::new_origin("base").unwrap();
+    /// let mut grasp = frame.add_pose(Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// let place = frame.add_pose(Vector3::x(), UnitQuaternion::identity()).unwrap();
+    /// grasp.set_position(Vector3::z()).unwrap();
+    /// drop(place);
+    ///
+    /// let poses = frame.poses();
+    /// assert_eq!(poses.len(), 1);
+    /// assert_eq!(poses[0].position(), Vector3::z());
+    /// ```
+    #[must_use]
+    pub fn poses(&self) -> Vec<Pose<T>> {
+        let entries: Vec<_> = {
+            let data = self.borrow();
+            let mut entries = data.pose_entries.borrow_mut();
+            entries.retain(|entry| entry.strong_count() > 0);
+            entries.iter().filter_map(Weak::upgrade).collect()
+        };
+        entries
+            .iter()
+            .map(|entry| Pose::from_entry(self, entry))
+            .collect()
+    }
+
+    /// Returns the number of live poses expressed in this frame, see [`Frame::poses`].
+    #[must_use]
+    pub fn pose_count(&self) -> usize {
+        let data = self.borrow();
+        let mut entries = data.pose_entries.borrow_mut();
+        entries.retain(|entry| entry.strong_count() > 0);
+        entries.len()
+    }
+
+    /// Attaches a metadata entry to this frame, replacing any previous value for `key`.
+    ///
+    /// Metadata is included in [`Frame::to_json`] and restored by [`Frame::from_json`] and
+    /// [`Frame::apply_config`].
+    ///
+    /// # Arguments
+    /// - `key`: The key of the entry.
+    /// - `value`: The value of the entry, e.g. a string, number, boolean or JSON value.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::metadata::MetadataValue;
+    ///
+    /// let frame = Frame::new_origin("base").unwrap();
+    /// frame.set_metadata("mesh", "base.stl");
+    /// frame.set_metadata("mass", 1.2);
+    /// assert_eq!(frame.get_metadata("mesh"), Some(MetadataValue::from("base.stl")));
+    /// assert_eq!(frame.get_metadata("mass").and_then(|mass| mass.as_f64()), Some(1.2));
+    /// ```
+    pub fn set_metadata(&self, key: impl Into<String>, value: impl Into<MetadataValue>) {
+        // Conversions are done before borrowing, as they may run user code.
+        let (key, value) = (key.into(), value.into());
+        self.borrow_mut().metadata.insert(key, value);
+    }
+
+    /// Adds a new child frame whose coordinates are uniformly scaled relative to this frame.
+    ///
+    /// A point `p` in the child maps to `orientation * (scale * p) + position` in this frame,
+    /// e.g. for registering a scan with a slightly wrong scale. Chains through scaled frames
+    /// are no isometries, so query them via [`Frame::similarity_to`]; point and pose
+    /// transformations such as [`Frame::transform_point_to`] and [`Pose::in_frame`] handle
+    /// them transparently.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new child frame.
+    /// - `position`: The translational offset from the parent.
+    /// - `orientation`: The orientation relative to the parent.
+    /// - `scale`: The scale of the child coordinates, strictly positive.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `scale` is not a positive finite number.
+    /// - The child cannot be added, see [`Frame::add_child`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Point3, UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let scan = root
+    ///     .add_scaled_child("scan", Vector3::zeros(), UnitQuaternion::identity(), 0.5)
+    ///     .unwrap();
+    /// let point = scan.transform_point_to(&root, &Point3::new(2.0, 0.0, 0.0)).unwrap();
+    /// assert_eq!(point, Point3::new(1.0, 0.0, 0.0));
+    /// ```
+    pub fn add_scaled_child(
+        &self,
+        name: impl Into<String>,
+        position: impl Into<Vector3<T>>,
+        orientation: impl Into<Rotation<T>>,
+        scale: f64,
+    ) -> Result<Self, CartesianTreeError> {
+        let name = name.into();
+        if !(scale.is_finite() && scale > 0.0) {
+            return Err(CartesianTreeError::InvalidScale { frame: name, scale });
+        }
+        let child = self.add_child(name, position, orientation)?;
+        child.borrow_mut().scale = Some(scale);
+        Ok(child)
+    }
+
+    /// Serializes the frame tree to a JSON string.
+    ///
+    /// This serializes the hierarchy starting from this frame (ideally the root), without
+    /// recursion, so even very deep trees fit on the stack.
+    /// Transforms for root frames are set to identity. If the tree declares a canonical length
+    /// unit (see [`Frame::set_canonical_unit`]), it is written as the top-level `units` field.
+    /// The document is tagged with the [`SCHEMA_VERSION`] of the format.
+    ///
+    /// # Returns
+    /// The serialized tree as a JSON string.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - On deserialization failure.
+    #[cfg(all(feature = "std", feature = "serde"))]
+    pub fn to_json(&self) -> Result<String, CartesianTreeError> {
+        Ok(self.to_serial().to_json(true)?)
+    }
+
+    /// Serializes the frame tree to a JSON string without whitespace.
+    ///
+    /// Holds the same document as [`Frame::to_json`], which indents every line by its nesting
+    /// depth. Prefer this one for very deep trees, e.g. long kinematic chains, whose indented
+    /// documents grow quadratically with the depth.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - On serialization failure.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let json = root.to_json_compact().unwrap();
+    /// assert!(json.starts_with(r#"{"schema_version":"#));
+    /// assert!(Frame::from_json(&json).is_ok());
+    /// ```
+    #[cfg(all(feature = "std", feature = "serde"))]
+    pub fn to_json_compact(&self) -> Result<String, CartesianTreeError> {
+        Ok(self.to_serial().to_json(false)?)
+    }
+
+    /// Helper function to convert the frame and its children into a serializable structure.
+    ///
+    /// This is used internally for JSON serialization. The result holds no references into the
+    /// tree, so it can be serialized while other threads run (see the Python bindings).
+    #[cfg(all(feature = "std", feature = "serde"))]
+    pub(crate) fn to_serial(&self) -> SerialFrame {
+        let mut serial = self.serial_node();
+        serial.schema_version = Some(SCHEMA_VERSION);
+        serial.precision = (T::PRECISION != Precision::F64).then_some(T::PRECISION);
+        serial.units = self.canonical_unit();
+        serial
+    }
+
+    #[cfg(all(feature = "std", feature = "serde"))]
+    fn serial_node(&self) -> SerialFrame {
+        // Nodes are collected in depth-first pre-order with the index of their parent, so every
+        // node is complete once all nodes after it have been moved into their parents.
+        // A parent of `None` stands for this frame.
+        let mut root = self.serial_fields();
+        let mut nodes: Vec<(SerialFrame, Option<usize>)> = Vec::new();
+        let mut stack: Vec<_> = self
+            .children()
+            .into_iter()
+            .rev()
+            .map(|child| (child, None))
+            .collect();
+        while let Some((frame, parent)) = stack.pop() {
+            let index = nodes.len();
+            nodes.push((frame.serial_fields(), parent));
+            for child in frame.children().into_iter().rev() {
+                stack.push((child, Some(index)));
+            }
+        }
+        while let Some((mut node, parent)) = nodes.pop() {
+            // Children are moved in from the last to the first.
+            node.children.reverse();
+            match parent {
+                Some(parent) => nodes[parent].0.children.push(node),
+                None => root.children.push(node),
+            }
+        }
+        root.children.reverse();
+        root
+    }
+
+    /// Returns the fields of this frame for serialization, without children.
+    #[cfg(all(feature = "std", feature = "serde"))]
+    fn serial_fields(&self) -> SerialFrame {
+        let (position, orientation) = if self.parent().is_some() {
+            let iso = isometry_to_f64(
+                self.transformation()
+                    .unwrap_or_else(|_| Isometry3::identity()),
+            );
+            (iso.translation.vector, iso.rotation)
+        } else {
+            (Vector3::zeros(), UnitQuaternion::identity())
+        };
+
+        SerialFrame {
+            schema_version: None,
+            precision: None,
+            name: self.name(),
+            position,
+            orientation: SerialOrientation::Quaternion(orientation),
+            scale: self.borrow().scale,
+            is_static: self.is_static(),
+            units: None,
+            metadata: self.borrow().metadata.clone(),
+            poses: self
+                .borrow()
+                .poses
+                .iter()
+                .map(|(name, pose)| {
+                    let serial = SerialPose {
+                        position: pose.translation.vector,
+                        orientation: SerialOrientation::Quaternion(pose.rotation),
+                    };
+                    (name.clone(), serial)
+                })
+                .collect(),
+            regions: self.borrow().regions.clone(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Builds a new frame tree of the scalar type `T`, e.g. `f32`, from a JSON string.
+    ///
+    /// Like [`Frame::from_json`], which builds `f64` trees. Documents of either precision are
+    /// accepted; their values are rounded to `T`.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the document cannot be read, see [`Frame::from_json`],
+    /// or its `precision` field is neither `"f32"` nor `"f64"`.
+    #[cfg(all(feature = "std", feature = "serde"))]
+    pub fn parse_json(json: &str) -> Result<Self, CartesianTreeError> {
+        let mut document = JsonDocument::parse(json)?;
+        migrate(&mut document.0)?;
+        let document = &document.0;
+        let root_path = "$".to_string();
+        json_vector(document, &root_path)?;
+        json_orientation::<f64>(document, &root_path)?;
+        json_precision(document)?;
+        let units = json_units(document)?;
+        let root = Self::origin(json_name(document, &root_path)?)?;
+        root.borrow_mut().canonical_unit = units;
+        root.set_static(json_static(document, &root_path)?);
+        json_metadata(&root, document, &root_path)?;
+        json_poses(&root, document, &root_path)?;
+        json_regions(&root, document, &root_path)?;
+
+        // Paths grow with the depth of the tree, so they are only built for errors.
+        let mut locations = vec![JsonLocation::ROOT];
+        let mut stack = vec![(root.clone(), document, 0)];
+        while let Some((frame, node, location)) = stack.pop() {
+            let children = json_field(node, "", "children")
+                .and_then(|children| {
+                    children
+                        .as_array()
+                        .ok_or_else(|| invalid_document(".children", "expected an array"))
+                })
+                .map_err(|error| locate_error(error, &locations, location))?;
+            for (index, child) in children.iter().enumerate() {
+                locations.push(JsonLocation {
+                    parent: location,
+                    index,
+                });
+                let child_location = locations.len() - 1;
+                let child_frame = Self::json_child(&frame, child)
+                    .map_err(|error| locate_error(error, &locations, child_location))?;
+                stack.push((child_frame, child, child_location));
+            }
+        }
+        Ok(root)
+    }
+
+    /// Adds the frame described by the JSON object `node` as a child of `parent`, with its
+    /// metadata, poses and regions but without its children.
+    ///
+    /// Errors locate the document problem relative to `node`.
+    #[cfg(all(feature = "std", feature = "serde"))]
+    fn json_child(parent: &Self, node: &serde_json::Value) -> Result<Self, CartesianTreeError> {
+        let name = json_name(node, "")?;
+        let position = json_vector(node, "")?.map(from_f64::<T>);
+        let orientation = json_orientation::<T>(node, "")?;
+        let scale = json_scale(node, "")?;
+        if parent
+            .borrow()
+            .children
+            .iter()
+            .any(|sibling| *sibling.borrow().name == *name)
+        {
+            return Err(invalid_document(
+                ".name",
+                &format!("duplicate sibling name '{name}'"),
+            ));
+        }
+        let frame = match scale {
+            Some(scale) => parent.add_scaled_child(name, position, orientation, scale)?,
+            None => parent.add_child(name, position, orientation)?,
+        };
+        frame.set_static(json_static(node, "")?);
+        json_metadata(&frame, node, "")?;
+        json_poses(&frame, node, "")?;
+        json_regions(&frame, node, "")?;
+        Ok(frame)
+    }
+}
+
+impl Frame {
+    /// Creates a new root frame (origin) with the given name.
+    ///
+    /// The origin has no parent and uses the identity transform. Names are checked against
+    /// [`FrameNameRules::Strict`], which also applies to all frames added to the tree.
+    /// The tree uses `f64`; [`Frame::origin`] creates trees of other scalar types, e.g. `f32`.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the root frame.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::InvalidFrameName`] if the name is empty, `.` or `..`,
+    /// contains `/` or control characters, or has leading or trailing whitespace.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    ///
+    /// let origin = Frame::new_origin("world").unwrap();
+    /// assert!(Frame::new_origin("world/robot").is_err());
+    /// ```
+    pub fn new_origin(name: impl Into<String>) -> Result<Self, CartesianTreeError> {
+        Self::new_origin_with(name, FrameNameRules::Strict)
+    }
+
+    /// Creates a new root frame (origin) whose tree checks frame names against `rules`.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::InvalidFrameName`] if the name violates `rules`.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::validation::FrameNameRules;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let origin = Frame::new_origin_with("world", FrameNameRules::Permissive).unwrap();
+    /// let exotic = origin
+    ///     .add_child(" left/arm ", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// assert_eq!(exotic.name(), " left/arm ");
+    /// ```
+    pub fn new_origin_with(
+        name: impl Into<String>,
+        rules: FrameNameRules,
+    ) -> Result<Self, CartesianTreeError> {
+        Self::origin_with(name, rules)
+    }
+
+    /// Expresses a batch of poses given in this frame in the target frame.
+    ///
+    /// Equivalent to calling [`Pose::in_frame`] on every pose, but the transformation between
+    /// the frames is resolved only once.
+    ///
+    /// # Arguments
+    /// - `target`: The frame to express the poses in.
+    /// - `poses`: The poses, all expressed in this frame.
+    ///
+    /// # Returns
+    /// The poses expressed in the target frame, in input order.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - A pose is not expressed in this frame.
+    /// - The frames do not share a common ancestor.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let camera = root
+    ///     .add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let detections = [
+    ///     camera.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap(),
+    ///     camera.add_pose(Vector3::new(0.0, 1.0, 0.0), UnitQuaternion::identity()).unwrap(),
+    /// ];
+    /// let in_world = camera.transform_poses_to(&root, &detections).unwrap();
+    /// assert_eq!(in_world[1].position(), Vector3::new(0.0, 1.0, 1.0));
+    /// ```
+    pub fn transform_poses_to(
+        &self,
+        target: &Self,
+        poses: &[Pose],
+    ) -> Result<Vec<Pose>, CartesianTreeError> {
+        if let Some(index) = poses
+            .iter()
+            .position(|pose| !pose.frame().is_ok_and(|frame| frame.is_same(self)))
+        {
+            return Err(CartesianTreeError::PoseNotInFrame {
+                frame: self.name(),
+                index,
+            });
+        }
+        let similarity = self.similarity_to(target)?;
+        Ok(poses
+            .iter()
+            .map(|pose| pose.moved_to(target, &similarity))
+            .collect())
+    }
+
+    /// Renames this frame.
+    ///
+    /// Paths, JSON documents and lookups by name use the new name afterwards. Renaming the root
+    /// is allowed; renaming a frame to its current name does nothing.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The name violates the [`FrameNameRules`] of the tree.
+    /// - A sibling of this frame already has the name.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let camera = root
+    ///     .add_child("camrea", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// camera.rename("camera").unwrap();
+    /// assert_eq!(camera.path(), "/world/camera");
+    /// ```
+    pub fn rename(&self, new_name: impl Into<String>) -> Result<(), CartesianTreeError> {
+        let new_name = new_name.into();
+        check_frame_name(&new_name, self.name_rules())?;
+        let interned = intern(&new_name);
+        if let Some(parent) = self.try_parent()? {
+            let parent = parent
+                .data
+                .try_borrow()
+                .map_err(|_| CartesianTreeError::ConcurrentAccess)?;
+            if parent.children.iter().any(|sibling| {
+                !sibling.is_same(self) && same_name(&sibling.borrow().name, &interned)
+            }) {
+                return Err(CartesianTreeError::DuplicateChildName {
+                    parent: parent.name.to_string(),
+                    child: new_name,
+                });
+            }
+        }
+        let mut frame = self.try_borrow_mut()?;
+        for child in &frame.children {
+            child.try_borrow_mut()?.parent_name = interned.clone();
+        }
+        frame.name = interned;
+        Ok(())
+    }
+
+    /// Returns the metadata entry stored under `key`, or `None` if there is no such entry.
+    #[must_use]
+    pub fn get_metadata(&self, key: &str) -> Option<MetadataValue> {
+        self.borrow().metadata.get(key).cloned()
+    }
+
+    /// Removes the metadata entry stored under `key` and returns it, if there was one.
+    #[must_use]
+    pub fn remove_metadata(&self, key: &str) -> Option<MetadataValue> {
+        self.borrow_mut().metadata.remove(key)
+    }
+
+    /// Returns the keys of all metadata entries of this frame in sorted order.
+    #[must_use]
+    pub fn metadata_keys(&self) -> Vec<String> {
+        self.borrow().metadata.keys().cloned().collect()
+    }
+
+    /// Stores a pose on this frame under the given name.
+    ///
+    /// The pose is expressed in this frame first, so it keeps its location if it belongs to
+    /// another frame of the same tree. Stored poses are included in [`Frame::to_json`] and
+    /// restored by [`Frame::from_json`] and [`Frame::apply_config`].
+    ///
+    /// # Arguments
+    /// - `name`: The name of the pose, e.g. `home` or `approach`.
+    /// - `pose`: The pose to store.
+    /// - `policy`: Whether an existing pose with the same name is replaced or kept.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - A pose with the same name is stored already and `policy` is [`StorePolicy::Reject`].
+    /// - The frame of the pose has been dropped or is not part of the same tree.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::frame::StorePolicy;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let robot = Frame::new_origin("robot").unwrap();
+    /// let home = robot.add_pose(Vector3::new(0.3, 0.0, 0.5), UnitQuaternion::identity()).unwrap();
+    /// robot.store_pose("home", &home, StorePolicy::Reject).unwrap();
+    ///
+    /// assert_eq!(robot.pose_names(), ["home"]);
+    /// assert_eq!(robot.get_pose("home").unwrap().position(), Vector3::new(0.3, 0.0, 0.5));
+    /// assert!(robot.store_pose("home", &home, StorePolicy::Reject).is_err());
+    /// ```
+    pub fn store_pose(
+        &self,
+        name: impl Into<String>,
+        pose: &Pose,
+        policy: StorePolicy,
+    ) -> Result<(), CartesianTreeError> {
+        let name = name.into();
+        let transform = pose.in_frame(self)?.transformation();
+        let mut data = self.try_borrow_mut()?;
+        if policy == StorePolicy::Reject && data.poses.contains_key(&name) {
+            return Err(CartesianTreeError::DuplicatePoseName {
+                frame: data.name.to_string(),
+                name,
+            });
+        }
+        data.poses.insert(name, transform);
+        Ok(())
+    }
+
+    /// Returns the pose stored under `name`, or `None` if there is no such pose.
+    #[must_use]
+    pub fn get_pose(&self, name: &str) -> Option<Pose> {
+        let transform = *self.borrow().poses.get(name)?;
+        Some(Pose::new(
+            self,
+            transform.translation.vector,
+            transform.rotation,
+        ))
+    }
+
+    /// Removes the pose stored under `name` and returns it, if there was one.
+    #[must_use]
+    pub fn remove_pose(&self, name: &str) -> Option<Pose> {
+        let transform = self.borrow_mut().poses.remove(name)?;
+        Some(Pose::new(
+            self,
+            transform.translation.vector,
+            transform.rotation,
+        ))
+    }
+
+    /// Returns the names of all poses stored on this frame in sorted order.
+    #[must_use]
+    pub fn pose_names(&self) -> Vec<String> {
+        self.borrow().poses.keys().cloned().collect()
+    }
+
+    /// Returns the uniform scale of this frame's coordinates, which is one unless the frame
+    /// was created via [`Frame::add_scaled_child`].
+    #[must_use]
+    pub fn scale(&self) -> f64 {
+        self.borrow().scale.unwrap_or(1.0)
+    }
+
+    /// Adds a new child frame at `position` that looks at `target`.
+    ///
+    /// The orientation follows the [`LookAtConvention::Camera`] convention (-Z towards the
+    /// target, +Y up). Use [`Frame::add_child_looking_at_with`] for other conventions.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new child frame.
+    /// - `position`: The origin of the new frame, expressed in this frame.
+    /// - `target`: The point to look at, expressed in this frame.
+    /// - `up`: The approximate up direction, expressed in this frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `target` coincides with `position`, or the viewing direction is parallel to `up`.
+    /// - A child with the same name already exists.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Point3, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let camera = root
+    ///     .add_child_looking_at("camera", Vector3::new(2.0, 0.0, 1.0), Point3::origin(), Vector3::z())
+    ///     .unwrap();
+    /// ```
+    pub fn add_child_looking_at(
+        &self,
+        name: impl Into<String>,
+        position: Vector3<f64>,
+        target: Point3<f64>,
+        up: Vector3<f64>,
+    ) -> Result<Self, CartesianTreeError> {
+        self.add_child_looking_at_with(name, position, target, up, LookAtConvention::Camera)
+    }
+
+    /// Adds a new child frame at `position` that looks at `target` using the given convention.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `target` coincides with `position`, or the viewing direction is parallel to `up`.
+    /// - A child with the same name already exists.
+    pub fn add_child_looking_at_with(
+        &self,
+        name: impl Into<String>,
+        position: Vector3<f64>,
+        target: Point3<f64>,
+        up: Vector3<f64>,
+        convention: LookAtConvention,
+    ) -> Result<Self, CartesianTreeError> {
+        let orientation = Rotation::look_at_with(target.coords - position, up, convention)?;
+        self.add_child(name, position, orientation)
+    }
+
+    /// Removes the child frame with the given name from this frame.
+    ///
+    /// The removed child (together with its own subtree) is detached from the tree: its parent
+    /// reference is cleared, so it becomes the root of a separate tree. Its stored transform is
+    /// kept and can still be read via [`Frame::position`] and [`Frame::orientation`], which makes it
+    /// possible to reattach it elsewhere. Poses defined in the removed subtree stay attached to
+    /// their frames and can no longer be expressed in frames of the original tree.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the child frame to remove.
+    ///
+    /// # Returns
+    /// The detached child frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - No child with the given name exists.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("base").unwrap();
+    /// root.add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let camera = root.remove_child("camera").unwrap();
+    /// assert_eq!(camera.name(), "camera");
+    /// ```
+    pub fn remove_child(&self, name: &str) -> Result<Self, CartesianTreeError> {
+        let child = {
+            let mut frame = self.try_borrow_mut()?;
+            let index = frame
+                .children
+                .iter()
+                .position(|child| *child.borrow().name == *name)
+                .ok_or_else(|| CartesianTreeError::ChildNotFound {
+                    parent: frame.name.to_string(),
+                    child: name.to_string(),
+                })?;
+            Self::from_data(frame.children.remove(index).data)
+        };
+        {
+            let mut data = child.borrow_mut();
+            data.parent = None;
+            data.parent_name = intern("");
+        }
+        child.reanchor();
+        #[cfg(feature = "std")]
+        child.adopt_audit(None);
+        child.invalidate_world_transform();
+        Ok(child)
+    }
+
+    /// Checks the structure of this frame and its descendants.
+    ///
+    /// Verifies that every child references the frame listing it as its parent, that sibling
+    /// names are unique, that no frame is reached twice (e.g. through a cycle), and that all
+    /// transforms are finite with orientations normalized within [`QUATERNION_NORM_TOLERANCE`].
+    ///
+    /// Trees modified only through this API always pass; this is meant as a sanity check, e.g.
+    /// in tests or after a long series of updates.
+    ///
+    /// # Errors
+    /// Returns every violation found, each reporting the path of the offending frame.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// root.add_child("camera", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// assert!(root.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<TreeValidationError>> {
+        let mut errors = Vec::new();
+        let mut visited = BTreeSet::new();
+        let mut stack = vec![(self.clone(), self.path())];
+        while let Some((frame, path)) = stack.pop() {
+            if !visited.insert(Rc::as_ptr(&frame.data)) {
+                errors.push(TreeValidationError::Cycle { path });
+                continue;
+            }
+            let data = frame.borrow();
+
+            let transform = &data.transform_to_parent;
+            if let Err(CartesianTreeError::NonFiniteValue { field, .. }) =
+                check_isometry("transform", transform)
+            {
+                errors.push(TreeValidationError::NonFiniteTransform {
+                    path: path.clone(),
+                    field,
+                });
+            } else if (transform.rotation.norm() - 1.0).abs() > QUATERNION_NORM_TOLERANCE {
+                errors.push(TreeValidationError::UnnormalizedOrientation {
+                    path: path.clone(),
+                    norm: transform.rotation.norm(),
+                });
+            }
+
+            let mut names = BTreeSet::new();
+            let mut duplicates = BTreeSet::new();
+            for child in &data.children {
+                let child_data = child.borrow();
+                let child_path = format!("{path}/{}", child_data.name);
+                if !child_data
+                    .parent
+                    .as_ref()
+                    .and_then(Weak::upgrade)
+                    .is_some_and(|parent| Rc::ptr_eq(&parent, &frame.data))
+                {
+                    errors.push(TreeValidationError::ParentMismatch {
+                        path: child_path.clone(),
+                    });
+                }
+                if !names.insert(child_data.name.clone())
+                    && duplicates.insert(child_data.name.clone())
+                {
+                    errors.push(TreeValidationError::DuplicateChildName {
+                        path: path.clone(),
+                        name: child_data.name.to_string(),
+                    });
+                }
+                stack.push((child.clone(), child_path));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Moves this frame (together with its subtree) to a new parent.
+    ///
+    /// # Arguments
+    /// - `new_parent`: The frame that becomes the new parent.
+    /// - `policy`: Whether the pose relative to the root ([`ReparentPolicy::KeepWorld`]) or the
+    ///   transform to the parent ([`ReparentPolicy::KeepLocal`]) is preserved.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the frame was moved successfully.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The new parent is this frame or one of its descendants.
+    /// - The new parent already has a child with the same name.
+    /// - [`ReparentPolicy::KeepWorld`] is requested and the new parent is not part of the same tree.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::frame::ReparentPolicy;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let table = root
+    ///     .add_child("table", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let cup = root
+    ///     .add_child("cup", Vector3::new(1.0, 0.0, 0.5), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// cup.reparent(&table, ReparentPolicy::KeepWorld).unwrap();
+    /// assert_eq!(cup.position(), Vector3::new(0.0, 0.0, 0.5));
+    /// ```
+    pub fn reparent(
+        &self,
+        new_parent: &Self,
+        policy: ReparentPolicy,
+    ) -> Result<(), CartesianTreeError> {
+        let mut current = Some(new_parent.clone());
+        while let Some(frame) = current {
+            if frame.is_same(self) {
+                return Err(CartesianTreeError::ReparentCycle {
+                    frame: self.name(),
+                    new_parent: new_parent.name(),
+                });
+            }
+            current = frame.parent();
+        }
+
+        let old_parent = self.parent();
+        if old_parent
+            .as_ref()
+            .is_some_and(|parent| parent.is_same(new_parent))
+        {
+            return Ok(());
+        }
+
+        let name = self.name();
+        if new_parent
+            .borrow()
+            .children
+            .iter()
+            .any(|child| *child.borrow().name == *name)
+        {
+            return Err(CartesianTreeError::DuplicateChildName {
+                parent: new_parent.name(),
+                child: name,
+            });
+        }
+
+        let transform = match policy {
+            ReparentPolicy::KeepLocal => self.borrow().transform_to_parent,
+            ReparentPolicy::KeepWorld => {
+                let ancestor = self.lca_with(new_parent).ok_or_else(|| {
+                    CartesianTreeError::NoCommonAncestor {
+                        from: self.name(),
+                        to: new_parent.name(),
+                    }
+                })?;
+                let t_self_to_ancestor = self.walk_up_and_transform(&ancestor)?;
+                let t_parent_to_ancestor = new_parent.walk_up_and_transform(&ancestor)?;
+                t_parent_to_ancestor.inverse() * t_self_to_ancestor
+            }
+        };
+
+        if let Some(old_parent) = old_parent {
+            old_parent
+                .try_borrow_mut()?
+                .children
+                .retain(|child| !child.is_same(self));
+        }
+        let old = {
+            let mut frame = self.try_borrow_mut()?;
+            frame.parent = Some(new_parent.downgrade());
+            frame.parent_name = new_parent.shared_name();
+            core::mem::replace(&mut frame.transform_to_parent, transform)
+        };
+        new_parent
+            .try_borrow_mut()?
+            .children
+            .push(Self::link(self.data.clone()));
+        self.reanchor();
+        #[cfg(feature = "std")]
+        self.adopt_audit(Some(new_parent));
+        self.invalidate_world_transform();
+        self.record_audit(AuditOperation::Reparent, Some(old));
+        self.notify_transform_changed();
+        Ok(())
+    }
+
+    /// Deep-copies this frame and its descendants into a new, detached tree.
+    ///
+    /// Names, transforms, metadata and stored poses are copied; the copy shares no data with the
+    /// original, and callbacks are not copied. The copy of this frame becomes the root of the
+    /// new tree, so its transform to the parent is dropped.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let tool = root
+    ///     .add_child("tool", Vector3::new(0.0, 0.0, 0.5), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// tool.add_child("tcp", Vector3::new(0.0, 0.0, 0.1), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let copy = tool.clone_subtree();
+    /// assert_eq!(copy.path(), "/tool");
+    /// assert_eq!(copy.resolve_path("tcp").unwrap().position(), Vector3::new(0.0, 0.0, 0.1));
+    /// ```
+    #[must_use]
+    pub fn clone_subtree(&self) -> Self {
+        let root = self.copy_node(None);
+        let mut stack = vec![(self.clone(), root.clone())];
+        while let Some((original, copy)) = stack.pop() {
+            for child in original.children() {
+                let child_copy = child.copy_node(Some(&copy));
+                copy.borrow_mut()
+                    .children
+                    .push(Self::link(child_copy.data.clone()));
+                stack.push((child, child_copy));
+            }
+        }
+        root
+    }
+
+    /// Deep-copies this frame and its descendants (see [`Frame::clone_subtree`]) and attaches the
+    /// copy to `parent`.
+    ///
+    /// The copy keeps the transform of this frame to its parent (identity for a root).
+    ///
+    /// # Arguments
+    /// - `parent`: The frame to attach the copy to. It may belong to any tree, including the
+    ///   subtree being copied.
+    /// - `rename`: The name of the copy, or `None` to keep the name of this frame.
+    ///
+    /// # Returns
+    /// The copy of this frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `parent` already has a child with the name of the copy.
+    /// - A copied name violates the [`FrameNameRules`] of the tree of `parent`.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let cell = Frame::new_origin("cell").unwrap();
+    /// let left = cell
+    ///     .add_child("left", Vector3::new(0.0, 1.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let right = cell
+    ///     .add_child("right", Vector3::new(0.0, -1.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let gripper = left
+    ///     .add_child("gripper", Vector3::new(0.0, 0.0, 0.2), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let copy = gripper.attach_copy_under(&right, None).unwrap();
+    /// assert_eq!(copy.path(), "/cell/right/gripper");
+    /// assert!(gripper.attach_copy_under(&right, None).is_err());
+    /// ```
+    pub fn attach_copy_under(
+        &self,
+        parent: &Self,
+        rename: Option<&str>,
+    ) -> Result<Self, CartesianTreeError> {
+        // Copying first keeps the traversal away from `parent`, which may be inside this subtree.
+        let copy = self.clone_subtree();
+        let rules = parent.name_rules();
+        for frame in copy.iter_dfs().skip(1) {
+            check_frame_name(&frame.borrow().name, rules)?;
+        }
+        let name = rename.map_or_else(|| self.name(), str::to_string);
+        let transform = self.borrow().transform_to_parent;
+        let grafted = parent.add_child(name, transform.translation.vector, transform.rotation)?;
+
+        let children = {
+            let mut copy_data = copy.borrow_mut();
+            let mut grafted_data = grafted.borrow_mut();
+            grafted_data.metadata = core::mem::take(&mut copy_data.metadata);
+            grafted_data.poses = core::mem::take(&mut copy_data.poses);
+            grafted_data.regions = core::mem::take(&mut copy_data.regions);
+            grafted_data.scale = copy_data.scale;
+            grafted_data.is_static = copy_data.is_static;
+            grafted_data.children = core::mem::take(&mut copy_data.children);
+            grafted_data.children.clone()
+        };
+        for child in children {
+            let mut data = child.borrow_mut();
+            data.parent = Some(grafted.downgrade());
+            data.parent_name = grafted.shared_name();
+        }
+        grafted.reanchor();
+        #[cfg(feature = "std")]
+        grafted.adopt_audit(Some(parent));
+        for frame in grafted.iter_dfs() {
+            frame.borrow_mut().name_rules = rules;
+        }
+        Ok(grafted)
+    }
+
+    /// Returns a childless copy of this frame, attached to `parent` or as a new root.
+    fn copy_node(&self, parent: Option<&Self>) -> Self {
+        let data = self.borrow();
+        let copy = Rc::new(RefCell::new(FrameData {
+            name: data.name.clone(),
+            parent: parent.map(Self::downgrade),
+            parent_name: parent.map_or_else(|| intern(""), Self::shared_name),
+            transform_to_parent: if parent.is_some() {
+                data.transform_to_parent
+            } else {
+                Isometry3::identity()
+            },
+            world_transform: parent.is_none().then(Isometry3::identity),
+            children: Vec::new(),
+            metadata: data.metadata.clone(),
+            poses: data.poses.clone(),
+            regions: data.regions.clone(),
+            subscriptions: Vec::new(),
+            name_rules: data.name_rules,
+            history: None,
+            twist: data.twist,
+            joint: if parent.is_some() {
+                data.joint.clone()
+            } else {
+                None
+            },
+            scale: data.scale,
+            is_static: data.is_static,
+            #[cfg(feature = "std")]
+            audit: None,
+            canonical_unit: if parent.is_none() {
+                self.canonical_unit()
+            } else {
+                None
+            },
+            anchor: RefCell::default(),
+            pose_entries: RefCell::default(),
+        }));
+        match parent {
+            Some(parent) => parent.related(copy),
+            None => Self::from_data(copy),
+        }
+    }
+
+    /// Adds a new child frame calibrated such that a reference pose, when expressed in the new frame,
+    /// matches the desired position and orientation.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new child frame.
+    /// - `desired_position`: The desired position of the reference pose in the new frame.
+    /// - `desired_orientation`: The desired orientation of the reference pose in the new frame.
+    /// - `reference_pose`: The existing pose (in some frame A) used as the calibration reference.
+    ///
+    /// # Returns
+    /// - The new child frame if successful.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The reference frame is invalid.
+    /// - No common ancestor exists.
+    /// - A child with the same name already exists.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let reference_pose = root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// let calibrated_child = root.calibrate_child(
+    ///     "calibrated",
+    ///     Vector3::zeros(),
+    ///     UnitQuaternion::identity(),
+    ///     &reference_pose,
+    /// ).unwrap();
+    /// ```
+    pub fn calibrate_child(
+        &self,
+        name: impl Into<String>,
+        desired_position: Vector3<f64>,
+        desired_orientation: impl Into<Rotation>,
+        reference_pose: &Pose,
+    ) -> Result<Self, CartesianTreeError> {
+        let reference_frame = reference_pose.frame()?;
+
+        let ancestor = self.lca_with(&reference_frame).ok_or_else(|| {
+            CartesianTreeError::NoCommonAncestor {
+                from: self.name(),
+                to: reference_frame.name(),
+            }
+        })?;
+
+        let t_reference_to_ancestor = reference_frame.walk_up_and_transform(&ancestor)?;
+        let t_pose_to_reference = reference_pose.transformation();
+        let t_pose_to_ancestor = t_reference_to_ancestor * t_pose_to_reference;
+
+        let t_parent_to_ancestor = self.walk_up_and_transform(&ancestor)?;
+        let t_ancestor_to_parent = t_parent_to_ancestor.inverse();
+
+        let desired_pose = Isometry3::from_parts(
+            Translation3::from(desired_position),
+            desired_orientation.into().as_quaternion(),
+        );
+
+        let t_calibrated_to_parent =
+            t_pose_to_ancestor * desired_pose.inverse() * t_ancestor_to_parent;
+
+        let child = self.add_child(
+            name,
+            t_calibrated_to_parent.translation.vector,
+            t_calibrated_to_parent.rotation,
+        )?;
+        child.record_audit(AuditOperation::Calibration, None);
+        Ok(child)
+    }
+
+    /// Adds a pose given as a 4x4 homogeneous transformation matrix, see [`Pose::to_matrix`].
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError::InvalidTransformMatrix`] if the last row is not
+    /// `[0, 0, 0, 1]`, the translation is not finite, or the rotation block is not orthonormal
+    /// (within a tolerance of `1e-6`) or is a reflection. Use
+    /// [`Frame::add_pose_from_matrix_orthonormalized`] for matrices with small deviations.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Matrix4, Vector3};
+    ///
+    /// let frame = Frame::new_origin("base").unwrap();
+    /// let matrix = Matrix4::new_translation(&Vector3::new(1.0, 2.0, 3.0));
+    /// let pose = frame.add_pose_from_matrix(&matrix).unwrap();
+    /// assert_eq!(pose.position(), Vector3::new(1.0, 2.0, 3.0));
+    /// assert_eq!(pose.to_matrix(), matrix);
+    /// ```
+    pub fn add_pose_from_matrix(&self, matrix: &Matrix4<f64>) -> Result<Pose, CartesianTreeError> {
+        self.add_pose_from_homogeneous(matrix, false)
+    }
+
+    /// Adds a pose given as a 4x4 homogeneous transformation matrix, using the rotation closest
+    /// to its rotation block.
+    ///
+    /// Useful for matrices that drifted from orthonormality, e.g. through accumulated rounding
+    /// or limited precision in files.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError::InvalidTransformMatrix`] if the last row is not
+    /// `[0, 0, 0, 1]`, the translation is not finite, or the rotation block is singular or a
+    /// reflection.
+    pub fn add_pose_from_matrix_orthonormalized(
+        &self,
+        matrix: &Matrix4<f64>,
+    ) -> Result<Pose, CartesianTreeError> {
+        self.add_pose_from_homogeneous(matrix, true)
+    }
+
+    fn add_pose_from_homogeneous(
+        &self,
+        matrix: &Matrix4<f64>,
+        orthonormalize: bool,
+    ) -> Result<Pose, CartesianTreeError> {
+        let invalid = |reason: String| CartesianTreeError::InvalidTransformMatrix { reason };
+        let deviation = (matrix.fixed_view::<1, 4>(3, 0) - Vector4::w().transpose())
+            .abs()
+            .max();
+        if deviation.is_nan() || deviation > MATRIX_TOLERANCE {
+            return Err(invalid(format!(
+                "the last row must be [0, 0, 0, 1] (deviation {deviation:e})"
+            )));
+        }
+        let translation: Vector3<f64> = matrix.fixed_view::<3, 1>(0, 3).into();
+        if translation.iter().any(|value| !value.is_finite()) {
+            return Err(invalid("the translation is not finite".to_string()));
+        }
+        let block: Matrix3<f64> = matrix.fixed_view::<3, 3>(0, 0).into();
+        let rotation = if orthonormalize {
+            Rotation::from_matrix_orthonormalized(block)
+        } else {
+            Rotation::from_matrix(block)
+        }
+        .map_err(|error| match error {
+            CartesianTreeError::InvalidRotationMatrix { reason } if orthonormalize => {
+                invalid(format!("the rotation block is invalid: {reason}"))
+            }
+            CartesianTreeError::InvalidRotationMatrix { reason } => invalid(format!(
+                "the rotation block is invalid: {reason}; orthonormalize the matrix to correct \
+                 small deviations"
+            )),
+            other => other,
+        })?;
+        self.add_pose(translation, rotation)
+    }
+
+    /// Renders this frame and its descendants as a tree, similar to the output of the `tree` command.
+    ///
+    /// Each line shows the frame name, its translation, and its RPY angles (in radians) relative
+    /// to its parent. Box-drawing characters are used for the branches, see
+    /// [`Frame::render_tree_with`] for a plain ASCII variant.
+    ///
+    /// # Returns
+    /// The rendered tree, one line per frame.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// root.add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// println!("{}", root.render_tree());
+    /// ```
+    #[must_use]
+    pub fn render_tree(&self) -> String {
+        self.render_tree_with(TreeStyle::Unicode)
+    }
+
+    /// Renders this frame and its descendants as a tree using the given branch style.
+    ///
+    /// # Arguments
+    /// - `style`: The characters used to draw the branches.
+    ///
+    /// # Returns
+    /// The rendered tree, one line per frame.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::frame::TreeStyle;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// root.add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// assert!(root.render_tree_with(TreeStyle::Ascii).contains("`-- camera"));
+    /// ```
+    #[must_use]
+    pub fn render_tree_with(&self, style: TreeStyle) -> String {
+        let (branch, last_branch, pipe) = match style {
+            TreeStyle::Unicode => ("├── ", "└── ", "│   "),
+            TreeStyle::Ascii => ("|-- ", "`-- ", "|   "),
+        };
+
+        let mut output = String::new();
+        // Each entry holds the frame, the indentation of its line, and its connector.
+        let mut stack = vec![(self.clone(), String::new(), "")];
+        while let Some((frame, indent, connector)) = stack.pop() {
+            let (position, rpy) = {
+                let data = frame.borrow();
+                (
+                    data.transform_to_parent.translation.vector,
+                    Rotation::from(data.transform_to_parent.rotation).as_rpy(),
+                )
+            };
+            let _ = writeln!(
+                output,
+                "{indent}{connector}{}  xyz: {}  rpy: {}",
+                frame.name(),
+                format_vector(&position, 3),
+                format_vector(&rpy, 3),
+            );
+
+            let child_indent = match connector {
+                "" => indent,
+                c if c == last_branch => format!("{indent}    "),
+                _ => format!("{indent}{pipe}"),
+            };
+            let children = frame.children();
+            let count = children.len();
+            for (index, child) in children.into_iter().enumerate().rev() {
+                let child_connector = if index + 1 == count {
+                    last_branch
+                } else {
+                    branch
+                };
+                stack.push((child, child_indent.clone(), child_connector));
+            }
+        }
+        output
+    }
+
+    /// Exports this frame and its descendants as a Graphviz `digraph`.
+    ///
+    /// Uses the default [`DotOptions`], see [`Frame::to_dot_with`].
+    ///
+    /// # Returns
+    /// The DOT source, which can be rendered with e.g. `dot -Tsvg`.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// root.add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// assert!(root.to_dot().starts_with("digraph frames {"));
+    /// ```
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DotOptions::default())
+    }
+
+    /// Exports this frame and its descendants as a Graphviz `digraph`.
+    ///
+    /// Every frame becomes a node labelled with its name and, if enabled, its world
+    /// translation. Every parent is connected to its children by an edge, which is labelled
+    /// with the translation and RPY angles (in radians) of the child relative to the parent
+    /// if transforms are enabled.
+    ///
+    /// # Arguments
+    /// - `options`: Controls which transforms are included and their precision.
+    ///
+    /// # Returns
+    /// The DOT source, which can be rendered with e.g. `dot -Tsvg`.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::frame::DotOptions;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// root.add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let options = DotOptions { include_transforms: false, ..DotOptions::default() };
+    /// assert!(root.to_dot_with(&options).contains("n0 -> n1;"));
+    /// ```
+    #[must_use]
+    pub fn to_dot_with(&self, options: &DotOptions) -> String {
+        let precision = options.precision;
+        let mut output = String::from("digraph frames {\n    node [shape=box];\n");
+        let mut next_id = 0;
+        // Each entry holds the frame and the node id of its parent in the output.
+        let mut stack: Vec<(Self, Option<usize>)> = vec![(self.clone(), None)];
+        while let Some((frame, parent_id)) = stack.pop() {
+            let id = next_id;
+            next_id += 1;
+
+            let name = escape_dot(&frame.name());
+            if options.include_transforms {
+                let world = frame.world_transform().map_or_else(
+                    |_| "?".to_string(),
+                    |tf| format_vector(&tf.translation.vector, precision),
+                );
+                let _ = writeln!(output, "    n{id} [label=\"{name}\\nworld: {world}\"];");
+            } else {
+                let _ = writeln!(output, "    n{id} [label=\"{name}\"];");
+            }
+
+            if let Some(parent_id) = parent_id {
+                if options.include_transforms {
+                    let (position, rpy) = {
+                        let data = frame.borrow();
+                        (
+                            data.transform_to_parent.translation.vector,
+                            Rotation::from(data.transform_to_parent.rotation).as_rpy(),
+                        )
+                    };
+                    let _ = writeln!(
+                        output,
+                        "    n{parent_id} -> n{id} [label=\"xyz: {}\\nrpy: {}\"];",
+                        format_vector(&position, precision),
+                        format_vector(&rpy, precision),
+                    );
+                } else {
+                    let _ = writeln!(output, "    n{parent_id} -> n{id};");
+                }
+            }
+
+            for child in frame.children().into_iter().rev() {
+                stack.push((child, Some(id)));
+            }
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    /// Applies a JSON config to this frame tree by updating matching transforms.
+    ///
+    /// Deserializes the JSON to a temporary structure, then updates transforms
+    /// where names match (partial apply; ignores unmatched frames in config).
+    /// Skips updating root frames (identity assumed) - assumes this frame is the root.
+    ///
+    /// If both the config and the tree declare a length unit, the translations of the config are
+    /// converted into the canonical unit of the tree. Otherwise they are applied as they are.
+    /// A config describes how the tree is constructed, so it updates static frames as well and
+    /// keeps the static flags of the tree as they are, see [`Frame::set_static`].
+    /// Configs written by older builds are migrated to the current format first, see
+    /// [`crate::schema`].
+    ///
+    /// # Arguments
+    /// - `json`: The JSON string to apply.
+    ///
+    /// # Returns
+    /// `Ok(())` if applied successfully (even if partial).
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - On deserialization failure.
+    /// - The config was written in an unsupported format version.
+    /// - The frame names do not match at the root.
+    /// - A frame name in the config violates the [`FrameNameRules`] of the tree. Nothing is
+    ///   changed in this case.
+    /// - A transform in the config is not finite, e.g. a zero-length orientation quaternion.
+    ///
+    #[cfg(all(feature = "std", feature = "serde"))]
+    pub fn apply_config(&self, json: &str) -> Result<(), CartesianTreeError> {
+        let mut document = JsonDocument::parse(json)?;
+        migrate(&mut document.0)?;
+        let mut serial: SerialFrame = document.deserialize()?;
+        if let (Some(from), Some(to)) = (serial.units, self.canonical_unit()) {
+            serial.convert_units(from, to);
+        }
+        // Names are checked up front, so a corrupt config fails before changing anything.
+        let rules = self.name_rules();
+        let mut pending = vec![&serial];
+        while let Some(node) = pending.pop() {
+            check_frame_name(&node.name, rules)?;
+            pending.extend(&node.children);
+        }
+        let mut changed = Vec::new();
+        let result = self.apply_serial(&serial, &mut changed);
+        // Frames updated before a failure keep their new transforms, so they are notified too.
+        Self::notify_transforms_changed(&changed);
+        result
+    }
+
+    #[cfg(all(feature = "std", feature = "serde"))]
+    fn apply_serial(
+        &self,
+        serial: &SerialFrame,
+        changed: &mut Vec<Self>,
+    ) -> Result<(), CartesianTreeError> {
+        if self.name() != serial.name {
+            return Err(CartesianTreeError::Mismatch {
+                expected: self.name(),
+                found: serial.name.clone(),
+            });
+        }
+        let mut pending = vec![(self.clone(), serial)];
+        while let Some((frame, serial)) = pending.pop() {
+            frame.apply_serial_node(serial, changed)?;
+            // Reversed, so siblings are applied in document order.
+            for potential_child in serial.children.iter().rev() {
+                if let Some(child) = frame
+                    .children()
+                    .into_iter()
+                    .find(|c| c.name() == potential_child.name)
+                {
+                    pending.push((child, potential_child));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the transform, metadata and poses of `serial`, but not its children.
+    #[cfg(all(feature = "std", feature = "serde"))]
+    fn apply_serial_node(
+        &self,
+        serial: &SerialFrame,
+        changed: &mut Vec<Self>,
+    ) -> Result<(), CartesianTreeError> {
+        // only update if frame has parent
+        if self.try_parent()?.is_some() {
+            let orientation = serial.orientation.normalized();
+            check_position(&format!("{}.position", serial.name), &serial.position)?;
+            check_orientation(&format!("{}.orientation", serial.name), &orientation)?;
+            let transform = Isometry3::from_parts(Translation3::from(serial.position), orientation);
+            let old =
+                core::mem::replace(&mut self.try_borrow_mut()?.transform_to_parent, transform);
+            self.invalidate_world_transform();
+            // Only actual changes are logged, not every frame listed in the configuration.
+            if old != transform {
+                self.record_audit(AuditOperation::Config, Some(old));
+            }
+            changed.push(self.clone());
+        }
+        for (key, value) in &serial.metadata {
+            self.set_metadata(key.clone(), value.clone());
+        }
+        for (name, pose) in &serial.poses {
+            let orientation = pose.orientation.normalized();
+            let field = format!("{}.poses.{name}", serial.name);
+            check_position(&format!("{field}.position"), &pose.position)?;
+            check_orientation(&format!("{field}.orientation"), &orientation)?;
+            self.try_borrow_mut()?.poses.insert(
+                name.clone(),
+                Isometry3::from_parts(Translation3::from(pose.position), orientation),
+            );
+        }
+        for (name, region) in &serial.regions {
+            region.check(&format!("{}.regions.{name}", serial.name))?;
+            self.try_borrow_mut()?
+                .regions
+                .insert(name.clone(), region.clone());
+        }
+        Ok(())
+    }
+
+    /// Builds a new frame tree from a JSON string in the format written by [`Frame::to_json`].
+    ///
+    /// The transform stored for the top-level frame is ignored, as it becomes the root. A
+    /// top-level `units` field becomes the canonical length unit of the new tree.
+    ///
+    /// # Arguments
+    /// - `json`: The JSON string describing the tree.
+    ///
+    /// # Returns
+    /// The root frame of the new tree.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The string is not valid JSON.
+    /// - The document was written in an unsupported format version. Older versions are migrated,
+    ///   see [`crate::schema`].
+    /// - A node is malformed (e.g. a missing field or an orientation that is not a unit quaternion).
+    /// - A frame name violates [`FrameNameRules::Strict`].
+    /// - Two siblings share the same name.
+    ///
+    /// Errors about individual nodes include the JSON path of the offending field.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// root.add_child("robot", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let copy = Frame::from_json(&root.to_json().unwrap()).unwrap();
+    /// assert_eq!(copy.path(), "/world");
+    /// assert!(copy.resolve_path("robot").is_ok());
+    /// ```
+    #[cfg(all(feature = "std", feature = "serde"))]
+    pub fn from_json(json: &str) -> Result<Self, CartesianTreeError> {
+        Self::parse_json(json)
+    }
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+fn invalid_document(path: &str, reason: &str) -> CartesianTreeError {
+    CartesianTreeError::InvalidDocument {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// The position of a frame in a JSON document: the `index`th child of the frame at `parent`.
+#[cfg(all(feature = "std", feature = "serde"))]
+#[derive(Clone, Copy)]
+struct JsonLocation {
+    parent: usize,
+    index: usize,
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+impl JsonLocation {
+    /// The root of the document, its own parent.
+    const ROOT: Self = Self {
+        parent: 0,
+        index: 0,
+    };
+}
+
+/// Prefixes the path of an [`CartesianTreeError::InvalidDocument`] error, which is relative
+/// to a frame, with the path of that frame at `location` in `locations`.
+#[cfg(all(feature = "std", feature = "serde"))]
+fn locate_error(
+    error: CartesianTreeError,
+    locations: &[JsonLocation],
+    mut location: usize,
+) -> CartesianTreeError {
+    let CartesianTreeError::InvalidDocument { path, reason } = error else {
+        return error;
+    };
+    let mut indices = Vec::new();
+    while location != 0 {
+        indices.push(locations[location].index);
+        location = locations[location].parent;
+    }
+    let mut prefix = "$".to_string();
+    for index in indices.iter().rev() {
+        let _ = write!(prefix, ".children[{index}]");
+    }
+    CartesianTreeError::InvalidDocument {
+        path: prefix + &path,
+        reason,
+    }
+}
+
+/// Returns the field `key` of the JSON object `node` located at `path`.
+#[cfg(all(feature = "std", feature = "serde"))]
+fn json_field<'a>(
+    node: &'a serde_json::Value,
+    path: &str,
+    key: &str,
+) -> Result<&'a serde_json::Value, CartesianTreeError> {
+    node.as_object()
+        .ok_or_else(|| invalid_document(path, "expected an object"))?
+        .get(key)
+        .ok_or_else(|| invalid_document(path, &format!("missing field '{key}'")))
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+fn json_name(node: &serde_json::Value, path: &str) -> Result<String, CartesianTreeError> {
+    let field_path = format!("{path}.name");
+    let name = json_field(node, path, "name")?
+        .as_str()
+        .ok_or_else(|| invalid_document(&field_path, "expected a string"))?;
+    check_frame_name(name, FrameNameRules::Strict)
+        .map_err(|error| invalid_document(&field_path, &error.to_string()))?;
+    Ok(name.to_string())
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+fn json_numbers<const N: usize>(
+    node: &serde_json::Value,
+    path: &str,
+    key: &str,
+) -> Result<[f64; N], CartesianTreeError> {
+    let field_path = format!("{path}.{key}");
+    let malformed = || invalid_document(&field_path, &format!("expected an array of {N} numbers"));
+    let values = json_field(node, path, key)?
+        .as_array()
+        .filter(|values| values.len() == N)
+        .ok_or_else(malformed)?;
+    let mut numbers = [0.0; N];
+    for (number, value) in numbers.iter_mut().zip(values) {
+        *number = value.as_f64().ok_or_else(malformed)?;
+    }
+    Ok(numbers)
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+fn json_vector(node: &serde_json::Value, path: &str) -> Result<Vector3<f64>, CartesianTreeError> {
+    json_numbers::<3>(node, path, "position").map(Vector3::from)
+}
+
+/// Reads the optional `scale` of `node`, which has to be a positive number.
+#[cfg(all(feature = "std", feature = "serde"))]
+fn json_scale(node: &serde_json::Value, path: &str) -> Result<Option<f64>, CartesianTreeError> {
+    let Some(scale) = node.get("scale") else {
+        return Ok(None);
+    };
+    scale
+        .as_f64()
+        .filter(|scale| *scale > 0.0)
+        .map(Some)
+        .ok_or_else(|| invalid_document(&format!("{path}.scale"), "expected a positive number"))
+}
+
+/// Reads the optional `static` flag of `node`, which defaults to `false`.
+#[cfg(all(feature = "std", feature = "serde"))]
+fn json_static(node: &serde_json::Value, path: &str) -> Result<bool, CartesianTreeError> {
+    node.get("static").map_or(Ok(false), |flag| {
+        flag.as_bool()
+            .ok_or_else(|| invalid_document(&format!("{path}.static"), "expected a boolean"))
+    })
+}
+
+/// Reads the optional top-level `units` of `document`.
+#[cfg(all(feature = "std", feature = "serde"))]
+fn json_units(document: &serde_json::Value) -> Result<Option<LengthUnit>, CartesianTreeError> {
+    document
+        .get("units")
+        .map(|units| {
+            LengthUnit::deserialize(units).map_err(|_| {
+                invalid_document("$.units", "expected one of \"mm\", \"cm\", \"m\" or \"in\"")
+            })
+        })
+        .transpose()
+}
+
+/// Reads the optional top-level `precision` field, which defaults to `f64`.
+#[cfg(all(feature = "std", feature = "serde"))]
+fn json_precision(document: &serde_json::Value) -> Result<Precision, CartesianTreeError> {
+    document
+        .get("precision")
+        .map_or(Ok(Precision::F64), |precision| {
+            Precision::deserialize(precision)
+                .map_err(|_| invalid_document("$.precision", "expected \"f32\" or \"f64\""))
+        })
+}
+
+/// Copies the optional `metadata` object of `node` onto `frame`.
+#[cfg(all(feature = "std", feature = "serde"))]
+fn json_metadata<T: Real>(
+    frame: &Frame<T>,
+    node: &serde_json::Value,
+    path: &str,
+) -> Result<(), CartesianTreeError> {
+    let Some(metadata) = node.get("metadata") else {
+        return Ok(());
+    };
+    let entries = metadata
+        .as_object()
+        .ok_or_else(|| invalid_document(&format!("{path}.metadata"), "expected an object"))?;
+    for (key, value) in entries {
+        frame.set_metadata(key.clone(), value.clone());
+    }
+    Ok(())
+}
+
+/// Stores the poses of the optional `poses` object of `node` on `frame`.
+#[cfg(all(feature = "std", feature = "serde"))]
+fn json_poses<T: Real>(
+    frame: &Frame<T>,
+    node: &serde_json::Value,
+    path: &str,
+) -> Result<(), CartesianTreeError> {
+    let Some(poses) = node.get("poses") else {
+        return Ok(());
+    };
+    let entries = poses
+        .as_object()
+        .ok_or_else(|| invalid_document(&format!("{path}.poses"), "expected an object"))?;
+    for (name, pose) in entries {
+        let pose_path = format!("{path}.poses.{name}");
+        let transform = Isometry3::from_parts(
+            Translation3::from(json_vector(pose, &pose_path)?),
+            json_orientation::<f64>(pose, &pose_path)?,
+        );
+        frame.borrow_mut().poses.insert(name.clone(), transform);
+    }
+    Ok(())
+}
+
+/// Stores the regions of the optional `regions` object of `node` on `frame`.
+#[cfg(all(feature = "std", feature = "serde"))]
+fn json_regions<T: Real>(
+    frame: &Frame<T>,
+    node: &serde_json::Value,
+    path: &str,
+) -> Result<(), CartesianTreeError> {
+    let Some(regions) = node.get("regions") else {
+        return Ok(());
+    };
+    let entries = regions
+        .as_object()
+        .ok_or_else(|| invalid_document(&format!("{path}.regions"), "expected an object"))?;
+    for (name, region) in entries {
+        let region_path = format!("{path}.regions.{name}");
+        let shape = RegionShape::deserialize(region)
+            .map_err(|error| invalid_document(&region_path, &error.to_string()))?;
+        shape
+            .check(&region_path)
+            .map_err(|error| invalid_document(&region_path, &error.to_string()))?;
+        frame.borrow_mut().regions.insert(name.clone(), shape);
+    }
+    Ok(())
+}
+
+/// Reads an orientation stored as a unit quaternion `{"quaternion": [x, y, z, w]}`.
+#[cfg(all(feature = "std", feature = "serde"))]
+fn json_orientation<T: Real>(
+    node: &serde_json::Value,
+    path: &str,
+) -> Result<UnitQuaternion<T>, CartesianTreeError> {
+    let field_path = format!("{path}.orientation");
+    let [x, y, z, w] = json_numbers::<4>(
+        json_field(node, path, "orientation")?,
+        &field_path,
+        "quaternion",
+    )
+    .map_err(|_| invalid_document(&field_path, "expected {\"quaternion\": [x, y, z, w]}"))?;
+    if (Quaternion::new(w, x, y, z).norm() - 1.0).abs() > QUATERNION_NORM_TOLERANCE {
+        return Err(invalid_document(
+            &format!("{path}.orientation"),
+            "expected a unit quaternion",
+        ));
+    }
+    Ok(read_unit_quaternion(Quaternion::new(
+        from_f64(w),
+        from_f64(x),
+        from_f64(y),
+        from_f64(z),
+    )))
+}
+
+/// Joins the names of a frame and its ancestors, from the frame up, into an absolute path.
+fn join_path(names: &[String]) -> String {
+    names.iter().rev().fold(String::new(), |mut path, name| {
+        path.push('/');
+        path.push_str(name);
+        path
+    })
+}
+
+/// Escapes a frame name for use inside a quoted DOT string.
+fn escape_dot(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Formats a vector as `(x, y, z)` with the given number of decimals.
+///
+/// Components that round to zero are printed without a sign, so noise like `-1e-17` does not
+/// show up as `-0.000`.
+pub(crate) fn format_vector(vector: &Vector3<f64>, precision: usize) -> String {
+    let scale = 10f64.powi(i32::try_from(precision).unwrap_or(i32::MAX));
+    let [x, y, z] = [vector.x, vector.y, vector.z].map(|value| {
+        if (value * scale).round() == 0.0 {
+            0.0
+        } else {
+            value
+        }
+    });
+    format!("({x:.precision$}, {y:.precision$}, {z:.precision$})")
+}
+
+#[cfg(feature = "std")]
+impl Add<LazyTranslation> for &Frame {
+    type Output = Frame;
+
+    fn add(self, rhs: LazyTranslation) -> Self::Output {
+        let auto_name = Uuid::new_v4().to_string();
+        let current_position = self.borrow().transform_to_parent.translation.vector;
+        let current_orientation = self.borrow().transform_to_parent.rotation;
+        let child = self
             .add_child(auto_name, current_position, current_orientation)
             .unwrap();
-        child.apply_in_local_frame(&rhs.inner).unwrap();
-        child // Not sure yet what to do with errors
+        child.apply_in_parent_frame(&rhs.inner).unwrap();
+        child // Not sure yet what to do with errors
+    }
+}
+
+#[cfg(feature = "std")]
+impl Sub<LazyTranslation> for &Frame {
+    type Output = Frame;
+
+    fn sub(self, rhs: LazyTranslation) -> Self::Output {
+        let auto_name = Uuid::new_v4().to_string();
+        let current_position = self.borrow().transform_to_parent.translation.vector;
+        let current_orientation = self.borrow().transform_to_parent.rotation;
+        let child = self
+            .add_child(auto_name, current_position, current_orientation)
+            .unwrap();
+        child.apply_in_parent_frame(&rhs.inner.inverse()).unwrap();
+        child // Not sure yet what to do with errors
+    }
+}
+
+#[cfg(feature = "std")]
+impl Mul<LazyRotation> for &Frame {
+    type Output = Frame;
+
+    fn mul(self, rhs: LazyRotation) -> Self::Output {
+        let auto_name = Uuid::new_v4().to_string();
+        let current_position = self.borrow().transform_to_parent.translation.vector;
+        let current_orientation = self.borrow().transform_to_parent.rotation;
+        let child = self
+            .add_child(auto_name, current_position, current_orientation)
+            .unwrap();
+        child.apply_in_local_frame(&rhs.inner).unwrap();
+        child // Not sure yet what to do with errors
+    }
+}
+
+impl<T: Real> HasParent for Frame<T> {
+    type Node = Self;
+
+    fn parent(&self) -> Option<Self::Node> {
+        self.borrow()
+            .parent
+            .clone()
+            .and_then(|data_weak| data_weak.upgrade().map(|data_rc| self.related(data_rc)))
+    }
+}
+
+impl<T: Real> NodeEquality for Frame<T> {
+    fn is_same(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.data, &other.data)
+    }
+}
+
+impl<T: Real> HasChildren for Frame<T> {
+    type Node = Self;
+    fn children(&self) -> Vec<Self> {
+        self.borrow()
+            .children
+            .iter()
+            .map(|child| self.related(child.data.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lazy_access::{rz, y, z};
+
+    use super::*;
+    use crate::scalar::from_f64;
+    use approx::assert_relative_eq;
+    use nalgebra::{Matrix6, UnitQuaternion, Vector3, Vector6};
+
+    #[test]
+    fn create_origin_frame() {
+        let root = Frame::new_origin("world").unwrap();
+        let root_borrow = root.borrow();
+        assert_eq!(&*root_borrow.name, "world");
+        assert!(root_borrow.parent.is_none());
+        assert_eq!(root_borrow.children.len(), 0);
+    }
+
+    #[test]
+    fn add_child_frame_with_quaternion() {
+        let root = Frame::new_origin("world").unwrap();
+        let child = root
+            .add_child(
+                "dummy",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+
+        let root_borrow = root.borrow();
+        assert_eq!(root_borrow.children.len(), 1);
+
+        let child_borrow = child.borrow();
+        assert_eq!(&*child_borrow.name, "dummy");
+        assert!(child_borrow.parent.is_some());
+
+        let parent_name = child_borrow
+            .parent
+            .as_ref()
+            .unwrap()
+            .upgrade()
+            .unwrap()
+            .borrow()
+            .name
+            .clone();
+        assert_eq!(&*parent_name, "world");
+    }
+
+    #[test]
+    fn add_child_frame_with_rpy() {
+        let root = Frame::new_origin("world").unwrap();
+        let child = root
+            .add_child(
+                "dummy",
+                Vector3::new(0.0, 1.0, 0.0),
+                Rotation::from_rpy(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+
+        let child_borrow = child.borrow();
+        assert_eq!(&*child_borrow.name, "dummy");
+
+        let rotation = child_borrow.transform_to_parent.rotation;
+        let expected = UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2);
+        assert!((rotation.angle() - expected.angle()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn multiple_child_frames() {
+        let root = Frame::new_origin("world").unwrap();
+
+        let a = root
+            .add_child("a", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        let b = root
+            .add_child("b", Vector3::new(0.0, 1.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+
+        let root_borrow = root.borrow();
+        assert_eq!(root_borrow.children.len(), 2);
+
+        let a_borrow = a.borrow();
+        let b_borrow = b.borrow();
+
+        assert_eq!(
+            a_borrow
+                .parent
+                .as_ref()
+                .unwrap()
+                .upgrade()
+                .unwrap()
+                .borrow()
+                .name,
+            "world".into()
+        );
+        assert_eq!(
+            b_borrow
+                .parent
+                .as_ref()
+                .unwrap()
+                .upgrade()
+                .unwrap()
+                .borrow()
+                .name,
+            "world".into()
+        );
+    }
+
+    #[test]
+    fn remove_leaf_child() {
+        let root = Frame::new_origin("world").unwrap();
+        root.add_child("a", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        root.add_child("b", Vector3::new(0.0, 1.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+
+        let removed = root.remove_child("a").unwrap();
+        assert_eq!(removed.name(), "a");
+        assert!(removed.parent().is_none());
+        assert_eq!(removed.position(), Vector3::new(1.0, 0.0, 0.0));
+
+        let names: Vec<String> = root.children().iter().map(Frame::name).collect();
+        assert_eq!(names, vec!["b".to_string()]);
+
+        // The name is free again.
+        root.add_child("a", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+    }
+
+    #[test]
+    fn remove_child_with_subtree() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "robot",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let tool = arm
+            .add_child(
+                "tool",
+                Vector3::new(0.0, 0.0, 1.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let pose = tool
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        let removed = root.remove_child("robot").unwrap();
+        assert!(removed.is_same(&arm));
+        assert!(root.children().is_empty());
+        assert_eq!(removed.children().len(), 1);
+        assert!(tool.root().is_same(&arm));
+
+        // The pose now lives in the detached tree.
+        assert!(pose.frame().unwrap().root().is_same(&arm));
+        assert!(matches!(
+            pose.in_frame(&root),
+            Err(CartesianTreeError::NoCommonAncestor { .. })
+        ));
+        let in_arm = pose.in_frame(&arm).unwrap();
+        assert_eq!(in_arm.position(), Vector3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn remove_nonexistent_child() {
+        let root = Frame::new_origin("world").unwrap();
+        root.add_child("a", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        let result = root.remove_child("missing");
+        assert!(matches!(
+            result,
+            Err(CartesianTreeError::ChildNotFound { parent, child }) if child == "missing" && parent == "world"
+        ));
+        assert_eq!(root.children().len(), 1);
+    }
+
+    #[test]
+    fn reparent_keep_world() {
+        let root = Frame::new_origin("world").unwrap();
+        let table = root
+            .add_child(
+                "table",
+                Vector3::new(1.0, 2.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        let shelf = root
+            .add_child(
+                "shelf",
+                Vector3::new(-1.0, 0.5, 0.3),
+                UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3),
+            )
+            .unwrap();
+        let cup = table
+            .add_child(
+                "cup",
+                Vector3::new(0.2, 0.1, 0.8),
+                UnitQuaternion::from_euler_angles(0.0, 0.4, 0.0),
+            )
+            .unwrap();
+        let pose = cup
+            .add_pose(Vector3::new(0.0, 0.0, 0.1), UnitQuaternion::identity())
+            .unwrap();
+        let before = pose.in_frame(&root).unwrap().transformation();
+
+        cup.reparent(&shelf, ReparentPolicy::KeepWorld).unwrap();
+
+        assert!(cup.parent().unwrap().is_same(&shelf));
+        assert!(table.children().is_empty());
+        assert_eq!(shelf.children().len(), 1);
+
+        let after = pose.in_frame(&root).unwrap().transformation();
+        assert_relative_eq!(
+            before.translation.vector,
+            after.translation.vector,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(before.rotation, after.rotation, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn reparent_keep_local() {
+        let root = Frame::new_origin("world").unwrap();
+        let a = root
+            .add_child("a", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        let b = root
+            .add_child("b", Vector3::new(0.0, 5.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        let child = a
+            .add_child(
+                "child",
+                Vector3::new(0.0, 0.0, 1.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+
+        child.reparent(&b, ReparentPolicy::KeepLocal).unwrap();
+
+        assert_eq!(child.position(), Vector3::new(0.0, 0.0, 1.0));
+        let pose = child
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert_relative_eq!(
+            pose.in_frame(&root).unwrap().position(),
+            Vector3::new(0.0, 5.0, 1.0),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn reparent_rejects_cycles_and_conflicts() {
+        let root = Frame::new_origin("world").unwrap();
+        let a = root
+            .add_child("a", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let b = a
+            .add_child("b", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        root.add_child("b", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        assert!(matches!(
+            a.reparent(&b, ReparentPolicy::KeepWorld),
+            Err(CartesianTreeError::ReparentCycle { .. })
+        ));
+        assert!(matches!(
+            a.reparent(&a, ReparentPolicy::KeepLocal),
+            Err(CartesianTreeError::ReparentCycle { .. })
+        ));
+        assert!(matches!(
+            b.reparent(&root, ReparentPolicy::KeepWorld),
+            Err(CartesianTreeError::DuplicateChildName { .. })
+        ));
+        assert!(b.parent().unwrap().is_same(&a));
+    }
+
+    #[test]
+    fn find_frames_by_name() {
+        let root = Frame::new_origin("world").unwrap();
+        let left = root
+            .add_child("left", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let right = root
+            .add_child("right", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let left_tool = left
+            .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let right_tool = right
+            .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        assert!(root.find("world").unwrap().is_same(&root));
+        assert!(root.find("right").unwrap().is_same(&right));
+        assert!(root.find("tool").unwrap().is_same(&left_tool));
+        assert!(right.find("tool").unwrap().is_same(&right_tool));
+        assert!(left.find("right").is_none());
+
+        let tools = root.find_all("tool");
+        assert_eq!(tools.len(), 2);
+        assert!(tools[0].is_same(&left_tool));
+        assert!(tools[1].is_same(&right_tool));
+        assert!(root.find_all("missing").is_empty());
+    }
+
+    #[test]
+    fn find_in_deep_tree() {
+        let root = Frame::new_origin("root").unwrap();
+        let mut leaf = root.clone();
+        for i in 0..5_000 {
+            leaf = leaf
+                .add_child(
+                    format!("f{i}"),
+                    Vector3::zeros(),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+        }
+        assert!(root.find("f4999").unwrap().is_same(&leaf));
+        assert!(root.find("missing").is_none());
+    }
+
+    #[test]
+    fn path_round_trip() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child("robot", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let flange = arm
+            .add_child("flange", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        flange
+            .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        root.add_child("table", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        assert_eq!(root.path(), "/world");
+        assert_eq!(flange.path(), "/world/robot/flange");
+
+        let frames = root.find_all("tool").into_iter().chain([
+            root.clone(),
+            arm.clone(),
+            flange.clone(),
+            root.find("table").unwrap(),
+        ]);
+        for frame in frames {
+            let path = frame.path();
+            for start in [&root, &arm, &flange] {
+                let resolved = start.resolve_path(&path).unwrap();
+                assert!(resolved.is_same(&frame));
+                assert_eq!(resolved.path(), path);
+            }
+        }
+    }
+
+    #[test]
+    fn resolve_relative_paths() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child("robot", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let camera = arm
+            .add_child("camera", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let table = root
+            .add_child("table", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        assert!(root.resolve_path("robot/camera").unwrap().is_same(&camera));
+        assert!(arm.resolve_path("camera").unwrap().is_same(&camera));
+        assert!(camera.resolve_path("..").unwrap().is_same(&arm));
+        assert!(camera.resolve_path("../../table").unwrap().is_same(&table));
+        assert!(arm.resolve_path("./camera/").unwrap().is_same(&camera));
+        assert!(arm.resolve_path("").unwrap().is_same(&arm));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn frame_name_rules() {
+        let root = Frame::new_origin("world").unwrap();
+        let rejected = [
+            ("", "empty"),
+            ("robot/arm", "'/'"),
+            ("/", "'/'"),
+            (".", "relative"),
+            ("..", "relative"),
+            ("arm\n", "control"),
+            ("a\tb", "control"),
+            ("cam\u{0}", "control"),
+            (" arm", "whitespace"),
+            ("arm ", "whitespace"),
+        ];
+        for (name, reason_part) in rejected {
+            for result in [
+                Frame::new_origin(name),
+                root.add_child(name, Vector3::zeros(), UnitQuaternion::identity()),
+            ] {
+                assert!(
+                    matches!(
+                        &result,
+                        Err(CartesianTreeError::InvalidFrameName { name: rejected, reason })
+                            if rejected == name && reason.contains(reason_part)
+                    ),
+                    "{name:?}: {result:?}"
+                );
+            }
+        }
+        assert!(root.children().is_empty());
+        for name in ["arm_1", "Kamera vorne", "tool.flange", "...", "ü"] {
+            root.add_child(name, Vector3::zeros(), UnitQuaternion::identity())
+                .unwrap();
+        }
+
+        let permissive = Frame::new_origin_with("world", FrameNameRules::Permissive).unwrap();
+        let exotic = permissive
+            .add_child("left/arm ", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert_eq!(exotic.name_rules(), FrameNameRules::Permissive);
+        exotic
+            .add_child("..", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert!(matches!(
+            permissive.add_child("", Vector3::zeros(), UnitQuaternion::identity()),
+            Err(CartesianTreeError::InvalidFrameName { .. })
+        ));
+        assert!(matches!(
+            Frame::from_json(&permissive.to_json().unwrap()),
+            Err(CartesianTreeError::InvalidDocument { path, .. }) if path == "$.children[0].name"
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn configs_with_invalid_frame_names_fail() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "arm",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let config = |name: &str| {
+            serde_json::json!({
+                "name": "world",
+                "position": [0, 0, 0],
+                "orientation": [0, 0, 0, 1],
+                "children": [
+                    {"name": "arm", "position": [2, 0, 0], "orientation": [0, 0, 0, 1], "children": [
+                        {"name": name, "position": [0, 0, 0], "orientation": [0, 0, 0, 1], "children": []}
+                    ]}
+                ]
+            })
+            .to_string()
+        };
+
+        assert!(matches!(
+            root.apply_config(&config("tool/flange")),
+            Err(CartesianTreeError::InvalidFrameName { name, .. }) if name == "tool/flange"
+        ));
+        // Nothing was applied.
+        assert_eq!(arm.position(), Vector3::new(1.0, 0.0, 0.0));
+        assert!(matches!(
+            Frame::from_json(&config(" tool")),
+            Err(CartesianTreeError::InvalidDocument { path, reason })
+                if path == "$.children[0].children[0].name" && reason.contains("whitespace")
+        ));
+
+        root.apply_config(&config("tool")).unwrap();
+        assert_eq!(arm.position(), Vector3::new(2.0, 0.0, 0.0));
+        assert!(Frame::from_json(&config("tool")).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn rename_frames() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "arm",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let camera = arm
+            .add_child("camrea", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        arm.add_child("gripper", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let home = camera
+            .add_pose(Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+            .unwrap();
+        camera
+            .store_pose("home", &home, StorePolicy::Reject)
+            .unwrap();
+
+        assert!(matches!(
+            camera.rename("gripper"),
+            Err(CartesianTreeError::DuplicateChildName { parent, child })
+                if parent == "arm" && child == "gripper"
+        ));
+        assert!(matches!(
+            camera.rename("arm/camera"),
+            Err(CartesianTreeError::InvalidFrameName { .. })
+        ));
+        assert_eq!(camera.name(), "camrea");
+
+        camera.rename("camera").unwrap();
+        camera.rename("camera").unwrap();
+        assert!(root.resolve_path("arm/camera").unwrap().is_same(&camera));
+        assert!(matches!(
+            root.resolve_path("arm/camrea"),
+            Err(CartesianTreeError::PathNotFound { segment, .. }) if segment == "camrea"
+        ));
+        assert_eq!(camera.path(), "/world/arm/camera");
+        assert!(camera.get_pose("home").is_some());
+
+        root.rename("lab").unwrap();
+        assert!(root.resolve_path("/lab/arm/camera").is_ok());
+        let copy = Frame::from_json(&root.to_json().unwrap()).unwrap();
+        assert_eq!(copy.name(), "lab");
+        assert!(
+            copy.resolve_path("arm/camera")
+                .unwrap()
+                .get_pose("home")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn clone_subtree_is_independent() {
+        let root = Frame::new_origin("world").unwrap();
+        let tool = root
+            .add_child(
+                "tool",
+                Vector3::new(0.1, 0.2, 0.3),
+                UnitQuaternion::from_euler_angles(0.1, 0.0, 0.4),
+            )
+            .unwrap();
+        let tcp = tool
+            .add_child(
+                "tcp",
+                Vector3::new(0.0, 0.0, 0.15),
+                UnitQuaternion::from_euler_angles(0.0, 0.3, 0.0),
+            )
+            .unwrap();
+        tool.add_child(
+            "camera",
+            Vector3::new(0.05, 0.0, 0.0),
+            UnitQuaternion::identity(),
+        )
+        .unwrap();
+        tcp.set_metadata("mass", 0.4);
+        let grasp = tcp
+            .add_pose(Vector3::new(0.0, 0.0, 0.05), UnitQuaternion::identity())
+            .unwrap();
+        tcp.store_pose("grasp", &grasp, StorePolicy::Reject)
+            .unwrap();
+
+        let copy = tool.clone_subtree();
+        assert!(copy.parent().is_none());
+        assert_eq!(copy.name(), "tool");
+        let originals: Vec<_> = tool.iter_dfs().collect();
+        let copies: Vec<_> = copy.iter_dfs().collect();
+        assert_eq!(copies.len(), originals.len());
+        for (original, copied) in originals.iter().zip(&copies).skip(1) {
+            assert_eq!(
+                copied.path().replace("/tool", "/world/tool"),
+                original.path()
+            );
+            assert_eq!(
+                copied.transformation().unwrap(),
+                original.transformation().unwrap()
+            );
+        }
+        for copied in &copies {
+            assert!(originals.iter().all(|original| !original.is_same(copied)));
+        }
+
+        let copied_tcp = copy.resolve_path("tcp").unwrap();
+        assert_eq!(
+            copied_tcp.get_metadata("mass"),
+            Some(MetadataValue::Float(0.4))
+        );
+        copied_tcp
+            .set(Vector3::new(1.0, 1.0, 1.0), UnitQuaternion::identity())
+            .unwrap();
+        copied_tcp.set_metadata("mass", 0.9);
+        assert!(copied_tcp.remove_pose("grasp").is_some());
+        copied_tcp
+            .add_child("extra", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert_eq!(tcp.position(), Vector3::new(0.0, 0.0, 0.15));
+        assert_eq!(tcp.get_metadata("mass"), Some(MetadataValue::Float(0.4)));
+        assert!(tcp.get_pose("grasp").is_some());
+        assert!(tcp.children().is_empty());
+    }
+
+    #[test]
+    fn attach_copy_under_grafts_copies() {
+        let root = Frame::new_origin("cell").unwrap();
+        let left = root
+            .add_child(
+                "left",
+                Vector3::new(0.0, 1.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let right = root
+            .add_child(
+                "right",
+                Vector3::new(0.0, -1.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let gripper = left
+            .add_child(
+                "gripper",
+                Vector3::new(0.0, 0.0, 0.2),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        gripper
+            .add_child(
+                "tcp",
+                Vector3::new(0.0, 0.0, 0.1),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+
+        let copy = gripper.attach_copy_under(&right, None).unwrap();
+        assert!(copy.parent().unwrap().is_same(&right));
+        let tcp = root.resolve_path("right/gripper/tcp").unwrap();
+        assert!(tcp.parent().unwrap().is_same(&copy));
+        assert_relative_eq!(
+            tcp.transform_to(&root).unwrap().translation.vector,
+            Vector3::new(0.0, -1.0, 0.3)
+        );
+        assert!(matches!(
+            gripper.attach_copy_under(&right, None),
+            Err(CartesianTreeError::DuplicateChildName { .. })
+        ));
+
+        // Grafting into the subtree being copied copies it as it was before.
+        let nested = gripper.attach_copy_under(&gripper, Some("spare")).unwrap();
+        assert_eq!(nested.path(), "/cell/left/gripper/spare");
+        assert_eq!(nested.children().len(), 1);
+        assert!(root.validate().is_ok());
+
+        let permissive = Frame::new_origin_with("lab", FrameNameRules::Permissive).unwrap();
+        permissive
+            .add_child("odd/name", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert!(matches!(
+            permissive.attach_copy_under(&right, Some("lab")),
+            Err(CartesianTreeError::InvalidFrameName { .. })
+        ));
+        assert!(right.resolve_path("lab").is_err());
+    }
+
+    #[test]
+    fn equal_names_share_one_allocation() {
+        let root = Frame::new_origin("world").unwrap();
+        let left = root
+            .add_child("left", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap()
+            .add_child("gripper", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let right = root
+            .add_child("right", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap()
+            .add_child("gripper", Vector3::z(), UnitQuaternion::identity())
+            .unwrap();
+        assert!(Rc::ptr_eq(&left.shared_name(), &right.shared_name()));
+        assert!(root.resolve_path("right/gripper").unwrap().is_same(&right));
+        assert_eq!(root.find_all("gripper").len(), 2);
+        assert!(root.find("unknown name").is_none());
+
+        left.rename("tool").unwrap();
+        assert_eq!(left.name(), "tool");
+        assert_eq!(&*right.shared_name(), "gripper");
+        assert!(Rc::ptr_eq(
+            &left.shared_name(),
+            &root.find("tool").unwrap().shared_name()
+        ));
+        assert!(root.resolve_path("left/gripper").is_err());
+    }
+
+    #[test]
+    fn resolve_path_errors_name_segment() {
+        let root = Frame::new_origin("world").unwrap();
+        root.add_child("robot", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        assert!(matches!(
+            root.resolve_path("robot/lidar/mount"),
+            Err(CartesianTreeError::PathNotFound { path, segment })
+                if path == "robot/lidar/mount" && segment == "lidar"
+        ));
+        assert!(matches!(
+            root.resolve_path(".."),
+            Err(CartesianTreeError::PathNotFound { segment, .. }) if segment == ".."
+        ));
+        assert!(matches!(
+            root.resolve_path("/earth/robot"),
+            Err(CartesianTreeError::PathNotFound { segment, .. }) if segment == "earth"
+        ));
+        let message = root.resolve_path("robot/lidar").unwrap_err().to_string();
+        assert!(message.contains("'lidar'"));
+    }
+
+    #[test]
+    fn iterate_asymmetric_tree() {
+        // world
+        // ├── a
+        // │   ├── a1
+        // │   │   └── a1x
+        // │   └── a2
+        // └── b
+        //     └── b1
+        let root = Frame::new_origin("world").unwrap();
+        let a = root
+            .add_child("a", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let b = root
+            .add_child("b", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let a1 = a
+            .add_child("a1", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        a.add_child("a2", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        a1.add_child("a1x", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        b.add_child("b1", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        let dfs: Vec<String> = root.iter_dfs().map(|frame| frame.name()).collect();
+        assert_eq!(dfs, ["world", "a", "a1", "a1x", "a2", "b", "b1"]);
+
+        let bfs: Vec<String> = root.iter_bfs().map(|frame| frame.name()).collect();
+        assert_eq!(bfs, ["world", "a", "b", "a1", "a2", "b1", "a1x"]);
+
+        let sub: Vec<String> = a.iter_bfs().map(|frame| frame.name()).collect();
+        assert_eq!(sub, ["a", "a1", "a2", "a1x"]);
+    }
+
+    #[test]
+    fn mutate_while_iterating() {
+        let root = Frame::new_origin("world").unwrap();
+        let a = root
+            .add_child("a", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        a.add_child("a1", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        for frame in root.iter_dfs() {
+            if let Some(parent) = frame.parent() {
+                let offset = parent.position() + Vector3::new(1.0, 0.0, 0.0);
+                frame.set(offset, UnitQuaternion::identity()).unwrap();
+            }
+        }
+        for frame in root.iter_bfs().skip(1) {
+            frame
+                .apply_in_local_frame(&Isometry3::translation(0.0, 1.0, 0.0))
+                .unwrap();
+        }
+        assert_eq!(a.position(), Vector3::new(1.0, 1.0, 0.0));
+        assert_eq!(
+            root.find("a1").unwrap().position(),
+            Vector3::new(2.0, 1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn iterate_deep_chain() {
+        let root = Frame::new_origin("root").unwrap();
+        let mut leaf = root.clone();
+        for i in 0..5_000 {
+            leaf = leaf
+                .add_child(
+                    format!("f{i}"),
+                    Vector3::zeros(),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+        }
+        assert_eq!(root.iter_dfs().count(), 5_001);
+        assert_eq!(root.iter_bfs().count(), 5_001);
+        assert!(root.iter_dfs().last().unwrap().is_same(&leaf));
+        assert!(root.iter_bfs().last().unwrap().is_same(&leaf));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn very_deep_chain_serializes_and_drops() {
+        // Like a discretized cable model; recursing once per frame overflows the stack.
+        const DEPTH: usize = 100_000;
+        let root = Frame::new_origin("root").unwrap();
+        let mut leaf = root.clone();
+        for i in 0..DEPTH {
+            leaf = leaf
+                .add_child(format!("f{i}"), Vector3::x(), UnitQuaternion::identity())
+                .unwrap();
+        }
+
+        let json = root.to_json_compact().unwrap();
+        assert_eq!(json.matches(r#""name":"#).count(), DEPTH + 1);
+        assert!(json.ends_with(&"]}".repeat(DEPTH + 1)));
+        // Read back beyond the nesting limit of `serde_json`.
+        let copy = Frame::from_json(&json).unwrap();
+        let copy_leaf = copy.iter_dfs().last().unwrap();
+        assert_eq!(copy_leaf.depth(), DEPTH);
+        assert_relative_eq!(
+            copy_leaf.world_transform().unwrap().translation.vector,
+            Vector3::new(100_000.0, 0.0, 0.0)
+        );
+        leaf.set(Vector3::y(), UnitQuaternion::identity()).unwrap();
+        copy.apply_config(&root.to_json_compact().unwrap()).unwrap();
+        assert_eq!(copy_leaf.position(), Vector3::y());
+        drop(copy);
+        drop(copy_leaf);
+        assert_relative_eq!(
+            leaf.world_transform().unwrap().translation.vector,
+            Vector3::new(99_999.0, 1.0, 0.0)
+        );
+        assert!(format!("{root:?}").contains(r#"children: ["f0"]"#));
+        drop(root);
+        drop(leaf);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn compact_json_holds_the_same_document() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "arm",
+                Vector3::new(1.0, 2.0, 3.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        arm.set_metadata("mesh", "arm.stl");
+        arm.add_child("tool", Vector3::z(), UnitQuaternion::identity())
+            .unwrap();
+        root.add_child("table", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        let pretty: serde_json::Value = serde_json::from_str(&root.to_json().unwrap()).unwrap();
+        let compact: serde_json::Value =
+            serde_json::from_str(&root.to_json_compact().unwrap()).unwrap();
+        assert_eq!(pretty, compact);
+        assert_eq!(pretty["children"][0]["children"][0]["name"], "tool");
+        assert_eq!(
+            serde_json::to_string_pretty(&pretty["children"][1]["children"]).unwrap(),
+            "[]"
+        );
+    }
+
+    /// Returns the root, a tip and a camera of the same tree with the scalar type `T`.
+    fn scalar_tree<T: Real>() -> (Frame<T>, Frame<T>, Frame<T>) {
+        let vector = |x, y, z| Vector3::new(from_f64::<T>(x), from_f64(y), from_f64(z));
+        let rpy = |r, p, y| Rotation::from_rpy(from_f64::<T>(r), from_f64(p), from_f64(y));
+        let root = Frame::<T>::origin("world").unwrap();
+        let tip = root
+            .add_child("arm", vector(1.0, 2.0, 0.5), rpy(0.1, -0.2, 0.7))
+            .unwrap()
+            .add_child("tool", vector(0.3, 0.0, 0.8), rpy(0.0, 0.4, -1.1))
+            .unwrap()
+            .add_child("tip", vector(0.0, 0.0, 0.15), rpy(0.2, 0.0, 0.3))
+            .unwrap();
+        let camera = root
+            .add_child("camera", vector(-0.5, 1.5, 2.0), rpy(3.0, 0.1, -0.4))
+            .unwrap();
+        (root, tip, camera)
+    }
+
+    #[test]
+    fn single_precision_trees_agree_with_double_precision() {
+        let (_, tip, camera) = scalar_tree::<f64>();
+        let (_, tip32, camera32) = scalar_tree::<f32>();
+        let compare = |actual: Isometry3<f32>, expected: Isometry3<f64>| {
+            assert_relative_eq!(
+                actual.cast::<f64>().to_homogeneous(),
+                expected.to_homogeneous(),
+                epsilon = 1e-5
+            );
+        };
+        compare(
+            tip32.world_transform().unwrap(),
+            tip.world_transform().unwrap(),
+        );
+        compare(
+            tip32.transform_to(&camera32).unwrap(),
+            tip.transform_to(&camera).unwrap(),
+        );
+
+        let pose = tip.add_pose([0.1, 0.2, 0.3], [0.0, 0.0, 0.5]).unwrap();
+        let pose32 = tip32.add_pose([0.1, 0.2, 0.3], [0.0, 0.0, 0.5]).unwrap();
+        compare(
+            pose32.in_frame(&camera32).unwrap().transformation(),
+            pose.in_frame(&camera).unwrap().transformation(),
+        );
+
+        let arm = tip.resolve_path("/world/arm").unwrap();
+        let arm32 = tip32.resolve_path("/world/arm").unwrap();
+        arm.set(Vector3::new(-1.0, 0.5, 0.0), [0.3, 0.0, 0.0])
+            .unwrap();
+        arm32
+            .set(Vector3::new(-1.0, 0.5, 0.0), [0.3, 0.0, 0.0])
+            .unwrap();
+        compare(
+            tip32.world_transform().unwrap(),
+            tip.world_transform().unwrap(),
+        );
+        assert!(
+            arm32
+                .set(Vector3::new(f32::NAN, 0.0, 0.0), UnitQuaternion::identity())
+                .is_err()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn json_records_the_precision_of_the_tree() {
+        let (root, _, _) = scalar_tree::<f64>();
+        let (root32, tip32, _) = scalar_tree::<f32>();
+        assert!(!root.to_json().unwrap().contains("precision"));
+        let json = root32.to_json().unwrap();
+        assert!(json.contains(r#""precision": "f32""#), "{json}");
+
+        let copy = Frame::<f32>::parse_json(&json).unwrap();
+        assert_eq!(copy.to_json().unwrap(), json);
+        let widened = Frame::from_json(&json).unwrap();
+        assert_relative_eq!(
+            widened
+                .resolve_path("arm/tool/tip")
+                .unwrap()
+                .world_transform()
+                .unwrap()
+                .to_homogeneous(),
+            tip32
+                .world_transform()
+                .unwrap()
+                .cast::<f64>()
+                .to_homogeneous(),
+            epsilon = 1e-6
+        );
+        assert!(!widened.to_json().unwrap().contains("precision"));
+        assert!(matches!(
+            Frame::<f32>::parse_json(&json.replace(r#""f32""#, r#""f16""#)),
+            Err(CartesianTreeError::InvalidDocument { path, .. }) if path == "$.precision"
+        ));
+    }
+
+    #[test]
+    fn render_three_level_tree() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "robot",
+                Vector3::new(1.0, 0.0, 0.0),
+                Rotation::from_rpy(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        arm.add_child(
+            "camera",
+            Vector3::new(0.0, 0.0, 0.5),
+            UnitQuaternion::identity(),
+        )
+        .unwrap();
+        arm.add_child(
+            "gripper",
+            Vector3::new(0.1, 0.0, 0.0),
+            UnitQuaternion::identity(),
+        )
+        .unwrap();
+        root.add_child(
+            "table",
+            Vector3::new(-2.0, 0.25, 0.0),
+            UnitQuaternion::identity(),
+        )
+        .unwrap();
+
+        let expected = "\
+world  xyz: (0.000, 0.000, 0.000)  rpy: (0.000, 0.000, 0.000)
+├── robot  xyz: (1.000, 0.000, 0.000)  rpy: (0.000, 0.000, 1.571)
+│   ├── camera  xyz: (0.000, 0.000, 0.500)  rpy: (0.000, 0.000, 0.000)
+│   └── gripper  xyz: (0.100, 0.000, 0.000)  rpy: (0.000, 0.000, 0.000)
+└── table  xyz: (-2.000, 0.250, 0.000)  rpy: (0.000, 0.000, 0.000)
+";
+        assert_eq!(root.render_tree(), expected);
+
+        let expected_ascii = "\
+world  xyz: (0.000, 0.000, 0.000)  rpy: (0.000, 0.000, 0.000)
+|-- robot  xyz: (1.000, 0.000, 0.000)  rpy: (0.000, 0.000, 1.571)
+|   |-- camera  xyz: (0.000, 0.000, 0.500)  rpy: (0.000, 0.000, 0.000)
+|   `-- gripper  xyz: (0.100, 0.000, 0.000)  rpy: (0.000, 0.000, 0.000)
+`-- table  xyz: (-2.000, 0.250, 0.000)  rpy: (0.000, 0.000, 0.000)
+";
+        assert_eq!(root.render_tree_with(TreeStyle::Ascii), expected_ascii);
+
+        let expected_subtree = "\
+robot  xyz: (1.000, 0.000, 0.000)  rpy: (0.000, 0.000, 1.571)
+├── camera  xyz: (0.000, 0.000, 0.500)  rpy: (0.000, 0.000, 0.000)
+└── gripper  xyz: (0.100, 0.000, 0.000)  rpy: (0.000, 0.000, 0.000)
+";
+        assert_eq!(arm.render_tree(), expected_subtree);
+    }
+
+    #[test]
+    fn dot_export_counts_nodes_and_edges() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "robot",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        arm.add_child(
+            "camera",
+            Vector3::new(0.0, 0.0, 0.5),
+            UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+        )
+        .unwrap();
+        arm.add_child(
+            "gripper",
+            Vector3::new(0.0, 0.2, 0.0),
+            UnitQuaternion::identity(),
+        )
+        .unwrap();
+        root.add_child(
+            "table \"A\"",
+            Vector3::new(2.0, 0.0, 0.0),
+            UnitQuaternion::identity(),
+        )
+        .unwrap();
+
+        let dot = root.to_dot();
+        let lines: Vec<&str> = dot.lines().collect();
+        assert_eq!(lines.first(), Some(&"digraph frames {"));
+        assert_eq!(lines.last(), Some(&"}"));
+        let edges = lines.iter().filter(|line| line.contains(" -> ")).count();
+        let nodes = lines
+            .iter()
+            .filter(|line| line.contains("[label=") && !line.contains(" -> "))
+            .count();
+        assert_eq!((nodes, edges), (5, 4));
+        assert!(dot.contains(r#"n2 [label="camera\nworld: (1.000, 0.000, 0.500)"];"#));
+        assert!(dot.contains(
+            r#"n1 -> n2 [label="xyz: (0.000, 0.000, 0.500)\nrpy: (0.000, 0.000, 1.571)"];"#
+        ));
+        assert!(dot.contains(r#"[label="table \"A\"\nworld"#));
+
+        let options = DotOptions {
+            include_transforms: false,
+            precision: 1,
+        };
+        let plain = root.to_dot_with(&options);
+        assert!(plain.contains("n0 -> n4;"));
+        assert!(!plain.contains("xyz"));
+    }
+
+    #[test]
+    fn world_transform_follows_updates() {
+        let root = Frame::new_origin("world").unwrap();
+        let base = root
+            .add_child(
+                "base",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        let link = base
+            .add_child(
+                "link",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let tool = link
+            .add_child(
+                "tool",
+                Vector3::new(0.0, 0.0, 0.5),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let table = root
+            .add_child(
+                "table",
+                Vector3::new(0.0, 3.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+
+        assert_eq!(root.world_transform().unwrap(), Isometry3::identity());
+        assert_relative_eq!(
+            tool.world_transform().unwrap().translation.vector,
+            Vector3::new(1.0, 1.0, 0.5),
+            epsilon = 1e-12
+        );
+        let table_world = table.world_transform().unwrap();
+
+        link.set(Vector3::new(2.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        assert!(link.borrow().world_transform.is_none());
+        assert!(tool.borrow().world_transform.is_none());
+        assert_eq!(
+            base.borrow().world_transform,
+            Some(base.world_transform().unwrap())
+        );
+        assert_eq!(table.borrow().world_transform, Some(table_world));
+
+        assert_relative_eq!(
+            tool.world_transform().unwrap().translation.vector,
+            Vector3::new(1.0, 2.0, 0.5),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            link.world_transform().unwrap().translation.vector,
+            Vector3::new(1.0, 2.0, 0.0),
+            epsilon = 1e-12
+        );
+
+        base.apply_in_local_frame(&Isometry3::translation(0.0, 0.0, 1.0))
+            .unwrap();
+        assert_relative_eq!(
+            tool.world_transform().unwrap().translation.vector,
+            Vector3::new(1.0, 2.0, 1.5),
+            epsilon = 1e-12
+        );
+
+        tool.reparent(&table, ReparentPolicy::KeepLocal).unwrap();
+        assert_relative_eq!(
+            tool.world_transform().unwrap().translation.vector,
+            Vector3::new(0.0, 3.0, 0.5),
+            epsilon = 1e-12
+        );
+
+        let removed = root.remove_child("table").unwrap();
+        assert_eq!(removed.world_transform().unwrap(), Isometry3::identity());
+        assert_relative_eq!(
+            tool.world_transform().unwrap().translation.vector,
+            Vector3::new(0.0, 0.0, 0.5),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn world_transform_queries_use_cache() {
+        let root = Frame::new_origin("root").unwrap();
+        let mut leaf = root.clone();
+        for i in 0..5_000 {
+            leaf = leaf
+                .add_child(
+                    format!("f{i}"),
+                    Vector3::new(0.0, 0.0, 1.0),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+        }
+        let expected = leaf.world_transform().unwrap();
+        assert_relative_eq!(expected.translation.vector, Vector3::new(0.0, 0.0, 5000.0));
+
+        // Tamper with an ancestor's stored transform behind the cache's back: a query that
+        // re-walked the chain would see the change, a cached query does not.
+        let first = root.children()[0].clone();
+        first.borrow_mut().transform_to_parent = Isometry3::translation(100.0, 0.0, 0.0);
+        for _ in 0..10_000 {
+            assert_eq!(leaf.world_transform().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn grandchild_keeps_its_ancestors_alive() {
+        let (tcp, root) = {
+            let root = Frame::new_origin("world").unwrap();
+            let arm = root
+                .add_child(
+                    "arm",
+                    Vector3::new(1.0, 0.0, 0.0),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+            let tcp = arm
+                .add_child(
+                    "tcp",
+                    Vector3::new(0.0, 0.0, 0.5),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+            (tcp, root.downgrade())
+        };
+        assert_eq!(
+            tcp.world_transform().unwrap(),
+            Isometry3::translation(1.0, 0.0, 0.5)
+        );
+        assert_eq!(tcp.depth(), 2);
+        assert_eq!(tcp.path(), "/world/arm/tcp");
+        let pose = tcp
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let world = tcp.root();
+        assert_eq!(
+            pose.in_frame(&world).unwrap().position(),
+            Vector3::new(1.0, 0.0, 0.5)
+        );
+
+        drop((world, pose, tcp));
+        assert!(root.upgrade().is_none());
+    }
+
+    #[test]
+    fn dropped_frames_are_named_in_errors() {
+        let pose = {
+            let camera = Frame::new_origin("camera").unwrap();
+            camera
+                .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+                .unwrap()
+        };
+        let world = Frame::new_origin("world").unwrap();
+        let error = pose.in_frame(&world).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Frame 'camera' has been dropped during in_frame walk from 'camera' to 'world'"
+        );
+
+        // Handles held by parents do not keep the tree alive, so walking up from one fails.
+        let orphan = {
+            let root = Frame::new_origin("world").unwrap();
+            let arm = root
+                .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+                .unwrap();
+            root.rename("cell").unwrap();
+            Frame::link(arm.data)
+        };
+        assert!(matches!(
+            orphan.world_transform(),
+            Err(CartesianTreeError::WeakUpgradeFailed { frame, operation })
+                if frame == "cell" && operation == "world_transform walk from 'arm'"
+        ));
+    }
+
+    #[test]
+    fn poses_of_dropped_trees_fail_loudly() {
+        let (mut pose, other) = {
+            let camera = Frame::new_origin("camera").unwrap();
+            let pose = camera
+                .add_pose(Vector3::x(), UnitQuaternion::identity())
+                .unwrap();
+            (pose.clone(), pose)
+        };
+        let world = Frame::new_origin("world").unwrap();
+        let alive = world
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        let dropped = |result: Result<(), CartesianTreeError>| {
+            assert!(
+                matches!(result, Err(CartesianTreeError::FrameDropped)),
+                "{result:?}"
+            );
+        };
+        dropped(pose.frame().map(drop));
+        dropped(pose.compose(&alive).map(drop));
+        dropped(pose.into_frame("target").map(drop));
+        dropped(pose.interpolate(&alive, 0.5).map(drop));
+        dropped(Pose::mean(std::slice::from_ref(&pose)).map(drop));
+        dropped(Pose::mean(&[pose.clone(), other]).map(drop));
+        dropped(pose.translational_distance_to(&alive).map(drop));
+        dropped(pose.angular_distance_to(&alive).map(drop));
+        dropped(pose.approx_eq(&alive, 1e-9, 1e-9).map(drop));
+        dropped(
+            world
+                .calibrate_child("tool", Vector3::zeros(), UnitQuaternion::identity(), &pose)
+                .map(drop),
+        );
+        for result in [
+            pose.in_frame(&world).map(drop),
+            pose.rebase(&world),
+            alive
+                .in_frame(&world)
+                .and_then(|alive| alive.compose(&pose))
+                .map(drop),
+        ] {
+            assert!(
+                matches!(result, Err(CartesianTreeError::WeakUpgradeFailed { ref frame, .. }) if frame == "camera"),
+                "{result:?}"
+            );
+        }
+        #[allow(deprecated)]
+        let frame = pose.frame_opt();
+        assert!(frame.is_none());
+    }
+
+    #[test]
+    fn frames_list_their_live_poses() {
+        let root = Frame::new_origin("world").unwrap();
+        let tool = root
+            .add_child(
+                "tool",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let first = tool
+            .add_pose(Vector3::x(), UnitQuaternion::identity())
+            .unwrap();
+        let second = tool
+            .add_pose(Vector3::y(), UnitQuaternion::identity())
+            .unwrap();
+        let third = tool
+            .add_pose(Vector3::z(), UnitQuaternion::identity())
+            .unwrap();
+        let converted = first.in_frame(&root).unwrap();
+        assert_eq!(tool.pose_count(), 3);
+        assert_eq!(root.pose_count(), 1);
+
+        drop(second);
+        let mut copy = third.clone();
+        copy.set_position(Vector3::new(0.0, 0.0, 2.0)).unwrap();
+        let poses = tool.poses();
+        let positions: Vec<_> = poses.iter().map(Pose::position).collect();
+        assert_eq!(
+            positions,
+            [Vector3::x(), Vector3::z(), Vector3::new(0.0, 0.0, 2.0)]
+        );
+        assert!(
+            poses
+                .iter()
+                .all(|pose| pose.frame().unwrap().is_same(&tool))
+        );
+        // The returned copies are not registered themselves.
+        assert_eq!(tool.pose_count(), 3);
+
+        drop((first, third, copy));
+        assert_eq!(tool.pose_count(), 0);
+        assert!(tool.poses().is_empty());
+        drop(converted);
+        assert_eq!(root.pose_count(), 0);
+
+        // Entries of dropped poses do not pile up.
+        for index in 0..1000 {
+            let _ = tool.add_pose(Vector3::x(), UnitQuaternion::identity());
+            assert!(tool.borrow().pose_entries.borrow().len() <= 4, "{index}");
+        }
+    }
+
+    #[test]
+    fn moved_frames_keep_their_new_tree_alive() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "arm",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let tcp = arm
+            .add_child(
+                "tcp",
+                Vector3::new(0.0, 0.0, 0.5),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        drop(arm);
+
+        // The removed subtree no longer holds the old tree, but still holds its own root.
+        let old_root = root.downgrade();
+        drop(root.remove_child("arm").unwrap());
+        drop(root);
+        assert!(old_root.upgrade().is_none());
+        assert_eq!(
+            tcp.world_transform().unwrap(),
+            Isometry3::translation(0.0, 0.0, 0.5)
+        );
+
+        let table = {
+            let cell = Frame::new_origin("cell").unwrap();
+            let table = cell
+                .add_child(
+                    "table",
+                    Vector3::new(0.0, 2.0, 0.0),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+            tcp.reparent(&table, ReparentPolicy::KeepLocal).unwrap();
+            table.downgrade()
+        };
+        assert!(table.upgrade().is_some());
+        assert_eq!(tcp.path(), "/cell/table/tcp");
+        assert_eq!(
+            tcp.world_transform().unwrap(),
+            Isometry3::translation(0.0, 2.0, 0.5)
+        );
+        drop(tcp);
+        assert!(table.upgrade().is_none());
+    }
+
+    #[test]
+    fn set_world_transform_round_trip() {
+        let root = Frame::new_origin("world").unwrap();
+        let base = root
+            .add_child(
+                "base",
+                Vector3::new(1.0, -2.0, 0.5),
+                UnitQuaternion::from_euler_angles(0.3, -0.2, 1.1),
+            )
+            .unwrap();
+        let link = base
+            .add_child(
+                "link",
+                Vector3::new(0.0, 0.7, 0.0),
+                UnitQuaternion::from_euler_angles(-0.4, 0.9, 0.0),
+            )
+            .unwrap();
+        let tool = link
+            .add_child(
+                "tool",
+                Vector3::new(0.0, 0.0, 0.2),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+
+        let position = Vector3::new(0.25, 1.5, -3.0);
+        let orientation = UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3);
+        link.set_world_transform(position, orientation).unwrap();
+
+        let world = link.world_transform().unwrap();
+        assert_relative_eq!(world.translation.vector, position, epsilon = 1e-12);
+        assert_relative_eq!(world.rotation, orientation, epsilon = 1e-12);
+
+        // Descendants move along with the frame.
+        let expected_tool = world * Isometry3::translation(0.0, 0.0, 0.2);
+        assert_relative_eq!(
+            tool.world_transform().unwrap().translation.vector,
+            expected_tool.translation.vector,
+            epsilon = 1e-12
+        );
+
+        assert!(matches!(
+            root.set_world_transform(position, orientation),
+            Err(CartesianTreeError::CannotUpdateRootTransform { .. })
+        ));
+    }
+
+    #[test]
+    fn update_during_borrow_reports_concurrent_access() {
+        let root = Frame::new_origin("root").unwrap();
+        let child = root
+            .add_child("child", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        let _borrow = child.borrow();
+        assert!(matches!(
+            child.set(Vector3::x(), UnitQuaternion::identity()),
+            Err(CartesianTreeError::ConcurrentAccess)
+        ));
+        assert!(matches!(
+            child.add_child("grandchild", Vector3::zeros(), UnitQuaternion::identity()),
+            Err(CartesianTreeError::ConcurrentAccess)
+        ));
+    }
+
+    #[test]
+    fn nested_access_during_traversal() {
+        let root = Frame::new_origin("root").unwrap();
+        let arm = root
+            .add_child("arm", Vector3::x(), UnitQuaternion::identity())
+            .unwrap();
+        arm.add_child("tool", Vector3::z(), UnitQuaternion::identity())
+            .unwrap();
+        let pose = arm
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        for frame in root.iter_dfs() {
+            if let Some(parent) = frame.parent() {
+                assert!(parent.children().iter().any(|child| child.is_same(&frame)));
+                frame
+                    .apply_in_parent_frame(&Isometry3::translation(0.0, 1.0, 0.0))
+                    .unwrap();
+            }
+            pose.in_frame(&frame).unwrap();
+            frame
+                .add_child("marker", Vector3::zeros(), UnitQuaternion::identity())
+                .unwrap();
+        }
+        assert_eq!(root.find_all("marker").len(), 3);
+    }
+
+    #[test]
+    fn conversions_may_read_the_frame_being_updated() {
+        struct Mirror(Frame);
+        impl From<Mirror> for MetadataValue {
+            fn from(mirror: Mirror) -> Self {
+                Self::from(mirror.0.metadata_keys().join(","))
+            }
+        }
+        impl From<Mirror> for Rotation {
+            fn from(mirror: Mirror) -> Self {
+                mirror.0.orientation()
+            }
+        }
+
+        let root = Frame::new_origin("root").unwrap();
+        root.set_metadata("first", 1);
+        root.set_metadata("count", Mirror(root.clone()));
+        assert_eq!(root.get_metadata("count"), Some("first".into()));
+
+        let child = root
+            .add_child("child", Vector3::zeros(), Mirror(root.clone()))
+            .unwrap();
+        child.set(Vector3::x(), Mirror(child.clone())).unwrap();
+    }
+
+    #[test]
+    fn dropping_a_callback_may_read_the_frame() {
+        struct ReadOnDrop(Frame);
+        impl Drop for ReadOnDrop {
+            fn drop(&mut self) {
+                let _ = self.0.metadata_keys();
+            }
+        }
+
+        let root = Frame::new_origin("root").unwrap();
+        let child = root
+            .add_child("child", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let guard = ReadOnDrop(child.clone());
+        let id = child.on_transform_changed(move |_| {
+            let _ = &guard;
+        });
+        assert!(child.unsubscribe(id));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn add_child_looking_at_target() {
+        let root = Frame::new_origin("world").unwrap();
+        let camera = root
+            .add_child_looking_at(
+                "camera",
+                Vector3::new(2.0, 0.0, 0.0),
+                Point3::origin(),
+                Vector3::z(),
+            )
+            .unwrap();
+
+        assert_relative_eq!(
+            camera.transformation().unwrap().rotation.into_inner(),
+            Quaternion::new(0.5, 0.5, 0.5, 0.5),
+            epsilon = 1e-12
+        );
+        let target_in_camera = root.transform_point_to(&camera, &Point3::origin()).unwrap();
+        assert_relative_eq!(
+            target_in_camera,
+            Point3::new(0.0, 0.0, -2.0),
+            epsilon = 1e-12
+        );
+
+        assert!(matches!(
+            root.add_child_looking_at("blind", Vector3::z(), Point3::origin(), Vector3::z()),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+        assert!(root.find("blind").is_none());
+    }
+
+    #[test]
+    fn pose_matrix_round_trip() {
+        let frame = Frame::new_origin("dummy").unwrap();
+        let pose = frame
+            .add_pose(
+                Vector3::new(0.3, -1.0 / 3.0, 2.5),
+                UnitQuaternion::from_euler_angles(0.4, -1.2, 2.9),
+            )
+            .unwrap();
+        let matrix = pose.to_matrix();
+        let copy = frame.add_pose_from_matrix(&matrix).unwrap();
+        assert!((copy.to_matrix() - matrix).abs().max() < 1e-12);
+
+        let mut skewed = matrix;
+        skewed[(3, 0)] = 0.5;
+        let mut drifted = matrix;
+        drifted[(0, 0)] += 1e-4;
+        let mut reflected = matrix;
+        reflected.fixed_view_mut::<3, 1>(0, 2).neg_mut();
+        let mut infinite = matrix;
+        infinite[(1, 3)] = f64::INFINITY;
+        for (invalid, hint) in [
+            (skewed, "last row"),
+            (drifted, "orthonormalize"),
+            (reflected, "reflection"),
+            (infinite, "translation"),
+        ] {
+            assert!(matches!(
+                frame.add_pose_from_matrix(&invalid),
+                Err(CartesianTreeError::InvalidTransformMatrix { reason }) if reason.contains(hint)
+            ));
+        }
+        let corrected = frame
+            .add_pose_from_matrix_orthonormalized(&drifted)
+            .unwrap();
+        assert!((corrected.to_matrix() - matrix).abs().max() < 1e-4);
+        assert!(
+            frame
+                .add_pose_from_matrix_orthonormalized(&reflected)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn pose_component_setters() {
+        let frame = Frame::new_origin("dummy").unwrap();
+        let mut pose = frame
+            .add_pose(
+                Vector3::new(1.0, 2.0, 3.0),
+                Rotation::from_rpy(0.1, -0.2, 0.3),
+            )
+            .unwrap();
+        let before = pose.transformation();
+        assert_relative_eq!(pose.rpy(), Vector3::new(0.1, -0.2, 0.3), epsilon = 1e-12);
+
+        pose.set_position(Vector3::new(-4.0, 5.0, 0.5)).unwrap();
+        assert_eq!(pose.position(), Vector3::new(-4.0, 5.0, 0.5));
+        // The rotation is left untouched bit for bit.
+        assert_eq!(pose.transformation().rotation, before.rotation);
+
+        pose.set_orientation(UnitQuaternion::from_euler_angles(0.0, 0.0, 1.0))
+            .unwrap();
+        assert_eq!(pose.position(), Vector3::new(-4.0, 5.0, 0.5));
+        assert_relative_eq!(pose.rpy(), Vector3::new(0.0, 0.0, 1.0), epsilon = 1e-12);
+
+        assert!(pose.set_position(Vector3::new(f64::NAN, 0.0, 0.0)).is_err());
+        assert!(
+            pose.set_orientation(Rotation::from_quaternion(0.0, 0.0, 0.0, 0.0))
+                .is_err()
+        );
+        assert_eq!(pose.position(), Vector3::new(-4.0, 5.0, 0.5));
+    }
+
+    #[test]
+    fn pose_into_frame() {
+        let root = Frame::new_origin("root").unwrap();
+        let table = root
+            .add_child(
+                "table",
+                Vector3::new(1.0, 0.0, 0.5),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, 0.7),
+            )
+            .unwrap();
+        let grasp = table
+            .add_pose(
+                Vector3::new(0.2, -0.1, 0.3),
+                UnitQuaternion::from_euler_angles(0.3, 0.0, -1.2),
+            )
+            .unwrap();
+
+        let grasp_frame = grasp.into_frame("grasp_1").unwrap();
+        assert!(grasp_frame.parent().unwrap().is_same(&table));
+        let expected = grasp.in_frame(&root).unwrap().transformation();
+        let actual = grasp_frame
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap()
+            .in_frame(&root)
+            .unwrap()
+            .transformation();
+        assert_relative_eq!(actual, expected, epsilon = 1e-12);
+
+        assert!(matches!(
+            grasp.into_frame("grasp_1"),
+            Err(CartesianTreeError::DuplicateChildName { .. })
+        ));
+        let orphan = Frame::new_origin("orphan")
+            .unwrap()
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert!(matches!(
+            orphan.into_frame("grasp"),
+            Err(CartesianTreeError::FrameDropped)
+        ));
+    }
+
+    #[test]
+    fn pose_rebase_round_trip() {
+        let root = Frame::new_origin("root").unwrap();
+        let a = root
+            .add_child(
+                "a",
+                Vector3::new(1.0, -2.0, 0.5),
+                UnitQuaternion::from_euler_angles(0.4, -0.3, 1.1),
+            )
+            .unwrap();
+        let b = root
+            .add_child(
+                "b",
+                Vector3::new(-0.7, 0.2, 3.0),
+                UnitQuaternion::from_euler_angles(-1.2, 0.6, 0.2),
+            )
+            .unwrap()
+            .add_child(
+                "b_tool",
+                Vector3::new(0.0, 0.0, 0.25),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, 2.5),
+            )
+            .unwrap();
+        let mut pose = a
+            .add_pose(
+                Vector3::new(0.3, 0.1, -0.2),
+                UnitQuaternion::from_euler_angles(0.2, 0.1, -0.9),
+            )
+            .unwrap();
+        let original = pose.transformation();
+        let world = pose.in_frame(&root).unwrap().transformation();
+
+        pose.rebase(&b).unwrap();
+        assert!(pose.frame().unwrap().is_same(&b));
+        assert_relative_eq!(
+            pose.in_frame(&root).unwrap().transformation(),
+            world,
+            epsilon = 1e-12
+        );
+
+        pose.rebase(&a).unwrap();
+        assert!(pose.frame().unwrap().is_same(&a));
+        assert_relative_eq!(pose.transformation(), original, epsilon = 1e-12);
+
+        let other = Frame::new_origin("other").unwrap();
+        assert!(matches!(
+            pose.rebase(&other),
+            Err(CartesianTreeError::NoCommonAncestor { .. })
+        ));
+        assert!(pose.frame().unwrap().is_same(&a));
+    }
+
+    #[test]
+    fn pose_mean_recovers_perturbed_pose() {
+        // Small linear congruential generator, so the noise is reproducible.
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut noise = |scale: f64| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            let unit = f64::from(u32::try_from(state >> 32).unwrap()) / f64::from(u32::MAX);
+            (2.0f64.mul_add(unit, -1.0)) * scale
+        };
+        let root = Frame::new_origin("root").unwrap();
+        let tracker = root
+            .add_child(
+                "tracker",
+                Vector3::new(0.5, -1.0, 2.0),
+                UnitQuaternion::from_euler_angles(0.2, 0.4, -2.8),
+            )
+            .unwrap();
+        let truth = Isometry3::from_parts(
+            Translation3::new(0.3, 0.2, -0.1),
+            UnitQuaternion::from_euler_angles(3.0, -0.5, 1.2),
+        );
+
+        let measurements: Vec<_> = (0..200)
+            .map(|index| {
+                let perturbation = UnitQuaternion::from_scaled_axis(Vector3::new(
+                    noise(0.02),
+                    noise(0.02),
+                    noise(0.02),
+                ));
+                let offset = Vector3::new(noise(0.001), noise(0.001), noise(0.001));
+                let mut rotation = truth.rotation * perturbation;
+                if index % 2 == 1 {
+                    // Both signs describe the same rotation.
+                    rotation = UnitQuaternion::new_unchecked(-rotation.into_inner());
+                }
+                let pose = root
+                    .add_pose(truth.translation.vector + offset, rotation)
+                    .unwrap();
+                // Mix frames; the mean is computed in the frame of the first pose.
+                if index % 3 == 0 {
+                    pose.in_frame(&tracker).unwrap()
+                } else {
+                    pose
+                }
+            })
+            .collect();
+
+        let mean = Pose::mean(&measurements).unwrap();
+        assert!(mean.frame().unwrap().is_same(&tracker));
+        let mean = mean.in_frame(&root).unwrap().transformation();
+        assert!((mean.translation.vector - truth.translation.vector).norm() < 1e-3);
+        assert!(mean.rotation.angle_to(&truth.rotation) < 5e-3);
+
+        let weights: Vec<_> = (0..measurements.len())
+            .map(|index| if index == 4 { 1.0 } else { 0.0 })
+            .collect();
+        let picked = Pose::weighted_mean(&measurements, &weights).unwrap();
+        assert_relative_eq!(
+            picked.in_frame(&root).unwrap().transformation(),
+            measurements[4].transformation(),
+            epsilon = 1e-9
+        );
+
+        let single = Pose::mean(&measurements[1..2]).unwrap();
+        assert_eq!(single.transformation(), measurements[1].transformation());
+        assert!(single.frame().unwrap().is_same(&root));
+
+        assert!(matches!(
+            Pose::mean(&[]),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+        assert!(matches!(
+            Pose::weighted_mean(&measurements[..2], &[1.0]),
+            Err(CartesianTreeError::LengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        ));
+        for weights in [[1.0, -1.0], [0.0, 0.0], [1.0, f64::NAN]] {
+            assert!(matches!(
+                Pose::weighted_mean(&measurements[..2], &weights),
+                Err(CartesianTreeError::DegenerateInput { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_apply_in_parent_frame() {
+        let root = Frame::new_origin("root").unwrap();
+        let child = root
+            .add_child(
+                "dummy",
+                Vector3::new(1.0, 0.0, 1.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        child
+            .apply_in_parent_frame(&Isometry3::from_parts(
+                Translation3::identity(),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            ))
+            .unwrap();
+
+        assert_relative_eq!(
+            child.transformation().unwrap().translation.vector,
+            Vector3::new(0.0, 1.0, 1.0),
+            epsilon = 1e-10
+        );
+
+        child
+            .apply_in_parent_frame(&Isometry3::from_parts(
+                Translation3::new(1.0, 0.0, 1.0),
+                UnitQuaternion::identity(),
+            ))
+            .unwrap();
+        assert_relative_eq!(
+            child.transformation().unwrap().translation.vector,
+            Vector3::new(1.0, 1.0, 2.0),
+            epsilon = 1e-10
+        );
+
+        // Test root frame error
+        assert!(
+            root.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_apply_in_local_frame() {
+        let root = Frame::new_origin("root").unwrap();
+        let child = root
+            .add_child(
+                "dummy",
+                Vector3::zeros(),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+
+        child
+            .apply_in_local_frame(&Isometry3::from_parts(
+                Translation3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            ))
+            .unwrap();
+
+        assert_relative_eq!(
+            child.transformation().unwrap().translation.vector,
+            Vector3::new(0.0, 1.0, 0.0),
+            epsilon = 1e-10
+        );
+
+        child
+            .apply_in_local_frame(&Isometry3::from_parts(
+                Translation3::identity(),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            ))
+            .unwrap();
+        assert_relative_eq!(
+            child.transformation().unwrap().translation.vector,
+            Vector3::new(0.0, 1.0, 0.0),
+            epsilon = 1e-10
+        );
+
+        let (roll, pitch, yaw) = child.transformation().unwrap().rotation.euler_angles();
+        assert_relative_eq!(roll, 0.0, epsilon = 1e-10);
+        assert_relative_eq!(pitch, 0.0, epsilon = 1e-10);
+        assert_relative_eq!(yaw, std::f64::consts::PI, epsilon = 1e-10);
+
+        // Test root frame error
+        assert!(
+            root.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_pose_apply_in_parent_frame() {
+        let root = Frame::new_origin("root").unwrap();
+        let mut pose = root
+            .add_pose(Vector3::new(1.0, 0.0, 1.0), UnitQuaternion::identity())
+            .unwrap();
+
+        pose.apply_in_parent_frame(&Isometry3::from_parts(
+            Translation3::identity(),
+            UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+        ));
+
+        assert_relative_eq!(
+            pose.transformation().translation.vector,
+            Vector3::new(0.0, 1.0, 1.0),
+            epsilon = 1e-10
+        );
+
+        pose.apply_in_parent_frame(&Isometry3::from_parts(
+            Translation3::new(1.0, 0.0, 1.0),
+            UnitQuaternion::identity(),
+        ));
+        assert_relative_eq!(
+            pose.transformation().translation.vector,
+            Vector3::new(1.0, 1.0, 2.0),
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn test_pose_apply_in_local_frame() {
+        let root = Frame::new_origin("root").unwrap();
+        let mut pose = root
+            .add_pose(
+                Vector3::zeros(),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+
+        pose.apply_in_local_frame(&Isometry3::from_parts(
+            Translation3::new(1.0, 0.0, 0.0),
+            UnitQuaternion::identity(),
+        ));
+
+        assert_relative_eq!(
+            pose.transformation().translation.vector,
+            Vector3::new(0.0, 1.0, 0.0),
+            epsilon = 1e-10
+        );
+
+        pose.apply_in_local_frame(&Isometry3::from_parts(
+            Translation3::identity(),
+            UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+        ));
+        assert_relative_eq!(
+            pose.transformation().translation.vector,
+            Vector3::new(0.0, 1.0, 0.0),
+            epsilon = 1e-10
+        );
+
+        let (roll, pitch, yaw) = pose.transformation().rotation.euler_angles();
+        assert_relative_eq!(roll, 0.0, epsilon = 1e-10);
+        assert_relative_eq!(pitch, 0.0, epsilon = 1e-10);
+        assert_relative_eq!(yaw, std::f64::consts::PI, epsilon = 1e-10);
+
+        // Test root frame error
+        assert!(
+            root.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn compose_and_invert_poses() {
+        let root = Frame::new_origin("root").unwrap();
+        let f1 = root
+            .add_child(
+                "f1",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+
+        let pose = f1
+            .add_pose(
+                Vector3::new(0.5, -1.0, 2.0),
+                UnitQuaternion::from_euler_angles(0.3, 0.2, -0.7),
+            )
+            .unwrap();
+        let identity = pose.compose(&pose.inverse()).unwrap();
+        assert!(identity.frame().unwrap().is_same(&f1));
+        assert_relative_eq!(
+            identity.transformation(),
+            Isometry3::identity(),
+            epsilon = 1e-12
+        );
+
+        // The other pose is expressed in f1 before chaining.
+        let offset = root
+            .add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        let composed = pose.compose(&offset).unwrap();
+        assert_relative_eq!(
+            composed.transformation(),
+            pose.transformation()
+                * f1.transformation().unwrap().inverse()
+                * offset.transformation(),
+            epsilon = 1e-12
+        );
+
+        let other_root = Frame::new_origin("other").unwrap();
+        let foreign = other_root
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert!(matches!(
+            pose.compose(&foreign),
+            Err(CartesianTreeError::NoCommonAncestor { .. })
+        ));
+    }
+
+    #[test]
+    fn scaled_frames_scale_points_once() {
+        let root = Frame::new_origin("world").unwrap();
+        let scan = root
+            .add_scaled_child(
+                "scan",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+                2.0,
+            )
+            .unwrap();
+        let marker = scan
+            .add_child(
+                "marker",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        assert_relative_eq!(scan.scale(), 2.0);
+        assert_relative_eq!(marker.scale(), 1.0);
+
+        let point = marker
+            .transform_point_to(&root, &Point3::new(0.0, 0.0, 1.0))
+            .unwrap();
+        assert_relative_eq!(point, Point3::new(1.0, 2.0, 2.0), epsilon = 1e-12);
+        assert_relative_eq!(
+            root.transform_point_to(&marker, &point).unwrap(),
+            Point3::new(0.0, 0.0, 1.0),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            marker
+                .transform_points_to(&root, &[Point3::origin()])
+                .unwrap()[0],
+            Point3::new(1.0, 2.0, 0.0),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(marker.similarity_to(&root).unwrap().scaling(), 2.0);
+        assert_relative_eq!(marker.similarity_to(&scan).unwrap().scaling(), 1.0);
+
+        // Poses keep a rigid orientation; only their position and its uncertainty scale.
+        let mut pose = marker
+            .add_pose(Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+            .unwrap();
+        pose.set_covariance(Some(Matrix6::identity())).unwrap();
+        let in_root = pose.in_frame(&root).unwrap();
+        assert_relative_eq!(
+            in_root.position(),
+            Vector3::new(1.0, 2.0, 2.0),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            in_root.orientation().as_quaternion().angle(),
+            std::f64::consts::FRAC_PI_2,
+            epsilon = 1e-12
+        );
+        // The scale squared, plus the rotational uncertainty acting at the offset (1, 2, 0).
+        assert_relative_eq!(
+            in_root.covariance().unwrap()[(2, 2)],
+            4.0 + 5.0,
+            epsilon = 1e-12
+        );
+
+        assert!(matches!(
+            marker.transform_to(&root),
+            Err(CartesianTreeError::ScaledTransform { .. })
+        ));
+        assert!(matches!(
+            marker.world_transform(),
+            Err(CartesianTreeError::ScaledTransform { to, .. }) if to == "world"
+        ));
+        assert!(marker.transform_to(&scan).is_ok());
+        assert!(matches!(
+            root.add_scaled_child("flat", Vector3::zeros(), UnitQuaternion::identity(), 0.0),
+            Err(CartesianTreeError::InvalidScale { .. })
+        ));
+        assert!(matches!(
+            root.add_scaled_child(
+                "nan",
+                Vector3::zeros(),
+                UnitQuaternion::identity(),
+                f64::NAN
+            ),
+            Err(CartesianTreeError::InvalidScale { .. })
+        ));
+        assert_eq!(root.children().len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn unscaled_trees_are_unaffected_by_similarities() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "arm",
+                Vector3::new(0.3, -1.2, 0.7),
+                UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3),
+            )
+            .unwrap();
+        let camera = arm
+            .add_child(
+                "camera",
+                Vector3::new(0.05, 0.0, 0.4),
+                UnitQuaternion::from_euler_angles(-0.4, 0.0, 1.1),
+            )
+            .unwrap();
+        let table = root
+            .add_child(
+                "table",
+                Vector3::new(2.0, 0.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, -0.5),
+            )
+            .unwrap();
+
+        let similarity = camera.similarity_to(&table).unwrap();
+        assert_relative_eq!(similarity.scaling(), 1.0);
+        assert_eq!(similarity.isometry, camera.transform_to(&table).unwrap());
+        assert_relative_eq!(camera.scale(), 1.0);
+        assert!(!camera.to_json().unwrap().contains("scale"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn scales_survive_serialization_and_copies() {
+        let root = Frame::new_origin("world").unwrap();
+        root.add_scaled_child(
+            "scan",
+            Vector3::new(1.0, 0.0, 0.0),
+            UnitQuaternion::identity(),
+            1.5,
+        )
+        .unwrap();
+
+        let restored = Frame::from_json(&root.to_json().unwrap()).unwrap();
+        assert_relative_eq!(restored.resolve_path("scan").unwrap().scale(), 1.5);
+        assert_relative_eq!(
+            root.clone_subtree().resolve_path("scan").unwrap().scale(),
+            1.5
+        );
+        let scan = root.resolve_path("scan").unwrap();
+        let copy = scan.attach_copy_under(&root, Some("scan_copy")).unwrap();
+        assert_relative_eq!(copy.scale(), 1.5);
+
+        let document = root.to_json().unwrap().replace("1.5", "-1.5");
+        assert!(matches!(
+            Frame::from_json(&document),
+            Err(CartesianTreeError::InvalidDocument { path, .. }) if path == "$.children[0].scale"
+        ));
+    }
+
+    #[test]
+    fn pose_covariance_follows_frame_changes() {
+        let root = Frame::new_origin("root").unwrap();
+        let rotated = root
+            .add_child(
+                "rotated",
+                Vector3::zeros(),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        let mut pose = rotated
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let diagonal = Matrix6::from_diagonal(&Vector6::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0));
+        pose.set_covariance(Some(diagonal)).unwrap();
+
+        // A quarter turn about z swaps the x and y variances of both blocks.
+        let in_root = pose.in_frame(&root).unwrap();
+        assert_relative_eq!(
+            in_root.covariance().unwrap(),
+            Matrix6::from_diagonal(&Vector6::new(2.0, 1.0, 3.0, 5.0, 4.0, 6.0)),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            in_root.in_frame(&rotated).unwrap().covariance().unwrap(),
+            diagonal,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            in_root.inverse().covariance().unwrap(),
+            diagonal,
+            epsilon = 1e-12
+        );
+
+        // An offset frame turns rotational uncertainty into translational uncertainty.
+        let shifted = root
+            .add_child(
+                "shifted",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let mut yaw_only = shifted
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let mut yaw_covariance = Matrix6::zeros();
+        yaw_covariance[(5, 5)] = 0.5;
+        yaw_only.set_covariance(Some(yaw_covariance)).unwrap();
+        let mut expected = yaw_covariance;
+        expected[(1, 1)] = 0.5;
+        expected[(1, 5)] = -0.5;
+        expected[(5, 1)] = -0.5;
+        assert_relative_eq!(
+            yaw_only.in_frame(&root).unwrap().covariance().unwrap(),
+            expected,
+            epsilon = 1e-12
+        );
+
+        let mut invalid = Matrix6::identity();
+        invalid[(2, 4)] = f64::NAN;
+        assert!(matches!(
+            pose.set_covariance(Some(invalid)),
+            Err(CartesianTreeError::NonFiniteValue { field, .. }) if field == "covariance[2][4]"
+        ));
+        assert_eq!(pose.covariance(), Some(diagonal));
+        pose.set_covariance(None).unwrap();
+        assert!(pose.in_frame(&root).unwrap().covariance().is_none());
     }
-}
 
-impl HasParent for Frame {
-    type Node = Self;
+    #[test]
+    fn compose_propagates_covariance() {
+        let root = Frame::new_origin("root").unwrap();
+        let mut turned = root
+            .add_pose(
+                Vector3::zeros(),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        let mut offset = root
+            .add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        assert!(turned.compose(&offset).unwrap().covariance().is_none());
 
-    fn parent(&self) -> Option<Self::Node> {
-        self.borrow()
-            .parent
-            .clone()
-            .and_then(|data_weak| data_weak.upgrade().map(|data_rc| Self { data: data_rc }))
+        let own = Matrix6::identity() * 0.1;
+        turned.set_covariance(Some(own)).unwrap();
+        assert_eq!(turned.compose(&offset).unwrap().covariance(), Some(own));
+
+        offset
+            .set_covariance(Some(Matrix6::from_diagonal(&Vector6::new(
+                1.0, 2.0, 3.0, 4.0, 5.0, 6.0,
+            ))))
+            .unwrap();
+        assert_relative_eq!(
+            turned.compose(&offset).unwrap().covariance().unwrap(),
+            own + Matrix6::from_diagonal(&Vector6::new(2.0, 1.0, 3.0, 5.0, 4.0, 6.0)),
+            epsilon = 1e-12
+        );
     }
-}
 
-impl NodeEquality for Frame {
-    fn is_same(&self, other: &Self) -> bool {
-        Rc::ptr_eq(&self.data, &other.data)
+    #[test]
+    fn multiply_pose_with_isometry() {
+        let root = Frame::new_origin("root").unwrap();
+        let pose = root
+            .add_pose(
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        let step = Isometry3::translation(1.0, 0.0, 0.0);
+
+        let moved = &pose * step;
+        assert!(moved.frame().unwrap().is_same(&root));
+        assert_relative_eq!(
+            moved.position(),
+            Vector3::new(1.0, 1.0, 0.0),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            (pose * step).transformation(),
+            moved.transformation(),
+            epsilon = 1e-12
+        );
     }
-}
 
-impl HasChildren for Frame {
-    type Node = Self;
-    fn children(&self) -> Vec<Self> {
-        self.borrow().children.clone()
+    #[test]
+    fn transform_points_and_vectors() {
+        let root = Frame::new_origin("root").unwrap();
+        let sensor = root
+            .add_child(
+                "sensor",
+                Vector3::new(1.0, 2.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+
+        // A yaw of 90 degrees maps x onto y and y onto -x.
+        let pose = root
+            .add_pose(
+                Vector3::new(1.0, 2.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        assert_relative_eq!(
+            pose.transform_vector(&Vector3::x()),
+            Vector3::y(),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            pose.transform_point(&Point3::new(1.0, 0.0, 3.0)),
+            Point3::new(1.0, 3.0, 3.0),
+            epsilon = 1e-12
+        );
+
+        let point = sensor
+            .transform_point_to(&root, &Point3::new(0.0, 1.0, 0.0))
+            .unwrap();
+        assert_relative_eq!(point, Point3::new(0.0, 2.0, 0.0), epsilon = 1e-12);
+        let back = root.transform_point_to(&sensor, &point).unwrap();
+        assert_relative_eq!(back, Point3::new(0.0, 1.0, 0.0), epsilon = 1e-12);
+
+        let other = Frame::new_origin("other").unwrap();
+        assert!(matches!(
+            sensor.transform_point_to(&other, &Point3::origin()),
+            Err(CartesianTreeError::NoCommonAncestor { .. })
+        ));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::lazy_access::{rz, y, z};
+    #[test]
+    fn transform_point_batches() {
+        let root = Frame::new_origin("root").unwrap();
+        let arm = root
+            .add_child(
+                "robot",
+                Vector3::new(1.0, -2.0, 0.5),
+                UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3),
+            )
+            .unwrap();
+        let camera = arm
+            .add_child(
+                "camera",
+                Vector3::new(0.0, 0.3, 0.1),
+                UnitQuaternion::from_euler_angles(-0.4, 0.0, 1.2),
+            )
+            .unwrap();
 
-    use super::*;
-    use approx::assert_relative_eq;
-    use nalgebra::{UnitQuaternion, Vector3};
+        let cloud: Vec<Point3<f64>> = (0..100)
+            .map(|i| {
+                let t = f64::from(i);
+                Point3::new(t.sin(), t.cos(), 0.01 * t)
+            })
+            .collect();
+        let batch = camera.transform_points_to(&root, &cloud).unwrap();
+
+        assert_eq!(batch.len(), cloud.len());
+        for (point, transformed) in cloud.iter().zip(&batch) {
+            let single = camera.transform_point_to(&root, point).unwrap();
+            assert_relative_eq!(*transformed, single, epsilon = 1e-12);
+        }
+        assert!(camera.transform_points_to(&root, &[]).unwrap().is_empty());
+    }
 
     #[test]
-    fn create_origin_frame() {
-        let root = Frame::new_origin("world");
-        let root_borrow = root.borrow();
-        assert_eq!(root_borrow.name, "world");
-        assert!(root_borrow.parent.is_none());
-        assert_eq!(root_borrow.children.len(), 0);
+    fn transform_pose_batches() {
+        let root = Frame::new_origin("root").unwrap();
+        let arm = root
+            .add_child(
+                "robot",
+                Vector3::new(1.0, -2.0, 0.5),
+                UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3),
+            )
+            .unwrap();
+        let camera = arm
+            .add_child(
+                "camera",
+                Vector3::new(0.0, 0.3, 0.1),
+                UnitQuaternion::from_euler_angles(-0.4, 0.0, 1.2),
+            )
+            .unwrap();
+        let table = root
+            .add_child(
+                "table",
+                Vector3::new(2.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+
+        let poses: Vec<Pose> = (0..10_000)
+            .map(|i| {
+                let t = f64::from(i);
+                camera
+                    .add_pose(
+                        Vector3::new(t.sin(), t.cos(), 0.01 * t),
+                        UnitQuaternion::from_euler_angles(0.0, 0.001 * t, 0.0),
+                    )
+                    .unwrap()
+            })
+            .collect();
+        let batch = camera.transform_poses_to(&table, &poses).unwrap();
+
+        assert_eq!(batch.len(), poses.len());
+        for (pose, transformed) in poses.iter().zip(&batch) {
+            let single = pose.in_frame(&table).unwrap();
+            assert!(transformed.frame().unwrap().is_same(&table));
+            assert_eq!(transformed.transformation(), single.transformation());
+        }
+        assert!(camera.transform_poses_to(&table, &[]).unwrap().is_empty());
     }
 
     #[test]
-    fn add_child_frame_with_quaternion() {
-        let root = Frame::new_origin("world");
-        let child = root
+    fn transform_pose_batches_rejects_foreign_poses() {
+        let root = Frame::new_origin("root").unwrap();
+        let camera = root
+            .add_child("camera", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let poses = [
+            camera
+                .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+                .unwrap(),
+            root.add_pose(Vector3::zeros(), UnitQuaternion::identity())
+                .unwrap(),
+        ];
+
+        assert!(matches!(
+            camera.transform_poses_to(&root, &poses),
+            Err(CartesianTreeError::PoseNotInFrame { frame, index: 1 }) if frame == "camera"
+        ));
+
+        let dropped = Frame::new_origin("dropped")
+            .unwrap()
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert!(matches!(
+            camera.transform_poses_to(&root, &[dropped]),
+            Err(CartesianTreeError::PoseNotInFrame { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn pose_distances_across_frames() {
+        let root = Frame::new_origin("root").unwrap();
+        let f1 = root
             .add_child(
-                "dummy",
+                "f1",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+
+        // The same pose, once attached to root and once to f1.
+        let in_root = root
+            .add_pose(
+                Vector3::new(1.0, 1.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        let in_f1 = f1
+            .add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        assert!(in_root.translational_distance_to(&in_f1).unwrap() < 1e-12);
+        assert!(in_root.angular_distance_to(&in_f1).unwrap() < 1e-12);
+        assert!(in_f1.approx_eq(&in_root, 1e-9, 1e-9).unwrap());
+
+        let moved = f1
+            .add_pose(
+                Vector3::new(1.0, 0.0, 2.0),
+                UnitQuaternion::from_euler_angles(0.3, 0.0, 0.0),
+            )
+            .unwrap();
+        assert_relative_eq!(
+            in_root.translational_distance_to(&moved).unwrap(),
+            2.0,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            in_root.angular_distance_to(&moved).unwrap(),
+            0.3,
+            epsilon = 1e-12
+        );
+        assert!(!in_root.approx_eq(&moved, 2.5, 0.2).unwrap());
+        assert!(in_root.approx_eq(&moved, 2.5, 0.4).unwrap());
+
+        // Opposite quaternion signs describe the same orientation.
+        let q = UnitQuaternion::from_euler_angles(0.2, -0.1, 0.4);
+        let negated = UnitQuaternion::new_unchecked(-q.into_inner());
+        let a = root.add_pose(Vector3::zeros(), q).unwrap();
+        let b = root.add_pose(Vector3::zeros(), negated).unwrap();
+        assert!(a.angular_distance_to(&b).unwrap() < 1e-12);
+
+        let other = Frame::new_origin("other").unwrap();
+        let foreign = other
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert!(matches!(
+            a.approx_eq(&foreign, 1.0, 1.0),
+            Err(CartesianTreeError::NoCommonAncestor { .. })
+        ));
+    }
+
+    #[test]
+    fn assert_pose_eq_compares_in_a_common_frame() {
+        use std::panic::{AssertUnwindSafe, catch_unwind};
+
+        let root = Frame::new_origin("root").unwrap();
+        let f1 = root
+            .add_child(
+                "f1",
                 Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        let in_root = root
+            .add_pose(
+                Vector3::new(1.0, 1.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        let in_f1 = f1
+            .add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        crate::assert_pose_eq!(in_root, in_f1);
+        crate::assert_pose_eq!(&in_f1, &in_root, 1e-12, 1e-12);
+
+        let moved = f1
+            .add_pose(Vector3::new(1.0, 0.0, 0.1), UnitQuaternion::identity())
+            .unwrap();
+        let failure = catch_unwind(AssertUnwindSafe(|| crate::assert_pose_eq!(in_root, moved)));
+        let message = *failure.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("(tolerance 0.000000001)"), "{message}");
+        assert!(message.contains("in 'f1'"), "{message}");
+        crate::assert_pose_eq!(in_root, moved, 0.2, 1e-9);
+
+        let other = Frame::new_origin("other").unwrap();
+        let foreign = other
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let failure = catch_unwind(AssertUnwindSafe(|| {
+            crate::assert_pose_eq!(in_root, foreign);
+        }));
+        let message = *failure.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("cannot compare the poses"), "{message}");
+    }
+
+    #[test]
+    fn test_calibrate_child() {
+        let root = Frame::new_origin("root").unwrap();
+
+        let reference_pose = root
+            .add_pose(
+                Vector3::new(1.0, 2.0, 3.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+
+        // Calibrate a child where the reference pose should appear at (0,0,0) with identity orientation.
+        let calibrated_frame = root
+            .calibrate_child(
+                "calibrated",
+                Vector3::zeros(),
                 UnitQuaternion::identity(),
+                &reference_pose,
             )
             .unwrap();
 
-        let root_borrow = root.borrow();
-        assert_eq!(root_borrow.children.len(), 1);
+        let pose_in_calibrated = reference_pose.in_frame(&calibrated_frame).unwrap();
+        let transformation = pose_in_calibrated.transformation();
 
-        let child_borrow = child.borrow();
-        assert_eq!(child_borrow.name, "dummy");
-        assert!(child_borrow.parent.is_some());
+        assert!((transformation.translation.vector - Vector3::zeros()).norm() < 1e-6);
+        assert!((transformation.rotation.angle() - 0.0).abs() < 1e-6);
 
-        let parent_name = child_borrow
-            .parent
-            .as_ref()
-            .unwrap()
-            .upgrade()
-            .unwrap()
-            .borrow()
-            .name
-            .clone();
-        assert_eq!(parent_name, "world");
+        // Verify the child's transform matches the reference pose's original transform.
+        let calibrated_transformation = calibrated_frame.transformation().unwrap();
+        assert!(
+            (calibrated_transformation.translation.vector - Vector3::new(1.0, 2.0, 3.0)).norm()
+                < 1e-6
+        );
+        assert!(
+            (calibrated_transformation.rotation.angle() - std::f64::consts::FRAC_PI_2).abs() < 1e-6
+        );
     }
 
     #[test]
-    fn add_child_frame_with_rpy() {
-        let root = Frame::new_origin("world");
-        let child = root
+    fn calibrate_child_with_dropped_reference() {
+        let root = Frame::new_origin("root").unwrap();
+        let pose = {
+            let sensor = Frame::new_origin("sensor").unwrap();
+            sensor
+                .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+                .unwrap()
+        };
+
+        assert!(matches!(
+            root.calibrate_child("tool", Vector3::zeros(), UnitQuaternion::identity(), &pose),
+            Err(CartesianTreeError::FrameDropped)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_to_json_and_apply_config() {
+        let root = Frame::new_origin("root").unwrap();
+        let _ = root
             .add_child(
-                "dummy",
-                Vector3::new(0.0, 1.0, 0.0),
-                Rotation::from_rpy(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+                "child",
+                Vector3::new(1.0, 2.0, 3.0),
+                UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3),
+            )
+            .unwrap();
+
+        let json = root.to_json().unwrap();
+        // roughly verify JSON structure
+        assert!(json.contains(r#""name": "root""#));
+        assert!(json.contains(r#""name": "child""#));
+
+        // Create a default tree with different transforms
+        let default_root = Frame::new_origin("root").unwrap();
+        default_root
+            .add_child(
+                "child",
+                Vector3::new(0.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
             )
             .unwrap();
 
-        let child_borrow = child.borrow();
-        assert_eq!(child_borrow.name, "dummy");
-
-        let rotation = child_borrow.transform_to_parent.rotation;
-        let expected = UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2);
-        assert!((rotation.angle() - expected.angle()).abs() < 1e-10);
+        // Apply config
+        default_root.apply_config(&json).unwrap();
+
+        // Verify child transform updated
+        let updated_child = default_root
+            .children()
+            .into_iter()
+            .find(|c| c.name() == "child")
+            .unwrap();
+        let iso = updated_child.transformation().unwrap();
+        assert_eq!(iso.translation.vector, Vector3::new(1.0, 2.0, 3.0));
+        let (r, p, y) = iso.rotation.euler_angles();
+        assert!((r - 0.1).abs() < 1e-6);
+        assert!((p - 0.2).abs() < 1e-6);
+        assert!((y - 0.3).abs() < 1e-6);
+
+        // Test partial: If config has extra, ignore it
+        let partial_json = r#"
+        {
+            "name": "root",
+            "position": [0.0, 0.0, 0.0],
+            "orientation": [0.0, 0.0, 0.0, 1.0],
+            "children": [
+                {
+                    "name": "child",
+                    "position": [4.0, 5.0, 6.0],
+                    "orientation": [0.0, 0.0, 0.0, 1.0],
+                    "children": []
+                },
+                {
+                    "name": "extra",
+                    "position": [0.0, 0.0, 0.0],
+                    "orientation": [0.0, 0.0, 0.0, 1.0],
+                    "children": []
+                }
+            ]
+        }
+        "#;
+        default_root.apply_config(partial_json).unwrap();
+        let updated_child = default_root
+            .children()
+            .into_iter()
+            .find(|c| c.name() == "child")
+            .unwrap();
+        assert_eq!(
+            updated_child.transformation().unwrap().translation.vector,
+            Vector3::new(4.0, 5.0, 6.0)
+        );
+
+        // Test mismatch
+        let mismatch_json = r#"
+        {
+            "name": "wrong_root",
+            "position": [0.0, 0.0, 0.0],
+            "orientation": [0.0, 0.0, 0.0, 1.0],
+            "children": []
+        }
+        "#;
+        assert!(matches!(
+            default_root.apply_config(mismatch_json),
+            Err(CartesianTreeError::Mismatch { expected, found })
+                if expected == "root" && found == "wrong_root"
+        ));
     }
 
     #[test]
-    fn test_child_frame_transform_to_parent() {
-        let root = Frame::new_origin("world");
-        let child = root
-            .add_child(
-                "dummy",
-                Vector3::new(0.0, 0.0, 1.0),
-                UnitQuaternion::identity(),
-            )
+    fn transform_callbacks_notify_all_subscribers() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
             .unwrap();
+        let calls = Rc::new(RefCell::new(Vec::new()));
 
-        let transform = child.transformation().unwrap();
-        assert_eq!(transform.translation.vector, Vector3::new(0.0, 0.0, 1.0));
-        assert_eq!(transform.rotation, UnitQuaternion::identity());
+        let first = {
+            let calls = calls.clone();
+            arm.on_transform_changed(move |frame| calls.borrow_mut().push(("first", frame.name())))
+        };
+        let _second = {
+            let calls = calls.clone();
+            arm.on_transform_changed(move |frame| calls.borrow_mut().push(("second", frame.name())))
+        };
 
-        assert_eq!(child.position(), Vector3::new(0.0, 0.0, 1.0));
+        arm.set(Vector3::x(), UnitQuaternion::identity()).unwrap();
         assert_eq!(
-            child.orientation().as_quaternion(),
-            UnitQuaternion::identity()
+            *calls.borrow(),
+            [("first", "arm".to_string()), ("second", "arm".to_string())]
         );
-    }
-
-    #[test]
-    fn multiple_child_frames() {
-        let root = Frame::new_origin("world");
 
-        let a = root
-            .add_child("a", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
-            .unwrap();
-        let b = root
-            .add_child("b", Vector3::new(0.0, 1.0, 0.0), UnitQuaternion::identity())
+        assert!(arm.unsubscribe(first));
+        assert!(!arm.unsubscribe(first));
+        calls.borrow_mut().clear();
+        arm.apply_in_local_frame(&Isometry3::translation(1.0, 0.0, 0.0))
             .unwrap();
+        assert_eq!(*calls.borrow(), [("second", "arm".to_string())]);
 
-        let root_borrow = root.borrow();
-        assert_eq!(root_borrow.children.len(), 2);
-
-        let a_borrow = a.borrow();
-        let b_borrow = b.borrow();
-
-        assert_eq!(
-            a_borrow
-                .parent
-                .as_ref()
-                .unwrap()
-                .upgrade()
-                .unwrap()
-                .borrow()
-                .name,
-            "world"
-        );
-        assert_eq!(
-            b_borrow
-                .parent
-                .as_ref()
-                .unwrap()
-                .upgrade()
-                .unwrap()
-                .borrow()
-                .name,
-            "world"
-        );
+        // Failed updates do not notify.
+        calls.borrow_mut().clear();
+        assert!(root.set(Vector3::x(), UnitQuaternion::identity()).is_err());
+        assert!(calls.borrow().is_empty());
     }
 
     #[test]
-    fn reject_duplicate_child_name() {
-        let root = Frame::new_origin("world");
-
-        let _ = root
-            .add_child(
-                "duplicate",
-                Vector3::new(1.0, 0.0, 0.0),
-                UnitQuaternion::identity(),
-            )
+    fn transform_callback_can_read_and_update_the_tree() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child("arm", Vector3::x(), UnitQuaternion::identity())
+            .unwrap();
+        let tool = arm
+            .add_child("tool", Vector3::z(), UnitQuaternion::identity())
             .unwrap();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        {
+            let seen = seen.clone();
+            tool.on_transform_changed(move |frame| {
+                let world = frame.world_transform().unwrap();
+                seen.borrow_mut().push(world.translation.vector);
+                // Changes made from within the callback do not re-enter it.
+                frame
+                    .apply_in_parent_frame(&Isometry3::translation(0.0, 1.0, 0.0))
+                    .unwrap();
+            });
+        }
 
-        let result = root.add_child(
-            "duplicate",
-            Vector3::new(2.0, 0.0, 0.0),
-            UnitQuaternion::identity(),
+        tool.set(Vector3::new(0.0, 0.0, 2.0), UnitQuaternion::identity())
+            .unwrap();
+        assert_eq!(*seen.borrow(), [Vector3::new(1.0, 0.0, 2.0)]);
+        assert_relative_eq!(
+            tool.world_transform().unwrap().translation.vector,
+            Vector3::new(1.0, 1.0, 2.0)
         );
-        assert!(result.is_err());
     }
 
     #[test]
-    #[should_panic(expected = "already borrowed")]
-    fn test_borrow_conflict() {
-        let frame = Frame::new_origin("root");
-        let _borrow = frame.borrow(); // Immutable borrow
-        frame.borrow_mut(); // Should panic
+    #[cfg(feature = "serde")]
+    fn subtree_callbacks_fire_for_descendants() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let tool = arm
+            .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let table = root
+            .add_child("table", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let subtree = Rc::new(RefCell::new(Vec::new()));
+        let local = Rc::new(RefCell::new(Vec::new()));
+        {
+            let subtree = subtree.clone();
+            arm.on_transform_changed_with(SubscriptionScope::Subtree, move |frame| {
+                subtree.borrow_mut().push(frame.name());
+            });
+            let local = local.clone();
+            arm.on_transform_changed(move |frame| local.borrow_mut().push(frame.name()));
+        }
+
+        tool.set(Vector3::x(), UnitQuaternion::identity()).unwrap();
+        arm.set(Vector3::x(), UnitQuaternion::identity()).unwrap();
+        table.set(Vector3::x(), UnitQuaternion::identity()).unwrap();
+        tool.reparent(&table, ReparentPolicy::KeepWorld).unwrap();
+        assert_eq!(*subtree.borrow(), ["tool", "arm"]);
+        assert_eq!(*local.borrow(), ["arm"]);
+
+        let config = root.to_json().unwrap();
+        subtree.borrow_mut().clear();
+        root.apply_config(&config).unwrap();
+        assert_eq!(*subtree.borrow(), ["arm"]);
+        assert_eq!(*local.borrow(), ["arm", "arm"]);
     }
 
     #[test]
-    fn test_add_pose_to_frame() {
-        let frame = Frame::new_origin("dummy");
-        let pose = frame.add_pose(Vector3::new(1.0, 2.0, 3.0), UnitQuaternion::identity());
-
-        assert_eq!(pose.frame().unwrap().name(), "dummy");
+    fn ancestor_queries() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let camera = arm
+            .add_child("camera", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let gripper = arm
+            .add_child("gripper", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let other = Frame::new_origin("other").unwrap();
+        let lca = |a: &Frame, b: &Frame| a.lowest_common_ancestor(b).map(|frame| frame.name());
+
+        // Same frame.
+        assert_eq!(lca(&camera, &camera).as_deref(), Some("camera"));
+        assert!(!camera.is_ancestor_of(&camera));
+        assert!(!camera.is_descendant_of(&camera));
+
+        // Direct parent and child.
+        assert_eq!(lca(&arm, &camera).as_deref(), Some("arm"));
+        assert_eq!(lca(&camera, &arm).as_deref(), Some("arm"));
+        assert!(arm.is_ancestor_of(&camera));
+        assert!(camera.is_descendant_of(&arm));
+        assert!(!camera.is_ancestor_of(&arm));
+
+        // Siblings.
+        assert_eq!(lca(&camera, &gripper).as_deref(), Some("arm"));
+        assert!(!camera.is_ancestor_of(&gripper));
+        assert!(!gripper.is_descendant_of(&camera));
+
+        // Disjoint trees.
+        assert_eq!(lca(&camera, &other), None);
+        assert!(!other.is_ancestor_of(&camera));
+        assert!(!camera.is_descendant_of(&other));
+
+        let names: Vec<String> = camera.ancestors().map(|frame| frame.name()).collect();
+        assert_eq!(names, ["arm", "world"]);
+        assert_eq!(root.ancestors().count(), 0);
     }
 
     #[test]
-    fn test_set_transform() {
-        let root = Frame::new_origin("root");
-        let child = root
-            .add_child(
-                "dummy",
-                Vector3::new(0.0, 0.0, 1.0),
-                UnitQuaternion::identity(),
-            )
+    #[cfg(feature = "serde")]
+    fn validate_reports_corrupted_trees() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
             .unwrap();
-        child
-            .set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+        let tool = arm
+            .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
             .unwrap();
-        assert_eq!(
-            child.transformation().unwrap().translation.vector,
-            Vector3::new(1.0, 0.0, 0.0)
-        );
-
-        // Test root frame error
-        assert!(
-            root.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
-                .is_err()
-        );
+        let camera = root
+            .add_child("camera", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert_eq!(root.validate(), Ok(()));
+
+        tool.borrow_mut().transform_to_parent.translation.vector.y = f64::NAN;
+        camera.borrow_mut().transform_to_parent.rotation =
+            UnitQuaternion::new_unchecked(Quaternion::new(2.0, 0.0, 0.0, 0.0));
+        // A second "arm" that claims another parent.
+        let impostor = camera
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        root.borrow_mut().children.push(Frame::link(impostor.data));
+
+        let errors = root.validate().unwrap_err();
+        assert_eq!(errors.len(), 5, "{errors:?}");
+        for expected in [
+            TreeValidationError::NonFiniteTransform {
+                path: "/world/arm/tool".to_string(),
+                field: "transform.translation.y".to_string(),
+            },
+            TreeValidationError::UnnormalizedOrientation {
+                path: "/world/camera".to_string(),
+                norm: 2.0,
+            },
+            TreeValidationError::DuplicateChildName {
+                path: "/world".to_string(),
+                name: "arm".to_string(),
+            },
+            TreeValidationError::ParentMismatch {
+                path: "/world/arm".to_string(),
+            },
+            // The impostor is reached a second time below the camera.
+            TreeValidationError::Cycle {
+                path: "/world/camera/arm".to_string(),
+            },
+        ] {
+            assert!(errors.contains(&expected), "{expected:?} not in {errors:?}");
+        }
     }
 
     #[test]
-    fn test_apply_in_parent_frame() {
-        let root = Frame::new_origin("root");
-        let child = root
-            .add_child(
-                "dummy",
-                Vector3::new(1.0, 0.0, 1.0),
-                UnitQuaternion::identity(),
-            )
+    fn validate_reports_cycles() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
             .unwrap();
-        child
-            .apply_in_parent_frame(&Isometry3::from_parts(
-                Translation3::identity(),
-                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
-            ))
+        let tool = arm
+            .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
             .unwrap();
+        tool.borrow_mut().children.push(Frame::link(arm.data));
 
-        assert_relative_eq!(
-            child.transformation().unwrap().translation.vector,
-            Vector3::new(0.0, 1.0, 1.0),
-            epsilon = 1e-10
-        );
-
-        child
-            .apply_in_parent_frame(&Isometry3::from_parts(
-                Translation3::new(1.0, 0.0, 1.0),
-                UnitQuaternion::identity(),
-            ))
-            .unwrap();
-        assert_relative_eq!(
-            child.transformation().unwrap().translation.vector,
-            Vector3::new(1.0, 1.0, 2.0),
-            epsilon = 1e-10
-        );
+        let errors = root.validate().unwrap_err();
+        assert!(errors.contains(&TreeValidationError::Cycle {
+            path: "/world/arm/tool/arm".to_string()
+        }));
 
-        // Test root frame error
-        assert!(
-            root.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
-                .is_err()
-        );
+        // Break the cycle again, so the frames can be dropped.
+        tool.borrow_mut().children.clear();
+        assert_eq!(root.validate(), Ok(()));
     }
 
     #[test]
-    fn test_apply_in_local_frame() {
-        let root = Frame::new_origin("root");
-        let child = root
-            .add_child(
-                "dummy",
-                Vector3::zeros(),
-                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
-            )
-            .unwrap();
+    #[cfg(feature = "serde")]
+    fn random_mutations_keep_tree_valid() {
+        // Small linear congruential generator, so the sequences are reproducible.
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut next = |bound: usize| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            usize::try_from(state >> 33).unwrap() % bound
+        };
+        let names = ["a", "b", "c", "d", "e"];
+
+        for sequence in 0..10 {
+            let root = Frame::new_origin("world").unwrap();
+            let mut frames = vec![root.clone()];
+            for step in 0..300 {
+                let frame = frames[next(frames.len())].clone();
+                let other = frames[next(frames.len())].clone();
+                let position = Vector3::new(
+                    f64::from(u32::try_from(next(200)).unwrap()) / 10.0 - 10.0,
+                    f64::from(u32::try_from(next(200)).unwrap()) / 10.0 - 10.0,
+                    f64::from(u32::try_from(next(200)).unwrap()) / 10.0 - 10.0,
+                );
+                let orientation = Rotation::from_rpy(position.z, position.x, position.y);
+                // Failing operations (e.g. duplicate names or cycles) must leave the tree intact.
+                match next(8) {
+                    0 | 1 => {
+                        if let Ok(child) =
+                            frame.add_child(names[next(names.len())], position, orientation)
+                        {
+                            frames.push(child);
+                        }
+                    }
+                    2 => drop(frame.set(position, orientation)),
+                    3 => drop(frame.set_world_transform(position, orientation)),
+                    4 => drop(frame.apply_in_local_frame(&Isometry3::from_parts(
+                        Translation3::from(position),
+                        orientation.as_quaternion(),
+                    ))),
+                    5 => {
+                        let policy = if next(2) == 0 {
+                            ReparentPolicy::KeepWorld
+                        } else {
+                            ReparentPolicy::KeepLocal
+                        };
+                        drop(frame.reparent(&other, policy));
+                    }
+                    6 => drop(frame.remove_child(names[next(names.len())])),
+                    _ => root.apply_config(&root.to_json().unwrap()).unwrap(),
+                }
+                assert_eq!(root.validate(), Ok(()), "sequence {sequence}, step {step}");
+            }
+            for frame in &frames {
+                assert_eq!(frame.root().validate(), Ok(()), "sequence {sequence}");
+            }
+        }
+    }
 
-        child
-            .apply_in_local_frame(&Isometry3::from_parts(
-                Translation3::new(1.0, 0.0, 0.0),
-                UnitQuaternion::identity(),
-            ))
+    #[test]
+    #[cfg(feature = "serde")]
+    fn non_finite_values_are_rejected() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child("arm", Vector3::x(), UnitQuaternion::identity())
             .unwrap();
+        let nan = Vector3::new(0.0, f64::NAN, 0.0);
+        let zero_length = Rotation::from_quaternion(0.0, 0.0, 0.0, 0.0);
+        let is_non_finite = |result: Result<(), CartesianTreeError>, expected: &str| {
+            matches!(result, Err(CartesianTreeError::NonFiniteValue { field, value })
+                if field == expected && !value.is_finite())
+        };
 
-        assert_relative_eq!(
-            child.transformation().unwrap().translation.vector,
-            Vector3::new(0.0, 1.0, 0.0),
-            epsilon = 1e-10
-        );
+        assert!(is_non_finite(
+            root.add_child("nan", nan, UnitQuaternion::identity())
+                .map(drop),
+            "position.y"
+        ));
+        assert!(is_non_finite(
+            root.add_child("zero", Vector3::zeros(), zero_length)
+                .map(drop),
+            "orientation.x"
+        ));
+        assert!(root.find("nan").is_none());
+        assert!(is_non_finite(
+            root.add_pose(Vector3::new(f64::INFINITY, 0.0, 0.0), Rotation::identity())
+                .map(drop),
+            "position.x"
+        ));
+        assert!(is_non_finite(
+            arm.set(nan, UnitQuaternion::identity()),
+            "position.y"
+        ));
+        assert!(is_non_finite(
+            arm.set_world_transform(Vector3::zeros(), zero_length),
+            "transform.rotation.x"
+        ));
+        assert!(is_non_finite(
+            arm.apply_in_parent_frame(&Isometry3::translation(0.0, 0.0, f64::NEG_INFINITY)),
+            "isometry.translation.z"
+        ));
+        assert!(is_non_finite(
+            arm.apply_in_local_frame(&Isometry3::translation(f64::NAN, 0.0, 0.0)),
+            "isometry.translation.x"
+        ));
+        assert_eq!(arm.position(), Vector3::x());
 
-        child
-            .apply_in_local_frame(&Isometry3::from_parts(
-                Translation3::identity(),
-                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
-            ))
+        let mut pose = arm
+            .add_pose(Vector3::zeros(), Rotation::identity())
             .unwrap();
-        assert_relative_eq!(
-            child.transformation().unwrap().translation.vector,
-            Vector3::new(0.0, 1.0, 0.0),
-            epsilon = 1e-10
-        );
+        assert!(is_non_finite(
+            pose.set(nan, Rotation::identity()),
+            "position.y"
+        ));
+        assert_eq!(pose.position(), Vector3::zeros());
+
+        let config = r#"{"name": "world", "position": [0, 0, 0], "orientation": [0, 0, 0, 1],
+            "children": [{"name": "arm", "position": [0, 0, 1], "orientation": [0, 0, 0, 0],
+            "children": []}]}"#;
+        assert!(is_non_finite(
+            root.apply_config(config),
+            "arm.orientation.x"
+        ));
+        assert_eq!(arm.position(), Vector3::x());
+    }
 
-        let (roll, pitch, yaw) = child.transformation().unwrap().rotation.euler_angles();
-        assert_relative_eq!(roll, 0.0, epsilon = 1e-10);
-        assert_relative_eq!(pitch, 0.0, epsilon = 1e-10);
-        assert_relative_eq!(yaw, std::f64::consts::PI, epsilon = 1e-10);
+    #[test]
+    fn metadata_entries() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
 
-        // Test root frame error
-        assert!(
-            root.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
-                .is_err()
-        );
+        arm.set_metadata("mass", 1.2);
+        arm.set_metadata("serial", "SN-1");
+        arm.set_metadata("mass", 1.5);
+
+        assert_eq!(arm.get_metadata("mass"), Some(MetadataValue::Float(1.5)));
+        assert_eq!(arm.get_metadata("missing"), None);
+        assert_eq!(root.get_metadata("mass"), None);
+        assert_eq!(arm.metadata_keys(), ["mass", "serial"]);
+        assert_eq!(arm.remove_metadata("serial"), Some("SN-1".into()));
+        assert_eq!(arm.remove_metadata("serial"), None);
+        assert_eq!(arm.metadata_keys(), ["mass"]);
     }
 
     #[test]
-    fn test_pose_apply_in_parent_frame() {
-        let root = Frame::new_origin("root");
-        let mut pose = root.add_pose(Vector3::new(1.0, 0.0, 1.0), UnitQuaternion::identity());
+    #[cfg(feature = "serde")]
+    fn metadata_survives_json_round_trips() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        root.set_metadata("site", "lab");
+        arm.set_metadata("mass", 1.25);
+        arm.set_metadata("links", 7);
+        arm.set_metadata("calibrated", true);
+        arm.set_metadata(
+            "mesh",
+            serde_json::json!({"file": "arm.stl", "scale": [1, 1, 1]}),
+        );
 
-        pose.apply_in_parent_frame(&Isometry3::from_parts(
-            Translation3::identity(),
-            UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
-        ));
+        let json = root.to_json().unwrap();
+        let copy = Frame::from_json(&json).unwrap();
+        let copied_arm = copy.resolve_path("arm").unwrap();
+        for key in arm.metadata_keys() {
+            assert_eq!(copied_arm.get_metadata(&key), arm.get_metadata(&key));
+        }
+        assert_eq!(copy.get_metadata("site"), Some("lab".into()));
 
-        assert_relative_eq!(
-            pose.transformation().translation.vector,
-            Vector3::new(0.0, 1.0, 1.0),
-            epsilon = 1e-10
+        let target = Frame::new_origin("world").unwrap();
+        let target_arm = target
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        target_arm.set_metadata("mass", 0.0);
+        target_arm.set_metadata("color", "red");
+        target.apply_config(&json).unwrap();
+        assert_eq!(
+            target_arm.get_metadata("mass"),
+            Some(MetadataValue::Float(1.25))
+        );
+        assert_eq!(
+            target_arm.get_metadata("links"),
+            Some(MetadataValue::Int(7))
         );
+        assert_eq!(target_arm.get_metadata("color"), Some("red".into()));
 
-        pose.apply_in_parent_frame(&Isometry3::from_parts(
-            Translation3::new(1.0, 0.0, 1.0),
-            UnitQuaternion::identity(),
+        assert!(matches!(
+            Frame::from_json(r#"{"name": "w", "position": [0, 0, 0], "orientation": [0, 0, 0, 1], "metadata": [], "children": []}"#),
+            Err(CartesianTreeError::InvalidDocument { path, .. }) if path == "$.metadata"
         ));
-        assert_relative_eq!(
-            pose.transformation().translation.vector,
-            Vector3::new(1.0, 1.0, 2.0),
-            epsilon = 1e-10
-        );
     }
 
     #[test]
-    fn test_pose_apply_in_local_frame() {
-        let root = Frame::new_origin("root");
-        let mut pose = root.add_pose(
-            Vector3::zeros(),
-            UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
-        );
-
-        pose.apply_in_local_frame(&Isometry3::from_parts(
-            Translation3::new(1.0, 0.0, 0.0),
-            UnitQuaternion::identity(),
-        ));
+    fn pose_registry() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "arm",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let home = root
+            .add_pose(Vector3::new(1.0, 2.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
 
-        assert_relative_eq!(
-            pose.transformation().translation.vector,
-            Vector3::new(0.0, 1.0, 0.0),
-            epsilon = 1e-10
-        );
+        arm.store_pose("home", &home, StorePolicy::Reject).unwrap();
+        let stored = arm.get_pose("home").unwrap();
+        assert!(stored.frame().unwrap().is_same(&arm));
+        assert_relative_eq!(stored.position(), Vector3::new(0.0, 2.0, 0.0));
 
-        pose.apply_in_local_frame(&Isometry3::from_parts(
-            Translation3::identity(),
-            UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+        assert!(matches!(
+            arm.store_pose("home", &stored, StorePolicy::Reject),
+            Err(CartesianTreeError::DuplicatePoseName { frame, name }) if frame == "arm" && name == "home"
         ));
+        let approach = arm
+            .add_pose(Vector3::new(0.0, 0.0, 0.5), UnitQuaternion::identity())
+            .unwrap();
+        arm.store_pose("home", &approach, StorePolicy::Overwrite)
+            .unwrap();
+        arm.store_pose("approach", &approach, StorePolicy::Reject)
+            .unwrap();
+        assert_eq!(arm.pose_names(), ["approach", "home"]);
         assert_relative_eq!(
-            pose.transformation().translation.vector,
-            Vector3::new(0.0, 1.0, 0.0),
-            epsilon = 1e-10
+            arm.get_pose("home").unwrap().position(),
+            Vector3::new(0.0, 0.0, 0.5)
         );
 
-        let (roll, pitch, yaw) = pose.transformation().rotation.euler_angles();
-        assert_relative_eq!(roll, 0.0, epsilon = 1e-10);
-        assert_relative_eq!(pitch, 0.0, epsilon = 1e-10);
-        assert_relative_eq!(yaw, std::f64::consts::PI, epsilon = 1e-10);
-
-        // Test root frame error
+        let other = Frame::new_origin("other")
+            .unwrap()
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
         assert!(
-            root.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+            arm.store_pose("other", &other, StorePolicy::Reject)
                 .is_err()
         );
+
+        assert!(arm.remove_pose("approach").is_some());
+        assert!(arm.remove_pose("approach").is_none());
+        assert!(arm.get_pose("approach").is_none());
+        assert_eq!(arm.pose_names(), ["home"]);
     }
 
     #[test]
-    fn test_pose_transform_to_parent() {
-        let root = Frame::new_origin("root");
-        let pose = root.add_pose(Vector3::new(1.0, 2.0, 3.0), UnitQuaternion::identity());
+    fn updating_a_static_frame_fails_unless_overridden() {
+        let root = Frame::new_origin("world").unwrap();
+        let mount = root
+            .add_child(
+                "mount",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let calls = Rc::new(RefCell::new(0));
+        let counter = calls.clone();
+        let _ = mount.on_transform_changed(move |_| *counter.borrow_mut() += 1);
+        assert!(!mount.is_static());
+        mount.set_static(true);
+        assert!(mount.is_static());
+
+        let shift = Isometry3::translation(0.0, 1.0, 0.0);
+        let is_static_error = |result: Result<(), CartesianTreeError>| matches!(result, Err(CartesianTreeError::StaticFrame { frame }) if frame == "mount");
+        assert!(is_static_error(
+            mount.set(Vector3::zeros(), UnitQuaternion::identity())
+        ));
+        assert!(is_static_error(mount.set_world_transform(
+            Vector3::zeros(),
+            UnitQuaternion::identity()
+        )));
+        assert!(is_static_error(mount.apply_in_parent_frame(&shift)));
+        assert!(is_static_error(mount.apply_in_local_frame(&shift)));
+        assert_eq!(mount.position(), Vector3::new(1.0, 0.0, 0.0));
+        {
+            // The flag is checked without panicking while the frame is borrowed elsewhere.
+            let _guard = mount.borrow_mut();
+            assert!(matches!(
+                mount.check_dynamic(),
+                Err(CartesianTreeError::ConcurrentAccess)
+            ));
+        }
+        assert_eq!(*calls.borrow(), 0);
 
-        let transformation = pose.transformation();
+        mount
+            .set_with(
+                Vector3::new(2.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+                StaticPolicy::Override,
+            )
+            .unwrap();
         assert_eq!(
-            transformation.translation.vector,
-            Vector3::new(1.0, 2.0, 3.0)
+            mount.world_transform().unwrap().translation.vector,
+            Vector3::new(2.0, 0.0, 0.0)
         );
-        assert_eq!(transformation.rotation, UnitQuaternion::identity());
+        assert_eq!(*calls.borrow(), 1);
 
-        assert_eq!(pose.position(), Vector3::new(1.0, 2.0, 3.0));
-        assert_eq!(
-            pose.orientation().as_quaternion(),
-            UnitQuaternion::identity()
-        );
+        mount.set_static(false);
+        mount.apply_in_parent_frame(&shift).unwrap();
+        assert_eq!(mount.position(), Vector3::new(2.0, 1.0, 0.0));
     }
 
     #[test]
-    fn test_pose_transformation_between_frames() {
-        let root = Frame::new_origin("root");
-
-        let f1 = root
+    fn static_subtrees_compute_their_world_transform_once() {
+        let root = Frame::new_origin("world").unwrap();
+        let table = root
             .add_child(
-                "f1",
-                Vector3::new(1.0, 0.0, 0.0),
+                "table",
+                Vector3::new(0.0, 3.0, 0.0),
                 UnitQuaternion::identity(),
             )
             .unwrap();
-
-        let f2 = f1
+        let camera = table
+            .add_child(
+                "camera",
+                Vector3::new(0.0, 0.0, 1.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        table.set_static(true);
+        camera.set_static(true);
+        let arm = root
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let tool = arm
             .add_child(
-                "f2",
-                Vector3::new(0.0, 2.0, 0.0),
+                "tool",
+                Vector3::new(0.0, 0.0, 0.5),
                 UnitQuaternion::identity(),
             )
             .unwrap();
 
-        let pose_in_f2 = f2.add_pose(Vector3::new(1.0, 1.0, 0.0), UnitQuaternion::identity());
-
-        let pose_in_root = pose_in_f2.in_frame(&root).unwrap();
-        let pos = pose_in_root.transformation().translation.vector;
-
-        // Total offset should be: f2 (0,2,0) + pose (1,1,0) + f1 (1,0,0)
-        assert!((pos - Vector3::new(2.0, 3.0, 0.0)).norm() < 1e-6);
-    }
-
-    #[test]
-    fn test_calibrate_child() {
-        let root = Frame::new_origin("root");
-
-        let reference_pose = root.add_pose(
-            Vector3::new(1.0, 2.0, 3.0),
-            UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
-        );
+        let world = camera.world_transform().unwrap();
+        assert_eq!(world.translation.vector, Vector3::new(0.0, 3.0, 1.0));
+        // A recomputation would overwrite the marker left in the cache.
+        let marker = Isometry3::translation(42.0, 0.0, 0.0);
+        camera.borrow_mut().world_transform = Some(marker);
+        for step in 0..100 {
+            let offset = f64::from(step);
+            arm.set(Vector3::new(offset, 0.0, 0.0), UnitQuaternion::identity())
+                .unwrap();
+            assert_eq!(
+                tool.world_transform().unwrap().translation.vector,
+                Vector3::new(offset, 0.0, 0.5)
+            );
+            assert_eq!(camera.world_transform().unwrap(), marker);
+        }
 
-        // Calibrate a child where the reference pose should appear at (0,0,0) with identity orientation.
-        let calibrated_frame = root
-            .calibrate_child(
-                "calibrated",
+        // Overriding an update of the subtree invalidates its cache like any other update.
+        table
+            .set_with(
                 Vector3::zeros(),
                 UnitQuaternion::identity(),
-                &reference_pose,
+                StaticPolicy::Override,
             )
             .unwrap();
+        assert_eq!(
+            camera.world_transform().unwrap().translation.vector,
+            Vector3::new(0.0, 0.0, 1.0)
+        );
+    }
 
-        let pose_in_calibrated = reference_pose.in_frame(&calibrated_frame).unwrap();
-        let transformation = pose_in_calibrated.transformation();
-
-        assert!((transformation.translation.vector - Vector3::zeros()).norm() < 1e-6);
-        assert!((transformation.rotation.angle() - 0.0).abs() < 1e-6);
+    #[test]
+    #[cfg(feature = "serde")]
+    fn static_flags_survive_serialization() {
+        let root = Frame::new_origin("world").unwrap();
+        let mount = root
+            .add_child("mount", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let arm = mount
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        mount.set_static(true);
 
-        // Verify the child's transform matches the reference pose's original transform.
-        let calibrated_transformation = calibrated_frame.transformation().unwrap();
-        assert!(
-            (calibrated_transformation.translation.vector - Vector3::new(1.0, 2.0, 3.0)).norm()
-                < 1e-6
-        );
-        assert!(
-            (calibrated_transformation.rotation.angle() - std::f64::consts::FRAC_PI_2).abs() < 1e-6
-        );
+        let json = root.to_json().unwrap();
+        assert_eq!(json.matches(r#""static": true"#).count(), 1);
+        let copy = Frame::from_json(&json).unwrap();
+        assert!(copy.resolve_path("mount").unwrap().is_static());
+        assert!(!copy.resolve_path("mount/arm").unwrap().is_static());
+        let grafted = mount.attach_copy_under(&root, Some("copy")).unwrap();
+        assert!(grafted.is_static());
+
+        // Configs update static frames, but keep the flags of the tree.
+        arm.set_static(true);
+        let mut config: serde_json::Value = serde_json::from_str(&json).unwrap();
+        config["children"][0]["children"][0]["position"] = serde_json::json!([1.0, 0.0, 0.0]);
+        root.apply_config(&config.to_string()).unwrap();
+        assert_eq!(arm.position(), Vector3::new(1.0, 0.0, 0.0));
+        assert!(arm.is_static());
+        assert!(mount.is_static());
+
+        assert!(matches!(
+            Frame::from_json(r#"{"name": "w", "position": [0, 0, 0], "orientation": {"quaternion": [0, 0, 0, 1]}, "static": "yes", "children": []}"#),
+            Err(CartesianTreeError::InvalidDocument { path, .. }) if path == "$.static"
+        ));
     }
 
     #[test]
-    fn test_to_json_and_apply_config() {
-        let root = Frame::new_origin("root");
-        let _ = root
-            .add_child(
-                "child",
-                Vector3::new(1.0, 2.0, 3.0),
+    #[cfg(feature = "serde")]
+    fn stored_poses_survive_json_round_trips() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let pick = arm
+            .add_pose(
+                Vector3::new(0.1, -0.2, 0.3),
                 UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3),
             )
             .unwrap();
+        arm.store_pose("pick", &pick, StorePolicy::Reject).unwrap();
 
         let json = root.to_json().unwrap();
-        // roughly verify JSON structure
-        assert!(json.contains(r#""name": "root""#));
-        assert!(json.contains(r#""name": "child""#));
+        let copy = Frame::from_json(&json).unwrap();
+        let copied = copy.resolve_path("arm").unwrap().get_pose("pick").unwrap();
+        assert_relative_eq!(copied.transformation(), pick.transformation());
+        assert!(copy.pose_names().is_empty());
+
+        let target = Frame::new_origin("world").unwrap();
+        let target_arm = target
+            .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        target.apply_config(&json).unwrap();
+        let applied = target_arm.get_pose("pick").unwrap();
+        assert_relative_eq!(applied.transformation(), pick.transformation());
 
-        // Create a default tree with different transforms
-        let default_root = Frame::new_origin("root");
-        default_root
+        assert!(matches!(
+            Frame::from_json(r#"{"name": "w", "position": [0, 0, 0], "orientation": [0, 0, 0, 1], "poses": {"p": {"position": [0, 0]}}, "children": []}"#),
+            Err(CartesianTreeError::InvalidDocument { path, .. }) if path.starts_with("$.poses.p")
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn from_json_round_trip() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
             .add_child(
-                "child",
-                Vector3::new(0.0, 0.0, 0.0),
-                UnitQuaternion::identity(),
+                "robot",
+                Vector3::new(0.1, 1.0 / 3.0, -2.7),
+                UnitQuaternion::from_euler_angles(0.3, -1.1, 2.9),
             )
             .unwrap();
+        arm.add_child(
+            "camera",
+            Vector3::new(1e-9, 7.123_456_789, 0.2),
+            UnitQuaternion::from_euler_angles(1.0 / 7.0, 0.0, -0.6),
+        )
+        .unwrap();
+        root.add_child(
+            "table",
+            Vector3::new(2.0, 0.0, 0.0),
+            UnitQuaternion::identity(),
+        )
+        .unwrap();
 
-        // Apply config
-        default_root.apply_config(&json).unwrap();
+        let copy = Frame::from_json(&root.to_json().unwrap()).unwrap();
 
-        // Verify child transform updated
-        let updated_child = default_root
-            .children()
-            .into_iter()
-            .find(|c| c.name() == "child")
-            .unwrap();
-        let iso = updated_child.transformation().unwrap();
-        assert_eq!(iso.translation.vector, Vector3::new(1.0, 2.0, 3.0));
-        let (r, p, y) = iso.rotation.euler_angles();
-        assert!((r - 0.1).abs() < 1e-6);
-        assert!((p - 0.2).abs() < 1e-6);
-        assert!((y - 0.3).abs() < 1e-6);
+        let describe = |frame: &Frame| -> Vec<(String, Isometry3<f64>)> {
+            frame
+                .iter_dfs()
+                .map(|f| (f.path(), f.borrow().transform_to_parent))
+                .collect()
+        };
+        assert_eq!(describe(&copy), describe(&root));
+    }
 
-        // Test partial: If config has extra, ignore it
-        let partial_json = r#"
-        {
-            "name": "root",
-            "position": [0.0, 0.0, 0.0],
-            "orientation": [0.0, 0.0, 0.0, 1.0],
-            "children": [
-                {
-                    "name": "child",
-                    "position": [4.0, 5.0, 6.0],
-                    "orientation": [0.0, 0.0, 0.0, 1.0],
-                    "children": []
-                },
-                {
-                    "name": "extra",
+    #[test]
+    #[cfg(feature = "serde")]
+    fn from_json_reports_json_path() {
+        let document = |camera_orientation: &str, second_name: &str| {
+            format!(
+                r#"{{
+                    "name": "world",
                     "position": [0.0, 0.0, 0.0],
                     "orientation": [0.0, 0.0, 0.0, 1.0],
-                    "children": []
-                }
-            ]
-        }
-        "#;
-        default_root.apply_config(partial_json).unwrap();
-        let updated_child = default_root
-            .children()
-            .into_iter()
-            .find(|c| c.name() == "child")
-            .unwrap();
-        assert_eq!(
-            updated_child.transformation().unwrap().translation.vector,
-            Vector3::new(4.0, 5.0, 6.0)
-        );
+                    "children": [
+                        {{
+                            "name": "robot",
+                            "position": [1.0, 0.0, 0.0],
+                            "orientation": [0.0, 0.0, 0.0, 1.0],
+                            "children": [
+                                {{
+                                    "name": "camera",
+                                    "position": [0.0, 0.0, 1.0],
+                                    "orientation": {camera_orientation},
+                                    "children": []
+                                }}
+                            ]
+                        }},
+                        {{
+                            "name": "{second_name}",
+                            "position": [2.0, 0.0, 0.0],
+                            "orientation": [0.0, 0.0, 0.0, 1.0],
+                            "children": []
+                        }}
+                    ]
+                }}"#
+            )
+        };
 
-        // Test mismatch
-        let mismatch_json = r#"
-        {
-            "name": "wrong_root",
-            "position": [0.0, 0.0, 0.0],
-            "orientation": [0.0, 0.0, 0.0, 1.0],
-            "children": []
-        }
-        "#;
-        assert!(default_root.apply_config(mismatch_json).is_err());
+        assert!(Frame::from_json(&document("[0.0, 0.0, 0.0, 1.0]", "table")).is_ok());
+        // Quaternions within the tolerance are stored normalized.
+        let root = Frame::from_json(&document("[0.0, 0.0, 0.0, 1.0000005]", "table")).unwrap();
+        let camera = root.children()[0].children()[0]
+            .orientation()
+            .as_quaternion();
+        assert_relative_eq!(camera.norm(), 1.0, epsilon = 1e-15);
+        assert!(matches!(
+            Frame::from_json(&document("[0.0, 0.0, 0.0, 1.0]", "robot")),
+            Err(CartesianTreeError::InvalidDocument { path, .. }) if path == "$.children[1].name"
+        ));
+        assert!(matches!(
+            Frame::from_json(&document("[0.0, 0.0, 1.0]", "table")),
+            Err(CartesianTreeError::InvalidDocument { path, .. })
+                if path == "$.children[0].children[0].orientation"
+        ));
+        assert!(matches!(
+            Frame::from_json(&document("[0.0, 0.0, 0.5, 0.5]", "table")),
+            Err(CartesianTreeError::InvalidDocument { path, .. })
+                if path == "$.children[0].children[0].orientation"
+        ));
+        assert!(matches!(
+            Frame::from_json(&document(r#"[0.0, "0.0", 0.0, 1.0]"#, "table")),
+            Err(CartesianTreeError::InvalidDocument { path, .. })
+                if path == "$.children[0].children[0].orientation"
+        ));
+        assert!(matches!(
+            Frame::from_json(r#"{"name": "world", "position": [0, 0, 0]}"#),
+            Err(CartesianTreeError::InvalidDocument { path, .. }) if path == "$"
+        ));
+        assert!(matches!(
+            Frame::from_json("not json"),
+            Err(CartesianTreeError::SerdeError(_))
+        ));
     }
 
     #[test]
     fn test_lazy_translation_frame() {
         use nalgebra::UnitQuaternion;
 
-        let root = Frame::new_origin("root");
+        let root = Frame::new_origin("root").unwrap();
         let child = root
             .add_child(
                 "child",
@@ -1150,7 +7463,7 @@ mod tests {
     #[test]
     fn test_lazy_rotation_frame() {
         use nalgebra::UnitQuaternion;
-        let root = Frame::new_origin("root");
+        let root = Frame::new_origin("root").unwrap();
         let child = root
             .add_child(
                 "child",
@@ -1183,8 +7496,10 @@ mod tests {
     fn test_lazy_translation_pose() {
         use nalgebra::UnitQuaternion;
 
-        let root = Frame::new_origin("root");
-        let pose = root.add_pose(Vector3::new(0.0, 0.0, 0.0), UnitQuaternion::identity());
+        let root = Frame::new_origin("root").unwrap();
+        let pose = root
+            .add_pose(Vector3::new(0.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
 
         let result = &pose + z(5.0);
         assert_relative_eq!(
@@ -1216,8 +7531,10 @@ mod tests {
     #[test]
     fn test_lazy_rotation_pose() {
         use nalgebra::UnitQuaternion;
-        let root = Frame::new_origin("root");
-        let pose = root.add_pose(Vector3::new(0.0, 0.0, 0.0), UnitQuaternion::identity());
+        let root = Frame::new_origin("root").unwrap();
+        let pose = root
+            .add_pose(Vector3::new(0.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
         let result = &pose * rz(std::f64::consts::FRAC_PI_4);
 
         let (roll, pitch, yaw) = result.transformation().rotation.euler_angles();
@@ -1238,4 +7555,388 @@ mod tests {
             epsilon = 1e-10
         );
     }
+
+    #[test]
+    fn frames_and_poses_display_their_transforms() {
+        let world = Frame::new_origin("world").unwrap();
+        let robot = world
+            .add_child(
+                "robot",
+                Vector3::new(1.0, -2.5, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        let camera = robot
+            .add_child(
+                "camera",
+                Vector3::new(0.125, 0.0, 1.0 / 3.0),
+                UnitQuaternion::from_euler_angles(-0.5, 0.25, 0.0),
+            )
+            .unwrap();
+        assert_eq!(
+            world.to_string(),
+            "world (/world): 0.0000 0.0000 0.0000 / 0.0000 0.0000 0.0000"
+        );
+        assert_eq!(
+            robot.to_string(),
+            "robot (/world/robot): 1.0000 -2.5000 0.0000 / 0.0000 0.0000 1.5708"
+        );
+        assert_eq!(
+            format!("{camera}"),
+            "camera (/world/robot/camera): 0.1250 0.0000 0.3333 / -0.5000 0.2500 0.0000"
+        );
+        assert_eq!(
+            format!("{camera:.2}"),
+            "camera (/world/robot/camera): 0.12 0.00 0.33 / -0.50 0.25 0.00"
+        );
+        // Full precision is only locked for exact rotations, whose angles round-trip.
+        let table = world
+            .add_child(
+                "table",
+                Vector3::new(0.1, -2.5, 1.0 / 3.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        assert_eq!(
+            format!("{table:#}"),
+            "table (/world/table): 0.1 -2.5 0.3333333333333333 / 0 0 0"
+        );
+
+        let pose = camera
+            .add_pose(Vector3::new(0.0, 0.0, 0.5), UnitQuaternion::identity())
+            .unwrap();
+        assert_eq!(
+            pose.to_string(),
+            "pose in camera: 0.0000 0.0000 0.5000 / 0.0000 0.0000 0.0000"
+        );
+        assert_eq!(
+            format!("{:.1}", pose.in_frame(&robot).unwrap()),
+            "pose in robot: 0.2 0.2 0.8 / -0.5 0.2 0.0"
+        );
+        let on_table = table
+            .add_pose(Vector3::new(0.0, 0.5, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        assert_eq!(format!("{on_table:#}"), "pose in table: 0 0.5 0 / 0 0 0");
+
+        let single = Frame::<f32>::origin("single").unwrap();
+        let tool = single
+            .add_child("tool", [0.1, 0.0, 0.0], UnitQuaternion::identity())
+            .unwrap();
+        assert_eq!(format!("{tool:#}"), "tool (/single/tool): 0.1 0 0 / 0 0 0");
+
+        let debug = format!("{camera:?}");
+        assert!(
+            debug.starts_with(r#"Frame { name: "camera", parent: Some("robot"), data: "#),
+            "{debug}"
+        );
+        assert_eq!(debug.matches("robot").count(), 1, "{debug}");
+        assert!(format!("{world:?}").starts_with(r#"Frame { name: "world", parent: None, "#));
+        let borrowed = camera.data.borrow_mut();
+        assert_eq!(format!("{camera:?}"), "Frame { .. }");
+        assert_eq!(camera.to_string(), "Frame { .. }");
+        drop(borrowed);
+        // Formatting a descendant does not panic while an ancestor is being updated either.
+        let borrowed = robot.data.borrow_mut();
+        assert_eq!(camera.to_string(), "Frame { .. }");
+        drop(borrowed);
+    }
+
+    #[test]
+    fn deltas_in_local_and_parent_space_differ() {
+        let world = Frame::new_origin("world").unwrap();
+        let quarter = UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2);
+        let place = || {
+            world
+                .add_child("tool", Vector3::new(1.0, 0.0, 0.0), quarter)
+                .unwrap()
+        };
+
+        // A translation along X moves along the Y axis of the world in the rotated frame.
+        let shift = Isometry3::translation(0.002, 0.0, 0.0);
+        let tool = place();
+        tool.apply_delta(shift, DeltaSpace::Local).unwrap();
+        assert_relative_eq!(
+            tool.position(),
+            Vector3::new(1.0, 0.002, 0.0),
+            epsilon = 1e-12
+        );
+        tool.set(Vector3::new(1.0, 0.0, 0.0), quarter).unwrap();
+        tool.apply_delta(shift, DeltaSpace::Parent).unwrap();
+        assert_relative_eq!(
+            tool.position(),
+            Vector3::new(1.002, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+        world.remove_child("tool").unwrap();
+
+        // A rotation turns about the origin of the tool, or swings it about that of the world.
+        let turn = Isometry3::rotation(Vector3::z() * std::f64::consts::FRAC_PI_2);
+        let tool = place();
+        tool.apply_delta(turn, DeltaSpace::Local).unwrap();
+        assert_relative_eq!(tool.position(), Vector3::new(1.0, 0.0, 0.0));
+        let local_orientation = tool.orientation().as_quaternion();
+        tool.set(Vector3::new(1.0, 0.0, 0.0), quarter).unwrap();
+        tool.apply_delta(turn, DeltaSpace::Parent).unwrap();
+        assert_relative_eq!(
+            tool.position(),
+            Vector3::new(0.0, 1.0, 0.0),
+            epsilon = 1e-12
+        );
+        // Both turn the tool by a half turn in total, as rotations about the same Z axis commute.
+        assert_relative_eq!(
+            tool.orientation().as_quaternion(),
+            local_orientation,
+            epsilon = 1e-12
+        );
+
+        // A rotation about X in the tool turns about the Y axis of the world.
+        let tilt = Isometry3::rotation(Vector3::x() * 0.1);
+        tool.set(Vector3::new(1.0, 0.0, 0.0), quarter).unwrap();
+        tool.apply_delta(tilt, DeltaSpace::Local).unwrap();
+        assert_relative_eq!(
+            tool.orientation().as_quaternion(),
+            UnitQuaternion::from_euler_angles(0.0, 0.1, 0.0) * quarter,
+            epsilon = 1e-12
+        );
+        tool.set(Vector3::new(1.0, 0.0, 0.0), quarter).unwrap();
+        tool.apply_delta(tilt, DeltaSpace::Parent).unwrap();
+        assert_relative_eq!(
+            tool.orientation().as_quaternion(),
+            UnitQuaternion::from_euler_angles(0.1, 0.0, 0.0) * quarter,
+            epsilon = 1e-12
+        );
+
+        let mut pose = world
+            .add_pose(Vector3::new(1.0, 0.0, 0.0), quarter)
+            .unwrap();
+        pose.apply_delta(shift, DeltaSpace::Local);
+        assert_relative_eq!(
+            pose.position(),
+            Vector3::new(1.0, 0.002, 0.0),
+            epsilon = 1e-12
+        );
+        pose.apply_delta(turn, DeltaSpace::Parent);
+        assert_relative_eq!(
+            pose.position(),
+            Vector3::new(-0.002, 1.0, 0.0),
+            epsilon = 1e-12
+        );
+
+        assert!(matches!(
+            world.apply_delta(shift, DeltaSpace::Local),
+            Err(CartesianTreeError::CannotUpdateRootTransform { .. })
+        ));
+    }
+
+    /// Generates the tests once per backend, as the arena-backed handles of [`super::arena`]
+    /// mirror the part of the API these tests use.
+    macro_rules! backend_tests {
+        ($($test:item)*) => {
+            mod reference_counted {
+                use super::*;
+
+                $($test)*
+            }
+
+            mod arena {
+                use super::*;
+                use crate::frame::arena::Frame;
+
+                $($test)*
+            }
+        };
+    }
+
+    backend_tests! {
+        #[test]
+        fn test_child_frame_transform_to_parent() {
+            let root = Frame::new_origin("world").unwrap();
+            let child = root
+                .add_child(
+                    "dummy",
+                    Vector3::new(0.0, 0.0, 1.0),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+
+            let transform = child.transformation().unwrap();
+            assert_eq!(transform.translation.vector, Vector3::new(0.0, 0.0, 1.0));
+            assert_eq!(transform.rotation, UnitQuaternion::identity());
+
+            assert_eq!(child.position(), Vector3::new(0.0, 0.0, 1.0));
+            assert_eq!(
+                child.orientation().as_quaternion(),
+                UnitQuaternion::identity()
+            );
+        }
+
+        #[test]
+        fn reject_duplicate_child_name() {
+            let root = Frame::new_origin("world").unwrap();
+
+            let _ = root
+                .add_child(
+                    "duplicate",
+                    Vector3::new(1.0, 0.0, 0.0),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+
+            let result = root.add_child(
+                "duplicate",
+                Vector3::new(2.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            );
+            assert!(matches!(
+                result,
+                Err(CartesianTreeError::DuplicateChildName { parent, child })
+                    if parent == "world" && child == "duplicate"
+            ));
+        }
+
+        #[test]
+        fn transform_between_frames() {
+            let root = Frame::new_origin("world").unwrap();
+            let a = root
+                .add_child(
+                    "a",
+                    Vector3::new(1.0, 2.0, 3.0),
+                    UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3),
+                )
+                .unwrap();
+            let b = root
+                .add_child(
+                    "b",
+                    Vector3::new(-1.0, 0.5, 0.0),
+                    UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+                )
+                .unwrap();
+            let b1 = b
+                .add_child(
+                    "b1",
+                    Vector3::new(0.0, 0.0, 2.0),
+                    UnitQuaternion::from_euler_angles(0.5, 0.0, 0.0),
+                )
+                .unwrap();
+
+            let a_to_b1 = a.transform_to(&b1).unwrap();
+            let b1_to_a = b1.transform_to(&a).unwrap();
+            let product = a_to_b1 * b1_to_a;
+            assert_relative_eq!(
+                product.translation.vector,
+                Vector3::zeros(),
+                epsilon = 1e-12
+            );
+            assert_relative_eq!(product.rotation.angle(), 0.0, epsilon = 1e-12);
+
+            // Consistent with expressing a pose in the target frame.
+            let pose = a
+                .add_pose(Vector3::new(0.3, 0.2, 0.1), UnitQuaternion::identity())
+                .unwrap();
+            let expected = pose.in_frame(&b1).unwrap().transformation();
+            let actual = a_to_b1 * pose.transformation();
+            assert_relative_eq!(
+                actual.translation.vector,
+                expected.translation.vector,
+                epsilon = 1e-12
+            );
+
+            // Same frame yields the identity.
+            assert_eq!(a.transform_to(&a).unwrap(), Isometry3::identity());
+
+            // Disjoint trees have no common ancestor.
+            let other = Frame::new_origin("other").unwrap();
+            assert!(matches!(
+                a.transform_to(&other),
+                Err(CartesianTreeError::NoCommonAncestor { .. })
+            ));
+        }
+
+        #[test]
+        fn test_add_pose_to_frame() {
+            let frame = Frame::new_origin("dummy").unwrap();
+            let pose = frame
+                .add_pose(Vector3::new(1.0, 2.0, 3.0), UnitQuaternion::identity())
+                .unwrap();
+
+            assert_eq!(pose.frame().unwrap().name(), "dummy");
+        }
+
+        #[test]
+        fn test_set_transform() {
+            let root = Frame::new_origin("root").unwrap();
+            let child = root
+                .add_child(
+                    "dummy",
+                    Vector3::new(0.0, 0.0, 1.0),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+            child
+                .set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+                .unwrap();
+            assert_eq!(
+                child.transformation().unwrap().translation.vector,
+                Vector3::new(1.0, 0.0, 0.0)
+            );
+
+            // Test root frame error
+            assert!(matches!(
+                root.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()),
+                Err(CartesianTreeError::CannotUpdateRootTransform { frame }) if frame == "root"
+            ));
+        }
+
+        #[test]
+        fn test_pose_transform_to_parent() {
+            let root = Frame::new_origin("root").unwrap();
+            let pose = root
+                .add_pose(Vector3::new(1.0, 2.0, 3.0), UnitQuaternion::identity())
+                .unwrap();
+
+            let transformation = pose.transformation();
+            assert_eq!(
+                transformation.translation.vector,
+                Vector3::new(1.0, 2.0, 3.0)
+            );
+            assert_eq!(transformation.rotation, UnitQuaternion::identity());
+
+            assert_eq!(pose.position(), Vector3::new(1.0, 2.0, 3.0));
+            assert_eq!(
+                pose.orientation().as_quaternion(),
+                UnitQuaternion::identity()
+            );
+        }
+
+        #[test]
+        fn test_pose_transformation_between_frames() {
+            let root = Frame::new_origin("root").unwrap();
+
+            let f1 = root
+                .add_child(
+                    "f1",
+                    Vector3::new(1.0, 0.0, 0.0),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+
+            let f2 = f1
+                .add_child(
+                    "f2",
+                    Vector3::new(0.0, 2.0, 0.0),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+
+            let pose_in_f2 = f2
+                .add_pose(Vector3::new(1.0, 1.0, 0.0), UnitQuaternion::identity())
+                .unwrap();
+
+            let pose_in_root = pose_in_f2.in_frame(&root).unwrap();
+            let pos = pose_in_root.transformation().translation.vector;
+
+            // Total offset should be: f2 (0,2,0) + pose (1,1,0) + f1 (1,0,0)
+            assert!((pos - Vector3::new(2.0, 3.0, 0.0)).norm() < 1e-6);
+        }
+    }
 }
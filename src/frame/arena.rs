@@ -0,0 +1,1015 @@
+//! Arena-backed variant of the frame tree.
+//!
+//! A [`Tree`] owns all of its frames in a single generational arena and hands out
+//! [`FrameId`]s, which are plain `Copy` indices. Parents, children and siblings are stored as
+//! indices into the arena, so walks, traversals and lowest-common-ancestor queries chase
+//! indices through one vector instead of upgrading a weak reference and borrowing a
+//! `RefCell` per frame. Removing a frame bumps the generation of its slot, so a stale id is
+//! reported as [`CartesianTreeError::FrameDropped`] instead of addressing the frame that
+//! reuses the slot.
+//!
+//! [`Frame`] and [`Pose`] in this module are thin handles to a shared [`Tree`]. They mirror the
+//! core of their counterparts in [`crate::frame`]: building a tree, moving frames, walking it and
+//! expressing poses in other frames. Metadata, serialization, callbacks and the other extensions
+//! are only available on the reference-counted frames. Every method borrows the tree once and
+//! runs no user code while it does, so the borrows cannot conflict. Frames cannot be removed
+//! through handles, which keeps every handle valid; remove frames with [`Tree::remove`] on a
+//! tree you own.
+
+use crate::CartesianTreeError;
+use crate::rotation::Rotation;
+use crate::tree::{HasChildren, HasParent, NodeEquality};
+use crate::validation::{FrameNameRules, check_frame_name, check_orientation, check_position};
+use alloc::rc::{Rc, Weak};
+use alloc::{string::String, vec, vec::Vec};
+use core::cell::RefCell;
+use nalgebra::{Isometry3, Translation3, UnitQuaternion, Vector3};
+
+/// The id of a frame in a [`Tree`].
+///
+/// Ids stay valid until their frame is removed; after that, every query with the id fails,
+/// even once a new frame reuses its slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FrameId {
+    index: usize,
+    generation: u64,
+}
+
+/// A frame tree whose frames live in one arena and are addressed by [`FrameId`].
+///
+/// # Example
+/// ```
+/// use cartesian_tree::frame::arena::Tree;
+/// use nalgebra::{UnitQuaternion, Vector3};
+///
+/// let mut tree = Tree::new("world").unwrap();
+/// let robot = tree
+///     .add_child(tree.root(), "robot", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+///     .unwrap();
+/// let camera = tree
+///     .add_child(robot, "camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+///     .unwrap();
+/// let world = tree.world_transform(camera).unwrap();
+/// assert_eq!(world.translation.vector, Vector3::new(1.0, 0.0, 1.0));
+///
+/// tree.remove(robot).unwrap();
+/// assert!(tree.world_transform(camera).is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tree {
+    slots: Vec<Slot>,
+    free: Vec<usize>,
+    name_rules: FrameNameRules,
+}
+
+#[derive(Clone, Debug)]
+struct Slot {
+    generation: u64,
+    alive: bool,
+    node: Node,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    name: String,
+    parent: Option<usize>,
+    first_child: Option<usize>,
+    last_child: Option<usize>,
+    next_sibling: Option<usize>,
+    depth: usize,
+    transform_to_parent: Isometry3<f64>,
+}
+
+/// The root always lives in the first slot, as it cannot be removed.
+const ROOT: usize = 0;
+
+impl Tree {
+    /// Creates a tree with a single root frame with the given name, checking names against
+    /// [`FrameNameRules::Strict`].
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::InvalidFrameName`] if `root` violates the rules.
+    pub fn new(root: impl Into<String>) -> Result<Self, CartesianTreeError> {
+        Self::with_rules(root, FrameNameRules::Strict)
+    }
+
+    /// Creates a tree with a single root frame with the given name, checking names against
+    /// `rules`.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::InvalidFrameName`] if `root` violates `rules`.
+    pub fn with_rules(
+        root: impl Into<String>,
+        rules: FrameNameRules,
+    ) -> Result<Self, CartesianTreeError> {
+        let name = root.into();
+        check_frame_name(&name, rules)?;
+        Ok(Self {
+            slots: vec![Slot {
+                generation: 0,
+                alive: true,
+                node: Node {
+                    name,
+                    parent: None,
+                    first_child: None,
+                    last_child: None,
+                    next_sibling: None,
+                    depth: 0,
+                    transform_to_parent: Isometry3::identity(),
+                },
+            }],
+            free: Vec::new(),
+            name_rules: rules,
+        })
+    }
+
+    /// Returns the id of the root frame.
+    #[must_use]
+    pub fn root(&self) -> FrameId {
+        self.id(ROOT)
+    }
+
+    /// Returns the number of frames in the tree, including the root.
+    #[must_use]
+    pub const fn frame_count(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// Returns whether `id` refers to a frame of this tree that has not been removed.
+    #[must_use]
+    pub fn contains(&self, id: FrameId) -> bool {
+        self.slots
+            .get(id.index)
+            .is_some_and(|slot| slot.alive && slot.generation == id.generation)
+    }
+
+    /// Returns the name of a frame.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::FrameDropped`] if the frame has been removed.
+    pub fn name(&self, id: FrameId) -> Result<&str, CartesianTreeError> {
+        Ok(&self.node(self.index(id)?).name)
+    }
+
+    /// Returns the parent of a frame, or `None` for the root.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::FrameDropped`] if the frame has been removed.
+    pub fn parent(&self, id: FrameId) -> Result<Option<FrameId>, CartesianTreeError> {
+        Ok(self
+            .node(self.index(id)?)
+            .parent
+            .map(|parent| self.id(parent)))
+    }
+
+    /// Returns the children of a frame in the order they were added.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::FrameDropped`] if the frame has been removed.
+    pub fn children(&self, id: FrameId) -> Result<Vec<FrameId>, CartesianTreeError> {
+        Ok(self
+            .child_indices(self.index(id)?)
+            .map(|child| self.id(child))
+            .collect())
+    }
+
+    /// Returns the number of edges between a frame and the root.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::FrameDropped`] if the frame has been removed.
+    pub fn depth(&self, id: FrameId) -> Result<usize, CartesianTreeError> {
+        Ok(self.node(self.index(id)?).depth)
+    }
+
+    /// Returns the transformation from a frame to its parent.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame has been removed.
+    /// - The frame is the root.
+    pub fn transformation(&self, id: FrameId) -> Result<Isometry3<f64>, CartesianTreeError> {
+        let node = self.node(self.index(id)?);
+        if node.parent.is_none() {
+            return Err(CartesianTreeError::RootHasNoParent {
+                frame: node.name.clone(),
+            });
+        }
+        Ok(node.transform_to_parent)
+    }
+
+    /// Sets the transformation of a frame relative to its parent.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame has been removed.
+    /// - The frame is the root.
+    /// - A component of the position or orientation is NaN or infinite.
+    pub fn set(
+        &mut self,
+        id: FrameId,
+        position: Vector3<f64>,
+        orientation: impl Into<Rotation>,
+    ) -> Result<(), CartesianTreeError> {
+        let index = self.index(id)?;
+        if index == ROOT {
+            return Err(CartesianTreeError::CannotUpdateRootTransform {
+                frame: self.node(ROOT).name.clone(),
+            });
+        }
+        let transform = checked_transform(position, orientation.into().as_quaternion())?;
+        self.slots[index].node.transform_to_parent = transform;
+        Ok(())
+    }
+
+    /// Adds a new child frame to `parent`.
+    ///
+    /// # Returns
+    /// The id of the new frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `parent` has been removed.
+    /// - The name violates the [`FrameNameRules`] of the tree.
+    /// - `parent` already has a child with the same name.
+    /// - A component of the position or orientation is NaN or infinite.
+    pub fn add_child(
+        &mut self,
+        parent: FrameId,
+        name: impl Into<String>,
+        position: impl Into<Vector3<f64>>,
+        orientation: impl Into<Rotation>,
+    ) -> Result<FrameId, CartesianTreeError> {
+        let name = name.into();
+        let transform = checked_transform(position.into(), orientation.into().as_quaternion())?;
+        let parent = self.index(parent)?;
+        check_frame_name(&name, self.name_rules)?;
+        if self
+            .child_indices(parent)
+            .any(|child| self.node(child).name == name)
+        {
+            return Err(CartesianTreeError::DuplicateChildName {
+                parent: self.node(parent).name.clone(),
+                child: name,
+            });
+        }
+
+        let node = Node {
+            name,
+            parent: Some(parent),
+            first_child: None,
+            last_child: None,
+            next_sibling: None,
+            depth: self.node(parent).depth + 1,
+            transform_to_parent: transform,
+        };
+        let index = if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index];
+            slot.alive = true;
+            slot.node = node;
+            index
+        } else {
+            self.slots.push(Slot {
+                generation: 0,
+                alive: true,
+                node,
+            });
+            self.slots.len() - 1
+        };
+        match self.node(parent).last_child {
+            Some(last) => self.slots[last].node.next_sibling = Some(index),
+            None => self.slots[parent].node.first_child = Some(index),
+        }
+        self.slots[parent].node.last_child = Some(index);
+        Ok(self.id(index))
+    }
+
+    /// Removes a frame and all of its descendants, invalidating their ids.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame has already been removed.
+    /// - The frame is the root.
+    pub fn remove(&mut self, id: FrameId) -> Result<(), CartesianTreeError> {
+        let index = self.index(id)?;
+        let Some(parent) = self.node(index).parent else {
+            return Err(CartesianTreeError::RootHasNoParent {
+                frame: self.node(index).name.clone(),
+            });
+        };
+
+        let next = self.node(index).next_sibling;
+        let previous = self
+            .child_indices(parent)
+            .find(|&child| self.node(child).next_sibling == Some(index));
+        match previous {
+            Some(previous) => self.slots[previous].node.next_sibling = next,
+            None => self.slots[parent].node.first_child = next,
+        }
+        if self.node(parent).last_child == Some(index) {
+            self.slots[parent].node.last_child = previous;
+        }
+
+        let removed: Vec<_> = self.subtree(index).collect();
+        for removed in removed {
+            let slot = &mut self.slots[removed];
+            slot.alive = false;
+            slot.generation += 1;
+            slot.node.name = String::new();
+            self.free.push(removed);
+        }
+        Ok(())
+    }
+
+    /// Returns the transformation from a frame to the root.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::FrameDropped`] if the frame has been removed.
+    pub fn world_transform(&self, id: FrameId) -> Result<Isometry3<f64>, CartesianTreeError> {
+        Ok(self.transform_up(self.index(id)?, ROOT))
+    }
+
+    /// Returns the lowest common ancestor of two frames.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::FrameDropped`] if either frame has been removed.
+    pub fn lca(&self, a: FrameId, b: FrameId) -> Result<FrameId, CartesianTreeError> {
+        Ok(self.id(self.lca_index(self.index(a)?, self.index(b)?)))
+    }
+
+    /// Returns the transformation from frame `from` to frame `to`.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::FrameDropped`] if either frame has been removed.
+    pub fn transform_to(
+        &self,
+        from: FrameId,
+        to: FrameId,
+    ) -> Result<Isometry3<f64>, CartesianTreeError> {
+        Ok(self.transform_between(self.index(from)?, self.index(to)?))
+    }
+
+    /// Returns an iterator over a frame and its descendants in depth-first pre-order.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::FrameDropped`] if the frame has been removed.
+    pub fn iter_dfs(
+        &self,
+        id: FrameId,
+    ) -> Result<impl Iterator<Item = FrameId> + '_, CartesianTreeError> {
+        Ok(self.subtree(self.index(id)?).map(|index| self.id(index)))
+    }
+
+    /// Returns the index of a live frame.
+    fn index(&self, id: FrameId) -> Result<usize, CartesianTreeError> {
+        if self.contains(id) {
+            Ok(id.index)
+        } else {
+            Err(CartesianTreeError::FrameDropped)
+        }
+    }
+
+    /// Returns the current id of the frame at `index`.
+    fn id(&self, index: usize) -> FrameId {
+        FrameId {
+            index,
+            generation: self.slots[index].generation,
+        }
+    }
+
+    fn node(&self, index: usize) -> &Node {
+        &self.slots[index].node
+    }
+
+    fn child_indices(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        core::iter::successors(self.node(index).first_child, |&child| {
+            self.node(child).next_sibling
+        })
+    }
+
+    /// Returns `index` and its descendants in depth-first pre-order.
+    fn subtree(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        core::iter::successors(Some(index), move |&current| {
+            if let Some(child) = self.node(current).first_child {
+                return Some(child);
+            }
+            // Climbs up to the first ancestor below `index` with a next sibling.
+            let mut current = current;
+            while current != index {
+                let node = self.node(current);
+                if node.next_sibling.is_some() {
+                    return node.next_sibling;
+                }
+                current = node.parent?;
+            }
+            None
+        })
+    }
+
+    /// Returns the parent of `index`, or `index` itself for the root.
+    fn up(&self, index: usize) -> usize {
+        self.node(index).parent.unwrap_or(index)
+    }
+
+    fn lca_index(&self, mut a: usize, mut b: usize) -> usize {
+        while self.node(a).depth > self.node(b).depth {
+            a = self.up(a);
+        }
+        while self.node(b).depth > self.node(a).depth {
+            b = self.up(b);
+        }
+        while a != b {
+            a = self.up(a);
+            b = self.up(b);
+        }
+        a
+    }
+
+    /// Returns the transformation from `index` to its ancestor `ancestor`.
+    fn transform_up(&self, mut index: usize, ancestor: usize) -> Isometry3<f64> {
+        let mut transform = Isometry3::identity();
+        while index != ancestor && index != ROOT {
+            let node = self.node(index);
+            transform = node.transform_to_parent * transform;
+            index = self.up(index);
+        }
+        transform
+    }
+
+    fn transform_between(&self, from: usize, to: usize) -> Isometry3<f64> {
+        let ancestor = self.lca_index(from, to);
+        self.transform_up(to, ancestor).inverse() * self.transform_up(from, ancestor)
+    }
+}
+
+/// Builds a transformation, rejecting non-finite positions and orientations.
+fn checked_transform(
+    position: Vector3<f64>,
+    orientation: UnitQuaternion<f64>,
+) -> Result<Isometry3<f64>, CartesianTreeError> {
+    check_position("position", &position)?;
+    check_orientation("orientation", &orientation)?;
+    Ok(Isometry3::from_parts(
+        Translation3::from(position),
+        orientation,
+    ))
+}
+
+/// A handle to a frame in a shared arena-backed [`Tree`].
+///
+/// See [`crate::Frame`] for the reference-counted variant and the meaning of each method.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::frame::arena::Frame;
+/// use nalgebra::{UnitQuaternion, Vector3};
+///
+/// let root = Frame::new_origin("world").unwrap();
+/// let camera = root
+///     .add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+///     .unwrap();
+/// let pose = camera.add_pose(Vector3::x(), UnitQuaternion::identity()).unwrap();
+/// let in_world = pose.in_frame(&root).unwrap();
+/// assert_eq!(in_world.position(), Vector3::new(1.0, 0.0, 1.0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Frame {
+    tree: Rc<RefCell<Tree>>,
+    id: FrameId,
+}
+
+impl Frame {
+    /// Creates a new tree with a root frame (origin) with the given name.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::InvalidFrameName`] if `name` violates
+    /// [`FrameNameRules::Strict`].
+    pub fn new_origin(name: impl Into<String>) -> Result<Self, CartesianTreeError> {
+        Self::new_origin_with(name, FrameNameRules::Strict)
+    }
+
+    /// Creates a new tree with a root frame (origin), checking frame names against `rules`.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::InvalidFrameName`] if `name` violates `rules`.
+    pub fn new_origin_with(
+        name: impl Into<String>,
+        rules: FrameNameRules,
+    ) -> Result<Self, CartesianTreeError> {
+        let tree = Tree::with_rules(name, rules)?;
+        Ok(Self {
+            id: tree.root(),
+            tree: Rc::new(RefCell::new(tree)),
+        })
+    }
+
+    /// Returns the id of this frame in its tree.
+    #[must_use]
+    pub const fn id(&self) -> FrameId {
+        self.id
+    }
+
+    fn related(&self, id: FrameId) -> Self {
+        Self {
+            tree: self.tree.clone(),
+            id,
+        }
+    }
+
+    /// Returns the name of the frame.
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.tree.borrow().node(self.id.index).name.clone()
+    }
+
+    /// Returns the transformation from this frame to its parent frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the frame has no parent.
+    pub fn transformation(&self) -> Result<Isometry3<f64>, CartesianTreeError> {
+        self.tree.borrow().transformation(self.id)
+    }
+
+    /// Returns the position of this frame relative to its parent frame.
+    #[must_use]
+    pub fn position(&self) -> Vector3<f64> {
+        let tree = self.tree.borrow();
+        tree.node(self.id.index)
+            .transform_to_parent
+            .translation
+            .vector
+    }
+
+    /// Returns the orientation of this frame relative to its parent frame.
+    #[must_use]
+    pub fn orientation(&self) -> Rotation {
+        let tree = self.tree.borrow();
+        tree.node(self.id.index).transform_to_parent.rotation.into()
+    }
+
+    /// Sets the frame's transformation relative to its parent.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame has no parent (i.e., the root frame).
+    /// - A component of the position or orientation is NaN or infinite.
+    pub fn set(
+        &self,
+        position: Vector3<f64>,
+        orientation: impl Into<Rotation>,
+    ) -> Result<(), CartesianTreeError> {
+        // Conversions are done before borrowing, as they may run user code.
+        let orientation = orientation.into();
+        self.tree.borrow_mut().set(self.id, position, orientation)
+    }
+
+    /// Adds a new child frame to the current frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The name violates the [`FrameNameRules`] of the tree.
+    /// - A child with the same name already exists.
+    /// - A component of the position or orientation is NaN or infinite.
+    pub fn add_child(
+        &self,
+        name: impl Into<String>,
+        position: impl Into<Vector3<f64>>,
+        orientation: impl Into<Rotation>,
+    ) -> Result<Self, CartesianTreeError> {
+        let (name, position, orientation) = (name.into(), position.into(), orientation.into());
+        let id = self
+            .tree
+            .borrow_mut()
+            .add_child(self.id, name, position, orientation)?;
+        Ok(self.related(id))
+    }
+
+    /// Adds a pose to the current frame.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::NonFiniteValue`] if a component of the position or
+    /// orientation is NaN or infinite.
+    pub fn add_pose(
+        &self,
+        position: impl Into<Vector3<f64>>,
+        orientation: impl Into<Rotation>,
+    ) -> Result<Pose, CartesianTreeError> {
+        Ok(Pose {
+            tree: Rc::downgrade(&self.tree),
+            frame: self.id,
+            transform_to_parent: checked_transform(
+                position.into(),
+                orientation.into().as_quaternion(),
+            )?,
+        })
+    }
+
+    /// Returns the transformation from this frame to its root frame.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::FrameDropped`] if the frame has been removed from its tree.
+    pub fn world_transform(&self) -> Result<Isometry3<f64>, CartesianTreeError> {
+        self.tree.borrow().world_transform(self.id)
+    }
+
+    /// Returns the transformation from this frame to the target frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the frames belong to different trees.
+    pub fn transform_to(&self, target: &Self) -> Result<Isometry3<f64>, CartesianTreeError> {
+        if !Rc::ptr_eq(&self.tree, &target.tree) {
+            return Err(CartesianTreeError::NoCommonAncestor {
+                from: self.name(),
+                to: target.name(),
+            });
+        }
+        Ok(self
+            .tree
+            .borrow()
+            .transform_between(self.id.index, target.id.index))
+    }
+
+    /// Returns the number of edges between this frame and the root.
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.tree.borrow().node(self.id.index).depth
+    }
+
+    /// Returns the root of the tree.
+    #[must_use]
+    pub fn root(&self) -> Self {
+        let root = self.tree.borrow().root();
+        self.related(root)
+    }
+
+    /// Returns the lowest common ancestor of both frames, or `None` if they belong to different
+    /// trees.
+    #[must_use]
+    pub fn lca_with(&self, other: &Self) -> Option<Self> {
+        if !Rc::ptr_eq(&self.tree, &other.tree) {
+            return None;
+        }
+        let tree = self.tree.borrow();
+        let ancestor = tree.id(tree.lca_index(self.id.index, other.id.index));
+        drop(tree);
+        Some(self.related(ancestor))
+    }
+
+    /// Returns an iterator over this frame and its descendants in depth-first pre-order.
+    pub fn iter_dfs(&self) -> impl Iterator<Item = Self> + use<> {
+        let ids: Vec<_> = {
+            let tree = self.tree.borrow();
+            tree.subtree(self.id.index)
+                .map(|index| tree.id(index))
+                .collect()
+        };
+        let root = self.clone();
+        ids.into_iter().map(move |id| root.related(id))
+    }
+}
+
+impl HasParent for Frame {
+    type Node = Self;
+
+    fn parent(&self) -> Option<Self::Node> {
+        let tree = self.tree.borrow();
+        let parent = tree.node(self.id.index).parent.map(|index| tree.id(index));
+        drop(tree);
+        parent.map(|id| self.related(id))
+    }
+}
+
+impl NodeEquality for Frame {
+    fn is_same(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.tree, &other.tree) && self.id == other.id
+    }
+}
+
+impl HasChildren for Frame {
+    type Node = Self;
+
+    fn children(&self) -> Vec<Self::Node> {
+        let children: Vec<_> = {
+            let tree = self.tree.borrow();
+            tree.child_indices(self.id.index)
+                .map(|index| tree.id(index))
+                .collect()
+        };
+        children.into_iter().map(|id| self.related(id)).collect()
+    }
+}
+
+/// A pose attached to an arena-backed [`Frame`].
+///
+/// See [`crate::Pose`] for the reference-counted variant.
+#[derive(Clone, Debug)]
+pub struct Pose {
+    tree: Weak<RefCell<Tree>>,
+    frame: FrameId,
+    transform_to_parent: Isometry3<f64>,
+}
+
+impl Pose {
+    /// Returns the frame of this pose.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::FrameDropped`] if the tree of the frame has been dropped.
+    pub fn frame(&self) -> Result<Frame, CartesianTreeError> {
+        let tree = self
+            .tree
+            .upgrade()
+            .ok_or(CartesianTreeError::FrameDropped)?;
+        Ok(Frame {
+            tree,
+            id: self.frame,
+        })
+    }
+
+    /// Returns the transformation from this pose to its frame.
+    #[must_use]
+    pub const fn transformation(&self) -> Isometry3<f64> {
+        self.transform_to_parent
+    }
+
+    /// Returns the position of this pose relative to its frame.
+    #[must_use]
+    pub const fn position(&self) -> Vector3<f64> {
+        self.transform_to_parent.translation.vector
+    }
+
+    /// Returns the orientation of this pose relative to its frame.
+    #[must_use]
+    pub fn orientation(&self) -> Rotation {
+        self.transform_to_parent.rotation.into()
+    }
+
+    /// Sets the pose's transformation relative to its frame.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::NonFiniteValue`] if a component of the position or
+    /// orientation is NaN or infinite; the pose is left unchanged.
+    pub fn set(
+        &mut self,
+        position: Vector3<f64>,
+        orientation: impl Into<Rotation>,
+    ) -> Result<(), CartesianTreeError> {
+        self.transform_to_parent = checked_transform(position, orientation.into().as_quaternion())?;
+        Ok(())
+    }
+
+    /// Transforms this pose into the coordinate system of the given target frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The tree of the pose has been dropped.
+    /// - The frame of the pose and `target` belong to different trees.
+    pub fn in_frame(&self, target: &Frame) -> Result<Self, CartesianTreeError> {
+        let source = self.frame()?;
+        Ok(Self {
+            tree: Rc::downgrade(&target.tree),
+            frame: target.id,
+            transform_to_parent: source.transform_to(target)? * self.transform_to_parent,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::Walking;
+    use approx::assert_relative_eq;
+    use nalgebra::Point3;
+    use std::f64::consts::FRAC_PI_2;
+
+    /// Builds the same tree with both backends: `world -> arm -> camera` and `world -> table`.
+    fn workcell() -> (Vec<crate::Frame>, Vec<Frame>) {
+        let specs = [
+            ("arm", 0, Vector3::new(1.0, 0.0, 0.0), FRAC_PI_2),
+            ("camera", 1, Vector3::new(0.0, 2.0, 0.0), 0.0),
+            ("table", 0, Vector3::new(0.0, 0.0, 1.0), -0.3),
+        ];
+        let mut reference = vec![crate::Frame::new_origin("world").unwrap()];
+        let mut arena = vec![Frame::new_origin("world").unwrap()];
+        for (name, parent, position, yaw) in specs {
+            let orientation = Rotation::from_rpy(0.0, 0.0, yaw);
+            let next = reference[parent]
+                .add_child(name, position, orientation)
+                .unwrap();
+            reference.push(next);
+            let next = arena[parent]
+                .add_child(name, position, orientation)
+                .unwrap();
+            arena.push(next);
+        }
+        (reference, arena)
+    }
+
+    #[test]
+    fn mirrors_reference_counted_api() {
+        let (_, frames) = workcell();
+        let [root, arm, camera, table] = [0, 1, 2, 3].map(|index| frames[index].clone());
+
+        assert!(matches!(
+            root.add_child("arm", Vector3::zeros(), Rotation::identity()),
+            Err(CartesianTreeError::DuplicateChildName { .. })
+        ));
+        assert!(matches!(
+            root.add_child("bad/name", Vector3::zeros(), Rotation::identity()),
+            Err(CartesianTreeError::InvalidFrameName { .. })
+        ));
+        assert!(matches!(
+            arm.add_child("nan", [f64::NAN, 0.0, 0.0], Rotation::identity()),
+            Err(CartesianTreeError::NonFiniteValue { .. })
+        ));
+        assert!(matches!(
+            root.set(Vector3::zeros(), Rotation::identity()),
+            Err(CartesianTreeError::CannotUpdateRootTransform { .. })
+        ));
+        assert!(matches!(
+            root.transformation(),
+            Err(CartesianTreeError::RootHasNoParent { .. })
+        ));
+        assert_relative_eq!(
+            camera.world_transform().unwrap() * Point3::origin(),
+            Point3::new(-1.0, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+        assert!(camera.lca_with(&table).unwrap().is_same(&root));
+        assert!(camera.parent().unwrap().is_same(&arm));
+        assert!(camera.root().is_same(&root));
+        assert_eq!(camera.depth(), 2);
+        assert_eq!(camera.walk_up(2).unwrap().name(), "world");
+        let names: Vec<_> = root.iter_dfs().map(|frame| frame.name()).collect();
+        assert_eq!(names, ["world", "arm", "camera", "table"]);
+        assert_eq!(root.children().len(), 2);
+
+        let mut pose = camera
+            .add_pose(Vector3::zeros(), Rotation::identity())
+            .unwrap();
+        assert!(pose.set(Vector3::zeros(), Rotation::identity()).is_ok());
+        let in_table = pose.in_frame(&table).unwrap();
+        assert!(in_table.frame().unwrap().is_same(&table));
+        assert_relative_eq!(
+            in_table.in_frame(&camera).unwrap().position(),
+            Vector3::zeros(),
+            epsilon = 1e-12
+        );
+
+        let other = Frame::new_origin("world").unwrap();
+        assert!(camera.lca_with(&other).is_none());
+        assert!(matches!(
+            pose.in_frame(&other),
+            Err(CartesianTreeError::NoCommonAncestor { .. })
+        ));
+        drop([root, arm, camera, table, other]);
+        drop(frames);
+        assert!(matches!(
+            pose.frame(),
+            Err(CartesianTreeError::FrameDropped)
+        ));
+    }
+
+    #[test]
+    fn agrees_with_reference_counted_backend() {
+        let (mut reference, mut arena) = workcell();
+        // A deterministic pseudo-random tree, the same in both backends.
+        let mut state = 0x2545_f491_u64;
+        let mut next = |bound: usize| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            usize::try_from(state >> 33).unwrap() % bound
+        };
+        for i in 0..60 {
+            let parent = next(reference.len());
+            let position = Vector3::new(
+                f64::from(u32::try_from(next(7)).unwrap()) - 3.0,
+                0.5,
+                f64::from(u32::try_from(i % 5).unwrap()),
+            );
+            let orientation = Rotation::from_rpy(0.1 * f64::from(i), 0.2, -0.05 * f64::from(i));
+            let name = format!("frame{i}");
+            let frame = reference[parent]
+                .add_child(&name, position, orientation)
+                .unwrap();
+            reference.push(frame);
+            let frame = arena[parent]
+                .add_child(&name, position, orientation)
+                .unwrap();
+            arena.push(frame);
+        }
+        // Moves some frames after creation, so cached transforms are invalidated.
+        for index in (1..reference.len()).step_by(7) {
+            let position = Vector3::new(0.0, 1.0, -1.0);
+            let orientation = Rotation::from_rpy(0.3, 0.0, 0.1);
+            reference[index].set(position, orientation).unwrap();
+            arena[index].set(position, orientation).unwrap();
+        }
+
+        for (reference_from, arena_from) in reference.iter().zip(&arena) {
+            assert_eq!(reference_from.depth(), arena_from.depth());
+            assert_relative_eq!(
+                reference_from.world_transform().unwrap(),
+                arena_from.world_transform().unwrap(),
+                epsilon = 1e-9
+            );
+            let reference_pose = reference_from
+                .add_pose(
+                    Vector3::new(0.1, 0.2, 0.3),
+                    Rotation::from_rpy(0.4, 0.5, 0.6),
+                )
+                .unwrap();
+            let arena_pose = arena_from
+                .add_pose(
+                    Vector3::new(0.1, 0.2, 0.3),
+                    Rotation::from_rpy(0.4, 0.5, 0.6),
+                )
+                .unwrap();
+            for (reference_to, arena_to) in reference.iter().zip(&arena) {
+                assert_eq!(
+                    reference_from.lca_with(reference_to).unwrap().name(),
+                    arena_from.lca_with(arena_to).unwrap().name()
+                );
+                assert_relative_eq!(
+                    reference_pose
+                        .in_frame(reference_to)
+                        .unwrap()
+                        .transformation(),
+                    arena_pose.in_frame(arena_to).unwrap().transformation(),
+                    epsilon = 1e-9
+                );
+            }
+        }
+        let reference_names: Vec<_> = reference[0].iter_dfs().map(|frame| frame.name()).collect();
+        let arena_names: Vec<_> = arena[0].iter_dfs().map(|frame| frame.name()).collect();
+        assert_eq!(reference_names, arena_names);
+    }
+
+    #[test]
+    fn removed_frames_invalidate_their_ids() {
+        let mut tree = Tree::new("world").unwrap();
+        let root = tree.root();
+        let arm = tree
+            .add_child(root, "arm", Vector3::x(), Rotation::identity())
+            .unwrap();
+        let tool = tree
+            .add_child(arm, "tool", Vector3::y(), Rotation::identity())
+            .unwrap();
+        let table = tree
+            .add_child(root, "table", Vector3::z(), Rotation::identity())
+            .unwrap();
+        assert_eq!(tree.frame_count(), 4);
+        assert_eq!(tree.lca(tool, table).unwrap(), root);
+
+        tree.remove(arm).unwrap();
+        assert_eq!(tree.frame_count(), 2);
+        assert_eq!(tree.children(root).unwrap(), [table]);
+        for stale in [arm, tool] {
+            assert!(!tree.contains(stale));
+            assert!(matches!(
+                tree.world_transform(stale),
+                Err(CartesianTreeError::FrameDropped)
+            ));
+        }
+        assert!(matches!(
+            tree.remove(root),
+            Err(CartesianTreeError::RootHasNoParent { .. })
+        ));
+
+        // New frames reuse the slots, but not the ids.
+        let arm = tree
+            .add_child(root, "arm", Vector3::x(), Rotation::identity())
+            .unwrap();
+        let gripper = tree
+            .add_child(arm, "gripper", Vector3::z(), Rotation::identity())
+            .unwrap();
+        assert_eq!(tree.frame_count(), 4);
+        assert_ne!(gripper, tool);
+        assert!(!tree.contains(tool));
+        let names: Vec<_> = tree
+            .iter_dfs(root)
+            .unwrap()
+            .map(|id| tree.name(id).unwrap().to_string())
+            .collect();
+        assert_eq!(names, ["world", "table", "arm", "gripper"]);
+        assert_relative_eq!(
+            tree.transform_to(gripper, table)
+                .unwrap()
+                .translation
+                .vector,
+            Vector3::new(1.0, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+
+        // Removing a middle child keeps its siblings linked.
+        let tool = tree
+            .add_child(root, "tool", Vector3::y(), Rotation::identity())
+            .unwrap();
+        tree.remove(arm).unwrap();
+        assert_eq!(tree.children(root).unwrap(), [table, tool]);
+        tree.remove(tool).unwrap();
+        let last = tree
+            .add_child(root, "last", Vector3::y(), Rotation::identity())
+            .unwrap();
+        assert_eq!(tree.children(root).unwrap(), [table, last]);
+    }
+}
@@ -0,0 +1,544 @@
+//! Thread-safe variant of the frame tree.
+//!
+//! [`Frame`] and [`Pose`] in this module mirror their single-threaded counterparts but keep
+//! their data behind `Arc<RwLock<_>>`, so they are `Send + Sync` and a tree can be shared
+//! between threads, e.g. a sensor thread updating transforms and a planner reading them.
+//!
+//! Queries walking up the tree copy the transform and parent of each frame out of a
+//! short-lived read lock before moving on, and locks are only ever nested from a parent down
+//! to its children. This rules out deadlocks between concurrent walks and updates, at the
+//! price that a query running concurrently with updates of several frames may observe some
+//! but not all of them.
+
+use crate::CartesianTreeError;
+use crate::rotation::Rotation;
+use crate::tree::{DepthFirst, HasChildren, HasParent, NodeEquality, Walking};
+use crate::validation::{FrameNameRules, check_frame_name, check_orientation, check_position};
+use nalgebra::{Isometry3, Translation3, UnitQuaternion, Vector3};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak};
+
+/// A coordinate frame that can be shared between threads.
+///
+/// See [`crate::Frame`] for the single-threaded variant and the meaning of each method.
+#[derive(Clone, Debug)]
+pub struct Frame {
+    data: Arc<RwLock<FrameData>>,
+}
+
+#[derive(Debug)]
+struct FrameData {
+    name: String,
+    parent: Option<Weak<RwLock<Self>>>,
+    transform_to_parent: Isometry3<f64>,
+    children: Vec<Frame>,
+    name_rules: FrameNameRules,
+}
+
+impl Frame {
+    /// Creates a new root frame (origin) with the given name.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::InvalidFrameName`] if `name` violates
+    /// [`FrameNameRules::Strict`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::frame::sync::Frame;
+    ///
+    /// let origin = Frame::new_origin("world").unwrap();
+    /// std::thread::spawn(move || assert_eq!(origin.name(), "world"))
+    ///     .join()
+    ///     .unwrap();
+    /// ```
+    pub fn new_origin(name: impl Into<String>) -> Result<Self, CartesianTreeError> {
+        Self::new_origin_with(name, FrameNameRules::Strict)
+    }
+
+    /// Creates a new root frame (origin), checking frame names against `rules`.
+    ///
+    /// The rules are inherited by every frame added to the tree.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::InvalidFrameName`] if `name` violates `rules`.
+    pub fn new_origin_with(
+        name: impl Into<String>,
+        rules: FrameNameRules,
+    ) -> Result<Self, CartesianTreeError> {
+        let name = name.into();
+        check_frame_name(&name, rules)?;
+        Ok(Self {
+            data: Arc::new(RwLock::new(FrameData {
+                name,
+                parent: None,
+                transform_to_parent: Isometry3::identity(),
+                children: Vec::new(),
+                name_rules: rules,
+            })),
+        })
+    }
+
+    // A panic while a lock is held cannot leave the data half-updated, as every write is a
+    // single assignment or push, so poisoned locks are recovered.
+    fn read(&self) -> RwLockReadGuard<'_, FrameData> {
+        self.data.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, FrameData> {
+        self.data.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the name of the frame.
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.read().name.clone()
+    }
+
+    /// Returns the transformation from this frame to its parent frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the frame has no parent.
+    pub fn transformation(&self) -> Result<Isometry3<f64>, CartesianTreeError> {
+        let data = self.read();
+        if data.parent.is_none() {
+            return Err(CartesianTreeError::RootHasNoParent {
+                frame: data.name.clone(),
+            });
+        }
+        Ok(data.transform_to_parent)
+    }
+
+    /// Returns the position of this frame relative to its parent frame.
+    #[must_use]
+    pub fn position(&self) -> Vector3<f64> {
+        self.read().transform_to_parent.translation.vector
+    }
+
+    /// Returns the orientation of this frame relative to its parent frame.
+    #[must_use]
+    pub fn orientation(&self) -> Rotation {
+        self.read().transform_to_parent.rotation.into()
+    }
+
+    /// Sets the frame's transformation relative to its parent.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame has no parent (i.e., the root frame).
+    /// - A component of the position or orientation is NaN or infinite.
+    pub fn set(
+        &self,
+        position: Vector3<f64>,
+        orientation: impl Into<Rotation>,
+    ) -> Result<(), CartesianTreeError> {
+        let transform = checked_transform(position, orientation.into().as_quaternion())?;
+        {
+            let mut data = self.write();
+            if data.parent.is_none() {
+                return Err(CartesianTreeError::CannotUpdateRootTransform {
+                    frame: data.name.clone(),
+                });
+            }
+            data.transform_to_parent = transform;
+        }
+        Ok(())
+    }
+
+    /// Adds a new child frame to the current frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The name violates the [`FrameNameRules`] of the tree.
+    /// - A child with the same name already exists.
+    /// - A component of the position or orientation is NaN or infinite.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::frame::sync::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let camera = root
+    ///     .add_child("camera", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// ```
+    pub fn add_child(
+        &self,
+        name: impl Into<String>,
+        position: Vector3<f64>,
+        orientation: impl Into<Rotation>,
+    ) -> Result<Self, CartesianTreeError> {
+        let child_name = name.into();
+        let transform = checked_transform(position, orientation.into().as_quaternion())?;
+        let name_rules = self.read().name_rules;
+        check_frame_name(&child_name, name_rules)?;
+        let child = Self {
+            data: Arc::new(RwLock::new(FrameData {
+                name: child_name.clone(),
+                parent: Some(Arc::downgrade(&self.data)),
+                transform_to_parent: transform,
+                children: Vec::new(),
+                name_rules,
+            })),
+        };
+
+        {
+            let mut data = self.write();
+            if data
+                .children
+                .iter()
+                .any(|existing| existing.read().name == child_name)
+            {
+                return Err(CartesianTreeError::DuplicateChildName {
+                    parent: data.name.clone(),
+                    child: child_name,
+                });
+            }
+            data.children.push(child.clone());
+        }
+        Ok(child)
+    }
+
+    /// Adds a pose to the current frame.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::NonFiniteValue`] if a component of the position or
+    /// orientation is NaN or infinite.
+    pub fn add_pose(
+        &self,
+        position: Vector3<f64>,
+        orientation: impl Into<Rotation>,
+    ) -> Result<Pose, CartesianTreeError> {
+        Ok(Pose {
+            parent: Arc::downgrade(&self.data),
+            transform_to_parent: checked_transform(position, orientation.into().as_quaternion())?,
+        })
+    }
+
+    /// Returns the transformation from this frame to its root frame.
+    #[must_use]
+    pub fn world_transform(&self) -> Isometry3<f64> {
+        let mut transform = Isometry3::identity();
+        let mut current = self.clone();
+        loop {
+            let (transform_to_parent, parent) = {
+                let data = current.read();
+                (data.transform_to_parent, data.parent.clone())
+            };
+            match parent.and_then(|parent| parent.upgrade()) {
+                Some(data) => {
+                    transform = transform_to_parent * transform;
+                    current = Self { data };
+                }
+                None => return transform,
+            }
+        }
+    }
+
+    /// Returns the transformation from this frame to the target frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the frames do not share a common ancestor.
+    pub fn transform_to(&self, target: &Self) -> Result<Isometry3<f64>, CartesianTreeError> {
+        let ancestor =
+            self.lca_with(target)
+                .ok_or_else(|| CartesianTreeError::NoCommonAncestor {
+                    from: self.name(),
+                    to: target.name(),
+                })?;
+        let source_to_ancestor = self.walk_up_and_transform(&ancestor)?;
+        let target_to_ancestor = target.walk_up_and_transform(&ancestor)?;
+        Ok(target_to_ancestor.inverse() * source_to_ancestor)
+    }
+
+    fn walk_up_and_transform(&self, target: &Self) -> Result<Isometry3<f64>, CartesianTreeError> {
+        let mut transform = Isometry3::identity();
+        let mut current = self.clone();
+        while !current.is_same(target) {
+            let transform_to_parent = current.read().transform_to_parent;
+            current = current
+                .parent()
+                .ok_or_else(|| CartesianTreeError::IsNoAncestor {
+                    ancestor: target.name(),
+                    frame: self.name(),
+                })?;
+            transform = transform_to_parent * transform;
+        }
+        Ok(transform)
+    }
+
+    /// Returns an iterator over this frame and its descendants in depth-first pre-order.
+    pub fn iter_dfs(&self) -> impl Iterator<Item = Self> + use<> {
+        DepthFirst::new(self.clone())
+    }
+}
+
+impl HasParent for Frame {
+    type Node = Self;
+
+    fn parent(&self) -> Option<Self::Node> {
+        let parent = self.read().parent.clone()?;
+        parent.upgrade().map(|data| Self { data })
+    }
+}
+
+impl NodeEquality for Frame {
+    fn is_same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
+    }
+}
+
+impl HasChildren for Frame {
+    type Node = Self;
+
+    fn children(&self) -> Vec<Self::Node> {
+        self.read().children.clone()
+    }
+}
+
+/// A pose attached to a thread-safe [`Frame`].
+///
+/// See [`crate::Pose`] for the single-threaded variant.
+#[derive(Clone, Debug)]
+pub struct Pose {
+    parent: Weak<RwLock<FrameData>>,
+    transform_to_parent: Isometry3<f64>,
+}
+
+impl Pose {
+    /// Returns the frame of this pose, or `None` if it has been dropped.
+    #[must_use]
+    pub fn frame(&self) -> Option<Frame> {
+        self.parent.upgrade().map(|data| Frame { data })
+    }
+
+    /// Returns the transformation from this pose to its frame.
+    #[must_use]
+    pub const fn transformation(&self) -> Isometry3<f64> {
+        self.transform_to_parent
+    }
+
+    /// Sets the pose's transformation relative to its frame.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::NonFiniteValue`] if a component of the position or
+    /// orientation is NaN or infinite; the pose is left unchanged.
+    pub fn set(
+        &mut self,
+        position: Vector3<f64>,
+        orientation: impl Into<Rotation>,
+    ) -> Result<(), CartesianTreeError> {
+        self.transform_to_parent = checked_transform(position, orientation.into().as_quaternion())?;
+        Ok(())
+    }
+
+    /// Transforms this pose into the coordinate system of the given target frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame of the pose has been dropped.
+    /// - There is no common ancestor between the frame of the pose and `target`.
+    pub fn in_frame(&self, target: &Frame) -> Result<Self, CartesianTreeError> {
+        let source = self.frame().ok_or(CartesianTreeError::FrameDropped)?;
+        Ok(Self {
+            parent: Arc::downgrade(&target.data),
+            transform_to_parent: source.transform_to(target)? * self.transform_to_parent,
+        })
+    }
+}
+
+/// Builds a transformation, rejecting non-finite positions and orientations.
+fn checked_transform(
+    position: Vector3<f64>,
+    orientation: UnitQuaternion<f64>,
+) -> Result<Isometry3<f64>, CartesianTreeError> {
+    check_position("position", &position)?;
+    check_orientation("orientation", &orientation)?;
+    Ok(Isometry3::from_parts(
+        Translation3::from(position),
+        orientation,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use nalgebra::{Point3, UnitQuaternion};
+    use std::thread;
+
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn frames_and_poses_are_send_and_sync() {
+        assert_send_sync::<Frame>();
+        assert_send_sync::<Pose>();
+    }
+
+    #[test]
+    fn mirrors_single_threaded_api() {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "arm",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            )
+            .unwrap();
+        let camera = arm
+            .add_child("camera", Vector3::new(0.0, 2.0, 0.0), Rotation::identity())
+            .unwrap();
+        let table = root
+            .add_child("table", Vector3::new(0.0, 0.0, 1.0), Rotation::identity())
+            .unwrap();
+
+        assert!(matches!(
+            root.add_child("arm", Vector3::zeros(), Rotation::identity()),
+            Err(CartesianTreeError::DuplicateChildName { .. })
+        ));
+        assert!(matches!(
+            root.set(Vector3::zeros(), Rotation::identity()),
+            Err(CartesianTreeError::CannotUpdateRootTransform { .. })
+        ));
+        assert_relative_eq!(
+            camera.world_transform() * Point3::origin(),
+            Point3::new(-1.0, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+        assert!(camera.lca_with(&table).unwrap().is_same(&root));
+        assert_eq!(camera.depth(), 2);
+        let names: Vec<_> = root.iter_dfs().map(|frame| frame.name()).collect();
+        assert_eq!(names, ["world", "arm", "camera", "table"]);
+
+        let mut pose = camera
+            .add_pose(Vector3::zeros(), Rotation::identity())
+            .unwrap();
+        assert!(matches!(
+            camera.add_child(
+                "nan",
+                Vector3::new(f64::NAN, 0.0, 0.0),
+                Rotation::identity()
+            ),
+            Err(CartesianTreeError::NonFiniteValue { .. })
+        ));
+        assert!(matches!(
+            camera.set(Vector3::new(0.0, f64::INFINITY, 0.0), Rotation::identity()),
+            Err(CartesianTreeError::NonFiniteValue { .. })
+        ));
+        assert!(matches!(
+            camera.add_pose(Vector3::zeros(), Rotation::from_rpy(f64::NAN, 0.0, 0.0)),
+            Err(CartesianTreeError::NonFiniteValue { .. })
+        ));
+        assert!(matches!(
+            pose.set(
+                Vector3::new(f64::NEG_INFINITY, 0.0, 0.0),
+                Rotation::identity()
+            ),
+            Err(CartesianTreeError::NonFiniteValue { .. })
+        ));
+        assert_eq!(pose.transformation(), Isometry3::identity());
+        for name in ["", "a/b", "tab\t"] {
+            assert!(matches!(
+                root.add_child(name, Vector3::zeros(), Rotation::identity()),
+                Err(CartesianTreeError::InvalidFrameName { .. })
+            ));
+            assert!(matches!(
+                Frame::new_origin(name),
+                Err(CartesianTreeError::InvalidFrameName { .. })
+            ));
+        }
+        let relaxed = Frame::new_origin_with("world", FrameNameRules::Permissive).unwrap();
+        assert!(
+            relaxed
+                .add_child("a/b", Vector3::zeros(), Rotation::identity())
+                .is_ok()
+        );
+        let in_table = pose.in_frame(&table).unwrap();
+        assert!(in_table.frame().unwrap().is_same(&table));
+        assert_relative_eq!(
+            in_table.transformation().translation.vector,
+            Vector3::new(-1.0, 0.0, -1.0),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn concurrent_updates_and_queries() {
+        let root = Frame::new_origin("world").unwrap();
+        let mut chain = vec![root.clone()];
+        for i in 0..20 {
+            let parent = chain.last().unwrap().clone();
+            chain.push(
+                parent
+                    .add_child(format!("link{i}"), Vector3::x(), Rotation::identity())
+                    .unwrap(),
+            );
+        }
+        let tip = chain.last().unwrap().clone();
+
+        let writers: Vec<_> = chain[1..]
+            .iter()
+            .cloned()
+            .zip(0..)
+            .map(|(link, index)| {
+                thread::spawn(move || {
+                    for step in 0_u32..500 {
+                        let offset = f64::from((step + index) % 3);
+                        link.set(Vector3::new(1.0, offset, 0.0), Rotation::identity())
+                            .unwrap();
+                    }
+                    link.set(Vector3::x(), Rotation::identity()).unwrap();
+                })
+            })
+            .collect();
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let tip = tip.clone();
+                let root = root.clone();
+                thread::spawn(move || {
+                    for _ in 0..500 {
+                        let world = tip.world_transform();
+                        assert_relative_eq!(world.translation.x, 20.0, epsilon = 1e-12);
+                        assert!(world.translation.y >= 0.0 && world.translation.y <= 40.0);
+                        assert!(tip.transform_to(&root).is_ok());
+                    }
+                })
+            })
+            .collect();
+        for handle in writers.into_iter().chain(readers) {
+            handle.join().unwrap();
+        }
+
+        assert_relative_eq!(
+            tip.world_transform().translation.vector,
+            Vector3::new(20.0, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn concurrent_child_insertion() {
+        let root = Frame::new_origin("world").unwrap();
+        let handles: Vec<_> = (0..8)
+            .map(|thread_index| {
+                let root = root.clone();
+                thread::spawn(move || {
+                    for i in 0..50 {
+                        root.add_child(
+                            format!("frame{thread_index}_{i}"),
+                            Vector3::zeros(),
+                            Rotation::identity(),
+                        )
+                        .unwrap();
+                        // Every thread also races for the same shared name.
+                        let _ = root.add_child("shared", Vector3::zeros(), Rotation::identity());
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(root.children().len(), 8 * 50 + 1);
+    }
+}
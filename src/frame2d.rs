@@ -0,0 +1,747 @@
+//! Planar frame trees, for applications that only move in the plane (e.g. AGVs).
+//!
+//! [`Frame2D`] and [`Pose2D`] mirror [`Frame`] and [`Pose`], but store an [`Isometry2`], i.e.
+//! a position in the plane and a single yaw angle. Planar trees can be lifted into a spatial
+//! tree via [`Frame2D::lift_to_3d`], and spatial poses can be projected onto the plane via
+//! [`Pose::project_to_2d`].
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::pose::Pose;
+use crate::tree::{HasChildren, HasParent, NodeEquality, Walking};
+use crate::validation::{FrameNameRules, check_frame_name, check_planar};
+use nalgebra::{Isometry2, Isometry3, Point2, Translation3, UnitQuaternion, Vector2, Vector3};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+/// A coordinate frame in a planar tree, see the [module documentation](self).
+///
+/// Cloning a `Frame2D` creates another handle to the same frame.
+#[derive(Clone, Debug)]
+pub struct Frame2D {
+    data: Rc<RefCell<Frame2DData>>,
+}
+
+#[derive(Debug)]
+struct Frame2DData {
+    name: String,
+    parent: Option<Weak<RefCell<Self>>>,
+    children: Vec<Frame2D>,
+    transform_to_parent: Isometry2<f64>,
+}
+
+/// A pose relative to a [`Frame2D`]. Use [`Frame2D::add_pose`] to create one.
+#[derive(Clone, Debug)]
+pub struct Pose2D {
+    parent: Weak<RefCell<Frame2DData>>,
+    transform_to_parent: Isometry2<f64>,
+}
+
+/// The out-of-plane content discarded by [`Pose::project_to_2d`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlanarResidual {
+    /// The distance of the pose from the plane, i.e. its z coordinate.
+    pub height: f64,
+    /// The angle in radians between the z axis of the pose and the plane normal, which covers
+    /// any roll and pitch.
+    pub tilt: f64,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SerialFrame2D {
+    name: String,
+    position: Vector2<f64>,
+    yaw: f64,
+    children: Vec<Self>,
+}
+
+impl Frame2D {
+    /// Creates a new planar root frame with the given name.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the name violates [`FrameNameRules::Strict`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::frame2d::Frame2D;
+    ///
+    /// let map = Frame2D::new_origin("map").unwrap();
+    /// assert_eq!(map.name(), "map");
+    /// ```
+    pub fn new_origin(name: impl Into<String>) -> Result<Self, CartesianTreeError> {
+        let name = name.into();
+        check_frame_name(&name, FrameNameRules::Strict)?;
+        Ok(Self {
+            data: Rc::new(RefCell::new(Frame2DData {
+                name,
+                parent: None,
+                children: Vec::new(),
+                transform_to_parent: Isometry2::identity(),
+            })),
+        })
+    }
+
+    /// Returns the name of the frame.
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.data.borrow().name.clone()
+    }
+
+    /// Returns the position of this frame relative to its parent.
+    #[must_use]
+    pub fn position(&self) -> Vector2<f64> {
+        self.data.borrow().transform_to_parent.translation.vector
+    }
+
+    /// Returns the yaw angle of this frame relative to its parent, in radians within `(-π, π]`.
+    #[must_use]
+    pub fn yaw(&self) -> f64 {
+        self.data.borrow().transform_to_parent.rotation.angle()
+    }
+
+    /// Returns the transformation from this frame to its parent frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the frame has no parent.
+    pub fn transformation(&self) -> Result<Isometry2<f64>, CartesianTreeError> {
+        if self.parent().is_none() {
+            return Err(CartesianTreeError::RootHasNoParent { frame: self.name() });
+        }
+        Ok(self.data.borrow().transform_to_parent)
+    }
+
+    /// Updates the transformation of this frame relative to its parent.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame is a root.
+    /// - A component of the transform is NaN or infinite.
+    pub fn set(
+        &self,
+        position: impl Into<Vector2<f64>>,
+        yaw: f64,
+    ) -> Result<(), CartesianTreeError> {
+        if self.parent().is_none() {
+            return Err(CartesianTreeError::CannotUpdateRootTransform { frame: self.name() });
+        }
+        let position = position.into();
+        check_planar(&position, yaw)?;
+        self.data.borrow_mut().transform_to_parent = planar_isometry(position, yaw);
+        Ok(())
+    }
+
+    /// Adds a new child frame at `position`, rotated by `yaw` radians relative to this frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The name violates [`FrameNameRules::Strict`].
+    /// - A child with the same name already exists.
+    /// - A component of the transform is NaN or infinite.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::frame2d::Frame2D;
+    ///
+    /// let map = Frame2D::new_origin("map").unwrap();
+    /// let agv = map.add_child("agv", [2.0, 1.0], 0.5).unwrap();
+    /// assert!((agv.yaw() - 0.5).abs() < 1e-12);
+    /// assert!(map.add_child("agv", [0.0, 0.0], 0.0).is_err());
+    /// ```
+    pub fn add_child(
+        &self,
+        name: impl Into<String>,
+        position: impl Into<Vector2<f64>>,
+        yaw: f64,
+    ) -> Result<Self, CartesianTreeError> {
+        let name = name.into();
+        let position = position.into();
+        check_frame_name(&name, FrameNameRules::Strict)?;
+        check_planar(&position, yaw)?;
+
+        let mut data = self.data.borrow_mut();
+        if data.children.iter().any(|child| child.name() == name) {
+            return Err(CartesianTreeError::DuplicateChildName {
+                parent: data.name.clone(),
+                child: name,
+            });
+        }
+        let child = Self {
+            data: Rc::new(RefCell::new(Frame2DData {
+                name,
+                parent: Some(Rc::downgrade(&self.data)),
+                children: Vec::new(),
+                transform_to_parent: planar_isometry(position, yaw),
+            })),
+        };
+        data.children.push(child.clone());
+        Ok(child)
+    }
+
+    /// Adds a pose at `position`, rotated by `yaw` radians, relative to this frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if a component of the pose is NaN or infinite.
+    pub fn add_pose(
+        &self,
+        position: impl Into<Vector2<f64>>,
+        yaw: f64,
+    ) -> Result<Pose2D, CartesianTreeError> {
+        let position = position.into();
+        check_planar(&position, yaw)?;
+        Ok(Pose2D {
+            parent: Rc::downgrade(&self.data),
+            transform_to_parent: planar_isometry(position, yaw),
+        })
+    }
+
+    /// Returns the lowest common ancestor of this frame and `other`, or `None` if they belong
+    /// to different trees.
+    #[must_use]
+    pub fn lowest_common_ancestor(&self, other: &Self) -> Option<Self> {
+        self.lca_with(other)
+    }
+
+    /// Returns the transformation from this frame to the target frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the frames do not share a common ancestor.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::frame2d::Frame2D;
+    /// use nalgebra::Point2;
+    ///
+    /// let map = Frame2D::new_origin("map").unwrap();
+    /// let dock = map.add_child("dock", [1.0, 0.0], 0.0).unwrap();
+    /// let agv = map.add_child("agv", [0.0, 1.0], 0.0).unwrap();
+    /// let agv_to_dock = agv.transform_to(&dock).unwrap();
+    /// assert_eq!(agv_to_dock * Point2::origin(), Point2::new(-1.0, 1.0));
+    /// ```
+    pub fn transform_to(&self, target: &Self) -> Result<Isometry2<f64>, CartesianTreeError> {
+        let ancestor =
+            self.lca_with(target)
+                .ok_or_else(|| CartesianTreeError::NoCommonAncestor {
+                    from: self.name(),
+                    to: target.name(),
+                })?;
+        Ok(target.transform_to_ancestor(&ancestor).inverse()
+            * self.transform_to_ancestor(&ancestor))
+    }
+
+    /// Maps a point expressed in this frame into the `target` frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the frames do not share a common ancestor.
+    pub fn transform_point_to(
+        &self,
+        target: &Self,
+        point: &Point2<f64>,
+    ) -> Result<Point2<f64>, CartesianTreeError> {
+        Ok(self.transform_to(target)? * point)
+    }
+
+    /// Composes the transforms up to `ancestor`, which has to be an ancestor of this frame.
+    fn transform_to_ancestor(&self, ancestor: &Self) -> Isometry2<f64> {
+        let mut transform = Isometry2::identity();
+        let mut current = self.clone();
+        while !current.is_same(ancestor) {
+            transform = current.data.borrow().transform_to_parent * transform;
+            let Some(parent) = current.parent() else {
+                break;
+            };
+            current = parent;
+        }
+        transform
+    }
+
+    /// Serializes this frame and its descendants to a JSON string.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if serialization fails.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String, CartesianTreeError> {
+        Ok(serde_json::to_string_pretty(&self.to_serial())?)
+    }
+
+    #[cfg(feature = "serde")]
+    fn to_serial(&self) -> SerialFrame2D {
+        let data = self.data.borrow();
+        SerialFrame2D {
+            name: data.name.clone(),
+            position: data.transform_to_parent.translation.vector,
+            yaw: data.transform_to_parent.rotation.angle(),
+            children: data.children.iter().map(Self::to_serial).collect(),
+        }
+    }
+
+    /// Builds a new planar tree from a JSON string in the format written by
+    /// [`Frame2D::to_json`].
+    ///
+    /// The transform stored for the top-level frame is ignored, as it becomes the root.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The string is not valid JSON or a node is malformed.
+    /// - A frame name violates [`FrameNameRules::Strict`].
+    /// - Two siblings share the same name.
+    /// - A transform is not finite.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::frame2d::Frame2D;
+    /// use cartesian_tree::tree::HasChildren;
+    ///
+    /// let map = Frame2D::new_origin("map").unwrap();
+    /// map.add_child("agv", [2.0, 1.0], 0.5).unwrap();
+    ///
+    /// let copy = Frame2D::from_json(&map.to_json().unwrap()).unwrap();
+    /// assert_eq!(copy.children()[0].name(), "agv");
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, CartesianTreeError> {
+        let serial: SerialFrame2D = serde_json::from_str(json)?;
+        let root = Self::new_origin(serial.name.clone())?;
+        let mut pending = vec![(root.clone(), &serial)];
+        while let Some((frame, node)) = pending.pop() {
+            for child in &node.children {
+                let child_frame = frame.add_child(child.name.clone(), child.position, child.yaw)?;
+                pending.push((child_frame, child));
+            }
+        }
+        Ok(root)
+    }
+
+    /// Adds a spatial copy of this frame and its descendants as a child of `parent`.
+    ///
+    /// Every planar frame becomes a [`Frame`] with the same name, its position in the xy-plane
+    /// with zero z, and its yaw as a rotation about z without roll and pitch. A root is lifted
+    /// to a child with the identity transform.
+    ///
+    /// # Returns
+    /// The spatial counterpart of this frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if a frame cannot be added, e.g. because `parent`
+    /// already has a child with the same name.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::frame2d::Frame2D;
+    /// use nalgebra::Vector3;
+    ///
+    /// let map = Frame2D::new_origin("map").unwrap();
+    /// map.add_child("agv", [2.0, 1.0], 0.0).unwrap();
+    ///
+    /// let world = Frame::new_origin("world").unwrap();
+    /// map.lift_to_3d(&world).unwrap();
+    /// let agv = world.resolve_path("map/agv").unwrap();
+    /// assert_eq!(agv.position(), Vector3::new(2.0, 1.0, 0.0));
+    /// ```
+    pub fn lift_to_3d(&self, parent: &Frame) -> Result<Frame, CartesianTreeError> {
+        let lifted = lift_isometry(&self.data.borrow().transform_to_parent);
+        let frame = parent.add_child(self.name(), lifted.translation.vector, lifted.rotation)?;
+        for child in self.children() {
+            child.lift_to_3d(&frame)?;
+        }
+        Ok(frame)
+    }
+}
+
+impl HasParent for Frame2D {
+    type Node = Self;
+
+    fn parent(&self) -> Option<Self::Node> {
+        self.data
+            .borrow()
+            .parent
+            .as_ref()
+            .and_then(Weak::upgrade)
+            .map(|data| Self { data })
+    }
+}
+
+impl NodeEquality for Frame2D {
+    fn is_same(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.data, &other.data)
+    }
+}
+
+impl HasChildren for Frame2D {
+    type Node = Self;
+
+    fn children(&self) -> Vec<Self> {
+        self.data.borrow().children.clone()
+    }
+}
+
+impl Pose2D {
+    /// Returns the frame of this pose, or `None` if it has been dropped.
+    #[must_use]
+    pub fn frame(&self) -> Option<Frame2D> {
+        self.parent.upgrade().map(|data| Frame2D { data })
+    }
+
+    /// Returns the position of the pose in its frame.
+    #[must_use]
+    pub const fn position(&self) -> Vector2<f64> {
+        self.transform_to_parent.translation.vector
+    }
+
+    /// Returns the yaw angle of the pose in its frame, in radians within `(-π, π]`.
+    #[must_use]
+    pub fn yaw(&self) -> f64 {
+        self.transform_to_parent.rotation.angle()
+    }
+
+    /// Returns the transformation from this pose to its frame.
+    #[must_use]
+    pub const fn transformation(&self) -> Isometry2<f64> {
+        self.transform_to_parent
+    }
+
+    /// Updates the position and yaw of the pose within its frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if a component is NaN or infinite.
+    pub fn set(
+        &mut self,
+        position: impl Into<Vector2<f64>>,
+        yaw: f64,
+    ) -> Result<(), CartesianTreeError> {
+        let position = position.into();
+        check_planar(&position, yaw)?;
+        self.transform_to_parent = planar_isometry(position, yaw);
+        Ok(())
+    }
+
+    /// Expresses this pose in the target frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame of the pose has been dropped.
+    /// - The frames do not share a common ancestor.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::frame2d::Frame2D;
+    /// use nalgebra::Vector2;
+    ///
+    /// let map = Frame2D::new_origin("map").unwrap();
+    /// let agv = map.add_child("agv", [1.0, 0.0], std::f64::consts::FRAC_PI_2).unwrap();
+    /// let pallet = agv.add_pose([1.0, 0.0], 0.0).unwrap();
+    /// let in_map = pallet.in_frame(&map).unwrap();
+    /// assert!((in_map.position() - Vector2::new(1.0, 1.0)).norm() < 1e-12);
+    /// ```
+    pub fn in_frame(&self, target: &Frame2D) -> Result<Self, CartesianTreeError> {
+        let frame = self.frame().ok_or(CartesianTreeError::FrameDropped)?;
+        Ok(Self {
+            parent: Rc::downgrade(&target.data),
+            transform_to_parent: frame.transform_to(target)? * self.transform_to_parent,
+        })
+    }
+
+    /// Creates the spatial counterpart of this pose in `frame`, with zero z and without roll
+    /// and pitch.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the pose cannot be added to `frame`.
+    pub fn lift_to_3d(&self, frame: &Frame) -> Result<Pose, CartesianTreeError> {
+        let lifted = lift_isometry(&self.transform_to_parent);
+        frame.add_pose(lifted.translation.vector, lifted.rotation)
+    }
+}
+
+impl Pose {
+    /// Projects this pose onto the xy-plane of its frame and expresses the result in `frame`.
+    ///
+    /// `frame` is the planar counterpart of the frame of this pose. The yaw is the rotation
+    /// about the z axis that remains after removing any roll and pitch (swing-twist
+    /// decomposition), so it stays well defined for slightly tilted poses.
+    ///
+    /// # Returns
+    /// The planar pose and the discarded out-of-plane content.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::frame2d::Frame2D;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let world = Frame::new_origin("world").unwrap();
+    /// let pose = world
+    ///     .add_pose(Vector3::new(1.0, 2.0, 0.01), UnitQuaternion::from_euler_angles(0.0, 0.02, 0.5))
+    ///     .unwrap();
+    ///
+    /// let map = Frame2D::new_origin("map").unwrap();
+    /// let (planar, residual) = pose.project_to_2d(&map);
+    /// assert!((planar.yaw() - 0.5).abs() < 1e-12);
+    /// assert_eq!(residual.height, 0.01);
+    /// assert!((residual.tilt - 0.02).abs() < 1e-12);
+    /// ```
+    #[must_use]
+    pub fn project_to_2d(&self, frame: &Frame2D) -> (Pose2D, PlanarResidual) {
+        let transform = self.transformation();
+        let position = transform.translation.vector;
+        let rotation = transform.rotation;
+        let yaw = 2.0 * rotation.k.atan2(rotation.w);
+        let tilt = (rotation * Vector3::z()).z.clamp(-1.0, 1.0).acos();
+        (
+            Pose2D {
+                parent: Rc::downgrade(&frame.data),
+                transform_to_parent: planar_isometry(position.xy(), yaw),
+            },
+            PlanarResidual {
+                height: position.z,
+                tilt,
+            },
+        )
+    }
+}
+
+fn planar_isometry(position: Vector2<f64>, yaw: f64) -> Isometry2<f64> {
+    Isometry2::new(position, yaw)
+}
+
+/// Embeds a planar isometry in the xy-plane.
+fn lift_isometry(isometry: &Isometry2<f64>) -> Isometry3<f64> {
+    let position = isometry.translation.vector;
+    Isometry3::from_parts(
+        Translation3::new(position.x, position.y, 0.0),
+        UnitQuaternion::from_axis_angle(&Vector3::z_axis(), isometry.rotation.angle()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use nalgebra::Point2;
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn create_origin_and_children() {
+        let map = Frame2D::new_origin("map").unwrap();
+        assert_eq!(map.name(), "map");
+        assert!(map.parent().is_none());
+        assert!(matches!(
+            map.transformation(),
+            Err(CartesianTreeError::RootHasNoParent { .. })
+        ));
+
+        let agv = map.add_child("agv", [1.0, 2.0], FRAC_PI_2).unwrap();
+        assert!(agv.parent().unwrap().is_same(&map));
+        assert_eq!(map.children().len(), 1);
+        assert_eq!(agv.position(), Vector2::new(1.0, 2.0));
+        assert_relative_eq!(agv.yaw(), FRAC_PI_2, epsilon = 1e-12);
+        assert_eq!(agv.depth(), 1);
+
+        assert!(matches!(
+            map.add_child("agv", [0.0, 0.0], 0.0),
+            Err(CartesianTreeError::DuplicateChildName { .. })
+        ));
+        assert!(matches!(
+            map.add_child("a/b", [0.0, 0.0], 0.0),
+            Err(CartesianTreeError::InvalidFrameName { .. })
+        ));
+        assert!(matches!(
+            map.add_child("nan", [f64::NAN, 0.0], 0.0),
+            Err(CartesianTreeError::NonFiniteValue { field, .. }) if field == "position.x"
+        ));
+        assert!(matches!(
+            map.add_child("inf", [0.0, 0.0], f64::INFINITY),
+            Err(CartesianTreeError::NonFiniteValue { field, .. }) if field == "yaw"
+        ));
+        assert_eq!(map.children().len(), 1);
+    }
+
+    #[test]
+    fn set_updates_transform() {
+        let map = Frame2D::new_origin("map").unwrap();
+        let agv = map.add_child("agv", [0.0, 0.0], 0.0).unwrap();
+        agv.set([3.0, -1.0], -FRAC_PI_2).unwrap();
+        assert_eq!(agv.position(), Vector2::new(3.0, -1.0));
+        assert_relative_eq!(agv.yaw(), -FRAC_PI_2, epsilon = 1e-12);
+
+        // Angles are normalized into (-π, π].
+        agv.set([0.0, 0.0], 3.0 * PI).unwrap();
+        assert_relative_eq!(agv.yaw(), PI, epsilon = 1e-12);
+
+        assert!(matches!(
+            map.set([1.0, 0.0], 0.0),
+            Err(CartesianTreeError::CannotUpdateRootTransform { .. })
+        ));
+        assert!(agv.set([f64::NAN, 0.0], 0.0).is_err());
+        assert_relative_eq!(agv.yaw(), PI, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn transforms_between_branches() {
+        let map = Frame2D::new_origin("map").unwrap();
+        let agv = map.add_child("agv", [1.0, 0.0], FRAC_PI_2).unwrap();
+        let lidar = agv.add_child("lidar", [0.5, 0.0], 0.0).unwrap();
+        let dock = map.add_child("dock", [0.0, 3.0], PI).unwrap();
+
+        // The lidar sits 0.5 ahead of the AGV, which faces +y.
+        assert_relative_eq!(
+            lidar.transform_point_to(&map, &Point2::origin()).unwrap(),
+            Point2::new(1.0, 0.5),
+            epsilon = 1e-12
+        );
+        let lidar_to_dock = lidar.transform_to(&dock).unwrap();
+        assert_relative_eq!(
+            lidar_to_dock * Point2::origin(),
+            Point2::new(-1.0, 2.5),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            dock.transform_to(&lidar).unwrap(),
+            lidar_to_dock.inverse(),
+            epsilon = 1e-12
+        );
+        assert_eq!(lidar.transform_to(&lidar).unwrap(), Isometry2::identity());
+
+        assert!(lidar.lowest_common_ancestor(&dock).unwrap().is_same(&map));
+        assert!(lidar.lowest_common_ancestor(&agv).unwrap().is_same(&agv));
+        let other = Frame2D::new_origin("other").unwrap();
+        assert!(lidar.lowest_common_ancestor(&other).is_none());
+        assert!(matches!(
+            lidar.transform_to(&other),
+            Err(CartesianTreeError::NoCommonAncestor { .. })
+        ));
+    }
+
+    #[test]
+    fn poses_move_between_frames() {
+        let map = Frame2D::new_origin("map").unwrap();
+        let agv = map.add_child("agv", [1.0, 0.0], FRAC_PI_2).unwrap();
+        let mut pallet = agv.add_pose([2.0, 0.0], 0.25).unwrap();
+        assert!(pallet.frame().unwrap().is_same(&agv));
+
+        let in_map = pallet.in_frame(&map).unwrap();
+        assert!(in_map.frame().unwrap().is_same(&map));
+        assert_relative_eq!(in_map.position(), Vector2::new(1.0, 2.0), epsilon = 1e-12);
+        assert_relative_eq!(in_map.yaw(), FRAC_PI_2 + 0.25, epsilon = 1e-12);
+        let back = in_map.in_frame(&agv).unwrap();
+        assert_relative_eq!(
+            back.transformation(),
+            pallet.transformation(),
+            epsilon = 1e-12
+        );
+
+        pallet.set([0.0, 1.0], 0.0).unwrap();
+        assert_eq!(pallet.position(), Vector2::new(0.0, 1.0));
+        assert!(pallet.set([0.0, 0.0], f64::NAN).is_err());
+
+        drop(map);
+        drop(agv);
+        assert!(matches!(
+            pallet.in_frame(&Frame2D::new_origin("other").unwrap()),
+            Err(CartesianTreeError::FrameDropped)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn json_round_trip() {
+        let map = Frame2D::new_origin("map").unwrap();
+        let agv = map.add_child("agv", [1.0, 2.0], 0.5).unwrap();
+        agv.add_child("lidar", [0.3, 0.0], -0.1).unwrap();
+        map.add_child("dock", [5.0, 0.0], PI).unwrap();
+
+        let copy = Frame2D::from_json(&map.to_json().unwrap()).unwrap();
+        assert_eq!(copy.name(), "map");
+        let names: Vec<_> = copy.children().iter().map(Frame2D::name).collect();
+        assert_eq!(names, ["agv", "dock"]);
+        let lidar = copy.children()[0].children()[0].clone();
+        assert_relative_eq!(
+            lidar.transform_to(&copy).unwrap(),
+            agv.children()[0].transform_to(&map).unwrap(),
+            epsilon = 1e-12
+        );
+
+        assert!(Frame2D::from_json("{").is_err());
+        let duplicate = r#"{"name": "map", "position": [0, 0], "yaw": 0, "children": [
+            {"name": "a", "position": [0, 0], "yaw": 0, "children": []},
+            {"name": "a", "position": [1, 0], "yaw": 0, "children": []}
+        ]}"#;
+        assert!(matches!(
+            Frame2D::from_json(duplicate),
+            Err(CartesianTreeError::DuplicateChildName { .. })
+        ));
+    }
+
+    #[test]
+    fn lift_to_3d_keeps_planar_transforms() {
+        let map = Frame2D::new_origin("map").unwrap();
+        let agv = map.add_child("agv", [1.0, 0.0], FRAC_PI_2).unwrap();
+        let lidar = agv.add_child("lidar", [0.5, 0.0], 0.0).unwrap();
+        let pose = lidar.add_pose([1.0, 0.0], 0.3).unwrap();
+
+        let world = Frame::new_origin("world").unwrap();
+        let lifted_map = map.lift_to_3d(&world).unwrap();
+        assert_eq!(lifted_map.transformation().unwrap(), Isometry3::identity());
+        let lifted_lidar = world.resolve_path("map/agv/lidar").unwrap();
+        let expected = lidar.transform_to(&map).unwrap();
+        let actual = lifted_lidar.transform_to(&lifted_map).unwrap();
+        assert_relative_eq!(
+            actual.translation.vector,
+            Vector3::new(expected.translation.x, expected.translation.y, 0.0),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            actual.rotation.euler_angles().2,
+            expected.rotation.angle(),
+            epsilon = 1e-12
+        );
+
+        let lifted_pose = pose.lift_to_3d(&lifted_lidar).unwrap();
+        let (projected, residual) = lifted_pose.project_to_2d(&lidar);
+        assert_relative_eq!(
+            projected.transformation(),
+            pose.transformation(),
+            epsilon = 1e-12
+        );
+        assert_eq!(
+            residual,
+            PlanarResidual {
+                height: 0.0,
+                tilt: 0.0
+            }
+        );
+
+        assert!(map.lift_to_3d(&world).is_err());
+    }
+
+    #[test]
+    fn projection_reports_discarded_content() {
+        let world = Frame::new_origin("world").unwrap();
+        let tilted = world
+            .add_pose(
+                Vector3::new(1.0, -2.0, 0.4),
+                UnitQuaternion::from_euler_angles(0.1, 0.0, -1.2),
+            )
+            .unwrap();
+        let map = Frame2D::new_origin("map").unwrap();
+
+        let (planar, residual) = tilted.project_to_2d(&map);
+        assert!(planar.frame().unwrap().is_same(&map));
+        assert_eq!(planar.position(), Vector2::new(1.0, -2.0));
+        assert_relative_eq!(residual.height, 0.4);
+        assert_relative_eq!(residual.tilt, 0.1, epsilon = 1e-12);
+        // Roll applied before the yaw only tilts the z axis; the heading is kept.
+        assert_relative_eq!(planar.yaw(), -1.2, epsilon = 1e-12);
+
+        // Upside down is as far out of plane as it gets.
+        let flipped = world
+            .add_pose(
+                Vector3::zeros(),
+                UnitQuaternion::from_euler_angles(PI, 0.0, 0.0),
+            )
+            .unwrap();
+        assert_relative_eq!(flipped.project_to_2d(&map).1.tilt, PI, epsilon = 1e-12);
+    }
+}
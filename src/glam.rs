@@ -0,0 +1,151 @@
+//! Conversions to and from [`glam`] types, enabled by the `glam` feature.
+//!
+//! The feature also enables nalgebra's own `glam` conversions, e.g. between `Vector3<f64>` and
+//! `DVec3`, `Isometry3<f64>` and `DMat4`, or `Matrix4<f64>` and `DMat4`. On top of these, this
+//! module converts [`Rotation`] to and from `DQuat`, and [`Pose`] to `DAffine3` and `DMat4`.
+//! The orphan rule keeps `Isometry3<f64>` and `DAffine3` from converting via `From`; use
+//! [`affine_from_isometry`] and [`isometry_from_affine`] instead.
+//!
+//! Both libraries rotate points actively in right-handed coordinates, so a `DQuat` and the
+//! [`Rotation`] converted from it rotate points alike.
+//!
+//! # Example
+//! ```
+//! use cartesian_tree::Frame;
+//! use glam::{DAffine3, DQuat, DVec3};
+//!
+//! let world = Frame::new_origin("world").unwrap();
+//! let camera = world
+//!     .add_child("camera", DVec3::new(0.0, 0.0, 1.0), DQuat::from_rotation_z(0.5))
+//!     .unwrap();
+//! let pose = camera.add_pose([1.0, 0.0, 0.0], DQuat::IDENTITY).unwrap();
+//! let affine = DAffine3::from(&pose.in_frame(&world).unwrap());
+//! assert!((affine.translation.z - 1.0).abs() < 1e-12);
+//! ```
+
+use crate::CartesianTreeError;
+use crate::pose::Pose;
+use crate::rotation::Rotation;
+use glam::{DAffine3, DMat4, DQuat, DVec3};
+use nalgebra::{Isometry3, Matrix3, Translation3, Vector3};
+
+/// Interprets the quaternion as is, normalizing it.
+impl From<DQuat> for Rotation {
+    fn from(quaternion: DQuat) -> Self {
+        Self::from_quaternion(quaternion.x, quaternion.y, quaternion.z, quaternion.w)
+    }
+}
+
+impl From<Rotation> for DQuat {
+    fn from(rotation: Rotation) -> Self {
+        let quaternion = rotation.as_quaternion();
+        Self::from_xyzw(quaternion.i, quaternion.j, quaternion.k, quaternion.w)
+    }
+}
+
+/// The transformation from the pose to its frame.
+impl From<&Pose> for DAffine3 {
+    fn from(pose: &Pose) -> Self {
+        affine_from_isometry(&pose.transformation())
+    }
+}
+
+/// The homogeneous matrix of the transformation from the pose to its frame.
+impl From<&Pose> for DMat4 {
+    fn from(pose: &Pose) -> Self {
+        Self::from(pose.transformation())
+    }
+}
+
+/// Returns `isometry` as an affine transform.
+#[must_use]
+pub fn affine_from_isometry(isometry: &Isometry3<f64>) -> DAffine3 {
+    let rotation = DQuat::from(Rotation::from(isometry.rotation));
+    DAffine3::from_rotation_translation(rotation, DVec3::from(isometry.translation.vector))
+}
+
+/// Returns `affine` as an isometry.
+///
+/// # Errors
+/// Returns [`CartesianTreeError::InvalidRotationMatrix`] if the linear part of `affine` is not
+/// a rotation (within a tolerance of `1e-6`), e.g. because it scales or shears.
+pub fn isometry_from_affine(affine: &DAffine3) -> Result<Isometry3<f64>, CartesianTreeError> {
+    let rotation = Rotation::from_matrix(Matrix3::from(affine.matrix3))?;
+    Ok(Isometry3::from_parts(
+        Translation3::from(Vector3::from(affine.translation)),
+        rotation.as_quaternion(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Frame;
+    use approx::assert_relative_eq;
+    use nalgebra::{Matrix4, Point3, Quaternion, UnitQuaternion};
+
+    /// Returns `quaternion` as nalgebra stores it.
+    fn nalgebra_quaternion(quaternion: DQuat) -> UnitQuaternion<f64> {
+        UnitQuaternion::new_normalize(Quaternion::new(
+            quaternion.w,
+            quaternion.x,
+            quaternion.y,
+            quaternion.z,
+        ))
+    }
+
+    #[test]
+    fn rotations_rotate_points_alike_in_both_libraries() {
+        let quaternion = DQuat::from_axis_angle(DVec3::new(1.0, 2.0, 3.0).normalize(), 0.7);
+        let rotation = Rotation::from(quaternion);
+        let point = Vector3::new(0.3, -1.2, 2.5);
+
+        let ours = rotation.as_quaternion() * point;
+        let theirs = quaternion * DVec3::from(point);
+        assert_relative_eq!(ours, Vector3::from(theirs), epsilon = 1e-12);
+        assert_relative_eq!(
+            rotation.as_quaternion(),
+            nalgebra_quaternion(quaternion),
+            epsilon = 1e-12
+        );
+
+        // A quarter turn about z maps x onto y in both.
+        let quarter = Rotation::from_rpy(0.0, 0.0, std::f64::consts::FRAC_PI_2);
+        let rotated = DQuat::from(quarter) * DVec3::X;
+        assert!(rotated.abs_diff_eq(DVec3::Y, 1e-12));
+    }
+
+    #[test]
+    fn glam_types_place_frames_and_poses() {
+        let world = Frame::new_origin("world").unwrap();
+        let quaternion = DQuat::from_euler(glam::EulerRot::ZYX, 0.4, -0.2, 0.1);
+        let arm = world
+            .add_child("arm", DVec3::new(1.0, 2.0, 3.0), quaternion)
+            .unwrap();
+        let pose = arm.add_pose([0.5, 0.0, -0.5], DQuat::IDENTITY).unwrap();
+        let in_world = pose.in_frame(&world).unwrap();
+
+        let point = Point3::new(0.1, 0.2, 0.3);
+        let ours = in_world.transform_point(&point);
+        let theirs = DAffine3::from(&in_world).transform_point3(DVec3::from(point.coords));
+        assert_relative_eq!(ours.coords, Vector3::from(theirs), epsilon = 1e-12);
+        assert_relative_eq!(
+            Matrix4::from(DMat4::from(&in_world)),
+            in_world.to_matrix(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn affine_transforms_round_trip_through_isometries() {
+        let isometry = Isometry3::new(Vector3::new(1.0, -2.0, 0.5), Vector3::new(0.3, 0.2, 0.1));
+        let back = isometry_from_affine(&affine_from_isometry(&isometry)).unwrap();
+        assert_relative_eq!(back, isometry, epsilon = 1e-12);
+
+        let scaled = DAffine3::from_scale(DVec3::splat(2.0));
+        assert!(matches!(
+            isometry_from_affine(&scaled),
+            Err(CartesianTreeError::InvalidRotationMatrix { .. })
+        ));
+    }
+}
@@ -0,0 +1,360 @@
+//! Export of frame trees as glTF 2.0 binaries (`.glb`), e.g. to inspect them in a web viewer.
+//!
+//! Every frame becomes a node with the same name, and the node hierarchy mirrors the frame tree.
+//! The translation and rotation of a node are the transform of the frame to its parent; scaled
+//! frames (see [`Frame::add_scaled_child`]) also set the node scale.
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::tree::HasChildren;
+use serde_json::{Value, json};
+
+/// Options for [`Frame::to_gltf_with`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GltfOptions {
+    /// Attaches an axis triad of this length to every node, drawn as red, green and blue lines
+    /// along x, y and z. `None` exports the bare node hierarchy.
+    pub axis_length: Option<f64>,
+}
+
+const GLB_MAGIC: &[u8; 4] = b"glTF";
+const GLB_VERSION: u32 = 2;
+const CHUNK_JSON: &[u8; 4] = b"JSON";
+const CHUNK_BIN: &[u8; 4] = b"BIN\0";
+
+impl Frame {
+    /// Exports the tree below this frame as a glTF binary without meshes.
+    ///
+    /// Like [`Frame::to_json`], the transform of this frame itself is not exported; it becomes
+    /// the root node of the scene.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the document does not fit into a glTF binary.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// root.add_child("robot", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let glb = root.to_gltf().unwrap();
+    /// assert_eq!(&glb[..4], b"glTF");
+    /// ```
+    pub fn to_gltf(&self) -> Result<Vec<u8>, CartesianTreeError> {
+        self.to_gltf_with(GltfOptions::default())
+    }
+
+    /// Exports the tree below this frame as a glTF binary, see [`Frame::to_gltf`].
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The axis length is not a positive finite number.
+    /// - The document does not fit into a glTF binary.
+    pub fn to_gltf_with(&self, options: GltfOptions) -> Result<Vec<u8>, CartesianTreeError> {
+        let mut nodes = Vec::new();
+        let mut children: Vec<Vec<usize>> = Vec::new();
+        let mut stack: Vec<(Self, Option<usize>)> = vec![(self.clone(), None)];
+        while let Some((frame, parent)) = stack.pop() {
+            let index = nodes.len();
+            if let Some(parent) = parent {
+                children[parent].push(index);
+            }
+            let mut node = json!({"name": frame.name()});
+            if parent.is_some() {
+                let data = frame.borrow();
+                let transform = data.transform_to_parent;
+                node["translation"] = json!(transform.translation.vector.as_slice());
+                node["rotation"] = json!(transform.rotation.coords.as_slice());
+                if let Some(scale) = data.scale {
+                    node["scale"] = json!([scale, scale, scale]);
+                }
+            }
+            if options.axis_length.is_some() {
+                node["mesh"] = 0.into();
+            }
+            nodes.push(node);
+            children.push(Vec::new());
+            for child in frame.children().into_iter().rev() {
+                stack.push((child, Some(index)));
+            }
+        }
+        // glTF requires `children` to be non-empty when present.
+        for (node, children) in nodes.iter_mut().zip(children) {
+            if !children.is_empty() {
+                node["children"] = children.into();
+            }
+        }
+
+        let mut document = json!({
+            "asset": {"version": "2.0", "generator": "cartesian_tree"},
+            "scene": 0,
+            "scenes": [{"name": self.name(), "nodes": [0]}],
+            "nodes": nodes,
+        });
+        let binary = match options.axis_length {
+            Some(length) if !(length.is_finite() && length > 0.0) => {
+                return Err(CartesianTreeError::DegenerateInput {
+                    reason: format!("the axis length has to be a positive number, got {length}"),
+                });
+            }
+            Some(length) => axis_triad(&mut document, length),
+            None => Vec::new(),
+        };
+        glb(&document, &binary)
+    }
+}
+
+/// Adds the mesh of an axis triad to `document` and returns its buffer.
+fn axis_triad(document: &mut Value, length: f64) -> Vec<u8> {
+    // glTF stores vertex data as single precision.
+    #[allow(clippy::cast_possible_truncation)]
+    let length = length as f32;
+    let positions = [
+        [0.0, 0.0, 0.0],
+        [length, 0.0, 0.0],
+        [0.0, 0.0, 0.0],
+        [0.0, length, 0.0],
+        [0.0, 0.0, 0.0],
+        [0.0, 0.0, length],
+    ];
+    let colors = [
+        [1.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+        [0.0, 0.0, 1.0],
+    ];
+    let mut buffer = Vec::new();
+    for value in positions.iter().chain(&colors).flatten() {
+        buffer.extend_from_slice(&f32::to_le_bytes(*value));
+    }
+    let half = buffer.len() / 2;
+    document["meshes"] = json!([{
+        "name": "axes",
+        // Mode 1 draws pairs of vertices as lines.
+        "primitives": [{"attributes": {"POSITION": 0, "COLOR_0": 1}, "mode": 1}],
+    }]);
+    // Component type 5126 is `f32`.
+    document["accessors"] = json!([
+        {
+            "bufferView": 0,
+            "componentType": 5126,
+            "count": positions.len(),
+            "type": "VEC3",
+            "min": [0.0, 0.0, 0.0],
+            "max": [length, length, length],
+        },
+        {"bufferView": 1, "componentType": 5126, "count": colors.len(), "type": "VEC3"},
+    ]);
+    document["bufferViews"] = json!([
+        {"buffer": 0, "byteOffset": 0, "byteLength": half},
+        {"buffer": 0, "byteOffset": half, "byteLength": half},
+    ]);
+    document["buffers"] = json!([{"byteLength": buffer.len()}]);
+    buffer
+}
+
+/// Packs the JSON document and the binary buffer into a glTF binary.
+fn glb(document: &Value, binary: &[u8]) -> Result<Vec<u8>, CartesianTreeError> {
+    let mut json = serde_json::to_vec(document)?;
+    // Chunks are aligned to four bytes; JSON is padded with spaces, binary data with zeros.
+    json.resize(json.len().next_multiple_of(4), b' ');
+    let mut binary = binary.to_vec();
+    binary.resize(binary.len().next_multiple_of(4), 0);
+
+    let mut length = 12 + 8 + json.len();
+    if !binary.is_empty() {
+        length += 8 + binary.len();
+    }
+    let too_large = |_| CartesianTreeError::DegenerateInput {
+        reason: "the glTF binary would exceed 4 GiB".to_string(),
+    };
+    let mut glb = Vec::with_capacity(length);
+    glb.extend_from_slice(GLB_MAGIC);
+    glb.extend_from_slice(&GLB_VERSION.to_le_bytes());
+    glb.extend_from_slice(&u32::try_from(length).map_err(too_large)?.to_le_bytes());
+    for (kind, chunk) in [(CHUNK_JSON, &json), (CHUNK_BIN, &binary)] {
+        if chunk.is_empty() {
+            continue;
+        }
+        glb.extend_from_slice(&u32::try_from(chunk.len()).map_err(too_large)?.to_le_bytes());
+        glb.extend_from_slice(kind);
+        glb.extend_from_slice(chunk);
+    }
+    Ok(glb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rotation::Rotation;
+    use approx::assert_relative_eq;
+    use nalgebra::{UnitQuaternion, Vector3};
+
+    /// Splits a glTF binary into its JSON document and binary chunk.
+    fn parse(glb: &[u8]) -> (Value, Vec<u8>) {
+        let word = |offset: usize| {
+            u32::from_le_bytes(glb[offset..offset + 4].try_into().unwrap()) as usize
+        };
+        assert_eq!(&glb[..4], GLB_MAGIC);
+        assert_eq!(word(4), 2);
+        assert_eq!(word(8), glb.len());
+
+        let mut chunks = Vec::new();
+        let mut offset = 12;
+        while offset < glb.len() {
+            let length = word(offset);
+            assert_eq!(length % 4, 0);
+            chunks.push((
+                &glb[offset + 4..offset + 8],
+                &glb[offset + 8..offset + 8 + length],
+            ));
+            offset += 8 + length;
+        }
+        assert_eq!(chunks[0].0, CHUNK_JSON);
+        let document = serde_json::from_slice(chunks[0].1).unwrap();
+        let binary = chunks
+            .get(1)
+            .map(|(kind, data)| {
+                assert_eq!(kind, CHUNK_BIN);
+                data.to_vec()
+            })
+            .unwrap_or_default();
+        (document, binary)
+    }
+
+    fn workcell() -> Frame {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "robot",
+                Vector3::new(1.0, 2.0, 0.5),
+                Rotation::from_rpy(0.0, 0.0, 0.3),
+            )
+            .unwrap();
+        arm.add_child(
+            "flange",
+            Vector3::new(0.0, 0.0, 0.8),
+            UnitQuaternion::identity(),
+        )
+        .unwrap();
+        root.add_scaled_child(
+            "drawing",
+            Vector3::zeros(),
+            UnitQuaternion::identity(),
+            0.001,
+        )
+        .unwrap();
+        root
+    }
+
+    fn node<'a>(document: &'a Value, name: &str) -> (usize, &'a Value) {
+        document["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .find(|(_, node)| node["name"] == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn nodes_mirror_the_frame_tree() {
+        let (document, binary) = parse(&workcell().to_gltf().unwrap());
+        assert!(binary.is_empty());
+        assert_eq!(document["asset"]["version"], "2.0");
+        assert_eq!(document["nodes"].as_array().unwrap().len(), 4);
+        assert_eq!(document["scenes"][0]["nodes"], json!([0]));
+
+        let (world, world_node) = node(&document, "world");
+        let (robot, robot_node) = node(&document, "robot");
+        let (flange, flange_node) = node(&document, "flange");
+        let (drawing, _) = node(&document, "drawing");
+        assert_eq!(world, 0);
+        assert_eq!(world_node["children"], json!([robot, drawing]));
+        assert_eq!(robot_node["children"], json!([flange]));
+        assert!(flange_node.get("children").is_none());
+        assert!(world_node.get("translation").is_none());
+
+        assert_eq!(robot_node["translation"], json!([1.0, 2.0, 0.5]));
+        let rotation = &robot_node["rotation"];
+        let expected = UnitQuaternion::from_euler_angles(0.0, 0.0, 0.3);
+        for (index, value) in expected.coords.iter().enumerate() {
+            assert_relative_eq!(rotation[index].as_f64().unwrap(), value, epsilon = 1e-12);
+        }
+        assert!(robot_node.get("scale").is_none());
+        assert_eq!(
+            document["nodes"][drawing]["scale"],
+            json!([0.001, 0.001, 0.001])
+        );
+    }
+
+    #[test]
+    fn exports_load_with_the_gltf_crate() {
+        for axis_length in [None, Some(0.25)] {
+            let glb = workcell()
+                .to_gltf_with(GltfOptions { axis_length })
+                .unwrap();
+            let gltf = ::gltf::Gltf::from_slice(&glb).unwrap();
+            assert_eq!(gltf.nodes().len(), 4);
+
+            let scene = gltf.default_scene().unwrap();
+            let roots: Vec<_> = scene.nodes().collect();
+            assert_eq!(roots.len(), 1);
+            let world = &roots[0];
+            assert_eq!(world.name(), Some("world"));
+            let names = |node: &::gltf::Node| {
+                node.children()
+                    .map(|child| child.name().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(names(world), ["robot", "drawing"]);
+            let robot = world.children().next().unwrap();
+            assert_eq!(names(&robot), ["flange"]);
+
+            let (translation, rotation, scale) = robot.transform().decomposed();
+            assert_relative_eq!(Vector3::from(translation), Vector3::new(1.0, 2.0, 0.5));
+            let expected = UnitQuaternion::from_euler_angles(0.0, 0.0, 0.3);
+            for (value, expected) in rotation.iter().zip(expected.coords.iter()) {
+                assert_relative_eq!(f64::from(*value), expected, epsilon = 1e-6);
+            }
+            assert_relative_eq!(Vector3::from(scale), Vector3::repeat(1.0));
+        }
+    }
+
+    #[test]
+    fn axis_triads_are_attached_to_every_node() {
+        let options = GltfOptions {
+            axis_length: Some(0.25),
+        };
+        let (document, binary) = parse(&workcell().to_gltf_with(options).unwrap());
+        assert_eq!(binary.len(), 144);
+        assert_eq!(document["buffers"][0]["byteLength"], 144);
+        assert_eq!(document["meshes"].as_array().unwrap().len(), 1);
+        assert!(
+            document["nodes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .all(|node| node["mesh"] == 0)
+        );
+        // The second vertex is the tip of the x axis.
+        let x_tip: Vec<f32> = binary[12..24]
+            .chunks(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        assert_eq!(x_tip, [0.25, 0.0, 0.0]);
+
+        let options = GltfOptions {
+            axis_length: Some(-1.0),
+        };
+        assert!(matches!(
+            workcell().to_gltf_with(options),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+    }
+}
@@ -0,0 +1,547 @@
+//! Time-stamped transform buffers, for looking up where frames were in the past.
+//!
+//! Frames do not record their transforms by default. After [`Frame::enable_history`],
+//! [`Frame::update_transform_at`] stores every update together with its time stamp, and the
+//! `*_at` lookups interpolate between the recorded samples, similar to the buffer of ROS tf2.
+//! Time stamps are plain `f64` values in a unit of the caller's choice, usually seconds.
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::pose::{Pose, interpolate_isometry};
+use crate::rotation::Rotation;
+use crate::tree::HasParent;
+use crate::validation::{check_orientation, check_position};
+use alloc::collections::VecDeque;
+use alloc::string::ToString;
+use core::time::Duration;
+use nalgebra::{Isometry3, Translation3, Vector3};
+
+/// Determines which samples a [`FrameHistory`] keeps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryCapacity {
+    /// Keeps the given number of most recent samples.
+    Samples(usize),
+    /// Keeps the samples recorded at most this long before the most recent one, interpreting
+    /// the time stamps as seconds.
+    Duration(Duration),
+}
+
+impl From<usize> for HistoryCapacity {
+    fn from(samples: usize) -> Self {
+        Self::Samples(samples)
+    }
+}
+
+impl From<Duration> for HistoryCapacity {
+    fn from(duration: Duration) -> Self {
+        Self::Duration(duration)
+    }
+}
+
+/// The recorded transforms of a frame relative to its parent, ordered by time stamp.
+#[derive(Clone, Debug)]
+pub struct FrameHistory {
+    capacity: HistoryCapacity,
+    samples: VecDeque<(f64, Isometry3<f64>)>,
+}
+
+impl FrameHistory {
+    /// Creates an empty history.
+    #[must_use]
+    pub const fn new(capacity: HistoryCapacity) -> Self {
+        Self {
+            capacity,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Returns which samples are kept.
+    #[must_use]
+    pub const fn capacity(&self) -> HistoryCapacity {
+        self.capacity
+    }
+
+    /// Returns the number of recorded samples.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns `true` if no samples are recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the time stamps of the oldest and the most recent sample.
+    #[must_use]
+    pub fn range(&self) -> Option<(f64, f64)> {
+        Some((self.samples.front()?.0, self.samples.back()?.0))
+    }
+
+    /// Returns the time stamps and transforms of the samples, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = (f64, Isometry3<f64>)> + '_ {
+        self.samples.iter().copied()
+    }
+
+    /// Records `transform` at `time`, replacing a sample with the same time stamp.
+    ///
+    /// Samples may arrive out of order. Afterwards, samples beyond the capacity are evicted,
+    /// oldest first, which may include the new sample itself.
+    pub fn insert(&mut self, time: f64, transform: Isometry3<f64>) {
+        // All samples from `index` on are not older than `time`, so `<=` means an equal stamp.
+        let index = self.samples.partition_point(|(stamp, _)| *stamp < time);
+        match self.samples.get_mut(index) {
+            Some(sample) if sample.0 <= time => sample.1 = transform,
+            _ => self.samples.insert(index, (time, transform)),
+        }
+        self.evict();
+    }
+
+    /// Returns the transform at `time`, or `None` if `time` lies outside [`FrameHistory::range`].
+    ///
+    /// Between two samples, the translation is interpolated linearly and the rotation along the
+    /// shortest arc.
+    #[must_use]
+    pub fn transform_at(&self, time: f64) -> Option<Isometry3<f64>> {
+        let index = self.samples.partition_point(|(stamp, _)| *stamp < time);
+        let (after_time, after) = *self.samples.get(index)?;
+        if after_time <= time {
+            return Some(after);
+        }
+        let (before_time, before) = *self.samples.get(index.checked_sub(1)?)?;
+        let t = (time - before_time) / (after_time - before_time);
+        Some(interpolate_isometry(&before, &after, t))
+    }
+
+    /// Applies `convert` to the translations of all samples.
+    pub(crate) fn map_translations(&mut self, convert: impl Fn(Vector3<f64>) -> Vector3<f64>) {
+        for (_, transform) in &mut self.samples {
+            transform.translation.vector = convert(transform.translation.vector);
+        }
+    }
+
+    fn evict(&mut self) {
+        match self.capacity {
+            HistoryCapacity::Samples(count) => {
+                let excess = self.samples.len().saturating_sub(count);
+                self.samples.drain(..excess);
+            }
+            HistoryCapacity::Duration(duration) => {
+                if let Some(&(latest, _)) = self.samples.back() {
+                    let oldest = latest - duration.as_secs_f64();
+                    while self
+                        .samples
+                        .front()
+                        .is_some_and(|(stamp, _)| *stamp < oldest)
+                    {
+                        self.samples.pop_front();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Frame {
+    /// Starts recording time-stamped transforms of this frame, see [`Frame::update_transform_at`].
+    ///
+    /// If the history is already enabled, only its capacity is changed, evicting samples that
+    /// no longer fit. Histories are not copied by [`Frame::clone_subtree`] nor serialized.
+    ///
+    /// # Arguments
+    /// - `capacity`: The number of samples to keep (`usize`), or how far back from the most
+    ///   recent sample to keep them ([`Duration`]).
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame is a root, whose transform cannot be updated.
+    /// - The capacity is zero samples.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    /// use std::time::Duration;
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let tool = root.add_child("tool", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// tool.enable_history(Duration::from_secs(10)).unwrap();
+    /// tool.update_transform_at(0.0, Vector3::new(0.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// tool.update_transform_at(1.0, Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let halfway = tool.transform_to_parent_at(0.5).unwrap();
+    /// assert_eq!(halfway.translation.vector, Vector3::new(0.5, 0.0, 0.0));
+    /// assert!(tool.transform_to_parent_at(1.5).is_err());
+    /// ```
+    pub fn enable_history(
+        &self,
+        capacity: impl Into<HistoryCapacity>,
+    ) -> Result<(), CartesianTreeError> {
+        if self.parent().is_none() {
+            return Err(CartesianTreeError::CannotUpdateRootTransform { frame: self.name() });
+        }
+        let capacity = capacity.into();
+        if capacity == HistoryCapacity::Samples(0) {
+            return Err(CartesianTreeError::DegenerateInput {
+                reason: "the history has to keep at least one sample".to_string(),
+            });
+        }
+        let mut data = self.data.borrow_mut();
+        match &mut data.history {
+            Some(history) => {
+                history.capacity = capacity;
+                history.evict();
+            }
+            history @ None => *history = Some(FrameHistory::new(capacity)),
+        }
+        Ok(())
+    }
+
+    /// Stops recording transforms and discards the recorded history.
+    pub fn disable_history(&self) {
+        self.data.borrow_mut().history = None;
+    }
+
+    /// Returns a copy of the recorded history, or `None` if it is not enabled.
+    #[must_use]
+    pub fn history(&self) -> Option<FrameHistory> {
+        self.borrow().history.clone()
+    }
+
+    /// Records the transform of this frame relative to its parent at `time`.
+    ///
+    /// If `time` is not older than any recorded sample, the sample also becomes the current
+    /// transform of the frame, as with [`Frame::set`] (including change notifications). Late
+    /// samples only fill in the history.
+    ///
+    /// # Arguments
+    /// - `time`: The time stamp of the transform.
+    /// - `position`: The translational offset from the parent at `time`.
+    /// - `orientation`: The orientation relative to the parent at `time`.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The history is not enabled, see [`Frame::enable_history`].
+    /// - The frame is static, see [`Frame::set_static`].
+    /// - `time` or a component of the transform is NaN or infinite.
+    ///
+    /// Nothing is recorded on error.
+    pub fn update_transform_at(
+        &self,
+        time: f64,
+        position: Vector3<f64>,
+        orientation: impl Into<Rotation>,
+    ) -> Result<(), CartesianTreeError> {
+        if !time.is_finite() {
+            return Err(CartesianTreeError::NonFiniteValue {
+                field: "time".to_string(),
+                value: time,
+            });
+        }
+        let orientation = orientation.into().as_quaternion();
+        check_position("position", &position)?;
+        check_orientation("orientation", &orientation)?;
+        // Checked before recording, as `Frame::set` would reject the sample only afterwards.
+        self.check_dynamic()?;
+
+        let is_latest = {
+            let data = &mut *self.data.borrow_mut();
+            let Some(history) = data.history.as_mut() else {
+                return Err(CartesianTreeError::HistoryNotEnabled {
+                    frame: data.name.to_string(),
+                });
+            };
+            let is_latest = history.range().is_none_or(|(_, latest)| time >= latest);
+            history.insert(
+                time,
+                Isometry3::from_parts(Translation3::from(position), orientation),
+            );
+            is_latest
+        };
+        if is_latest {
+            self.set(position, orientation)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the transform of this frame relative to its parent at `time`.
+    ///
+    /// The transform is interpolated between the two recorded samples around `time`. Frames
+    /// without a history are treated as static and return their current transform.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame is a root.
+    /// - The history is enabled, but has no samples yet or `time` lies outside the recorded
+    ///   range. The latter error reports the available range.
+    pub fn transform_to_parent_at(&self, time: f64) -> Result<Isometry3<f64>, CartesianTreeError> {
+        let data = self.borrow();
+        let Some(history) = &data.history else {
+            return self.transformation();
+        };
+        let (earliest, latest) =
+            history
+                .range()
+                .ok_or_else(|| CartesianTreeError::EmptyHistory {
+                    frame: data.name.to_string(),
+                })?;
+        history
+            .transform_at(time)
+            .ok_or_else(|| CartesianTreeError::TimeOutOfRange {
+                frame: data.name.to_string(),
+                time,
+                earliest,
+                latest,
+            })
+    }
+
+    /// Returns the transform from this frame to its root at `time`.
+    ///
+    /// Composes [`Frame::transform_to_parent_at`] of this frame and all its ancestors, so frames
+    /// with and without a history can be mixed along the chain.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the transform of this frame or of an ancestor is
+    /// not available at `time`.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let robot = root
+    ///     .add_child("robot", Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let tool = robot.add_child("tool", Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// tool.enable_history(100).unwrap();
+    /// tool.update_transform_at(0.0, Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// tool.update_transform_at(2.0, Vector3::new(2.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let world = tool.world_transform_at(1.0).unwrap();
+    /// assert_eq!(world.translation.vector, Vector3::new(1.0, 0.0, 1.0));
+    /// ```
+    pub fn world_transform_at(&self, time: f64) -> Result<Isometry3<f64>, CartesianTreeError> {
+        let mut transform = Isometry3::identity();
+        let mut current = self.clone();
+        while let Some(parent) = current.parent() {
+            transform = current.transform_to_parent_at(time)? * transform;
+            current = parent;
+        }
+        Ok(transform)
+    }
+}
+
+impl Pose {
+    /// Returns the transform from this pose to the root of its frame at `time`, see
+    /// [`Frame::world_transform_at`].
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame of the pose has been dropped.
+    /// - The transform of the frame or of an ancestor is not available at `time`.
+    pub fn world_transform_at(&self, time: f64) -> Result<Isometry3<f64>, CartesianTreeError> {
+        let frame = self.frame()?;
+        Ok(frame.world_transform_at(time)? * self.transformation())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use nalgebra::UnitQuaternion;
+    use std::f64::consts::FRAC_PI_2;
+
+    fn tracked_tool(capacity: impl Into<HistoryCapacity>) -> (Frame, Frame) {
+        let root = Frame::new_origin("world").unwrap();
+        let tool = root
+            .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        tool.enable_history(capacity).unwrap();
+        (root, tool)
+    }
+
+    #[test]
+    fn interpolates_between_samples() {
+        let (_root, tool) = tracked_tool(10);
+        tool.update_transform_at(1.0, Vector3::new(0.0, 0.0, 0.0), Rotation::identity())
+            .unwrap();
+        tool.update_transform_at(
+            3.0,
+            Vector3::new(2.0, -4.0, 0.0),
+            Rotation::from_rpy(0.0, 0.0, FRAC_PI_2),
+        )
+        .unwrap();
+
+        let quarter = tool.transform_to_parent_at(1.5).unwrap();
+        assert_relative_eq!(
+            quarter.translation.vector,
+            Vector3::new(0.5, -1.0, 0.0),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(quarter.rotation.angle(), FRAC_PI_2 / 4.0, epsilon = 1e-12);
+
+        // Exact hits return the sample, and the newest sample is the current transform.
+        let latest = tool.transform_to_parent_at(3.0).unwrap();
+        assert_relative_eq!(latest, tool.transformation().unwrap(), epsilon = 1e-12);
+        assert_eq!(
+            tool.transform_to_parent_at(1.0).unwrap(),
+            Isometry3::identity()
+        );
+
+        assert_eq!(
+            tool.transform_to_parent_at(3.5).unwrap_err().to_string(),
+            "Time 3.5 is outside the recorded history of frame 'tool', which covers [1, 3]"
+        );
+    }
+
+    #[test]
+    fn late_samples_only_fill_in_the_history() {
+        let (_root, tool) = tracked_tool(10);
+        tool.update_transform_at(2.0, Vector3::new(2.0, 0.0, 0.0), Rotation::identity())
+            .unwrap();
+        tool.update_transform_at(0.0, Vector3::new(0.0, 0.0, 0.0), Rotation::identity())
+            .unwrap();
+
+        assert_eq!(tool.position(), Vector3::new(2.0, 0.0, 0.0));
+        assert_relative_eq!(
+            tool.transform_to_parent_at(1.0).unwrap().translation.vector,
+            Vector3::new(1.0, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+
+        // A sample with a known time stamp replaces the recorded one.
+        tool.update_transform_at(2.0, Vector3::new(4.0, 0.0, 0.0), Rotation::identity())
+            .unwrap();
+        assert_eq!(tool.history().unwrap().len(), 2);
+        assert_eq!(tool.position(), Vector3::new(4.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn evicts_old_samples() {
+        let (_root, tool) = tracked_tool(3);
+        for step in 0..5 {
+            let time = f64::from(step);
+            tool.update_transform_at(time, Vector3::new(time, 0.0, 0.0), Rotation::identity())
+                .unwrap();
+        }
+        assert_eq!(tool.history().unwrap().range(), Some((2.0, 4.0)));
+        let error = tool.transform_to_parent_at(1.5).unwrap_err();
+        assert!(matches!(error, CartesianTreeError::TimeOutOfRange { .. }));
+        assert!(error.to_string().ends_with("[2, 4]"));
+
+        tool.enable_history(Duration::from_millis(1500)).unwrap();
+        assert_eq!(tool.history().unwrap().range(), Some((3.0, 4.0)));
+        tool.update_transform_at(5.0, Vector3::zeros(), Rotation::identity())
+            .unwrap();
+        assert_eq!(tool.history().unwrap().range(), Some((4.0, 5.0)));
+    }
+
+    #[test]
+    fn composes_histories_up_the_chain() {
+        let (root, arm) = tracked_tool(10);
+        let fixed = arm
+            .add_child(
+                "fixed",
+                Vector3::new(0.0, 0.0, 1.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let camera = fixed
+            .add_child("camera", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        camera.enable_history(10).unwrap();
+
+        arm.update_transform_at(0.0, Vector3::zeros(), Rotation::identity())
+            .unwrap();
+        arm.update_transform_at(
+            2.0,
+            Vector3::zeros(),
+            Rotation::from_rpy(0.0, 0.0, FRAC_PI_2),
+        )
+        .unwrap();
+        camera
+            .update_transform_at(0.0, Vector3::zeros(), Rotation::identity())
+            .unwrap();
+        camera
+            .update_transform_at(4.0, Vector3::new(4.0, 0.0, 0.0), Rotation::identity())
+            .unwrap();
+
+        let expected = Isometry3::from_parts(
+            Translation3::identity(),
+            UnitQuaternion::from_euler_angles(0.0, 0.0, FRAC_PI_2 / 2.0),
+        ) * Isometry3::translation(1.0, 0.0, 1.0);
+        assert_relative_eq!(
+            camera.world_transform_at(1.0).unwrap(),
+            expected,
+            epsilon = 1e-12
+        );
+
+        let pose = camera
+            .add_pose(Vector3::new(0.0, 1.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        assert_relative_eq!(
+            pose.world_transform_at(1.0).unwrap(),
+            expected * Isometry3::translation(0.0, 1.0, 0.0),
+            epsilon = 1e-12
+        );
+        // The arm history ends before the camera history, so the chain cannot be resolved.
+        assert!(matches!(
+            camera.world_transform_at(3.0),
+            Err(CartesianTreeError::TimeOutOfRange { frame, .. }) if frame == "tool"
+        ));
+        assert_eq!(root.world_transform_at(3.0).unwrap(), Isometry3::identity());
+    }
+
+    #[test]
+    fn rejects_invalid_history_use() {
+        let root = Frame::new_origin("world").unwrap();
+        let tool = root
+            .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert!(matches!(
+            tool.update_transform_at(0.0, Vector3::zeros(), Rotation::identity()),
+            Err(CartesianTreeError::HistoryNotEnabled { .. })
+        ));
+        assert!(matches!(
+            root.enable_history(10),
+            Err(CartesianTreeError::CannotUpdateRootTransform { .. })
+        ));
+        assert!(matches!(
+            tool.enable_history(0),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+
+        tool.enable_history(10).unwrap();
+        assert!(matches!(
+            tool.transform_to_parent_at(0.0),
+            Err(CartesianTreeError::EmptyHistory { .. })
+        ));
+        assert!(matches!(
+            tool.update_transform_at(f64::NAN, Vector3::zeros(), Rotation::identity()),
+            Err(CartesianTreeError::NonFiniteValue { field, .. }) if field == "time"
+        ));
+        assert!(tool.history().unwrap().is_empty());
+
+        tool.set_static(true);
+        assert!(matches!(
+            tool.update_transform_at(1.0, Vector3::x(), Rotation::identity()),
+            Err(CartesianTreeError::StaticFrame { .. })
+        ));
+        assert!(tool.history().unwrap().is_empty());
+        assert_eq!(tool.position(), Vector3::zeros());
+        tool.set_static(false);
+
+        tool.disable_history();
+        assert!(tool.history().is_none());
+        assert_eq!(
+            tool.transform_to_parent_at(42.0).unwrap(),
+            Isometry3::identity()
+        );
+    }
+}
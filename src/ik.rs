@@ -0,0 +1,324 @@
+//! Inverse kinematics of kinematic chains by damped least squares.
+//!
+//! [`KinematicChain::solve_ik`] iterates on a snapshot of the chain, so the tree is not moved;
+//! apply the solution with [`Frame::set_joint_values`](crate::Frame::set_joint_values) or
+//! [`Frame::set_joint_value`](crate::Frame::set_joint_value).
+
+use crate::CartesianTreeError;
+use crate::joint::{JointLimits, KinematicChain};
+use crate::pose::Pose;
+use nalgebra::{DMatrix, DVector, Isometry3, Vector6};
+
+/// Settings of [`KinematicChain::solve_ik`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IkOptions {
+    /// The damping factor λ; larger values trade convergence speed for stability near
+    /// singularities.
+    pub damping: f64,
+    /// The largest number of iterations before giving up.
+    pub max_iterations: usize,
+    /// The largest accepted distance between the tip and the target.
+    pub position_tolerance: f64,
+    /// The largest accepted angle in radians between the tip and the target.
+    pub orientation_tolerance: f64,
+}
+
+impl Default for IkOptions {
+    fn default() -> Self {
+        Self {
+            damping: 0.05,
+            max_iterations: 200,
+            position_tolerance: 1e-6,
+            orientation_tolerance: 1e-6,
+        }
+    }
+}
+
+/// Joint values reaching a target, found by [`KinematicChain::solve_ik`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct IkSolution {
+    /// One value per joint, ordered from base to tip.
+    pub joint_values: Vec<f64>,
+    /// The distance between the tip and the target.
+    pub position_error: f64,
+    /// The angle in radians between the tip and the target.
+    pub orientation_error: f64,
+    /// The number of iterations taken.
+    pub iterations: usize,
+}
+
+impl KinematicChain {
+    /// Finds joint values that move the tip of the chain onto `target`.
+    ///
+    /// Starting from `initial`, each iteration moves the joints by the damped least-squares
+    /// step `(JᵀJ + λ²I)⁻¹ Jᵀ e`, where `J` is the Jacobian in the base frame and `e` the
+    /// position and rotation-vector error of the tip, and then clamps them to their limits.
+    ///
+    /// # Arguments
+    /// - `target`: The pose the tip should reach, in any frame sharing an ancestor with the base
+    ///   of the chain. It is converted to the base with the current transforms of the tree.
+    /// - `initial`: The joint values to start from, one per joint.
+    /// - `options`: The damping, iteration limit and tolerances.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `initial` does not hold one value per joint.
+    /// - The damping is negative or not finite.
+    /// - The frame of `target` has been dropped or shares no ancestor with the base.
+    /// - The tolerances are not met within the iteration limit; the error carries the best
+    ///   attempt.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::ik::IkOptions;
+    /// use cartesian_tree::joint::Joint;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let base = Frame::new_origin("base").unwrap();
+    /// let slide = base
+    ///     .add_child("slide", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// slide.attach_joint(Joint::prismatic(Vector3::x_axis())).unwrap();
+    ///
+    /// let chain = base.chain_to(&slide).unwrap();
+    /// let target = base
+    ///     .add_pose(Vector3::new(0.3, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let solution = chain.solve_ik(&target, &[0.0], IkOptions::default()).unwrap();
+    /// assert!((solution.joint_values[0] - 0.3).abs() < 1e-6);
+    /// ```
+    pub fn solve_ik(
+        &self,
+        target: &Pose,
+        initial: &[f64],
+        options: IkOptions,
+    ) -> Result<IkSolution, CartesianTreeError> {
+        if initial.len() != self.joint_count() {
+            return Err(CartesianTreeError::LengthMismatch {
+                expected: self.joint_count(),
+                found: initial.len(),
+            });
+        }
+        if !(options.damping.is_finite() && options.damping >= 0.0) {
+            return Err(CartesianTreeError::DegenerateInput {
+                reason: format!(
+                    "the damping has to be a non-negative number, got {}",
+                    options.damping
+                ),
+            });
+        }
+        let target = target.in_frame(self.base())?.transformation();
+        let limits: Vec<_> = self
+            .links()
+            .iter()
+            .filter_map(|link| link.joint.as_ref().map(|joint| joint.limits))
+            .collect();
+        let clamp = |values: &mut [f64]| {
+            for (value, limits) in values.iter_mut().zip(&limits) {
+                if let Some(JointLimits { lower, upper }) = limits {
+                    *value = value.clamp(*lower, *upper);
+                }
+            }
+        };
+
+        let mut values = initial.to_vec();
+        clamp(&mut values);
+        let mut best: Option<IkSolution> = None;
+        let mut iterations = 0;
+        for iteration in 0..=options.max_iterations {
+            iterations = iteration;
+            let error = tip_error(&self.forward(&values), &target);
+            let attempt = IkSolution {
+                joint_values: values.clone(),
+                position_error: error.fixed_rows::<3>(0).norm(),
+                orientation_error: error.fixed_rows::<3>(3).norm(),
+                iterations: iteration,
+            };
+            if attempt.position_error <= options.position_tolerance
+                && attempt.orientation_error <= options.orientation_tolerance
+            {
+                return Ok(attempt);
+            }
+            let total =
+                |solution: &IkSolution| solution.position_error + solution.orientation_error;
+            if best
+                .as_ref()
+                .is_none_or(|best| total(&attempt) < total(best))
+            {
+                best = Some(attempt);
+            }
+            if iteration == options.max_iterations {
+                break;
+            }
+
+            let jacobian = self.jacobian(&values);
+            let transposed = jacobian.transpose();
+            let damped = &transposed * &jacobian
+                + DMatrix::identity(values.len(), values.len()) * options.damping.powi(2);
+            let Some(inverse) = damped.try_inverse() else {
+                break;
+            };
+            let step: DVector<f64> = inverse * transposed * error;
+            for (value, delta) in values.iter_mut().zip(step.iter()) {
+                *value += delta;
+            }
+            clamp(&mut values);
+        }
+
+        let best = best.unwrap_or(IkSolution {
+            joint_values: values,
+            position_error: f64::INFINITY,
+            orientation_error: f64::INFINITY,
+            iterations: 0,
+        });
+        Err(CartesianTreeError::IkNotConverged {
+            joint_values: best.joint_values,
+            position_error: best.position_error,
+            orientation_error: best.orientation_error,
+            iterations,
+        })
+    }
+}
+
+/// Returns the position and rotation-vector error from `tip` to `target`, in the base frame.
+fn tip_error(tip: &Isometry3<f64>, target: &Isometry3<f64>) -> Vector6<f64> {
+    let position = target.translation.vector - tip.translation.vector;
+    let rotation = (target.rotation * tip.rotation.inverse()).scaled_axis();
+    Vector6::new(
+        position.x, position.y, position.z, rotation.x, rotation.y, rotation.z,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Frame;
+    use crate::joint::Joint;
+    use approx::assert_relative_eq;
+    use nalgebra::{UnitQuaternion, Vector3};
+
+    /// A planar arm with two revolute joints about z, links of length 1.0 and 0.5 and the
+    /// elbow limited to `[0, 2.5]`.
+    fn planar_arm() -> (Frame, KinematicChain) {
+        let base = Frame::new_origin("base").unwrap();
+        let upper = base
+            .add_child("upper", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        upper
+            .attach_joint(Joint::revolute(Vector3::z_axis()).with_limits(-3.0, 3.0))
+            .unwrap();
+        let lower = upper
+            .add_child(
+                "lower",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        lower
+            .attach_joint(Joint::revolute(Vector3::z_axis()).with_limits(0.0, 2.5))
+            .unwrap();
+        let tool = lower
+            .add_child(
+                "tool",
+                Vector3::new(0.5, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let chain = base.chain_to(&tool).unwrap();
+        (base, chain)
+    }
+
+    /// Returns the analytic joint values of the planar arm reaching `(x, y)` with the elbow
+    /// bent towards positive angles.
+    fn analytic_solution(x: f64, y: f64) -> [f64; 2] {
+        // Law of cosines: `x² + y² = 1.0² + 0.5² + 2 · 1.0 · 0.5 · cos(elbow)`.
+        let elbow = x.mul_add(x, y.mul_add(y, -1.25)).acos();
+        let shoulder = y.atan2(x) - (0.5 * elbow.sin()).atan2(0.5f64.mul_add(elbow.cos(), 1.0));
+        [shoulder, elbow]
+    }
+
+    #[test]
+    fn solves_planar_arm_analytically() {
+        let (base, chain) = planar_arm();
+        // The target is attached to a frame away from the base of the chain.
+        let table = base
+            .add_child(
+                "table",
+                Vector3::new(0.5, 0.5, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, 0.3),
+            )
+            .unwrap();
+        for (x, y) in [(0.8, 0.6), (-0.2, 1.1), (1.2, -0.3)] {
+            let expected = analytic_solution(x, y);
+            let in_base = base
+                .add_pose(
+                    Vector3::new(x, y, 0.0),
+                    UnitQuaternion::from_euler_angles(0.0, 0.0, expected[0] + expected[1]),
+                )
+                .unwrap();
+            let target = in_base.in_frame(&table).unwrap();
+
+            let solution = chain
+                .solve_ik(&target, &[0.1, 0.5], IkOptions::default())
+                .unwrap();
+
+            assert_relative_eq!(solution.joint_values[0], expected[0], epsilon = 1e-5);
+            assert_relative_eq!(solution.joint_values[1], expected[1], epsilon = 1e-5);
+            assert!(solution.position_error <= 1e-6);
+            assert!(solution.orientation_error <= 1e-6);
+            assert!(solution.iterations > 0);
+        }
+    }
+
+    #[test]
+    fn unreachable_targets_report_the_best_attempt() {
+        let (base, chain) = planar_arm();
+        // Beyond the reach of 1.5 along x; the best attempt stretches the arm.
+        let target = base
+            .add_pose(Vector3::new(2.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        let options = IkOptions {
+            max_iterations: 50,
+            ..IkOptions::default()
+        };
+        match chain.solve_ik(&target, &[0.4, 0.8], options) {
+            Err(CartesianTreeError::IkNotConverged {
+                joint_values,
+                position_error,
+                iterations,
+                ..
+            }) => {
+                assert_eq!(iterations, 50);
+                assert_relative_eq!(position_error, 0.5, epsilon = 1e-3);
+                assert_relative_eq!(joint_values[0], 0.0, epsilon = 1e-2);
+                assert!((0.0..=2.5).contains(&joint_values[1]));
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        // The elbow limit excludes the solution bending the other way.
+        let [shoulder, elbow] = analytic_solution(0.8, 0.6);
+        let mirrored_shoulder = 2.0f64.mul_add(0.6f64.atan2(0.8), -shoulder);
+        let mirrored = base
+            .add_pose(
+                Vector3::new(0.8, 0.6, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, mirrored_shoulder - elbow),
+            )
+            .unwrap();
+        match chain.solve_ik(&mirrored, &[mirrored_shoulder, -elbow], options) {
+            Err(CartesianTreeError::IkNotConverged { joint_values, .. }) => {
+                assert!((0.0..=2.5).contains(&joint_values[1]));
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        assert!(matches!(
+            chain.solve_ik(&target, &[0.0], options),
+            Err(CartesianTreeError::LengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        ));
+    }
+}
@@ -0,0 +1,100 @@
+//! Interning of frame names.
+//!
+//! Trees imported from URDF files or configurations repeat the same names many times, e.g. a
+//! `base_link` in every robot. Frames therefore keep their names as shared `Rc<str>`s taken
+//! from a per-thread pool, so each distinct name is stored once and equal names share one
+//! allocation. Frames cannot leave their thread, so neither can their names.
+
+use alloc::rc::Rc;
+#[cfg(feature = "std")]
+use core::cell::RefCell;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+/// The number of names below which the pool is never pruned.
+#[cfg(feature = "std")]
+const MIN_PRUNE_LEN: usize = 64;
+
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct Pool {
+    names: HashSet<Rc<str>>,
+    /// The size at which names no longer used by any frame are dropped from the pool.
+    prune_len: usize,
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    static POOL: RefCell<Pool> = RefCell::new(Pool::default());
+}
+
+/// Returns the shared copy of `name`, adding it to the pool if it is not there yet.
+#[cfg(feature = "std")]
+pub fn intern(name: &str) -> Rc<str> {
+    POOL.with_borrow_mut(|pool| {
+        if let Some(interned) = pool.names.get(name) {
+            return interned.clone();
+        }
+        // Pruning only when the pool doubled keeps interning O(1) amortized.
+        if pool.names.len() >= pool.prune_len {
+            pool.names.retain(|name| Rc::strong_count(name) > 1);
+            pool.prune_len = (2 * pool.names.len()).max(MIN_PRUNE_LEN);
+        }
+        let interned: Rc<str> = Rc::from(name);
+        pool.names.insert(interned.clone());
+        interned
+    })
+}
+
+/// Returns the shared copy of `name` without adding it, or `None` if no frame on this thread
+/// has (recently had) that name.
+#[cfg(feature = "std")]
+pub fn lookup(name: &str) -> Option<Rc<str>> {
+    POOL.with_borrow(|pool| pool.names.get(name).cloned())
+}
+
+/// Without `std` there are no thread-locals to keep a pool in, so every name is its own copy.
+#[cfg(not(feature = "std"))]
+pub fn intern(name: &str) -> Rc<str> {
+    Rc::from(name)
+}
+
+/// Without a pool, any name may belong to a frame.
+#[cfg(not(feature = "std"))]
+#[allow(clippy::unnecessary_wraps)]
+pub fn lookup(name: &str) -> Option<Rc<str>> {
+    Some(Rc::from(name))
+}
+
+/// Returns whether two names are equal, comparing the pointers of interned names first.
+pub fn same_name(a: &Rc<str>, b: &Rc<str>) -> bool {
+    Rc::ptr_eq(a, b) || a == b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_names_share_one_allocation() {
+        let a = intern("base_link");
+        let b = intern(&String::from("base_link"));
+        assert!(Rc::ptr_eq(&a, &b));
+        assert!(same_name(&a, &b));
+        assert!(!same_name(&a, &intern("tool0")));
+        assert!(same_name(&a, &Rc::from("base_link")));
+        assert!(lookup("base_link").is_some_and(|name| Rc::ptr_eq(&name, &a)));
+        assert!(lookup("never interned").is_none());
+    }
+
+    #[test]
+    fn unused_names_are_pruned() {
+        let kept = intern("kept");
+        for i in 0..4 * MIN_PRUNE_LEN {
+            intern(&format!("temporary{i}"));
+        }
+        let size = POOL.with_borrow(|pool| pool.names.len());
+        assert!(size <= 2 * MIN_PRUNE_LEN, "{size}");
+        assert!(lookup("kept").is_some_and(|name| Rc::ptr_eq(&name, &kept)));
+    }
+}
@@ -0,0 +1,899 @@
+//! Joints that articulate frames, e.g. the links of a robot arm.
+//!
+//! A [`Joint`] attached to a frame with [`Frame::attach_joint`] turns its transform to the parent
+//! into a function of a single joint value: the transform at the time of attaching becomes the
+//! joint origin, and [`Frame::set_joint_value`] sets the transform to the origin followed by a
+//! rotation about (revolute) or a translation along (prismatic) the joint axis. The axis is
+//! expressed in the frame itself, as in URDF.
+//!
+//! Joints move frames with [`Frame::set`], so world transforms, pose conversions, histories and
+//! subscriptions all follow the joint values. Setting the transform of a jointed frame directly
+//! does not move its origin; the next joint value overrides it. Joints are not serialized.
+//!
+//! For evaluating many candidate joint values, e.g. in a solver loop, [`Frame::chain_to`]
+//! extracts a [`KinematicChain`] that computes forward kinematics without touching the tree.
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::tree::{HasParent, NodeEquality};
+use alloc::{format, string::ToString, vec::Vec};
+use nalgebra::{Isometry3, Matrix6xX, Translation3, Unit, UnitQuaternion, Vector3};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// How a joint moves its frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JointType {
+    /// Rotates about the axis by the joint value in radians.
+    Revolute,
+    /// Translates along the axis by the joint value.
+    Prismatic,
+}
+
+/// The range of values a joint may take, bounds included.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JointLimits {
+    /// The smallest allowed value.
+    pub lower: f64,
+    /// The largest allowed value.
+    pub upper: f64,
+}
+
+/// A single degree of freedom of a frame relative to its parent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Joint {
+    /// How the joint moves its frame.
+    pub kind: JointType,
+    /// The axis of the motion, in the coordinates of the jointed frame.
+    pub axis: Unit<Vector3<f64>>,
+    /// The allowed values, `None` for an unlimited (e.g. continuous) joint.
+    pub limits: Option<JointLimits>,
+    /// The current value, in radians for revolute joints.
+    pub value: f64,
+}
+
+impl Joint {
+    /// Creates an unlimited revolute joint at value zero.
+    #[must_use]
+    pub const fn revolute(axis: Unit<Vector3<f64>>) -> Self {
+        Self {
+            kind: JointType::Revolute,
+            axis,
+            limits: None,
+            value: 0.0,
+        }
+    }
+
+    /// Creates an unlimited prismatic joint at value zero.
+    #[must_use]
+    pub const fn prismatic(axis: Unit<Vector3<f64>>) -> Self {
+        Self {
+            kind: JointType::Prismatic,
+            axis,
+            limits: None,
+            value: 0.0,
+        }
+    }
+
+    /// Restricts the joint to values between `lower` and `upper`.
+    #[must_use]
+    pub const fn with_limits(mut self, lower: f64, upper: f64) -> Self {
+        self.limits = Some(JointLimits { lower, upper });
+        self
+    }
+
+    /// Returns the motion of the joint at `value`, relative to its origin.
+    #[must_use]
+    pub fn motion(&self, value: f64) -> Isometry3<f64> {
+        match self.kind {
+            JointType::Revolute => Isometry3::from_parts(
+                Translation3::identity(),
+                UnitQuaternion::from_axis_angle(&self.axis, value),
+            ),
+            JointType::Prismatic => Isometry3::from_parts(
+                Translation3::from(self.axis.into_inner() * value),
+                UnitQuaternion::identity(),
+            ),
+        }
+    }
+
+    /// Checks that `value` is finite and within the limits of the joint of `frame`.
+    fn check(&self, frame: &Frame, value: f64) -> Result<(), CartesianTreeError> {
+        if !value.is_finite() {
+            return Err(CartesianTreeError::NonFiniteValue {
+                field: "joint value".to_string(),
+                value,
+            });
+        }
+        match self.limits {
+            Some(JointLimits { lower, upper }) if !(lower..=upper).contains(&value) => {
+                Err(CartesianTreeError::JointLimitExceeded {
+                    frame: frame.name(),
+                    value,
+                    lower,
+                    upper,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// One frame of a [`KinematicChain`], as captured by [`Frame::chain_to`].
+#[derive(Clone, Debug)]
+pub struct ChainLink {
+    /// The frame of the link.
+    pub frame: Frame,
+    /// The transform of the frame to the previous link at joint value zero; for frames without
+    /// a joint, their fixed transform to the parent.
+    pub origin: Isometry3<f64>,
+    /// The joint of the frame, with the value it had when the chain was captured.
+    pub joint: Option<Joint>,
+}
+
+/// The frame a Jacobian of [`KinematicChain::jacobian_in`] is expressed in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JacobianFrame {
+    /// The base frame of the chain.
+    #[default]
+    Base,
+    /// The tip frame of the chain, at the given joint values.
+    Tip,
+}
+
+/// A snapshot of the frames between a base frame and one of its descendants.
+///
+/// The chain keeps the transforms and joints the frames had when it was captured, so it can
+/// evaluate forward kinematics for any joint values without reading or changing the tree.
+#[derive(Clone, Debug)]
+pub struct KinematicChain {
+    base: Frame,
+    links: Vec<ChainLink>,
+}
+
+impl KinematicChain {
+    /// Returns the frame the chain starts at.
+    #[must_use]
+    pub const fn base(&self) -> &Frame {
+        &self.base
+    }
+
+    /// Returns the links from the child of the base down to the tip.
+    #[must_use]
+    pub fn links(&self) -> &[ChainLink] {
+        &self.links
+    }
+
+    /// Returns the number of joints along the chain.
+    #[must_use]
+    pub fn joint_count(&self) -> usize {
+        self.links
+            .iter()
+            .filter(|link| link.joint.is_some())
+            .count()
+    }
+
+    /// Returns the joint values captured with the chain, ordered from base to tip.
+    #[must_use]
+    pub fn joint_values(&self) -> Vec<f64> {
+        self.links
+            .iter()
+            .filter_map(|link| link.joint.as_ref().map(|joint| joint.value))
+            .collect()
+    }
+
+    /// Returns the geometric Jacobian of the tip, expressed in the base frame.
+    ///
+    /// See [`KinematicChain::jacobian_in`].
+    ///
+    /// # Panics
+    /// Panics if the number of values differs from [`KinematicChain::joint_count`].
+    #[must_use]
+    pub fn jacobian(&self, joint_values: &[f64]) -> Matrix6xX<f64> {
+        self.jacobian_in(joint_values, JacobianFrame::Base)
+    }
+
+    /// Returns the geometric Jacobian of the tip for the given joint values.
+    ///
+    /// Column `i` maps the rate of joint `i` (ordered from base to tip) to the velocity of the
+    /// tip: the linear velocity of its origin in rows 0 to 2 and its angular velocity in rows 3
+    /// to 5, both expressed in `frame`. The columns are computed from the joint axes and link
+    /// transforms, without finite differences.
+    ///
+    /// # Panics
+    /// Panics if the number of values differs from [`KinematicChain::joint_count`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::joint::Joint;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let base = Frame::new_origin("base").unwrap();
+    /// let arm = base
+    ///     .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// arm.attach_joint(Joint::revolute(Vector3::z_axis())).unwrap();
+    /// let tool = arm
+    ///     .add_child("tool", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// // Turning the arm moves the tool along y and turns it about z.
+    /// let jacobian = base.chain_to(&tool).unwrap().jacobian(&[0.0]);
+    /// assert_eq!(jacobian.column(0).as_slice(), [0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+    /// ```
+    #[must_use]
+    pub fn jacobian_in(&self, joint_values: &[f64], frame: JacobianFrame) -> Matrix6xX<f64> {
+        assert_eq!(
+            joint_values.len(),
+            self.joint_count(),
+            "expected one value per joint of the chain"
+        );
+        // Joint axes and positions in the base frame, collected while walking to the tip.
+        let mut axes = Vec::with_capacity(joint_values.len());
+        let mut values = joint_values.iter();
+        let mut transform = Isometry3::identity();
+        for link in &self.links {
+            transform *= link.origin;
+            if let Some(joint) = &link.joint {
+                let value = values.next().copied().unwrap_or_default();
+                axes.push((
+                    joint.kind,
+                    transform.rotation * joint.axis.into_inner(),
+                    transform.translation.vector,
+                ));
+                transform *= joint.motion(value);
+            }
+        }
+
+        let tip = transform.translation.vector;
+        let to_frame = match frame {
+            JacobianFrame::Base => UnitQuaternion::identity(),
+            JacobianFrame::Tip => transform.rotation.inverse(),
+        };
+        let mut jacobian = Matrix6xX::zeros(axes.len());
+        for (mut column, (kind, axis, position)) in jacobian.column_iter_mut().zip(axes) {
+            let (linear, angular) = match kind {
+                JointType::Revolute => (axis.cross(&(tip - position)), axis),
+                JointType::Prismatic => (axis, Vector3::zeros()),
+            };
+            column
+                .fixed_rows_mut::<3>(0)
+                .copy_from(&(to_frame * linear));
+            column
+                .fixed_rows_mut::<3>(3)
+                .copy_from(&(to_frame * angular));
+        }
+        jacobian
+    }
+
+    /// Returns the transform of the tip to the base for the given joint values.
+    ///
+    /// `joint_values` holds one value per joint, ordered from base to tip. The values are not
+    /// checked against the joint limits.
+    ///
+    /// # Panics
+    /// Panics if the number of values differs from [`KinematicChain::joint_count`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::joint::Joint;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let base = Frame::new_origin("base").unwrap();
+    /// let arm = base
+    ///     .add_child("arm", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// arm.attach_joint(Joint::revolute(Vector3::z_axis())).unwrap();
+    /// let tool = arm
+    ///     .add_child("tool", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let chain = base.chain_to(&tool).unwrap();
+    /// let tip = chain.forward(&[std::f64::consts::FRAC_PI_2]);
+    /// assert!((tip.translation.vector - Vector3::new(0.0, 1.0, 0.0)).norm() < 1e-12);
+    /// // The tree itself has not moved.
+    /// assert_eq!(arm.joint().unwrap().value, 0.0);
+    /// ```
+    #[must_use]
+    pub fn forward(&self, joint_values: &[f64]) -> Isometry3<f64> {
+        assert_eq!(
+            joint_values.len(),
+            self.joint_count(),
+            "expected one value per joint of the chain"
+        );
+        let mut values = joint_values.iter();
+        self.links
+            .iter()
+            .fold(Isometry3::identity(), |transform, link| {
+                let motion = link
+                    .joint
+                    .as_ref()
+                    .map_or_else(Isometry3::identity, |joint| {
+                        joint.motion(values.next().copied().unwrap_or_default())
+                    });
+                transform * link.origin * motion
+            })
+    }
+}
+
+impl Frame {
+    /// Makes this frame movable by `joint`.
+    ///
+    /// The current transform to the parent becomes the joint origin, i.e. the transform at
+    /// joint value zero, and the frame is then moved to the value of `joint`. An existing joint
+    /// of the frame is replaced, keeping its origin.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame is a root, which cannot move.
+    /// - The limits are not finite or the lower limit exceeds the upper one.
+    /// - The value of `joint` is not finite or outside its limits.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::joint::Joint;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("base").unwrap();
+    /// let arm = root
+    ///     .add_child("arm", Vector3::new(0.0, 0.0, 0.5), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// arm.attach_joint(Joint::revolute(Vector3::z_axis()).with_limits(-1.0, 1.0))
+    ///     .unwrap();
+    ///
+    /// arm.set_joint_value(0.5).unwrap();
+    /// assert!((arm.orientation().as_rpy().z - 0.5).abs() < 1e-12);
+    /// assert!(arm.set_joint_value(2.0).is_err());
+    /// ```
+    pub fn attach_joint(&self, joint: Joint) -> Result<(), CartesianTreeError> {
+        if self.parent().is_none() {
+            return Err(CartesianTreeError::CannotUpdateRootTransform { frame: self.name() });
+        }
+        if let Some(JointLimits { lower, upper }) = joint.limits
+            && !(lower.is_finite() && upper.is_finite() && lower <= upper)
+        {
+            return Err(CartesianTreeError::DegenerateInput {
+                reason: format!("invalid joint limits [{lower}, {upper}]"),
+            });
+        }
+        joint.check(self, joint.value)?;
+
+        let origin = {
+            let data = self.borrow();
+            data.joint
+                .as_ref()
+                .map_or(data.transform_to_parent, |(_, origin)| *origin)
+        };
+        let value = joint.value;
+        self.data.borrow_mut().joint = Some((joint, origin));
+        self.move_joint(value)
+    }
+
+    /// Removes the joint of this frame, keeping its current transform.
+    pub fn detach_joint(&self) {
+        self.data.borrow_mut().joint = None;
+    }
+
+    /// Returns the joint of this frame with its current value, or `None` if the frame has none.
+    #[must_use]
+    pub fn joint(&self) -> Option<Joint> {
+        self.borrow().joint.as_ref().map(|(joint, _)| joint.clone())
+    }
+
+    /// Moves the joint of this frame to `value`.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame has no joint.
+    /// - `value` is not finite or outside the limits of the joint.
+    pub fn set_joint_value(&self, value: f64) -> Result<(), CartesianTreeError> {
+        let joint = self
+            .joint()
+            .ok_or_else(|| CartesianTreeError::NoJoint { frame: self.name() })?;
+        joint.check(self, value)?;
+        self.move_joint(value)
+    }
+
+    /// Moves several joints of the tree below this frame at once.
+    ///
+    /// `values` maps the names of jointed frames (this frame or its descendants) to their new
+    /// values; frames not listed keep their values. After URDF import, these are the names of
+    /// the child links. All values are checked before any joint moves, so on error the tree is
+    /// unchanged.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - A name does not belong to this frame or a descendant.
+    /// - A named frame has no joint.
+    /// - A value is not finite or outside the limits of its joint.
+    #[cfg(feature = "std")]
+    pub fn set_joint_values(
+        &self,
+        values: &HashMap<String, f64>,
+    ) -> Result<(), CartesianTreeError> {
+        let mut moves = Vec::with_capacity(values.len());
+        for frame in self.iter_dfs() {
+            let name = frame.name();
+            if let Some(&value) = values.get(&name) {
+                if moves
+                    .iter()
+                    .any(|(moved, _): &(Self, f64)| moved.name() == name)
+                {
+                    continue;
+                }
+                let joint = frame.joint().ok_or_else(|| CartesianTreeError::NoJoint {
+                    frame: name.clone(),
+                })?;
+                joint.check(&frame, value)?;
+                moves.push((frame, value));
+            }
+        }
+        if moves.len() < values.len() {
+            let mut missing: Vec<_> = values
+                .keys()
+                .filter(|name| moves.iter().all(|(frame, _)| frame.name() != **name))
+                .cloned()
+                .collect();
+            missing.sort();
+            return Err(CartesianTreeError::MissingFrames { paths: missing });
+        }
+        for (frame, value) in moves {
+            frame.move_joint(value)?;
+        }
+        Ok(())
+    }
+
+    /// Captures the frames from this frame down to `tip` as a [`KinematicChain`].
+    ///
+    /// The chain starts below this frame and ends with `tip`; it is empty if `tip` is this frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `tip` is neither this frame nor one of its descendants.
+    /// - A frame along the chain is scaled, so the chain is no rigid transform.
+    pub fn chain_to(&self, tip: &Self) -> Result<KinematicChain, CartesianTreeError> {
+        let mut frames = Vec::new();
+        let mut current = tip.clone();
+        while !current.is_same(self) {
+            let parent = current
+                .parent()
+                .ok_or_else(|| CartesianTreeError::IsNoAncestor {
+                    ancestor: self.name(),
+                    frame: tip.name(),
+                })?;
+            if parent.borrow().scale.is_some() {
+                return Err(CartesianTreeError::ScaledTransform {
+                    from: tip.name(),
+                    to: self.name(),
+                });
+            }
+            frames.push(current);
+            current = parent;
+        }
+        frames.reverse();
+
+        let links = frames
+            .into_iter()
+            .map(|frame| {
+                let (origin, joint) = {
+                    let data = frame.borrow();
+                    data.joint
+                        .as_ref()
+                        .map_or((data.transform_to_parent, None), |(joint, origin)| {
+                            (*origin, Some(joint.clone()))
+                        })
+                };
+                ChainLink {
+                    frame,
+                    origin,
+                    joint,
+                }
+            })
+            .collect();
+        Ok(KinematicChain {
+            base: self.clone(),
+            links,
+        })
+    }
+
+    /// Sets the transform of this jointed frame to its origin moved by the joint at `value`.
+    fn move_joint(&self, value: f64) -> Result<(), CartesianTreeError> {
+        let transform = {
+            let mut data = self.data.borrow_mut();
+            let Some((joint, origin)) = data.joint.as_mut() else {
+                return Err(CartesianTreeError::NoJoint {
+                    frame: data.name.to_string(),
+                });
+            };
+            joint.value = value;
+            *origin * joint.motion(value)
+        };
+        self.set(transform.translation.vector, transform.rotation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use nalgebra::Point3;
+    use std::f64::consts::FRAC_PI_2;
+
+    /// A planar arm with two revolute joints about z and links of length 1.0 and 0.5.
+    fn planar_arm() -> (Frame, Frame, Frame, Frame) {
+        let base = Frame::new_origin("base").unwrap();
+        let upper = base
+            .add_child("upper", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let lower = upper
+            .add_child(
+                "lower",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let tool = lower
+            .add_child(
+                "tool",
+                Vector3::new(0.5, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        upper
+            .attach_joint(Joint::revolute(Vector3::z_axis()).with_limits(-3.0, 3.0))
+            .unwrap();
+        lower
+            .attach_joint(Joint::revolute(Vector3::z_axis()).with_limits(-2.5, 2.5))
+            .unwrap();
+        (base, upper, lower, tool)
+    }
+
+    #[test]
+    fn planar_arm_matches_analytic_forward_kinematics() {
+        let (base, _, _, tool) = planar_arm();
+        let tool_point = tool
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        for (q1, q2) in [(0.0, 0.0), (0.3, -1.2), (-2.0, 2.4), (FRAC_PI_2, FRAC_PI_2)] {
+            let values = HashMap::from([("upper".to_string(), q1), ("lower".to_string(), q2)]);
+            base.set_joint_values(&values).unwrap();
+
+            let expected = Vector3::new(
+                0.5f64.mul_add((q1 + q2).cos(), q1.cos()),
+                0.5f64.mul_add((q1 + q2).sin(), q1.sin()),
+                0.0,
+            );
+            let world = tool.world_transform().unwrap();
+            assert_relative_eq!(world.translation.vector, expected, epsilon = 1e-12);
+            assert_relative_eq!(
+                world
+                    .rotation
+                    .angle_to(&UnitQuaternion::from_euler_angles(0.0, 0.0, q1 + q2)),
+                0.0,
+                epsilon = 1e-12
+            );
+            let in_base = tool_point.in_frame(&base).unwrap().transformation();
+            assert_relative_eq!(in_base.translation.vector, expected, epsilon = 1e-12);
+            assert_relative_eq!(
+                tool.transform_point_to(&base, &Point3::origin())
+                    .unwrap()
+                    .coords,
+                expected,
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn prismatic_joints_translate_along_their_axis() {
+        let root = Frame::new_origin("world").unwrap();
+        let slide = root
+            .add_child(
+                "slide",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, FRAC_PI_2),
+            )
+            .unwrap();
+        let mut joint = Joint::prismatic(Vector3::x_axis()).with_limits(0.0, 0.4);
+        joint.value = 0.25;
+        slide.attach_joint(joint).unwrap();
+
+        // The axis is expressed in the slide itself, which is turned about z.
+        assert_relative_eq!(
+            slide.position(),
+            Vector3::new(1.0, 0.25, 0.0),
+            epsilon = 1e-12
+        );
+        slide.set_joint_value(0.0).unwrap();
+        assert_relative_eq!(
+            slide.position(),
+            Vector3::new(1.0, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(slide.joint().unwrap().value, 0.0);
+
+        // Replacing the joint keeps the origin.
+        slide
+            .attach_joint(Joint::prismatic(Vector3::y_axis()))
+            .unwrap();
+        slide.set_joint_value(2.0).unwrap();
+        assert_relative_eq!(
+            slide.position(),
+            Vector3::new(-1.0, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+        slide.detach_joint();
+        assert!(slide.joint().is_none());
+        assert_relative_eq!(
+            slide.position(),
+            Vector3::new(-1.0, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn invalid_joint_values_are_rejected() {
+        let (base, upper, lower, _) = planar_arm();
+        match lower.set_joint_value(2.6) {
+            Err(CartesianTreeError::JointLimitExceeded {
+                frame,
+                value,
+                lower,
+                upper,
+            }) => {
+                assert_eq!(frame, "lower");
+                assert_relative_eq!(value, 2.6);
+                assert_relative_eq!(lower, -2.5);
+                assert_relative_eq!(upper, 2.5);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+        assert!(matches!(
+            upper.set_joint_value(f64::NAN),
+            Err(CartesianTreeError::NonFiniteValue { .. })
+        ));
+        assert!(matches!(
+            base.set_joint_value(0.0),
+            Err(CartesianTreeError::NoJoint { .. })
+        ));
+        assert!(matches!(
+            base.attach_joint(Joint::revolute(Vector3::z_axis())),
+            Err(CartesianTreeError::CannotUpdateRootTransform { .. })
+        ));
+        assert!(matches!(
+            upper.attach_joint(Joint::revolute(Vector3::z_axis()).with_limits(1.0, -1.0)),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+
+        // Nothing moves if one of several values is invalid.
+        let values = HashMap::from([("upper".to_string(), 1.0), ("lower".to_string(), 3.0)]);
+        assert!(matches!(
+            base.set_joint_values(&values),
+            Err(CartesianTreeError::JointLimitExceeded { .. })
+        ));
+        let values = HashMap::from([("upper".to_string(), 1.0), ("elbow".to_string(), 0.0)]);
+        match base.set_joint_values(&values) {
+            Err(CartesianTreeError::MissingFrames { paths }) => assert_eq!(paths, ["elbow"]),
+            other => panic!("unexpected result: {other:?}"),
+        }
+        let values = HashMap::from([("upper".to_string(), 1.0), ("tool".to_string(), 0.0)]);
+        assert!(matches!(
+            base.set_joint_values(&values),
+            Err(CartesianTreeError::NoJoint { frame }) if frame == "tool"
+        ));
+        assert_relative_eq!(upper.joint().unwrap().value, 0.0);
+        assert_relative_eq!(upper.orientation().as_rpy().z, 0.0);
+    }
+
+    #[test]
+    fn chain_forward_matches_articulated_tree() {
+        let (base, upper, lower, tool) = planar_arm();
+        let wrist = tool
+            .add_child(
+                "wrist",
+                Vector3::new(0.0, 0.0, 0.2),
+                UnitQuaternion::from_euler_angles(0.0, FRAC_PI_2, 0.0),
+            )
+            .unwrap();
+        wrist
+            .attach_joint(Joint::prismatic(Vector3::y_axis()))
+            .unwrap();
+        let flange = wrist
+            .add_child(
+                "flange",
+                Vector3::new(0.1, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+
+        let chain = base.chain_to(&flange).unwrap();
+        let names: Vec<_> = chain.links().iter().map(|link| link.frame.name()).collect();
+        assert_eq!(names, ["upper", "lower", "tool", "wrist", "flange"]);
+        assert_eq!(chain.joint_count(), 3);
+        assert_eq!(chain.joint_values(), [0.0, 0.0, 0.0]);
+
+        for values in [[0.4, -0.7, 0.05], [-2.0, 1.5, -0.3], [3.0, 0.0, 1.0]] {
+            let forward = chain.forward(&values);
+            // Evaluating the chain leaves the tree untouched.
+            assert_relative_eq!(upper.joint().unwrap().value, 0.0);
+
+            let joint_values = HashMap::from([
+                ("upper".to_string(), values[0]),
+                ("lower".to_string(), values[1]),
+                ("wrist".to_string(), values[2]),
+            ]);
+            base.set_joint_values(&joint_values).unwrap();
+            assert_relative_eq!(
+                forward,
+                flange.transform_to(&base).unwrap(),
+                epsilon = 1e-12
+            );
+            for frame in [&upper, &lower, &wrist] {
+                frame.set_joint_value(0.0).unwrap();
+            }
+        }
+
+        // Chains may start below the root and end at their base.
+        let partial = lower.chain_to(&flange).unwrap();
+        assert_eq!(partial.joint_count(), 1);
+        assert_relative_eq!(
+            partial.forward(&[0.2]),
+            {
+                wrist.set_joint_value(0.2).unwrap();
+                flange.transform_to(&lower).unwrap()
+            },
+            epsilon = 1e-12
+        );
+        let empty = flange.chain_to(&flange).unwrap();
+        assert!(empty.links().is_empty());
+        assert_eq!(empty.forward(&[]), Isometry3::identity());
+    }
+
+    #[test]
+    fn chain_requires_a_rigid_descendant() {
+        let (base, upper, _, tool) = planar_arm();
+        assert!(matches!(
+            tool.chain_to(&upper),
+            Err(CartesianTreeError::IsNoAncestor { .. })
+        ));
+        let other = Frame::new_origin("other").unwrap();
+        assert!(matches!(
+            base.chain_to(&other),
+            Err(CartesianTreeError::IsNoAncestor { .. })
+        ));
+
+        let drawing = base
+            .add_scaled_child(
+                "drawing",
+                Vector3::zeros(),
+                UnitQuaternion::identity(),
+                0.001,
+            )
+            .unwrap();
+        let hole = drawing
+            .add_child(
+                "hole",
+                Vector3::new(5.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        assert!(matches!(
+            base.chain_to(&hole),
+            Err(CartesianTreeError::ScaledTransform { .. })
+        ));
+        // The scale of the tip itself only applies below it.
+        assert!(base.chain_to(&drawing).is_ok());
+    }
+
+    /// Differentiates the forward kinematics of `chain` numerically, as a reference for the
+    /// analytic Jacobian.
+    fn finite_difference_jacobian(
+        chain: &KinematicChain,
+        values: &[f64],
+        frame: JacobianFrame,
+    ) -> Matrix6xX<f64> {
+        let step = 1e-6;
+        let mut jacobian = Matrix6xX::zeros(values.len());
+        for (index, mut column) in jacobian.column_iter_mut().enumerate() {
+            let mut shifted = values.to_vec();
+            shifted[index] += step;
+            let after = chain.forward(&shifted);
+            shifted[index] -= 2.0 * step;
+            let before = chain.forward(&shifted);
+            let (linear, angular) = match frame {
+                JacobianFrame::Base => (
+                    after.translation.vector - before.translation.vector,
+                    (after.rotation * before.rotation.inverse()).scaled_axis(),
+                ),
+                JacobianFrame::Tip => {
+                    let to_tip = chain.forward(values).rotation.inverse();
+                    (
+                        to_tip * (after.translation.vector - before.translation.vector),
+                        (before.rotation.inverse() * after.rotation).scaled_axis(),
+                    )
+                }
+            };
+            column
+                .fixed_rows_mut::<3>(0)
+                .copy_from(&(linear / (2.0 * step)));
+            column
+                .fixed_rows_mut::<3>(3)
+                .copy_from(&(angular / (2.0 * step)));
+        }
+        jacobian
+    }
+
+    #[test]
+    fn jacobian_matches_finite_differences() {
+        let base = Frame::new_origin("base").unwrap();
+        let shoulder = base
+            .add_child(
+                "shoulder",
+                Vector3::new(0.0, 0.0, 0.4),
+                UnitQuaternion::from_euler_angles(0.1, 0.0, 0.3),
+            )
+            .unwrap();
+        shoulder
+            .attach_joint(Joint::revolute(Vector3::z_axis()))
+            .unwrap();
+        let slide = shoulder
+            .add_child(
+                "slide",
+                Vector3::new(0.5, 0.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, -0.6, 0.0),
+            )
+            .unwrap();
+        slide
+            .attach_joint(Joint::prismatic(Unit::new_normalize(Vector3::new(
+                1.0, 1.0, 0.0,
+            ))))
+            .unwrap();
+        // Frames without joints contribute no columns.
+        let offset = slide
+            .add_child(
+                "offset",
+                Vector3::new(0.0, 0.2, 0.1),
+                UnitQuaternion::from_euler_angles(0.4, 0.2, 0.0),
+            )
+            .unwrap();
+        let wrist = offset
+            .add_child(
+                "wrist",
+                Vector3::new(0.3, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        wrist
+            .attach_joint(Joint::revolute(Vector3::y_axis()))
+            .unwrap();
+        let tool = wrist
+            .add_child(
+                "tool",
+                Vector3::new(0.0, 0.0, 0.15),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+
+        let chain = base.chain_to(&tool).unwrap();
+        assert_eq!(chain.joint_count(), 3);
+        for values in [[0.0, 0.0, 0.0], [0.7, 0.25, -1.1], [-2.3, -0.4, 2.0]] {
+            let jacobian = chain.jacobian(&values);
+            assert_eq!(jacobian.shape(), (6, 3));
+            for frame in [JacobianFrame::Base, JacobianFrame::Tip] {
+                assert_relative_eq!(
+                    chain.jacobian_in(&values, frame),
+                    finite_difference_jacobian(&chain, &values, frame),
+                    epsilon = 1e-6
+                );
+            }
+            // The prismatic joint only moves the tip.
+            assert_relative_eq!(
+                jacobian.fixed_view::<3, 1>(3, 1).into_owned(),
+                Vector3::zeros()
+            );
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Reading JSON documents of any nesting depth.
+//!
+//! Trees are written as deeply nested as they are (see [`crate::Frame::to_json`]), while
+//! `serde_json` recurses once per level of nesting and stops at 128 levels. Documents are
+//! therefore parsed without that limit on a stack grown on demand, and every value built from
+//! them is dismantled iteratively, so even malformed documents cannot overflow the stack when
+//! they are dropped.
+
+use serde::Deserializer;
+use serde::de::{DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Number, Value};
+
+/// A parsed JSON document, dropped iteratively.
+pub struct JsonDocument(pub Value);
+
+impl JsonDocument {
+    /// Parses `json`, however deeply it is nested.
+    pub fn parse(json: &str) -> Result<Self, serde_json::Error> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        deserializer.disable_recursion_limit();
+        let value = DeepValue.deserialize(serde_stacker::Deserializer::new(&mut deserializer))?;
+        let document = Self(value);
+        deserializer.end()?;
+        Ok(document)
+    }
+
+    /// Deserializes the document into `D`, like `serde_json::from_value`.
+    pub fn deserialize<D: DeserializeOwned>(&self) -> Result<D, serde_json::Error> {
+        D::deserialize(serde_stacker::Deserializer::new(&self.0))
+    }
+}
+
+impl Drop for JsonDocument {
+    fn drop(&mut self) {
+        dismantle(core::mem::take(&mut self.0));
+    }
+}
+
+/// Drops `value` without recursing into nested arrays and objects.
+fn dismantle(value: Value) {
+    let mut pending = vec![value];
+    while let Some(value) = pending.pop() {
+        match value {
+            Value::Array(values) => pending.extend(values),
+            Value::Object(map) => pending.extend(map.into_iter().map(|(_, value)| value)),
+            _ => {}
+        }
+    }
+}
+
+/// Holds a value under construction, so it is dismantled if reading it fails.
+struct Partial(Value);
+
+impl Drop for Partial {
+    fn drop(&mut self) {
+        dismantle(core::mem::take(&mut self.0));
+    }
+}
+
+/// Reads a [`Value`] like its own `Deserialize` implementation, but keeps every array and
+/// object under construction in a [`Partial`].
+struct DeepValue;
+
+impl<'de> DeserializeSeed<'de> for DeepValue {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for DeepValue {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E> {
+        Ok(Number::from_f64(value).map_or(Value::Null, Value::Number))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut array = Partial(Value::Array(Vec::new()));
+        while let Some(value) = seq.next_element_seed(Self)? {
+            if let Value::Array(values) = &mut array.0 {
+                values.push(value);
+            }
+        }
+        Ok(core::mem::take(&mut array.0))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut object = Partial(Value::Object(Map::new()));
+        while let Some(key) = map.next_key::<String>()? {
+            let value = map.next_value_seed(Self)?;
+            if let Value::Object(entries) = &mut object.0 {
+                // A duplicate key replaces the earlier value, like in `serde_json`.
+                if let Some(replaced) = entries.insert(key, value) {
+                    dismantle(replaced);
+                }
+            }
+        }
+        Ok(core::mem::take(&mut object.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_documents_beyond_the_recursion_limit() {
+        const DEPTH: usize = 100_000;
+        let json = format!("{}1{}", r#"{"a": ["#.repeat(DEPTH), "]}".repeat(DEPTH));
+        let document = JsonDocument::parse(&json).unwrap();
+        let mut depth = 0;
+        let mut node = &document.0;
+        while let Some(child) = node.get("a").and_then(|a| a.get(0)) {
+            depth += 1;
+            node = child;
+        }
+        assert_eq!(depth, DEPTH);
+        assert_eq!(*node, Value::from(1));
+    }
+
+    #[test]
+    fn malformed_deep_documents_fail_without_overflowing() {
+        const DEPTH: usize = 100_000;
+        // The deep array is complete when the error is found, and dropped with the document.
+        let json = format!(
+            r#"{{"deep": {}{}, "bad": }}"#,
+            "[".repeat(DEPTH),
+            "]".repeat(DEPTH)
+        );
+        assert!(JsonDocument::parse(&json).is_err());
+        assert!(JsonDocument::parse(&"[".repeat(DEPTH)).is_err());
+    }
+
+    #[test]
+    fn matches_serde_json() {
+        let json = r#"{"a": [1, -2, 2.5, "xé", null, true, {"b": {}}], "a": 3, "c": []}"#;
+        let expected: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(JsonDocument::parse(json).unwrap().0, expected);
+        assert!(JsonDocument::parse("[1] trailing").is_err());
+    }
+}
@@ -3,14 +3,90 @@
 //! This crate provides a tree-based coordinate system where each frame has a position
 //! and orientation relative to its parent. You can create hierarchical transformations
 //! and convert poses between frames.
+//!
+//! # `no_std`
+//! The frame, pose and rotation math builds without the default `std` feature on targets with
+//! an allocator. The Python bindings and the optional integrations need `std`. The default
+//! `serde` feature derives the serde traits, also without `std`; together with `std` it adds the
+//! JSON documents written by `Frame::to_json`. Without `std`, enable `nalgebra/libm` for the
+//! floating-point functions, e.g.
+//!
+//! ```toml
+//! cartesian_tree = { package = "CartesianTree", version = "0.3", default-features = false }
+//! nalgebra = { version = "0.33", default-features = false, features = ["libm"] }
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
+#[cfg(feature = "approx")]
+pub mod approx;
+pub mod audit;
+#[cfg(feature = "binary")]
+pub mod binary;
+#[cfg(feature = "std")]
+pub mod builder;
+pub mod calibration;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "std")]
+pub mod conventions;
+#[cfg(feature = "std")]
+pub mod diff;
 pub mod errors;
 pub mod frame;
+#[cfg(feature = "std")]
+pub mod frame2d;
+#[cfg(feature = "glam")]
+pub mod glam;
+#[cfg(feature = "gltf")]
+pub mod gltf;
+pub mod history;
+#[cfg(feature = "std")]
+pub mod ik;
+mod intern;
+pub mod joint;
+#[cfg(all(feature = "std", feature = "serde"))]
+mod json;
 pub mod lazy_access;
+pub mod metadata;
+#[cfg(feature = "mint")]
+pub mod mint;
+pub mod mirror;
+#[cfg(feature = "std")]
+pub mod opencv;
+#[cfg(all(feature = "std", feature = "serde"))]
+pub mod patch;
 pub mod pose;
+pub mod region;
+#[cfg(feature = "rerun")]
+pub mod rerun;
+#[cfg(feature = "ros")]
+pub mod ros;
+#[cfg(feature = "ros2")]
+pub mod ros2;
 pub mod rotation;
+pub mod scalar;
+#[cfg(all(feature = "std", feature = "serde"))]
+pub mod schema;
+#[cfg(feature = "std")]
+pub mod snapshot;
+pub mod spatial;
+#[cfg(feature = "std")]
+pub mod table;
+#[cfg(feature = "std")]
+pub mod trajectory;
+pub mod transaction;
 
 pub mod tree;
+pub mod twist;
+pub mod units;
+#[cfg(feature = "urdf")]
+pub mod urdf;
+pub mod validation;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub use errors::CartesianTreeError;
 pub use frame::Frame;
 pub use pose::Pose;
@@ -31,6 +107,13 @@ fn cartesian_tree(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<bindings::utils::PyVector3>()?;
     m.add_class::<bindings::utils::PyRotation>()?;
     m.add_class::<bindings::utils::PyIsometry>()?;
+    m.add_class::<bindings::calibration::PyCalibrationReport>()?;
+    m.add_class::<bindings::calibration::PyPlaneFitReport>()?;
+    m.add_class::<bindings::trajectory::PyTrajectory>()?;
+    m.add_class::<bindings::region::PyRegionShape>()?;
+    m.add_class::<bindings::region::PyRegion>()?;
+    m.add_class::<bindings::diff::PyTreeDiff>()?;
+    m.add_class::<bindings::snapshot::PyTreeState>()?;
     m.add_class::<bindings::lazy_access::PyLazyTranslation>()?;
     m.add_class::<bindings::lazy_access::PyLazyRotation>()?;
     m.add_function(wrap_pyfunction!(bindings::lazy_access::x, m)?)?;
@@ -39,5 +122,6 @@ fn cartesian_tree(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(bindings::lazy_access::rx, m)?)?;
     m.add_function(wrap_pyfunction!(bindings::lazy_access::ry, m)?)?;
     m.add_function(wrap_pyfunction!(bindings::lazy_access::rz, m)?)?;
+    bindings::errors::register(m)?;
     Ok(())
 }
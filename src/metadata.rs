@@ -0,0 +1,179 @@
+//! Application data attached to frames.
+
+use alloc::string::{String, ToString};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A metadata value stored on a frame (see [`crate::Frame::set_metadata`]).
+///
+/// Scalars have dedicated variants; anything else (objects, arrays, null) is kept as JSON
+/// (feature `serde`).
+/// In JSON documents the variants are written as plain JSON values.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::metadata::MetadataValue;
+///
+/// assert_eq!(MetadataValue::from(1.5).as_f64(), Some(1.5));
+/// assert_eq!(MetadataValue::from("base.stl").as_str(), Some("base.stl"));
+/// assert_eq!(
+///     MetadataValue::from(serde_json::json!(true)),
+///     MetadataValue::Bool(true)
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum MetadataValue {
+    /// A boolean flag.
+    Bool(bool),
+    /// An integer.
+    Int(i64),
+    /// A floating-point number.
+    Float(f64),
+    /// A string.
+    String(String),
+    /// Any other JSON value, e.g. a nested object or an array.
+    #[cfg(feature = "serde")]
+    Json(serde_json::Value),
+}
+
+impl MetadataValue {
+    /// Returns the string, if this is a string value.
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the number as `f64`, if this is a float or an integer value.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub const fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Float(value) => Some(*value),
+            Self::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer, if this is an integer value.
+    #[must_use]
+    pub const fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the flag, if this is a boolean value.
+    #[must_use]
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Converts the value to JSON.
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::Bool(value) => (*value).into(),
+            Self::Int(value) => (*value).into(),
+            Self::Float(value) => (*value).into(),
+            Self::String(value) => value.clone().into(),
+            Self::Json(value) => value.clone(),
+        }
+    }
+}
+
+impl From<bool> for MetadataValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for MetadataValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<i32> for MetadataValue {
+    fn from(value: i32) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<f64> for MetadataValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<String> for MetadataValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for MetadataValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+/// Maps JSON scalars to the dedicated variants, matching how values are read back from JSON.
+#[cfg(feature = "serde")]
+impl From<serde_json::Value> for MetadataValue {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Bool(value) => Self::Bool(value),
+            serde_json::Value::String(value) => Self::String(value),
+            serde_json::Value::Number(number) => number
+                .as_i64()
+                .map(Self::Int)
+                .or_else(|| number.as_f64().map(Self::Float))
+                .unwrap_or(Self::Json(serde_json::Value::Number(number))),
+            other => Self::Json(other),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn json_round_trip_keeps_variants() {
+        let values = [
+            MetadataValue::from(true),
+            MetadataValue::from(-3),
+            MetadataValue::from(1.25),
+            MetadataValue::from(2.0),
+            MetadataValue::from("serial-42"),
+            MetadataValue::from(json!({"mesh": "arm.stl", "scale": [1, 1, 1]})),
+            MetadataValue::from(json!(null)),
+        ];
+        for value in values {
+            let text = serde_json::to_string(&value).unwrap();
+            let copy: MetadataValue = serde_json::from_str(&text).unwrap();
+            assert_eq!(copy, value, "{text}");
+            assert_eq!(MetadataValue::from(value.to_json()), value);
+        }
+    }
+
+    #[test]
+    fn accessors_match_variants() {
+        assert_eq!(MetadataValue::from(3).as_f64(), Some(3.0));
+        assert_eq!(MetadataValue::from(3.5).as_i64(), None);
+        assert_eq!(MetadataValue::from("yes").as_bool(), None);
+        assert_eq!(MetadataValue::from(false).as_bool(), Some(false));
+        assert_eq!(MetadataValue::from(json!([1])).as_str(), None);
+    }
+}
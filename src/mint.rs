@@ -0,0 +1,95 @@
+//! Conversions to and from [`mint`] types, enabled by the `mint` feature.
+//!
+//! The feature also enables nalgebra's own `mint` conversions, e.g. between `Vector3<T>` and
+//! `mint::Vector3<T>`, so mint vectors can be passed wherever a position is expected. This
+//! module converts [`Rotation`] to and from `mint::Quaternion`, whose vector part `v` holds
+//! `(x, y, z)` and whose scalar part `s` holds `w`.
+//!
+//! # Example
+//! ```
+//! use cartesian_tree::Frame;
+//!
+//! let world = Frame::new_origin("world").unwrap();
+//! let identity = mint::Quaternion { v: mint::Vector3 { x: 0.0, y: 0.0, z: 0.0 }, s: 1.0 };
+//! let camera = world
+//!     .add_child("camera", mint::Vector3 { x: 0.0, y: 0.0, z: 1.0 }, identity)
+//!     .unwrap();
+//! let position: mint::Vector3<f64> = camera.position().into();
+//! assert_eq!(position.z, 1.0);
+//! ```
+
+use crate::rotation::Rotation;
+use crate::scalar::Real;
+
+/// Interprets the quaternion as is, normalizing it.
+impl<T: Real> From<mint::Quaternion<T>> for Rotation<T> {
+    fn from(quaternion: mint::Quaternion<T>) -> Self {
+        let mint::Quaternion { v, s } = quaternion;
+        Self::from_quaternion(v.x, v.y, v.z, s)
+    }
+}
+
+impl<T: Real> From<Rotation<T>> for mint::Quaternion<T> {
+    fn from(rotation: Rotation<T>) -> Self {
+        rotation.as_quaternion().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Frame;
+    use approx::assert_relative_eq;
+    use nalgebra::{Quaternion, Unit, UnitQuaternion, Vector3};
+
+    #[test]
+    fn quaternions_keep_their_components_and_handedness() {
+        let rotation =
+            Rotation::from_axis_angle(Unit::new_normalize(Vector3::new(1.0, 2.0, 3.0)), 0.7);
+        let quaternion = mint::Quaternion::from(rotation);
+        // nalgebra reads mint quaternions itself; both ways must agree on the components.
+        let read_back = UnitQuaternion::new_normalize(Quaternion::from(quaternion));
+        assert_relative_eq!(read_back, rotation.as_quaternion(), epsilon = 1e-12);
+
+        let point = Vector3::new(0.3, -1.2, 2.5);
+        let rotated = Rotation::from(quaternion).as_quaternion() * point;
+        assert_relative_eq!(rotated, rotation.as_quaternion() * point, epsilon = 1e-12);
+
+        // A quarter turn about z has its vector part along z and maps x onto y.
+        let quarter =
+            mint::Quaternion::from(Rotation::from_rpy(0.0, 0.0, std::f64::consts::FRAC_PI_2));
+        assert_relative_eq!(quarter.v.z, 0.5_f64.sqrt(), epsilon = 1e-12);
+        assert_relative_eq!(quarter.s, 0.5_f64.sqrt(), epsilon = 1e-12);
+        let rotated = Rotation::from(quarter).as_quaternion() * Vector3::x();
+        assert_relative_eq!(rotated, Vector3::y(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn mint_types_place_frames() {
+        let world = Frame::new_origin("world").unwrap();
+        let half = 0.5_f64.sqrt();
+        let quarter = mint::Quaternion {
+            v: mint::Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: half,
+            },
+            s: half,
+        };
+        let arm = world
+            .add_child(
+                "arm",
+                mint::Vector3 {
+                    x: 1.0,
+                    y: 0.0,
+                    z: 0.0,
+                },
+                quarter,
+            )
+            .unwrap();
+        let pose = arm.add_pose([1.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]).unwrap();
+        let position: mint::Vector3<f64> = pose.in_frame(&world).unwrap().position().into();
+        assert_relative_eq!(position.x, 1.0, epsilon = 1e-12);
+        assert_relative_eq!(position.y, 1.0, epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,413 @@
+//! Mirroring of poses and frames across a plane, e.g. for symmetric robot cells.
+//!
+//! A [`MirrorPlane`] is one of the coordinate planes of a frame. Reflecting across it negates
+//! the coordinate along its normal, e.g. x for [`MirrorPlane::YZ`]. A reflected orientation is
+//! left-handed, so it cannot be represented as a rotation. Instead, the axis along the normal is
+//! flipped back after reflecting: the mirrored frame's axes are the mirror images of the
+//! original axes, except that the one along the normal of the plane (x for
+//! [`MirrorPlane::YZ`]) points the opposite way, keeping the frame right-handed.
+//!
+//! With `S` the reflection, a transform `T` relative to the frame of the plane is mirrored to
+//! `S T S`. A rotation thus keeps its angle, but turns the opposite way about the mirror image
+//! of its axis, e.g. a yaw of 30° becomes -30° when mirrored across the YZ plane. As `S S` is
+//! the identity, the local transforms within a mirrored subtree are mirrored alike, and
+//! mirroring twice restores the original.
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::pose::Pose;
+use crate::tree::HasChildren;
+use crate::validation::check_frame_name;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec;
+use nalgebra::{Isometry3, Matrix3, Rotation3, Translation3, UnitQuaternion, Vector3};
+
+/// A coordinate plane of a frame to mirror across.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MirrorPlane {
+    /// The plane spanned by the x and y axes, negating z.
+    XY,
+    /// The plane spanned by the x and z axes, negating y.
+    XZ,
+    /// The plane spanned by the y and z axes, negating x.
+    YZ,
+}
+
+impl MirrorPlane {
+    /// Returns the unit normal of the plane.
+    #[must_use]
+    pub fn normal(self) -> Vector3<f64> {
+        match self {
+            Self::XY => Vector3::z(),
+            Self::XZ => Vector3::y(),
+            Self::YZ => Vector3::x(),
+        }
+    }
+
+    /// Returns `isometry`, given in the frame of the plane, mirrored across it, see the
+    /// [module documentation](crate::mirror) for the convention.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::mirror::MirrorPlane;
+    /// use nalgebra::{Isometry3, Vector3};
+    ///
+    /// let right = Isometry3::new(Vector3::new(1.0, 2.0, 0.0), Vector3::z() * 0.5);
+    /// let left = MirrorPlane::YZ.mirror(&right);
+    /// assert_eq!(left.translation.vector, Vector3::new(-1.0, 2.0, 0.0));
+    /// assert!((left.rotation.euler_angles().2 + 0.5).abs() < 1e-12);
+    /// ```
+    #[must_use]
+    pub fn mirror(self, isometry: &Isometry3<f64>) -> Isometry3<f64> {
+        let reflection = Matrix3::identity() - 2.0 * self.normal() * self.normal().transpose();
+        let rotation = reflection * isometry.rotation.to_rotation_matrix().matrix() * reflection;
+        Isometry3::from_parts(
+            Translation3::from(reflection * isometry.translation.vector),
+            UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(rotation)),
+        )
+    }
+}
+
+impl Pose {
+    /// Returns this pose mirrored across a coordinate plane of `in_frame`.
+    ///
+    /// The mirrored pose is expressed in the frame of this pose. See the
+    /// [module documentation](crate::mirror) for how orientations are kept right-handed.
+    ///
+    /// # Arguments
+    /// - `plane`: The plane of `in_frame` to mirror across.
+    /// - `in_frame`: The frame the plane belongs to.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame of this pose has been dropped.
+    /// - The frame of this pose and `in_frame` do not share a common ancestor.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::mirror::MirrorPlane;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let cell = Frame::new_origin("cell").unwrap();
+    /// let table = cell
+    ///     .add_child("table", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let right = table.add_pose(Vector3::new(0.5, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// let left = right.mirrored(MirrorPlane::YZ, &cell).unwrap();
+    /// assert_eq!(left.position(), Vector3::new(-2.5, 0.0, 0.0));
+    /// ```
+    pub fn mirrored(
+        &self,
+        plane: MirrorPlane,
+        in_frame: &Frame,
+    ) -> Result<Self, CartesianTreeError> {
+        let frame = self.frame()?;
+        let in_plane = self.in_frame(in_frame)?;
+        let mirrored = plane.mirror(&in_plane.transformation());
+        in_frame
+            .add_pose(mirrored.translation.vector, mirrored.rotation)?
+            .in_frame(&frame)
+    }
+}
+
+impl Frame {
+    /// Adds a mirrored copy of the subtree of `source` as a child of this frame.
+    ///
+    /// The subtree is mirrored across `plane` of this frame, see the
+    /// [module documentation](crate::mirror) for how orientations are kept right-handed. The copy
+    /// keeps where `source` is relative to this frame, mirrored, and the transforms within the
+    /// subtree are mirrored alike. Names are mapped by `name_transform`, metadata and static
+    /// flags are copied, and stored poses and regions are mirrored along with their frames.
+    ///
+    /// # Arguments
+    /// - `source`: The root of the subtree to mirror. It may be any frame of the tree, including
+    ///   an ancestor of this frame.
+    /// - `plane`: The plane of this frame to mirror across.
+    /// - `name_transform`: Returns the name of the mirrored copy of a frame, e.g. replacing
+    ///   `right` by `left`.
+    ///
+    /// # Returns
+    /// The mirrored copy of `source`.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] without changing the tree if:
+    /// - `source` and this frame do not share a common ancestor.
+    /// - The transform from `source` to this frame passes through a scaled frame.
+    /// - A mapped name violates the [`FrameNameRules`](crate::validation::FrameNameRules) of
+    ///   the tree, or two siblings are mapped to the same name.
+    /// - This frame already has a child with the mapped name of `source`.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::mirror::MirrorPlane;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let cell = Frame::new_origin("cell").unwrap();
+    /// let right_arm = cell
+    ///     .add_child("right_arm", Vector3::new(0.8, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// right_arm
+    ///     .add_child("right_gripper", Vector3::new(0.2, 0.1, 0.5), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let left_arm = cell
+    ///     .add_mirrored_subtree(&right_arm, MirrorPlane::YZ, |name| name.replace("right", "left"))
+    ///     .unwrap();
+    /// let gripper = left_arm.resolve_path("left_gripper").unwrap();
+    /// assert_eq!(gripper.path(), "/cell/left_arm/left_gripper");
+    /// assert!((gripper.world_transform().unwrap().translation.vector - Vector3::new(-1.0, 0.1, 0.5)).norm() < 1e-12);
+    /// ```
+    pub fn add_mirrored_subtree(
+        &self,
+        source: &Self,
+        plane: MirrorPlane,
+        name_transform: impl Fn(&str) -> String,
+    ) -> Result<Self, CartesianTreeError> {
+        let transform = source.transform_to(self)?;
+        // Copying first keeps the traversal away from this frame, which may be inside the subtree.
+        let copy = source.clone_subtree();
+
+        // Names are checked up front, so a conflict fails before anything is added.
+        let rules = self.name_rules();
+        let root_name = name_transform(&copy.name());
+        check_frame_name(&root_name, rules)?;
+        if self
+            .children()
+            .iter()
+            .any(|child| child.name() == root_name)
+        {
+            return Err(CartesianTreeError::DuplicateChildName {
+                parent: self.name(),
+                child: root_name,
+            });
+        }
+        for frame in copy.iter_dfs() {
+            let mut names = BTreeSet::new();
+            for child in frame.children() {
+                let name = name_transform(&child.name());
+                check_frame_name(&name, rules)?;
+                if !names.insert(name.clone()) {
+                    return Err(CartesianTreeError::DuplicateChildName {
+                        parent: name_transform(&frame.name()),
+                        child: name,
+                    });
+                }
+            }
+        }
+
+        let mirrored =
+            self.add_mirrored_frame(&copy, root_name, &plane.mirror(&transform), plane)?;
+        let mut stack = vec![(copy, mirrored.clone())];
+        while let Some((original, mirror)) = stack.pop() {
+            for child in original.children() {
+                let transform = child.borrow().transform_to_parent;
+                let name = name_transform(&child.name());
+                let child_mirror =
+                    mirror.add_mirrored_frame(&child, name, &plane.mirror(&transform), plane)?;
+                stack.push((child, child_mirror));
+            }
+        }
+        Ok(mirrored)
+    }
+
+    /// Adds a child at `transform` with the metadata, static flag and mirrored stored poses and
+    /// regions of `original`.
+    fn add_mirrored_frame(
+        &self,
+        original: &Self,
+        name: String,
+        transform: &Isometry3<f64>,
+        plane: MirrorPlane,
+    ) -> Result<Self, CartesianTreeError> {
+        let child = self.add_child(name, transform.translation.vector, transform.rotation)?;
+        let data = original.borrow();
+        let mut child_data = child.data.borrow_mut();
+        child_data.metadata.clone_from(&data.metadata);
+        child_data.poses = data
+            .poses
+            .iter()
+            .map(|(name, pose)| (name.clone(), plane.mirror(pose)))
+            .collect();
+        child_data.regions = data
+            .regions
+            .iter()
+            .map(|(name, region)| (name.clone(), region.mirrored(plane)))
+            .collect();
+        child_data.is_static = data.is_static;
+        drop(child_data);
+        Ok(child)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::NodeEquality;
+    use approx::assert_relative_eq;
+    use core::f64::consts::FRAC_PI_6;
+
+    #[test]
+    fn poses_mirror_to_the_other_side_of_the_plane() {
+        let cell = Frame::new_origin("cell").unwrap();
+        let right = cell
+            .add_pose(
+                Vector3::new(1.0, 2.0, 0.5),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, FRAC_PI_6),
+            )
+            .unwrap();
+        let left = right.mirrored(MirrorPlane::YZ, &cell).unwrap();
+        assert_relative_eq!(left.position(), Vector3::new(-1.0, 2.0, 0.5));
+        // The yaw turns the other way, keeping the frame right-handed.
+        let rotation = left.orientation().as_quaternion();
+        assert_relative_eq!(
+            rotation,
+            UnitQuaternion::from_euler_angles(0.0, 0.0, -FRAC_PI_6),
+            epsilon = 1e-12
+        );
+        // The y and z axes are mirror images, the x axis points the opposite way.
+        let matrix = rotation.to_rotation_matrix();
+        let original = right.orientation().as_quaternion().to_rotation_matrix();
+        let flip = |axis: Vector3<f64>| Vector3::new(-axis.x, axis.y, axis.z);
+        assert_relative_eq!(
+            matrix * Vector3::x(),
+            -flip(original * Vector3::x()),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            matrix * Vector3::y(),
+            flip(original * Vector3::y()),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(matrix.matrix().determinant(), 1.0, epsilon = 1e-12);
+
+        // The plane may belong to another frame; the result stays in the frame of the pose.
+        let mount = cell
+            .add_child(
+                "mount",
+                Vector3::new(0.0, 0.0, 1.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let below = right.mirrored(MirrorPlane::XY, &mount).unwrap();
+        assert!(below.frame().unwrap().is_same(&cell));
+        assert_relative_eq!(below.position(), Vector3::new(1.0, 2.0, 1.5));
+        let twice = below.mirrored(MirrorPlane::XY, &mount).unwrap();
+        crate::assert_pose_eq!(twice, right);
+
+        let other = Frame::new_origin("other").unwrap();
+        assert!(matches!(
+            right.mirrored(MirrorPlane::YZ, &other),
+            Err(CartesianTreeError::NoCommonAncestor { .. })
+        ));
+    }
+
+    #[test]
+    fn subtrees_mirror_into_the_other_side() {
+        let cell = Frame::new_origin("cell").unwrap();
+        let right_arm = cell
+            .add_child(
+                "right_arm",
+                Vector3::new(1.0, 2.0, 0.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, FRAC_PI_6),
+            )
+            .unwrap();
+        right_arm.set_static(true);
+        right_arm.set_metadata("side", "right");
+        let right_tool = right_arm
+            .add_child(
+                "right_tool",
+                Vector3::new(0.3, 0.1, 0.2),
+                UnitQuaternion::from_euler_angles(0.2, -0.1, 0.4),
+            )
+            .unwrap();
+        right_tool
+            .store_pose(
+                "grasp",
+                &right_tool
+                    .add_pose(Vector3::new(0.0, 0.05, 0.1), UnitQuaternion::identity())
+                    .unwrap(),
+                crate::frame::StorePolicy::Reject,
+            )
+            .unwrap();
+        right_arm
+            .add_child("right_camera", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        let rename = |name: &str| name.replace("right", "left");
+        let left_arm = cell
+            .add_mirrored_subtree(&right_arm, MirrorPlane::YZ, rename)
+            .unwrap();
+        assert_eq!(left_arm.path(), "/cell/left_arm");
+        assert!(left_arm.is_static());
+        assert_eq!(
+            left_arm.get_metadata("side"),
+            Some(crate::metadata::MetadataValue::from("right"))
+        );
+        assert_relative_eq!(left_arm.position(), Vector3::new(-1.0, 2.0, 0.0));
+        assert_relative_eq!(
+            left_arm.orientation().as_quaternion(),
+            UnitQuaternion::from_euler_angles(0.0, 0.0, -FRAC_PI_6),
+            epsilon = 1e-12
+        );
+
+        // Every frame and stored pose sits at the mirror image of its original in the world.
+        let left_tool = left_arm.resolve_path("left_tool").unwrap();
+        for (original, mirror) in [(&right_arm, &left_arm), (&right_tool, &left_tool)] {
+            let expected = MirrorPlane::YZ.mirror(&original.world_transform().unwrap());
+            assert_relative_eq!(mirror.world_transform().unwrap(), expected, epsilon = 1e-12);
+        }
+        let grasp = left_tool.get_pose("grasp").unwrap();
+        let expected = right_tool
+            .get_pose("grasp")
+            .unwrap()
+            .mirrored(MirrorPlane::YZ, &cell)
+            .unwrap();
+        crate::assert_pose_eq!(grasp, expected);
+
+        assert_eq!(cell.iter_dfs().count(), 7);
+
+        // Failing names leave the tree as it is.
+        assert!(matches!(
+            cell.add_mirrored_subtree(&right_arm, MirrorPlane::YZ, rename),
+            Err(CartesianTreeError::DuplicateChildName { parent, child })
+                if parent == "cell" && child == "left_arm"
+        ));
+        assert!(matches!(
+            cell.add_mirrored_subtree(&right_arm, MirrorPlane::YZ, |_| "twin".to_owned()),
+            Err(CartesianTreeError::DuplicateChildName { parent, child })
+                if parent == "twin" && child == "twin"
+        ));
+        assert!(matches!(
+            cell.add_mirrored_subtree(&right_arm, MirrorPlane::YZ, |name| name.replace('_', "/")),
+            Err(CartesianTreeError::InvalidFrameName { .. })
+        ));
+        assert_eq!(cell.iter_dfs().count(), 7);
+    }
+
+    #[test]
+    fn subtrees_mirror_into_themselves() {
+        let cell = Frame::new_origin("cell").unwrap();
+        let arm = cell
+            .add_child(
+                "arm",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let mirrored = arm
+            .add_mirrored_subtree(&cell, MirrorPlane::XY, |name| format!("{name}_mirror"))
+            .unwrap();
+        assert_eq!(mirrored.path(), "/cell/arm/cell_mirror");
+        assert_eq!(
+            mirrored.children()[0].path(),
+            "/cell/arm/cell_mirror/arm_mirror"
+        );
+        assert_relative_eq!(
+            mirrored.world_transform().unwrap().translation.vector,
+            Vector3::zeros()
+        );
+    }
+}
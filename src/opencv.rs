@@ -0,0 +1,316 @@
+//! Import of camera extrinsics calibrated with `OpenCV`.
+//!
+//! `OpenCV` describes the pose of a camera by a Rodrigues rotation vector `rvec` and a
+//! translation `tvec`. As returned by `solvePnP` or `calibrateCamera`, they map points from the
+//! world (or calibration target) into the camera: `x_camera = R(rvec) * x_world + tvec`.
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::rotation::Rotation;
+#[cfg(feature = "serde")]
+use crate::tree::HasChildren;
+use nalgebra::{Isometry3, Translation3, Vector3};
+#[cfg(feature = "serde")]
+use serde_json::Value;
+
+/// States which way a pair of `rvec` and `tvec` maps points.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExtrinsicsDirection {
+    /// The extrinsics map points from the world into the camera, as returned by `OpenCV`.
+    #[default]
+    WorldToCamera,
+    /// The extrinsics map points from the camera into the world, i.e. they are the camera pose.
+    CameraToWorld,
+}
+
+/// Returns the transform from the camera to the world described by `rvec` and `tvec`.
+fn camera_to_world(
+    rvec: Vector3<f64>,
+    tvec: Vector3<f64>,
+    direction: ExtrinsicsDirection,
+) -> Isometry3<f64> {
+    let rotation = Rotation::from_rotation_vector(rvec).as_quaternion();
+    let extrinsics = Isometry3::from_parts(Translation3::from(tvec), rotation);
+    match direction {
+        ExtrinsicsDirection::WorldToCamera => extrinsics.inverse(),
+        ExtrinsicsDirection::CameraToWorld => extrinsics,
+    }
+}
+
+impl Frame {
+    /// Adds a camera frame placed by `OpenCV` extrinsics relative to this frame.
+    ///
+    /// This frame is the world of the extrinsics. The axes of the new frame follow the `OpenCV`
+    /// camera convention (x right, y down, z along the optical axis).
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new camera frame.
+    /// - `rvec`: The Rodrigues rotation vector (axis scaled by the angle in radians).
+    /// - `tvec`: The translation vector.
+    /// - `direction`: Which way the extrinsics map points.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the frame cannot be added, see [`Frame::add_child`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::opencv::ExtrinsicsDirection;
+    /// use nalgebra::{Point3, Vector3};
+    ///
+    /// let world = Frame::new_origin("world").unwrap();
+    /// let camera = world
+    ///     .add_child_from_opencv_extrinsics(
+    ///         "camera",
+    ///         Vector3::zeros(),
+    ///         Vector3::new(0.0, 0.0, 2.0),
+    ///         ExtrinsicsDirection::WorldToCamera,
+    ///     )
+    ///     .unwrap();
+    ///
+    /// // The world origin lies 2 units in front of the camera.
+    /// let origin = world.transform_point_to(&camera, &Point3::origin()).unwrap();
+    /// assert_eq!(origin, Point3::new(0.0, 0.0, 2.0));
+    /// ```
+    pub fn add_child_from_opencv_extrinsics(
+        &self,
+        name: impl Into<String>,
+        rvec: Vector3<f64>,
+        tvec: Vector3<f64>,
+        direction: ExtrinsicsDirection,
+    ) -> Result<Self, CartesianTreeError> {
+        let transform = camera_to_world(rvec, tvec, direction);
+        self.add_child(name, transform.translation.vector, transform.rotation)
+    }
+
+    /// Adds one camera frame per camera of a multi-camera `OpenCV` calibration.
+    ///
+    /// The calibration is a JSON object with one member per camera, named like the camera
+    /// frame, holding its `rvec` and `tvec`. Both are given either as arrays of three numbers or
+    /// as matrices the way `cv::FileStorage` writes them
+    /// (`{"type_id": "opencv-matrix", "rows": 3, "cols": 1, "dt": "d", "data": [...]}`). Members
+    /// without `rvec` and `tvec`, e.g. the image size, are ignored. Cameras are added in the
+    /// order of their names.
+    ///
+    /// # Arguments
+    /// - `json`: The calibration document.
+    /// - `direction`: Which way the extrinsics of all cameras map points.
+    ///
+    /// # Returns
+    /// The new camera frames.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The document is not valid JSON or a camera is malformed. The error holds the JSON path
+    ///   of the offending field.
+    /// - This frame already has a child named like one of the cameras.
+    /// - A camera name or transform is invalid, see [`Frame::add_child`].
+    ///
+    /// Nothing is added on error.
+    #[cfg(feature = "serde")]
+    pub fn add_cameras_from_opencv_calibration(
+        &self,
+        json: &str,
+        direction: ExtrinsicsDirection,
+    ) -> Result<Vec<Self>, CartesianTreeError> {
+        let document: Value = serde_json::from_str(json)?;
+        let members = document
+            .as_object()
+            .ok_or_else(|| invalid_document("$", "expected an object"))?;
+        let mut cameras = Vec::new();
+        for (name, camera) in members {
+            if camera.get("rvec").is_none() && camera.get("tvec").is_none() {
+                continue;
+            }
+            let path = format!("$.{name}");
+            let rvec = opencv_vector(camera, &path, "rvec")?;
+            let tvec = opencv_vector(camera, &path, "tvec")?;
+            cameras.push((name, camera_to_world(rvec, tvec, direction)));
+        }
+
+        let existing: Vec<String> = self.children().iter().map(Self::name).collect();
+        if let Some((name, _)) = cameras.iter().find(|(name, _)| existing.contains(name)) {
+            return Err(CartesianTreeError::DuplicateChildName {
+                parent: self.name(),
+                child: (*name).clone(),
+            });
+        }
+        let mut added = Vec::new();
+        for (name, transform) in cameras {
+            match self.add_child(name, transform.translation.vector, transform.rotation) {
+                Ok(camera) => added.push(camera),
+                Err(error) => {
+                    for camera in &added {
+                        let _ = self.remove_child(&camera.name());
+                    }
+                    return Err(error);
+                }
+            }
+        }
+        Ok(added)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn invalid_document(path: &str, reason: &str) -> CartesianTreeError {
+    CartesianTreeError::InvalidDocument {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// Reads the vector `key` of `camera`, either as a plain array or as an `OpenCV` matrix.
+#[cfg(feature = "serde")]
+fn opencv_vector(
+    camera: &Value,
+    path: &str,
+    key: &str,
+) -> Result<Vector3<f64>, CartesianTreeError> {
+    let field_path = format!("{path}.{key}");
+    let malformed = || {
+        invalid_document(
+            &field_path,
+            "expected an array of 3 numbers or an OpenCV matrix with 3 elements",
+        )
+    };
+    let value = camera
+        .get(key)
+        .ok_or_else(|| invalid_document(path, &format!("missing field '{key}'")))?;
+    let data = value.get("data").unwrap_or(value);
+    match data.as_array().map(Vec::as_slice) {
+        Some([x, y, z]) => Ok(Vector3::new(
+            x.as_f64().ok_or_else(malformed)?,
+            y.as_f64().ok_or_else(malformed)?,
+            z.as_f64().ok_or_else(malformed)?,
+        )),
+        _ => Err(malformed()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use nalgebra::{Matrix3, Point3};
+    use std::f64::consts::FRAC_PI_2;
+
+    /// The rotation matrix of a Rodrigues vector, following the formula documented by `OpenCV`.
+    fn rodrigues(rvec: Vector3<f64>) -> Matrix3<f64> {
+        let angle = rvec.norm();
+        let k = (rvec / angle).cross_matrix();
+        Matrix3::identity() + k * angle.sin() + k * k * (1.0 - angle.cos())
+    }
+
+    #[test]
+    fn world_points_project_into_the_camera() {
+        let world = Frame::new_origin("world").unwrap();
+        let rvec = Vector3::new(0.0, 0.0, FRAC_PI_2);
+        let tvec = Vector3::new(1.0, 2.0, 3.0);
+        let camera = world
+            .add_child_from_opencv_extrinsics(
+                "camera",
+                rvec,
+                tvec,
+                ExtrinsicsDirection::WorldToCamera,
+            )
+            .unwrap();
+        // x_camera = R * x_world + t, with R turning x onto y.
+        let point = world
+            .transform_point_to(&camera, &Point3::new(1.0, 0.0, 0.0))
+            .unwrap();
+        assert_relative_eq!(point, Point3::new(1.0, 3.0, 3.0), epsilon = 1e-12);
+
+        // A general rotation, checked against the Rodrigues formula.
+        let rvec = Vector3::new(0.3, -1.2, 0.5);
+        let tvec = Vector3::new(-0.1, 0.05, 1.5);
+        let camera = world
+            .add_child_from_opencv_extrinsics(
+                "tilted",
+                rvec,
+                tvec,
+                ExtrinsicsDirection::WorldToCamera,
+            )
+            .unwrap();
+        let world_point = Point3::new(0.4, -0.2, 0.7);
+        let expected = rodrigues(rvec) * world_point.coords + tvec;
+        let point = world.transform_point_to(&camera, &world_point).unwrap();
+        assert_relative_eq!(point.coords, expected, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn camera_to_world_extrinsics_are_the_camera_pose() {
+        let world = Frame::new_origin("world").unwrap();
+        let rvec = Vector3::new(0.3, -1.2, 0.5);
+        let tvec = Vector3::new(-0.1, 0.05, 1.5);
+        let camera = world
+            .add_child_from_opencv_extrinsics(
+                "camera",
+                rvec,
+                tvec,
+                ExtrinsicsDirection::CameraToWorld,
+            )
+            .unwrap();
+        assert_relative_eq!(camera.position(), tvec, epsilon = 1e-12);
+        let camera_point = Point3::new(0.4, -0.2, 0.7);
+        let point = camera.transform_point_to(&world, &camera_point).unwrap();
+        assert_relative_eq!(
+            point.coords,
+            rodrigues(rvec) * camera_point.coords + tvec,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn calibration_files_add_one_frame_per_camera() {
+        let calibration = r#"{
+            "image_width": 1920,
+            "left": {"rvec": [0.0, 0.0, 0.0], "tvec": [0.1, 0.0, 0.0]},
+            "right": {
+                "rvec": {"type_id": "opencv-matrix", "rows": 3, "cols": 1, "dt": "d",
+                         "data": [0.0, 0.0, 0.0]},
+                "tvec": {"type_id": "opencv-matrix", "rows": 3, "cols": 1, "dt": "d",
+                         "data": [-0.1, 0.0, 0.0]}
+            }
+        }"#;
+        let rig = Frame::new_origin("rig").unwrap();
+        let cameras = rig
+            .add_cameras_from_opencv_calibration(calibration, ExtrinsicsDirection::WorldToCamera)
+            .unwrap();
+        let names: Vec<_> = cameras.iter().map(Frame::name).collect();
+        assert_eq!(names, ["left", "right"]);
+        assert_relative_eq!(
+            cameras[0].position(),
+            Vector3::new(-0.1, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            cameras[1].position(),
+            Vector3::new(0.1, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+
+        // Loading the same cameras again conflicts with the existing frames.
+        assert!(matches!(
+            rig.add_cameras_from_opencv_calibration(calibration, ExtrinsicsDirection::default()),
+            Err(CartesianTreeError::DuplicateChildName { .. })
+        ));
+
+        let malformed = r#"{"left": {"rvec": [0.0, 0.0, 0.0]}, "right": {"rvec": [0.0, 0.0], "tvec": [0.0, 0.0, 0.0]}}"#;
+        let empty = Frame::new_origin("rig").unwrap();
+        assert!(matches!(
+            empty.add_cameras_from_opencv_calibration(malformed, ExtrinsicsDirection::default()),
+            Err(CartesianTreeError::InvalidDocument { path, .. }) if path == "$.left"
+        ));
+        assert!(empty.children().is_empty());
+
+        // Cameras added before an invalid name are removed again.
+        let invalid_name = r#"{"left": {"rvec": [0.0, 0.0, 0.0], "tvec": [0.0, 0.0, 0.0]},
+            "right ": {"rvec": [0.0, 0.0, 0.0], "tvec": [0.0, 0.0, 0.0]}}"#;
+        assert!(matches!(
+            empty.add_cameras_from_opencv_calibration(invalid_name, ExtrinsicsDirection::default()),
+            Err(CartesianTreeError::InvalidFrameName { .. })
+        ));
+        assert!(empty.children().is_empty());
+    }
+}
@@ -0,0 +1,348 @@
+//! Partial updates of frame transforms addressed by path.
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::rotation::Rotation;
+use crate::tree::{HasChildren, HasParent};
+use crate::validation::{check_frame_name, check_orientation, check_position};
+use nalgebra::{UnitQuaternion, Vector3};
+use serde::Deserialize;
+
+/// How [`Frame::apply_patch`] handles paths that do not point to an existing frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingFramePolicy {
+    /// Fail with [`CartesianTreeError::MissingFrames`] listing all unknown paths.
+    #[default]
+    Reject,
+    /// Create the missing frames. Intermediate frames get identity transforms.
+    Create,
+}
+
+/// An entry of a patch document.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PatchEntry {
+    path: String,
+    position: Option<[f64; 3]>,
+    rpy: Option<[f64; 3]>,
+    orientation: Option<[f64; 4]>,
+}
+
+/// A patch entry with checked values.
+struct Update<'a> {
+    path: &'a str,
+    position: Option<Vector3<f64>>,
+    orientation: Option<UnitQuaternion<f64>>,
+}
+
+impl Frame {
+    /// Updates the transforms of individual frames from a JSON patch document.
+    ///
+    /// The document is a list of entries such as
+    /// `{"path": "robot/flange/tool", "position": [0.0, 0.0, 0.1], "rpy": [0.0, 0.0, 1.57]}`.
+    /// Paths are relative to this frame and may contain `.` and `..` segments, see
+    /// [`Frame::resolve_path`]. The orientation is given either as `rpy` (radians) or as an
+    /// `orientation` quaternion `[x, y, z, w]`. Omitted fields keep their current values. Entries
+    /// are applied in order.
+    ///
+    /// # Arguments
+    /// - `json`: The JSON patch document.
+    /// - `missing`: Whether unknown paths are rejected or the missing frames are created.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The document is not valid JSON or an entry is malformed.
+    /// - An entry sets both `rpy` and `orientation`, or a value is not finite.
+    /// - Paths do not point to existing frames and `missing` is [`MissingFramePolicy::Reject`].
+    ///   All unknown paths are reported together in [`CartesianTreeError::MissingFrames`].
+    /// - A frame to create has an invalid name, or an entry updates a root frame or a static
+    ///   frame (see [`Frame::set_static`]).
+    ///
+    /// Nothing is changed if any of these errors occur.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::patch::MissingFramePolicy;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let robot = root
+    ///     .add_child("robot", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// root.apply_patch(
+    ///     r#"[{"path": "robot", "position": [1.0, 0.0, 0.5]}]"#,
+    ///     MissingFramePolicy::Reject,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(robot.position(), Vector3::new(1.0, 0.0, 0.5));
+    /// ```
+    pub fn apply_patch(
+        &self,
+        json: &str,
+        missing: MissingFramePolicy,
+    ) -> Result<(), CartesianTreeError> {
+        let entries: Vec<PatchEntry> = serde_json::from_str(json)?;
+        let updates = entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| check_entry(index, entry))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Everything is checked up front, so a faulty patch fails before changing anything.
+        let mut unknown = Vec::new();
+        for update in &updates {
+            let (frame, remaining) = self.walk_existing(update.path)?;
+            if remaining.is_empty() {
+                let changes = update.position.is_some() || update.orientation.is_some();
+                if changes && frame.parent().is_none() {
+                    return Err(CartesianTreeError::CannotUpdateRootTransform {
+                        frame: frame.name(),
+                    });
+                }
+                if changes && frame.is_static() {
+                    return Err(CartesianTreeError::StaticFrame {
+                        frame: frame.name(),
+                    });
+                }
+            } else if missing == MissingFramePolicy::Reject {
+                unknown.push(update.path.to_string());
+            } else {
+                for segment in remaining {
+                    if segment == ".." {
+                        return Err(CartesianTreeError::PathNotFound {
+                            path: update.path.to_string(),
+                            segment: segment.to_string(),
+                        });
+                    }
+                    check_frame_name(segment, frame.name_rules())?;
+                }
+            }
+        }
+        if !unknown.is_empty() {
+            return Err(CartesianTreeError::MissingFrames { paths: unknown });
+        }
+
+        for update in updates {
+            let (mut frame, remaining) = self.walk_existing(update.path)?;
+            for segment in remaining {
+                frame = frame.add_child(segment, Vector3::zeros(), UnitQuaternion::identity())?;
+            }
+            if update.position.is_none() && update.orientation.is_none() {
+                continue;
+            }
+            let position = update.position.unwrap_or_else(|| frame.position());
+            let orientation = update
+                .orientation
+                .unwrap_or_else(|| frame.orientation().as_quaternion());
+            frame.set(position, orientation)?;
+        }
+        Ok(())
+    }
+
+    /// Follows `path` as far as frames exist.
+    ///
+    /// Returns the last existing frame and the segments that could not be resolved. `..` above
+    /// a root is an error.
+    fn walk_existing<'a>(&self, path: &'a str) -> Result<(Self, Vec<&'a str>), CartesianTreeError> {
+        let mut current = self.clone();
+        let mut segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty() && *segment != ".")
+            .peekable();
+        while let Some(&segment) = segments.peek() {
+            let next = if segment == ".." {
+                let parent = current
+                    .parent()
+                    .ok_or_else(|| CartesianTreeError::PathNotFound {
+                        path: path.to_string(),
+                        segment: segment.to_string(),
+                    })?;
+                Some(parent)
+            } else {
+                current
+                    .children()
+                    .into_iter()
+                    .find(|child| child.name() == segment)
+            };
+            let Some(next) = next else {
+                break;
+            };
+            current = next;
+            segments.next();
+        }
+        Ok((current, segments.collect()))
+    }
+}
+
+/// Converts and checks the values of the `index`-th patch entry.
+fn check_entry(index: usize, entry: &PatchEntry) -> Result<Update<'_>, CartesianTreeError> {
+    let field = |name: &str| format!("$[{index}].{name}");
+    let position = entry.position.map(Vector3::from);
+    if let Some(position) = &position {
+        check_position(&field("position"), position)?;
+    }
+    let orientation = match (entry.rpy, entry.orientation) {
+        (Some(_), Some(_)) => {
+            return Err(CartesianTreeError::InvalidDocument {
+                path: format!("$[{index}]"),
+                reason: "'rpy' and 'orientation' are mutually exclusive".to_string(),
+            });
+        }
+        (Some(rpy), None) => Some((field("rpy"), Rotation::from(rpy).as_quaternion())),
+        (None, Some(quaternion)) => Some((
+            field("orientation"),
+            Rotation::from(quaternion).as_quaternion(),
+        )),
+        (None, None) => None,
+    };
+    if let Some((field, orientation)) = &orientation {
+        check_orientation(field, orientation)?;
+    }
+    Ok(Update {
+        path: &entry.path,
+        position,
+        orientation: orientation.map(|(_, orientation)| orientation),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn build_tree() -> Frame {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "arm",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        arm.add_child(
+            "flange",
+            Vector3::new(0.0, 0.0, 0.5),
+            UnitQuaternion::from_euler_angles(0.0, 0.0, 0.3),
+        )
+        .unwrap()
+        .add_child(
+            "tool",
+            Vector3::new(0.0, 0.0, 0.1),
+            UnitQuaternion::identity(),
+        )
+        .unwrap();
+        root
+    }
+
+    #[test]
+    fn patches_set_position_or_orientation_only() {
+        let root = build_tree();
+        let flange = root.resolve_path("arm/flange").unwrap();
+        let tool = root.resolve_path("arm/flange/tool").unwrap();
+        let arm = root.resolve_path("arm").unwrap();
+        let patch = r#"[
+            {"path": "arm/flange", "position": [0.0, 0.1, 0.5]},
+            {"path": "arm/flange/tool", "rpy": [0.0, 0.0, 1.5]},
+            {"path": "./arm/flange/../", "orientation": [0.0, 0.0, 0.0, 2.0]},
+            {"path": "arm"}
+        ]"#;
+        root.apply_patch(patch, MissingFramePolicy::Reject).unwrap();
+
+        assert_eq!(flange.position(), Vector3::new(0.0, 0.1, 0.5));
+        assert_relative_eq!(
+            flange.orientation().as_quaternion(),
+            UnitQuaternion::from_euler_angles(0.0, 0.0, 0.3),
+            epsilon = 1e-12
+        );
+        assert_eq!(tool.position(), Vector3::new(0.0, 0.0, 0.1));
+        assert_relative_eq!(
+            tool.orientation().as_quaternion(),
+            UnitQuaternion::from_euler_angles(0.0, 0.0, 1.5),
+            epsilon = 1e-12
+        );
+        assert_eq!(arm.position(), Vector3::new(1.0, 0.0, 0.0));
+        assert_relative_eq!(
+            arm.orientation().as_quaternion(),
+            UnitQuaternion::identity(),
+            epsilon = 1e-12
+        );
+        // Patches are relative to the frame they are applied to.
+        flange
+            .apply_patch(
+                r#"[{"path": "tool", "position": [0.0, 0.0, 0.2]}]"#,
+                MissingFramePolicy::Reject,
+            )
+            .unwrap();
+        assert_eq!(tool.position(), Vector3::new(0.0, 0.0, 0.2));
+    }
+
+    #[test]
+    fn unknown_paths_are_reported_together() {
+        let root = build_tree();
+        let flange = root.resolve_path("arm/flange").unwrap();
+        let patch = r#"[
+            {"path": "arm/flange", "position": [9.0, 9.0, 9.0]},
+            {"path": "arm/gripper", "position": [0.0, 0.0, 0.2]},
+            {"path": "arm/flange/tool"},
+            {"path": "camera/lens", "rpy": [0.1, 0.0, 0.0]}
+        ]"#;
+
+        let Err(CartesianTreeError::MissingFrames { paths }) =
+            root.apply_patch(patch, MissingFramePolicy::Reject)
+        else {
+            panic!("unknown paths must be rejected");
+        };
+        assert_eq!(paths, ["arm/gripper", "camera/lens"]);
+        assert_eq!(flange.position(), Vector3::new(0.0, 0.0, 0.5));
+
+        root.apply_patch(patch, MissingFramePolicy::Create).unwrap();
+        assert_eq!(flange.position(), Vector3::new(9.0, 9.0, 9.0));
+        let gripper = root.resolve_path("arm/gripper").unwrap();
+        assert_eq!(gripper.position(), Vector3::new(0.0, 0.0, 0.2));
+        let camera = root.resolve_path("camera").unwrap();
+        assert_eq!(
+            camera.transformation().unwrap(),
+            nalgebra::Isometry3::identity()
+        );
+        assert_relative_eq!(
+            root.resolve_path("camera/lens")
+                .unwrap()
+                .orientation()
+                .as_quaternion(),
+            UnitQuaternion::from_euler_angles(0.1, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn invalid_patches_change_nothing() {
+        let root = build_tree();
+        let arm = root.resolve_path("arm").unwrap();
+        let cases = [
+            r#"[{"path": "arm", "rotation": [0.0, 0.0, 0.0]}]"#,
+            r#"[{"path": "arm", "rpy": [0.0, 0.0, 0.0], "orientation": [0.0, 0.0, 0.0, 1.0]}]"#,
+            r#"[{"path": "arm", "position": [1.0, 1.0, 1.0]}, {"path": ".", "position": [0.0, 0.0, 1.0]}]"#,
+            r#"[{"path": "arm", "position": [1.0, 1.0, 1.0]}, {"path": "new/ bad"}]"#,
+            r#"[{"path": "arm", "position": [1.0, 1.0, 1.0]}, {"path": "new/../arm"}]"#,
+            r#"[{"path": "arm", "orientation": [0.0, 0.0, 0.0, 0.0]}]"#,
+            r#"{"path": "arm"}"#,
+        ];
+        for patch in cases {
+            assert!(
+                root.apply_patch(patch, MissingFramePolicy::Create).is_err(),
+                "{patch}"
+            );
+        }
+        assert_eq!(arm.position(), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(root.iter_dfs().count(), 4);
+        assert!(matches!(
+            root.apply_patch(
+                r#"[{"path": "..", "position": [0.0, 0.0, 0.0]}]"#,
+                MissingFramePolicy::Create
+            ),
+            Err(CartesianTreeError::PathNotFound { .. })
+        ));
+    }
+}
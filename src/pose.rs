@@ -1,59 +1,167 @@
 use crate::CartesianTreeError;
-use crate::frame::{Frame, FrameData};
+use crate::calibration::weighted_average_rotations;
+use crate::frame::{DeltaSpace, Frame, FrameData, write_transform};
 use crate::lazy_access::{LazyRotation, LazyTranslation};
 use crate::rotation::Rotation;
-use crate::tree::Walking;
-use nalgebra::{Isometry3, Translation3, Vector3};
-use std::cell::RefCell;
-use std::ops::{Add, Mul, Sub};
-use std::rc::Weak;
+use crate::scalar::Real;
+use crate::validation::{check_orientation, check_position};
+use alloc::rc::{Rc, Weak};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::cell::{Cell, RefCell};
+use core::ops::{Add, Mul, Sub};
+use nalgebra::{
+    Isometry3, Matrix4, Matrix6, Point3, Similarity3, Translation3, UnitQuaternion, Vector3,
+};
 
 /// Use [`Frame::add_pose`] to create a new pose.
-#[derive(Clone, Debug)]
-pub struct Pose {
+#[derive(Debug)]
+pub struct Pose<T: Real = f64> {
     /// Reference to the parent frame.
-    parent: Weak<RefCell<FrameData>>,
+    parent: Weak<RefCell<FrameData<T>>>,
+    /// The name of the parent frame when the pose was created, to report it once dropped.
+    frame_name: String,
     /// Transformation from this frame to its parent frame.
-    transform_to_parent: Isometry3<f64>,
+    transform_to_parent: Isometry3<T>,
+    /// Uncertainty of the transformation, see [`Pose::covariance`].
+    covariance: Option<Matrix6<T>>,
+    /// The entry of this pose in the registry of its frame, see [`Frame::poses`]; `None` for
+    /// the copies returned from there.
+    entry: Option<Rc<PoseEntry<T>>>,
 }
 
-impl Pose {
+/// The state of a pose as seen by its frame, updated whenever the pose changes.
+#[derive(Debug)]
+pub(crate) struct PoseEntry<T: Real> {
+    transform_to_parent: Cell<Isometry3<T>>,
+    covariance: Cell<Option<Matrix6<T>>>,
+}
+
+/// Summarizes the pose as the name of its frame and its transform to it, e.g.
+/// `pose in camera: 0.0000 0.0000 0.5000 / 0.0000 0.0000 1.5708`.
+///
+/// The transform is written like the one of a frame, see the
+/// [`Display`](core::fmt::Display) of [`Frame`], including the precision and the alternate
+/// flag `{:#}`. The Python bindings convert poses to strings alike.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::Frame;
+/// use nalgebra::{UnitQuaternion, Vector3};
+///
+/// let camera = Frame::new_origin("camera").unwrap();
+/// let pose = camera.add_pose(Vector3::new(0.0, 0.0, 0.5), UnitQuaternion::identity()).unwrap();
+/// assert_eq!(pose.to_string(), "pose in camera: 0.0000 0.0000 0.5000 / 0.0000 0.0000 0.0000");
+/// assert_eq!(format!("{pose:#}"), "pose in camera: 0 0 0.5 / 0 0 0");
+/// ```
+impl<T: Real> core::fmt::Display for Pose<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "pose in {}: ", self.frame_name)?;
+        write_transform(f, &self.transform_to_parent)
+    }
+}
+
+impl<T: Real> Clone for Pose<T> {
+    /// Returns a copy of this pose, registered with its frame like any other pose.
+    fn clone(&self) -> Self {
+        Self::attached(
+            self.parent.clone(),
+            self.frame_name.clone(),
+            self.transform_to_parent,
+            self.covariance,
+        )
+    }
+}
+
+impl<T: Real> Pose<T> {
     /// Creates a new pose relative to a frame.
     ///
     /// This function is intended for internal use. To create a pose associated with a frame,
     /// use [`Frame::add_pose`], which handles the association safely.
     pub(crate) fn new(
-        frame: Weak<RefCell<FrameData>>,
-        position: Vector3<f64>,
-        orientation: impl Into<Rotation>,
+        frame: &Frame<T>,
+        position: Vector3<T>,
+        orientation: impl Into<Rotation<T>>,
     ) -> Self {
-        Self {
-            parent: frame,
-            transform_to_parent: Isometry3::from_parts(
+        Self::attached(
+            frame.downgrade(),
+            frame.name(),
+            Isometry3::from_parts(
                 Translation3::from(position),
                 orientation.into().as_quaternion(),
             ),
+            None,
+        )
+    }
+
+    /// Creates a pose and registers it with its frame, unless the frame has been dropped.
+    // Covariances are mostly `None`, so they are passed by value like in `set_covariance`.
+    #[allow(clippy::large_types_passed_by_value)]
+    fn attached(
+        parent: Weak<RefCell<FrameData<T>>>,
+        frame_name: String,
+        transform_to_parent: Isometry3<T>,
+        covariance: Option<Matrix6<T>>,
+    ) -> Self {
+        let entry = Rc::new(PoseEntry {
+            transform_to_parent: Cell::new(transform_to_parent),
+            covariance: Cell::new(covariance),
+        });
+        if let Some(frame) = parent.upgrade() {
+            frame.borrow().register_pose(&entry);
+        }
+        Self {
+            parent,
+            frame_name,
+            transform_to_parent,
+            covariance,
+            entry: Some(entry),
+        }
+    }
+
+    /// Returns an unregistered copy of the pose behind `entry`, a pose of `frame`.
+    pub(crate) fn from_entry(frame: &Frame<T>, entry: &PoseEntry<T>) -> Self {
+        Self {
+            parent: frame.downgrade(),
+            frame_name: frame.name(),
+            transform_to_parent: entry.transform_to_parent.get(),
+            covariance: entry.covariance.get(),
+            entry: None,
+        }
+    }
+
+    /// Passes the current state of this pose on to its registry entry.
+    fn publish(&self) {
+        if let Some(entry) = &self.entry {
+            entry.transform_to_parent.set(self.transform_to_parent);
+            entry.covariance.set(self.covariance);
         }
     }
 
     /// Returns the parent frame of this pose.
     ///
-    /// # Returns
-    /// `Some(Frame)` if the parent frame is still valid, or `None` if the frame
-    /// has been dropped or no longer exists.
+    /// # Errors
+    /// Returns [`CartesianTreeError::FrameDropped`] if the frame has been dropped, e.g. because
+    /// no handle to its tree is left.
     ///
     /// # Example
     /// ```
     /// use cartesian_tree::Frame;
     /// use nalgebra::{Vector3, UnitQuaternion};
     ///
-    /// let frame = Frame::new_origin("base");
-    /// let pose = frame.add_pose(Vector3::new(0.0, 0.0, 0.0), UnitQuaternion::identity());
+    /// let frame = Frame::new_origin("base").unwrap();
+    /// let pose = frame.add_pose(Vector3::new(0.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
     /// assert_eq!(pose.frame().unwrap().name(), "base");
     /// ```
-    #[must_use]
-    pub fn frame(&self) -> Option<Frame> {
-        self.parent.upgrade().map(|data| Frame { data })
+    pub fn frame(&self) -> Result<Frame<T>, CartesianTreeError> {
+        self.parent
+            .upgrade()
+            .map(Frame::from_data)
+            .ok_or(CartesianTreeError::FrameDropped)
     }
 
     /// Returns the transformation from this pose to its parent frame.
@@ -61,7 +169,7 @@ impl Pose {
     /// # Returns
     /// The transformation of the pose in its parent frame.
     #[must_use]
-    pub const fn transformation(&self) -> Isometry3<f64> {
+    pub const fn transformation(&self) -> Isometry3<T> {
         self.transform_to_parent
     }
 
@@ -69,7 +177,7 @@ impl Pose {
     /// # Returns
     /// The position of the pose in its parent frame.
     #[must_use]
-    pub const fn position(&self) -> Vector3<f64> {
+    pub const fn position(&self) -> Vector3<T> {
         self.transform_to_parent.translation.vector
     }
 
@@ -77,7 +185,7 @@ impl Pose {
     /// # Returns
     /// The orientation of the pose in its parent frame.
     #[must_use]
-    pub fn orientation(&self) -> Rotation {
+    pub fn orientation(&self) -> Rotation<T> {
         self.transform_to_parent.rotation.into()
     }
 
@@ -87,20 +195,259 @@ impl Pose {
     /// - `position`: A 3D vector representing the new translational offset from the parent.
     /// - `orientation`: An orientation convertible into a unit quaternion for new orientational offset from the parent.
     ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::NonFiniteValue`] if a component of the position or
+    /// orientation is NaN or infinite; the pose is left unchanged.
+    ///
     /// # Example
     /// ```
     /// use cartesian_tree::Frame;
     /// use nalgebra::{Vector3, UnitQuaternion};
     ///
-    /// let root = Frame::new_origin("root");
-    /// let mut pose = root.add_pose(Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity());
-    /// pose.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity());
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let mut pose = root.add_pose(Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity()).unwrap();
+    /// pose.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
     /// ```
-    pub fn set(&mut self, position: Vector3<f64>, orientation: impl Into<Rotation>) {
-        self.transform_to_parent = Isometry3::from_parts(
-            Translation3::from(position),
-            orientation.into().as_quaternion(),
-        );
+    pub fn set(
+        &mut self,
+        position: Vector3<T>,
+        orientation: impl Into<Rotation<T>>,
+    ) -> Result<(), CartesianTreeError> {
+        let orientation = orientation.into().as_quaternion();
+        check_position("position", &position)?;
+        check_orientation("orientation", &orientation)?;
+        self.transform_to_parent = Isometry3::from_parts(Translation3::from(position), orientation);
+        self.publish();
+        Ok(())
+    }
+
+    /// Transforms this pose into the coordinate system of the given target frame.
+    ///
+    /// # Arguments
+    /// * `target` - The frame to express this pose in.
+    ///
+    /// # Returns
+    /// A new `Pose`, expressed in the `target` frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame hierarchy cannot be resolved (e.g., due to dropped frames).
+    /// - There is no common ancestor between `self` and `target`.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let pose = root.add_pose(Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity()).unwrap();
+    /// let new_frame = root.add_child("child", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// let pose_in_new_frame = pose.in_frame(&new_frame);
+    /// ```
+    pub fn in_frame(&self, target: &Frame<T>) -> Result<Self, CartesianTreeError> {
+        let source_data =
+            self.parent
+                .upgrade()
+                .ok_or_else(|| CartesianTreeError::WeakUpgradeFailed {
+                    frame: self.frame_name.clone(),
+                    operation: format!(
+                        "in_frame walk from '{}' to '{}'",
+                        self.frame_name,
+                        target.name()
+                    ),
+                })?;
+        let source = Frame::link(source_data);
+        Ok(self.moved_to(target, &source.similarity_to(target)?))
+    }
+
+    /// Returns this pose expressed in `target`, given the similarity from its frame to `target`.
+    ///
+    /// A scaling only moves the position of the pose; its orientation stays a rotation.
+    pub(crate) fn moved_to(&self, target: &Frame<T>, similarity: &Similarity3<T>) -> Self {
+        Self::attached(
+            target.downgrade(),
+            target.name(),
+            (similarity * self.transform_to_parent).isometry,
+            self.covariance.as_ref().map(|covariance| {
+                transform_covariance(covariance, &similarity.isometry, similarity.scaling())
+            }),
+        )
+    }
+
+    /// Re-parents this pose onto `new_parent` in place, keeping its world location.
+    ///
+    /// Unlike [`Pose::in_frame`], which returns a new pose, this updates the pose itself:
+    /// afterwards [`Pose::frame`] returns `new_parent` and the transformation is expressed
+    /// relative to it.
+    ///
+    /// # Arguments
+    /// * `new_parent` - The frame to attach this pose to.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame hierarchy cannot be resolved (e.g., due to dropped frames).
+    /// - There is no common ancestor between `self` and `new_parent`.
+    ///
+    /// The pose is left unchanged on error.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let mut pose = root.add_pose(Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity()).unwrap();
+    /// let child = root.add_child("child", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// pose.rebase(&child).unwrap();
+    ///
+    /// assert_eq!(pose.frame().unwrap().name(), "child");
+    /// assert_eq!(pose.position(), Vector3::new(-1.0, 0.0, 1.0));
+    /// ```
+    pub fn rebase(&mut self, new_parent: &Frame<T>) -> Result<(), CartesianTreeError> {
+        *self = self.in_frame(new_parent)?;
+        Ok(())
+    }
+
+    /// Returns `true` if this pose and `other` are expressed in the same frame.
+    pub(crate) fn same_frame(&self, other: &Self) -> bool {
+        Weak::ptr_eq(&self.parent, &other.parent)
+    }
+}
+
+impl Pose {
+    /// Returns the parent frame of this pose, or `None` if it has been dropped.
+    #[deprecated(note = "use `Pose::frame`, which reports a dropped frame as an error")]
+    #[must_use]
+    pub fn frame_opt(&self) -> Option<Frame> {
+        self.frame().ok()
+    }
+
+    /// Returns the 6x6 covariance of this pose, if one is set.
+    ///
+    /// The covariance describes a small motion `ξ = [ρ; φ]` applied in the parent frame, i.e.
+    /// the uncertain pose is `exp(ξ) * T` with the translation `ρ` and the rotation vector `φ`
+    /// both expressed in parent frame coordinates. Rows and columns are ordered as
+    /// `[translation; rotation vector]`.
+    ///
+    /// [`Pose::in_frame`], [`Pose::rebase`], [`Pose::compose`], [`Pose::inverse`] and
+    /// [`Pose::apply_in_parent_frame`] transform the covariance with the adjoint of the applied
+    /// isometry; motions in the local frame leave it unchanged. Setting the position or
+    /// orientation keeps the covariance as is, and poses combined from several inputs, such as
+    /// [`Pose::interpolate`] and [`Pose::mean`], have none.
+    #[must_use]
+    pub const fn covariance(&self) -> Option<Matrix6<f64>> {
+        self.covariance
+    }
+
+    /// Sets or clears the covariance of this pose, see [`Pose::covariance`].
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::NonFiniteValue`] if an entry of the covariance is NaN or
+    /// infinite; the pose is left unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Matrix6, UnitQuaternion, Vector3, Vector6};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let mut pose = root.add_pose(Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// let covariance = Matrix6::from_diagonal(&Vector6::new(1e-4, 1e-4, 1e-4, 1e-3, 1e-3, 1e-3));
+    /// pose.set_covariance(Some(covariance)).unwrap();
+    /// assert_eq!(pose.covariance(), Some(covariance));
+    /// ```
+    pub fn set_covariance(
+        &mut self,
+        covariance: Option<Matrix6<f64>>,
+    ) -> Result<(), CartesianTreeError> {
+        // Entries are stored column by column.
+        if let Some((index, value)) = covariance
+            .iter()
+            .flat_map(|matrix| matrix.iter())
+            .enumerate()
+            .find(|(_, value)| !value.is_finite())
+        {
+            return Err(CartesianTreeError::NonFiniteValue {
+                field: format!("covariance[{}][{}]", index % 6, index / 6),
+                value: *value,
+            });
+        }
+        self.covariance = covariance;
+        self.publish();
+        Ok(())
+    }
+
+    /// Returns the transformation from this pose to its parent frame as a 4x4 homogeneous
+    /// matrix.
+    #[must_use]
+    pub fn to_matrix(&self) -> Matrix4<f64> {
+        self.transform_to_parent.to_homogeneous()
+    }
+
+    /// Returns the orientation of this pose relative to its parent frame as roll, pitch and yaw
+    /// angles (in radians), see [`Rotation::as_rpy`].
+    #[must_use]
+    pub fn rpy(&self) -> Vector3<f64> {
+        self.orientation().as_rpy()
+    }
+
+    /// Sets the position of this pose relative to its parent frame, keeping its orientation.
+    ///
+    /// # Arguments
+    /// - `position`: The new translational offset from the parent.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::NonFiniteValue`] if a component of the position is NaN or
+    /// infinite; the pose is left unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::rotation::Rotation;
+    /// use nalgebra::Vector3;
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let mut pose = root.add_pose(Vector3::zeros(), Rotation::from_rpy(0.0, 0.0, 1.0)).unwrap();
+    /// pose.set_position(Vector3::new(1.0, 0.0, 0.0)).unwrap();
+    /// assert_eq!(pose.position(), Vector3::new(1.0, 0.0, 0.0));
+    /// assert_eq!(pose.rpy(), Vector3::new(0.0, 0.0, 1.0));
+    /// ```
+    pub fn set_position(&mut self, position: Vector3<f64>) -> Result<(), CartesianTreeError> {
+        check_position("position", &position)?;
+        self.transform_to_parent.translation.vector = position;
+        self.publish();
+        Ok(())
+    }
+
+    /// Sets the orientation of this pose relative to its parent frame, keeping its position.
+    ///
+    /// # Arguments
+    /// - `orientation`: The new orientational offset from the parent.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::NonFiniteValue`] if a component of the orientation is NaN
+    /// or infinite; the pose is left unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::rotation::Rotation;
+    /// use nalgebra::Vector3;
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let mut pose = root.add_pose(Vector3::new(1.0, 2.0, 3.0), Rotation::identity()).unwrap();
+    /// pose.set_orientation(Rotation::from_rpy(0.0, 0.0, 1.0)).unwrap();
+    /// assert_eq!(pose.position(), Vector3::new(1.0, 2.0, 3.0));
+    /// ```
+    pub fn set_orientation(
+        &mut self,
+        orientation: impl Into<Rotation>,
+    ) -> Result<(), CartesianTreeError> {
+        let orientation = orientation.into().as_quaternion();
+        check_orientation("orientation", &orientation)?;
+        self.transform_to_parent.rotation = orientation;
+        self.publish();
+        Ok(())
     }
 
     /// Applies the provided isometry interpreted in the parent frame to the pose.
@@ -113,12 +460,17 @@ impl Pose {
     /// use cartesian_tree::Frame;
     /// use nalgebra::{Isometry3, Translation3, Vector3, UnitQuaternion};
     ///
-    /// let root = Frame::new_origin("root");
-    /// let mut pose = root.add_pose(Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity());
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let mut pose = root.add_pose(Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity()).unwrap();
     /// pose.apply_in_parent_frame(&Isometry3::from_parts(Translation3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()));
     /// ```
     pub fn apply_in_parent_frame(&mut self, isometry: &Isometry3<f64>) {
         self.transform_to_parent = isometry * self.transform_to_parent;
+        self.covariance = self
+            .covariance
+            .as_ref()
+            .map(|covariance| transform_covariance(covariance, isometry, 1.0));
+        self.publish();
     }
 
     /// Applies the provided isometry interpreted in the body frame to this pose.
@@ -131,57 +483,591 @@ impl Pose {
     /// use cartesian_tree::Frame;
     /// use nalgebra::{Isometry3, Translation3, Vector3, UnitQuaternion};
     ///
-    /// let root = Frame::new_origin("root");
-    /// let mut pose = root.add_pose(Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity());
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let mut pose = root.add_pose(Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity()).unwrap();
     /// pose.apply_in_local_frame(&Isometry3::from_parts(Translation3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()));
     /// ```
     pub fn apply_in_local_frame(&mut self, isometry: &Isometry3<f64>) {
         self.transform_to_parent *= isometry;
+        self.publish();
     }
 
-    /// Transforms this pose into the coordinate system of the given target frame.
+    /// Moves this pose by `delta`, expressed in the local frame of the pose or in its frame.
+    ///
+    /// With `T` the transformation to the frame, [`DeltaSpace::Local`] sets it to `T * delta`,
+    /// like [`Pose::apply_in_local_frame`], and [`DeltaSpace::Parent`] to `delta * T`, like
+    /// [`Pose::apply_in_parent_frame`]. See [`DeltaSpace`] for how the results differ.
     ///
     /// # Arguments
-    /// * `target` - The frame to express this pose in.
+    /// - `delta`: The motion to apply.
+    /// - `space`: Where `delta` is expressed: in the pose itself, or in the frame of the pose.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::frame::DeltaSpace;
+    /// use nalgebra::{Isometry3, UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let mut pose = root
+    ///     .add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2))
+    ///     .unwrap();
+    /// pose.apply_delta(Isometry3::translation(0.1, 0.0, 0.0), DeltaSpace::Parent);
+    /// assert!((pose.position() - Vector3::new(1.1, 0.0, 0.0)).norm() < 1e-12);
+    /// ```
+    pub fn apply_delta(&mut self, delta: Isometry3<f64>, space: DeltaSpace) {
+        match space {
+            DeltaSpace::Local => self.apply_in_local_frame(&delta),
+            DeltaSpace::Parent => self.apply_in_parent_frame(&delta),
+        }
+    }
+
+    /// Chains `other` onto this pose.
+    ///
+    /// `other` is first expressed in the frame of this pose, then its transformation is applied
+    /// in the local frame of this pose.
+    ///
+    /// If either pose has a covariance (see [`Pose::covariance`]), the result carries the
+    /// first-order propagation `Σ = Σ_self + Ad(T_self) Σ_other Ad(T_self)ᵀ`, which treats both
+    /// poses as independent and neglects terms of second order in the uncertainties.
+    ///
+    /// # Arguments
+    /// * `other` - The pose to chain onto this pose.
     ///
     /// # Returns
-    /// A new `Pose`, expressed in the `target` frame.
+    /// A new `Pose` in the frame of this pose.
     ///
     /// # Errors
     /// Returns a [`CartesianTreeError`] if:
-    /// - The frame hierarchy cannot be resolved (e.g., due to dropped frames).
-    /// - There is no common ancestor between `self` and `target`.
+    /// - The frame of either pose has been dropped.
+    /// - There is no common ancestor between the frames of the two poses.
     ///
     /// # Example
     /// ```
     /// use cartesian_tree::Frame;
     /// use nalgebra::{Vector3, UnitQuaternion};
     ///
-    /// let root = Frame::new_origin("root");
-    /// let pose = root.add_pose(Vector3::new(0.0, 0.0, 1.0), UnitQuaternion::identity());
-    /// let new_frame = root.add_child("child", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
-    /// let pose_in_new_frame = pose.in_frame(&new_frame);
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let pose = root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// let offset = root.add_pose(Vector3::new(0.0, 2.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// let composed = pose.compose(&offset).unwrap();
+    /// assert_eq!(composed.position(), Vector3::new(1.0, 2.0, 0.0));
     /// ```
-    pub fn in_frame(&self, target: &Frame) -> Result<Self, CartesianTreeError> {
-        let source_data = self
-            .parent
-            .upgrade()
-            .ok_or(CartesianTreeError::WeakUpgradeFailed())?;
-        let source = Frame { data: source_data };
-        let ancestor = source
-            .lca_with(target)
-            .ok_or_else(|| CartesianTreeError::NoCommonAncestor(source.name(), target.name()))?;
+    pub fn compose(&self, other: &Self) -> Result<Self, CartesianTreeError> {
+        let frame = self.frame()?;
+        let other_in_frame = other.in_frame(&frame)?;
+        let other_covariance = other_in_frame
+            .covariance
+            .as_ref()
+            .map(|covariance| transform_covariance(covariance, &self.transform_to_parent, 1.0));
+        let covariance = match (self.covariance, other_covariance) {
+            (Some(own), Some(other)) => Some(own + other),
+            (own, other) => own.or(other),
+        };
+        Ok(Self::attached(
+            self.parent.clone(),
+            self.frame_name.clone(),
+            self.transform_to_parent * other_in_frame.transform_to_parent,
+            covariance,
+        ))
+    }
+
+    /// Adds a child frame at this pose to the frame of the pose.
+    ///
+    /// The new frame has the transformation of the pose, so other poses can then be defined
+    /// relative to it. The pose itself is not changed.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the new child frame.
+    ///
+    /// # Returns
+    /// The newly added child frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame of the pose has been dropped.
+    /// - The frame of the pose already has a child with the same name.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let grasp = root.add_pose(Vector3::new(0.2, 0.0, 0.1), UnitQuaternion::identity()).unwrap();
+    /// let grasp_frame = grasp.into_frame("grasp_1").unwrap();
+    /// assert_eq!(grasp_frame.position(), Vector3::new(0.2, 0.0, 0.1));
+    /// ```
+    pub fn into_frame(&self, name: impl Into<String>) -> Result<Frame, CartesianTreeError> {
+        let frame = self.frame()?;
+        frame.add_child(
+            name,
+            self.transform_to_parent.translation.vector,
+            self.transform_to_parent.rotation,
+        )
+    }
+
+    /// Interpolates between this pose (`t = 0`) and `other` (`t = 1`).
+    ///
+    /// `other` is expressed in the frame of this pose first. The position is interpolated
+    /// linearly and the orientation along the shortest arc (see [`Rotation::slerp`]). Values of
+    /// `t` outside `[0, 1]` extrapolate.
+    ///
+    /// # Returns
+    /// A new `Pose` in the frame of this pose.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `t` is NaN or infinite.
+    /// - The frame of either pose has been dropped.
+    /// - There is no common ancestor between the frames of the two poses.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let a = root.add_pose(Vector3::new(0.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// let b = root.add_pose(Vector3::new(2.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// assert_eq!(a.interpolate(&b, 0.25).unwrap().position(), Vector3::new(0.5, 0.0, 0.0));
+    /// ```
+    pub fn interpolate(&self, other: &Self, t: f64) -> Result<Self, CartesianTreeError> {
+        if !t.is_finite() {
+            return Err(CartesianTreeError::NonFiniteValue {
+                field: "t".to_string(),
+                value: t,
+            });
+        }
+        let other = self.expressed_alongside(other)?;
+        Ok(Self::attached(
+            self.parent.clone(),
+            self.frame_name.clone(),
+            interpolate_isometry(&self.transform_to_parent, &other, t),
+            None,
+        ))
+    }
 
-        // Transformation from source frame up to ancestor
-        let tf_up = source.walk_up_and_transform(&ancestor)? * self.transform_to_parent;
+    /// Averages several measurements of the same pose.
+    ///
+    /// Equivalent to [`Pose::weighted_mean`] with equal weights.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `poses` is empty.
+    /// - The frame of a pose has been dropped.
+    /// - There is no common ancestor between the frames of the poses.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::{Frame, Pose};
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let measurements = [
+    ///     root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::from_euler_angles(0.0, 0.0, 0.1)).unwrap(),
+    ///     root.add_pose(Vector3::new(3.0, 0.0, 0.0), UnitQuaternion::from_euler_angles(0.0, 0.0, 0.3)).unwrap(),
+    /// ];
+    /// let mean = Pose::mean(&measurements).unwrap();
+    /// assert_eq!(mean.position(), Vector3::new(2.0, 0.0, 0.0));
+    /// assert!((mean.rpy().z - 0.2).abs() < 1e-12);
+    /// ```
+    pub fn mean(poses: &[Self]) -> Result<Self, CartesianTreeError> {
+        Self::weighted_mean(poses, &vec![1.0; poses.len()])
+    }
+
+    /// Computes the weighted average of several measurements of the same pose.
+    ///
+    /// All poses are expressed in the frame of the first pose. Positions are averaged
+    /// arithmetically. Orientations are averaged as the dominant eigenvector of the weighted sum
+    /// of quaternion outer products, which is insensitive to the sign of the quaternions. A single
+    /// pose is returned unchanged.
+    ///
+    /// # Arguments
+    /// - `poses`: The measurements to average.
+    /// - `weights`: The non-negative weight of each measurement, in the order of `poses`.
+    ///
+    /// # Returns
+    /// The average pose in the frame of the first pose.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `poses` is empty or the weights do not sum to a positive finite value.
+    /// - A weight is negative or not finite.
+    /// - `weights` and `poses` differ in length.
+    /// - The frame of a pose has been dropped.
+    /// - There is no common ancestor between the frames of the poses.
+    pub fn weighted_mean(poses: &[Self], weights: &[f64]) -> Result<Self, CartesianTreeError> {
+        if weights.len() != poses.len() {
+            return Err(CartesianTreeError::LengthMismatch {
+                expected: poses.len(),
+                found: weights.len(),
+            });
+        }
+        let Some(first) = poses.first() else {
+            return Err(CartesianTreeError::DegenerateInput {
+                reason: "cannot average an empty set of poses".to_string(),
+            });
+        };
+        if let Some((index, weight)) = weights
+            .iter()
+            .enumerate()
+            .find(|(_, weight)| !(weight.is_finite() && **weight >= 0.0))
+        {
+            return Err(CartesianTreeError::DegenerateInput {
+                reason: format!("weight {index} is {weight}, expected a non-negative number"),
+            });
+        }
+        let total: f64 = weights.iter().sum();
+        if !(total.is_finite() && total > 0.0) {
+            return Err(CartesianTreeError::DegenerateInput {
+                reason: "the weights must sum to a positive finite value".to_string(),
+            });
+        }
+        if poses.len() == 1 {
+            // Fails for a dropped frame, like larger sets do.
+            first.frame()?;
+            return Ok(first.clone());
+        }
+
+        let transforms = poses
+            .iter()
+            .map(|pose| first.expressed_alongside(pose))
+            .collect::<Result<Vec<_>, _>>()?;
+        let position = transforms
+            .iter()
+            .zip(weights)
+            .map(|(transform, weight)| transform.translation.vector * *weight)
+            .sum::<Vector3<f64>>()
+            / total;
+        let mut orientation = weighted_average_rotations(
+            transforms
+                .iter()
+                .zip(weights)
+                .map(|(transform, weight)| (transform.rotation, *weight)),
+        );
+        // Keep the hemisphere of the first pose, so results are stable for equal inputs.
+        if orientation
+            .coords
+            .dot(&first.transform_to_parent.rotation.coords)
+            < 0.0
+        {
+            orientation = UnitQuaternion::new_unchecked(-orientation.into_inner());
+        }
+        Ok(Self::attached(
+            first.parent.clone(),
+            first.frame_name.clone(),
+            Isometry3::from_parts(Translation3::from(position), orientation),
+            None,
+        ))
+    }
+
+    /// Returns the Euclidean distance between the positions of this pose and `other`.
+    ///
+    /// `other` is expressed in the frame of this pose first, so poses in different frames of
+    /// the same tree are compared correctly.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame of either pose has been dropped.
+    /// - There is no common ancestor between the frames of the two poses.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let a = root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// let b = root.add_pose(Vector3::new(1.0, 3.0, 4.0), UnitQuaternion::identity()).unwrap();
+    /// assert_eq!(a.translational_distance_to(&b).unwrap(), 5.0);
+    /// ```
+    pub fn translational_distance_to(&self, other: &Self) -> Result<f64, CartesianTreeError> {
+        let other = self.expressed_alongside(other)?;
+        Ok((other.translation.vector - self.transform_to_parent.translation.vector).norm())
+    }
+
+    /// Returns the angle in radians of the rotation between the orientations of this pose and
+    /// `other`, in `[0, pi]`.
+    ///
+    /// `other` is expressed in the frame of this pose first. The result does not depend on the
+    /// sign of the underlying quaternions.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame of either pose has been dropped.
+    /// - There is no common ancestor between the frames of the two poses.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let a = root.add_pose(Vector3::zeros(), UnitQuaternion::identity()).unwrap();
+    /// let b = root.add_pose(Vector3::zeros(), UnitQuaternion::from_euler_angles(0.0, 0.0, 0.5)).unwrap();
+    /// assert!((a.angular_distance_to(&b).unwrap() - 0.5).abs() < 1e-12);
+    /// ```
+    pub fn angular_distance_to(&self, other: &Self) -> Result<f64, CartesianTreeError> {
+        let other = self.expressed_alongside(other)?;
+        Ok(Rotation::from(self.transform_to_parent.rotation).angle_to(&other.rotation.into()))
+    }
+
+    /// Checks whether this pose and `other` are within the given tolerances of each other.
+    ///
+    /// # Arguments
+    /// * `other` - The pose to compare with.
+    /// * `lin_tol` - The maximal translational distance.
+    /// * `ang_tol` - The maximal angular distance in radians.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame of either pose has been dropped.
+    /// - There is no common ancestor between the frames of the two poses.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let a = root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// let b = root.add_pose(Vector3::new(1.0, 0.0, 1e-9), UnitQuaternion::identity()).unwrap();
+    /// assert!(a.approx_eq(&b, 1e-6, 1e-6).unwrap());
+    /// ```
+    pub fn approx_eq(
+        &self,
+        other: &Self,
+        lin_tol: f64,
+        ang_tol: f64,
+    ) -> Result<bool, CartesianTreeError> {
+        Ok(self.translational_distance_to(other)? <= lin_tol
+            && self.angular_distance_to(other)? <= ang_tol)
+    }
+
+    /// Returns the transformation of `other` expressed in the frame of this pose.
+    fn expressed_alongside(&self, other: &Self) -> Result<Isometry3<f64>, CartesianTreeError> {
+        let frame = self.frame()?;
+        Ok(other.in_frame(&frame)?.transform_to_parent)
+    }
+
+    /// Maps a point from the local coordinates of this pose into its frame.
+    ///
+    /// # Arguments
+    /// * `point` - The point relative to this pose.
+    ///
+    /// # Returns
+    /// The point expressed in the frame of this pose.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Point3, Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let pose = root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// assert_eq!(pose.transform_point(&Point3::new(0.0, 1.0, 0.0)), Point3::new(1.0, 1.0, 0.0));
+    /// ```
+    #[must_use]
+    pub fn transform_point(&self, point: &Point3<f64>) -> Point3<f64> {
+        self.transform_to_parent.transform_point(point)
+    }
+
+    /// Maps a direction from the local coordinates of this pose into its frame.
+    ///
+    /// Only the orientation of the pose is applied, its position is ignored.
+    ///
+    /// # Arguments
+    /// * `vector` - The direction relative to this pose.
+    ///
+    /// # Returns
+    /// The direction expressed in the frame of this pose.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let pose = root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// assert_eq!(pose.transform_vector(&Vector3::y()), Vector3::y());
+    /// ```
+    #[must_use]
+    pub fn transform_vector(&self, vector: &Vector3<f64>) -> Vector3<f64> {
+        self.transform_to_parent.transform_vector(vector)
+    }
+
+    /// Returns the inverse of this pose, expressed in the same frame.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Vector3, UnitQuaternion};
+    ///
+    /// let root = Frame::new_origin("root").unwrap();
+    /// let pose = root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// assert_eq!(pose.inverse().position(), Vector3::new(-1.0, 0.0, 0.0));
+    /// ```
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        let inverse = self.transform_to_parent.inverse();
+        Self::attached(
+            self.parent.clone(),
+            self.frame_name.clone(),
+            inverse,
+            self.covariance
+                .as_ref()
+                .map(|covariance| transform_covariance(covariance, &inverse, 1.0)),
+        )
+    }
+}
+
+/// Returns the adjoint of `isometry`, which maps a small motion `[translation; rotation vector]`
+/// in the source frame of the isometry to the equivalent motion in its target frame.
+///
+/// A `scale` of the source coordinates, as in a similarity, stretches the translational part.
+fn adjoint<T: Real>(isometry: &Isometry3<T>, scale: T) -> Matrix6<T> {
+    let rotation = isometry.rotation.to_rotation_matrix().into_inner();
+    let mut adjoint = Matrix6::zeros();
+    adjoint
+        .fixed_view_mut::<3, 3>(0, 0)
+        .copy_from(&(rotation * scale));
+    adjoint
+        .fixed_view_mut::<3, 3>(0, 3)
+        .copy_from(&(isometry.translation.vector.cross_matrix() * rotation));
+    adjoint.fixed_view_mut::<3, 3>(3, 3).copy_from(&rotation);
+    adjoint
+}
+
+/// Re-expresses a covariance after the pose has been moved by `isometry` in its parent frame,
+/// with the parent coordinates scaled by `scale`.
+fn transform_covariance<T: Real>(
+    covariance: &Matrix6<T>,
+    isometry: &Isometry3<T>,
+    scale: T,
+) -> Matrix6<T> {
+    let adjoint = adjoint(isometry, scale);
+    adjoint * covariance * adjoint.transpose()
+}
+
+/// Interpolates linearly between the translations and along the shortest arc between the
+/// rotations of two isometries.
+pub(crate) fn interpolate_isometry(
+    from: &Isometry3<f64>,
+    to: &Isometry3<f64>,
+    t: f64,
+) -> Isometry3<f64> {
+    let rotation = Rotation::from(from.rotation).slerp(&Rotation::from(to.rotation), t);
+    Isometry3::from_parts(
+        Translation3::from(from.translation.vector.lerp(&to.translation.vector, t)),
+        rotation.as_quaternion(),
+    )
+}
+
+/// Poses are equal if they are expressed in the same frame and their transforms to it are equal,
+/// with `q` and `-q` as the same orientation. Covariances are not compared.
+///
+/// Poses in different frames are never equal, even if they coincide in space: equality does not
+/// depend on the current transforms of the tree. Compare such poses in a common frame, e.g. with
+/// [`Pose::approx_eq`] or [`assert_pose_eq!`](crate::assert_pose_eq), which express one in the
+/// frame of the other.
+impl<T: Real> PartialEq for Pose<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.same_frame(other) && self.transform_to_parent == other.transform_to_parent
+    }
+}
+
+/// The default tolerance of [`assert_pose_eq!`](crate::assert_pose_eq), in meters and radians.
+pub const DEFAULT_POSE_TOLERANCE: f64 = 1e-9;
+
+/// Asserts that two poses are within a tolerance of each other, see [`Pose::approx_eq`].
+///
+/// The right pose is expressed in the frame of the left one, so poses in different frames of
+/// the same tree are compared where they are in space. The tolerances default to
+/// [`DEFAULT_POSE_TOLERANCE`](crate::pose::DEFAULT_POSE_TOLERANCE); pass the translational
+/// tolerance and the angular tolerance in radians to override them.
+///
+/// # Panics
+/// Panics if the poses are further apart than the tolerances, or if they cannot be compared,
+/// e.g. because their frames belong to different trees.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::{Frame, assert_pose_eq};
+/// use nalgebra::{UnitQuaternion, Vector3};
+///
+/// let world = Frame::new_origin("world").unwrap();
+/// let table = world
+///     .add_child("table", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+///     .unwrap();
+/// let cup = table.add_pose(Vector3::new(0.0, 0.0, 0.5), UnitQuaternion::identity()).unwrap();
+/// let expected = world.add_pose(Vector3::new(1.0, 0.0, 0.5), UnitQuaternion::identity()).unwrap();
+/// assert_pose_eq!(cup, expected);
+/// assert_pose_eq!(cup, expected, 1e-3, 1e-3);
+/// ```
+#[macro_export]
+macro_rules! assert_pose_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::pose::assert_pose_eq(
+            &$left,
+            &$right,
+            $crate::pose::DEFAULT_POSE_TOLERANCE,
+            $crate::pose::DEFAULT_POSE_TOLERANCE,
+        )
+    };
+    ($left:expr, $right:expr, $lin_tol:expr, $ang_tol:expr $(,)?) => {
+        $crate::pose::assert_pose_eq(&$left, &$right, $lin_tol, $ang_tol)
+    };
+}
+
+/// Implements [`assert_pose_eq!`](crate::assert_pose_eq).
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_pose_eq(left: &Pose, right: &Pose, lin_tol: f64, ang_tol: f64) {
+    let distances = left
+        .translational_distance_to(right)
+        .and_then(|distance| Ok((distance, left.angular_distance_to(right)?)));
+    let (distance, angle) = match distances {
+        Ok(distances) => distances,
+        Err(error) => panic!("assertion `left == right` failed: cannot compare the poses: {error}"),
+    };
+    let describe = |pose: &Pose| {
+        let position = pose.position();
+        let rpy = pose.orientation().as_rpy();
+        format!(
+            "position ({}, {}, {}), rpy ({}, {}, {}) in '{}'",
+            position.x, position.y, position.z, rpy.x, rpy.y, rpy.z, pose.frame_name
+        )
+    };
+    assert!(
+        distance <= lin_tol && angle <= ang_tol,
+        "assertion `left == right` failed: the poses are {distance} apart (tolerance {lin_tol}) \
+         and {angle} rad (tolerance {ang_tol})\n  left: {}\n right: {}",
+        describe(left),
+        describe(right),
+    );
+}
+
+impl Mul<Isometry3<f64>> for &Pose {
+    type Output = Pose;
+
+    /// Applies the isometry in the local frame of the pose, keeping its frame.
+    fn mul(self, rhs: Isometry3<f64>) -> Self::Output {
+        Pose::attached(
+            self.parent.clone(),
+            self.frame_name.clone(),
+            self.transform_to_parent * rhs,
+            self.covariance,
+        )
+    }
+}
 
-        // Transformation from target frame up to ancestor (to be inverted)
-        let tf_down = target.walk_up_and_transform(&ancestor)?;
+impl Mul<Isometry3<f64>> for Pose {
+    type Output = Self;
 
-        Ok(Self {
-            parent: target.downgrade(),
-            transform_to_parent: tf_down.inverse() * tf_up,
-        })
+    /// Applies the isometry in the local frame of the pose, keeping its frame.
+    fn mul(self, rhs: Isometry3<f64>) -> Self::Output {
+        &self * rhs
     }
 }
 
@@ -190,10 +1076,12 @@ impl Add<LazyTranslation> for &Pose {
 
     fn add(self, rhs: LazyTranslation) -> Self::Output {
         let parent = self.frame().unwrap();
-        let mut new_pose = parent.add_pose(
+        let mut new_pose = Pose::new(
+            &parent,
             self.transform_to_parent.translation.vector,
             self.transform_to_parent.rotation,
         );
+        new_pose.covariance = self.covariance;
         new_pose.apply_in_parent_frame(&rhs.inner);
         new_pose
     }
@@ -204,10 +1092,12 @@ impl Sub<LazyTranslation> for &Pose {
 
     fn sub(self, rhs: LazyTranslation) -> Self::Output {
         let parent = self.frame().unwrap();
-        let mut new_pose = parent.add_pose(
+        let mut new_pose = Pose::new(
+            &parent,
             self.transform_to_parent.translation.vector,
             self.transform_to_parent.rotation,
         );
+        new_pose.covariance = self.covariance;
         new_pose.apply_in_parent_frame(&rhs.inner.inverse());
         new_pose
     }
@@ -218,10 +1108,12 @@ impl Mul<LazyRotation> for &Pose {
 
     fn mul(self, rhs: LazyRotation) -> Self::Output {
         let parent = self.frame().unwrap();
-        let mut new_pose = parent.add_pose(
+        let mut new_pose = Pose::new(
+            &parent,
             self.transform_to_parent.translation.vector,
             self.transform_to_parent.rotation,
         );
+        new_pose.covariance = self.covariance;
         new_pose.apply_in_local_frame(&rhs.inner);
         new_pose
     }
@@ -0,0 +1,517 @@
+//! Regions of space anchored to frames, for checks like "is the tool inside its allowed box".
+//!
+//! A [`Region`] pairs a [`RegionShape`] with the frame it is defined in. Poses are expressed in
+//! that frame before they are tested, so a region follows its frame when the frame moves or is
+//! reparented. Shapes can also be stored on frames by name, see [`Frame::store_region`], which
+//! includes them in [`Frame::to_json`] and restores them with [`Frame::from_json`] and
+//! [`Frame::apply_config`].
+
+use crate::CartesianTreeError;
+use crate::frame::{DISPLAY_PRECISION, Frame, StorePolicy, format_vector};
+use crate::mirror::MirrorPlane;
+use crate::pose::Pose;
+use crate::units::LengthUnit;
+use crate::validation::check_position;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use nalgebra::Vector3;
+
+/// The shape of a [`Region`], in the coordinates of its frame.
+///
+/// Shapes are closed: points on the boundary belong to them. In JSON documents, shapes are
+/// tagged with their kind, e.g. `{"box": {"min": [x, y, z], "max": [x, y, z]}}` or
+/// `{"sphere": {"center": [x, y, z], "radius": r}}`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RegionShape {
+    /// A box aligned with the axes of the frame, spanning from the corner `min` to `max`.
+    Box {
+        min: Vector3<f64>,
+        max: Vector3<f64>,
+    },
+    /// A ball around `center`.
+    Sphere { center: Vector3<f64>, radius: f64 },
+}
+
+impl RegionShape {
+    /// Returns the signed distance of `point` to the boundary of the shape: positive outside,
+    /// negative inside and zero on the boundary.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::region::RegionShape;
+    /// use nalgebra::Vector3;
+    ///
+    /// let shape = RegionShape::Box { min: Vector3::zeros(), max: Vector3::new(2.0, 2.0, 2.0) };
+    /// assert_eq!(shape.signed_distance(&Vector3::new(1.0, 1.0, 1.5)), -0.5);
+    /// assert_eq!(shape.signed_distance(&Vector3::new(1.0, 1.0, 3.0)), 1.0);
+    /// ```
+    #[must_use]
+    pub fn signed_distance(&self, point: &Vector3<f64>) -> f64 {
+        match self {
+            Self::Box { min, max } => {
+                // Per axis, how far the point lies beyond the nearer face; negative inside.
+                let beyond = (min - point).sup(&(point - max));
+                let outside = beyond.map(|value| value.max(0.0)).norm();
+                outside + beyond.max().min(0.0)
+            }
+            Self::Sphere { center, radius } => (point - center).norm() - radius,
+        }
+    }
+
+    /// Returns whether `point` lies inside the shape or on its boundary.
+    #[must_use]
+    pub fn contains_point(&self, point: &Vector3<f64>) -> bool {
+        self.signed_distance(point) <= 0.0
+    }
+
+    /// Returns an error if a value of the shape is NaN or infinite, a corner of a box exceeds the
+    /// other, or a radius is negative.
+    ///
+    /// `field` names the shape in the error, e.g. `table.regions.workspace`.
+    pub(crate) fn check(&self, field: &str) -> Result<(), CartesianTreeError> {
+        match self {
+            Self::Box { min, max } => {
+                check_position(&format!("{field}.min"), min)?;
+                check_position(&format!("{field}.max"), max)?;
+                if min.iter().zip(max.iter()).any(|(min, max)| min > max) {
+                    return Err(CartesianTreeError::DegenerateInput {
+                        reason: format!("the corner min of box '{field}' exceeds its corner max"),
+                    });
+                }
+            }
+            Self::Sphere { center, radius } => {
+                check_position(&format!("{field}.center"), center)?;
+                if !radius.is_finite() {
+                    return Err(CartesianTreeError::NonFiniteValue {
+                        field: format!("{field}.radius"),
+                        value: *radius,
+                    });
+                }
+                if *radius < 0.0 {
+                    return Err(CartesianTreeError::DegenerateInput {
+                        reason: format!("the radius of sphere '{field}' is negative"),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the shape mirrored across `plane` of its frame, see [`crate::mirror`].
+    pub(crate) fn mirrored(&self, plane: MirrorPlane) -> Self {
+        let normal = plane.normal();
+        let reflect = |point: &Vector3<f64>| point - normal * (2.0 * point.dot(&normal));
+        match self {
+            Self::Box { min, max } => {
+                let (a, b) = (reflect(min), reflect(max));
+                Self::Box {
+                    min: a.inf(&b),
+                    max: a.sup(&b),
+                }
+            }
+            Self::Sphere { center, radius } => Self::Sphere {
+                center: reflect(center),
+                radius: *radius,
+            },
+        }
+    }
+
+    /// Converts the lengths of the shape from `from` to `to`.
+    pub(crate) fn convert_units(&mut self, from: LengthUnit, to: LengthUnit) {
+        match self {
+            Self::Box { min, max } => {
+                *min = from.convert_vector(*min, to);
+                *max = from.convert_vector(*max, to);
+            }
+            Self::Sphere { center, radius } => {
+                *center = from.convert_vector(*center, to);
+                *radius = from.convert(*radius, to);
+            }
+        }
+    }
+}
+
+impl core::fmt::Display for RegionShape {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let precision = f.precision().unwrap_or(DISPLAY_PRECISION);
+        match self {
+            Self::Box { min, max } => write!(
+                f,
+                "box from {} to {}",
+                format_vector(min, precision),
+                format_vector(max, precision)
+            ),
+            Self::Sphere { center, radius } => write!(
+                f,
+                "sphere around {} with radius {radius:.precision$}",
+                format_vector(center, precision)
+            ),
+        }
+    }
+}
+
+/// A [`RegionShape`] anchored to a frame.
+///
+/// The region keeps a handle to its frame, so it stays valid, and keeps following the frame,
+/// while the frame is moved or reparented.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::Frame;
+/// use cartesian_tree::region::Region;
+/// use nalgebra::{UnitQuaternion, Vector3};
+///
+/// let world = Frame::new_origin("world").unwrap();
+/// let table = world
+///     .add_child("table", Vector3::new(2.0, 0.0, 0.8), UnitQuaternion::identity())
+///     .unwrap();
+/// let allowed =
+///     Region::new_box(&table, Vector3::new(-0.5, -0.5, 0.0), Vector3::new(0.5, 0.5, 0.5)).unwrap();
+///
+/// let tool = world.add_pose(Vector3::new(2.0, 0.5, 1.0), UnitQuaternion::identity()).unwrap();
+/// assert!(allowed.contains(&tool).unwrap());
+/// assert_eq!(allowed.distance_to_boundary(&tool).unwrap(), 0.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Region {
+    frame: Frame,
+    shape: RegionShape,
+}
+
+impl Region {
+    /// Creates a region of `shape` in the coordinates of `frame`.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - A value of the shape is NaN or infinite.
+    /// - The corner `min` of a box exceeds `max` along an axis, or the radius of a sphere is
+    ///   negative. Boxes and spheres of size zero are allowed.
+    pub fn new(frame: &Frame, shape: RegionShape) -> Result<Self, CartesianTreeError> {
+        shape.check("shape")?;
+        Ok(Self {
+            frame: frame.clone(),
+            shape,
+        })
+    }
+
+    /// Creates a box region aligned with the axes of `frame`, see [`Region::new`].
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] in the same cases as [`Region::new`].
+    pub fn new_box(
+        frame: &Frame,
+        min: Vector3<f64>,
+        max: Vector3<f64>,
+    ) -> Result<Self, CartesianTreeError> {
+        Self::new(frame, RegionShape::Box { min, max })
+    }
+
+    /// Creates a sphere region around `center` in `frame`, see [`Region::new`].
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] in the same cases as [`Region::new`].
+    pub fn new_sphere(
+        frame: &Frame,
+        center: Vector3<f64>,
+        radius: f64,
+    ) -> Result<Self, CartesianTreeError> {
+        Self::new(frame, RegionShape::Sphere { center, radius })
+    }
+
+    /// Returns the frame the region is defined in.
+    #[must_use]
+    pub fn frame(&self) -> Frame {
+        self.frame.clone()
+    }
+
+    /// Returns the shape of the region, in the coordinates of its frame.
+    #[must_use]
+    pub const fn shape(&self) -> &RegionShape {
+        &self.shape
+    }
+
+    /// Returns whether the position of `pose` lies inside the region or on its boundary.
+    ///
+    /// The pose is expressed in the frame of the region first, so positions computed exactly on
+    /// the boundary in another frame may end up just outside due to rounding.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the frame of the pose has been dropped or belongs to
+    /// another tree than the frame of the region.
+    pub fn contains(&self, pose: &Pose) -> Result<bool, CartesianTreeError> {
+        Ok(self.distance_to_boundary(pose)? <= 0.0)
+    }
+
+    /// Returns the signed distance of the position of `pose` to the boundary of the region:
+    /// positive outside, negative inside and zero on the boundary, see
+    /// [`RegionShape::signed_distance`].
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] in the same cases as [`Region::contains`].
+    pub fn distance_to_boundary(&self, pose: &Pose) -> Result<f64, CartesianTreeError> {
+        let position = pose.in_frame(&self.frame)?.position();
+        Ok(self.shape.signed_distance(&position))
+    }
+}
+
+impl core::fmt::Display for Region {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let precision = f.precision().unwrap_or(DISPLAY_PRECISION);
+        write!(f, "{:.precision$} in {}", self.shape, self.frame.name())
+    }
+}
+
+impl Frame {
+    /// Stores a region of `shape` on this frame under the given name and returns it.
+    ///
+    /// Stored regions are included in [`Frame::to_json`] and restored by [`Frame::from_json`]
+    /// and [`Frame::apply_config`].
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The shape is invalid, see [`Region::new`].
+    /// - A region with the same name is stored already and `policy` is [`StorePolicy::Reject`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::frame::StorePolicy;
+    /// use cartesian_tree::region::RegionShape;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let table = Frame::new_origin("table").unwrap();
+    /// let shape = RegionShape::Sphere { center: Vector3::zeros(), radius: 0.3 };
+    /// table.store_region("keep_out", shape, StorePolicy::Reject).unwrap();
+    ///
+    /// let cup = table.add_pose(Vector3::new(0.0, 0.3, 0.0), UnitQuaternion::identity()).unwrap();
+    /// assert!(table.get_region("keep_out").unwrap().contains(&cup).unwrap());
+    /// assert_eq!(table.region_names(), ["keep_out"]);
+    /// ```
+    pub fn store_region(
+        &self,
+        name: impl Into<String>,
+        shape: RegionShape,
+        policy: StorePolicy,
+    ) -> Result<Region, CartesianTreeError> {
+        let name = name.into();
+        let region = Region::new(self, shape)?;
+        let mut data = self.try_borrow_mut()?;
+        if policy == StorePolicy::Reject && data.regions.contains_key(&name) {
+            return Err(CartesianTreeError::DuplicateRegionName {
+                frame: data.name.to_string(),
+                name,
+            });
+        }
+        data.regions.insert(name, region.shape.clone());
+        Ok(region)
+    }
+
+    /// Returns the region stored under `name`, or `None` if there is no such region.
+    #[must_use]
+    pub fn get_region(&self, name: &str) -> Option<Region> {
+        let shape = self.borrow().regions.get(name)?.clone();
+        Some(Region {
+            frame: self.clone(),
+            shape,
+        })
+    }
+
+    /// Removes the region stored under `name` and returns it, if there was one.
+    #[must_use]
+    pub fn remove_region(&self, name: &str) -> Option<Region> {
+        let shape = self.data.borrow_mut().regions.remove(name)?;
+        Some(Region {
+            frame: self.clone(),
+            shape,
+        })
+    }
+
+    /// Returns the names of all regions stored on this frame in sorted order.
+    #[must_use]
+    pub fn region_names(&self) -> Vec<String> {
+        self.borrow().regions.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::ReparentPolicy;
+    use crate::tree::NodeEquality;
+    use approx::assert_relative_eq;
+    use nalgebra::UnitQuaternion;
+
+    #[test]
+    fn poses_on_the_boundary_are_contained() {
+        let world = Frame::new_origin("world").unwrap();
+        let table = world
+            .add_child(
+                "table",
+                Vector3::new(1.0, 2.0, 0.5),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let bounds = Region::new_box(
+            &table,
+            Vector3::new(-0.5, -0.25, 0.0),
+            Vector3::new(0.5, 0.25, 1.0),
+        )
+        .unwrap();
+        let pose = |x, y, z| {
+            world
+                .add_pose(Vector3::new(x, y, z), UnitQuaternion::identity())
+                .unwrap()
+        };
+
+        // On a face, an edge and a corner, expressed in the world.
+        for on_boundary in [
+            pose(1.5, 2.0, 1.0),
+            pose(0.5, 2.25, 1.0),
+            pose(1.5, 1.75, 0.5),
+        ] {
+            assert!(bounds.contains(&on_boundary).unwrap());
+            assert_relative_eq!(
+                bounds.distance_to_boundary(&on_boundary).unwrap(),
+                0.0,
+                epsilon = 0.0
+            );
+        }
+        let inside = pose(1.25, 2.0, 0.75);
+        assert!(bounds.contains(&inside).unwrap());
+        assert_relative_eq!(bounds.distance_to_boundary(&inside).unwrap(), -0.25);
+        let beyond_a_corner = pose(1.75, 2.25, 0.5);
+        assert!(!bounds.contains(&beyond_a_corner).unwrap());
+        assert_relative_eq!(bounds.distance_to_boundary(&beyond_a_corner).unwrap(), 0.25);
+
+        let ball = Region::new_sphere(&table, Vector3::new(0.0, 0.0, 0.5), 0.5).unwrap();
+        assert!(ball.contains(&pose(1.0, 2.0, 1.5)).unwrap());
+        assert_relative_eq!(
+            ball.distance_to_boundary(&pose(1.0, 2.0, 1.5)).unwrap(),
+            0.0,
+            epsilon = 0.0
+        );
+        assert_relative_eq!(
+            ball.distance_to_boundary(&pose(1.0, 2.0, 1.0)).unwrap(),
+            -0.5
+        );
+        assert!(!ball.contains(&pose(1.0, 2.5, 1.5)).unwrap());
+
+        // Degenerate boxes and spheres still contain their boundary.
+        let point = Region::new_sphere(&table, Vector3::zeros(), 0.0).unwrap();
+        assert!(point.contains(&pose(1.0, 2.0, 0.5)).unwrap());
+
+        assert!(matches!(
+            Region::new_box(&table, Vector3::new(1.0, 0.0, 0.0), Vector3::zeros()),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+        assert!(matches!(
+            Region::new_sphere(&table, Vector3::zeros(), f64::NAN),
+            Err(CartesianTreeError::NonFiniteValue { .. })
+        ));
+        assert!(Region::new_sphere(&table, Vector3::zeros(), -1.0).is_err());
+        let other = Frame::new_origin("other").unwrap();
+        let stranger = other
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert!(bounds.contains(&stranger).is_err());
+    }
+
+    #[test]
+    fn regions_follow_their_frame() {
+        let world = Frame::new_origin("world").unwrap();
+        let left = world
+            .add_child(
+                "left",
+                Vector3::new(0.0, 1.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let right = world
+            .add_child(
+                "right",
+                Vector3::new(0.0, -1.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let table = left
+            .add_child("table", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let shape = RegionShape::Sphere {
+            center: Vector3::zeros(),
+            radius: 0.5,
+        };
+        let region = table
+            .store_region("workspace", shape.clone(), StorePolicy::Reject)
+            .unwrap();
+        let tool = world
+            .add_pose(Vector3::new(0.0, 1.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        assert!(region.contains(&tool).unwrap());
+
+        table.reparent(&right, ReparentPolicy::KeepLocal).unwrap();
+        assert!(!region.contains(&tool).unwrap());
+        assert_relative_eq!(region.distance_to_boundary(&tool).unwrap(), 1.5);
+        let stored = table.get_region("workspace").unwrap();
+        assert_eq!(stored.shape(), &shape);
+        assert!(stored.frame().is_same(&table));
+
+        assert!(matches!(
+            table.store_region("workspace", shape, StorePolicy::Reject),
+            Err(CartesianTreeError::DuplicateRegionName { .. })
+        ));
+        assert_eq!(table.region_names(), ["workspace"]);
+        assert!(table.remove_region("workspace").is_some());
+        assert!(table.get_region("workspace").is_none());
+
+        assert_eq!(
+            format!("{region:.1}"),
+            "sphere around (0.0, 0.0, 0.0) with radius 0.5 in table"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn regions_round_trip_through_json() {
+        let world = Frame::new_origin("world").unwrap();
+        let table = world
+            .add_child(
+                "table",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let bounds = RegionShape::Box {
+            min: Vector3::new(-0.5, -0.5, 0.0),
+            max: Vector3::new(0.5, 0.5, 1.0),
+        };
+        table
+            .store_region("bounds", bounds.clone(), StorePolicy::Reject)
+            .unwrap();
+        let json = world.to_json().unwrap();
+        assert!(json.contains(r#""regions": {"#));
+
+        let copy = Frame::from_json(&json).unwrap();
+        let copied = copy.resolve_path("table").unwrap();
+        assert_eq!(copied.get_region("bounds").unwrap().shape(), &bounds);
+
+        let smaller = json.replace("-0.5", "-0.25");
+        world.apply_config(&smaller).unwrap();
+        assert_eq!(
+            table.get_region("bounds").unwrap().shape(),
+            &RegionShape::Box {
+                min: Vector3::new(-0.25, -0.25, 0.0),
+                max: Vector3::new(0.5, 0.5, 1.0),
+            }
+        );
+
+        let inverted = json.replace("-0.5", "0.75");
+        assert!(matches!(
+            Frame::from_json(&inverted),
+            Err(CartesianTreeError::InvalidDocument { path, .. }) if path == "$.children[0].regions.bounds"
+        ));
+        assert!(world.apply_config(&inverted).is_err());
+    }
+}
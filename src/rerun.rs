@@ -0,0 +1,332 @@
+//! Logging of frame trees to [Rerun](https://rerun.io), enabled by the `rerun` feature.
+//!
+//! Every frame is logged as a [`Transform3D`] of its transform to the parent, under an entity
+//! path of the prefix followed by the [path](Frame::path) of the frame, e.g.
+//! `robot/world/arm/camera` for the prefix `robot`. Rerun composes the transforms along the
+//! entity hierarchy, so anything logged below the entity of a frame is drawn in that frame.
+//! Rerun stores transforms in single precision.
+
+use crate::CartesianTreeError;
+use crate::frame::{Frame, SubscriptionId, SubscriptionScope};
+use crate::pose::Pose;
+use crate::tree::HasChildren;
+use nalgebra::Isometry3;
+use rerun::{RecordingStream, RecordingStreamError, Transform3D, TransformAxes3D};
+
+/// Options for [`Frame::log_to_rerun_with`] and [`Pose::log_to_rerun_with`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RerunOptions {
+    /// Draws the axes of every logged transform as arrows of this length. `None` logs the bare
+    /// transforms.
+    pub axis_length: Option<f64>,
+}
+
+/// Why logging to Rerun failed.
+#[derive(Debug, thiserror::Error)]
+pub enum RerunError {
+    /// The frame of a pose has been dropped.
+    #[error(transparent)]
+    Tree(#[from] CartesianTreeError),
+    /// The recording stream rejected the data.
+    #[error(transparent)]
+    Recording(#[from] RecordingStreamError),
+}
+
+/// Returns the entity path of a frame at `path` below `entity_prefix`.
+fn entity_path(entity_prefix: &str, path: &str) -> String {
+    format!("{}{path}", entity_prefix.trim_end_matches('/'))
+}
+
+/// Returns `transform`, scaled by `scale` if any, as a Rerun transform.
+// Rerun stores transforms as single precision.
+#[allow(clippy::cast_possible_truncation)]
+fn transform3d(transform: &Isometry3<f64>, scale: Option<f64>) -> Transform3D {
+    let translation = transform.translation.vector.map(|value| value as f32);
+    let rotation = transform.rotation.coords.map(|value| value as f32);
+    let transform = Transform3D::from_translation_rotation(
+        [translation.x, translation.y, translation.z],
+        rerun::Quaternion::from_xyzw([rotation.x, rotation.y, rotation.z, rotation.w]),
+    );
+    match scale {
+        Some(scale) => transform.with_scale(scale as f32),
+        None => transform,
+    }
+}
+
+/// Logs `transform` and, if requested, its axes under `path`.
+fn log_transform(
+    rec: &RecordingStream,
+    path: String,
+    transform: &Transform3D,
+    options: &RerunOptions,
+) -> Result<(), RecordingStreamError> {
+    rec.log(path.as_str(), transform)?;
+    if let Some(length) = options.axis_length {
+        // Rerun stores lengths as single precision.
+        #[allow(clippy::cast_possible_truncation)]
+        rec.log(path, &TransformAxes3D::new(length as f32))?;
+    }
+    Ok(())
+}
+
+impl Frame {
+    /// Logs the tree below this frame to `rec`, one [`Transform3D`] per frame.
+    ///
+    /// Equivalent to [`Frame::log_to_rerun_with`] with default options.
+    ///
+    /// # Errors
+    /// Returns a [`RerunError`] if the recording stream rejects the data.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let (rec, storage) = rerun::RecordingStreamBuilder::new("frames").memory().unwrap();
+    /// let world = Frame::new_origin("world").unwrap();
+    /// world.add_child("robot", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// world.log_to_rerun(&rec, "cell").unwrap();
+    /// rec.flush_blocking().unwrap();
+    /// assert!(storage.num_msgs() > 0);
+    /// ```
+    pub fn log_to_rerun(
+        &self,
+        rec: &RecordingStream,
+        entity_prefix: &str,
+    ) -> Result<(), RerunError> {
+        self.log_to_rerun_with(rec, entity_prefix, &RerunOptions::default())
+    }
+
+    /// Logs the tree below this frame to `rec`, one [`Transform3D`] per frame.
+    ///
+    /// Each frame is logged under `entity_prefix` followed by its path, see the
+    /// [module documentation](crate::rerun). This frame is logged with its transform to its
+    /// parent too, so that the tree lines up with earlier logs of the ancestors.
+    ///
+    /// # Errors
+    /// Returns a [`RerunError`] if the recording stream rejects the data.
+    pub fn log_to_rerun_with(
+        &self,
+        rec: &RecordingStream,
+        entity_prefix: &str,
+        options: &RerunOptions,
+    ) -> Result<(), RerunError> {
+        for (path, transform) in self.rerun_transforms(entity_prefix) {
+            log_transform(rec, path, &transform, options)?;
+        }
+        Ok(())
+    }
+
+    /// Logs the tree below this frame to `rec` now and again whenever a frame of it changes.
+    ///
+    /// The changed frame and the frames below it are logged again after every change reported
+    /// to [`Frame::on_transform_changed_with`] with [`SubscriptionScope::Subtree`]. Callbacks
+    /// cannot return errors, so failures to log a change are dropped.
+    ///
+    /// # Returns
+    /// The id used to [`unsubscribe`](Frame::unsubscribe) from the changes.
+    ///
+    /// # Errors
+    /// Returns a [`RerunError`] if the recording stream rejects the initial data.
+    pub fn watch_rerun(
+        &self,
+        rec: &RecordingStream,
+        entity_prefix: &str,
+        options: &RerunOptions,
+    ) -> Result<SubscriptionId, RerunError> {
+        self.log_to_rerun_with(rec, entity_prefix, options)?;
+        let rec = rec.clone();
+        let entity_prefix = entity_prefix.to_owned();
+        let options = *options;
+        Ok(
+            self.on_transform_changed_with(SubscriptionScope::Subtree, move |changed| {
+                let _ = changed.log_to_rerun_with(&rec, &entity_prefix, &options);
+            }),
+        )
+    }
+
+    /// Returns the entity path and the transform of every frame of the tree below this frame.
+    fn rerun_transforms(&self, entity_prefix: &str) -> Vec<(String, Transform3D)> {
+        let mut transforms = Vec::new();
+        // The paths of the children extend the path of their parent, which spares walking up
+        // from every frame of deep trees.
+        let mut pending = vec![(self.clone(), entity_path(entity_prefix, &self.path()))];
+        while let Some((frame, path)) = pending.pop() {
+            let transform = {
+                let data = frame.borrow();
+                transform3d(&data.transform_to_parent, data.scale)
+            };
+            pending.extend(frame.children().into_iter().rev().map(|child| {
+                let child_path = format!("{path}/{}", child.name());
+                (child, child_path)
+            }));
+            transforms.push((path, transform));
+        }
+        transforms
+    }
+}
+
+impl Pose {
+    /// Logs this pose to `rec` as a [`Transform3D`] named `name` below the entity of its frame.
+    ///
+    /// Equivalent to [`Pose::log_to_rerun_with`] with default options.
+    ///
+    /// # Errors
+    /// Returns a [`RerunError`] if the frame of the pose has been dropped or the recording
+    /// stream rejects the data.
+    pub fn log_to_rerun(
+        &self,
+        rec: &RecordingStream,
+        entity_prefix: &str,
+        name: &str,
+    ) -> Result<(), RerunError> {
+        self.log_to_rerun_with(rec, entity_prefix, name, &RerunOptions::default())
+    }
+
+    /// Logs this pose to `rec` as a [`Transform3D`] named `name` below the entity of its frame.
+    ///
+    /// The entity path is the one [`Frame::log_to_rerun`] logs the frame of the pose under with
+    /// the same `entity_prefix`, followed by `/name`.
+    ///
+    /// # Errors
+    /// Returns a [`RerunError`] if the frame of the pose has been dropped or the recording
+    /// stream rejects the data.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::rerun::RerunOptions;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let (rec, storage) = rerun::RecordingStreamBuilder::new("poses").memory().unwrap();
+    /// let world = Frame::new_origin("world").unwrap();
+    /// let grasp = world
+    ///     .add_pose(Vector3::new(0.5, 0.0, 0.2), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let options = RerunOptions { axis_length: Some(0.1) };
+    /// grasp.log_to_rerun_with(&rec, "cell", "grasp", &options).unwrap();
+    /// rec.flush_blocking().unwrap();
+    /// assert!(storage.num_msgs() > 0);
+    /// ```
+    pub fn log_to_rerun_with(
+        &self,
+        rec: &RecordingStream,
+        entity_prefix: &str,
+        name: &str,
+        options: &RerunOptions,
+    ) -> Result<(), RerunError> {
+        let path = format!(
+            "{}/{name}",
+            entity_path(entity_prefix, &self.frame()?.path())
+        );
+        log_transform(
+            rec,
+            path,
+            &transform3d(&self.transformation(), None),
+            options,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{UnitQuaternion, Vector3};
+    use rerun::RecordingStreamBuilder;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn transforms_mirror_the_frame_paths() {
+        let world = Frame::new_origin("world").unwrap();
+        let arm = world
+            .add_child(
+                "arm",
+                Vector3::new(1.0, 2.0, 3.0),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, FRAC_PI_2),
+            )
+            .unwrap();
+        arm.add_scaled_child("tool", Vector3::zeros(), UnitQuaternion::identity(), 0.5)
+            .unwrap();
+        world
+            .add_child("camera", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        let transforms = world.rerun_transforms("cell/");
+        let paths: Vec<_> = transforms.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "cell/world",
+                "cell/world/arm",
+                "cell/world/arm/tool",
+                "cell/world/camera"
+            ]
+        );
+
+        let half = 0.5_f32.sqrt();
+        let expected = Transform3D::from_translation_rotation(
+            [1.0, 2.0, 3.0],
+            rerun::Quaternion::from_xyzw([0.0, 0.0, half, half]),
+        );
+        assert_eq!(transforms[1].1, expected);
+        let tool = Transform3D::from_translation_rotation_scale(
+            [0.0; 3],
+            rerun::Quaternion::IDENTITY,
+            0.5,
+        );
+        assert_eq!(transforms[2].1, tool);
+
+        // Subtrees keep the full path, so they line up with the rest of the tree.
+        assert_eq!(arm.rerun_transforms("")[0].0, "/world/arm");
+    }
+
+    #[test]
+    fn frames_and_poses_are_logged_without_a_viewer() {
+        let (rec, storage) = RecordingStreamBuilder::new("cartesian_tree_test")
+            .memory()
+            .unwrap();
+        let world = Frame::new_origin("world").unwrap();
+        let arm = world
+            .add_child("arm", Vector3::x(), UnitQuaternion::identity())
+            .unwrap();
+        let pose = arm
+            .add_pose(Vector3::y(), UnitQuaternion::identity())
+            .unwrap();
+
+        let options = RerunOptions {
+            axis_length: Some(0.1),
+        };
+        let id = world.watch_rerun(&rec, "cell", &options).unwrap();
+        pose.log_to_rerun(&rec, "cell", "grasp").unwrap();
+        rec.flush_blocking().unwrap();
+        let logged = storage.num_msgs();
+        assert!(logged > 0);
+
+        arm.set(Vector3::z(), UnitQuaternion::identity()).unwrap();
+        rec.flush_blocking().unwrap();
+        assert!(storage.num_msgs() > logged);
+
+        assert!(world.unsubscribe(id));
+        let logged = storage.num_msgs();
+        arm.set(Vector3::x(), UnitQuaternion::identity()).unwrap();
+        rec.flush_blocking().unwrap();
+        assert_eq!(storage.num_msgs(), logged);
+    }
+
+    #[test]
+    fn poses_of_dropped_frames_fail() {
+        let (rec, _storage) = RecordingStreamBuilder::new("cartesian_tree_test")
+            .memory()
+            .unwrap();
+        let pose = Frame::new_origin("world")
+            .unwrap()
+            .add_pose(Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        assert!(matches!(
+            pose.log_to_rerun(&rec, "cell", "grasp"),
+            Err(RerunError::Tree(CartesianTreeError::FrameDropped))
+        ));
+    }
+}
@@ -0,0 +1,279 @@
+//! Export of frame trees as ROS transforms.
+//!
+//! Frame names become frame ids after [`sanitize_frame_id`]. `tf` needs frame ids to be unique
+//! within the whole tree, while frame names only have to be unique among siblings, so exports
+//! fail if two frames share a frame id.
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::tree::{HasChildren, HasParent};
+use std::collections::HashMap;
+
+/// A parent-to-child transform laid out like `geometry_msgs/TransformStamped`.
+///
+/// The header stamp is left to the caller, who knows the clock of the target system.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransformStampedData {
+    /// The frame id of the parent frame (`header.frame_id`).
+    pub parent_frame_id: String,
+    /// The frame id of the child frame (`child_frame_id`).
+    pub child_frame_id: String,
+    /// The translation of the child in the parent frame (`transform.translation`), as `[x, y, z]`.
+    pub translation: [f64; 3],
+    /// The orientation of the child in the parent frame (`transform.rotation`), as `[x, y, z, w]`.
+    pub rotation: [f64; 4],
+    /// Whether the child is static (see [`Frame::set_static`]), i.e. the transform belongs on
+    /// `/tf_static` rather than `/tf`.
+    pub is_static: bool,
+}
+
+/// Returns `name` as a valid `tf` frame id.
+///
+/// Leading slashes are removed, as `tf2` rejects them, and characters other than ASCII letters,
+/// digits, `_` and `/` are replaced by `_`. An empty result becomes `_`.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::ros::sanitize_frame_id;
+///
+/// assert_eq!(sanitize_frame_id("/robot/base link"), "robot/base_link");
+/// ```
+#[must_use]
+pub fn sanitize_frame_id(name: &str) -> String {
+    let id: String = name
+        .trim_start_matches('/')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '/' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if id.is_empty() { "_".to_owned() } else { id }
+}
+
+/// A parent-to-child edge of a tree with the frame ids of both frames.
+pub(crate) struct TfEdge {
+    pub(crate) parent_frame_id: String,
+    pub(crate) child: Frame,
+    pub(crate) child_frame_id: String,
+}
+
+impl TfEdge {
+    /// Returns the edge from `frame` to its parent, `None` for a root.
+    pub(crate) fn to_parent(frame: &Frame) -> Option<Self> {
+        let parent = frame.parent()?;
+        Some(Self {
+            parent_frame_id: sanitize_frame_id(&parent.name()),
+            child: frame.clone(),
+            child_frame_id: sanitize_frame_id(&frame.name()),
+        })
+    }
+}
+
+/// Returns the edges below `root` in depth-first order, grouped by parent.
+///
+/// # Errors
+/// Returns [`CartesianTreeError::DuplicateFrameId`] if two frames of the subtree share a frame id.
+pub(crate) fn tf_edges(root: &Frame) -> Result<Vec<TfEdge>, CartesianTreeError> {
+    let mut frames_by_id: HashMap<String, Frame> = HashMap::new();
+    let mut edges = Vec::new();
+    for frame in root.iter_dfs() {
+        let id = sanitize_frame_id(&frame.name());
+        if let Some(other) = frames_by_id.get(&id) {
+            return Err(CartesianTreeError::DuplicateFrameId {
+                id,
+                first: other.path(),
+                second: frame.path(),
+            });
+        }
+        for child in frame.children() {
+            edges.push(TfEdge {
+                parent_frame_id: id.clone(),
+                child_frame_id: sanitize_frame_id(&child.name()),
+                child,
+            });
+        }
+        frames_by_id.insert(id, frame);
+    }
+    Ok(edges)
+}
+
+impl Frame {
+    /// Exports every parent-to-child edge below this frame as a ROS-style transform.
+    ///
+    /// The edges leaving this frame use its own frame id as the parent frame id, see the
+    /// [module documentation](crate::ros). The edges are listed in depth-first order.
+    ///
+    /// # Returns
+    /// One transform per edge of the subtree rooted at this frame.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::DuplicateFrameId`] if two frames of the subtree share a
+    /// frame id.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let world = Frame::new_origin("world").unwrap();
+    /// world.add_child("base_link", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let transforms = world.to_transform_stamped_all().unwrap();
+    /// assert_eq!(transforms[0].parent_frame_id, "world");
+    /// assert_eq!(transforms[0].child_frame_id, "base_link");
+    /// assert_eq!(transforms[0].rotation, [0.0, 0.0, 0.0, 1.0]);
+    /// ```
+    pub fn to_transform_stamped_all(
+        &self,
+    ) -> Result<Vec<TransformStampedData>, CartesianTreeError> {
+        Ok(tf_edges(self)?
+            .into_iter()
+            .map(|edge| {
+                let data = edge.child.borrow();
+                let translation = data.transform_to_parent.translation.vector;
+                let rotation = data.transform_to_parent.rotation;
+                TransformStampedData {
+                    parent_frame_id: edge.parent_frame_id,
+                    child_frame_id: edge.child_frame_id,
+                    translation: [translation.x, translation.y, translation.z],
+                    rotation: [rotation.i, rotation.j, rotation.k, rotation.w],
+                    is_static: data.is_static,
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use nalgebra::{UnitQuaternion, Vector3, Vector4};
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn export_one_transform_per_edge() {
+        let world = Frame::new_origin("world").unwrap();
+        let base = world
+            .add_child(
+                "base_link",
+                Vector3::new(1.0, 2.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        base.add_child(
+            "camera",
+            Vector3::new(0.0, 0.0, 0.5),
+            UnitQuaternion::from_euler_angles(0.0, 0.0, FRAC_PI_2),
+        )
+        .unwrap();
+        base.add_child(
+            "lidar",
+            Vector3::new(0.2, 0.0, 0.3),
+            UnitQuaternion::identity(),
+        )
+        .unwrap()
+        .set_static(true);
+        world
+            .add_child("map", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        let transforms = world.to_transform_stamped_all().unwrap();
+
+        let edges = world.iter_dfs().count() - 1;
+        assert_eq!(transforms.len(), edges);
+        let edge_names: Vec<(&str, &str)> = transforms
+            .iter()
+            .map(|t| (t.parent_frame_id.as_str(), t.child_frame_id.as_str()))
+            .collect();
+        assert_eq!(
+            edge_names,
+            [
+                ("world", "base_link"),
+                ("world", "map"),
+                ("base_link", "camera"),
+                ("base_link", "lidar"),
+            ]
+        );
+
+        let camera = &transforms[2];
+        assert_relative_eq!(
+            Vector3::from(camera.translation),
+            Vector3::new(0.0, 0.0, 0.5)
+        );
+        let half = FRAC_PI_2 / 2.0;
+        assert_relative_eq!(
+            Vector4::from(camera.rotation),
+            Vector4::new(0.0, 0.0, half.sin(), half.cos()),
+            epsilon = 1e-12
+        );
+        let static_edges: Vec<_> = transforms.iter().map(|t| t.is_static).collect();
+        assert_eq!(static_edges, [false, false, false, true]);
+    }
+
+    #[test]
+    fn export_single_frame_has_no_edges() {
+        let world = Frame::new_origin("world").unwrap();
+        assert!(world.to_transform_stamped_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn frame_ids_are_sanitized() {
+        assert_eq!(sanitize_frame_id("base_link"), "base_link");
+        assert_eq!(sanitize_frame_id("//robot/tool0"), "robot/tool0");
+        assert_eq!(sanitize_frame_id("Kamera-1 (links)"), "Kamera_1__links_");
+        assert_eq!(sanitize_frame_id("/"), "_");
+        assert_eq!(sanitize_frame_id("wörld"), "w_rld");
+    }
+
+    #[test]
+    fn export_sanitizes_frame_ids() {
+        let world = Frame::new_origin("world").unwrap();
+        world
+            .add_child("front camera", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+
+        let transforms = world.to_transform_stamped_all().unwrap();
+        assert_eq!(transforms[0].child_frame_id, "front_camera");
+    }
+
+    #[test]
+    fn export_rejects_duplicate_frame_ids() {
+        let duplicate_id = |world: &Frame| match world.to_transform_stamped_all() {
+            Err(CartesianTreeError::DuplicateFrameId { id, first, second }) => (id, first, second),
+            other => panic!("expected a duplicate frame id error, got {other:?}"),
+        };
+
+        // Frame names only have to be unique among siblings.
+        let world = Frame::new_origin("world").unwrap();
+        for robot in ["left", "right"] {
+            world
+                .add_child(robot, Vector3::zeros(), UnitQuaternion::identity())
+                .unwrap()
+                .add_child("tool", Vector3::zeros(), UnitQuaternion::identity())
+                .unwrap();
+        }
+        assert_eq!(
+            duplicate_id(&world),
+            (
+                "tool".to_owned(),
+                "/world/left/tool".to_owned(),
+                "/world/right/tool".to_owned()
+            )
+        );
+
+        // Distinct names may collide once sanitized.
+        let world = Frame::new_origin("world").unwrap();
+        for name in ["a b", "a_b"] {
+            world
+                .add_child(name, Vector3::zeros(), UnitQuaternion::identity())
+                .unwrap();
+        }
+        assert_eq!(duplicate_id(&world).0, "a_b");
+    }
+}
@@ -0,0 +1,611 @@
+//! Publishing of frame trees to ROS 2 `tf`, enabled by the `ros2` feature.
+//!
+//! The messages in [`msg`] mirror `tf2_msgs/TFMessage` and the message types it contains field by
+//! field and implement `serde`'s traits, so any ROS 2 client library with serde support can
+//! publish them without this crate depending on a ROS installation.
+//!
+//! Every parent-to-child edge becomes one `TransformStamped`. Edges to frames marked static by
+//! [`Frame::set_static`] belong on `/tf_static`, as `robot_state_publisher` does for fixed
+//! joints; all other edges are dynamic and belong on `/tf`. Scales of frames are not part of
+//! `tf` and are left out.
+//!
+//! Frame ids are derived from frame names like for [ROS transforms](crate::ros), with
+//! [`sanitize_frame_id`], and must be unique within the whole tree.
+
+use crate::CartesianTreeError;
+use crate::frame::{Frame, SubscriptionId, SubscriptionScope};
+use crate::ros::{TfEdge, tf_edges};
+use core::time::Duration;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+/// Message types laid out like their ROS 2 counterparts.
+pub mod msg {
+    use serde::{Deserialize, Serialize};
+
+    /// `builtin_interfaces/Time`.
+    #[derive(
+        Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+    )]
+    pub struct Time {
+        /// Seconds since the epoch of the clock.
+        pub sec: i32,
+        /// Nanoseconds on top of `sec`, below `1_000_000_000`.
+        pub nanosec: u32,
+    }
+
+    /// `std_msgs/Header`.
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct Header {
+        /// The time the transform is valid at.
+        pub stamp: Time,
+        /// The parent frame.
+        pub frame_id: String,
+    }
+
+    /// `geometry_msgs/Vector3`.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+    pub struct Vector3 {
+        pub x: f64,
+        pub y: f64,
+        pub z: f64,
+    }
+
+    /// `geometry_msgs/Quaternion`.
+    #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct Quaternion {
+        pub x: f64,
+        pub y: f64,
+        pub z: f64,
+        pub w: f64,
+    }
+
+    /// `geometry_msgs/Transform`.
+    #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct Transform {
+        /// The position of the child in the parent frame.
+        pub translation: Vector3,
+        /// The orientation of the child in the parent frame.
+        pub rotation: Quaternion,
+    }
+
+    /// `geometry_msgs/TransformStamped`.
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    pub struct TransformStamped {
+        /// The stamp and the parent frame.
+        pub header: Header,
+        /// The child frame.
+        pub child_frame_id: String,
+        /// The transform from the child to the parent frame.
+        pub transform: Transform,
+    }
+
+    /// `tf2_msgs/TFMessage`.
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    pub struct TfMessage {
+        pub transforms: Vec<TransformStamped>,
+    }
+}
+
+use msg::{Header, Quaternion, TfMessage, Time, Transform, TransformStamped, Vector3};
+
+impl Time {
+    /// Returns the current system time.
+    #[must_use]
+    pub fn now() -> Self {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(Self::from)
+            .unwrap_or_default()
+    }
+}
+
+/// Interprets the duration as time since the epoch, saturating at the largest `sec`.
+impl From<Duration> for Time {
+    fn from(duration: Duration) -> Self {
+        i32::try_from(duration.as_secs()).map_or(
+            Self {
+                sec: i32::MAX,
+                nanosec: 999_999_999,
+            },
+            |sec| Self {
+                sec,
+                nanosec: duration.subsec_nanos(),
+            },
+        )
+    }
+}
+
+/// The `tf` topic a transform belongs on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TfTopic {
+    /// `/tf`, for transforms that change.
+    Tf,
+    /// `/tf_static`, for transforms that are published once and latched.
+    TfStatic,
+}
+
+/// The `tf` messages of a tree, split by topic.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TfMessages {
+    /// The dynamic transforms, for `/tf`.
+    pub tf: TfMessage,
+    /// The static transforms, for `/tf_static`.
+    pub tf_static: TfMessage,
+}
+
+pub use crate::ros::sanitize_frame_id;
+
+/// Returns the topic the transform of `frame` to its parent belongs on.
+fn topic_of(frame: &Frame) -> TfTopic {
+    if frame.is_static() {
+        TfTopic::TfStatic
+    } else {
+        TfTopic::Tf
+    }
+}
+
+/// Returns the transform of the child of `edge` to its parent.
+fn transform_stamped(edge: TfEdge, stamp: Time) -> TransformStamped {
+    let data = edge.child.borrow();
+    let translation = data.transform_to_parent.translation.vector;
+    let rotation = data.transform_to_parent.rotation;
+    TransformStamped {
+        header: Header {
+            stamp,
+            frame_id: edge.parent_frame_id,
+        },
+        child_frame_id: edge.child_frame_id,
+        transform: Transform {
+            translation: Vector3 {
+                x: translation.x,
+                y: translation.y,
+                z: translation.z,
+            },
+            rotation: Quaternion {
+                x: rotation.i,
+                y: rotation.j,
+                z: rotation.k,
+                w: rotation.w,
+            },
+        },
+    }
+}
+
+impl TfMessages {
+    /// Returns the messages of `topic`.
+    const fn topic_mut(&mut self, topic: TfTopic) -> &mut TfMessage {
+        match topic {
+            TfTopic::Tf => &mut self.tf,
+            TfTopic::TfStatic => &mut self.tf_static,
+        }
+    }
+}
+
+impl Frame {
+    /// Exports every parent-to-child edge below this frame as a `tf` transform.
+    ///
+    /// The edges are listed in depth-first order and split into dynamic and static ones, see the
+    /// [module documentation](crate::ros2).
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::DuplicateFrameId`] if two frames of the subtree share a
+    /// frame id.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::ros2::msg::Time;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let world = Frame::new_origin("world").unwrap();
+    /// let base = world
+    ///     .add_child("base_link", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// base.set_static(true);
+    ///
+    /// let messages = world.tf_messages(Time { sec: 42, nanosec: 0 }).unwrap();
+    /// let base = &messages.tf_static.transforms[0];
+    /// assert_eq!(base.header.frame_id, "world");
+    /// assert_eq!(base.child_frame_id, "base_link");
+    /// assert_eq!(base.transform.translation.x, 1.0);
+    /// ```
+    pub fn tf_messages(&self, stamp: Time) -> Result<TfMessages, CartesianTreeError> {
+        let mut messages = TfMessages::default();
+        for edge in tf_edges(self)? {
+            messages
+                .topic_mut(topic_of(&edge.child))
+                .transforms
+                .push(transform_stamped(edge, stamp));
+        }
+        Ok(messages)
+    }
+}
+
+/// Publishes the `tf` transforms of a tree through a callback.
+///
+/// Creating a broadcaster publishes all transforms. Afterwards, every change of a frame
+/// reported to [`Frame::on_transform_changed_with`] publishes its transform again, stamped with
+/// the time of the broadcaster's clock, and [`TfBroadcaster::tick`] republishes the dynamic
+/// transforms once per period, as `tf` listeners expect. Dropping the broadcaster stops listening
+/// for changes.
+///
+/// The callback receives the topic and the message to publish, e.g. to hand them to the
+/// publishers of a ROS 2 node. It must not change the tree.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::Frame;
+/// use cartesian_tree::ros2::{TfBroadcaster, TfTopic};
+/// use cartesian_tree::ros2::msg::Time;
+/// use nalgebra::{UnitQuaternion, Vector3};
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use std::time::Duration;
+///
+/// let world = Frame::new_origin("world").unwrap();
+/// let base = world
+///     .add_child("base_link", Vector3::zeros(), UnitQuaternion::identity())
+///     .unwrap();
+///
+/// let published = Rc::new(RefCell::new(Vec::new()));
+/// let sink = published.clone();
+/// let period = Duration::from_millis(100);
+/// let broadcaster = TfBroadcaster::new(&world, period, Time::now, move |topic, message| {
+///     sink.borrow_mut().push((topic, message.transforms.len()));
+/// })
+/// .unwrap();
+/// base.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+/// assert_eq!(*published.borrow(), [(TfTopic::Tf, 1), (TfTopic::Tf, 1)]);
+/// ```
+pub struct TfBroadcaster {
+    root: Frame,
+    subscription: SubscriptionId,
+    state: Rc<RefCell<BroadcasterState>>,
+}
+
+/// Hands a message to the publisher of its topic.
+type PublishCallback = Box<dyn FnMut(TfTopic, &TfMessage)>;
+
+/// Returns the time to stamp changes with.
+type Clock = Box<dyn FnMut() -> Time>;
+
+/// The parts of a [`TfBroadcaster`] shared with its change callback.
+struct BroadcasterState {
+    clock: Clock,
+    publish: PublishCallback,
+    period: Duration,
+    last_tick: Option<Time>,
+}
+
+impl BroadcasterState {
+    /// Publishes the non-empty messages.
+    fn publish_all(&mut self, messages: &TfMessages) {
+        for (topic, message) in [
+            (TfTopic::TfStatic, &messages.tf_static),
+            (TfTopic::Tf, &messages.tf),
+        ] {
+            if !message.transforms.is_empty() {
+                (self.publish)(topic, message);
+            }
+        }
+    }
+}
+
+/// Returns `time` as time since the epoch, with negative times as zero.
+fn since_epoch(time: Time) -> Duration {
+    u64::try_from(time.sec).map_or(Duration::ZERO, |sec| Duration::new(sec, time.nanosec))
+}
+
+impl TfBroadcaster {
+    /// Publishes all transforms below `root` and starts listening for changes.
+    ///
+    /// # Arguments
+    /// - `root`: The frame whose subtree is published.
+    /// - `period`: The interval [`TfBroadcaster::tick`] republishes the dynamic transforms at.
+    /// - `clock`: Returns the time to stamp the first publication and every change with, e.g.
+    ///   [`Time::now`] or the clock of a ROS 2 node.
+    /// - `publish`: Called with the topic and the message for every publication.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::DuplicateFrameId`] if two frames of the subtree share a
+    /// frame id.
+    pub fn new(
+        root: &Frame,
+        period: Duration,
+        clock: impl FnMut() -> Time + 'static,
+        publish: impl FnMut(TfTopic, &TfMessage) + 'static,
+    ) -> Result<Self, CartesianTreeError> {
+        let state = Rc::new(RefCell::new(BroadcasterState {
+            clock: Box::new(clock),
+            publish: Box::new(publish),
+            period,
+            last_tick: None,
+        }));
+        {
+            let mut state = state.borrow_mut();
+            let now = (state.clock)();
+            state.publish_all(&root.tf_messages(now)?);
+        }
+
+        let shared = Rc::clone(&state);
+        let subscription =
+            root.on_transform_changed_with(SubscriptionScope::Subtree, move |changed| {
+                let Some(edge) = TfEdge::to_parent(changed) else {
+                    return;
+                };
+                let mut state = shared.borrow_mut();
+                let mut messages = TfMessages::default();
+                messages
+                    .topic_mut(topic_of(changed))
+                    .transforms
+                    .push(transform_stamped(edge, (state.clock)()));
+                state.publish_all(&messages);
+            });
+        Ok(Self {
+            root: root.clone(),
+            subscription,
+            state,
+        })
+    }
+
+    /// Republishes the dynamic transforms stamped with `now` if a period has passed since the
+    /// last republication.
+    ///
+    /// # Returns
+    /// Whether the transforms were republished.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::DuplicateFrameId`] if frames added since the broadcaster was
+    /// created share a frame id with another frame. Nothing is published in this case.
+    pub fn tick(&self, now: Time) -> Result<bool, CartesianTreeError> {
+        let mut state = self.state.borrow_mut();
+        let due = state
+            .last_tick
+            .is_none_or(|last| since_epoch(now).saturating_sub(since_epoch(last)) >= state.period);
+        if due {
+            let mut messages = self.root.tf_messages(now)?;
+            state.last_tick = Some(now);
+            messages.tf_static.transforms.clear();
+            state.publish_all(&messages);
+        }
+        Ok(due)
+    }
+
+    /// Publishes all transforms, static and dynamic, stamped with `now`, e.g. for a late
+    /// subscriber.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::DuplicateFrameId`] if two frames of the subtree share a
+    /// frame id. Nothing is published in this case.
+    pub fn publish_all(&self, now: Time) -> Result<(), CartesianTreeError> {
+        let messages = self.root.tf_messages(now)?;
+        self.state.borrow_mut().publish_all(&messages);
+        Ok(())
+    }
+}
+
+impl Drop for TfBroadcaster {
+    fn drop(&mut self) {
+        let _ = self.root.unsubscribe(self.subscription);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::joint::Joint;
+    use approx::assert_relative_eq;
+    use nalgebra::{UnitQuaternion, Vector3 as NVector3};
+    use std::cell::Cell;
+    use std::f64::consts::FRAC_PI_2;
+
+    /// A robot on a map with a revolute shoulder, a static camera and a name `tf` rejects.
+    fn robot() -> Frame {
+        let world = Frame::new_origin("world").unwrap();
+        let base = world
+            .add_child(
+                "base_link",
+                NVector3::new(1.0, 2.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        base.set_static(true);
+        let shoulder = base
+            .add_child(
+                "shoulder",
+                NVector3::new(0.0, 0.0, 0.5),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        shoulder
+            .attach_joint(Joint::revolute(NVector3::z_axis()))
+            .unwrap();
+        shoulder.set_joint_value(FRAC_PI_2).unwrap();
+        base.add_child(
+            "front camera",
+            NVector3::new(0.3, 0.0, 0.2),
+            UnitQuaternion::identity(),
+        )
+        .unwrap()
+        .set_static(true);
+        world
+    }
+
+    #[test]
+    fn messages_split_static_and_dynamic_edges() {
+        let stamp = Time {
+            sec: 12,
+            nanosec: 500,
+        };
+        let messages = robot().tf_messages(stamp).unwrap();
+
+        let edges = |message: &TfMessage| -> Vec<(String, String)> {
+            message
+                .transforms
+                .iter()
+                .map(|t| (t.header.frame_id.clone(), t.child_frame_id.clone()))
+                .collect()
+        };
+        assert_eq!(
+            edges(&messages.tf_static),
+            [
+                ("world".to_owned(), "base_link".to_owned()),
+                ("base_link".to_owned(), "front_camera".to_owned()),
+            ]
+        );
+        assert_eq!(
+            edges(&messages.tf),
+            [("base_link".to_owned(), "shoulder".to_owned())]
+        );
+        assert!(
+            messages
+                .tf
+                .transforms
+                .iter()
+                .chain(&messages.tf_static.transforms)
+                .all(|t| t.header.stamp == stamp)
+        );
+
+        let shoulder = &messages.tf.transforms[0].transform;
+        assert_eq!(
+            shoulder.translation,
+            Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 0.5
+            }
+        );
+        let half = FRAC_PI_2 / 2.0;
+        assert_relative_eq!(shoulder.rotation.z, half.sin(), epsilon = 1e-12);
+        assert_relative_eq!(shoulder.rotation.w, half.cos(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn messages_serialize_with_ros_field_names() {
+        let world = Frame::new_origin("world").unwrap();
+        world
+            .add_child("map", NVector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let message = world.tf_messages(Time::default()).unwrap().tf;
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(
+            json["transforms"][0],
+            serde_json::json!({
+                "header": { "stamp": { "sec": 0, "nanosec": 0 }, "frame_id": "world" },
+                "child_frame_id": "map",
+                "transform": {
+                    "translation": { "x": 0.0, "y": 0.0, "z": 0.0 },
+                    "rotation": { "x": 0.0, "y": 0.0, "z": 0.0, "w": 1.0 }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn times_convert_from_durations() {
+        assert_eq!(
+            Time::from(Duration::new(3, 250)),
+            Time {
+                sec: 3,
+                nanosec: 250
+            }
+        );
+        assert_eq!(Time::from(Duration::MAX).sec, i32::MAX);
+    }
+
+    #[test]
+    fn broadcaster_publishes_changes_and_ticks() {
+        let world = robot();
+        let published = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&published);
+        let period = Duration::from_millis(100);
+        let broadcaster = TfBroadcaster::new(&world, period, Time::now, move |topic, message| {
+            let children: Vec<_> = message
+                .transforms
+                .iter()
+                .map(|t| t.child_frame_id.clone())
+                .collect();
+            sink.borrow_mut().push((topic, children));
+        })
+        .unwrap();
+        assert_eq!(
+            published.borrow_mut().drain(..).collect::<Vec<_>>(),
+            [
+                (
+                    TfTopic::TfStatic,
+                    vec!["base_link".to_owned(), "front_camera".to_owned()]
+                ),
+                (TfTopic::Tf, vec!["shoulder".to_owned()]),
+            ]
+        );
+
+        let shoulder = world.find("shoulder").unwrap();
+        shoulder.set_joint_value(0.0).unwrap();
+        assert_eq!(
+            published.borrow_mut().drain(..).collect::<Vec<_>>(),
+            [(TfTopic::Tf, vec!["shoulder".to_owned()])]
+        );
+
+        let at = |millis| Time::from(Duration::from_millis(millis));
+        assert!(broadcaster.tick(at(1_000)).unwrap());
+        assert!(!broadcaster.tick(at(1_050)).unwrap());
+        assert!(broadcaster.tick(at(1_100)).unwrap());
+        assert_eq!(
+            published.borrow_mut().drain(..).collect::<Vec<_>>(),
+            [
+                (TfTopic::Tf, vec!["shoulder".to_owned()]),
+                (TfTopic::Tf, vec!["shoulder".to_owned()]),
+            ]
+        );
+
+        drop(broadcaster);
+        shoulder.set_joint_value(0.5).unwrap();
+        assert!(published.borrow().is_empty());
+    }
+
+    #[test]
+    fn broadcaster_stamps_changes_with_its_clock() {
+        let world = robot();
+        let clock = Rc::new(Cell::new(Time { sec: 7, nanosec: 0 }));
+        let stamps = Rc::new(RefCell::new(Vec::new()));
+        let (time, sink) = (Rc::clone(&clock), Rc::clone(&stamps));
+        let _broadcaster = TfBroadcaster::new(
+            &world,
+            Duration::from_millis(100),
+            move || time.get(),
+            move |_, message| {
+                let stamps = message.transforms.iter().map(|t| t.header.stamp.sec);
+                sink.borrow_mut().extend(stamps);
+            },
+        )
+        .unwrap();
+
+        clock.set(Time { sec: 9, nanosec: 0 });
+        world
+            .find("shoulder")
+            .unwrap()
+            .set_joint_value(0.0)
+            .unwrap();
+        assert_eq!(*stamps.borrow(), [7, 7, 7, 9]);
+    }
+
+    #[test]
+    fn duplicate_frame_ids_are_rejected() {
+        let world = robot();
+        world
+            .add_child(
+                "front-camera",
+                NVector3::zeros(),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+
+        assert!(matches!(
+            world.tf_messages(Time::default()),
+            Err(CartesianTreeError::DuplicateFrameId { id, .. }) if id == "front_camera"
+        ));
+        assert!(TfBroadcaster::new(&world, Duration::ZERO, Time::now, |_, _| {}).is_err());
+    }
+}
@@ -1,57 +1,728 @@
-use nalgebra::{Quaternion, UnitQuaternion, Vector3};
+use crate::CartesianTreeError;
+use crate::scalar::Real;
+use alloc::{format, string::ToString};
+// Without `std`, `f64` has no `atan2` of its own; nalgebra provides it through `libm`.
+use core::f64::consts::{PI, TAU};
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use nalgebra::RealField as _;
+use nalgebra::{Matrix3, Quaternion, Rotation3, Unit, UnitQuaternion, Vector3};
+
+/// Tolerance on the deviation of `M^T * M` from identity accepted by [`Rotation::from_matrix`].
+pub(crate) const MATRIX_TOLERANCE: f64 = 1e-6;
+
+/// Norm of the cross product of the unit forward and up vectors below which
+/// [`Rotation::look_at`] considers them parallel.
+const LOOK_AT_TOLERANCE: f64 = 1e-9;
+
+/// Selects which axes of the resulting frame [`Rotation::look_at_with`] aligns with the viewing
+/// direction and the up vector.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LookAtConvention {
+    /// -Z points forward and +Y up, as in OpenGL-style camera frames.
+    #[default]
+    Camera,
+    /// +X points forward and +Z up, as in typical robot tool frames.
+    Tool,
+}
 
 /// Unified representation for rotations, allowing different input formats.
+///
+/// Functions taking an orientation accept anything convertible into a `Rotation`, including
+/// plain arrays and tuples. **Quaternion components are always ordered `(x, y, z, w)`**, as in
+/// ROS messages, not `(w, x, y, z)` as in [`Quaternion::new`]:
+///
+/// | Input | Interpretation |
+/// |---|---|
+/// | `[x, y, z, w]`, `(x, y, z, w)` | Quaternion, normalized |
+/// | `[roll, pitch, yaw]` | RPY angles in radians |
+/// | [`Degrees`] | RPY angles in degrees |
+/// | [`UnitQuaternion`], [`Rotation3`], `&Rotation` | As is |
+///
+/// # Example
+/// ```
+/// use cartesian_tree::Frame;
+/// use cartesian_tree::rotation::Rotation;
+///
+/// let root = Frame::new_origin("world").unwrap();
+/// // Quaternion (x, y, z, w): a quarter turn about z.
+/// let half = std::f64::consts::FRAC_1_SQRT_2;
+/// let a = root.add_child("a", [1.0, 0.0, 0.0], [0.0, 0.0, half, half]).unwrap();
+/// // RPY in radians: the same orientation.
+/// let b = root.add_child("b", [1.0, 0.0, 0.0], [0.0, 0.0, std::f64::consts::FRAC_PI_2]).unwrap();
+/// assert!(a.orientation().angle_to(&b.orientation()) < 1e-12);
+///
+/// let rotation = Rotation::from_rpy(0.1, 0.2, 0.3);
+/// let c = root.add_child("c", [0.0; 3], &rotation).unwrap();
+/// ```
 #[derive(Clone, Copy, Debug)]
-pub enum Rotation {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rotation<T: Real = f64> {
     /// Quaternion representation (x, y, z, w).
-    Quaternion(UnitQuaternion<f64>),
+    Quaternion(UnitQuaternion<T>),
     /// Roll-Pitch-Yaw (Euler angles in radians, ZYX convention).
-    Rpy(Vector3<f64>),
+    Rpy(Vector3<T>),
+    /// Rotation by an angle (in radians) about a unit axis.
+    AxisAngle(Unit<Vector3<T>>, T),
 }
 
-impl Rotation {
+impl<T: Real> Rotation<T> {
     /// Creates a Rotation from a quaternion (x, y, z, w).
     #[must_use]
-    pub fn from_quaternion(x: f64, y: f64, z: f64, w: f64) -> Self {
+    pub fn from_quaternion(x: T, y: T, z: T, w: T) -> Self {
         Self::Quaternion(UnitQuaternion::new_normalize(Quaternion::new(w, x, y, z)))
     }
 
     /// Creates a Rotation from RPY angles in radians (roll, pitch, yaw).
     #[must_use]
-    pub const fn from_rpy(roll: f64, pitch: f64, yaw: f64) -> Self {
+    pub const fn from_rpy(roll: T, pitch: T, yaw: T) -> Self {
         Self::Rpy(Vector3::new(roll, pitch, yaw))
     }
 
-    /// Creates the identity rotation using the identity quaternion.
+    /// Creates a Rotation from a unit axis and an angle in radians.
     #[must_use]
-    pub fn identity() -> Self {
-        Self::Quaternion(UnitQuaternion::identity())
+    pub const fn from_axis_angle(axis: Unit<Vector3<T>>, angle: T) -> Self {
+        Self::AxisAngle(axis, angle)
+    }
+
+    /// Composes this rotation with `other`, applying `other` first and then this rotation.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::rotation::Rotation;
+    ///
+    /// let quarter = Rotation::from_rpy(0.0, 0.0, std::f64::consts::FRAC_PI_4);
+    /// let half = quarter.compose(&quarter);
+    /// assert!((half.as_rpy().z - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+    /// ```
+    #[must_use]
+    pub fn compose(&self, other: &Self) -> Self {
+        Self::Quaternion(self.as_quaternion() * other.as_quaternion())
+    }
+
+    /// Returns the inverse rotation.
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        Self::Quaternion(self.as_quaternion().inverse())
     }
 
     /// Converts this rotation to a `UnitQuaternion`.
     #[must_use]
-    pub fn as_quaternion(&self) -> UnitQuaternion<f64> {
+    pub fn as_quaternion(&self) -> UnitQuaternion<T> {
         match self {
             Self::Quaternion(q) => *q,
             Self::Rpy(rpy) => UnitQuaternion::from_euler_angles(rpy.x, rpy.y, rpy.z),
+            Self::AxisAngle(axis, angle) => UnitQuaternion::from_axis_angle(axis, *angle),
         }
     }
 
     /// Converts to RPY (roll, pitch, yaw) in radians.
     #[must_use]
-    pub fn as_rpy(&self) -> Vector3<f64> {
+    pub fn as_rpy(&self) -> Vector3<T> {
+        if let Self::Rpy(rpy) = self {
+            *rpy
+        } else {
+            let (roll, pitch, yaw) = self.as_quaternion().euler_angles();
+            Vector3::new(roll, pitch, yaw)
+        }
+    }
+
+    /// Converts to a 3x3 rotation matrix.
+    #[must_use]
+    pub fn to_matrix(self) -> Matrix3<T> {
+        self.as_quaternion().to_rotation_matrix().into_inner()
+    }
+
+    /// Converts to a rotation vector (axis scaled by the angle in radians).
+    #[must_use]
+    pub fn to_rotation_vector(self) -> Vector3<T> {
+        match self {
+            Self::AxisAngle(axis, angle) => axis.into_inner() * angle,
+            _ => self.as_quaternion().scaled_axis(),
+        }
+    }
+}
+
+impl Rotation {
+    /// Creates a Rotation from RPY angles in degrees (roll, pitch, yaw).
+    ///
+    /// The angles are stored in radians, so [`Rotation::as_rpy`] returns radians.
+    #[must_use]
+    pub const fn from_rpy_degrees(roll: f64, pitch: f64, yaw: f64) -> Self {
+        Self::from_rpy(roll.to_radians(), pitch.to_radians(), yaw.to_radians())
+    }
+
+    /// Creates a Rotation from a rotation vector (axis scaled by the angle in radians), as used
+    /// e.g. by the Rodrigues representation of `OpenCV`.
+    ///
+    /// The zero vector yields a zero rotation about the x-axis.
+    #[must_use]
+    pub fn from_rotation_vector(vector: Vector3<f64>) -> Self {
+        Unit::try_new_and_get(vector, 0.0).map_or_else(
+            || Self::AxisAngle(Vector3::x_axis(), 0.0),
+            |(axis, angle)| Self::AxisAngle(axis, angle),
+        )
+    }
+
+    /// Creates a Rotation from a 3x3 rotation matrix.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the matrix is not orthonormal (within a tolerance of
+    /// `1e-6`) or is a reflection (determinant -1).
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::rotation::Rotation;
+    /// use nalgebra::{Matrix3, Vector3};
+    ///
+    /// let matrix = Matrix3::new(0.0, -1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let camera = root
+    ///     .add_child("camera", Vector3::zeros(), Rotation::from_matrix(matrix).unwrap())
+    ///     .unwrap();
+    /// ```
+    pub fn from_matrix(matrix: Matrix3<f64>) -> Result<Self, CartesianTreeError> {
+        if matrix.determinant() <= 0.0 {
+            return Err(invalid_matrix("determinant is not positive (reflection)"));
+        }
+        let deviation = (matrix.transpose() * matrix - Matrix3::identity())
+            .abs()
+            .max();
+        if deviation > MATRIX_TOLERANCE {
+            return Err(invalid_matrix(&format!(
+                "columns are not orthonormal (deviation {deviation:e})"
+            )));
+        }
+        Ok(Self::Quaternion(UnitQuaternion::from_rotation_matrix(
+            &Rotation3::from_matrix_unchecked(matrix),
+        )))
+    }
+
+    /// Creates a Rotation from the rotation matrix closest to the given 3x3 matrix.
+    ///
+    /// Useful for matrices that drifted from orthonormality, e.g. through accumulated rounding.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the matrix is singular or a reflection.
+    pub fn from_matrix_orthonormalized(matrix: Matrix3<f64>) -> Result<Self, CartesianTreeError> {
+        if matrix.determinant() <= 0.0 {
+            return Err(invalid_matrix(
+                "determinant is not positive (singular or reflection)",
+            ));
+        }
+        let svd = matrix.svd(true, true);
+        let (Some(u), Some(v_t)) = (svd.u, svd.v_t) else {
+            return Err(invalid_matrix("singular value decomposition failed"));
+        };
+        Self::from_matrix(u * v_t)
+    }
+
+    /// Creates the orientation of a frame looking along `forward`, using the
+    /// [`LookAtConvention::Camera`] convention (-Z forward, +Y up).
+    ///
+    /// The up axis of the frame is the component of `up` perpendicular to `forward`.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if either vector is zero or the vectors are parallel.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::rotation::Rotation;
+    /// use nalgebra::Vector3;
+    ///
+    /// let rotation = Rotation::look_at(Vector3::new(0.0, 0.0, -1.0), Vector3::y()).unwrap();
+    /// assert!(rotation.angle_to(&Rotation::identity()) < 1e-12);
+    /// ```
+    pub fn look_at(forward: Vector3<f64>, up: Vector3<f64>) -> Result<Self, CartesianTreeError> {
+        Self::look_at_with(forward, up, LookAtConvention::Camera)
+    }
+
+    /// Creates the orientation of a frame looking along `forward` using the given convention.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if either vector is zero or the vectors are parallel.
+    pub fn look_at_with(
+        forward: Vector3<f64>,
+        up: Vector3<f64>,
+        convention: LookAtConvention,
+    ) -> Result<Self, CartesianTreeError> {
+        let forward = forward
+            .try_normalize(0.0)
+            .ok_or_else(|| degenerate_look_at("forward vector is zero"))?;
+        let up = up
+            .try_normalize(0.0)
+            .ok_or_else(|| degenerate_look_at("up vector is zero"))?;
+        let side = forward
+            .cross(&up)
+            .try_normalize(LOOK_AT_TOLERANCE)
+            .ok_or_else(|| degenerate_look_at("forward and up vectors are parallel"))?;
+        let true_up = side.cross(&forward);
+        let axes = match convention {
+            // x = right, y = up, z = backward
+            LookAtConvention::Camera => [side, true_up, -forward],
+            // x = forward, y = left, z = up
+            LookAtConvention::Tool => [forward, -side, true_up],
+        };
+        Ok(Self::Quaternion(UnitQuaternion::from_rotation_matrix(
+            &Rotation3::from_matrix_unchecked(Matrix3::from_columns(&axes)),
+        )))
+    }
+
+    /// Creates the identity rotation using the identity quaternion.
+    #[must_use]
+    pub fn identity() -> Self {
+        Self::Quaternion(UnitQuaternion::identity())
+    }
+
+    /// Interpolates along the shortest arc between this rotation (`t = 0`) and `other` (`t = 1`).
+    ///
+    /// For rotations exactly half a turn apart, the arc about the axis of the relative rotation
+    /// is used.
+    #[must_use]
+    pub fn slerp(&self, other: &Self, t: f64) -> Self {
+        let start = self.as_quaternion();
+        let mut delta = start.inverse() * other.as_quaternion();
+        if delta.w < 0.0 {
+            delta = UnitQuaternion::new_unchecked(-delta.into_inner());
+        }
+        Self::Quaternion(start * UnitQuaternion::from_scaled_axis(delta.scaled_axis() * t))
+    }
+
+    /// Returns the angle in radians of the rotation between this rotation and `other`, in
+    /// `[0, pi]`.
+    ///
+    /// The result does not depend on the sign of the underlying quaternions.
+    #[must_use]
+    pub fn angle_to(&self, other: &Self) -> f64 {
+        let delta = self.as_quaternion().inverse() * other.as_quaternion();
+        // atan2 stays accurate for small angles, where acos(w) loses precision.
+        2.0 * delta.imag().norm().atan2(delta.w.abs())
+    }
+
+    /// Converts to RPY (roll, pitch, yaw) in degrees.
+    #[must_use]
+    pub fn to_rpy_degrees(&self) -> Vector3<f64> {
+        self.as_rpy().map(f64::to_degrees)
+    }
+
+    /// Converts to a unit axis and an angle in radians within `[0, pi]`.
+    ///
+    /// A zero rotation yields the x-axis with an angle of zero.
+    #[must_use]
+    pub fn to_axis_angle(self) -> (Unit<Vector3<f64>>, f64) {
         match self {
-            Self::Quaternion(q) => {
-                let (roll, pitch, yaw) = UnitQuaternion::euler_angles(q);
-                Vector3::new(roll, pitch, yaw)
+            Self::AxisAngle(axis, angle) => {
+                // Wraps the angle into `(-pi, pi]` and flips the axis for negative angles.
+                let mut angle = angle % TAU;
+                if angle > PI {
+                    angle -= TAU;
+                } else if angle <= -PI {
+                    angle += TAU;
+                }
+                if angle < 0.0 {
+                    (-axis, -angle)
+                } else {
+                    (axis, angle)
+                }
             }
-            Self::Rpy(rpy) => *rpy,
+            _ => self
+                .as_quaternion()
+                .axis_angle()
+                .unwrap_or_else(|| (Vector3::x_axis(), 0.0)),
         }
     }
 }
 
-impl From<UnitQuaternion<f64>> for Rotation {
-    fn from(q: UnitQuaternion<f64>) -> Self {
+impl<T: Real> From<UnitQuaternion<T>> for Rotation<T> {
+    fn from(q: UnitQuaternion<T>) -> Self {
         Self::Quaternion(q)
     }
 }
+
+impl<T: Real> From<&Self> for Rotation<T> {
+    fn from(rotation: &Self) -> Self {
+        *rotation
+    }
+}
+
+/// Interprets the array as quaternion components in the order `[x, y, z, w]` and normalizes
+/// them.
+impl<T: Real> From<[T; 4]> for Rotation<T> {
+    fn from([x, y, z, w]: [T; 4]) -> Self {
+        Self::from_quaternion(x, y, z, w)
+    }
+}
+
+/// Interprets the tuple as quaternion components in the order `(x, y, z, w)` and normalizes
+/// them.
+impl<T: Real> From<(T, T, T, T)> for Rotation<T> {
+    fn from((x, y, z, w): (T, T, T, T)) -> Self {
+        Self::from_quaternion(x, y, z, w)
+    }
+}
+
+/// Interprets the array as roll, pitch and yaw angles in radians.
+impl<T: Real> From<[T; 3]> for Rotation<T> {
+    fn from([roll, pitch, yaw]: [T; 3]) -> Self {
+        Self::from_rpy(roll, pitch, yaw)
+    }
+}
+
+/// Roll-pitch-yaw angles in degrees, usable wherever an orientation is expected.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::Frame;
+/// use cartesian_tree::rotation::Degrees;
+/// use nalgebra::Vector3;
+///
+/// let root = Frame::new_origin("world").unwrap();
+/// let camera = root
+///     .add_child("camera", Vector3::zeros(), Degrees((0.0, 0.0, 90.0)))
+///     .unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Degrees(pub (f64, f64, f64));
+
+impl From<Degrees> for Rotation {
+    fn from(Degrees((roll, pitch, yaw)): Degrees) -> Self {
+        Self::from_rpy_degrees(roll, pitch, yaw)
+    }
+}
+
+impl<T: Real> From<Rotation3<T>> for Rotation<T> {
+    fn from(rotation: Rotation3<T>) -> Self {
+        Self::Quaternion(UnitQuaternion::from_rotation_matrix(&rotation))
+    }
+}
+
+impl TryFrom<Matrix3<f64>> for Rotation {
+    type Error = CartesianTreeError;
+
+    fn try_from(matrix: Matrix3<f64>) -> Result<Self, Self::Error> {
+        Self::from_matrix(matrix)
+    }
+}
+
+/// Interprets the nested arrays as rows, matching the usual notation and numpy's layout.
+impl TryFrom<[[f64; 3]; 3]> for Rotation {
+    type Error = CartesianTreeError;
+
+    fn try_from(rows: [[f64; 3]; 3]) -> Result<Self, Self::Error> {
+        Self::from_matrix(Matrix3::from_fn(|row, column| rows[row][column]))
+    }
+}
+
+/// Rotations are equal if they rotate points alike, regardless of their representation: `q` and
+/// `-q` are the same rotation.
+///
+/// Representations are compared exactly after converting them to quaternions, so e.g. RPY
+/// angles and the quaternion of the same rotation may differ in the last bits. Compare with a
+/// tolerance instead, e.g. with [`Rotation::angle_to`] or, with the `approx` feature, with
+/// [`approx::relative_eq!`].
+///
+/// # Example
+/// ```
+/// use cartesian_tree::rotation::Rotation;
+///
+/// let half = std::f64::consts::FRAC_1_SQRT_2;
+/// let rotation = Rotation::from_quaternion(0.0, 0.0, half, half);
+/// assert_eq!(rotation, Rotation::from_quaternion(0.0, 0.0, -half, -half));
+/// ```
+impl<T: Real> PartialEq for Rotation<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_quaternion() == other.as_quaternion()
+    }
+}
+
+fn degenerate_look_at(reason: &str) -> CartesianTreeError {
+    CartesianTreeError::DegenerateInput {
+        reason: reason.to_string(),
+    }
+}
+
+fn invalid_matrix(reason: &str) -> CartesianTreeError {
+    CartesianTreeError::InvalidRotationMatrix {
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn conversions_from_plain_values() {
+        let expected = UnitQuaternion::from_euler_angles(0.1, -0.4, 2.0);
+        let [x, y, z, w] = [expected.i, expected.j, expected.k, expected.w];
+
+        for rotation in [
+            Rotation::from([x, y, z, w]),
+            Rotation::from((x, y, z, w)),
+            // Scaled components are normalized.
+            Rotation::from([2.0 * x, 2.0 * y, 2.0 * z, 2.0 * w]),
+            Rotation::from([0.1, -0.4, 2.0]),
+            Rotation::from(&Rotation::from(expected)),
+        ] {
+            assert!(rotation.as_quaternion().angle_to(&expected) < 1e-12);
+        }
+        // The scalar part comes last, so [1, 0, 0, 0] is a half turn about x, not the identity.
+        assert_relative_eq!(
+            Rotation::from([1.0, 0.0, 0.0, 0.0]).as_quaternion().angle(),
+            std::f64::consts::PI
+        );
+    }
+
+    #[test]
+    fn rotations_equal_regardless_of_the_quaternion_sign() {
+        let q = UnitQuaternion::from_euler_angles(0.3, -0.2, 1.1);
+        let negated = UnitQuaternion::new_unchecked(-q.into_inner());
+        assert_eq!(Rotation::Quaternion(q), Rotation::Quaternion(negated));
+        assert_eq!(
+            Rotation::from_quaternion(0.0, 0.0, 0.0, 1.0),
+            Rotation::from_quaternion(0.0, 0.0, 0.0, -1.0)
+        );
+        assert_ne!(Rotation::Quaternion(q), Rotation::Quaternion(q.inverse()));
+        // Representations are compared as quaternions.
+        let quarter = Rotation::from_axis_angle(Vector3::z_axis(), std::f64::consts::FRAC_PI_2);
+        assert_eq!(quarter, Rotation::Quaternion(quarter.as_quaternion()));
+    }
+
+    #[test]
+    fn rotation_vector_round_trip() {
+        for (roll, pitch, yaw) in [
+            (0.1, -0.2, 0.3),
+            (1.5, 0.7, -2.9),
+            (0.0, 0.0, std::f64::consts::PI - 1e-3),
+            (1e-9, 0.0, 0.0),
+        ] {
+            let q = UnitQuaternion::from_euler_angles(roll, pitch, yaw);
+            let vector = Rotation::from(q).to_rotation_vector();
+            let back = Rotation::from_rotation_vector(vector).as_quaternion();
+            assert!(back.angle_to(&q) < 1e-12);
+        }
+    }
+
+    #[test]
+    fn axis_angle_conversions() {
+        let rotation = Rotation::from_axis_angle(Vector3::z_axis(), std::f64::consts::FRAC_PI_2);
+        assert_relative_eq!(
+            rotation.as_quaternion() * Vector3::x(),
+            Vector3::y(),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            rotation.as_rpy(),
+            Vector3::new(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            epsilon = 1e-12
+        );
+
+        let (axis, angle) = Rotation::from_rpy(0.0, -0.5, 0.0).to_axis_angle();
+        assert_relative_eq!(axis.into_inner(), -Vector3::y(), epsilon = 1e-12);
+        assert_relative_eq!(angle, 0.5, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn axis_angle_is_normalized_to_half_turn() {
+        let (axis, angle) = Rotation::from_axis_angle(Vector3::z_axis(), -1.0).to_axis_angle();
+        assert_relative_eq!(axis.into_inner(), -Vector3::z(), epsilon = 1e-12);
+        assert_relative_eq!(angle, 1.0, epsilon = 1e-12);
+
+        let rotation = Rotation::from_rotation_vector(Vector3::new(0.0, 4.0, 0.0));
+        let (axis, angle) = rotation.to_axis_angle();
+        assert_relative_eq!(axis.into_inner(), -Vector3::y(), epsilon = 1e-12);
+        assert_relative_eq!(angle, TAU - 4.0, epsilon = 1e-12);
+        assert_relative_eq!(
+            Rotation::from_axis_angle(axis, angle).as_quaternion(),
+            rotation.as_quaternion(),
+            epsilon = 1e-12
+        );
+
+        let (_, angle) = Rotation::from_axis_angle(Vector3::x_axis(), 7.0 * PI).to_axis_angle();
+        assert_relative_eq!(angle, PI, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn matrix_conversions() {
+        let rows = [[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]];
+        let rotation = Rotation::try_from(rows).unwrap();
+        assert_relative_eq!(
+            rotation.as_rpy(),
+            Vector3::new(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            rotation.to_matrix(),
+            Matrix3::new(0.0, -1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0),
+            epsilon = 1e-12
+        );
+
+        let q = UnitQuaternion::from_euler_angles(0.4, -0.3, 2.0);
+        let round_trip = Rotation::from_matrix(Rotation::from(q).to_matrix()).unwrap();
+        assert!(round_trip.as_quaternion().angle_to(&q) < 1e-12);
+        let from_rotation3 = Rotation::from(q.to_rotation_matrix());
+        assert!(from_rotation3.as_quaternion().angle_to(&q) < 1e-12);
+    }
+
+    #[test]
+    fn invalid_matrices_are_rejected() {
+        let reflection = Matrix3::from_diagonal(&Vector3::new(1.0, 1.0, -1.0));
+        assert!(matches!(
+            Rotation::from_matrix(reflection),
+            Err(CartesianTreeError::InvalidRotationMatrix { .. })
+        ));
+        assert!(Rotation::from_matrix_orthonormalized(reflection).is_err());
+        assert!(Rotation::from_matrix_orthonormalized(Matrix3::zeros()).is_err());
+
+        let drifted = Rotation::from_rpy(0.1, 0.2, 0.3).to_matrix() * 1.001;
+        assert!(matches!(
+            Rotation::try_from(drifted),
+            Err(CartesianTreeError::InvalidRotationMatrix { .. })
+        ));
+        let repaired = Rotation::from_matrix_orthonormalized(drifted).unwrap();
+        assert_relative_eq!(
+            repaired.as_rpy(),
+            Vector3::new(0.1, 0.2, 0.3),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn degree_conversions() {
+        assert_eq!(
+            Rotation::from_rpy_degrees(90.0, -90.0, 180.0).as_rpy(),
+            Vector3::new(
+                std::f64::consts::FRAC_PI_2,
+                -std::f64::consts::FRAC_PI_2,
+                std::f64::consts::PI
+            )
+        );
+        assert_eq!(
+            Rotation::from(Degrees((0.0, 0.0, 90.0))).as_rpy(),
+            Vector3::new(0.0, 0.0, std::f64::consts::FRAC_PI_2)
+        );
+
+        for degrees in [0.0, 1.0, 15.0, 30.0, 45.0, 60.0, 90.0, 135.0, -120.0, 33.3] {
+            let mut angle = degrees;
+            for _ in 0..100 {
+                angle = Rotation::from_rpy_degrees(angle, 0.0, 0.0)
+                    .to_rpy_degrees()
+                    .x;
+            }
+            assert_relative_eq!(angle, degrees, max_relative = 1e-14);
+        }
+    }
+
+    #[test]
+    fn rotation_algebra() {
+        let a = Rotation::from_rpy(0.3, -0.2, 1.1);
+        let b = Rotation::from_axis_angle(Vector3::y_axis(), 0.7);
+        let composed = a.compose(&b);
+        assert!(matches!(composed, Rotation::Quaternion(_)));
+        assert!(
+            composed
+                .as_quaternion()
+                .angle_to(&(a.as_quaternion() * b.as_quaternion()))
+                < 1e-12
+        );
+        assert!(a.compose(&a.inverse()).angle_to(&Rotation::identity()) < 1e-12);
+
+        let slerped = Rotation::identity().slerp(&b, 0.25);
+        assert_relative_eq!(
+            slerped.to_rotation_vector(),
+            Vector3::y() * 0.175,
+            epsilon = 1e-12
+        );
+        assert!(a.slerp(&b, 0.0).angle_to(&a) < 1e-12);
+        assert!(a.slerp(&b, 1.0).angle_to(&b) < 1e-12);
+    }
+
+    #[test]
+    fn slerp_takes_shortest_arc() {
+        let start = Rotation::from_rpy(0.0, 0.0, 3.0);
+        let end = Rotation::Quaternion(UnitQuaternion::new_unchecked(
+            -UnitQuaternion::from_euler_angles(0.0, 0.0, -3.0).into_inner(),
+        ));
+        let halfway = start.slerp(&end, 0.5);
+        assert_relative_eq!(
+            halfway.as_rpy().z.abs(),
+            std::f64::consts::PI,
+            epsilon = 1e-12
+        );
+
+        let half_turn = Rotation::from_axis_angle(Vector3::z_axis(), std::f64::consts::PI);
+        let quarter = Rotation::identity().slerp(&half_turn, 0.5);
+        assert_relative_eq!(
+            quarter.as_rpy().z,
+            std::f64::consts::FRAC_PI_2,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn angle_to_ignores_quaternion_sign() {
+        let a = Rotation::from_rpy(0.0, 0.0, 0.4);
+        let b = Rotation::from_rpy(0.0, 0.0, -2.8);
+        assert_relative_eq!(a.angle_to(&b), std::f64::consts::TAU - 3.2, epsilon = 1e-12);
+        let negated = Rotation::Quaternion(UnitQuaternion::new_unchecked(
+            -b.as_quaternion().into_inner(),
+        ));
+        assert_relative_eq!(a.angle_to(&negated), a.angle_to(&b), epsilon = 1e-12);
+        assert_relative_eq!(
+            Rotation::identity().angle_to(&Rotation::from_rpy(0.0, 0.0, std::f64::consts::PI)),
+            std::f64::consts::PI,
+            epsilon = 1e-12
+        );
+        assert!(a.angle_to(&a).abs() < 1e-12);
+    }
+
+    #[test]
+    fn look_at_conventions() {
+        // A camera on the +X axis looking at the origin with +Z up.
+        let camera = Rotation::look_at(-Vector3::x(), Vector3::z()).unwrap();
+        assert_relative_eq!(
+            camera.as_quaternion().into_inner(),
+            Quaternion::new(0.5, 0.5, 0.5, 0.5),
+            epsilon = 1e-12
+        );
+
+        let tool =
+            Rotation::look_at_with(-Vector3::x(), Vector3::z(), LookAtConvention::Tool).unwrap();
+        let q = tool.as_quaternion();
+        assert_relative_eq!(q * Vector3::x(), -Vector3::x(), epsilon = 1e-12);
+        assert_relative_eq!(q * Vector3::z(), Vector3::z(), epsilon = 1e-12);
+
+        // Up vectors are projected onto the plane perpendicular to forward.
+        let tilted = Rotation::look_at(-Vector3::z(), Vector3::new(0.0, 1.0, 1.0)).unwrap();
+        assert!(tilted.angle_to(&Rotation::identity()) < 1e-12);
+    }
+
+    #[test]
+    fn look_at_rejects_degenerate_vectors() {
+        for (forward, up) in [
+            (Vector3::zeros(), Vector3::z()),
+            (Vector3::x(), Vector3::zeros()),
+            (Vector3::z(), Vector3::z() * 3.0),
+            (Vector3::z(), -Vector3::z()),
+        ] {
+            assert!(matches!(
+                Rotation::look_at(forward, up),
+                Err(CartesianTreeError::DegenerateInput { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn zero_rotation_has_deterministic_axis() {
+        let from_vector = Rotation::from_rotation_vector(Vector3::zeros());
+        assert_eq!(from_vector.to_axis_angle(), (Vector3::x_axis(), 0.0));
+        assert_eq!(
+            Rotation::identity().to_axis_angle(),
+            (Vector3::x_axis(), 0.0)
+        );
+        assert_eq!(Rotation::identity().to_rotation_vector(), Vector3::zeros());
+        assert_eq!(from_vector.as_quaternion(), UnitQuaternion::identity());
+    }
+}
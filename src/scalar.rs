@@ -0,0 +1,65 @@
+//! The floating-point types frames, poses and rotations can be built on.
+//!
+//! Everything defaults to `f64`. Trees of `f32` frames, e.g. for game engines or GPUs, are
+//! created with [`Frame::origin`](crate::Frame::origin) and support the core operations:
+//! building, updating and resolving transforms, and expressing poses in other frames.
+//!
+//! # Example
+//! ```
+//! use cartesian_tree::Frame;
+//! use nalgebra::{UnitQuaternion, Vector3};
+//!
+//! let world = Frame::<f32>::origin("world").unwrap();
+//! let camera = world
+//!     .add_child("camera", Vector3::new(0.0, 0.0, 1.5), UnitQuaternion::identity())
+//!     .unwrap();
+//! assert_eq!(camera.world_transform().unwrap().translation.z, 1.5_f32);
+//! ```
+
+#[cfg(feature = "std")]
+use nalgebra::Isometry3;
+use nalgebra::RealField;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A floating-point type usable as the scalar of a frame tree, i.e. `f32` or `f64`.
+pub trait Real: RealField + Copy {
+    /// The precision recorded in serialized trees of this type.
+    const PRECISION: Precision;
+}
+
+impl Real for f32 {
+    const PRECISION: Precision = Precision::F32;
+}
+
+impl Real for f64 {
+    const PRECISION: Precision = Precision::F64;
+}
+
+/// The floating-point precision of a serialized tree, see [`Frame::to_json`](crate::Frame::to_json).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Precision {
+    /// Single precision (`f32`).
+    F32,
+    /// Double precision (`f64`).
+    #[default]
+    F64,
+}
+
+/// Converts `value` to `f64`, which holds every value of a [`Real`] type exactly.
+pub(crate) fn to_f64<T: Real>(value: T) -> f64 {
+    nalgebra::convert_unchecked(value)
+}
+
+/// Converts `value` to `T`, rounding it to the nearest value for `f32`.
+pub(crate) fn from_f64<T: Real>(value: f64) -> T {
+    nalgebra::convert(value)
+}
+
+/// Converts `isometry` to `f64`, like [`to_f64`].
+#[cfg(feature = "std")]
+pub(crate) fn isometry_to_f64<T: Real>(isometry: Isometry3<T>) -> Isometry3<f64> {
+    nalgebra::convert_unchecked(isometry)
+}
@@ -0,0 +1,180 @@
+//! Versions of the JSON format written by [`Frame::to_json`](crate::Frame::to_json).
+//!
+//! Documents carry their version in the top-level `schema_version` field; documents without it
+//! were written before the field existed and are version 1. [`Frame::from_json`] and
+//! [`Frame::apply_config`] migrate older documents step by step to [`SCHEMA_VERSION`] before
+//! reading them, so configs written by older builds keep working.
+//!
+//! To change the format, bump [`SCHEMA_VERSION`] and append a migration from the previous
+//! version to [`MIGRATIONS`].
+//!
+//! [`Frame::from_json`]: crate::Frame::from_json
+//! [`Frame::apply_config`]: crate::Frame::apply_config
+
+use crate::CartesianTreeError;
+use serde_json::{Map, Value};
+
+/// The version of the JSON format written by this build.
+pub const SCHEMA_VERSION: u64 = 2;
+
+/// Migrations between consecutive versions; the entry at index `i` migrates version `i + 1`.
+const MIGRATIONS: &[fn(&mut Value)] = &[tag_orientations];
+
+/// Migrates `document` in place to [`SCHEMA_VERSION`].
+///
+/// # Errors
+/// Returns a [`CartesianTreeError`] if:
+/// - The `schema_version` is not a positive integer.
+/// - The document was written by a newer build, or its version was never supported.
+pub(crate) fn migrate(document: &mut Value) -> Result<(), CartesianTreeError> {
+    let found = match document.get("schema_version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| CartesianTreeError::InvalidDocument {
+                path: "$.schema_version".to_string(),
+                reason: "expected a positive integer".to_string(),
+            })?,
+    };
+    if !(1..=SCHEMA_VERSION).contains(&found) {
+        return Err(CartesianTreeError::UnsupportedConfigVersion {
+            found,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    let applied = usize::try_from(found - 1).unwrap_or(usize::MAX);
+    for migration in MIGRATIONS.iter().skip(applied) {
+        migration(document);
+    }
+    if let Some(object) = document.as_object_mut() {
+        object.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+    }
+    Ok(())
+}
+
+/// Version 1 → 2: orientations are tagged with their representation.
+///
+/// Version 1 stored the quaternion `[x, y, z, w]` directly as `"orientation"` of frames and
+/// poses; version 2 stores it as `{"quaternion": [x, y, z, w]}`. Malformed nodes are left as they
+/// are for the reader to report.
+fn tag_orientations(document: &mut Value) {
+    let mut pending = vec![document];
+    while let Some(node) = pending.pop() {
+        let Some(object) = node.as_object_mut() else {
+            continue;
+        };
+        tag_orientation(object);
+        if let Some(poses) = object.get_mut("poses").and_then(Value::as_object_mut) {
+            for pose in poses.values_mut().filter_map(Value::as_object_mut) {
+                tag_orientation(pose);
+            }
+        }
+        if let Some(children) = object.get_mut("children").and_then(Value::as_array_mut) {
+            pending.extend(children);
+        }
+    }
+}
+
+fn tag_orientation(node: &mut Map<String, Value>) {
+    if let Some(orientation) = node.get_mut("orientation").filter(|value| value.is_array()) {
+        let quaternion = orientation.take();
+        *orientation = Value::Object(Map::from_iter([("quaternion".to_string(), quaternion)]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Frame;
+    use crate::tree::Walking;
+
+    const WORKCELL_V1: &str = include_str!("../tests/fixtures/workcell_v1.json");
+    const WORKCELL_V2: &str = include_str!("../tests/fixtures/workcell_v2.json");
+
+    /// Lists path, transform, poses and metadata of every frame for comparing trees.
+    fn describe(root: &Frame) -> Vec<String> {
+        root.iter_dfs()
+            .map(|frame| {
+                let poses: Vec<_> = frame
+                    .pose_names()
+                    .into_iter()
+                    .map(|name| (frame.get_pose(&name).unwrap().transformation(), name))
+                    .collect();
+                let metadata: Vec<_> = frame
+                    .metadata_keys()
+                    .into_iter()
+                    .map(|key| (frame.get_metadata(&key), key))
+                    .collect();
+                format!(
+                    "{} {:?} {poses:?} {metadata:?}",
+                    frame.path(),
+                    frame.borrow().transform_to_parent,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn v1_fixture_loads_like_v2_fixture() {
+        let v1 = Frame::from_json(WORKCELL_V1).unwrap();
+        let v2 = Frame::from_json(WORKCELL_V2).unwrap();
+        assert_eq!(v1.iter_dfs().count(), 4);
+        assert_eq!(describe(&v1), describe(&v2));
+        assert_eq!(v1.to_json().unwrap(), v2.to_json().unwrap());
+
+        // Applied configs are migrated as well.
+        let target = Frame::from_json(WORKCELL_V2).unwrap();
+        for frame in target.iter_dfs().filter(|frame| frame.depth() > 0) {
+            frame
+                .set(
+                    nalgebra::Vector3::zeros(),
+                    nalgebra::UnitQuaternion::identity(),
+                )
+                .unwrap();
+        }
+        target.apply_config(WORKCELL_V1).unwrap();
+        assert_eq!(describe(&target), describe(&v2));
+    }
+
+    #[test]
+    fn every_version_has_a_migration() {
+        assert_eq!(u64::try_from(MIGRATIONS.len()).unwrap() + 1, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn to_json_writes_the_current_version() {
+        let json = Frame::from_json(WORKCELL_V1).unwrap().to_json().unwrap();
+        let document: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(document["schema_version"], SCHEMA_VERSION);
+        assert!(document["orientation"]["quaternion"].is_array());
+        assert!(document["children"][0]["poses"]["home"]["orientation"]["quaternion"].is_array());
+    }
+
+    #[test]
+    fn unsupported_versions_are_rejected() {
+        let versioned = |version: &str| {
+            WORKCELL_V2.replacen(
+                "\"schema_version\": 2",
+                &format!("\"schema_version\": {version}"),
+                1,
+            )
+        };
+        for version in [0, SCHEMA_VERSION + 1] {
+            match Frame::from_json(&versioned(&version.to_string())) {
+                Err(CartesianTreeError::UnsupportedConfigVersion { found, supported }) => {
+                    assert_eq!((found, supported), (version, SCHEMA_VERSION));
+                }
+                other => panic!("unexpected result: {other:?}"),
+            }
+        }
+        let root = Frame::new_origin("world").unwrap();
+        assert!(matches!(
+            root.apply_config(&versioned("3")),
+            Err(CartesianTreeError::UnsupportedConfigVersion { found: 3, .. })
+        ));
+        assert!(matches!(
+            Frame::from_json(&versioned("\"2\"")),
+            Err(CartesianTreeError::InvalidDocument { path, .. }) if path == "$.schema_version"
+        ));
+    }
+}
@@ -0,0 +1,951 @@
+//! Snapshots of frame trees and poses.
+//!
+//! Frames and poses hold shared, single-threaded references into the tree. Snapshots capture
+//! their content as plain data instead:
+//! - [`TreeSnapshot`] is an immutable, `Send + Sync` view of a whole tree that answers
+//!   transform queries from any thread.
+//! - [`TreeState`] records only the transforms of a subtree, so they can be restored later, e.g.
+//!   to undo edits.
+//! - [`FrameTreeSnapshot`] and [`PoseSnapshot`] (feature `serde`) can be persisted with any
+//!   serde data format and turned back into live frames and poses later.
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+#[cfg(feature = "serde")]
+use crate::pose::Pose;
+use crate::tree::{HasChildren, HasParent, Walking};
+use nalgebra::Isometry3;
+#[cfg(feature = "serde")]
+use nalgebra::{UnitQuaternion, Vector3};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// An immutable point-in-time copy of a frame tree.
+///
+/// Frames are addressed by their absolute path (see [`Frame::path`]), resolved through their
+/// names, so the paths themselves are not stored. Cloning is cheap, as the frames are shared
+/// between clones.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::Frame;
+/// use nalgebra::{UnitQuaternion, Vector3};
+///
+/// let root = Frame::new_origin("world").unwrap();
+/// let arm = root
+///     .add_child("arm", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+///     .unwrap();
+/// let snapshot = root.snapshot();
+///
+/// let handle = std::thread::spawn(move || snapshot.world_transform("/world/arm").unwrap());
+/// assert_eq!(handle.join().unwrap().translation.x, 1.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct TreeSnapshot {
+    frames: Arc<[SnapshotFrame]>,
+}
+
+/// A frame captured in a [`TreeSnapshot`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnapshotFrame {
+    /// The name of the frame.
+    pub name: String,
+    /// The index of the parent frame in the snapshot, `None` for the root.
+    pub parent: Option<usize>,
+    /// The transformation from this frame to its parent frame.
+    pub transform_to_parent: Isometry3<f64>,
+    /// The transformation from this frame to the root frame.
+    pub world_transform: Isometry3<f64>,
+    children: Vec<usize>,
+}
+
+impl Frame {
+    /// Captures the whole tree containing this frame in an immutable [`TreeSnapshot`].
+    ///
+    /// Later changes to the tree do not affect the snapshot.
+    #[must_use]
+    pub fn snapshot(&self) -> TreeSnapshot {
+        let root = self.root();
+        let mut frames: Vec<SnapshotFrame> = Vec::new();
+        let mut stack = vec![(root, None)];
+        while let Some((frame, parent)) = stack.pop() {
+            let index = frames.len();
+            let (name, transform_to_parent) = {
+                let data = frame.borrow();
+                (data.name.to_string(), data.transform_to_parent)
+            };
+            let world_transform = parent.map_or_else(Isometry3::identity, |parent_index| {
+                let parent: &mut SnapshotFrame = &mut frames[parent_index];
+                parent.children.push(index);
+                parent.world_transform * transform_to_parent
+            });
+            frames.push(SnapshotFrame {
+                name,
+                parent,
+                transform_to_parent,
+                world_transform,
+                children: Vec::new(),
+            });
+            stack.extend(
+                frame
+                    .children()
+                    .into_iter()
+                    .rev()
+                    .map(|child| (child, Some(index))),
+            );
+        }
+        TreeSnapshot {
+            frames: frames.into(),
+        }
+    }
+}
+
+impl TreeSnapshot {
+    /// Returns the captured frame at the given path.
+    ///
+    /// Paths are resolved from the root, whether or not they start with `/`, and may contain
+    /// `.` and `..` segments.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if a segment of the path cannot be resolved.
+    pub fn frame(&self, path: &str) -> Result<&SnapshotFrame, CartesianTreeError> {
+        let unresolved = |segment: &str| CartesianTreeError::PathNotFound {
+            path: path.to_string(),
+            segment: segment.to_string(),
+        };
+
+        let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+        let first = segments.next().unwrap_or_default();
+        if self.frames[0].name != first {
+            return Err(unresolved(first));
+        }
+        let mut current = 0;
+        for segment in segments {
+            current = match segment {
+                "." => current,
+                ".." => self.frames[current]
+                    .parent
+                    .ok_or_else(|| unresolved(segment))?,
+                name => *self.frames[current]
+                    .children
+                    .iter()
+                    .find(|&&child| self.frames[child].name == name)
+                    .ok_or_else(|| unresolved(segment))?,
+            };
+        }
+        Ok(&self.frames[current])
+    }
+
+    /// Returns the transformation from the frame at `path` to the root frame.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the path cannot be resolved.
+    pub fn world_transform(&self, path: &str) -> Result<Isometry3<f64>, CartesianTreeError> {
+        Ok(self.frame(path)?.world_transform)
+    }
+
+    /// Returns the transformation mapping coordinates in the frame at `from` to coordinates in
+    /// the frame at `to`, like [`Frame::transform_to`].
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if either path cannot be resolved.
+    pub fn transform_between(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<Isometry3<f64>, CartesianTreeError> {
+        Ok(self.world_transform(to)?.inverse() * self.world_transform(from)?)
+    }
+
+    /// Returns the absolute path of the frame at `index`, built from the names of its ancestors.
+    ///
+    /// Returns `None` if the snapshot has no frame at `index`.
+    #[must_use]
+    pub fn path(&self, index: usize) -> Option<String> {
+        self.frames.get(index)?;
+        let mut names = Vec::new();
+        let mut current = Some(index);
+        while let Some(index) = current {
+            names.push(self.frames[index].name.as_str());
+            current = self.frames[index].parent;
+        }
+        names.push("");
+        names.reverse();
+        Some(names.join("/"))
+    }
+
+    /// Returns the root frame of the snapshot.
+    #[must_use]
+    pub fn root(&self) -> &SnapshotFrame {
+        &self.frames[0]
+    }
+
+    /// Returns the number of captured frames.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns whether the snapshot contains no frames, which is never the case.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Returns an iterator over the captured frames in depth-first pre-order.
+    ///
+    /// The position of a frame in this order is the index used by [`SnapshotFrame::parent`].
+    pub fn iter(&self) -> std::slice::Iter<'_, SnapshotFrame> {
+        self.frames.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a TreeSnapshot {
+    type Item = &'a SnapshotFrame;
+    type IntoIter = std::slice::Iter<'a, SnapshotFrame>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The transforms of a frame and its descendants, see [`Frame::capture_state`].
+///
+/// Frames are addressed by their path relative to the captured frame, which itself is addressed
+/// by `.` (unless it is a root, whose transform cannot change). Like in a [`TreeSnapshot`], only
+/// the names of the frames are stored, and paths are built on demand.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TreeState {
+    /// The captured frames in depth-first pre-order, starting with the captured frame.
+    frames: Vec<StateFrame>,
+}
+
+/// A frame captured in a [`TreeState`].
+#[derive(Clone, Debug, PartialEq)]
+struct StateFrame {
+    name: String,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// The transform to the parent, `None` for a root.
+    transform: Option<Isometry3<f64>>,
+}
+
+impl TreeState {
+    /// Returns the captured transform to the parent of the frame at `path`, if any.
+    #[must_use]
+    pub fn transform(&self, path: &str) -> Option<&Isometry3<f64>> {
+        let mut current = 0;
+        if path != "." {
+            for segment in path.split('/') {
+                current = *self
+                    .frames
+                    .get(current)?
+                    .children
+                    .iter()
+                    .find(|&&child| self.frames[child].name == segment)?;
+            }
+        }
+        self.frames.get(current)?.transform.as_ref()
+    }
+
+    /// Returns the paths of all captured frames in lexicographic order.
+    pub fn paths(&self) -> impl Iterator<Item = String> {
+        let mut paths: Vec<String> = (0..self.frames.len())
+            .filter(|&index| self.frames[index].transform.is_some())
+            .map(|index| self.path(index))
+            .collect();
+        paths.sort_unstable();
+        paths.into_iter()
+    }
+
+    /// Returns the number of captured frames.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.frames
+            .iter()
+            .filter(|frame| frame.transform.is_some())
+            .count()
+    }
+
+    /// Returns `true` if no frame was captured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the path of the frame at `index` relative to the captured frame.
+    fn path(&self, index: usize) -> String {
+        if index == 0 {
+            return ".".to_string();
+        }
+        let mut names = Vec::new();
+        let mut current = index;
+        while current != 0 {
+            names.push(self.frames[current].name.as_str());
+            current = self.frames[current].parent.unwrap_or_default();
+        }
+        names.reverse();
+        names.join("/")
+    }
+
+    /// Returns the paths of the frame at `index` and its descendants.
+    fn subtree_paths(&self, index: usize) -> Vec<String> {
+        let mut paths = Vec::new();
+        let mut stack = vec![index];
+        while let Some(index) = stack.pop() {
+            paths.push(self.path(index));
+            stack.extend(&self.frames[index].children);
+        }
+        paths
+    }
+}
+
+impl Frame {
+    /// Records the transforms to the parents of this frame and its descendants.
+    ///
+    /// Unlike [`Frame::to_json`], only the transforms are copied, so capturing is cheap enough
+    /// to do before every edit.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let arm = root
+    ///     .add_child("arm", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let state = root.capture_state();
+    ///
+    /// arm.set(Vector3::new(2.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// root.restore_state(&state).unwrap();
+    /// assert_eq!(arm.position(), Vector3::new(1.0, 0.0, 0.0));
+    /// ```
+    #[must_use]
+    pub fn capture_state(&self) -> TreeState {
+        let mut frames: Vec<StateFrame> = Vec::new();
+        let mut stack: Vec<(Self, Option<usize>)> = vec![(self.clone(), None)];
+        while let Some((frame, parent)) = stack.pop() {
+            let index = frames.len();
+            if let Some(parent) = parent {
+                frames[parent].children.push(index);
+            }
+            let transform = frame.parent().map(|_| frame.borrow().transform_to_parent);
+            frames.push(StateFrame {
+                name: frame.name(),
+                parent,
+                children: Vec::new(),
+                transform,
+            });
+            stack.extend(
+                frame
+                    .children()
+                    .into_iter()
+                    .rev()
+                    .map(|child| (child, Some(index))),
+            );
+        }
+        TreeState { frames }
+    }
+
+    /// Writes the transforms recorded by [`Frame::capture_state`] back into the tree.
+    ///
+    /// Frames are matched by their path relative to this frame. Frames added since the capture
+    /// keep their transforms; structure, metadata and stored poses are not touched. Subscribers
+    /// are notified for every frame whose transform changes.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError::MissingFrames`] listing the paths of captured frames that
+    /// no longer exist. No transform is changed in this case.
+    pub fn restore_state(&self, state: &TreeState) -> Result<(), CartesianTreeError> {
+        let mut missing = Vec::new();
+        let mut updates = Vec::new();
+        let mut stack = Vec::new();
+        if !state.frames.is_empty() {
+            stack.push((self.clone(), 0));
+        }
+        while let Some((frame, index)) = stack.pop() {
+            let captured = &state.frames[index];
+            if let Some(transform) = &captured.transform
+                && frame.borrow().transform_to_parent != *transform
+            {
+                updates.push((frame.clone(), transform));
+            }
+            let mut children: HashMap<String, Self> = frame
+                .children()
+                .into_iter()
+                .map(|child| (child.name(), child))
+                .collect();
+            for &child in &captured.children {
+                match children.remove(&state.frames[child].name) {
+                    Some(frame) => stack.push((frame, child)),
+                    None => missing.extend(state.subtree_paths(child)),
+                }
+            }
+        }
+        if !missing.is_empty() {
+            missing.sort_unstable();
+            return Err(CartesianTreeError::MissingFrames { paths: missing });
+        }
+        for (frame, transform) in updates {
+            frame.set(transform.translation.vector, transform.rotation)?;
+        }
+        Ok(())
+    }
+}
+
+/// A serializable copy of a frame and all its descendants.
+///
+/// Snapshots are captured and dropped iteratively, but serde data formats read and write them one
+/// level per frame, so very deep trees are better persisted with [`Frame::to_json`].
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FrameTreeSnapshot {
+    /// The name of the frame.
+    pub name: String,
+    /// The translation of the frame relative to its parent.
+    pub position: Vector3<f64>,
+    /// The orientation of the frame relative to its parent.
+    pub orientation: UnitQuaternion<f64>,
+    /// Snapshots of the child frames.
+    pub children: Vec<Self>,
+}
+
+#[cfg(feature = "serde")]
+impl FrameTreeSnapshot {
+    /// Captures the given frame and its descendants.
+    ///
+    /// The captured frame becomes the root of the snapshot, so its own transform is stored
+    /// as identity.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::snapshot::FrameTreeSnapshot;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// root.add_child("robot", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let snapshot = FrameTreeSnapshot::from_frame(&root);
+    /// assert_eq!(snapshot.children[0].name, "robot");
+    /// ```
+    #[must_use]
+    pub fn from_frame(frame: &Frame) -> Self {
+        let mut snapshot = Self::capture(frame);
+        snapshot.position = Vector3::zeros();
+        snapshot.orientation = UnitQuaternion::identity();
+        snapshot
+    }
+
+    fn capture(frame: &Frame) -> Self {
+        // Like `Frame::to_json`, nodes are collected in depth-first pre-order with the index of
+        // their parent, and moved into their parents from the last one on.
+        let mut nodes: Vec<(Self, Option<usize>)> = Vec::new();
+        let mut stack = vec![(frame.clone(), None)];
+        while let Some((frame, parent)) = stack.pop() {
+            let index = nodes.len();
+            nodes.push((Self::capture_fields(&frame), parent));
+            for child in frame.children().into_iter().rev() {
+                stack.push((child, Some(index)));
+            }
+        }
+        loop {
+            let (mut node, parent) = nodes.pop().expect("the captured frame is the first node");
+            // Children are moved in from the last to the first.
+            node.children.reverse();
+            match parent {
+                Some(parent) => nodes[parent].0.children.push(node),
+                None => return node,
+            }
+        }
+    }
+
+    /// Captures the given frame without its children.
+    fn capture_fields(frame: &Frame) -> Self {
+        let data = frame.borrow();
+        Self {
+            name: data.name.to_string(),
+            position: data.transform_to_parent.translation.vector,
+            orientation: data.transform_to_parent.rotation,
+            children: Vec::new(),
+        }
+    }
+
+    /// Rebuilds a new frame tree from this snapshot.
+    ///
+    /// # Returns
+    /// The root frame of the reconstructed tree.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the snapshot contains siblings with the same name.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::snapshot::FrameTreeSnapshot;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// root.add_child("robot", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let copy = FrameTreeSnapshot::from_frame(&root).to_frame().unwrap();
+    /// assert!(copy.resolve_path("/world/robot").is_ok());
+    /// ```
+    pub fn to_frame(&self) -> Result<Frame, CartesianTreeError> {
+        let root = Frame::new_origin(self.name.clone())?;
+        let mut stack: Vec<(Frame, &Self)> = vec![(root.clone(), self)];
+        while let Some((frame, snapshot)) = stack.pop() {
+            for child in &snapshot.children {
+                let child_frame =
+                    frame.add_child(child.name.clone(), child.position, child.orientation)?;
+                stack.push((child_frame, child));
+            }
+        }
+        Ok(root)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Drop for FrameTreeSnapshot {
+    fn drop(&mut self) {
+        // Like for frames, deep snapshots are dismantled iteratively.
+        let mut pending = core::mem::take(&mut self.children);
+        while let Some(mut child) = pending.pop() {
+            pending.append(&mut child.children);
+        }
+    }
+}
+
+/// A serializable copy of a pose, referencing its frame by path.
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PoseSnapshot {
+    /// The absolute path of the frame the pose is expressed in (see [`Frame::path`]).
+    pub frame: String,
+    /// The position of the pose in its frame.
+    pub position: Vector3<f64>,
+    /// The orientation of the pose in its frame.
+    pub orientation: UnitQuaternion<f64>,
+}
+
+#[cfg(feature = "serde")]
+impl PoseSnapshot {
+    /// Captures the given pose.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the frame of the pose has been dropped.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::snapshot::PoseSnapshot;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let pose = root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    ///
+    /// let snapshot = PoseSnapshot::from_pose(&pose).unwrap();
+    /// assert_eq!(snapshot.frame, "/world");
+    /// ```
+    pub fn from_pose(pose: &Pose) -> Result<Self, CartesianTreeError> {
+        let frame = pose.frame()?;
+        let transform = pose.transformation();
+        Ok(Self {
+            frame: frame.path(),
+            position: transform.translation.vector,
+            orientation: transform.rotation,
+        })
+    }
+
+    /// Recreates the pose in the tree containing `frame`.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the stored frame path cannot be resolved.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::snapshot::PoseSnapshot;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let pose = root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// let snapshot = PoseSnapshot::from_pose(&pose).unwrap();
+    ///
+    /// let restored = snapshot.to_pose(&root).unwrap();
+    /// assert_eq!(restored.position(), pose.position());
+    /// ```
+    pub fn to_pose(&self, frame: &Frame) -> Result<Pose, CartesianTreeError> {
+        let target = frame.resolve_path(&self.frame)?;
+        target.add_pose(self.position, self.orientation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "serde")]
+    use crate::rotation::Rotation;
+    #[cfg(feature = "serde")]
+    use crate::tree::NodeEquality;
+    use approx::assert_relative_eq;
+    use nalgebra::{Point3, UnitQuaternion, Vector3};
+
+    fn build_tree() -> Frame {
+        let root = Frame::new_origin("world").unwrap();
+        let arm = root
+            .add_child(
+                "robot",
+                Vector3::new(0.1, 1.0 / 3.0, -2.7),
+                UnitQuaternion::from_euler_angles(0.3, -1.1, 2.9),
+            )
+            .unwrap();
+        arm.add_child(
+            "camera",
+            Vector3::new(1e-9, 7.123_456_789, 0.2),
+            UnitQuaternion::from_euler_angles(1.0 / 7.0, 0.0, -0.6),
+        )
+        .unwrap();
+        root.add_child(
+            "table",
+            Vector3::new(2.0, 0.0, 0.0),
+            UnitQuaternion::identity(),
+        )
+        .unwrap();
+        root
+    }
+
+    #[cfg(feature = "serde")]
+    fn transforms(root: &Frame) -> Vec<(String, Isometry3<f64>)> {
+        root.iter_dfs()
+            .map(|frame| {
+                let data = frame.borrow();
+                (data.name.to_string(), data.transform_to_parent)
+            })
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn frame_tree_json_round_trip() {
+        let root = build_tree();
+
+        let json = serde_json::to_string(&FrameTreeSnapshot::from_frame(&root)).unwrap();
+        let snapshot: FrameTreeSnapshot = serde_json::from_str(&json).unwrap();
+        let copy = snapshot.to_frame().unwrap();
+
+        assert!(!copy.is_same(&root));
+        assert_eq!(transforms(&copy), transforms(&root));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn frame_tree_yaml_round_trip() {
+        let root = build_tree();
+
+        let yaml = serde_yaml::to_string(&FrameTreeSnapshot::from_frame(&root)).unwrap();
+        let snapshot: FrameTreeSnapshot = serde_yaml::from_str(&yaml).unwrap();
+        let copy = snapshot.to_frame().unwrap();
+
+        assert!(!copy.is_same(&root));
+        assert_eq!(transforms(&copy), transforms(&root));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn subtree_snapshot_becomes_root() {
+        let root = build_tree();
+        let arm = root.resolve_path("/world/robot").unwrap();
+
+        let copy = FrameTreeSnapshot::from_frame(&arm).to_frame().unwrap();
+
+        assert_eq!(copy.name(), "robot");
+        assert!(copy.parent().is_none());
+        assert_eq!(transforms(&copy)[1], transforms(&arm)[1]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn duplicate_names_in_snapshot() {
+        let mut snapshot = FrameTreeSnapshot::from_frame(&build_tree());
+        let duplicate = snapshot.children[0].clone();
+        snapshot.children.push(duplicate);
+
+        assert!(matches!(
+            snapshot.to_frame(),
+            Err(CartesianTreeError::DuplicateChildName { parent, child })
+                if parent == "world" && child == "robot"
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn very_deep_chain_snapshot() {
+        const DEPTH: usize = 100_000;
+        let root = Frame::new_origin("root").unwrap();
+        let mut leaf = root.clone();
+        for i in 0..DEPTH {
+            leaf = leaf
+                .add_child(format!("f{i}"), Vector3::x(), UnitQuaternion::identity())
+                .unwrap();
+        }
+
+        let snapshot = FrameTreeSnapshot::from_frame(&root);
+        let copy = snapshot.to_frame().unwrap();
+        drop(snapshot);
+        let copy_leaf = copy.iter_dfs().last().unwrap();
+        assert_eq!(copy_leaf.name(), format!("f{}", DEPTH - 1));
+        assert_relative_eq!(
+            copy_leaf.world_transform().unwrap().translation.vector,
+            Vector3::new(100_000.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn pose_json_round_trip() {
+        let root = build_tree();
+        let camera = root.resolve_path("robot/camera").unwrap();
+        let pose = camera
+            .add_pose(
+                Vector3::new(0.3, -0.7, 1.0 / 9.0),
+                UnitQuaternion::from_euler_angles(-0.2, 0.4, 3.0),
+            )
+            .unwrap();
+
+        let json = serde_json::to_string(&PoseSnapshot::from_pose(&pose).unwrap()).unwrap();
+        let snapshot: PoseSnapshot = serde_json::from_str(&json).unwrap();
+        let copy = FrameTreeSnapshot::from_frame(&root).to_frame().unwrap();
+        let restored = snapshot.to_pose(&copy).unwrap();
+
+        assert_eq!(restored.frame().unwrap().path(), "/world/robot/camera");
+        assert_eq!(restored.transformation(), pose.transformation());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn rotation_json_round_trip() {
+        for rotation in [
+            Rotation::from_rpy(0.1, -0.2, 0.3),
+            Rotation::from_quaternion(0.1, 0.2, 0.3, 0.9),
+        ] {
+            let json = serde_json::to_string(&rotation).unwrap();
+            let copy: Rotation = serde_json::from_str(&json).unwrap();
+            assert_eq!(copy.as_quaternion(), rotation.as_quaternion());
+        }
+    }
+
+    #[test]
+    fn tree_snapshot_matches_live_tree() {
+        let root = build_tree();
+        let camera = root.resolve_path("robot/camera").unwrap();
+        let table = root.resolve_path("table").unwrap();
+
+        let snapshot = camera.snapshot();
+
+        assert_eq!(snapshot.len(), 4);
+        assert_eq!(snapshot.root().name, "world");
+        let paths: Vec<_> = (0..snapshot.len())
+            .map(|index| snapshot.path(index).unwrap())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "/world",
+                "/world/robot",
+                "/world/robot/camera",
+                "/world/table"
+            ]
+        );
+        assert_eq!(
+            snapshot.frame("/world/robot/camera").unwrap().parent,
+            Some(1)
+        );
+        assert_relative_eq!(
+            snapshot.world_transform("/world/robot/camera").unwrap(),
+            camera.world_transform().unwrap(),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            snapshot
+                .transform_between("/world/robot/camera", "world/table")
+                .unwrap(),
+            camera.transform_to(&table).unwrap(),
+            epsilon = 1e-12
+        );
+        assert!(snapshot.frame("/world/robot/../table/.").is_ok());
+        assert_eq!(snapshot.path(4), None);
+    }
+
+    #[test]
+    fn tree_snapshot_ignores_later_changes() {
+        let root = build_tree();
+        let arm = root.resolve_path("robot").unwrap();
+        let snapshot = root.snapshot();
+        let copy = snapshot.clone();
+        let before = snapshot.world_transform("/world/robot/camera").unwrap();
+
+        arm.set(Vector3::new(5.0, 5.0, 5.0), UnitQuaternion::identity())
+            .unwrap();
+        arm.add_child("gripper", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        root.remove_child("table").unwrap();
+
+        for view in [&snapshot, &copy] {
+            assert_eq!(view.world_transform("/world/robot/camera").unwrap(), before);
+            assert!(view.frame("/world/table").is_ok());
+            assert!(matches!(
+                view.frame("/world/robot/gripper"),
+                Err(CartesianTreeError::PathNotFound { segment, .. }) if segment == "gripper"
+            ));
+        }
+    }
+
+    #[test]
+    fn tree_snapshot_is_shared_across_threads() {
+        let snapshot = build_tree().snapshot();
+        let expected = snapshot.world_transform("/world/robot/camera").unwrap() * Point3::origin();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let snapshot = snapshot.clone();
+                std::thread::spawn(move || {
+                    snapshot
+                        .transform_between("/world/robot/camera", "/world")
+                        .unwrap()
+                        * Point3::origin()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn tree_snapshot_rejects_unknown_paths() {
+        let snapshot = build_tree().snapshot();
+
+        for (path, missing) in [("/earth/robot", "earth"), ("/world/..", ".."), ("", "")] {
+            assert!(matches!(
+                snapshot.world_transform(path),
+                Err(CartesianTreeError::PathNotFound { segment, .. }) if segment == missing
+            ));
+        }
+    }
+
+    #[test]
+    fn restore_state_undoes_edits() {
+        let root = build_tree();
+        let arm = root.resolve_path("robot").unwrap();
+        let camera = root.resolve_path("robot/camera").unwrap();
+        let table = root.resolve_path("table").unwrap();
+        let state = root.capture_state();
+        assert_eq!(
+            state.paths().collect::<Vec<_>>(),
+            ["robot", "robot/camera", "table"]
+        );
+        let before: Vec<_> = [&arm, &camera, &table]
+            .iter()
+            .map(|frame| frame.transformation().unwrap())
+            .collect();
+
+        arm.set(Vector3::new(5.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        camera
+            .apply_in_local_frame(&Isometry3::rotation(Vector3::new(0.0, 0.0, 0.4)))
+            .unwrap();
+        table
+            .set(
+                Vector3::zeros(),
+                UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3),
+            )
+            .unwrap();
+        camera.set_metadata("serial", "A-17");
+        arm.add_child(
+            "gripper",
+            Vector3::new(0.0, 0.0, 0.1),
+            UnitQuaternion::identity(),
+        )
+        .unwrap();
+
+        root.restore_state(&state).unwrap();
+        let after: Vec<_> = [&arm, &camera, &table]
+            .iter()
+            .map(|frame| frame.transformation().unwrap())
+            .collect();
+        assert_eq!(before, after);
+        assert_eq!(
+            camera.world_transform().unwrap(),
+            root.snapshot()
+                .world_transform("/world/robot/camera")
+                .unwrap()
+        );
+        assert!(camera.get_metadata("serial").is_some());
+        assert_eq!(
+            arm.resolve_path("gripper").unwrap().position(),
+            Vector3::new(0.0, 0.0, 0.1)
+        );
+
+        // A subtree state includes the captured frame itself.
+        let arm_state = arm.capture_state();
+        assert!(arm_state.transform(".").is_some());
+        assert_eq!(arm_state.len(), 3);
+        assert!(
+            Frame::new_origin("empty")
+                .unwrap()
+                .capture_state()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn very_deep_chain_snapshot_and_state() {
+        // Storing the path of every frame takes quadratic memory in the depth of the tree.
+        const DEPTH: usize = 100_000;
+        let root = Frame::new_origin("root").unwrap();
+        let mut leaf = root.clone();
+        for i in 0..DEPTH {
+            leaf = leaf
+                .add_child(format!("f{i}"), Vector3::x(), UnitQuaternion::identity())
+                .unwrap();
+        }
+
+        let snapshot = root.snapshot();
+        assert_eq!(snapshot.len(), DEPTH + 1);
+        assert_eq!(snapshot.path(2).unwrap(), "/root/f0/f1");
+        assert_relative_eq!(
+            snapshot.world_transform(&leaf.path()).unwrap(),
+            leaf.world_transform().unwrap()
+        );
+
+        let state = root.capture_state();
+        assert_eq!(state.len(), DEPTH);
+        leaf.set(Vector3::y(), UnitQuaternion::identity()).unwrap();
+        root.restore_state(&state).unwrap();
+        assert_eq!(leaf.position(), Vector3::x());
+    }
+
+    #[test]
+    fn restore_state_reports_missing_frames() {
+        let root = build_tree();
+        let state = root.capture_state();
+        let table = root.resolve_path("table").unwrap();
+        table
+            .set(Vector3::new(9.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        let _robot = root.remove_child("robot").unwrap();
+
+        let Err(CartesianTreeError::MissingFrames { paths }) = root.restore_state(&state) else {
+            panic!("restoring removed frames must fail");
+        };
+        assert_eq!(paths, ["robot", "robot/camera"]);
+        // Nothing is restored if any frame is missing.
+        assert_eq!(table.position(), Vector3::new(9.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,330 @@
+//! Spatial queries over the origins of frames, see [`Frame::frames_within`] and
+//! [`Frame::closest_frame_to`].
+//!
+//! Queries search the frame they are called on and its descendants, so calling them on the root
+//! searches the whole tree and calling them on another frame restricts them to its subtree. The
+//! `_matching` variants further restrict them to frames whose names satisfy a predicate.
+//!
+//! Distances are measured between the origins of the frames and the queried point, in the
+//! shared coordinates of the root. Each frame's position is taken from its cached world
+//! transform (see [`Frame::world_transform`]), so repeated queries on an unchanged tree only
+//! compute the distances. Results are sorted by distance; frames at the same distance keep
+//! their depth-first pre-order, see [`Frame::iter_dfs`].
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::validation::check_position;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use nalgebra::Point3;
+
+impl Frame {
+    /// Returns this frame and its descendants whose origins are within `radius` of `center`,
+    /// closest first, each with its distance.
+    ///
+    /// Frames exactly `radius` away are included. See the [module documentation](crate::spatial)
+    /// for how frames are searched and ordered.
+    ///
+    /// # Arguments
+    /// - `center`: The center of the search, expressed in `expressed_in`.
+    /// - `radius`: The largest distance of a returned frame, in the units of the tree.
+    /// - `expressed_in`: The frame `center` is expressed in, which may be any frame of the tree.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `expressed_in` belongs to another tree.
+    /// - A component of `center` is NaN or infinite, or `radius` is negative or NaN.
+    /// - A searched frame or `expressed_in` is scaled, see [`Frame::world_transform`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Point3, UnitQuaternion, Vector3};
+    ///
+    /// let world = Frame::new_origin("world").unwrap();
+    /// let table = world
+    ///     .add_child("table", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// table.add_child("cup", Vector3::new(0.0, 0.0, 0.3), UnitQuaternion::identity()).unwrap();
+    ///
+    /// let near = world.frames_within(Point3::new(0.0, 0.0, 0.4), 0.5, &table).unwrap();
+    /// let names: Vec<_> = near.iter().map(|(frame, _)| frame.name()).collect();
+    /// assert_eq!(names, ["cup", "table"]);
+    /// ```
+    pub fn frames_within(
+        &self,
+        center: Point3<f64>,
+        radius: f64,
+        expressed_in: &Self,
+    ) -> Result<Vec<(Self, f64)>, CartesianTreeError> {
+        self.frames_within_matching(center, radius, expressed_in, |_| true)
+    }
+
+    /// Like [`Frame::frames_within`], but only returns frames whose names satisfy `predicate`.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] in the same cases as [`Frame::frames_within`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Point3, UnitQuaternion, Vector3};
+    ///
+    /// let world = Frame::new_origin("world").unwrap();
+    /// world.add_child("marker_1", Vector3::new(0.2, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// world.add_child("camera", Vector3::new(0.1, 0.0, 0.0), UnitQuaternion::identity()).unwrap();
+    ///
+    /// let markers = world
+    ///     .frames_within_matching(Point3::origin(), 0.5, &world, |name| name.starts_with("marker_"))
+    ///     .unwrap();
+    /// assert_eq!(markers.len(), 1);
+    /// ```
+    pub fn frames_within_matching(
+        &self,
+        center: Point3<f64>,
+        radius: f64,
+        expressed_in: &Self,
+        predicate: impl Fn(&str) -> bool,
+    ) -> Result<Vec<(Self, f64)>, CartesianTreeError> {
+        if radius.is_nan() || radius < 0.0 {
+            return Err(CartesianTreeError::DegenerateInput {
+                reason: "the radius must not be negative or NaN".to_string(),
+            });
+        }
+        let mut frames = self.distances_to(center, expressed_in, predicate)?;
+        frames.retain(|(_, distance)| *distance <= radius);
+        Ok(frames)
+    }
+
+    /// Returns the frame among this frame and its descendants whose origin is closest to
+    /// `point`, with its distance.
+    ///
+    /// Of several frames at the same distance, the first in depth-first pre-order is returned.
+    ///
+    /// # Arguments
+    /// - `point`: The point to search from, expressed in `expressed_in`.
+    /// - `expressed_in`: The frame `point` is expressed in, which may be any frame of the tree.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - `expressed_in` belongs to another tree.
+    /// - A component of `point` is NaN or infinite.
+    /// - A searched frame or `expressed_in` is scaled, see [`Frame::world_transform`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{Point3, UnitQuaternion, Vector3};
+    ///
+    /// let world = Frame::new_origin("world").unwrap();
+    /// world.add_child("left", Vector3::new(0.0, 1.0, 0.0), UnitQuaternion::identity()).unwrap();
+    /// world.add_child("right", Vector3::new(0.0, -1.0, 0.0), UnitQuaternion::identity()).unwrap();
+    ///
+    /// let (closest, distance) = world
+    ///     .closest_frame_to(Point3::new(0.0, -0.8, 0.0), &world)
+    ///     .unwrap();
+    /// assert_eq!(closest.name(), "right");
+    /// assert!((distance - 0.2).abs() < 1e-12);
+    /// ```
+    pub fn closest_frame_to(
+        &self,
+        point: Point3<f64>,
+        expressed_in: &Self,
+    ) -> Result<(Self, f64), CartesianTreeError> {
+        let mut frames = self.distances_to(point, expressed_in, |_| true)?;
+        // This frame itself is always searched, so there is at least one frame.
+        Ok(frames.swap_remove(0))
+    }
+
+    /// Like [`Frame::closest_frame_to`], but only considers frames whose names satisfy
+    /// `predicate`, returning `None` if none does.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] in the same cases as [`Frame::closest_frame_to`].
+    pub fn closest_frame_to_matching(
+        &self,
+        point: Point3<f64>,
+        expressed_in: &Self,
+        predicate: impl Fn(&str) -> bool,
+    ) -> Result<Option<(Self, f64)>, CartesianTreeError> {
+        Ok(self
+            .distances_to(point, expressed_in, predicate)?
+            .into_iter()
+            .next())
+    }
+
+    /// Returns the matching frames of the subtree of this frame with the distances of their
+    /// origins to `point`, closest first.
+    fn distances_to(
+        &self,
+        point: Point3<f64>,
+        expressed_in: &Self,
+        predicate: impl Fn(&str) -> bool,
+    ) -> Result<Vec<(Self, f64)>, CartesianTreeError> {
+        check_position("point", &point.coords)?;
+        if self.lowest_common_ancestor(expressed_in).is_none() {
+            return Err(CartesianTreeError::NoCommonAncestor {
+                from: expressed_in.name(),
+                to: self.name(),
+            });
+        }
+        let point = expressed_in.world_transform()? * point;
+        let mut frames = Vec::new();
+        for frame in self.iter_dfs() {
+            // The name is copied out, so the predicate may access the frame it is visiting.
+            if predicate(&frame.name()) {
+                let origin = frame.world_transform()?.translation.vector;
+                let distance = (origin - point.coords).norm();
+                frames.push((frame, distance));
+            }
+        }
+        // Sorting is stable, so ties keep their depth-first order.
+        frames.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        Ok(frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use nalgebra::{UnitQuaternion, Vector3};
+
+    /// Builds a 3x3 grid of frames 1 m apart in the xy plane of a `grid` frame, which is lifted
+    /// 1 m, with the cells named `cell_<row>_<column>`. The offsets are exact, so distances tie.
+    fn grid() -> (Frame, Frame) {
+        let world = Frame::new_origin("world").unwrap();
+        let grid = world
+            .add_child(
+                "grid",
+                Vector3::new(0.0, 0.0, 1.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        for row in 0..3 {
+            for column in 0..3 {
+                grid.add_child(
+                    format!("cell_{row}_{column}"),
+                    Vector3::new(f64::from(column), f64::from(row), 0.0),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+            }
+        }
+        (world, grid)
+    }
+
+    fn names(frames: &[(Frame, f64)]) -> Vec<String> {
+        frames.iter().map(|(frame, _)| frame.name()).collect()
+    }
+
+    #[test]
+    fn frames_within_a_radius_are_sorted_by_distance() {
+        let (world, grid) = grid();
+        let center = Point3::new(1.0, 1.0, 0.0);
+        let near = world.frames_within(center, 1.0, &grid).unwrap();
+        // Ties keep the depth-first order of the grid.
+        assert_eq!(
+            names(&near),
+            ["cell_1_1", "cell_0_1", "cell_1_0", "cell_1_2", "cell_2_1"]
+        );
+        assert_relative_eq!(near[0].1, 0.0, epsilon = 1e-12);
+        // Exactly on the boundary.
+        for (_, distance) in &near[1..] {
+            assert_relative_eq!(*distance, 1.0, epsilon = 0.0);
+        }
+
+        // The same point expressed in the world, where the grid is lifted.
+        let again = world
+            .frames_within(Point3::new(1.0, 1.0, 1.0), 1.0, &world)
+            .unwrap();
+        assert_eq!(names(&again), names(&near));
+
+        // The grid frame is as far away as the corners and precedes them in depth-first order.
+        let corners = world.frames_within(center, 1.5, &grid).unwrap();
+        assert_eq!(
+            names(&corners[5..]),
+            ["grid", "cell_0_0", "cell_0_2", "cell_2_0", "cell_2_2"]
+        );
+        assert_relative_eq!(corners[5].1, 2.0_f64.sqrt());
+        let all = world.frames_within(center, f64::INFINITY, &grid).unwrap();
+        assert_eq!(all.len(), 11);
+        assert_eq!(all[10].0.name(), "world");
+        assert_relative_eq!(all[10].1, 3.0_f64.sqrt());
+
+        let diagonal = world
+            .frames_within_matching(center, 2.0, &grid, |name| {
+                name.len() == 8 && name[5..6] == name[7..8]
+            })
+            .unwrap();
+        assert_eq!(names(&diagonal), ["cell_1_1", "cell_0_0", "cell_2_2"]);
+
+        // The search is restricted to the subtree of the frame it is called on.
+        let cell = grid.resolve_path("cell_2_2").unwrap();
+        let subtree = cell.frames_within(center, 5.0, &grid).unwrap();
+        assert_eq!(names(&subtree), ["cell_2_2"]);
+
+        assert!(world.frames_within(center, -1.0, &grid).is_err());
+        assert!(world.frames_within(center, f64::NAN, &grid).is_err());
+        assert!(
+            world
+                .frames_within(Point3::new(f64::NAN, 0.0, 0.0), 1.0, &grid)
+                .is_err()
+        );
+        let other = Frame::new_origin("other").unwrap();
+        assert!(matches!(
+            world.frames_within(center, 1.0, &other),
+            Err(CartesianTreeError::NoCommonAncestor { .. })
+        ));
+    }
+
+    #[test]
+    fn closest_frames_follow_the_tree() {
+        let (world, grid) = grid();
+        let (closest, distance) = world
+            .closest_frame_to(Point3::new(1.9, 0.1, 0.5), &grid)
+            .unwrap();
+        assert_eq!(closest.name(), "cell_0_2");
+        assert_relative_eq!(distance, (0.01_f64 + 0.01 + 0.25).sqrt(), epsilon = 1e-12);
+
+        // Of the four cells around the center of a square, the first one is returned.
+        let (tied, _) = world
+            .closest_frame_to(Point3::new(1.5, 1.5, 0.0), &grid)
+            .unwrap();
+        assert_eq!(tied.name(), "cell_1_1");
+
+        // Moving the grid updates the cached positions.
+        grid.set(Vector3::new(0.0, 0.0, 10.0), UnitQuaternion::identity())
+            .unwrap();
+        let (closest, _) = world.closest_frame_to(Point3::origin(), &world).unwrap();
+        assert_eq!(closest.name(), "world");
+        let (closest, distance) = world
+            .closest_frame_to_matching(Point3::new(2.0, 2.0, 9.0), &world, |name| {
+                name.starts_with("cell_")
+            })
+            .unwrap()
+            .unwrap();
+        assert_eq!(closest.name(), "cell_2_2");
+        assert_relative_eq!(distance, 1.0, epsilon = 1e-12);
+        assert!(
+            world
+                .closest_frame_to_matching(Point3::origin(), &world, |name| name == "missing")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn predicates_may_change_the_visited_frames() {
+        let (world, grid) = grid();
+        let near = world
+            .frames_within_matching(Point3::origin(), 1.0, &grid, |name| {
+                let frame = world.find(name).unwrap();
+                frame.set_metadata("visited", true);
+                name.starts_with("cell_")
+            })
+            .unwrap();
+        assert_eq!(names(&near), ["cell_0_0", "cell_0_1", "cell_1_0"]);
+        assert!(world.get_metadata("visited").is_some());
+    }
+}
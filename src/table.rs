@@ -0,0 +1,266 @@
+//! Flat tables of all frames in a tree with their world transforms, e.g. for reports.
+
+use crate::frame::Frame;
+use crate::rotation::Rotation;
+use crate::tree::{HasParent, Walking};
+use nalgebra::{Isometry3, Vector3};
+use std::fmt::Write;
+
+/// Controls the columns of [`Frame::to_table`] and [`Frame::to_csv`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TableOptions {
+    /// Whether roll, pitch and yaw are given in degrees instead of radians.
+    pub degrees: bool,
+    /// Whether the orientation is given as quaternion `[x, y, z, w]` instead of roll, pitch and
+    /// yaw.
+    pub quaternion: bool,
+}
+
+impl TableOptions {
+    /// Returns the names of the columns, as written in the header of [`Frame::to_csv`].
+    #[must_use]
+    pub fn columns(&self) -> Vec<&'static str> {
+        let orientation: &[&str] = if self.quaternion {
+            &["qx", "qy", "qz", "qw"]
+        } else {
+            &["roll", "pitch", "yaw"]
+        };
+        ["path", "x", "y", "z"]
+            .into_iter()
+            .chain(orientation.iter().copied())
+            .chain(["depth", "parent"])
+            .collect()
+    }
+}
+
+/// One frame in a table built by [`Frame::to_table`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameRow {
+    /// The path of the frame, see [`Frame::path`].
+    pub path: String,
+    /// The position of the frame in the root of its tree.
+    pub position: Vector3<f64>,
+    /// The orientation of the frame in the root of its tree, as roll, pitch and yaw or as
+    /// quaternion `[x, y, z, w]`, depending on the [`TableOptions`].
+    pub orientation: Vec<f64>,
+    /// The number of edges between the frame and the root of its tree.
+    pub depth: usize,
+    /// The path of the parent frame, or `None` for a root.
+    pub parent: Option<String>,
+}
+
+impl Frame {
+    /// Lists this frame and its descendants in depth-first order with their world transforms.
+    ///
+    /// World transforms are taken from the per-frame cache (see [`Frame::world_transform`]), so
+    /// each one is computed at most once. Frames below a scaled frame report the rigid part of
+    /// their similarity to the root, see [`Frame::similarity_to`].
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::table::TableOptions;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// root.add_child("robot", Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let rows = root.to_table(TableOptions::default());
+    /// assert_eq!(rows[1].path, "/world/robot");
+    /// assert_eq!(rows[1].parent.as_deref(), Some("/world"));
+    /// ```
+    #[must_use]
+    pub fn to_table(&self, options: TableOptions) -> Vec<FrameRow> {
+        let root = self.root();
+        self.iter_dfs()
+            .map(|frame| {
+                let world = frame
+                    .world_transform()
+                    .or_else(|_| {
+                        frame
+                            .similarity_to(&root)
+                            .map(|similarity| similarity.isometry)
+                    })
+                    // Frames of the same tree always share `root`.
+                    .unwrap_or_else(|_| Isometry3::identity());
+                let orientation = if options.quaternion {
+                    world.rotation.coords.as_slice().to_vec()
+                } else if options.degrees {
+                    Rotation::from(world.rotation)
+                        .to_rpy_degrees()
+                        .as_slice()
+                        .to_vec()
+                } else {
+                    Rotation::from(world.rotation).as_rpy().as_slice().to_vec()
+                };
+                FrameRow {
+                    path: frame.path(),
+                    position: world.translation.vector,
+                    orientation,
+                    depth: frame.depth(),
+                    parent: frame.parent().map(|parent| parent.path()),
+                }
+            })
+            .collect()
+    }
+
+    /// Writes the table of [`Frame::to_table`] as CSV, with a header and one line per frame.
+    ///
+    /// Numbers are written with full precision; paths containing commas or quotes are quoted.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::table::TableOptions;
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// assert_eq!(
+    ///     root.to_csv(TableOptions::default()),
+    ///     "path,x,y,z,roll,pitch,yaw,depth,parent\n/world,0,0,0,0,0,0,0,\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_csv(&self, options: TableOptions) -> String {
+        let mut csv = options.columns().join(",");
+        csv.push('\n');
+        for row in self.to_table(options) {
+            csv.push_str(&csv_field(&row.path));
+            for value in row.position.iter().chain(&row.orientation) {
+                // Adding zero turns `-0` into `0`.
+                let _ = write!(csv, ",{}", value + 0.0);
+            }
+            let parent = row.parent.as_deref().map(csv_field).unwrap_or_default();
+            let _ = writeln!(csv, ",{},{parent}", row.depth);
+        }
+        csv
+    }
+}
+
+/// Quotes `value` if it contains a separator, a quote or a line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use nalgebra::UnitQuaternion;
+    use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2};
+
+    /// Builds `world -> base -> tool`, with `base` turned by 90° about z.
+    fn workcell() -> Frame {
+        let root = Frame::new_origin("world").unwrap();
+        let base = root
+            .add_child(
+                "base",
+                Vector3::new(1.0, 0.0, 0.0),
+                Rotation::from_rpy(0.0, 0.0, FRAC_PI_2),
+            )
+            .unwrap();
+        base.add_child(
+            "tool, left",
+            Vector3::new(2.0, 0.0, 0.5),
+            UnitQuaternion::identity(),
+        )
+        .unwrap();
+        root
+    }
+
+    #[test]
+    fn csv_lists_world_transforms_in_depth_first_order() {
+        let csv = workcell().to_csv(TableOptions {
+            degrees: true,
+            quaternion: false,
+        });
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "path,x,y,z,roll,pitch,yaw,depth,parent");
+        assert_eq!(lines[1], "/world,0,0,0,0,0,0,0,");
+
+        // Checks the text columns exactly and the numbers up to rounding.
+        let check = |line: &str, path: &str, values: [f64; 6], tail: [&str; 2]| {
+            let fields: Vec<_> = line.rsplitn(9, ',').collect();
+            assert_eq!(fields[8], path);
+            assert_eq!([fields[1], fields[0]], tail);
+            for (field, expected) in fields[2..8].iter().rev().zip(values) {
+                assert_relative_eq!(field.parse::<f64>().unwrap(), expected, epsilon = 1e-12);
+            }
+        };
+        check(
+            lines[2],
+            "/world/base",
+            [1.0, 0.0, 0.0, 0.0, 0.0, 90.0],
+            ["1", "/world"],
+        );
+        check(
+            lines[3],
+            "\"/world/base/tool, left\"",
+            [1.0, 2.0, 0.5, 0.0, 0.0, 90.0],
+            ["2", "/world/base"],
+        );
+    }
+
+    #[test]
+    fn table_options_select_the_orientation_columns() {
+        let root = workcell();
+        let rows = root.to_table(TableOptions::default());
+        assert_relative_eq!(rows[2].orientation[2], FRAC_PI_2, epsilon = 1e-12);
+        assert_eq!(rows[2].parent.as_deref(), Some("/world/base"));
+        assert!(rows[0].parent.is_none());
+
+        let options = TableOptions {
+            degrees: false,
+            quaternion: true,
+        };
+        assert_eq!(
+            options.columns(),
+            [
+                "path", "x", "y", "z", "qx", "qy", "qz", "qw", "depth", "parent"
+            ]
+        );
+        let rows = root.to_table(options);
+        let half = FRAC_1_SQRT_2;
+        for (value, expected) in rows[2].orientation.iter().zip([0.0, 0.0, half, half]) {
+            assert_relative_eq!(*value, expected, epsilon = 1e-12);
+        }
+
+        // Subtrees keep the paths and depths of the whole tree.
+        let base = root.resolve_path("base").unwrap();
+        let rows = base.to_table(options);
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].path.as_str(), rows[0].depth), ("/world/base", 1));
+        assert_eq!(rows[0].position, Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn scaled_frames_report_their_rigid_part() {
+        let root = Frame::new_origin("world").unwrap();
+        let drawing = root
+            .add_scaled_child(
+                "drawing",
+                Vector3::new(1.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+                0.001,
+            )
+            .unwrap();
+        drawing
+            .add_child(
+                "hole",
+                Vector3::new(500.0, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let rows = root.to_table(TableOptions::default());
+        assert_relative_eq!(
+            rows[2].position,
+            Vector3::new(1.5, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+    }
+}
@@ -0,0 +1,433 @@
+//! Ordered sequences of poses, e.g. for motion execution.
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::pose::{Pose, interpolate_isometry};
+use crate::tree::NodeEquality;
+use nalgebra::Isometry3;
+
+/// Determines how a [`Trajectory`] handles poses that are not expressed in its frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FrameMismatch {
+    /// Returns [`CartesianTreeError::PoseNotInFrame`].
+    #[default]
+    Reject,
+    /// Expresses the pose in the frame of the trajectory.
+    Convert,
+}
+
+/// An ordered sequence of poses, all expressed in the same frame.
+///
+/// The trajectory keeps its frame alive.
+///
+/// # Example
+/// ```
+/// use cartesian_tree::Frame;
+/// use cartesian_tree::trajectory::{FrameMismatch, Trajectory};
+/// use nalgebra::{UnitQuaternion, Vector3};
+///
+/// let root = Frame::new_origin("root").unwrap();
+/// let poses = [
+///     root.add_pose(Vector3::new(0.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap(),
+///     root.add_pose(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity()).unwrap(),
+/// ];
+/// let trajectory = Trajectory::new(&root, poses, FrameMismatch::Reject).unwrap();
+///
+/// let resampled = trajectory.resample(5).unwrap();
+/// assert_eq!(resampled.len(), 5);
+/// assert_eq!(resampled[1].position(), Vector3::new(0.25, 0.0, 0.0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Trajectory {
+    frame: Frame,
+    poses: Vec<Pose>,
+    mismatch: FrameMismatch,
+}
+
+impl Trajectory {
+    /// Creates a trajectory in `frame` from the given poses.
+    ///
+    /// # Arguments
+    /// - `frame`: The frame all poses are expressed in.
+    /// - `poses`: The poses, in order.
+    /// - `mismatch`: How poses in other frames are handled, here and in [`Trajectory::push`].
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - A pose is not expressed in `frame` and `mismatch` is [`FrameMismatch::Reject`].
+    /// - A pose cannot be converted into `frame`, e.g. because it belongs to another tree.
+    pub fn new(
+        frame: &Frame,
+        poses: impl IntoIterator<Item = Pose>,
+        mismatch: FrameMismatch,
+    ) -> Result<Self, CartesianTreeError> {
+        let mut trajectory = Self {
+            frame: frame.clone(),
+            poses: Vec::new(),
+            mismatch,
+        };
+        for pose in poses {
+            trajectory.push(pose)?;
+        }
+        Ok(trajectory)
+    }
+
+    /// Appends a pose to the end of the trajectory.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The pose is not expressed in the frame of the trajectory and the trajectory was
+    ///   created with [`FrameMismatch::Reject`].
+    /// - The pose cannot be converted into the frame of the trajectory.
+    pub fn push(&mut self, pose: Pose) -> Result<(), CartesianTreeError> {
+        let in_frame = pose.frame().is_ok_and(|frame| frame.is_same(&self.frame));
+        let pose = match (in_frame, self.mismatch) {
+            (true, _) => pose,
+            (false, FrameMismatch::Convert) => pose.in_frame(&self.frame)?,
+            (false, FrameMismatch::Reject) => {
+                return Err(CartesianTreeError::PoseNotInFrame {
+                    frame: self.frame.name(),
+                    index: self.poses.len(),
+                });
+            }
+        };
+        self.poses.push(pose);
+        Ok(())
+    }
+
+    /// Returns the frame the poses are expressed in.
+    #[must_use]
+    pub fn frame(&self) -> Frame {
+        self.frame.clone()
+    }
+
+    /// Returns the poses in order.
+    #[must_use]
+    pub fn poses(&self) -> &[Pose] {
+        &self.poses
+    }
+
+    /// Returns the number of poses.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.poses.len()
+    }
+
+    /// Returns `true` if the trajectory has no poses.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.poses.is_empty()
+    }
+
+    /// Returns an iterator over the poses in order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Pose> {
+        self.poses.iter()
+    }
+
+    /// Returns the trajectory expressed in `target`.
+    ///
+    /// The transformation between the frames is resolved only once.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the frames do not share a common ancestor.
+    pub fn in_frame(&self, target: &Frame) -> Result<Self, CartesianTreeError> {
+        Ok(Self {
+            frame: target.clone(),
+            poses: self.frame.transform_poses_to(target, &self.poses)?,
+            mismatch: self.mismatch,
+        })
+    }
+
+    /// Creates a trajectory in `target` from transforms relative to it, keeping the mismatch
+    /// policy of this trajectory.
+    ///
+    /// Used by the Python bindings, which convert the transforms without holding the GIL.
+    #[cfg(feature = "bindings")]
+    pub(crate) fn with_transforms(
+        &self,
+        target: &Frame,
+        transforms: impl IntoIterator<Item = Isometry3<f64>>,
+    ) -> Self {
+        Self {
+            frame: target.clone(),
+            poses: transforms
+                .into_iter()
+                .map(|transform| {
+                    Pose::new(target, transform.translation.vector, transform.rotation)
+                })
+                .collect(),
+            mismatch: self.mismatch,
+        }
+    }
+
+    /// Returns the summed translational distance between consecutive poses.
+    #[must_use]
+    pub fn total_length(&self) -> f64 {
+        self.poses
+            .windows(2)
+            .map(|pair| (pair[1].position() - pair[0].position()).norm())
+            .sum()
+    }
+
+    /// Resamples the trajectory into `count` poses evenly spaced along its path.
+    ///
+    /// The first and last poses are kept. Positions are interpolated linearly and orientations
+    /// along the shortest arc between the neighbouring poses. If all poses share the same
+    /// position, the poses are spaced evenly by index instead.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::DegenerateInput`] if the trajectory is empty or `count`
+    /// is less than 2.
+    pub fn resample(&self, count: usize) -> Result<Self, CartesianTreeError> {
+        if self.is_empty() {
+            return Err(degenerate_input("the trajectory is empty"));
+        }
+        if count < 2 {
+            return Err(degenerate_input("resampling needs at least two samples"));
+        }
+        let distances = self.distances();
+        #[allow(clippy::cast_precision_loss)]
+        let knots = if distances[distances.len() - 1] > 0.0 {
+            distances
+        } else {
+            (0..self.len()).map(|index| index as f64).collect()
+        };
+        let end = knots[knots.len() - 1];
+        #[allow(clippy::cast_precision_loss)]
+        let parameters = (0..count).map(|index| end * index as f64 / (count - 1) as f64);
+        let mut resampled = self.interpolated(&knots, parameters);
+        resampled.poses[count - 1] = self.poses[self.len() - 1].clone();
+        Ok(resampled)
+    }
+
+    /// Resamples the trajectory into poses spaced `step` apart along its path.
+    ///
+    /// The first and last poses are kept, so the last step may be shorter. Positions are
+    /// interpolated linearly and orientations along the shortest arc between the neighbouring
+    /// poses.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::DegenerateInput`] if the trajectory is empty or `step` is
+    /// not a positive finite number.
+    pub fn resample_by_distance(&self, step: f64) -> Result<Self, CartesianTreeError> {
+        if self.is_empty() {
+            return Err(degenerate_input("the trajectory is empty"));
+        }
+        if !(step.is_finite() && step > 0.0) {
+            return Err(degenerate_input("the step must be positive and finite"));
+        }
+        let knots = self.distances();
+        let length = knots[knots.len() - 1];
+        let parameters = (0..)
+            .map(|index| f64::from(index) * step)
+            .take_while(|distance| *distance == 0.0 || *distance < length);
+        let mut resampled = self.interpolated(&knots, parameters);
+        if self.len() > 1 {
+            resampled.poses.push(self.poses[self.len() - 1].clone());
+        }
+        Ok(resampled)
+    }
+
+    /// Returns the distance travelled up to each pose.
+    fn distances(&self) -> Vec<f64> {
+        let mut travelled = 0.0;
+        std::iter::once(0.0)
+            .chain(self.poses.windows(2).map(|pair| {
+                travelled += (pair[1].position() - pair[0].position()).norm();
+                travelled
+            }))
+            .collect()
+    }
+
+    /// Interpolates the poses at the given path parameters, given the parameter of each pose.
+    fn interpolated(&self, knots: &[f64], parameters: impl Iterator<Item = f64>) -> Self {
+        let poses = parameters
+            .map(|parameter| {
+                let transform = self.transform_at(knots, parameter);
+                Pose::new(
+                    &self.frame,
+                    transform.translation.vector,
+                    transform.rotation,
+                )
+            })
+            .collect();
+        Self {
+            frame: self.frame.clone(),
+            poses,
+            mismatch: self.mismatch,
+        }
+    }
+
+    /// Returns the interpolated transformation at `parameter`.
+    fn transform_at(&self, knots: &[f64], parameter: f64) -> Isometry3<f64> {
+        if self.len() == 1 {
+            return self.poses[0].transformation();
+        }
+        // The first pose whose parameter is not below `parameter` ends the segment.
+        let segment = knots
+            .partition_point(|knot| *knot < parameter)
+            .clamp(1, knots.len() - 1);
+        let (start, end) = (knots[segment - 1], knots[segment]);
+        let t = if end > start {
+            (parameter - start) / (end - start)
+        } else {
+            0.0
+        };
+        interpolate_isometry(
+            &self.poses[segment - 1].transformation(),
+            &self.poses[segment].transformation(),
+            t,
+        )
+    }
+}
+
+impl std::ops::Index<usize> for Trajectory {
+    type Output = Pose;
+
+    fn index(&self, index: usize) -> &Pose {
+        &self.poses[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a Trajectory {
+    type Item = &'a Pose;
+    type IntoIter = std::slice::Iter<'a, Pose>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.poses.iter()
+    }
+}
+
+fn degenerate_input(reason: &str) -> CartesianTreeError {
+    CartesianTreeError::DegenerateInput {
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use nalgebra::{UnitQuaternion, Vector3};
+
+    fn pose(frame: &Frame, position: [f64; 3], yaw: f64) -> Pose {
+        frame
+            .add_pose(
+                Vector3::from(position),
+                UnitQuaternion::from_euler_angles(0.0, 0.0, yaw),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn resample_matches_pose_interpolation() {
+        let root = Frame::new_origin("root").unwrap();
+        let start = root
+            .add_pose(
+                Vector3::new(0.1, -0.4, 0.2),
+                UnitQuaternion::from_euler_angles(0.3, -0.2, 0.1),
+            )
+            .unwrap();
+        let end = root
+            .add_pose(
+                Vector3::new(0.9, 0.5, -0.3),
+                UnitQuaternion::from_euler_angles(-0.6, 0.4, 2.1),
+            )
+            .unwrap();
+        let trajectory =
+            Trajectory::new(&root, [start.clone(), end.clone()], FrameMismatch::Reject).unwrap();
+
+        let resampled = trajectory.resample(3).unwrap();
+        assert_eq!(resampled.len(), 3);
+        assert_eq!(resampled[0].transformation(), start.transformation());
+        assert_eq!(resampled[2].transformation(), end.transformation());
+        assert_relative_eq!(
+            resampled[1].transformation(),
+            start.interpolate(&end, 0.5).unwrap().transformation(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn resample_spaces_poses_along_the_path() {
+        let root = Frame::new_origin("root").unwrap();
+        let poses = [
+            pose(&root, [0.0, 0.0, 0.0], 0.0),
+            pose(&root, [1.0, 0.0, 0.0], 0.0),
+            pose(&root, [1.0, 3.0, 0.0], 1.0),
+        ];
+        let trajectory = Trajectory::new(&root, poses, FrameMismatch::Reject).unwrap();
+        assert_relative_eq!(trajectory.total_length(), 4.0);
+
+        let resampled = trajectory.resample(5).unwrap();
+        let positions: Vec<_> = resampled.iter().map(Pose::position).collect();
+        assert_relative_eq!(positions[1], Vector3::new(1.0, 0.0, 0.0));
+        assert_relative_eq!(positions[2], Vector3::new(1.0, 1.0, 0.0));
+        assert_relative_eq!(resampled[2].rpy().z, 1.0 / 3.0, epsilon = 1e-12);
+        assert_relative_eq!(resampled.total_length(), 4.0);
+
+        let stepped = trajectory.resample_by_distance(1.5).unwrap();
+        let positions: Vec<_> = stepped.iter().map(Pose::position).collect();
+        assert_eq!(positions.len(), 4);
+        assert_relative_eq!(positions[1], Vector3::new(1.0, 0.5, 0.0));
+        assert_relative_eq!(positions[2], Vector3::new(1.0, 2.0, 0.0));
+        assert_eq!(positions[3], Vector3::new(1.0, 3.0, 0.0));
+
+        // Poses at the same position are spaced by index.
+        let turns = [
+            pose(&root, [0.0, 0.0, 0.0], 0.0),
+            pose(&root, [0.0, 0.0, 0.0], 1.0),
+        ];
+        let turning = Trajectory::new(&root, turns, FrameMismatch::Reject).unwrap();
+        assert_relative_eq!(
+            turning.resample(3).unwrap()[1].rpy().z,
+            0.5,
+            epsilon = 1e-12
+        );
+        assert_eq!(turning.resample_by_distance(0.1).unwrap().len(), 2);
+
+        let empty = Trajectory::new(&root, [], FrameMismatch::Reject).unwrap();
+        for result in [
+            empty.resample(3),
+            trajectory.resample(1),
+            trajectory.resample_by_distance(0.0),
+            trajectory.resample_by_distance(f64::NAN),
+        ] {
+            assert!(matches!(
+                result,
+                Err(CartesianTreeError::DegenerateInput { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn mixed_frames_are_rejected_or_converted() {
+        let root = Frame::new_origin("root").unwrap();
+        let tool = root
+            .add_child(
+                "tool",
+                Vector3::new(0.0, 0.0, 1.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let poses = [
+            pose(&root, [1.0, 0.0, 0.0], 0.0),
+            pose(&tool, [0.0, 1.0, 0.0], 0.0),
+        ];
+
+        assert!(matches!(
+            Trajectory::new(&root, poses.clone(), FrameMismatch::Reject),
+            Err(CartesianTreeError::PoseNotInFrame { frame, index: 1 }) if frame == "root"
+        ));
+        let mut trajectory = Trajectory::new(&root, poses, FrameMismatch::Convert).unwrap();
+        assert_eq!(trajectory[1].position(), Vector3::new(0.0, 1.0, 1.0));
+        let other = Frame::new_origin("other").unwrap();
+        assert!(trajectory.push(pose(&other, [0.0; 3], 0.0)).is_err());
+        assert_eq!(trajectory.len(), 2);
+
+        let in_tool = trajectory.in_frame(&tool).unwrap();
+        assert!(in_tool.frame().is_same(&tool));
+        assert_relative_eq!(in_tool[0].position(), Vector3::new(1.0, 0.0, -1.0));
+        assert_relative_eq!(in_tool.total_length(), trajectory.total_length());
+    }
+}
@@ -0,0 +1,395 @@
+//! Batched updates of frame transforms, see [`Frame::batch_update`].
+//!
+//! A [`TreeTransaction`] only records updates. They are checked and applied when the
+//! transaction commits, all or none: if any update fails, no transform of the tree changes and
+//! no callback is invoked. Otherwise the cached world transforms are invalidated in a single
+//! pass, and the change callbacks run once per changed frame after all transforms are in place,
+//! so they never observe a half-updated tree.
+
+use crate::CartesianTreeError;
+use crate::audit::AuditOperation;
+use crate::frame::{Frame, FrameData};
+use crate::rotation::Rotation;
+use crate::scalar::Real;
+use crate::validation::check_isometry;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use nalgebra::{Isometry3, Translation3, Vector3};
+
+/// The frame an update of a [`TreeTransaction`] applies to.
+#[derive(Clone, Debug)]
+pub enum UpdateTarget<T: Real = f64> {
+    /// The frame of a handle.
+    Frame(Frame<T>),
+    /// The frame at a path, resolved like [`Frame::resolve_path`] from the frame the
+    /// transaction was started on.
+    Path(String),
+}
+
+impl<T: Real> From<&Frame<T>> for UpdateTarget<T> {
+    fn from(frame: &Frame<T>) -> Self {
+        Self::Frame(frame.clone())
+    }
+}
+
+impl<T: Real> From<&str> for UpdateTarget<T> {
+    fn from(path: &str) -> Self {
+        Self::Path(path.to_string())
+    }
+}
+
+impl<T: Real> From<String> for UpdateTarget<T> {
+    fn from(path: String) -> Self {
+        Self::Path(path)
+    }
+}
+
+/// How an update changes the transform of a frame to its parent.
+#[derive(Clone, Copy, Debug)]
+enum Update<T: Real> {
+    /// Replaces the transform, like [`Frame::set`].
+    Set(Isometry3<T>),
+    /// Applies a motion in the parent frame, like [`Frame::apply_in_parent_frame`].
+    InParentFrame(Isometry3<T>),
+    /// Applies a motion in the frame itself, like [`Frame::apply_in_local_frame`].
+    InLocalFrame(Isometry3<T>),
+}
+
+/// Updates recorded by [`Frame::batch_update`] to be applied at once.
+///
+/// Updates of the same frame compose in the order they were recorded, e.g. a
+/// [`TreeTransaction::set`] followed by a [`TreeTransaction::apply_in_local_frame`] moves the
+/// frame relative to the newly set transform.
+#[derive(Debug)]
+pub struct TreeTransaction<T: Real = f64> {
+    /// The frame paths are resolved from.
+    origin: Frame<T>,
+    updates: Vec<(UpdateTarget<T>, Update<T>)>,
+}
+
+impl<T: Real> TreeTransaction<T> {
+    /// Records setting the transform of `target` relative to its parent.
+    pub fn set(
+        &mut self,
+        target: impl Into<UpdateTarget<T>>,
+        position: Vector3<T>,
+        orientation: impl Into<Rotation<T>>,
+    ) -> &mut Self {
+        let transform = Isometry3::from_parts(
+            Translation3::from(position),
+            orientation.into().as_quaternion(),
+        );
+        self.updates.push((target.into(), Update::Set(transform)));
+        self
+    }
+
+    /// Records applying `isometry`, interpreted in the parent frame, to `target`.
+    pub fn apply_in_parent_frame(
+        &mut self,
+        target: impl Into<UpdateTarget<T>>,
+        isometry: &Isometry3<T>,
+    ) -> &mut Self {
+        self.updates
+            .push((target.into(), Update::InParentFrame(*isometry)));
+        self
+    }
+
+    /// Records applying `isometry`, interpreted in `target` itself, to `target`.
+    pub fn apply_in_local_frame(
+        &mut self,
+        target: impl Into<UpdateTarget<T>>,
+        isometry: &Isometry3<T>,
+    ) -> &mut Self {
+        self.updates
+            .push((target.into(), Update::InLocalFrame(*isometry)));
+        self
+    }
+
+    /// Returns the number of recorded updates.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.updates.len()
+    }
+
+    /// Returns whether no update has been recorded.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    /// Checks all updates and applies them if none fails.
+    fn commit(self) -> Result<(), CartesianTreeError> {
+        // The new transforms per changed frame, in the order the frames were first updated.
+        let mut changed: Vec<(Frame<T>, Isometry3<T>)> = Vec::new();
+        let mut indices: BTreeMap<*const RefCell<FrameData<T>>, usize> = BTreeMap::new();
+        for (target, update) in self.updates {
+            let frame = match target {
+                UpdateTarget::Frame(frame) => frame,
+                UpdateTarget::Path(path) => self.origin.resolve_path(&path)?,
+            };
+            if frame.try_parent()?.is_none() {
+                return Err(CartesianTreeError::CannotUpdateRootTransform {
+                    frame: frame.name(),
+                });
+            }
+            frame.check_dynamic()?;
+            let index = *indices.entry(Rc::as_ptr(&frame.data)).or_insert_with(|| {
+                let current = frame.borrow().transform_to_parent;
+                changed.push((frame.clone(), current));
+                changed.len() - 1
+            });
+            let transform = &mut changed[index].1;
+            *transform = match update {
+                Update::Set(new) => new,
+                Update::InParentFrame(isometry) => isometry * *transform,
+                Update::InLocalFrame(isometry) => *transform * isometry,
+            };
+            check_isometry(&frame.path(), transform)?;
+        }
+        // Every frame is checked to be free before the first one changes.
+        for (frame, _) in &changed {
+            frame.try_borrow_mut()?;
+        }
+
+        // The old transforms take the place of the new ones, for the audit log.
+        for (frame, transform) in &mut changed {
+            core::mem::swap(&mut frame.try_borrow_mut()?.transform_to_parent, transform);
+        }
+        Frame::invalidate_world_transforms(changed.iter().map(|(frame, _)| frame.clone()));
+        for (frame, old) in &changed {
+            frame.record_audit(AuditOperation::Update, Some(*old));
+        }
+        let changed: Vec<_> = changed.into_iter().map(|(frame, _)| frame).collect();
+        Frame::notify_transforms_changed(&changed);
+        Ok(())
+    }
+}
+
+impl<T: Real> Frame<T> {
+    /// Records updates of frame transforms with `f` and applies them all at once.
+    ///
+    /// Frames are updated by handle or by a path relative to this frame, see
+    /// [`UpdateTarget`]. Nothing changes while `f` runs. Afterwards, the updates are checked and
+    /// applied all or none, see the [module documentation](crate::transaction): the cached world
+    /// transforms are invalidated in a single pass, and the callbacks of every changed frame (see
+    /// [`Frame::on_transform_changed_with`]) are invoked once, in the order the frames were first
+    /// updated, after all transforms are in place.
+    ///
+    /// # Errors
+    /// Returns the error of `f`, which discards the recorded updates, or, without changing any
+    /// transform, a [`CartesianTreeError`] if:
+    /// - A path does not resolve, see [`Frame::resolve_path`].
+    /// - An updated frame is a root or static, see [`Frame::set_static`].
+    /// - A component of a resulting transform is NaN or infinite.
+    /// - An updated frame is being accessed, e.g. by a running callback.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let world = Frame::new_origin("world").unwrap();
+    /// let robot = world
+    ///     .add_child("robot", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// let camera = robot
+    ///     .add_child("camera", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// world
+    ///     .batch_update(|batch| {
+    ///         batch
+    ///             .set(&robot, Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+    ///             .set("robot/camera", Vector3::new(0.0, 0.0, 0.5), UnitQuaternion::identity());
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     camera.world_transform().unwrap().translation.vector,
+    ///     Vector3::new(1.0, 0.0, 0.5)
+    /// );
+    /// ```
+    pub fn batch_update(
+        &self,
+        f: impl FnOnce(&mut TreeTransaction<T>) -> Result<(), CartesianTreeError>,
+    ) -> Result<(), CartesianTreeError> {
+        let mut transaction = TreeTransaction {
+            origin: self.clone(),
+            updates: Vec::new(),
+        };
+        f(&mut transaction)?;
+        transaction.commit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::SubscriptionScope;
+    use crate::tree::Walking;
+    use approx::assert_relative_eq;
+    use core::cell::Cell;
+    use nalgebra::UnitQuaternion;
+
+    /// A world with a robot carrying an arm and a camera.
+    fn workcell() -> (Frame, Frame, Frame, Frame) {
+        let world = Frame::new_origin("world").unwrap();
+        let robot = world
+            .add_child("robot", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let arm = robot
+            .add_child(
+                "arm",
+                Vector3::new(0.0, 0.0, 1.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        let camera = robot
+            .add_child(
+                "camera",
+                Vector3::new(0.5, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        (world, robot, arm, camera)
+    }
+
+    /// Counts the callbacks invoked for `frame`.
+    fn count_changes(frame: &Frame) -> Rc<Cell<usize>> {
+        let count = Rc::new(Cell::new(0));
+        let counter = count.clone();
+        let _ = frame.on_transform_changed(move |_| counter.set(counter.get() + 1));
+        count
+    }
+
+    #[test]
+    fn updates_apply_at_once_and_notify_each_frame_once() {
+        let (world, robot, arm, camera) = workcell();
+        let robot_changes = count_changes(&robot);
+        let arm_changes = count_changes(&arm);
+        let camera_changes = count_changes(&camera);
+        let subtree_changes = Rc::new(RefCell::new(Vec::new()));
+        let seen = subtree_changes.clone();
+        let _ = world.on_transform_changed_with(SubscriptionScope::Subtree, move |frame| {
+            // Callbacks only run once the whole batch is in place.
+            let arm = frame.root().resolve_path("robot/arm").unwrap();
+            assert_eq!(arm.position(), Vector3::new(0.0, 0.0, 2.0));
+            seen.borrow_mut().push(frame.name());
+        });
+        assert_eq!(
+            arm.world_transform().unwrap().translation.vector,
+            Vector3::new(0.0, 0.0, 1.0)
+        );
+
+        world
+            .batch_update(|batch| {
+                batch.set(
+                    &robot,
+                    Vector3::new(1.0, 0.0, 0.0),
+                    UnitQuaternion::identity(),
+                );
+                for _ in 0..4 {
+                    batch
+                        .apply_in_local_frame("robot/arm", &Isometry3::translation(0.0, 0.0, 0.25));
+                }
+                batch.apply_in_parent_frame(&robot, &Isometry3::translation(0.0, 2.0, 0.0));
+                assert_eq!(batch.len(), 6);
+                // Nothing changes while the updates are recorded.
+                assert_eq!(robot.position(), Vector3::zeros());
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(robot.position(), Vector3::new(1.0, 2.0, 0.0));
+        assert_relative_eq!(arm.position(), Vector3::new(0.0, 0.0, 2.0), epsilon = 1e-12);
+        assert_relative_eq!(
+            arm.world_transform().unwrap().translation.vector,
+            Vector3::new(1.0, 2.0, 2.0),
+            epsilon = 1e-12
+        );
+        assert_eq!(
+            (robot_changes.get(), arm_changes.get(), camera_changes.get()),
+            (1, 1, 0)
+        );
+        assert_eq!(*subtree_changes.borrow(), ["robot", "arm"]);
+    }
+
+    #[test]
+    fn a_failing_update_rolls_the_batch_back() {
+        let (world, robot, arm, camera) = workcell();
+        let changes = count_changes(&robot);
+        let world_before = camera.world_transform().unwrap();
+
+        let result = world.batch_update(|batch| {
+            batch
+                .set(
+                    &robot,
+                    Vector3::new(1.0, 0.0, 0.0),
+                    UnitQuaternion::identity(),
+                )
+                .set(
+                    &arm,
+                    Vector3::new(f64::NAN, 0.0, 0.0),
+                    UnitQuaternion::identity(),
+                )
+                .set(&camera, Vector3::zeros(), UnitQuaternion::identity());
+            Ok(())
+        });
+        assert!(matches!(
+            result,
+            Err(CartesianTreeError::NonFiniteValue { field, .. })
+                if field == "/world/robot/arm.translation.x"
+        ));
+        assert_eq!(robot.position(), Vector3::zeros());
+        assert_eq!(arm.position(), Vector3::new(0.0, 0.0, 1.0));
+        assert_eq!(camera.world_transform().unwrap(), world_before);
+        assert_eq!(changes.get(), 0);
+
+        for path in ["robot/missing", "/world"] {
+            assert!(
+                world
+                    .batch_update(|batch| {
+                        batch.set(
+                            &robot,
+                            Vector3::new(1.0, 0.0, 0.0),
+                            UnitQuaternion::identity(),
+                        );
+                        batch.set(path, Vector3::zeros(), UnitQuaternion::identity());
+                        Ok(())
+                    })
+                    .is_err()
+            );
+        }
+        camera.set_static(true);
+        assert!(matches!(
+            world.batch_update(|batch| {
+                batch.set(
+                    &robot,
+                    Vector3::new(1.0, 0.0, 0.0),
+                    UnitQuaternion::identity(),
+                );
+                batch.set(&camera, Vector3::zeros(), UnitQuaternion::identity());
+                Ok(())
+            }),
+            Err(CartesianTreeError::StaticFrame { .. })
+        ));
+        assert!(matches!(
+            world.batch_update(|batch| {
+                batch.set(
+                    &robot,
+                    Vector3::new(1.0, 0.0, 0.0),
+                    UnitQuaternion::identity(),
+                );
+                Err(CartesianTreeError::DegenerateInput {
+                    reason: "sensor timeout".to_string(),
+                })
+            }),
+            Err(CartesianTreeError::DegenerateInput { .. })
+        ));
+        assert_eq!(robot.position(), Vector3::zeros());
+        assert_eq!(changes.get(), 0);
+    }
+}
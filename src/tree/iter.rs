@@ -0,0 +1,98 @@
+use alloc::collections::VecDeque;
+
+use crate::tree::{HasChildren, HasParent};
+use alloc::{vec, vec::Vec};
+
+/// Iterator visiting a node and its descendants in depth-first pre-order.
+///
+/// Children are fetched one node at a time when that node is visited, so no reference into
+/// the tree is held between two calls of `next`.
+#[derive(Clone, Debug)]
+pub struct DepthFirst<N> {
+    stack: Vec<N>,
+}
+
+impl<N> DepthFirst<N> {
+    /// Creates an iterator starting at (and including) the given node.
+    pub fn new(start: N) -> Self {
+        Self { stack: vec![start] }
+    }
+}
+
+impl<N> Iterator for DepthFirst<N>
+where
+    N: HasChildren<Node = N>,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children().into_iter().rev());
+        Some(node)
+    }
+}
+
+/// Iterator visiting a node and its descendants in breadth-first (level) order.
+///
+/// Children are fetched one node at a time when that node is visited, so no reference into
+/// the tree is held between two calls of `next`.
+#[derive(Clone, Debug)]
+pub struct BreadthFirst<N> {
+    queue: VecDeque<N>,
+}
+
+impl<N> BreadthFirst<N> {
+    /// Creates an iterator starting at (and including) the given node.
+    pub fn new(start: N) -> Self {
+        Self {
+            queue: VecDeque::from([start]),
+        }
+    }
+}
+
+impl<N> Iterator for BreadthFirst<N>
+where
+    N: HasChildren<Node = N>,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.queue.pop_front()?;
+        self.queue.extend(node.children());
+        Some(node)
+    }
+}
+
+/// Iterator visiting the ancestors of a node, from its parent up to the root.
+///
+/// The parent is fetched when the next item is requested, so no reference into the tree is
+/// held between two calls of `next`.
+#[derive(Clone, Debug)]
+pub struct Ancestors<N> {
+    next: Option<N>,
+}
+
+impl<N> Ancestors<N>
+where
+    N: HasParent<Node = N>,
+{
+    /// Creates an iterator starting at (and excluding) the given node.
+    pub fn new(start: &N) -> Self {
+        Self {
+            next: start.parent(),
+        }
+    }
+}
+
+impl<N> Iterator for Ancestors<N>
+where
+    N: HasParent<Node = N>,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next.take()?;
+        self.next = node.parent();
+        Some(node)
+    }
+}
@@ -1,5 +1,7 @@
 //! Description needed.
 
+pub mod iter;
 pub mod traits;
 
+pub use iter::{Ancestors, BreadthFirst, DepthFirst};
 pub use traits::{HasChildren, HasParent, NodeEquality, Walking};
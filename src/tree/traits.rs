@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 /// Defines the parent trait.
 pub trait HasParent {
     type Node: Clone;
@@ -106,7 +107,7 @@ mod tests {
 
     #[test]
     fn test_depth() {
-        let root = Frame::new_origin("root");
+        let root = Frame::new_origin("root").unwrap();
         assert_eq!(root.depth(), 0);
 
         let child = root
@@ -122,7 +123,7 @@ mod tests {
 
     #[test]
     fn test_walk_up() {
-        let root = Frame::new_origin("root");
+        let root = Frame::new_origin("root").unwrap();
         let child = root
             .add_child("child", Vector3::zeros(), UnitQuaternion::identity())
             .unwrap();
@@ -138,7 +139,7 @@ mod tests {
 
     #[test]
     fn test_root() {
-        let root = Frame::new_origin("root");
+        let root = Frame::new_origin("root").unwrap();
         let child = root
             .add_child("child", Vector3::zeros(), UnitQuaternion::identity())
             .unwrap();
@@ -153,7 +154,7 @@ mod tests {
 
     #[test]
     fn test_lca_with() {
-        let root = Frame::new_origin("root");
+        let root = Frame::new_origin("root").unwrap();
         let child1 = root
             .add_child("child1", Vector3::zeros(), UnitQuaternion::identity())
             .unwrap();
@@ -176,4 +177,94 @@ mod tests {
         // LCA of two grandchildren with different parents is root
         assert!(grandchild1.lca_with(&grandchild2).unwrap().is_same(&root));
     }
+
+    #[test]
+    fn lca_on_deep_chain() {
+        let root = Frame::new_origin("root").unwrap();
+        let mut chain = vec![root.clone()];
+        for i in 0..10_000 {
+            let link = chain
+                .last()
+                .unwrap()
+                .add_child(
+                    format!("link{i}"),
+                    Vector3::zeros(),
+                    UnitQuaternion::identity(),
+                )
+                .unwrap();
+            chain.push(link);
+        }
+        let branch = chain[5_000]
+            .add_child("branch", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        let tip = chain.last().unwrap();
+
+        assert_eq!(tip.depth(), 10_000);
+        assert!(tip.lca_with(&branch).unwrap().is_same(&chain[5_000]));
+        assert!(branch.lca_with(tip).unwrap().is_same(&chain[5_000]));
+        assert!(tip.lca_with(&root).unwrap().is_same(&root));
+        assert!(tip.lca_with(tip).unwrap().is_same(tip));
+    }
+
+    /// Reference implementation materializing the ancestors of both nodes.
+    fn lca_by_ancestor_lists(a: &Frame, b: &Frame) -> Option<Frame> {
+        let ancestors = |node: &Frame| {
+            let mut path = vec![node.clone()];
+            while let Some(parent) = path.last().unwrap().parent() {
+                path.push(parent);
+            }
+            path
+        };
+        let b_ancestors = ancestors(b);
+        ancestors(a)
+            .into_iter()
+            .find(|candidate| b_ancestors.iter().any(|node| node.is_same(candidate)))
+    }
+
+    #[test]
+    fn lca_agrees_with_reference_on_random_trees() {
+        // Small linear congruential generator, so the trees are reproducible.
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = |bound: usize| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            usize::try_from(state >> 33).unwrap() % bound
+        };
+
+        for tree in 0..20 {
+            let roots = [
+                Frame::new_origin("a").unwrap(),
+                Frame::new_origin("b").unwrap(),
+            ];
+            let mut nodes = roots.to_vec();
+            for i in 0..200 {
+                let parent = nodes[next(nodes.len())].clone();
+                let child = parent
+                    .add_child(
+                        format!("n{i}"),
+                        Vector3::zeros(),
+                        UnitQuaternion::identity(),
+                    )
+                    .unwrap();
+                nodes.push(child);
+            }
+            for _ in 0..200 {
+                let a = &nodes[next(nodes.len())];
+                let b = &nodes[next(nodes.len())];
+                let expected = lca_by_ancestor_lists(a, b);
+                let actual = a.lca_with(b);
+                assert_eq!(
+                    actual.as_ref().map(Frame::name),
+                    expected.as_ref().map(Frame::name),
+                    "tree {tree}: lca of {} and {}",
+                    a.name(),
+                    b.name()
+                );
+                if let (Some(actual), Some(expected)) = (actual, expected) {
+                    assert!(actual.is_same(&expected));
+                }
+            }
+        }
+    }
 }
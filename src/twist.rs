@@ -0,0 +1,309 @@
+//! Velocities of frames, for trees whose frames move.
+//!
+//! A frame can be given a [`Twist`] relative to its parent via [`Frame::set_twist`]. The twist is
+//! expressed in the coordinates of the parent: `linear` is the velocity of the frame origin and
+//! `angular` the angular velocity (rotation axis scaled by the rate in rad/s). Frames without a
+//! twist are static relative to their parent. [`Frame::twist_in`] composes the twists along the
+//! tree to the velocity of one frame relative to any other frame of the same tree.
+//!
+//! Twists are kinematic state only: they are neither integrated over time nor serialized.
+
+use crate::CartesianTreeError;
+use crate::frame::{Frame, FrameData};
+use crate::tree::HasParent;
+use crate::validation::check_position;
+use alloc::rc::Weak;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use nalgebra::{Isometry3, Vector3};
+
+/// The velocity of a frame relative to a reference frame, expressed in the reference frame.
+#[derive(Clone, Debug)]
+pub struct Twist {
+    /// Reference to the frame the velocity is relative to and expressed in.
+    frame: Weak<RefCell<FrameData>>,
+    /// Velocity of the moving frame's origin.
+    linear: Vector3<f64>,
+    /// Angular velocity of the moving frame.
+    angular: Vector3<f64>,
+}
+
+impl Twist {
+    /// Returns the reference frame of this twist.
+    ///
+    /// # Returns
+    /// `Some(Frame)` if the frame is still valid, or `None` if it has been dropped.
+    #[must_use]
+    pub fn frame(&self) -> Option<Frame> {
+        self.frame.upgrade().map(Frame::from_data)
+    }
+
+    /// Returns the velocity of the moving frame's origin.
+    #[must_use]
+    pub const fn linear(&self) -> Vector3<f64> {
+        self.linear
+    }
+
+    /// Returns the angular velocity of the moving frame.
+    #[must_use]
+    pub const fn angular(&self) -> Vector3<f64> {
+        self.angular
+    }
+}
+
+/// The motion of a frame relative to its root, expressed in root coordinates.
+struct Motion {
+    transform: Isometry3<f64>,
+    linear: Vector3<f64>,
+    angular: Vector3<f64>,
+}
+
+impl Motion {
+    /// Composes the twists from the root of `frame` down to `frame`.
+    fn of(frame: &Frame) -> Self {
+        let mut chain: Vec<Frame> = frame.ancestors().collect();
+        chain.reverse();
+        chain.push(frame.clone());
+
+        let mut motion = Self {
+            transform: Isometry3::identity(),
+            linear: Vector3::zeros(),
+            angular: Vector3::zeros(),
+        };
+        // The root itself does not move, so its own entry is skipped.
+        for frame in chain.iter().skip(1) {
+            let data = frame.borrow();
+            let rotation = motion.transform.rotation;
+            motion.linear += motion
+                .angular
+                .cross(&(rotation * data.transform_to_parent.translation.vector));
+            if let Some((linear, angular)) = data.twist {
+                motion.linear += rotation * linear;
+                motion.angular += rotation * angular;
+            }
+            motion.transform *= data.transform_to_parent;
+        }
+        motion
+    }
+}
+
+impl Frame {
+    /// Sets the velocity of this frame relative to its parent.
+    ///
+    /// Both parts are expressed in the coordinates of the parent. The transform of the frame is
+    /// not changed; the twist only describes how it is currently moving.
+    ///
+    /// # Arguments
+    /// - `linear`: The velocity of the frame origin.
+    /// - `angular`: The angular velocity of the frame, in rad/s.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The frame is a root, which cannot move.
+    /// - A component of `linear` or `angular` is NaN or infinite.
+    pub fn set_twist(
+        &self,
+        linear: Vector3<f64>,
+        angular: Vector3<f64>,
+    ) -> Result<(), CartesianTreeError> {
+        if self.parent().is_none() {
+            return Err(CartesianTreeError::CannotUpdateRootTransform { frame: self.name() });
+        }
+        check_position("linear", &linear)?;
+        check_position("angular", &angular)?;
+        self.data.borrow_mut().twist = Some((linear, angular));
+        Ok(())
+    }
+
+    /// Removes the twist of this frame, making it static relative to its parent again.
+    pub fn clear_twist(&self) {
+        self.data.borrow_mut().twist = None;
+    }
+
+    /// Returns the twist of this frame relative to its parent, or `None` if it is static.
+    #[must_use]
+    pub fn twist(&self) -> Option<Twist> {
+        let (linear, angular) = self.borrow().twist?;
+        Some(Twist {
+            frame: self.parent()?.downgrade(),
+            linear,
+            angular,
+        })
+    }
+
+    /// Returns the velocity of this frame relative to `target`, expressed in `target`.
+    ///
+    /// The twists of all frames between the root and both frames are composed, so `target` may
+    /// move as well. Frames without a twist are treated as static relative to their parent.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if the frames do not share a common ancestor.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// let turntable = root
+    ///     .add_child("turntable", Vector3::zeros(), UnitQuaternion::identity())
+    ///     .unwrap();
+    /// turntable.set_twist(Vector3::zeros(), Vector3::new(0.0, 0.0, 2.0)).unwrap();
+    /// let part = turntable
+    ///     .add_child("part", Vector3::new(0.5, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// let twist = part.twist_in(&root).unwrap();
+    /// assert_eq!(twist.linear(), Vector3::new(0.0, 1.0, 0.0));
+    /// assert_eq!(twist.angular(), Vector3::new(0.0, 0.0, 2.0));
+    /// ```
+    pub fn twist_in(&self, target: &Self) -> Result<Twist, CartesianTreeError> {
+        if self.lowest_common_ancestor(target).is_none() {
+            return Err(CartesianTreeError::NoCommonAncestor {
+                from: self.name(),
+                to: target.name(),
+            });
+        }
+        let source = Motion::of(self);
+        let reference = Motion::of(target);
+        let to_target = reference.transform.rotation.inverse();
+        let offset = source.transform.translation.vector - reference.transform.translation.vector;
+        Ok(Twist {
+            frame: target.downgrade(),
+            linear: to_target
+                * (source.linear - reference.linear - reference.angular.cross(&offset)),
+            angular: to_target * (source.angular - reference.angular),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rotation::Rotation;
+    use crate::tree::NodeEquality;
+    use approx::assert_relative_eq;
+    use nalgebra::UnitQuaternion;
+    use std::f64::consts::FRAC_PI_2;
+
+    /// Builds `world -> base -> tool` with `base` spinning about z and `tool` offset along x.
+    fn turntable(yaw: f64, rate: f64, radius: f64) -> (Frame, Frame, Frame) {
+        let root = Frame::new_origin("world").unwrap();
+        let base = root
+            .add_child("base", Vector3::zeros(), Rotation::from_rpy(0.0, 0.0, yaw))
+            .unwrap();
+        base.set_twist(Vector3::zeros(), Vector3::new(0.0, 0.0, rate))
+            .unwrap();
+        let tool = base
+            .add_child(
+                "tool",
+                Vector3::new(radius, 0.0, 0.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        (root, base, tool)
+    }
+
+    #[test]
+    fn rotating_parent_induces_tangential_velocity() {
+        let (root, _base, tool) = turntable(0.0, 3.0, 2.0);
+        let twist = tool.twist_in(&root).unwrap();
+        assert_relative_eq!(twist.linear(), Vector3::new(0.0, 6.0, 0.0), epsilon = 1e-12);
+        assert_relative_eq!(
+            twist.angular(),
+            Vector3::new(0.0, 0.0, 3.0),
+            epsilon = 1e-12
+        );
+        assert!(twist.frame().unwrap().is_same(&root));
+
+        // After a quarter turn the tool sits on the y axis and moves along -x.
+        let (root, _base, tool) = turntable(FRAC_PI_2, 3.0, 2.0);
+        let twist = tool.twist_in(&root).unwrap();
+        assert_relative_eq!(
+            twist.linear(),
+            Vector3::new(-6.0, 0.0, 0.0),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn velocities_relative_to_moving_frames() {
+        let (root, base, tool) = turntable(0.3, 3.0, 2.0);
+
+        // The tool is rigidly attached to the base.
+        let twist = tool.twist_in(&base).unwrap();
+        assert_relative_eq!(twist.linear(), Vector3::zeros(), epsilon = 1e-12);
+        assert_relative_eq!(twist.angular(), Vector3::zeros(), epsilon = 1e-12);
+
+        // Seen from the tool, the world origin stays in place while the world spins backwards.
+        let twist = root.twist_in(&tool).unwrap();
+        assert_relative_eq!(twist.linear(), Vector3::zeros(), epsilon = 1e-12);
+        assert_relative_eq!(
+            twist.angular(),
+            Vector3::new(0.0, 0.0, -3.0),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn child_twists_are_expressed_in_the_parent() {
+        let root = Frame::new_origin("world").unwrap();
+        let cart = root
+            .add_child(
+                "cart",
+                Vector3::zeros(),
+                Rotation::from_rpy(0.0, 0.0, FRAC_PI_2),
+            )
+            .unwrap();
+        cart.set_twist(Vector3::new(1.0, 0.0, 0.0), Vector3::zeros())
+            .unwrap();
+        let slider = cart
+            .add_child("slider", Vector3::zeros(), UnitQuaternion::identity())
+            .unwrap();
+        // Moving along the cart's y axis, which is the world's -x axis.
+        slider
+            .set_twist(Vector3::new(0.0, 1.0, 0.0), Vector3::zeros())
+            .unwrap();
+
+        let twist = slider.twist_in(&root).unwrap();
+        assert_relative_eq!(twist.linear(), Vector3::zeros(), epsilon = 1e-12);
+        let twist = slider.twist_in(&cart).unwrap();
+        assert_relative_eq!(twist.linear(), Vector3::new(0.0, 1.0, 0.0), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn static_frames_and_invalid_twists() {
+        let root = Frame::new_origin("world").unwrap();
+        let tool = root
+            .add_child(
+                "tool",
+                Vector3::new(1.0, 2.0, 3.0),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+        assert!(tool.twist().is_none());
+        assert_eq!(tool.twist_in(&root).unwrap().linear(), Vector3::zeros());
+
+        tool.set_twist(Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.5))
+            .unwrap();
+        let twist = tool.twist().unwrap();
+        assert!(twist.frame().unwrap().is_same(&root));
+        assert_eq!(twist.angular(), Vector3::new(0.0, 0.0, 0.5));
+        tool.clear_twist();
+        assert!(tool.twist().is_none());
+
+        assert!(matches!(
+            root.set_twist(Vector3::zeros(), Vector3::zeros()),
+            Err(CartesianTreeError::CannotUpdateRootTransform { .. })
+        ));
+        assert!(matches!(
+            tool.set_twist(Vector3::new(f64::NAN, 0.0, 0.0), Vector3::zeros()),
+            Err(CartesianTreeError::NonFiniteValue { .. })
+        ));
+        assert!(tool.twist().is_none());
+        assert!(matches!(
+            tool.twist_in(&Frame::new_origin("other").unwrap()),
+            Err(CartesianTreeError::NoCommonAncestor { .. })
+        ));
+    }
+}
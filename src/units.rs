@@ -0,0 +1,299 @@
+//! Length units of the translations stored in a frame tree.
+//!
+//! A tree can declare the unit all of its translations are given in via
+//! [`Frame::set_canonical_unit`]. The unit is written to and read from JSON as the top-level
+//! `units` field, and [`Frame::apply_config`] converts configs declared in another unit into it.
+//! Rotations are unit-less and never affected.
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::scalar::Real;
+use crate::tree::{HasParent, NodeEquality, Walking};
+use nalgebra::Vector3;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A unit of length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LengthUnit {
+    /// Millimeters, written as `"mm"`.
+    #[cfg_attr(feature = "serde", serde(rename = "mm"))]
+    Millimeter,
+    /// Centimeters, written as `"cm"`.
+    #[cfg_attr(feature = "serde", serde(rename = "cm"))]
+    Centimeter,
+    /// Meters, written as `"m"`.
+    #[cfg_attr(feature = "serde", serde(rename = "m"))]
+    Meter,
+    /// Inches, written as `"in"`.
+    #[cfg_attr(feature = "serde", serde(rename = "in"))]
+    Inch,
+}
+
+impl LengthUnit {
+    /// Returns the length of this unit in micrometers, which is exact for all units.
+    const fn micrometers(self) -> u32 {
+        match self {
+            Self::Millimeter => 1_000,
+            Self::Centimeter => 10_000,
+            Self::Meter => 1_000_000,
+            Self::Inch => 25_400,
+        }
+    }
+
+    /// Converts a length given in this unit into `to`.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::units::LengthUnit;
+    ///
+    /// assert_eq!(LengthUnit::Millimeter.convert(1000.0, LengthUnit::Meter), 1.0);
+    /// assert_eq!(LengthUnit::Inch.convert(2.0, LengthUnit::Millimeter), 50.8);
+    /// ```
+    #[must_use]
+    pub fn convert(self, value: f64, to: Self) -> f64 {
+        value * f64::from(self.micrometers()) / f64::from(to.micrometers())
+    }
+
+    /// Converts a translation given in this unit into `to`.
+    #[must_use]
+    pub fn convert_vector(self, vector: Vector3<f64>, to: Self) -> Vector3<f64> {
+        vector.map(|value| self.convert(value, to))
+    }
+}
+
+impl<T: Real> Frame<T> {
+    /// Returns the length unit declared for the tree of this frame, if any.
+    #[must_use]
+    pub fn canonical_unit(&self) -> Option<LengthUnit> {
+        self.root().borrow().canonical_unit
+    }
+
+    /// Declares the length unit all translations in the tree of this frame are given in.
+    ///
+    /// Only the declaration changes; the stored translations are kept as they are. Use
+    /// [`Frame::convert_units`] to rescale them instead.
+    pub fn set_canonical_unit(&self, unit: LengthUnit) {
+        self.root().data.borrow_mut().canonical_unit = Some(unit);
+    }
+}
+
+impl Frame {
+    /// Rescales all translations in the subtree of this frame from the canonical unit to `to`.
+    ///
+    /// This covers the transforms of all descendants, the named poses and regions of this frame
+    /// and its descendants, and the recorded histories and linear velocities of the descendants. The
+    /// transform of this frame to its parent stays in the parent's coordinates and is kept.
+    /// Called on a root, the canonical unit of the tree becomes `to`.
+    ///
+    /// # Errors
+    /// Returns [`CartesianTreeError::UndeclaredUnit`] if the tree declares no canonical unit.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    /// use cartesian_tree::units::LengthUnit;
+    /// use nalgebra::{UnitQuaternion, Vector3};
+    ///
+    /// let root = Frame::new_origin("world").unwrap();
+    /// root.set_canonical_unit(LengthUnit::Meter);
+    /// let tool = root
+    ///     .add_child("tool", Vector3::new(0.5, 0.0, 0.0), UnitQuaternion::identity())
+    ///     .unwrap();
+    ///
+    /// root.convert_units(LengthUnit::Millimeter).unwrap();
+    /// assert_eq!(tool.position(), Vector3::new(500.0, 0.0, 0.0));
+    /// assert_eq!(root.canonical_unit(), Some(LengthUnit::Millimeter));
+    /// ```
+    pub fn convert_units(&self, to: LengthUnit) -> Result<(), CartesianTreeError> {
+        let Some(from) = self.canonical_unit() else {
+            return Err(CartesianTreeError::UndeclaredUnit { frame: self.name() });
+        };
+        for frame in self.iter_dfs() {
+            let is_subtree_root = frame.is_same(self);
+            let transform = {
+                let mut data = frame.data.borrow_mut();
+                for pose in data.poses.values_mut() {
+                    pose.translation.vector = from.convert_vector(pose.translation.vector, to);
+                }
+                for region in data.regions.values_mut() {
+                    region.convert_units(from, to);
+                }
+                if is_subtree_root {
+                    continue;
+                }
+                if let Some(history) = &mut data.history {
+                    history.map_translations(|vector| from.convert_vector(vector, to));
+                }
+                if let Some((linear, _)) = &mut data.twist {
+                    *linear = from.convert_vector(*linear, to);
+                }
+                data.transform_to_parent
+            };
+            frame.set(
+                from.convert_vector(transform.translation.vector, to),
+                transform.rotation,
+            )?;
+        }
+        if self.parent().is_none() {
+            self.data.borrow_mut().canonical_unit = Some(to);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::StorePolicy;
+    use crate::rotation::Rotation;
+    #[cfg(feature = "serde")]
+    use crate::tree::HasChildren;
+    use nalgebra::UnitQuaternion;
+
+    fn workcell(unit: Option<LengthUnit>) -> (Frame, Frame) {
+        let root = Frame::new_origin("world").unwrap();
+        if let Some(unit) = unit {
+            root.set_canonical_unit(unit);
+        }
+        let tool = root
+            .add_child("tool", Vector3::zeros(), Rotation::from_rpy(0.0, 0.0, 0.5))
+            .unwrap();
+        (root, tool)
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn config_in_millimeters_is_applied_to_a_tree_in_meters() {
+        let (root, tool) = workcell(Some(LengthUnit::Meter));
+        let config = r#"{
+            "name": "world",
+            "units": "mm",
+            "position": [0.0, 0.0, 0.0],
+            "orientation": [0.0, 0.0, 0.0, 1.0],
+            "children": [{
+                "name": "tool",
+                "position": [1000.0, 0.0, 250.0],
+                "orientation": [0.0, 0.0, 0.0, 1.0],
+                "poses": {"tcp": {"position": [0.0, 0.0, 120.0], "orientation": [0.0, 0.0, 0.0, 1.0]}},
+                "children": []
+            }]
+        }"#;
+        root.apply_config(config).unwrap();
+
+        assert_eq!(tool.position(), Vector3::new(1.0, 0.0, 0.25));
+        assert_eq!(
+            tool.orientation().as_quaternion(),
+            UnitQuaternion::identity()
+        );
+        assert_eq!(
+            tool.get_pose("tcp").unwrap().position(),
+            Vector3::new(0.0, 0.0, 0.12)
+        );
+        assert_eq!(root.canonical_unit(), Some(LengthUnit::Meter));
+
+        // Without a declared unit on either side, the values are applied as they are.
+        let (root, tool) = workcell(None);
+        root.apply_config(config).unwrap();
+        assert_eq!(tool.position(), Vector3::new(1000.0, 0.0, 250.0));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn round_trip_preserves_the_declared_unit() {
+        let (root, tool) = workcell(Some(LengthUnit::Millimeter));
+        tool.set(Vector3::new(250.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        let json = root.to_json().unwrap();
+        assert!(json.contains(r#""units": "mm""#));
+
+        let copy = Frame::from_json(&json).unwrap();
+        assert_eq!(copy.canonical_unit(), Some(LengthUnit::Millimeter));
+        assert_eq!(copy.children()[0].position(), Vector3::new(250.0, 0.0, 0.0));
+        assert_eq!(copy.to_json().unwrap(), json);
+        assert_eq!(
+            root.clone_subtree().canonical_unit(),
+            Some(LengthUnit::Millimeter)
+        );
+
+        let (root, _) = workcell(None);
+        assert!(!root.to_json().unwrap().contains("units"));
+        assert_eq!(
+            Frame::from_json(&root.to_json().unwrap())
+                .unwrap()
+                .canonical_unit(),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn unknown_units_are_rejected() {
+        let json = r#"{"name": "world", "units": "ft", "position": [0.0, 0.0, 0.0],
+            "orientation": [0.0, 0.0, 0.0, 1.0], "children": []}"#;
+        match Frame::from_json(json) {
+            Err(CartesianTreeError::InvalidDocument { path, .. }) => assert_eq!(path, "$.units"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn convert_units_rescales_translations_only() {
+        let (root, tool) = workcell(Some(LengthUnit::Meter));
+        tool.enable_history(4).unwrap();
+        tool.update_transform_at(
+            1.0,
+            Vector3::new(0.5, 0.0, 1.0),
+            Rotation::from_rpy(0.0, 0.0, 0.5),
+        )
+        .unwrap();
+        tool.set_twist(Vector3::new(0.1, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0))
+            .unwrap();
+        let home = root
+            .add_pose(Vector3::new(0.0, 2.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        root.store_pose("home", &home, StorePolicy::Overwrite)
+            .unwrap();
+        let orientation = tool.orientation().as_quaternion();
+
+        root.convert_units(LengthUnit::Centimeter).unwrap();
+        assert_eq!(root.canonical_unit(), Some(LengthUnit::Centimeter));
+        assert_eq!(tool.position(), Vector3::new(50.0, 0.0, 100.0));
+        assert_eq!(tool.orientation().as_quaternion(), orientation);
+        assert_eq!(tool.twist().unwrap().linear(), Vector3::new(10.0, 0.0, 0.0));
+        assert_eq!(tool.twist().unwrap().angular(), Vector3::new(0.0, 0.0, 1.0));
+        let (_, sample) = tool.history().unwrap().iter().next().unwrap();
+        assert_eq!(sample.translation.vector, Vector3::new(50.0, 0.0, 100.0));
+        assert_eq!(
+            root.get_pose("home").unwrap().position(),
+            Vector3::new(0.0, 200.0, 0.0)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn convert_units_on_a_subtree_keeps_its_own_transform() {
+        let (root, tool) = workcell(Some(LengthUnit::Meter));
+        tool.set(Vector3::new(1.0, 0.0, 0.0), UnitQuaternion::identity())
+            .unwrap();
+        let flange = tool
+            .add_child(
+                "flange",
+                Vector3::new(0.0, 0.0, 0.1),
+                UnitQuaternion::identity(),
+            )
+            .unwrap();
+
+        tool.convert_units(LengthUnit::Millimeter).unwrap();
+        assert_eq!(tool.position(), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(flange.position(), Vector3::new(0.0, 0.0, 100.0));
+        assert_eq!(root.canonical_unit(), Some(LengthUnit::Meter));
+
+        let (_, tool) = workcell(None);
+        assert!(matches!(
+            tool.convert_units(LengthUnit::Meter),
+            Err(CartesianTreeError::UndeclaredUnit { .. })
+        ));
+    }
+}
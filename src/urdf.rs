@@ -0,0 +1,625 @@
+//! Import of URDF robot descriptions.
+//!
+//! Every link becomes a frame and every joint places its child link relative to its parent
+//! link using the joint `<origin>`. Joint dynamics are ignored and movable joints are imported
+//! at their zero position. The joint of a frame is recorded in its metadata under
+//! `joint_name`, `joint_type` and, for movable joints, `joint_axis` (`"x y z"`). Revolute,
+//! continuous and prismatic joints are also attached as [`Joint`]s with the `<limit>` bounds, so
+//! the imported robot can be articulated with [`Frame::set_joint_values`].
+
+use crate::CartesianTreeError;
+use crate::frame::Frame;
+use crate::joint::Joint;
+use nalgebra::{Unit, UnitQuaternion, Vector3};
+use roxmltree::{Document, Node, ParsingOptions};
+use std::collections::HashMap;
+
+impl Frame {
+    /// Builds a frame tree from a URDF document.
+    ///
+    /// The root link becomes the root frame and every joint adds its child link as a child
+    /// frame of its parent link.
+    ///
+    /// # Arguments
+    /// - `xml`: The URDF document.
+    ///
+    /// # Returns
+    /// The frame of the root link.
+    ///
+    /// # Errors
+    /// Returns a [`CartesianTreeError`] if:
+    /// - The document is not well-formed XML, nests elements more than 64 levels deep or has no
+    ///   `<robot>` root element.
+    /// - A link or joint is missing a required attribute or has a malformed origin or axis.
+    /// - A joint references an unknown link, or the links do not form a single tree.
+    ///
+    /// # Example
+    /// ```
+    /// use cartesian_tree::Frame;
+    ///
+    /// let urdf = r#"
+    /// <robot name="bot">
+    ///   <link name="base"/>
+    ///   <link name="arm"/>
+    ///   <joint name="shoulder" type="revolute">
+    ///     <parent link="base"/>
+    ///     <child link="arm"/>
+    ///     <origin xyz="0 0 0.5" rpy="0 0 0"/>
+    ///     <axis xyz="0 0 1"/>
+    ///   </joint>
+    /// </robot>"#;
+    ///
+    /// let base = Frame::from_urdf(urdf).unwrap();
+    /// let arm = base.resolve_path("arm").unwrap();
+    /// assert_eq!(arm.get_metadata("joint_name"), Some("shoulder".into()));
+    /// ```
+    pub fn from_urdf(xml: &str) -> Result<Self, CartesianTreeError> {
+        let document = parse_xml(xml)?;
+        let document = document.root_element();
+        if !document.has_tag_name("robot") {
+            return Err(invalid("/", "expected a <robot> root element"));
+        }
+
+        let mut links = Vec::new();
+        for link in children_named(document, "link") {
+            let name = required_attribute(link, "name", "link")?;
+            if links.contains(&name) {
+                return Err(invalid(&format!("link[{name}]"), "duplicate link name"));
+            }
+            links.push(name);
+        }
+
+        let mut joints = Vec::new();
+        for element in children_named(document, "joint") {
+            let joint = UrdfJoint::parse(element)?;
+            for link in [joint.parent, joint.child] {
+                if !links.contains(&link) {
+                    return Err(invalid(
+                        &format!("joint[{}]", joint.name),
+                        &format!("unknown link '{link}'"),
+                    ));
+                }
+            }
+            if joints
+                .iter()
+                .any(|other: &UrdfJoint| other.child == joint.child)
+            {
+                return Err(invalid(
+                    &format!("joint[{}]", joint.name),
+                    &format!("link '{}' already has a parent joint", joint.child),
+                ));
+            }
+            joints.push(joint);
+        }
+
+        let roots: Vec<&str> = links
+            .iter()
+            .copied()
+            .filter(|link| joints.iter().all(|joint| joint.child != *link))
+            .collect();
+        let [root_link] = roots[..] else {
+            return Err(invalid(
+                "robot",
+                &format!("expected exactly one root link, found {}", roots.len()),
+            ));
+        };
+
+        let mut joints_by_parent: HashMap<&str, Vec<&UrdfJoint>> = HashMap::new();
+        for joint in &joints {
+            joints_by_parent
+                .entry(joint.parent)
+                .or_default()
+                .push(joint);
+        }
+
+        let root = Self::new_origin(root_link)?;
+        let mut created = 1;
+        let mut stack = vec![(root.clone(), root_link)];
+        while let Some((frame, link)) = stack.pop() {
+            for joint in joints_by_parent.get(link).into_iter().flatten() {
+                let child = frame.add_child(joint.child, joint.xyz, joint.rpy)?;
+                child.set_metadata("joint_name", joint.name);
+                child.set_metadata("joint_type", joint.kind);
+                if let Some(axis) = joint.axis {
+                    child.set_metadata("joint_axis", format!("{} {} {}", axis.x, axis.y, axis.z));
+                }
+                if let Some(articulation) = joint.articulation() {
+                    child.attach_joint(articulation)?;
+                }
+                created += 1;
+                stack.push((child, joint.child));
+            }
+        }
+        if created != links.len() {
+            return Err(invalid(
+                "robot",
+                "some links are not connected to the root link",
+            ));
+        }
+        Ok(root)
+    }
+}
+
+/// The parts of a URDF `<joint>` needed to place its child link.
+struct UrdfJoint<'a> {
+    name: &'a str,
+    kind: &'a str,
+    parent: &'a str,
+    child: &'a str,
+    xyz: Vector3<f64>,
+    rpy: UnitQuaternion<f64>,
+    /// The joint axis, `None` for joints that cannot move.
+    axis: Option<Vector3<f64>>,
+    /// The lower and upper bound of the `<limit>` element, if any.
+    limits: Option<(f64, f64)>,
+}
+
+impl<'a> UrdfJoint<'a> {
+    fn parse(element: Node<'a, '_>) -> Result<Self, CartesianTreeError> {
+        let name = required_attribute(element, "name", "joint")?;
+        let path = format!("joint[{name}]");
+        let kind = required_attribute(element, "type", &path)?;
+        let link_of = |tag: &str| {
+            let element = first_child(element, tag)
+                .ok_or_else(|| invalid(&path, &format!("missing <{tag}> element")))?;
+            required_attribute(element, "link", &format!("{path}/{tag}"))
+        };
+        let parent = link_of("parent")?;
+        let child = link_of("child")?;
+
+        let origin = first_child(element, "origin");
+        let origin_path = format!("{path}/origin");
+        let xyz = parse_triple(origin.and_then(|o| o.attribute("xyz")), &origin_path, "xyz")?
+            .unwrap_or_else(Vector3::zeros);
+        let rpy = parse_triple(origin.and_then(|o| o.attribute("rpy")), &origin_path, "rpy")?
+            .unwrap_or_else(Vector3::zeros);
+
+        let axis = if kind == "fixed" {
+            None
+        } else {
+            let axis = first_child(element, "axis").and_then(|a| a.attribute("xyz"));
+            Some(parse_triple(axis, &format!("{path}/axis"), "xyz")?.unwrap_or_else(Vector3::x))
+        };
+        if axis.is_some_and(|axis| axis.norm() == 0.0) {
+            return Err(invalid(
+                &format!("{path}/axis"),
+                "the axis must not be zero",
+            ));
+        }
+
+        let limits = match first_child(element, "limit") {
+            // Continuous joints ignore their limits.
+            Some(_) if kind == "continuous" => None,
+            Some(limit) => {
+                let limit_path = format!("{path}/limit");
+                // Missing bounds default to zero, as in the URDF specification.
+                let bound = |key: &str| {
+                    limit.attribute(key).map_or(Ok(0.0), |value| {
+                        value.trim().parse::<f64>().map_err(|_| {
+                            invalid(&limit_path, &format!("attribute '{key}' must be a number"))
+                        })
+                    })
+                };
+                Some((bound("lower")?, bound("upper")?))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            name,
+            kind,
+            parent,
+            child,
+            xyz,
+            rpy: UnitQuaternion::from_euler_angles(rpy.x, rpy.y, rpy.z),
+            axis,
+            limits,
+        })
+    }
+
+    /// Returns the joint moving the child link, `None` for joints without a single degree of
+    /// freedom.
+    fn articulation(&self) -> Option<Joint> {
+        let axis = Unit::new_normalize(self.axis?);
+        let joint = match self.kind {
+            "revolute" | "continuous" => Joint::revolute(axis),
+            "prismatic" => Joint::prismatic(axis),
+            _ => return None,
+        };
+        Some(match self.limits {
+            Some((lower, upper)) => joint.with_limits(lower, upper),
+            None => joint,
+        })
+    }
+}
+
+fn invalid(path: &str, reason: &str) -> CartesianTreeError {
+    CartesianTreeError::InvalidDocument {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// Parses an optional attribute holding three whitespace-separated numbers.
+fn parse_triple(
+    value: Option<&str>,
+    path: &str,
+    attribute: &str,
+) -> Result<Option<Vector3<f64>>, CartesianTreeError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let malformed = || {
+        invalid(
+            path,
+            &format!("attribute '{attribute}' must hold three numbers"),
+        )
+    };
+    let numbers = value
+        .split_whitespace()
+        .map(str::parse::<f64>)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| malformed())?;
+    match numbers[..] {
+        [x, y, z] => Ok(Some(Vector3::new(x, y, z))),
+        _ => Err(malformed()),
+    }
+}
+
+/// The deepest element nesting accepted. URDF files nest a few levels, while `roxmltree` takes
+/// several kilobytes of stack per level in debug builds.
+const MAX_DEPTH: usize = 64;
+
+/// Parses an XML document, reporting syntax errors by line.
+///
+/// Document type declarations are allowed, as some URDF files declare their entities in one.
+fn parse_xml(xml: &str) -> Result<Document<'_>, CartesianTreeError> {
+    let line = |offset: usize| format!("line {}", xml[..offset].matches('\n').count() + 1);
+    // `roxmltree` recurses once per level of nesting, so deeper documents are rejected first.
+    if let Some(offset) = too_deep(xml) {
+        return Err(invalid(
+            &line(offset),
+            &format!("elements are nested deeper than {MAX_DEPTH} levels"),
+        ));
+    }
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    Document::parse_with_options(xml, options)
+        .map_err(|error| invalid(&format!("line {}", error.pos().row), &error.to_string()))
+}
+
+/// Returns the offset of the first start tag nested deeper than [`MAX_DEPTH`], if any.
+///
+/// Comments, CDATA sections and processing instructions are skipped, and quoted attribute
+/// values cannot end a tag. Markup in declarations may be counted as well, which only
+/// overestimates the depth.
+fn too_deep(xml: &str) -> Option<usize> {
+    let mut depth = 0_usize;
+    let mut offset = 0;
+    while let Some(start) = xml[offset..].find('<') {
+        offset += start;
+        let rest = &xml[offset..];
+        let skip_to = [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>")]
+            .into_iter()
+            .find_map(|(open, close)| rest.starts_with(open).then_some(close));
+        offset += if let Some(close) = skip_to {
+            rest.find(close).map_or(rest.len(), |end| end + close.len())
+        } else if rest.starts_with("</") {
+            depth = depth.saturating_sub(1);
+            2
+        } else if rest.starts_with("<!") {
+            2
+        } else {
+            let end = tag_end(rest)?;
+            if !rest[..end].ends_with('/') {
+                depth += 1;
+                if depth > MAX_DEPTH {
+                    return Some(offset);
+                }
+            }
+            end + 1
+        };
+    }
+    None
+}
+
+/// Returns the index of the `>` closing the tag at the start of `input`, skipping quoted values.
+fn tag_end(input: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn required_attribute<'a>(
+    element: Node<'a, '_>,
+    key: &str,
+    path: &str,
+) -> Result<&'a str, CartesianTreeError> {
+    element
+        .attribute(key)
+        .ok_or_else(|| invalid(path, &format!("missing attribute '{key}'")))
+}
+
+fn first_child<'a, 'input>(element: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    element.children().find(|child| child.has_tag_name(name))
+}
+
+fn children_named<'a, 'input>(
+    element: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    element
+        .children()
+        .filter(move |child| child.has_tag_name(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::MetadataValue;
+    use approx::assert_relative_eq;
+    use nalgebra::Isometry3;
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    const ARM_URDF: &str = r#"<?xml version="1.0"?>
+<!-- A small arm on a mobile base. -->
+<robot name="test_arm">
+  <link name="base_link">
+    <inertial><mass value="10.0"/></inertial>
+  </link>
+  <link name="torso"/>
+  <link name="upper_arm"/>
+  <link name="forearm"/>
+  <link name="tool"/>
+  <link name="camera"/>
+
+  <joint name="base_to_torso" type="fixed">
+    <parent link="base_link"/>
+    <child link="torso"/>
+    <origin xyz="0 0 0.5" rpy="0 0 0"/>
+  </joint>
+  <joint name="shoulder" type="revolute">
+    <parent link="torso"/>
+    <child link="upper_arm"/>
+    <origin xyz="0 0 0.3" rpy="0 0 1.5707963267948966"/>
+    <axis xyz="0 0 1"/>
+    <limit lower="-3.14" upper="3.14" effort="10" velocity="1"/>
+  </joint>
+  <joint name="elbow" type="prismatic">
+    <parent link="upper_arm"/>
+    <child link="forearm"/>
+    <origin xyz="0.4 0 0"/>
+    <axis xyz="0 1 0"/>
+  </joint>
+  <joint name="wrist" type="fixed">
+    <parent link="forearm"/>
+    <child link="tool"/>
+    <origin xyz="0.3 0 0" rpy="0 1.5707963267948966 0"/>
+  </joint>
+  <joint name='base_to_camera' type='fixed'>
+    <parent link='base_link'/>
+    <child link='camera'/>
+    <origin xyz='0.1 0 0.2' rpy='3.141592653589793 0 0'/>
+  </joint>
+</robot>
+"#;
+
+    fn assert_world(frame: &Frame, path: &str, xyz: [f64; 3], rpy: [f64; 3]) {
+        let expected = Isometry3::from_parts(
+            Vector3::from(xyz).into(),
+            UnitQuaternion::from_euler_angles(rpy[0], rpy[1], rpy[2]),
+        );
+        let actual = frame.resolve_path(path).unwrap().world_transform().unwrap();
+        assert_relative_eq!(actual, expected, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn import_arm_world_transforms() {
+        let base = Frame::from_urdf(ARM_URDF).unwrap();
+
+        assert_eq!(base.name(), "base_link");
+        assert_eq!(base.iter_dfs().count(), 6);
+        assert_world(&base, "torso", [0.0, 0.0, 0.5], [0.0, 0.0, 0.0]);
+        assert_world(
+            &base,
+            "torso/upper_arm",
+            [0.0, 0.0, 0.8],
+            [0.0, 0.0, FRAC_PI_2],
+        );
+        assert_world(
+            &base,
+            "torso/upper_arm/forearm",
+            [0.0, 0.4, 0.8],
+            [0.0, 0.0, FRAC_PI_2],
+        );
+        assert_world(
+            &base,
+            "torso/upper_arm/forearm/tool",
+            [0.0, 0.7, 0.8],
+            [0.0, FRAC_PI_2, FRAC_PI_2],
+        );
+        assert_world(&base, "camera", [0.1, 0.0, 0.2], [PI, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn import_stores_joint_metadata() {
+        let base = Frame::from_urdf(ARM_URDF).unwrap();
+
+        let upper_arm = base.resolve_path("torso/upper_arm").unwrap();
+        assert_eq!(
+            upper_arm
+                .get_metadata("joint_name")
+                .as_ref()
+                .and_then(MetadataValue::as_str),
+            Some("shoulder")
+        );
+        assert_eq!(
+            upper_arm
+                .get_metadata("joint_type")
+                .as_ref()
+                .and_then(MetadataValue::as_str),
+            Some("revolute")
+        );
+        assert_eq!(
+            upper_arm
+                .get_metadata("joint_axis")
+                .as_ref()
+                .and_then(MetadataValue::as_str),
+            Some("0 0 1")
+        );
+
+        let forearm = base.resolve_path("torso/upper_arm/forearm").unwrap();
+        assert_eq!(
+            forearm
+                .get_metadata("joint_type")
+                .as_ref()
+                .and_then(MetadataValue::as_str),
+            Some("prismatic")
+        );
+        assert_eq!(
+            forearm
+                .get_metadata("joint_axis")
+                .as_ref()
+                .and_then(MetadataValue::as_str),
+            Some("0 1 0")
+        );
+
+        let camera = base.resolve_path("camera").unwrap();
+        assert_eq!(
+            camera
+                .get_metadata("joint_type")
+                .as_ref()
+                .and_then(MetadataValue::as_str),
+            Some("fixed")
+        );
+        assert_eq!(camera.get_metadata("joint_axis"), None);
+        assert_eq!(base.get_metadata("joint_name"), None);
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
+    fn imported_joints_articulate_the_robot() {
+        let base = Frame::from_urdf(ARM_URDF).unwrap();
+        let upper_arm = base.resolve_path("torso/upper_arm").unwrap();
+        let forearm = base.resolve_path("torso/upper_arm/forearm").unwrap();
+        assert_eq!(
+            upper_arm.joint().unwrap().limits,
+            Some(crate::joint::JointLimits {
+                lower: -3.14,
+                upper: 3.14
+            })
+        );
+        assert!(forearm.joint().unwrap().limits.is_none());
+        assert!(base.resolve_path("camera").unwrap().joint().is_none());
+
+        let values = HashMap::from([
+            ("upper_arm".to_string(), FRAC_PI_2),
+            ("forearm".to_string(), 0.1),
+        ]);
+        base.set_joint_values(&values).unwrap();
+        assert_relative_eq!(
+            forearm.world_transform().unwrap().translation.vector,
+            Vector3::new(-0.4, -0.1, 0.8),
+            epsilon = 1e-12
+        );
+        assert!(matches!(
+            upper_arm.set_joint_value(4.0),
+            Err(CartesianTreeError::JointLimitExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn import_reads_document_type_declarations() {
+        let urdf = r#"<?xml version="1.0"?>
+<!DOCTYPE robot [
+  <!ELEMENT robot ANY>
+  <!ENTITY height "0.5">
+]>
+<robot name="bot">
+  <link name="base"/>
+  <link name="arm"/>
+  <joint name="lift" type="fixed">
+    <parent link="base"/>
+    <child link="arm"/>
+    <origin xyz="0 0 &height;"/>
+  </joint>
+</robot>"#;
+
+        let base = Frame::from_urdf(urdf).unwrap();
+        assert_world(&base, "arm", [0.0, 0.0, 0.5], [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn import_survives_deeply_nested_elements() {
+        const DEPTH: usize = 1_000_000;
+        let urdf = format!(
+            r#"<robot name="bot"><link name="base">{}{}</link></robot>"#,
+            "<a>".repeat(DEPTH),
+            "</a>".repeat(DEPTH)
+        );
+
+        assert!(matches!(
+            Frame::from_urdf(&urdf),
+            Err(CartesianTreeError::InvalidDocument { path, .. }) if path == "line 1"
+        ));
+
+        let depth = MAX_DEPTH - 2;
+        let urdf = format!(
+            r#"<robot name="bot"><link name="base">{}{}</link></robot>"#,
+            "<a x='>'>".repeat(depth),
+            "</a>".repeat(depth)
+        );
+        assert_eq!(Frame::from_urdf(&urdf).unwrap().name(), "base");
+    }
+
+    #[test]
+    fn import_rejects_invalid_documents() {
+        let error_path = |xml: &str| match Frame::from_urdf(xml) {
+            Err(CartesianTreeError::InvalidDocument { path, .. }) => path,
+            other => panic!("expected an invalid document error, got {other:?}"),
+        };
+
+        assert_eq!(error_path("<robot><link name=\"a\"></robot>"), "line 1");
+        assert_eq!(error_path("<model/>"), "/");
+        assert_eq!(
+            error_path(r#"<robot><link name="a"/><link name="b"/></robot>"#),
+            "robot"
+        );
+        assert_eq!(
+            error_path(
+                r#"<robot><link name="a"/>
+                <joint name="j" type="fixed"><parent link="a"/><child link="b"/></joint>
+                </robot>"#
+            ),
+            "joint[j]"
+        );
+        assert_eq!(
+            error_path(
+                r#"<robot><link name="a"/><link name="b"/>
+                <joint name="j" type="fixed">
+                  <parent link="a"/><child link="b"/><origin xyz="1 2"/>
+                </joint>
+                </robot>"#
+            ),
+            "joint[j]/origin"
+        );
+        assert_eq!(
+            error_path(
+                r#"<robot><link name="a"/><link name="b"/><link name="c"/>
+                <joint name="j1" type="fixed"><parent link="b"/><child link="c"/></joint>
+                <joint name="j2" type="fixed"><parent link="c"/><child link="b"/></joint>
+                </robot>"#
+            ),
+            "robot"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Checks applied to values entering the tree and to the structure of a tree.
+
+#[cfg(all(feature = "std", feature = "serde"))]
+use nalgebra::Quaternion;
+#[cfg(feature = "std")]
+use nalgebra::Vector2;
+use nalgebra::{Isometry3, UnitQuaternion, Vector3};
+
+use crate::CartesianTreeError;
+#[cfg(all(feature = "std", feature = "serde"))]
+use crate::scalar::from_f64;
+use crate::scalar::{Real, to_f64};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+/// The maximum deviation of an orientation quaternion's norm from 1 accepted by
+/// [`crate::Frame::validate`].
+pub const QUATERNION_NORM_TOLERANCE: f64 = 1e-6;
+
+/// The rules frame names are checked against when frames are created.
+///
+/// Frames are addressed by `/`-separated paths (see [`crate::Frame::resolve_path`]), so the
+/// default rules reject names that would make such paths ambiguous.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FrameNameRules {
+    /// Rejects empty names, `.` and `..`, names containing `/` or control characters, and names
+    /// with leading or trailing whitespace.
+    #[default]
+    Strict,
+    /// Only rejects empty names. Frames with other names may not be reachable by path and
+    /// cannot be read back by [`crate::Frame::from_json`].
+    Permissive,
+}
+
+/// A structural problem found by [`crate::Frame::validate`].
+///
+/// Each violation carries the path of the offending frame, see [`crate::Frame::path`].
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum TreeValidationError {
+    /// The parent reference of a frame does not point to the frame listing it as a child.
+    #[error("Frame '{path}' does not reference its parent")]
+    ParentMismatch { path: String },
+    /// A frame has several children with the same name.
+    #[error("Frame '{path}' has more than one child named '{name}'")]
+    DuplicateChildName { path: String, name: String },
+    /// A frame is reached more than once while walking down the subtree, because the children
+    /// form a cycle or a frame is listed as the child of several frames.
+    #[error("Frame '{path}' is reached more than once (cycle or shared child)")]
+    Cycle { path: String },
+    /// A component of the transform to the parent is NaN or infinite.
+    #[error("Frame '{path}' has a non-finite value for '{field}'")]
+    NonFiniteTransform { path: String, field: String },
+    /// The orientation quaternion is not normalized within [`QUATERNION_NORM_TOLERANCE`].
+    #[error("Frame '{path}' has an orientation quaternion with norm {norm}")]
+    UnnormalizedOrientation { path: String, norm: f64 },
+}
+
+/// Returns an error naming the first component of `position` that is NaN or infinite.
+pub(crate) fn check_position<T: Real>(
+    field: &str,
+    position: &Vector3<T>,
+) -> Result<(), CartesianTreeError> {
+    check_components(
+        field,
+        &[("x", position.x), ("y", position.y), ("z", position.z)],
+    )
+}
+
+/// Returns an error naming the first component of `orientation` that is NaN or infinite.
+///
+/// Normalizing a zero-length quaternion yields NaN components, so such inputs are rejected too.
+pub(crate) fn check_orientation<T: Real>(
+    field: &str,
+    orientation: &UnitQuaternion<T>,
+) -> Result<(), CartesianTreeError> {
+    check_components(
+        field,
+        &[
+            ("x", orientation.i),
+            ("y", orientation.j),
+            ("z", orientation.k),
+            ("w", orientation.w),
+        ],
+    )
+}
+
+/// Returns a quaternion read from a document, whose norm is within
+/// [`QUATERNION_NORM_TOLERANCE`] of 1, as a unit quaternion.
+///
+/// Quaternions that are unit up to rounding in `T` are kept as they are, as renormalizing them
+/// changes their last bits and would break exact round trips of written trees. All others are
+/// renormalized.
+#[cfg(all(feature = "std", feature = "serde"))]
+pub(crate) fn read_unit_quaternion<T: Real>(quaternion: Quaternion<T>) -> UnitQuaternion<T> {
+    if (quaternion.norm() - T::one()).abs() <= from_f64::<T>(4.0) * T::default_epsilon() {
+        UnitQuaternion::new_unchecked(quaternion)
+    } else {
+        UnitQuaternion::new_normalize(quaternion)
+    }
+}
+
+/// Checks the translation and rotation of `isometry` with [`check_position`] and
+/// [`check_orientation`].
+pub(crate) fn check_isometry<T: Real>(
+    field: &str,
+    isometry: &Isometry3<T>,
+) -> Result<(), CartesianTreeError> {
+    check_position(
+        &format!("{field}.translation"),
+        &isometry.translation.vector,
+    )?;
+    check_orientation(&format!("{field}.rotation"), &isometry.rotation)
+}
+
+/// Returns an error naming the first component of a planar transform that is NaN or infinite.
+#[cfg(feature = "std")]
+pub(crate) fn check_planar(position: &Vector2<f64>, yaw: f64) -> Result<(), CartesianTreeError> {
+    check_components("position", &[("x", position.x), ("y", position.y)])?;
+    if yaw.is_finite() {
+        Ok(())
+    } else {
+        Err(CartesianTreeError::NonFiniteValue {
+            field: "yaw".to_string(),
+            value: yaw,
+        })
+    }
+}
+
+/// Returns an error if `name` is not a valid frame name under `rules`.
+pub(crate) fn check_frame_name(
+    name: &str,
+    rules: FrameNameRules,
+) -> Result<(), CartesianTreeError> {
+    let reason = if name.is_empty() {
+        "the name is empty"
+    } else if rules == FrameNameRules::Permissive {
+        return Ok(());
+    } else if name.contains('/') {
+        "'/' separates the segments of frame paths"
+    } else if name == "." || name == ".." {
+        "'.' and '..' are relative path segments"
+    } else if name.chars().any(char::is_control) {
+        "the name contains control characters"
+    } else if name.trim() != name {
+        "the name has leading or trailing whitespace"
+    } else {
+        return Ok(());
+    };
+    Err(CartesianTreeError::InvalidFrameName {
+        name: name.to_string(),
+        reason: reason.to_string(),
+    })
+}
+
+fn check_components<T: Real>(
+    field: &str,
+    components: &[(&str, T)],
+) -> Result<(), CartesianTreeError> {
+    match components.iter().find(|(_, value)| !value.is_finite()) {
+        Some((component, value)) => Err(CartesianTreeError::NonFiniteValue {
+            field: format!("{field}.{component}"),
+            value: to_f64(*value),
+        }),
+        None => Ok(()),
+    }
+}
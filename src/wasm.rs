@@ -0,0 +1,330 @@
+//! WebAssembly bindings, enabled by the `wasm` feature.
+//!
+//! [`WasmFrame`] and [`WasmPose`] are exported to JavaScript as `Frame` and `Pose` and mirror
+//! the Python classes. Positions are passed as 3 numbers `[x, y, z]` and orientations as
+//! quaternions of 4 numbers `[x, y, z, w]`, e.g. in a `Float64Array`. Transforms are returned as
+//! new `Float64Array`s: `[x, y, z, qx, qy, qz, qw]` for a position and an orientation, or 16
+//! numbers in column-major order for a homogeneous matrix, as expected by WebGL and three.js.
+//!
+//! Nothing returned to JavaScript borrows from the tree: arrays are copies, and every frame
+//! object holds its own handle, which keeps the whole tree alive until it is freed. A pose
+//! object does not keep its frame alive; once the tree is freed, conversions of the pose fail.
+
+use crate::frame::DotOptions;
+use crate::rotation::Rotation;
+use crate::tree::{HasChildren, HasParent, NodeEquality, Walking};
+use crate::{Frame, Pose};
+use nalgebra::{Isometry3, Matrix4, Point3, Vector3};
+use wasm_bindgen::prelude::*;
+
+/// A frame of a tree, exported as `Frame`.
+#[wasm_bindgen(js_name = Frame)]
+#[derive(Clone)]
+pub struct WasmFrame {
+    frame: Frame,
+}
+
+/// A pose in a frame, exported as `Pose`.
+#[wasm_bindgen(js_name = Pose)]
+#[derive(Clone)]
+pub struct WasmPose {
+    pose: Pose,
+}
+
+/// Reads a position from 3 numbers.
+fn read_position(values: &[f64]) -> Result<Vector3<f64>, JsError> {
+    let values: [f64; 3] = values.try_into().map_err(|_| {
+        JsError::new(&format!(
+            "a position has 3 components, got {}",
+            values.len()
+        ))
+    })?;
+    Ok(Vector3::from(values))
+}
+
+/// Reads an orientation from a quaternion of 4 numbers `[x, y, z, w]`.
+fn read_orientation(values: &[f64]) -> Result<Rotation, JsError> {
+    let values: [f64; 4] = values.try_into().map_err(|_| {
+        JsError::new(&format!(
+            "an orientation has 4 components, got {}",
+            values.len()
+        ))
+    })?;
+    Ok(Rotation::from(values))
+}
+
+/// Returns `[x, y, z, qx, qy, qz, qw]` of `isometry`.
+fn transform_array(isometry: &Isometry3<f64>) -> Vec<f64> {
+    let translation = isometry.translation.vector.iter();
+    translation
+        .chain(isometry.rotation.coords.iter())
+        .copied()
+        .collect()
+}
+
+/// Returns the entries of `matrix` in column-major order.
+fn matrix_array(matrix: &Matrix4<f64>) -> Vec<f64> {
+    matrix.as_slice().to_vec()
+}
+
+#[wasm_bindgen(js_class = Frame)]
+impl WasmFrame {
+    /// Creates the root frame of a new tree.
+    ///
+    /// # Errors
+    /// Fails if the name is not a valid frame name.
+    #[wasm_bindgen(constructor)]
+    pub fn new(name: &str) -> Result<Self, JsError> {
+        Ok(Self {
+            frame: Frame::new_origin(name)?,
+        })
+    }
+
+    /// Returns a tree read from JSON, see [`Frame::from_json`].
+    ///
+    /// # Errors
+    /// Fails if the JSON does not describe a valid tree.
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<Self, JsError> {
+        Ok(Self {
+            frame: Frame::from_json(json)?,
+        })
+    }
+
+    /// The name of the frame.
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.frame.name()
+    }
+
+    /// The path of the frame from the root, see [`Frame::path`].
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn path(&self) -> String {
+        self.frame.path()
+    }
+
+    /// Adds a child at `position` and `orientation` relative to this frame.
+    ///
+    /// # Errors
+    /// Fails for malformed arrays, invalid or duplicate names and non-finite transforms.
+    #[wasm_bindgen(js_name = addChild)]
+    pub fn add_child(
+        &self,
+        name: &str,
+        position: &[f64],
+        orientation: &[f64],
+    ) -> Result<Self, JsError> {
+        let frame = self.frame.add_child(
+            name,
+            read_position(position)?,
+            read_orientation(orientation)?,
+        )?;
+        Ok(Self { frame })
+    }
+
+    /// Adds a pose at `position` and `orientation` in this frame.
+    ///
+    /// # Errors
+    /// Fails for malformed arrays and non-finite transforms.
+    #[wasm_bindgen(js_name = addPose)]
+    pub fn add_pose(&self, position: &[f64], orientation: &[f64]) -> Result<WasmPose, JsError> {
+        let pose = self
+            .frame
+            .add_pose(read_position(position)?, read_orientation(orientation)?)?;
+        Ok(WasmPose { pose })
+    }
+
+    /// Moves the frame to `position` and `orientation` relative to its parent.
+    ///
+    /// # Errors
+    /// Fails for malformed arrays, non-finite transforms and root frames.
+    pub fn set(&self, position: &[f64], orientation: &[f64]) -> Result<(), JsError> {
+        Ok(self
+            .frame
+            .set(read_position(position)?, read_orientation(orientation)?)?)
+    }
+
+    /// Returns `[x, y, z, qx, qy, qz, qw]` of the transform to the parent.
+    ///
+    /// # Errors
+    /// Fails for root frames.
+    pub fn transformation(&self) -> Result<Vec<f64>, JsError> {
+        Ok(transform_array(&self.frame.transformation()?))
+    }
+
+    /// Returns `[x, y, z, qx, qy, qz, qw]` of the transform to `target`.
+    ///
+    /// # Errors
+    /// Fails if the frames are not in the same tree.
+    #[wasm_bindgen(js_name = transformationTo)]
+    pub fn transformation_to(&self, target: &Self) -> Result<Vec<f64>, JsError> {
+        Ok(transform_array(&self.frame.transform_to(&target.frame)?))
+    }
+
+    /// Returns the homogeneous matrix, including scale, of the transform to `target` in
+    /// column-major order.
+    ///
+    /// # Errors
+    /// Fails if the frames are not in the same tree.
+    #[wasm_bindgen(js_name = transformTo)]
+    pub fn transform_to(&self, target: &Self) -> Result<Vec<f64>, JsError> {
+        let similarity = self.frame.similarity_to(&target.frame)?;
+        Ok(matrix_array(&similarity.to_homogeneous()))
+    }
+
+    /// Returns `[x, y, z, qx, qy, qz, qw]` of the transform to the root.
+    ///
+    /// # Errors
+    /// Fails if an ancestor has been dropped.
+    #[wasm_bindgen(js_name = worldTransform)]
+    pub fn world_transform(&self) -> Result<Vec<f64>, JsError> {
+        Ok(transform_array(&self.frame.world_transform()?))
+    }
+
+    /// Expresses `point` of this frame in `target`.
+    ///
+    /// # Errors
+    /// Fails for malformed arrays and frames of different trees.
+    #[wasm_bindgen(js_name = transformPointTo)]
+    pub fn transform_point_to(&self, target: &Self, point: &[f64]) -> Result<Vec<f64>, JsError> {
+        let point = Point3::from(read_position(point)?);
+        let transformed = self.frame.similarity_to(&target.frame)? * point;
+        Ok(transformed.coords.as_slice().to_vec())
+    }
+
+    /// The parent frame, or `undefined` for a root.
+    #[must_use]
+    pub fn parent(&self) -> Option<Self> {
+        self.frame.parent().map(|frame| Self { frame })
+    }
+
+    /// The root frame of the tree.
+    #[must_use]
+    pub fn root(&self) -> Self {
+        Self {
+            frame: self.frame.root(),
+        }
+    }
+
+    /// The children of the frame.
+    #[must_use]
+    pub fn children(&self) -> Vec<Self> {
+        self.frame
+            .children()
+            .into_iter()
+            .map(|frame| Self { frame })
+            .collect()
+    }
+
+    /// The first frame named `name` in the subtree, see [`Frame::find`].
+    #[must_use]
+    pub fn find(&self, name: &str) -> Option<Self> {
+        self.frame.find(name).map(|frame| Self { frame })
+    }
+
+    /// The frame at `path`, see [`Frame::resolve_path`].
+    ///
+    /// # Errors
+    /// Fails if no frame is at the path.
+    pub fn resolve(&self, path: &str) -> Result<Self, JsError> {
+        Ok(Self {
+            frame: self.frame.resolve_path(path)?,
+        })
+    }
+
+    /// Serializes the tree below this frame to JSON, see [`Frame::to_json`].
+    ///
+    /// # Errors
+    /// Fails if the tree cannot be serialized.
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsError> {
+        Ok(self.frame.to_json()?)
+    }
+
+    /// Applies the transforms of a JSON config, see [`Frame::apply_config`].
+    ///
+    /// # Errors
+    /// Fails if the config does not match the tree.
+    #[wasm_bindgen(js_name = applyConfig)]
+    pub fn apply_config(&self, json: &str) -> Result<(), JsError> {
+        Ok(self.frame.apply_config(json)?)
+    }
+
+    /// Renders the tree below this frame in the DOT language, see [`Frame::to_dot_with`].
+    #[wasm_bindgen(js_name = toDot)]
+    #[must_use]
+    pub fn to_dot(&self, include_transforms: bool, precision: usize) -> String {
+        self.frame.to_dot_with(&DotOptions {
+            include_transforms,
+            precision,
+        })
+    }
+
+    /// Whether `other` refers to the same frame.
+    #[wasm_bindgen(js_name = sameFrame)]
+    #[must_use]
+    pub fn same_frame(&self, other: &Self) -> bool {
+        self.frame.is_same(&other.frame)
+    }
+}
+
+#[wasm_bindgen(js_class = Pose)]
+impl WasmPose {
+    /// The frame of the pose.
+    ///
+    /// # Errors
+    /// Fails if the frame has been dropped.
+    pub fn frame(&self) -> Result<WasmFrame, JsError> {
+        Ok(WasmFrame {
+            frame: self.pose.frame()?,
+        })
+    }
+
+    /// Returns `[x, y, z, qx, qy, qz, qw]` of the pose in its frame.
+    #[must_use]
+    pub fn transformation(&self) -> Vec<f64> {
+        transform_array(&self.pose.transformation())
+    }
+
+    /// Returns the homogeneous matrix of the pose in its frame in column-major order.
+    #[wasm_bindgen(js_name = toMatrix)]
+    #[must_use]
+    pub fn to_matrix(&self) -> Vec<f64> {
+        matrix_array(&self.pose.to_matrix())
+    }
+
+    /// Moves the pose to `position` and `orientation` in its frame.
+    ///
+    /// # Errors
+    /// Fails for malformed arrays and non-finite transforms.
+    pub fn set(&mut self, position: &[f64], orientation: &[f64]) -> Result<(), JsError> {
+        Ok(self
+            .pose
+            .set(read_position(position)?, read_orientation(orientation)?)?)
+    }
+
+    /// Expresses the pose in `target` as a new pose.
+    ///
+    /// # Errors
+    /// Fails if the frames are not in the same tree or the frame has been dropped.
+    #[wasm_bindgen(js_name = inFrame)]
+    pub fn in_frame(&self, target: &WasmFrame) -> Result<Self, JsError> {
+        Ok(Self {
+            pose: self.pose.in_frame(&target.frame)?,
+        })
+    }
+
+    /// Expresses `point` of the pose in its frame.
+    ///
+    /// # Errors
+    /// Fails for malformed arrays.
+    #[wasm_bindgen(js_name = transformPoint)]
+    pub fn transform_point(&self, point: &[f64]) -> Result<Vec<f64>, JsError> {
+        let point = self
+            .pose
+            .transform_point(&Point3::from(read_position(point)?));
+        Ok(point.coords.as_slice().to_vec())
+    }
+}
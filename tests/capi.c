@@ -0,0 +1,89 @@
+/* Exercises the C interface and its ownership rules, built and run by tests/capi.rs. */
+#include "cartesian_tree.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#define CHECK(condition)                                                        \
+  do {                                                                          \
+    if (!(condition)) {                                                         \
+      const char *message = ct_last_error_message();                            \
+      fprintf(stderr, "%s:%d: check failed: %s (last error: %s)\n", __FILE__,   \
+              __LINE__, #condition, message ? message : "none");                \
+      return 1;                                                                 \
+    }                                                                           \
+  } while (0)
+
+static int close_to(const double *actual, const double *expected, size_t len) {
+  for (size_t i = 0; i < len; ++i) {
+    if (fabs(actual[i] - expected[i]) > 1e-12) {
+      return 0;
+    }
+  }
+  return 1;
+}
+
+int main(void) {
+  const double identity[4] = {0.0, 0.0, 0.0, 1.0};
+  const double quarter_turn[4] = {0.0, 0.0, sqrt(0.5), sqrt(0.5)};
+  const double offset[3] = {1.0, 0.0, 0.0};
+  double position[3];
+  double orientation[4];
+
+  CtFrame *world = NULL;
+  CHECK(ct_frame_new_origin("world", &world) == CT_STATUS_OK);
+  CtFrame *arm = NULL;
+  CHECK(ct_frame_add_child(world, "arm", offset, quarter_turn, &arm) == CT_STATUS_OK);
+
+  /* Names are copies owned by the caller. */
+  char *name = NULL;
+  CHECK(ct_frame_name(arm, &name) == CT_STATUS_OK);
+  CHECK(strcmp(name, "arm") == 0);
+  ct_string_free(name);
+
+  CHECK(ct_frame_world_transform(arm, position, orientation) == CT_STATUS_OK);
+  CHECK(close_to(position, offset, 3));
+  CHECK(close_to(orientation, quarter_turn, 4));
+
+  /* Poses convert between frames and are released independently. */
+  CtPose *pose = NULL;
+  CHECK(ct_frame_add_pose(arm, offset, identity, &pose) == CT_STATUS_OK);
+  CtPose *in_world = NULL;
+  CHECK(ct_pose_in_frame(pose, world, &in_world) == CT_STATUS_OK);
+  CHECK(ct_pose_transformation(in_world, position, orientation) == CT_STATUS_OK);
+  const double expected[3] = {1.0, 1.0, 0.0};
+  CHECK(close_to(position, expected, 3));
+  ct_pose_free(in_world);
+  ct_pose_free(pose);
+
+  /* Failures report a status and a message owned by the library. */
+  CtFrame *duplicate = NULL;
+  CHECK(ct_frame_add_child(world, "arm", offset, identity, &duplicate) ==
+        CT_STATUS_TREE_ERROR);
+  CHECK(duplicate == NULL);
+  CHECK(strstr(ct_last_error_message(), "arm") != NULL);
+  CHECK(ct_frame_world_transform(NULL, position, orientation) == CT_STATUS_NULL_POINTER);
+
+  /* Handles keep their whole tree alive, so the root may be released first. */
+  CtFrame *tool = NULL;
+  CHECK(ct_frame_add_child(arm, "tool", offset, identity, &tool) == CT_STATUS_OK);
+  ct_frame_free(arm);
+  CHECK(ct_frame_add_pose(tool, offset, identity, &pose) == CT_STATUS_OK);
+  ct_frame_free(world);
+  CHECK(ct_frame_world_transform(tool, position, orientation) == CT_STATUS_OK);
+  CHECK(close_to(orientation, quarter_turn, 4));
+
+  /* Poses do not keep their frame alive and fail once the tree is freed. */
+  ct_frame_free(tool);
+  CtFrame *other = NULL;
+  CHECK(ct_frame_new_origin("other", &other) == CT_STATUS_OK);
+  CHECK(ct_pose_in_frame(pose, other, &in_world) == CT_STATUS_TREE_ERROR);
+  ct_pose_free(pose);
+  ct_frame_free(other);
+
+  ct_frame_free(NULL);
+  ct_pose_free(NULL);
+  ct_string_free(NULL);
+  return 0;
+}
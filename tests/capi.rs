@@ -0,0 +1,55 @@
+//! Builds `tests/capi.c` against the shared library with the system C compiler and runs it.
+//!
+//! `cargo test` only builds the `rlib` for integration tests, so the test builds the shared
+//! library itself, in a target directory of its own to stay clear of the running build.
+#![cfg(feature = "capi")]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Builds the shared library with the `capi` feature and returns the directory it is in.
+fn build_library(manifest: &Path) -> PathBuf {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("capi-target");
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--lib", "--features", "capi", "--manifest-path"])
+        .arg(manifest.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .expect("cargo runs");
+    assert!(status.success(), "building the shared library failed");
+    target_dir.join("debug")
+}
+
+#[test]
+fn c_program_runs_against_the_shared_library() {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let library_dir = build_library(manifest);
+    let program = Path::new(env!("CARGO_TARGET_TMPDIR")).join("capi");
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+
+    let status = Command::new(&compiler)
+        .arg(manifest.join("tests/capi.c"))
+        .arg("-I")
+        .arg(manifest.join("include"))
+        .arg("-L")
+        .arg(&library_dir)
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .args(["-lcartesian_tree", "-lm", "-Wall", "-Werror", "-o"])
+        .arg(&program)
+        .status()
+        .unwrap_or_else(|error| panic!("failed to run '{compiler}': {error}"));
+    assert!(status.success(), "compiling tests/capi.c failed");
+
+    // `cargo test` puts its own target directory on the library path, which takes precedence
+    // over the rpath and may hold a library built without the `capi` feature.
+    let output = Command::new(&program)
+        .env("LD_LIBRARY_PATH", &library_dir)
+        .output()
+        .expect("the program runs");
+    assert!(
+        output.status.success(),
+        "tests/capi.c failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,54 @@
+//! Runs the WebAssembly bindings in headless Node, see `just wasm`.
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use approx::assert_relative_eq;
+use cartesian_tree::wasm::WasmFrame;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn poses_round_trip_between_frames() {
+    let world = WasmFrame::new("world").unwrap();
+    let quarter_turn = [0.0, 0.0, 0.5_f64.sqrt(), 0.5_f64.sqrt()];
+    let arm = world
+        .add_child("arm", &[1.0, 0.0, 0.0], &quarter_turn)
+        .unwrap();
+    let pose = arm
+        .add_pose(&[1.0, 0.0, 0.0], &[0.0, 0.0, 0.0, 1.0])
+        .unwrap();
+
+    let in_world = pose.in_frame(&world).unwrap();
+    assert_relative_eq!(
+        in_world.transformation().as_slice(),
+        [1.0, 1.0, 0.0, 0.0, 0.0, 0.5_f64.sqrt(), 0.5_f64.sqrt()].as_slice(),
+        epsilon = 1e-12
+    );
+    let back = in_world.in_frame(&arm).unwrap();
+    assert_relative_eq!(
+        back.transformation().as_slice(),
+        pose.transformation().as_slice(),
+        epsilon = 1e-12
+    );
+    assert!(back.frame().unwrap().same_frame(&arm));
+}
+
+#[wasm_bindgen_test]
+fn trees_export_and_reload() {
+    let world = WasmFrame::new("world").unwrap();
+    world
+        .add_child("camera", &[0.0, 0.0, 1.0], &[0.0, 0.0, 0.0, 1.0])
+        .unwrap();
+
+    let copy = WasmFrame::from_json(&world.to_json().unwrap()).unwrap();
+    let camera = copy.resolve("camera").unwrap();
+    assert_eq!(camera.path(), "/world/camera");
+    assert_relative_eq!(
+        camera.transform_to(&copy).unwrap()[12..15].as_ref(),
+        [0.0, 0.0, 1.0].as_slice()
+    );
+    assert!(world.to_dot(false, 3).contains("camera"));
+    assert!(
+        world
+            .add_child("arm", &[0.0; 2], &[0.0, 0.0, 0.0, 1.0])
+            .is_err()
+    );
+}